@@ -0,0 +1,103 @@
+/// ANSI colors for the CLI report. Colors are decided once at startup with
+/// `init`, so the display functions don't need to pass the choice around.
+use clap::ValueEnum;
+use packtrack::tracker::PackageStatus;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub enum ColorChoice {
+    /// Only when printing to a terminal, and NO_COLOR isn't set
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// Turn colors on or off for the rest of the program
+pub fn init(choice: ColorChoice) {
+    let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    let enabled =
+        should_color(choice, std::io::stdout().is_terminal(), no_color);
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// See https://no-color.org: NO_COLOR turns off colors, unless they're asked
+/// for explicitly
+fn should_color(
+    choice: ColorChoice,
+    is_terminal: bool,
+    no_color: bool,
+) -> bool {
+    match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => is_terminal && !no_color,
+    }
+}
+
+fn paint(text: &str, code: &str, enabled: bool) -> String {
+    match enabled {
+        true => format!("\x1b[{code}m{text}\x1b[0m"),
+        false => text.to_owned(),
+    }
+}
+
+fn paint_if_enabled(text: &str, code: &str) -> String {
+    paint(text, code, ENABLED.load(Ordering::Relaxed))
+}
+
+pub fn green(text: &str) -> String {
+    paint_if_enabled(text, "32")
+}
+
+pub fn yellow(text: &str) -> String {
+    paint_if_enabled(text, "33")
+}
+
+pub fn red(text: &str) -> String {
+    paint_if_enabled(text, "31")
+}
+
+pub fn dim(text: &str) -> String {
+    paint_if_enabled(text, "2")
+}
+
+pub fn bold(text: &str) -> String {
+    paint_if_enabled(text, "1")
+}
+
+/// Reverse video, for values that changed since the previous refresh
+pub fn highlight(text: &str) -> String {
+    paint_if_enabled(text, "7")
+}
+
+/// Green for delivered packages, yellow for the rest
+pub fn by_status(text: &str, status: &PackageStatus) -> String {
+    match status.is_final() {
+        true => green(text),
+        false => yellow(text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_color() {
+        assert!(should_color(ColorChoice::Auto, true, false));
+        assert!(!should_color(ColorChoice::Auto, false, false));
+        assert!(!should_color(ColorChoice::Auto, true, true));
+        assert!(should_color(ColorChoice::Always, false, true));
+        assert!(!should_color(ColorChoice::Never, true, false));
+    }
+
+    #[test]
+    fn test_paint() {
+        assert_eq!(paint("Delivered", "32", true), "\x1b[32mDelivered\x1b[0m");
+        assert_eq!(paint("Delivered", "32", false), "Delivered");
+    }
+}
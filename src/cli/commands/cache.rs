@@ -1,19 +1,22 @@
-use crate::cli::display::human_readable_bytes;
+use crate::cli::display::{display_time, human_readable_bytes};
 use crate::cli::url::UrlArgs;
 use clap::Subcommand;
 use packtrack::Result;
-use packtrack::cache::{Cache, FileCache};
+use packtrack::api::{Context, track_urls};
+use packtrack::cache::{Cache, CacheBackend};
+use packtrack::gc::gc;
 use packtrack::settings::Settings;
-use packtrack::url_store::{FileUrlStore, UrlStore};
+use packtrack::url_store::{AnnotatedUrl, FileUrlStore, UrlStore};
 
 pub async fn handle_cache_command(
     command: CacheCommand,
     settings: &Settings,
+    ctx: &Context,
 ) -> Result<()> {
     let cache_file = settings.cache_file.clone();
     match command {
         CacheCommand::Clear => {
-            let mut cache = FileCache::new(cache_file)?;
+            let mut cache = settings.open_cache()?;
             let bytes = cache.size_bytes()?;
             cache.clear();
             cache.save()?;
@@ -22,15 +25,57 @@ pub async fn handle_cache_command(
             return Ok(());
         }
         CacheCommand::Location => {
-            println!("{}", cache_file.display())
+            println!(
+                "{}",
+                settings
+                    .cache_backend
+                    .location(&cache_file)
+            )
+        }
+        CacheCommand::List => {
+            let cache = settings.open_cache()?;
+            let mut urls = cache.get_all_urls();
+            if urls.is_empty() {
+                println!("Cache is empty");
+                return Ok(());
+            }
+            urls.sort();
+            println!("{:<10} {:>7} {:<16} url", "tracker", "entries", "newest");
+            for url in urls {
+                let entries = cache.get_all(&url);
+                // Older entries don't record the tracker
+                let mut trackers: Vec<&str> = entries
+                    .iter()
+                    .filter_map(|e| e.tracker.as_deref())
+                    .collect();
+                trackers.sort();
+                trackers.dedup();
+                if trackers.is_empty() {
+                    trackers.push("unknown");
+                }
+                let newest = entries
+                    .iter()
+                    .map(|e| e.created)
+                    .max()
+                    .map(display_time)
+                    .unwrap_or_default();
+                println!(
+                    "{:<10} {:>7} {:<16} {url}",
+                    trackers.join(","),
+                    entries.len(),
+                    newest
+                );
+            }
         }
         CacheCommand::Size => {
-            let cache = FileCache::new(cache_file)?;
+            let cache = settings.open_cache()?;
             let bytes = cache.size_bytes()?;
             println!("{}", human_readable_bytes(bytes))
         }
         CacheCommand::Prune { dry_run, args } => {
-            if !cache_file.exists() {
+            if settings.cache_backend == CacheBackend::Json
+                && !cache_file.exists()
+            {
                 println!("Cache is empty");
                 return Ok(());
             }
@@ -41,7 +86,7 @@ pub async fn handle_cache_command(
                 .unwrap_or(&settings.urls_file);
             log::info!("Using URLs file {urls_file:#?}");
 
-            let mut cache = FileCache::new(cache_file)?;
+            let mut cache = settings.open_cache()?;
             let cache_size_before = cache.size_bytes()?;
             let url_store = FileUrlStore::new(urls_file.clone())?;
 
@@ -69,7 +114,7 @@ pub async fn handle_cache_command(
                 for url in &removed_urls {
                     log::debug!("Removed {url}");
                 }
-                if removed_urls.len() > 0 {
+                if !removed_urls.is_empty() {
                     println!(
                         "Cache size reduced from {} to {}",
                         human_readable_bytes(cache_size_before),
@@ -83,6 +128,51 @@ pub async fn handle_cache_command(
                 }
             }
         }
+        CacheCommand::Refresh { urls, args } => {
+            let urls_file = args
+                .urls_file
+                .as_ref()
+                .unwrap_or(&settings.urls_file);
+            let stored = FileUrlStore::new(urls_file.clone())?.filter(None);
+            let tracked: Vec<AnnotatedUrl> = ctx
+                .registry
+                .merge_duplicates(stored)
+                .into_iter()
+                .filter(|u| !u.is_placeholder())
+                .filter(|u| urls.is_empty() || urls.contains(&u.url))
+                .collect();
+            let jobs = track_urls(tracked, settings.open_cache()?, ctx).await?;
+            let refreshed = jobs
+                .iter()
+                .filter(|job| job.result.is_ok())
+                .count();
+            println!("Refreshed {refreshed} of {} packages", jobs.len());
+        }
+        CacheCommand::Gc { dry_run, args } => {
+            let urls_file = args
+                .urls_file
+                .as_ref()
+                .unwrap_or(&settings.urls_file);
+            let keep: Vec<String> = FileUrlStore::new(urls_file.clone())?
+                .filter(None)
+                .into_iter()
+                .map(|au| au.url)
+                .collect();
+            let mut cache = settings.open_cache()?;
+            let report = gc(cache.as_mut(), &ctx.registry, Some(&keep));
+            let verb = match dry_run {
+                true => "Would remove",
+                false => "Removed",
+            };
+            println!(
+                "{verb} {} entries that don't parse to a package, and {} \
+                 entries for URLs that aren't in the urls file",
+                report.unparseable, report.unknown_url
+            );
+            if !dry_run && !report.is_empty() {
+                cache.save()?;
+            }
+        }
     }
     Ok(())
 }
@@ -91,6 +181,9 @@ pub async fn handle_cache_command(
 pub enum CacheCommand {
     /// Get the cache size
     Size,
+    /// List the cached URLs, with the tracker that fetched them, the number
+    /// of entries and when the newest one was created
+    List,
     /// Remove cache entries for URLs that are no longer in the URL store
     Prune {
         /// Perform a dry run without modifying the cache
@@ -99,7 +192,24 @@ pub enum CacheCommand {
         #[clap(flatten)]
         args:    UrlArgs,
     },
-    /// Show where the cache is stored on disk
+    /// Remove cache entries that don't parse to a package, and entries for
+    /// URLs that are no longer in the URL store. Tracking does this too.
+    Gc {
+        /// Perform a dry run without modifying the cache
+        #[arg(long)]
+        dry_run: bool,
+        #[clap(flatten)]
+        args:    UrlArgs,
+    },
+    /// Fetch the packages whose cache entries are too old to reuse, and
+    /// update the cache, without showing a report
+    Refresh {
+        /// Only refresh these URLs
+        urls: Vec<String>,
+        #[clap(flatten)]
+        args: UrlArgs,
+    },
+    /// Show where the cache is stored
     Location,
     /// Empty the cache
     Clear,
@@ -0,0 +1,55 @@
+use clap::Subcommand;
+use packtrack::Result;
+use packtrack::api::Context;
+use packtrack::health::check_carriers;
+
+#[derive(Subcommand)]
+pub enum CarriersCommand {
+    /// Check that each carrier is reachable and responds as expected
+    Check,
+}
+
+pub async fn handle_carriers_command(
+    command: CarriersCommand,
+    ctx: &Context,
+) -> Result<()> {
+    match command {
+        CarriersCommand::Check => {
+            let reports = check_carriers(ctx).await;
+            let width = reports
+                .iter()
+                .map(|r| r.tracker.len())
+                .max()
+                .unwrap_or_default();
+            for report in &reports {
+                let latency = format!("{} ms", report.latency.as_millis());
+                let outcome = match &report.result {
+                    Ok(status) => format!("OK (HTTP {status})"),
+                    Err(err) => format!("FAILED: {err}"),
+                };
+                println!("{:width$}  {latency:>8}  {outcome}", report.tracker);
+                log::debug!("Probed {}", report.url);
+            }
+            for name in ctx.registry.names() {
+                if !reports
+                    .iter()
+                    .any(|r| r.tracker == name)
+                {
+                    println!("{name:width$}  skipped (can't be checked)");
+                }
+            }
+            let failed = reports
+                .iter()
+                .filter(|r| !r.is_ok())
+                .count();
+            if failed > 0 {
+                return Err(format!(
+                    "{failed} of {} carriers failed the check",
+                    reports.len()
+                )
+                .into());
+            }
+        }
+    }
+    Ok(())
+}
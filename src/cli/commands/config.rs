@@ -2,6 +2,8 @@ use crate::cli::display::display_settings;
 use clap::Subcommand;
 use packtrack::Result;
 use packtrack::settings::{FileSettingsManager, Settings, SettingsManager};
+use packtrack::tracker::store_in_keyring;
+use std::io;
 
 #[derive(Subcommand)]
 pub enum ConfigCommand {
@@ -11,6 +13,14 @@ pub enum ConfigCommand {
     Set { key: String, value: String },
     /// Reset settings to the defaults
     Reset,
+    /// Store a carrier credential (e.g. an API key) in the OS keyring. The
+    /// secret is read from stdin, so it doesn't end up in your shell history.
+    SetSecret {
+        /// e.g. "ups"
+        carrier: String,
+        /// e.g. "client_secret"
+        key:     String,
+    },
 }
 
 pub fn handle_config_command(
@@ -31,6 +41,17 @@ pub fn handle_config_command(
             settings_manager.save()?;
             display_settings(&settings_manager.settings)?;
         }
+        ConfigCommand::SetSecret { carrier, key } => {
+            eprint!("{carrier} {key}: ");
+            let mut secret = String::new();
+            io::stdin().read_line(&mut secret)?;
+            let secret = secret.trim();
+            if secret.is_empty() {
+                return Err("No secret given".into());
+            }
+            store_in_keyring(&carrier, &key, secret)?;
+            println!("Stored {carrier} {key} in the OS keyring");
+        }
     }
     Ok(())
 }
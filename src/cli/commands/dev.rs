@@ -0,0 +1,87 @@
+use clap::Subcommand;
+use packtrack::Result;
+use packtrack::api::Context;
+use packtrack::sanitize::sanitize;
+use std::fs;
+use std::path::PathBuf;
+
+pub async fn handle_dev_command(
+    command: DevCommand,
+    ctx: &Context,
+) -> Result<()> {
+    match command {
+        DevCommand::Capture {
+            url,
+            name,
+            redact,
+            dir,
+            force,
+        } => {
+            let tracker = ctx.registry.get_handler(&url)?;
+            let raw = tracker
+                .get_raw(&url, &ctx.tracker_context())
+                .await?;
+            if !raw.is_success() {
+                return Err(format!("HTTP {} from {url}", raw.status).into());
+            }
+
+            let mut redact = redact;
+            redact.extend(ctx.default_postcode.clone());
+            let text = sanitize(&raw.body, &url, &redact);
+
+            let extension = match raw.is_html() {
+                true => "html",
+                false => "json",
+            };
+            let path = dir.join(format!("{name}.{extension}"));
+            if path.exists() && !force {
+                return Err(format!(
+                    "{} already exists. Pass --force to overwrite it.",
+                    path.display()
+                )
+                .into());
+            }
+            fs::write(&path, &text)?;
+            println!("Wrote {}", path.display());
+
+            // Check that the mock is still usable after sanitizing
+            match tracker.parse(text) {
+                Ok(package) => println!(
+                    "Parsed by the {} tracker as {}",
+                    tracker.name(),
+                    package.status
+                ),
+                Err(err) => eprintln!(
+                    "Warning: the {} tracker couldn't parse the mock: {err}",
+                    tracker.name()
+                ),
+            }
+            println!(
+                "Check the mock for any personal information that wasn't \
+                 removed before committing it."
+            );
+        }
+    }
+    Ok(())
+}
+
+#[derive(Subcommand)]
+pub enum DevCommand {
+    /// Save a carrier's response as a mock, with personal information removed
+    Capture {
+        /// Tracking URL to fetch
+        url:    String,
+        /// Name of the mock, e.g. "postnl_returned". The file extension is
+        /// added automatically.
+        name:   String,
+        /// Other values to remove, e.g. your name in an HTML page
+        #[arg(short, long)]
+        redact: Vec<String>,
+        /// Directory to save the mock in
+        #[arg(long, default_value = "mocks")]
+        dir:    PathBuf,
+        /// Overwrite an existing mock
+        #[arg(short, long)]
+        force:  bool,
+    },
+}
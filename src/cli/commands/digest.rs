@@ -0,0 +1,218 @@
+use crate::cli::display::{display_event, display_time, display_timewindow};
+use crate::cli::url::UrlArgs;
+use chrono::{DateTime, Local, TimeDelta, Utc};
+use clap::Args;
+use packtrack::Result;
+use packtrack::api::{Context, track_urls};
+use packtrack::digest::{Digest, DigestItem, DigestOptions};
+use packtrack::notify::{Notification, Priority, get_notifiers, notify_all};
+use packtrack::settings::Settings;
+use packtrack::url_store::{FileUrlStore, UrlStore};
+
+#[derive(Args)]
+pub struct DigestArgs {
+    /// Report events from the last this many hours
+    #[arg(long, default_value_t = 24)]
+    since:        i64,
+    /// Report packages without news for this many days as stalled
+    #[arg(long, default_value_t = 3)]
+    stalled_days: i64,
+    /// Don't print anything if there's nothing to report
+    #[arg(short, long)]
+    quiet:        bool,
+    /// Send the digest through the notification channels (e.g. email)
+    /// instead of printing it
+    #[arg(long)]
+    notify:       bool,
+    #[clap(flatten)]
+    args:         UrlArgs,
+}
+
+pub async fn handle_digest(
+    args: DigestArgs,
+    settings: &Settings,
+    ctx: &Context,
+) -> Result<()> {
+    let file = args
+        .args
+        .urls_file
+        .as_ref()
+        .unwrap_or(&settings.urls_file);
+    let url_store = FileUrlStore::new(file.clone())?;
+    let (placeholders, urls): (Vec<_>, Vec<_>) = ctx
+        .registry
+        .merge_duplicates(url_store.filter(None))
+        .into_iter()
+        .partition(|u| u.is_placeholder());
+    let cache = settings.open_cache()?;
+    let jobs = track_urls(urls, cache, ctx).await?;
+
+    let options = DigestOptions {
+        since: TimeDelta::hours(args.since),
+        stalled_after: TimeDelta::days(args.stalled_days),
+        ..DigestOptions::default()
+    };
+    let digest = Digest::new(&jobs, &placeholders, Utc::now(), &options);
+    if digest.is_empty() {
+        if !args.quiet {
+            println!("Nothing to report");
+        }
+        return Ok(());
+    }
+    match args.notify {
+        true => {
+            let notifiers = get_notifiers(&settings.notify, &ctx.credentials)?;
+            let notification = digest_notification(&digest, Local::now());
+            notify_all(&notifiers, &notification).await?;
+        }
+        false => println!("{}", display_digest(&digest)),
+    }
+    Ok(())
+}
+
+/// The digest as one notification, e.g. for an email every morning
+pub(crate) fn digest_notification(
+    digest: &Digest,
+    now: DateTime<Local>,
+) -> Notification {
+    Notification {
+        title:    format!("Your packages on {}", now.format("%a %d %b")),
+        body:     display_digest(digest),
+        url:      None,
+        priority: Priority::Normal,
+        update:   None,
+    }
+}
+
+fn display_digest(digest: &Digest) -> String {
+    let mut sections: Vec<String> = vec![];
+    let mut section = |title: &str, lines: Vec<String>| {
+        if !lines.is_empty() {
+            sections.push(format!("{title}\n  {}", lines.join("\n  ")));
+        }
+    };
+    section(
+        "Arriving today",
+        digest
+            .arriving
+            .iter()
+            .map(|item| {
+                let package = item.package;
+                let when = match (&package.eta_window, package.eta) {
+                    (Some(window), _) => display_timewindow(window),
+                    (None, Some(eta)) => display_time(eta),
+                    (None, None) => "today".into(),
+                };
+                format!("{}: {when}", display_item(item))
+            })
+            .collect(),
+    );
+    section(
+        "Expected today, but no tracking URL yet",
+        digest
+            .pending
+            .iter()
+            .map(|url| {
+                url.description
+                    .clone()
+                    .unwrap_or(url.url.clone())
+            })
+            .collect(),
+    );
+    section(
+        "Delivered yesterday",
+        digest
+            .delivered
+            .iter()
+            .map(|item| match item.package.delivered {
+                Some(delivered) => format!(
+                    "{}: {}",
+                    display_item(item),
+                    display_time(delivered)
+                ),
+                None => display_item(item),
+            })
+            .collect(),
+    );
+    section(
+        "Recent updates",
+        digest
+            .changed
+            .iter()
+            .map(|item| {
+                let latest = item
+                    .package
+                    .events
+                    .iter()
+                    .max_by_key(|e| e.timestamp)
+                    .map(display_event)
+                    .unwrap_or_default();
+                format!("{}: {latest}", display_item(item))
+            })
+            .collect(),
+    );
+    section(
+        "Stalled",
+        digest
+            .stalled
+            .iter()
+            .map(|item| {
+                let last = item
+                    .package
+                    .events
+                    .iter()
+                    .map(|e| e.timestamp)
+                    .max()
+                    .map(display_time)
+                    .unwrap_or_default();
+                format!("{}: no news since {last}", display_item(item))
+            })
+            .collect(),
+    );
+    section(
+        "Waiting to be collected",
+        digest
+            .pickups
+            .iter()
+            .map(|item| {
+                let mut line = display_item(item);
+                if let Some(pickup) = &item.package.pickup {
+                    line += &format!(" at {}", pickup.location);
+                    if let Some(deadline) = pickup.deadline {
+                        line += &format!(
+                            ", collect before {}",
+                            display_time(deadline)
+                        );
+                    }
+                }
+                line
+            })
+            .collect(),
+    );
+    section(
+        "Errors",
+        digest
+            .errors
+            .iter()
+            .map(|job| {
+                let err = job
+                    .result
+                    .as_ref()
+                    .err()
+                    .map(|e| e.to_string())
+                    .unwrap_or_default();
+                format!("{}: {err}", job.url.url)
+            })
+            .collect(),
+    );
+    sections.join("\n\n")
+}
+
+/// "PostNL 3SABCD1234567 (running shoes)"
+fn display_item(item: &DigestItem) -> String {
+    let mut out = item.url.package_name(item.package);
+    if let Some(description) = &item.url.description {
+        out += &format!(" ({description})");
+    }
+    out
+}
@@ -0,0 +1,48 @@
+use crate::cli::url::UrlArgs;
+use clap::Subcommand;
+use packtrack::Result;
+use packtrack::api::Context;
+use packtrack::export::{export_sqlite, histories_from_cache};
+use packtrack::settings::Settings;
+use packtrack::url_store::{FileUrlStore, UrlStore};
+use std::path::PathBuf;
+
+#[derive(Subcommand)]
+pub enum ExportCommand {
+    /// Write the packages, events and ETAs in the cache to an SQLite
+    /// database. Existing packtrack tables in the database are replaced.
+    Sqlite {
+        /// Database file to write to
+        file: PathBuf,
+        #[clap(flatten)]
+        args: UrlArgs,
+    },
+}
+
+pub fn handle_export_command(
+    command: ExportCommand,
+    settings: &Settings,
+    ctx: &Context,
+) -> Result<()> {
+    match command {
+        ExportCommand::Sqlite { file, args } => {
+            let urls_file = args
+                .urls_file
+                .as_ref()
+                .unwrap_or(&settings.urls_file);
+            // Only used for the descriptions, so a missing urls file is fine
+            let urls = FileUrlStore::new(urls_file.clone())
+                .map(|store| store.filter(None))
+                .unwrap_or_default();
+            let cache = settings.open_cache()?;
+            let histories = histories_from_cache(&cache, &ctx.registry, &urls);
+            export_sqlite(&file, &histories)?;
+            println!(
+                "Exported {} packages to {}",
+                histories.len(),
+                file.display()
+            );
+        }
+    }
+    Ok(())
+}
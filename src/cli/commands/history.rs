@@ -0,0 +1,54 @@
+use crate::cli::display::display_history;
+use crate::cli::url::UrlArgs;
+use clap::Args;
+use packtrack::Result;
+use packtrack::api::Context;
+use packtrack::history::history;
+use packtrack::settings::Settings;
+use packtrack::url_store::{AnnotatedUrl, FileUrlStore, UrlStore};
+
+#[derive(Args)]
+pub struct HistoryArgs {
+    /// Part of the URL or description of the package(s)
+    query: String,
+    /// Also show the snapshots in which nothing changed
+    #[arg(long)]
+    all:   bool,
+    #[clap(flatten)]
+    args:  UrlArgs,
+}
+
+pub fn handle_history(
+    args: HistoryArgs,
+    settings: &Settings,
+    ctx: &Context,
+) -> Result<()> {
+    let file = args
+        .args
+        .urls_file
+        .as_ref()
+        .unwrap_or(&settings.urls_file);
+    let cache = settings.open_cache()?;
+    let mut urls = FileUrlStore::new(file.clone())?.filter(Some(&args.query));
+    // Archived packages aren't in the urls file anymore, but their history
+    // can still be in the cache
+    if urls.is_empty() {
+        let mut cached = cache.get_all_urls();
+        cached.sort();
+        urls = cached
+            .into_iter()
+            .filter(|url| url.contains(&args.query))
+            .map(|url| AnnotatedUrl::new(url, None))
+            .collect();
+    }
+    if urls.is_empty() {
+        return Err(format!("No packages match {}", args.query).into());
+    }
+    let mut sections = vec![];
+    for url in &urls {
+        let snapshots = history(&cache, &ctx.registry, &url.url)?;
+        sections.push(display_history(url, &snapshots, args.all));
+    }
+    println!("{}", sections.join("\n\n"));
+    Ok(())
+}
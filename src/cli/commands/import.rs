@@ -0,0 +1,307 @@
+use crate::cli::ingest::confirm;
+use crate::cli::url::UrlArgs;
+use clap::Subcommand;
+use packtrack::Result;
+use packtrack::api::Context;
+use packtrack::email::{
+    FoundUrl, fetch_recent_emails, find_tracking_urls, split_mbox,
+};
+use packtrack::import::{AfterShip, Aggregator, SeventeenTrack, to_urls};
+use packtrack::settings::Settings;
+use packtrack::tracker::TrackerRegistry;
+use packtrack::url_store::{AnnotatedUrl, FileUrlStore, UrlStore};
+use packtrack::utils::check_path_exists;
+use std::path::PathBuf;
+
+#[derive(Subcommand)]
+pub enum ImportCommand {
+    /// Import the shipments in your AfterShip account. Needs an API key in
+    /// `credentials.aftership.api_key`.
+    Aftership {
+        #[clap(flatten)]
+        args: UrlArgs,
+    },
+    /// Import the shipments in your 17TRACK account. Needs an API key in
+    /// `credentials.17track.api_key`.
+    #[command(name = "17track")]
+    SeventeenTrack {
+        #[clap(flatten)]
+        args: UrlArgs,
+    },
+    /// Find tracking links in the recent emails in your IMAP mailbox (see the
+    /// `imap` settings), and offer to add them
+    Imap {
+        /// How many days back to look
+        #[arg(short, long, default_value_t = 14)]
+        days: u32,
+        /// Add all the links without asking
+        #[arg(short, long)]
+        yes:  bool,
+        #[clap(flatten)]
+        args: UrlArgs,
+    },
+    /// Add the tracking links in saved emails: .eml files, or mbox archives
+    Email {
+        #[arg(required = true, value_parser = check_path_exists)]
+        files:   Vec<PathBuf>,
+        /// Only show which links would be added
+        #[arg(long)]
+        dry_run: bool,
+        #[clap(flatten)]
+        args:    UrlArgs,
+    },
+}
+
+pub async fn handle_import_command(
+    command: ImportCommand,
+    settings: &Settings,
+    ctx: &Context,
+) -> Result<()> {
+    let (aggregator, args): (Box<dyn Aggregator>, UrlArgs) = match command {
+        ImportCommand::Aftership { args } => {
+            let api_key = ctx
+                .credentials
+                .require("aftership", "api_key")?;
+            (Box::new(AfterShip::new(api_key)), args)
+        }
+        ImportCommand::SeventeenTrack { args } => {
+            let api_key = ctx
+                .credentials
+                .require("17track", "api_key")?;
+            (Box::new(SeventeenTrack::new(api_key)), args)
+        }
+        ImportCommand::Imap { days, yes, args } => {
+            return import_imap(days, yes, &args, settings, ctx).await;
+        }
+        ImportCommand::Email {
+            files,
+            dry_run,
+            args,
+        } => {
+            let mut found: Vec<FoundUrl> = vec![];
+            for file in files {
+                let raw = std::fs::read(&file)?;
+                for email in split_mbox(&raw) {
+                    match find_tracking_urls(email, &ctx.registry) {
+                        Ok(urls) => found.extend(urls),
+                        Err(err) => log::warn!("{}: {err}", file.display()),
+                    }
+                }
+            }
+            return add_found_urls(found, &args, settings, ctx, true, dry_run);
+        }
+    };
+    let shipments = aggregator.shipments().await?;
+    println!(
+        "Found {} shipments in your {} account",
+        shipments.len(),
+        aggregator.name()
+    );
+    let result = to_urls(shipments, &ctx.registry);
+    for (shipment, reason) in &result.skipped {
+        println!("Skipped {}: {reason}", shipment.tracking_number);
+    }
+
+    let file = args
+        .urls_file
+        .as_ref()
+        .unwrap_or(&settings.urls_file);
+    let mut url_store = FileUrlStore::new(file.clone())?;
+    let added = add_all(&mut url_store, result.urls);
+    url_store.save()?;
+    println!("Added {added} urls");
+    Ok(())
+}
+
+/// Find tracking links in the emails of the last `days` days in the IMAP
+/// mailbox, and offer to add them
+pub async fn import_imap(
+    days: u32,
+    yes: bool,
+    args: &UrlArgs,
+    settings: &Settings,
+    ctx: &Context,
+) -> Result<()> {
+    let password = ctx
+        .credentials
+        .require("imap", "password")?;
+    let emails = fetch_recent_emails(&settings.imap, &password, days).await?;
+    let mut found: Vec<FoundUrl> = vec![];
+    for email in emails {
+        match find_tracking_urls(&email, &ctx.registry) {
+            Ok(urls) => found.extend(urls),
+            Err(err) => log::warn!("{err}"),
+        }
+    }
+    add_found_urls(found, args, settings, ctx, yes, false)
+}
+
+/// Add the entries to the url store, skipping the ones it refuses (e.g. a URL
+/// that is part of one it already has) instead of giving up on the rest.
+/// Returns how many were added.
+fn add_all(
+    url_store: &mut dyn UrlStore,
+    urls: impl IntoIterator<Item = AnnotatedUrl>,
+) -> usize {
+    let mut added = 0;
+    for aurl in urls {
+        let url = aurl.url.clone();
+        match url_store.add(aurl) {
+            Ok(()) => {
+                println!("Added {url}");
+                added += 1;
+            }
+            Err(err) => println!("Skipped {url}: {err}"),
+        }
+    }
+    added
+}
+
+/// The found links for packages that aren't in the url store yet, each
+/// package once. Links for the same package (see
+/// `TrackerRegistry::shipment_key`), e.g. with and without the postcode, count
+/// as one, of which the longest is kept.
+fn new_found_urls(
+    found: Vec<FoundUrl>,
+    stored: &[AnnotatedUrl],
+    registry: &TrackerRegistry,
+) -> Vec<FoundUrl> {
+    // Each URL with its carrier and tracking number
+    type Keyed = (String, Option<(String, String)>);
+    let with_key = |url: &str| (url.to_owned(), registry.shipment_key(url));
+    let same = |(url, key): &Keyed, (other, other_key): &Keyed| {
+        url == other || key.is_some() && key == other_key
+    };
+    let known: Vec<_> = stored
+        .iter()
+        .map(|u| with_key(&u.url))
+        .collect();
+    let mut new: Vec<(_, FoundUrl)> = vec![];
+    for f in found {
+        let key = with_key(&f.url);
+        if known.iter().any(|k| same(k, &key)) {
+            continue;
+        }
+        match new
+            .iter_mut()
+            .find(|(k, _)| same(k, &key))
+        {
+            Some((k, existing)) if f.url.len() > existing.url.len() => {
+                (*k, *existing) = (key, f);
+            }
+            Some(_) => {}
+            None => new.push((key, f)),
+        }
+    }
+    new.into_iter()
+        .map(|(_, f)| f)
+        .collect()
+}
+
+/// Add the tracking links found in emails to the urls file, asking about each
+/// one unless `yes`, or only list them if `dry_run`. Links that are already in
+/// the file are skipped.
+fn add_found_urls(
+    found: Vec<FoundUrl>,
+    args: &UrlArgs,
+    settings: &Settings,
+    ctx: &Context,
+    yes: bool,
+    dry_run: bool,
+) -> Result<()> {
+    let file = args
+        .urls_file
+        .as_ref()
+        .unwrap_or(&settings.urls_file);
+    let mut url_store = FileUrlStore::new(file.clone())?;
+    // Skip URLs we already have, and ones found in several emails
+    let found = new_found_urls(found, &url_store.filter(None), &ctx.registry);
+    if found.is_empty() {
+        println!("No new tracking links found");
+        return Ok(());
+    }
+
+    if dry_run {
+        for FoundUrl { url, shop } in &found {
+            println!(
+                "Would add {}",
+                AnnotatedUrl::new(url.clone(), shop.clone())
+            );
+        }
+        println!("Would add {} urls", found.len());
+        return Ok(());
+    }
+
+    let mut confirmed = vec![];
+    for FoundUrl { url, shop } in found {
+        let aurl = AnnotatedUrl::new(url, shop);
+        if yes || confirm(&format!("Add {aurl}?"))? {
+            confirmed.push(aurl);
+        }
+    }
+    let added = add_all(&mut url_store, confirmed);
+    url_store.save()?;
+    println!("Added {added} urls");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn found(url: &str) -> FoundUrl {
+        FoundUrl {
+            url:  url.into(),
+            shop: None,
+        }
+    }
+
+    #[test]
+    fn test_new_found_urls() {
+        let registry = TrackerRegistry::default();
+        let postnl = "https://jouw.postnl.nl/track-and-trace/3SABCD1234567";
+        let with_postcode = format!("{postnl}-NL-1234AB");
+        let stored = vec![AnnotatedUrl::from("https://example.com/track/1")];
+        let new = new_found_urls(
+            vec![
+                found(postnl),
+                found(&with_postcode),
+                found(postnl),
+                found("https://example.com/track/1"),
+                found("https://example.com/track/12"),
+            ],
+            &stored,
+            &registry,
+        );
+        let new: Vec<_> = new
+            .iter()
+            .map(|f| f.url.as_str())
+            .collect();
+        assert_eq!(
+            new,
+            [with_postcode.as_str(), "https://example.com/track/12"]
+        );
+    }
+
+    #[test]
+    fn test_add_all() -> Result<()> {
+        let path = std::env::temp_dir()
+            .join(format!("packtrack-import-test-{}.json", std::process::id()));
+        std::fs::write(&path, "[]")?;
+        let mut store = FileUrlStore::new(path.clone())?;
+        // The second one is part of the first, so the store refuses it
+        let added = add_all(
+            &mut store,
+            [
+                "example.com/track/12",
+                "example.com/track/1",
+                "example.com/2",
+            ]
+            .map(AnnotatedUrl::from),
+        );
+        std::fs::remove_file(&path)?;
+        assert_eq!(added, 2);
+        assert_eq!(store.filter(None).len(), 2);
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+use crate::cli::import::import_imap;
+use crate::cli::url::UrlArgs;
+use clap::Subcommand;
+use packtrack::Result;
+use packtrack::api::Context;
+use packtrack::clipboard::{ClipboardWatcher, SystemClipboard};
+use packtrack::settings::Settings;
+use packtrack::url_store::{AnnotatedUrl, FileUrlStore, UrlStore};
+use std::io::{self, Write};
+use std::time::Duration;
+
+#[derive(Subcommand)]
+pub enum IngestCommand {
+    /// Find tracking links in the emails in your IMAP mailbox (the same as
+    /// `packtrack import imap`)
+    Imap {
+        /// How many days back to look
+        #[arg(short, long, default_value_t = 14)]
+        days: u32,
+        /// Add all the links without asking
+        #[arg(short, long)]
+        yes:  bool,
+        #[clap(flatten)]
+        args: UrlArgs,
+    },
+    /// Watch the clipboard, and offer to add tracking links as you copy them
+    Clipboard {
+        /// How often to check the clipboard, in milliseconds
+        #[arg(short, long, default_value_t = 500)]
+        interval: u64,
+        #[clap(flatten)]
+        args:     UrlArgs,
+    },
+}
+
+pub async fn handle_ingest_command(
+    command: IngestCommand,
+    settings: &Settings,
+    ctx: &Context,
+) -> Result<()> {
+    match command {
+        IngestCommand::Imap { days, yes, args } => {
+            import_imap(days, yes, &args, settings, ctx).await?
+        }
+        IngestCommand::Clipboard { interval, args } => {
+            let file = args
+                .urls_file
+                .as_ref()
+                .unwrap_or(&settings.urls_file);
+            let mut watcher = ClipboardWatcher::new(SystemClipboard::new()?);
+            let mut ticker =
+                tokio::time::interval(Duration::from_millis(interval));
+            println!(
+                "Watching the clipboard for tracking links (Ctrl-C to stop)"
+            );
+            loop {
+                ticker.tick().await;
+                let Some(url) = watcher.poll(&ctx.registry) else {
+                    continue;
+                };
+                // Reload every time, in case the file was changed meanwhile
+                let mut url_store = FileUrlStore::new(file.clone())?;
+                if url_store
+                    .filter(None)
+                    .iter()
+                    .any(|u| u.url == url)
+                {
+                    println!("Already tracking {url}");
+                    continue;
+                }
+                if confirm(&format!("Add {url}?"))? {
+                    url_store.add(AnnotatedUrl::new(url.clone(), None))?;
+                    url_store.save()?;
+                    println!("Added {url}");
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Ask a yes/no question on the terminal; no is the default
+pub(crate) fn confirm(question: &str) -> Result<bool> {
+    print!("{question} [y/N] ");
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
@@ -1,4 +1,22 @@
 pub mod cache;
+pub mod carriers;
 pub mod config;
+pub mod dev;
+pub mod digest;
+pub mod export;
+pub mod history;
+pub mod import;
+pub mod ingest;
+pub mod note;
+pub mod prompt;
+pub mod purge;
+pub mod remind;
+pub mod schema;
+pub mod serve;
+pub mod service;
+pub mod stats;
+pub mod statusbar;
+pub mod sync;
 pub mod track;
 pub mod url;
+pub mod watch;
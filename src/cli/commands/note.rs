@@ -0,0 +1,48 @@
+use crate::cli::url::UrlArgs;
+use clap::Args;
+use packtrack::Result;
+use packtrack::settings::Settings;
+use packtrack::url_store::{FileUrlStore, UrlError, UrlStore};
+
+#[derive(Args)]
+pub struct NoteArgs {
+    /// Fragment of the package's URL, label or description
+    query: String,
+    /// The note, e.g. "left with the neighbours last time". Without one, the
+    /// package's notes are printed.
+    note:  Option<String>,
+    /// Remove the package's notes
+    #[arg(long, conflicts_with = "note")]
+    clear: bool,
+    #[clap(flatten)]
+    args:  UrlArgs,
+}
+
+pub fn handle_note(args: NoteArgs, settings: &Settings) -> Result<()> {
+    let file = args
+        .args
+        .urls_file
+        .as_ref()
+        .unwrap_or(&settings.urls_file);
+    let mut url_store = FileUrlStore::new(file.clone())?;
+    if args.note.is_none() && !args.clear {
+        let urls = url_store.filter(Some(&args.query));
+        let [url] = urls.as_slice() else {
+            return Err(match urls.is_empty() {
+                true => UrlError::NotFound(args.query).into(),
+                false => UrlError::Ambiguous(args.query).into(),
+            });
+        };
+        for note in &url.notes {
+            println!("{note}");
+        }
+        return Ok(());
+    }
+    let url = url_store.note(&args.query, args.note)?;
+    url_store.save()?;
+    match url.notes.last() {
+        Some(note) => println!("Added note to {}: {note}", url.url),
+        None => println!("Removed the notes of {}", url.url),
+    }
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use crate::cli::url::UrlArgs;
+use chrono::Utc;
+use packtrack::Result;
+use packtrack::api::Context;
+use packtrack::prompt::PromptSummary;
+use packtrack::settings::Settings;
+use packtrack::url_store::{FileUrlStore, UrlStore};
+
+/// Print the summary for a shell prompt. Errors are only logged, because a
+/// prompt segment shouldn't print error messages before every command.
+pub fn handle_prompt(args: UrlArgs, settings: &Settings, ctx: &Context) {
+    match summarize(args, settings, ctx) {
+        Ok(summary) if summary.in_progress > 0 => println!("{summary}"),
+        Ok(_) => {}
+        Err(err) => log::warn!("Couldn't summarise packages: {err}"),
+    }
+}
+
+fn summarize(
+    args: UrlArgs,
+    settings: &Settings,
+    ctx: &Context,
+) -> Result<PromptSummary> {
+    let file = args
+        .urls_file
+        .as_ref()
+        .unwrap_or(&settings.urls_file);
+    let urls = FileUrlStore::new(file.clone())?.filter(None);
+    let cache = settings.open_cache()?;
+    Ok(PromptSummary::from_cache(
+        &urls,
+        &cache,
+        &ctx.registry,
+        Utc::now(),
+    ))
+}
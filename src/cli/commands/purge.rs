@@ -0,0 +1,55 @@
+use chrono::{TimeDelta, Utc};
+use clap::Args;
+use packtrack::Result;
+use packtrack::cache::{Cache, CacheBackend, MemoryCache};
+use packtrack::notify::{SentNotifications, get_sent_notifications_file};
+use packtrack::purge::purge;
+use packtrack::reminders::{ReminderState, get_reminder_state_file};
+use packtrack::settings::Settings;
+use packtrack::utils::parse_age;
+
+#[derive(Args)]
+#[group(required = true, multiple = false)]
+pub struct PurgeArgs {
+    /// Delete data older than this, e.g. "90d", "2w" or "12h"
+    #[arg(long, value_name = "AGE", value_parser = parse_age)]
+    older_than: Option<TimeDelta>,
+    /// Delete all data
+    #[arg(long)]
+    all:        bool,
+}
+
+pub fn handle_purge(args: PurgeArgs, settings: &Settings) -> Result<()> {
+    let cutoff = match args.all {
+        true => None,
+        false => args
+            .older_than
+            .map(|age| Utc::now() - age),
+    };
+    // Without a cache file there's nothing to purge from the JSON cache
+    let json = settings.cache_backend == CacheBackend::Json;
+    let mut cache: Box<dyn Cache> = match json && !settings.cache_file.exists()
+    {
+        true => Box::new(MemoryCache::default()),
+        false => settings.open_cache()?,
+    };
+    let state_file = get_reminder_state_file()?;
+    let mut reminders = ReminderState::load(&state_file)?;
+    let sent_file = get_sent_notifications_file()?;
+    let mut notifications = SentNotifications::load(&sent_file)?;
+
+    let report =
+        purge(cache.as_mut(), &mut reminders, &mut notifications, cutoff);
+    if report.is_empty() {
+        println!("Nothing to purge");
+        return Ok(());
+    }
+    cache.save()?;
+    reminders.save(&state_file)?;
+    notifications.save(&sent_file)?;
+    println!(
+        "Deleted {} cache entries, {} reminders and {} sent notifications",
+        report.cache_entries, report.reminders, report.notifications
+    );
+    Ok(())
+}
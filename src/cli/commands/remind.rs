@@ -0,0 +1,83 @@
+use crate::cli::url::UrlArgs;
+use chrono::{Local, TimeDelta, Utc};
+use clap::Args;
+use packtrack::Result;
+use packtrack::api::{Context, Job, track_urls};
+use packtrack::notify::{Notifier, Priority, get_notifiers, notify_all};
+use packtrack::reminders::{
+    ReminderOptions, ReminderState, due_pickup_reminders,
+    get_reminder_state_file,
+};
+use packtrack::settings::Settings;
+use packtrack::url_store::{FileUrlStore, UrlStore};
+
+#[derive(Args)]
+pub struct RemindArgs {
+    /// Remind when there are fewer than this many days left to collect a
+    /// package. Defaults to the `pickup_reminder_days` setting.
+    #[arg(long)]
+    days: Option<i64>,
+    #[clap(flatten)]
+    args: UrlArgs,
+}
+
+pub async fn handle_remind(
+    args: RemindArgs,
+    settings: &Settings,
+    ctx: &Context,
+) -> Result<()> {
+    let file = args
+        .args
+        .urls_file
+        .as_ref()
+        .unwrap_or(&settings.urls_file);
+    let urls = ctx
+        .registry
+        .merge_duplicates(FileUrlStore::new(file.clone())?.filter(None))
+        .into_iter()
+        .filter(|u| !u.is_placeholder())
+        .collect();
+    let cache = settings.open_cache()?;
+    let jobs = track_urls(urls, cache, ctx).await?;
+
+    let notifiers = get_notifiers(&settings.notify, &ctx.credentials)?;
+    let days = args
+        .days
+        .unwrap_or(settings.pickup_reminder_days);
+    send_pickup_reminders(&jobs, days, settings, &notifiers).await
+}
+
+/// Send the reminders that are due for the packages waiting at a pickup point
+/// with fewer than `days` left, and remember them so they aren't sent again
+/// too soon. Also used by watch mode.
+pub async fn send_pickup_reminders<'j>(
+    jobs: impl IntoIterator<Item = &'j Job>,
+    days: i64,
+    settings: &Settings,
+    notifiers: &[Box<dyn Notifier>],
+) -> Result<()> {
+    let options = ReminderOptions {
+        within: TimeDelta::days(days),
+        ..ReminderOptions::default()
+    };
+    let state_file = get_reminder_state_file()?;
+    let mut state = ReminderState::load(&state_file)?;
+    let now = Utc::now();
+    let due = due_pickup_reminders(jobs, &mut state, &options, now);
+    let quiet = settings
+        .notify
+        .quiet_hours
+        .is_some_and(|hours| hours.contains(Local::now().time()));
+    for (url, notification) in due {
+        // Not marked as sent, so a later run sends it
+        if quiet && notification.priority < Priority::High {
+            log::info!("Not reminding about {url} during quiet hours");
+            continue;
+        }
+        match notify_all(notifiers, &notification).await {
+            Ok(()) => state.mark_sent(&url, now),
+            Err(err) => log::error!("Couldn't send reminder for {url}: {err}"),
+        }
+    }
+    state.save(&state_file)
+}
@@ -0,0 +1,8 @@
+use packtrack::Result;
+use packtrack::output::job_schema;
+
+/// Print the JSON Schema for the machine-readable output
+pub fn handle_schema() -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(&job_schema())?);
+    Ok(())
+}
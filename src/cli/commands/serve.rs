@@ -0,0 +1,113 @@
+use crate::cli::url::UrlArgs;
+use clap::Args;
+use packtrack::Result;
+use packtrack::api::Context;
+use packtrack::settings::Settings;
+use std::net::SocketAddr;
+
+#[derive(Args)]
+pub struct ServeArgs {
+    /// Address to listen on. Use e.g. "0.0.0.0:8080" to make the API
+    /// available to other devices on the network.
+    #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:8080")]
+    listen: SocketAddr,
+    #[clap(flatten)]
+    args:   UrlArgs,
+}
+
+/// Serve the packages over HTTP until the process is stopped. With
+/// `server.users`, every user gets their own urls file and cache.
+#[cfg(feature = "server")]
+pub async fn handle_serve(
+    args: ServeArgs,
+    settings: &Settings,
+    ctx: Context,
+) -> Result<()> {
+    use packtrack::cache::CacheBackend;
+    use packtrack::polling::PollingSettings;
+    use packtrack::server::{ServerState, User, serve, serve_users, user_file};
+    use packtrack::url_store::{FileUrlStore, UrlStore};
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    let ctx = Arc::new(ctx);
+    // `user` keeps a shared (Redis) cache apart per user
+    let state =
+        |urls_file: PathBuf, cache_file: PathBuf, user: Option<&str>| {
+            let backend = settings.cache_backend.clone();
+            let user = user.map(str::to_owned);
+            ServerState::new(
+                ctx.clone(),
+                Box::new(move || {
+                    let store: Box<dyn UrlStore> =
+                        Box::new(FileUrlStore::new(urls_file.clone())?);
+                    Ok(store)
+                }),
+                Box::new(move || match &user {
+                    Some(user) => backend.open_namespaced(&cache_file, user),
+                    None => backend.open(&cache_file),
+                }),
+            )
+            .with_polling(PollingSettings {
+                min_minutes: settings.polling.min_minutes,
+                max_minutes: settings.polling.max_minutes,
+            })
+        };
+    let urls_file = args
+        .args
+        .urls_file
+        .unwrap_or(settings.urls_file.clone());
+    let listener = tokio::net::TcpListener::bind(args.listen).await?;
+    if settings.server.users.is_empty() {
+        let state = state(urls_file, settings.cache_file.clone(), None);
+        println!("Serving the packages on http://{}", args.listen);
+        return serve(listener, state).await;
+    }
+
+    let mut users = vec![];
+    for name in &settings.server.users {
+        let token = ctx
+            .credentials
+            .require("server", name)?;
+        let user_urls = user_file(&urls_file, name);
+        let user_cache = user_file(&settings.cache_file, name);
+        create_if_missing(&user_urls, "")?;
+        if settings.cache_backend == CacheBackend::Json {
+            create_if_missing(&user_cache, "{}")?;
+        }
+        users.push(User::new(
+            name.clone(),
+            token,
+            state(user_urls, user_cache, Some(name)),
+        ));
+    }
+    println!(
+        "Serving the packages of {} on http://{}",
+        settings.server.users.join(", "),
+        args.listen
+    );
+    serve_users(listener, users).await
+}
+
+/// Start a new user off with an empty file, as the stores need one
+#[cfg(feature = "server")]
+fn create_if_missing(path: &std::path::Path, contents: &str) -> Result<()> {
+    if !path.try_exists()? {
+        log::info!("Creating {}", path.display());
+        std::fs::write(path, contents)?;
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "server"))]
+pub async fn handle_serve(
+    _args: ServeArgs,
+    _settings: &Settings,
+    _ctx: Context,
+) -> Result<()> {
+    Err(
+        "The HTTP API needs the `server` feature. Reinstall packtrack with \
+         `--features server` to enable this."
+            .into(),
+    )
+}
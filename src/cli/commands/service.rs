@@ -0,0 +1,46 @@
+use clap::Subcommand;
+use packtrack::Result;
+use packtrack::service::{systemd_units, systemd_user_dir};
+use std::net::SocketAddr;
+
+#[derive(Subcommand)]
+pub enum ServiceCommand {
+    /// Write systemd user units that run `packtrack watch --daemon`, and
+    /// `packtrack remind` every hour
+    Install {
+        /// Serve a /healthz endpoint on this address, e.g. "127.0.0.1:8787"
+        #[arg(long, value_name = "ADDR")]
+        healthz: Option<SocketAddr>,
+        /// Print the units instead of writing them
+        #[arg(long)]
+        print:   bool,
+    },
+}
+
+pub fn handle_service_command(command: ServiceCommand) -> Result<()> {
+    match command {
+        ServiceCommand::Install { healthz, print } => {
+            let exe = std::env::current_exe()?;
+            let units = systemd_units(&exe, healthz);
+            if print {
+                for (name, contents) in units {
+                    println!("# {name}\n{contents}");
+                }
+                return Ok(());
+            }
+            let dir = systemd_user_dir()?;
+            std::fs::create_dir_all(&dir)?;
+            for (name, contents) in units {
+                let path = dir.join(name);
+                std::fs::write(&path, contents)?;
+                println!("Wrote {}", path.display());
+            }
+            println!(
+                "\nStart them with:\n  systemctl --user daemon-reload\n  \
+                 systemctl --user enable --now packtrack.service \
+                 packtrack-remind.timer"
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use chrono::TimeDelta;
+use clap::Subcommand;
+use packtrack::Result;
+use packtrack::api::Context;
+use packtrack::settings::Settings;
+use packtrack::stats::{deliveries_from_cache, punctuality};
+
+#[derive(Subcommand)]
+pub enum StatsCommand {
+    /// How often each carrier delivered within the promised window
+    Punctuality,
+}
+
+pub fn handle_stats_command(
+    command: StatsCommand,
+    settings: &Settings,
+    ctx: &Context,
+) -> Result<()> {
+    match command {
+        StatsCommand::Punctuality => {
+            let cache = settings.open_cache()?;
+            let deliveries = deliveries_from_cache(&cache, &ctx.registry);
+            if deliveries.is_empty() {
+                println!(
+                    "No delivered packages with a promised delivery window in \
+                     the cache yet"
+                );
+                return Ok(());
+            }
+            println!(
+                "{:<12} {:>10} {:>8} {:>12}",
+                "carrier", "deliveries", "on time", "average slip"
+            );
+            for stats in punctuality(&deliveries) {
+                println!(
+                    "{:<12} {:>10} {:>7.0}% {:>12}",
+                    stats.carrier,
+                    stats.deliveries,
+                    stats.on_time_percentage(),
+                    display_slip(stats.average_slip)
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// "+1h 05m", "-20m", "0m"
+fn display_slip(slip: TimeDelta) -> String {
+    let sign = match slip {
+        slip if slip > TimeDelta::zero() => "+",
+        slip if slip < TimeDelta::zero() => "-",
+        _ => "",
+    };
+    let minutes = slip.num_minutes().abs();
+    match minutes / 60 {
+        0 => format!("{sign}{minutes}m"),
+        hours => format!("{sign}{hours}h {:02}m", minutes % 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_slip() {
+        assert_eq!(display_slip(TimeDelta::minutes(65)), "+1h 05m");
+        assert_eq!(display_slip(TimeDelta::minutes(-20)), "-20m");
+        assert_eq!(display_slip(TimeDelta::zero()), "0m");
+    }
+}
@@ -0,0 +1,31 @@
+use crate::cli::url::UrlArgs;
+use chrono::Utc;
+use packtrack::Result;
+use packtrack::api::Context;
+use packtrack::settings::Settings;
+use packtrack::statusbar::StatusBar;
+use packtrack::url_store::{FileUrlStore, UrlStore};
+
+/// Print the status bar JSON. Like the prompt, errors are only logged, and
+/// the module shows nothing.
+pub fn handle_statusbar(args: UrlArgs, settings: &Settings, ctx: &Context) {
+    match status_bar(args, settings, ctx) {
+        Ok(json) => println!("{json}"),
+        Err(err) => log::warn!("Couldn't summarise packages: {err}"),
+    }
+}
+
+fn status_bar(
+    args: UrlArgs,
+    settings: &Settings,
+    ctx: &Context,
+) -> Result<String> {
+    let file = args
+        .urls_file
+        .as_ref()
+        .unwrap_or(&settings.urls_file);
+    let urls = FileUrlStore::new(file.clone())?.filter(None);
+    let cache = settings.open_cache()?;
+    let bar = StatusBar::from_cache(&urls, &cache, &ctx.registry, Utc::now());
+    Ok(serde_json::to_string(&bar)?)
+}
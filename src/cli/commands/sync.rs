@@ -0,0 +1,39 @@
+use crate::cli::url::UrlArgs;
+use packtrack::Result;
+use packtrack::api::Context;
+use packtrack::archive::{ArchivedUrls, get_archived_urls_file};
+use packtrack::notify::{SentNotifications, get_sent_notifications_file};
+use packtrack::settings::Settings;
+use packtrack::sync::{get_backend, sync};
+use packtrack::url_store::FileUrlStore;
+
+pub async fn handle_sync(
+    args: UrlArgs,
+    settings: &Settings,
+    ctx: &Context,
+) -> Result<()> {
+    let backend = get_backend(&settings.sync, &ctx.credentials)?;
+    let file = args
+        .urls_file
+        .as_ref()
+        .unwrap_or(&settings.urls_file);
+    let mut url_store = FileUrlStore::new(file.clone())?;
+    let archived_file = get_archived_urls_file()?;
+    let mut archived = ArchivedUrls::load(&archived_file)?;
+    let sent_file = get_sent_notifications_file()?;
+    let mut sent = SentNotifications::load(&sent_file)?;
+    let report =
+        sync(&mut url_store, &mut archived, &mut sent, backend.as_ref())
+            .await?;
+    archived.save(&archived_file)?;
+    sent.save(&sent_file)?;
+    println!(
+        "Synced with {}: {} URLs pulled, {} URLs pushed, {} archived URLs \
+         removed",
+        backend.describe(),
+        report.pulled,
+        report.pushed,
+        report.removed
+    );
+    Ok(())
+}
@@ -1,20 +1,40 @@
-use crate::cli::display::{display_job, heading, line};
-use clap::Args;
+use crate::cli::csv::csv_jobs;
+use crate::cli::display::{
+    display_job, display_placeholder, display_qr, display_verification,
+    heading, line,
+};
+use crate::cli::fields::{DEFAULT_FIELDS, Field, display_fields};
+use crate::cli::i18n::{Label, tr};
+use crate::cli::markdown::markdown_jobs;
+use crate::cli::table::table_jobs;
+use chrono::Utc;
+use clap::{Args, ValueEnum};
 use log;
 use packtrack::Result;
 use packtrack::api::Job;
-use packtrack::api::{Context, track_urls};
-use packtrack::cache::FileCache;
+use packtrack::api::{Context, stale_urls, track_urls, track_urls_with};
+use packtrack::archive::{archive, expired_urls, remember_archived};
+use packtrack::cache::{Cache, MemoryCache};
+use packtrack::diff::diff;
+use packtrack::gc::gc_newest;
+use packtrack::history::jobs_as_of;
+use packtrack::ical::jobs_to_ical;
+use packtrack::output::{job_to_json_line, jobs_to_json, jobs_to_yaml};
 use packtrack::settings::Settings;
-use packtrack::url_store::{AnnotatedUrl, FileUrlStore, UrlStore};
-use packtrack::utils::check_path_exists;
+use packtrack::tracker::{Package, demo_urls};
+use packtrack::url_store::{AnnotatedUrl, FileUrlStore, UrlStore, filter};
+use packtrack::utils::{UtcTime, check_path_exists, parse_time};
+use packtrack::verify::{CachedPackage, Verification, cached_packages, verify};
+use std::borrow::Borrow;
 use std::cmp::Ordering;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
 use std::time::Instant;
 
 #[derive(Args)]
 pub struct TrackArgs {
-    /// Either a new URL, or a fragment of an existing URL
+    /// Either a new URL or tracking number, or a fragment of an existing URL
     pub url: Option<String>,
 
     /// Path to the URLs file
@@ -33,6 +53,10 @@ pub struct TrackArgs {
     #[arg(short, long)]
     pub recipient: Option<String>,
 
+    /// Only show the packages with this tag
+    #[arg(long)]
+    pub tag: Option<String>,
+
     /// Max age for cache entries to be reused
     #[arg(short = 'C', long)]
     pub cache_seconds: Option<usize>,
@@ -53,6 +77,66 @@ pub struct TrackArgs {
     /// Recipient postcode (sometimes required to get full info)
     #[arg(short, long)]
     pub postcode: Option<String>,
+
+    /// Show made-up packages instead of your own (no network access needed)
+    #[arg(long)]
+    pub demo: bool,
+
+    /// Fetch fresh data for every package, and report how it differs from the
+    /// newest cache entry
+    #[arg(long, conflicts_with = "demo")]
+    pub verify: bool,
+
+    /// How to print the results
+    #[arg(short, long, value_enum, default_value_t, conflicts_with = "verify")]
+    pub output: OutputFormat,
+
+    /// Which fields to print per package, e.g. "barcode,carrier,eta,status".
+    /// Works with the text, table and csv output.
+    #[arg(long, value_enum, value_delimiter = ',', conflicts_with = "verify")]
+    pub fields: Vec<Field>,
+
+    /// Show the tracking URL as a QR code, to open it on your phone. Only
+    /// works when tracking a single package.
+    #[arg(long, conflicts_with_all = ["output", "verify"])]
+    pub qr: bool,
+
+    /// Mark what changed since the previous cache entry of each package: new
+    /// events, and the old status and ETA if they changed
+    #[arg(long, conflicts_with_all = ["output", "fields", "verify", "demo"])]
+    pub diff: bool,
+
+    /// Show the packages as they were at this time, from the newest cache
+    /// entries before it, e.g. "yesterday 09:00", "2026-10-14 08:00" or
+    /// "12h" (ago). Nothing is fetched.
+    #[arg(
+        long,
+        value_name = "TIME",
+        value_parser = parse_time,
+        conflicts_with_all = ["verify", "diff", "demo", "no_cache"]
+    )]
+    pub as_of: Option<UtcTime>,
+}
+
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub enum OutputFormat {
+    /// The human-readable report
+    #[default]
+    Text,
+    /// A JSON array with one object per URL (see `packtrack schema`)
+    Json,
+    /// One JSON object per line, printed as soon as each URL is tracked
+    Jsonl,
+    /// A YAML list with the same fields as the JSON
+    Yaml,
+    /// An iCalendar file with the delivery windows of the packages in transit
+    Ical,
+    /// Markdown sections and tables, e.g. to paste into notes
+    Markdown,
+    /// One aligned table, with a row per package
+    Table,
+    /// Comma-separated values, with a row per package
+    Csv,
 }
 
 /// Provide an ordering for two jobs, based on various time fields.
@@ -82,13 +166,37 @@ fn order_jobs(a: &Job, b: &Job) -> Ordering {
     a_time.cmp(&b_time)
 }
 
-/// Display jobs to the user in the CLI
-pub fn display_jobs(jobs: Vec<Job>, completed_detail: bool) {
-    let mut completed: Vec<Job> = Vec::new(); // Packages with a final status
-    let mut in_progress: Vec<Job> = Vec::new();
-    let mut errors: Vec<Job> = Vec::new();
+/// Display the packages that don't have a tracking URL yet, soonest first
+pub fn display_pending(mut pending: Vec<AnnotatedUrl>) {
+    if pending.is_empty() {
+        return;
+    }
+    pending.sort_by_key(|u| (u.expected.is_none(), u.expected));
+    heading(&tr(Label::PendingTracking).to_lowercase());
+    let s = pending
+        .iter()
+        .map(display_placeholder)
+        .collect::<Vec<_>>()
+        .join("\n");
+    println!("{s}")
+}
+
+/// The jobs grouped for display. `J` is either `Job` or `&Job`.
+pub struct JobGroups<J = Job> {
+    /// Packages with a final status
+    pub completed:   Vec<J>,
+    pub in_progress: Vec<J>,
+    pub errors:      Vec<J>,
+}
+
+/// Group the jobs by whether they are completed, in progress or failed, and
+/// sort the packages by time
+pub fn group_jobs<J: Borrow<Job>>(jobs: Vec<J>) -> JobGroups<J> {
+    let mut completed: Vec<J> = Vec::new();
+    let mut in_progress: Vec<J> = Vec::new();
+    let mut errors: Vec<J> = Vec::new();
     for job in jobs {
-        match &job.result {
+        match &job.borrow().result {
             Ok(package) => match package.status.is_final() {
                 true => completed.push(job),
                 false => in_progress.push(job),
@@ -97,12 +205,56 @@ pub fn display_jobs(jobs: Vec<Job>, completed_detail: bool) {
         }
     }
     for list in [&mut completed, &mut in_progress] {
-        list.sort_by(order_jobs);
+        list.sort_by(|a, b| order_jobs(a.borrow(), b.borrow()));
+    }
+    JobGroups {
+        completed,
+        in_progress,
+        errors,
     }
+}
+
+/// Display jobs to the user in the CLI
+pub fn display_jobs(jobs: Vec<Job>, completed_detail: bool) {
+    display_changed_jobs(jobs, completed_detail, &HashMap::new(), false)
+}
+
+/// Display jobs to the user in the CLI, highlighting what changed since the
+/// previous versions of the packages (by URL). With `annotate`, the changes
+/// are also marked in the text (see `display_job`).
+pub fn display_changed_jobs<J: Borrow<Job>>(
+    jobs: Vec<J>,
+    completed_detail: bool,
+    previous: &HashMap<String, Package>,
+    annotate: bool,
+) {
+    display_groups(jobs, completed_detail, |job| {
+        display_job(job, completed_detail, previous.get(&job.url.url), annotate)
+    })
+}
+
+/// Display only the given fields of each job, grouped like the full report
+pub fn display_jobs_fields(jobs: Vec<Job>, fields: &[Field]) {
+    display_groups(jobs, true, |job| display_fields(job, fields))
+}
+
+/// Print the completed, in progress and failed jobs under their headings.
+/// Completed jobs are separated by lines if they take up more than one.
+fn display_groups<J: Borrow<Job>>(
+    jobs: Vec<J>,
+    completed_detail: bool,
+    display: impl Fn(&Job) -> String,
+) {
+    let show = |job: &J| display(job.borrow());
+    let JobGroups {
+        completed,
+        in_progress,
+        errors,
+    } = group_jobs(jobs);
 
     // display final packages
-    if completed.len() > 0 {
-        heading(&"completed");
+    if !completed.is_empty() {
+        heading(&tr(Label::Completed).to_lowercase());
         let separator = if completed_detail {
             format!("\n{}\n", line())
         } else {
@@ -110,36 +262,172 @@ pub fn display_jobs(jobs: Vec<Job>, completed_detail: bool) {
         };
         let s = completed
             .iter()
-            .map(|job| display_job(job, completed_detail))
+            .map(show)
             .collect::<Vec<_>>()
             .join(&separator);
         println!("{s}")
     }
 
-    if in_progress.len() > 0 {
-        heading(&"in progress");
+    if !in_progress.is_empty() {
+        heading(&tr(Label::InProgress).to_lowercase());
         let line = format!("\n{}\n", line());
         let s = in_progress
             .iter()
-            .map(|job| display_job(job, completed_detail))
+            .map(show)
             .collect::<Vec<_>>()
             .join(&line);
         println!("{s}")
     }
 
-    if errors.len() > 0 {
+    if !errors.is_empty() {
         // display errors
-        heading(&"errors");
+        heading(&tr(Label::Errors).to_lowercase());
         let line = format!("\n{}\n", line());
         let s = errors
             .iter()
-            .map(|job| display_job(job, completed_detail))
+            .map(show)
             .collect::<Vec<_>>()
             .join(&line);
         println!("{s}");
     }
 }
 
+/// Display how the fresh packages differ from the cache, packages that
+/// differed first
+fn display_verifications(verifications: &[Verification]) {
+    if verifications.is_empty() {
+        return;
+    }
+    let differing = verifications
+        .iter()
+        .filter(|v| !v.discrepancies.is_empty())
+        .count();
+    let mut sorted: Vec<_> = verifications.iter().collect();
+    sorted.sort_by_key(|v| v.discrepancies.is_empty());
+    heading(&"verify");
+    let separator = format!("\n{}\n", line());
+    let s = sorted
+        .iter()
+        .map(|v| display_verification(v))
+        .collect::<Vec<_>>()
+        .join(&separator);
+    println!("{s}");
+    println!(
+        "\n{differing} of {} packages differed from the cache",
+        verifications.len()
+    );
+}
+
+/// How many of the packages changed since the previous cache entries
+fn diff_summary(
+    jobs: &[Job],
+    previous: &HashMap<String, CachedPackage>,
+) -> String {
+    let tracked = jobs
+        .iter()
+        .filter(|job| job.result.is_ok())
+        .count();
+    format!(
+        "{} of {tracked} packages changed since the last run",
+        diff(jobs, previous).len()
+    )
+}
+
+/// Open the cache, and drop the newest entries that can't be used anymore.
+/// Entries for URLs that aren't in the urls file are only dropped by
+/// `packtrack cache gc`, because a shared cache also holds other users' URLs.
+fn open_cache(settings: &Settings, ctx: &Context) -> Result<Box<dyn Cache>> {
+    let mut cache = settings.open_cache()?;
+    let report = gc_newest(cache.as_mut(), &ctx.registry);
+    if !report.is_empty() {
+        log::info!("Removed {} unusable cache entries", report.total());
+    }
+    Ok(cache)
+}
+
+/// Remove the packages delivered more than `auto_archive_days` ago from the
+/// urls file and the cache, returning how many were removed
+fn auto_archive(
+    settings: &Settings,
+    ctx: &Context,
+    jobs: &[Job],
+    urls_file: &Path,
+) -> Result<usize> {
+    let Some(days) = settings.auto_archive_days else {
+        return Ok(0);
+    };
+    let expired = expired_urls(jobs, days, Utc::now());
+    if expired.is_empty() {
+        return Ok(0);
+    }
+    let mut url_store = FileUrlStore::new(urls_file.to_path_buf())?;
+    let mut cache = settings.open_cache()?;
+    let archived =
+        archive(&mut url_store, cache.as_mut(), &ctx.registry, &expired)?;
+    if !archived.is_empty() {
+        url_store.save()?;
+        cache.save()?;
+        remember_archived(&archived, Utc::now())?;
+        log::info!("Archived {} packages", archived.len());
+    }
+    Ok(archived.len())
+}
+
+/// Archive the expired packages after tracking them, except for the demo and
+/// `--as-of` packages, which aren't fresh or aren't the user's
+fn auto_archive_tracked(
+    settings: &Settings,
+    ctx: &Context,
+    track_args: &TrackArgs,
+    jobs: &[Job],
+) -> Result<usize> {
+    match track_args.demo || track_args.as_of.is_some() {
+        true => Ok(0),
+        false => auto_archive(
+            settings,
+            ctx,
+            jobs,
+            track_args
+                .urls_file
+                .as_ref()
+                .unwrap_or(&settings.urls_file),
+        ),
+    }
+}
+
+/// Run `packtrack cache refresh` for the URLs in a separate process, which
+/// keeps going after this one has exited, so the next run has fresh data
+fn refresh_in_background(urls: &[String], urls_file: Option<&PathBuf>) {
+    let spawn = || -> Result<()> {
+        let mut command = std::process::Command::new(std::env::current_exe()?);
+        command.args(["cache", "refresh"]);
+        if let Some(file) = urls_file {
+            command.arg("--urls-file").arg(file);
+        }
+        command
+            .args(urls)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+        Ok(())
+    };
+    match spawn() {
+        Ok(()) => {
+            log::info!("Refreshing {} packages in the background", urls.len())
+        }
+        Err(err) => log::warn!("Couldn't refresh the stale packages: {err}"),
+    }
+}
+
+/// Print the job as a line of JSON, as soon as it's finished
+fn print_line(job: &Job) {
+    match job_to_json_line(job) {
+        Ok(line) => println!("{line}"),
+        Err(err) => log::error!("Couldn't serialize {}: {err}", job.url.url),
+    }
+}
+
 pub async fn track(
     settings: &Settings,
     ctx: &Context,
@@ -153,24 +441,174 @@ pub async fn track(
     // - Pass -f urls_file (use different URLs file)
     //     - allow filtering by query
     // - Pass one or more URLs as a "\n" separated string
-    let urls_file: &PathBuf = track_args
-        .urls_file
-        .as_ref()
-        .unwrap_or(&settings.urls_file);
-    let url_store = FileUrlStore::new(urls_file.clone())?;
-    let mut urls = url_store.filter(ctx.filters.url.as_deref());
+    let mut urls = if track_args.demo {
+        filter(&demo_urls(), ctx.filters.url.as_deref())
+    } else {
+        let urls_file: &PathBuf = track_args
+            .urls_file
+            .as_ref()
+            .unwrap_or(&settings.urls_file);
+        let url_store = FileUrlStore::new(urls_file.clone())?;
+        url_store.filter(ctx.filters.url.as_deref())
+    };
 
     // TODO: make this clearer
-    if urls.len() == 0 && ctx.filters.url.is_some() {
-        urls = vec![AnnotatedUrl::new(
-            ctx.filters.url.clone().unwrap(),
-            Some("dynamic".into()),
-        )]
+    if urls.is_empty()
+        && let Some(query) = &ctx.filters.url
+    {
+        let url = ctx
+            .registry
+            .resolve(query, ctx.filters.carrier.as_deref())?;
+        urls = vec![AnnotatedUrl::new(url, Some("dynamic".into()))]
+    }
+    // Don't track the packages that are filtered out by their tag anyway
+    urls.retain(|u| ctx.filters.matches_url(u));
+    // Track the same package only once, even if it's in the file twice
+    urls = ctx.registry.merge_duplicates(urls);
+    let (pending, urls): (Vec<_>, Vec<_>) = urls
+        .into_iter()
+        .partition(|u| u.is_placeholder());
+    if track_args.qr && urls.len() != 1 {
+        return Err(format!(
+            "--qr needs a single package, but {} URLs match. Pass a URL, or a \
+             part of one that matches only one package.",
+            urls.len()
+        )
+        .into());
+    }
+    let fields = match track_args.fields.is_empty() {
+        true => DEFAULT_FIELDS.to_vec(),
+        false => track_args.fields.clone(),
+    };
+    if !track_args.fields.is_empty()
+        && !matches!(
+            track_args.output,
+            OutputFormat::Text | OutputFormat::Table | OutputFormat::Csv
+        )
+    {
+        return Err("--fields only works with the text, table and csv \
+                    output"
+            .into());
+    }
+    if let OutputFormat::Jsonl = track_args.output
+        && track_args.as_of.is_none()
+    {
+        let mut jobs = vec![];
+        let on_job = |_, job: Job| {
+            print_line(&job);
+            jobs.push(job);
+        };
+        // Don't put demo packages in the user's cache
+        match track_args.demo {
+            true => {
+                track_urls_with(urls, MemoryCache::default(), ctx, on_job)
+                    .await?
+            }
+            false => {
+                let cache = open_cache(settings, ctx)?;
+                track_urls_with(urls, cache, ctx, on_job).await?
+            }
+        }
+        auto_archive_tracked(settings, ctx, &track_args, &jobs)?;
+        log::info!("track_all took {:?}", start.elapsed());
+        return Ok(());
+    }
+    let mut stale = vec![];
+    // Don't put demo packages in the user's cache
+    let jobs = if let Some(time) = track_args.as_of {
+        let cache = settings.open_cache()?;
+        jobs_as_of(&cache, &ctx.registry, urls, time)
+    } else if track_args.demo {
+        track_urls(urls, MemoryCache::default(), ctx).await?
+    } else if track_args.verify {
+        // `--verify` disables the cache in the context, so this is fresh
+        let cache = open_cache(settings, ctx)?;
+        let cached = cached_packages(&cache, &ctx.registry, &urls);
+        let jobs = track_urls(urls, cache, ctx).await?;
+        auto_archive_tracked(settings, ctx, &track_args, &jobs)?;
+        display_verifications(&verify(&jobs, &cached));
+        display_jobs(
+            jobs.into_iter()
+                .filter(|job| job.result.is_err())
+                .collect(),
+            false,
+        );
+        return Ok(());
+    } else if track_args.diff {
+        let cache = open_cache(settings, ctx)?;
+        let previous = cached_packages(&cache, &ctx.registry, &urls);
+        let jobs = track_urls(urls, cache, ctx).await?;
+        auto_archive_tracked(settings, ctx, &track_args, &jobs)?;
+        let qr = match track_args.qr {
+            true => Some(display_qr(&jobs[0].url.url)?),
+            false => None,
+        };
+        let summary = diff_summary(&jobs, &previous);
+        display_pending(pending);
+        display_changed_jobs(
+            jobs,
+            track_args.detail,
+            &previous
+                .into_iter()
+                .map(|(url, cached)| (url, cached.package))
+                .collect(),
+            true,
+        );
+        println!("\n{summary}");
+        if let Some(qr) = qr {
+            println!("{}\n{qr}", line());
+        }
+        log::info!("track_all took {:?}", start.elapsed());
+        return Ok(());
+    } else {
+        let cache = open_cache(settings, ctx)?;
+        if ctx.stale_while_revalidate && ctx.use_cache {
+            stale = stale_urls(&cache, &urls, ctx);
+        }
+        track_urls(urls, cache, ctx).await?
+    };
+    let archived = auto_archive_tracked(settings, ctx, &track_args, &jobs)?;
+    match track_args.output {
+        OutputFormat::Text => {
+            let qr = match track_args.qr {
+                true => Some(display_qr(&jobs[0].url.url)?),
+                false => None,
+            };
+            display_pending(pending);
+            match track_args.fields.is_empty() {
+                true => display_jobs(jobs, track_args.detail),
+                false => display_jobs_fields(jobs, &fields),
+            }
+            if let Some(qr) = qr {
+                println!("{}\n{qr}", line());
+            }
+            if archived > 0 {
+                println!(
+                    "\nArchived {archived} packages delivered more than {} \
+                     days ago",
+                    settings
+                        .auto_archive_days
+                        .unwrap_or_default()
+                );
+            }
+        }
+        OutputFormat::Json => println!("{}", jobs_to_json(&jobs)?),
+        // With --as-of, otherwise the jobs were streamed while tracking
+        OutputFormat::Jsonl => jobs.iter().for_each(print_line),
+        OutputFormat::Yaml => print!("{}", jobs_to_yaml(&jobs)?),
+        OutputFormat::Ical => print!("{}", jobs_to_ical(&jobs, Utc::now())),
+        OutputFormat::Markdown => {
+            print!("{}", markdown_jobs(jobs, pending, track_args.detail))
+        }
+        OutputFormat::Table => {
+            display_pending(pending);
+            print!("{}", table_jobs(jobs, &fields));
+        }
+        OutputFormat::Csv => print!("{}", csv_jobs(jobs, &fields)),
+    }
+    if !stale.is_empty() {
+        refresh_in_background(&stale, track_args.urls_file.as_ref());
     }
-    let cache_file = settings.cache_file.clone();
-    let cache = FileCache::new(cache_file)?;
-    let jobs = track_urls(urls, cache, ctx).await?;
-    display_jobs(jobs, track_args.detail);
     log::info!("track_all took {:?}", start.elapsed());
     Ok(())
 }
@@ -200,23 +638,12 @@ mod tests {
                         end:   "2026-06-18T14:00:00Z".parse()?,
                     }),
                     events:     vec![
-                        Event {
-                            text:      "Package accepted".into(),
-                            timestamp: "2026-06-16T12:00:00Z".parse()?,
-                        },
-                        Event {
-                            text:      "Package sorted at depot".into(),
-                            timestamp: "2026-06-17T12:00:00Z".parse()?,
-                        },
-                        Event {
-                            text:      "Package out for delivery".into(),
-                            timestamp: "2026-06-18T12:00:00Z".parse()?,
-                        },
-                        Event {
-                            text:      "Package delivered to neighbour".into(),
-                            timestamp: "2026-06-18T13:00:00Z".parse()?,
-                        },
+                        Event::new("2026-06-16T12:00:00Z".parse()?, "Package accepted"),
+                        Event::new("2026-06-17T12:00:00Z".parse()?, "Package sorted at depot"),
+                        Event::new("2026-06-18T12:00:00Z".parse()?, "Package out for delivery"),
+                        Event::new("2026-06-18T13:00:00Z".parse()?, "Package delivered to neighbour"),
                     ],
+                    pickup:     None,
                 }),
             },
             Job {
@@ -225,7 +652,12 @@ mod tests {
                     url: "https://jouw.postnl.nl/track-and-trace/POSTNL1-NL-1234AB".into(),
                     description: Some("shoes".into()), 
                     created: None,
-
+                    expected: None,
+                    language: None,
+                    postcode: None,
+                    label: None,
+                    tags: vec![],
+                    notes: vec![],
                 },
                 result: Ok(Package {
                     barcode:    "POSTNL1".into(),
@@ -240,23 +672,12 @@ mod tests {
                         end:   "2026-06-18T14:00:00Z".parse()?,
                     }),
                     events:     vec![
-                        Event {
-                            text:      "Package accepted".into(),
-                            timestamp: "2026-06-16T12:00:00Z".parse()?,
-                        },
-                        Event {
-                            text:      "Package sorted at depot".into(),
-                            timestamp: "2026-06-17T12:00:00Z".parse()?,
-                        },
-                        Event {
-                            text:      "Package out for delivery".into(),
-                            timestamp: "2026-06-18T12:00:00Z".parse()?,
-                        },
-                        Event {
-                            text:      "Package delivered".into(),
-                            timestamp: "2026-06-18T13:00:00Z".parse()?,
-                        },
+                        Event::new("2026-06-16T12:00:00Z".parse()?, "Package accepted"),
+                        Event::new("2026-06-17T12:00:00Z".parse()?, "Package sorted at depot"),
+                        Event::new("2026-06-18T12:00:00Z".parse()?, "Package out for delivery"),
+                        Event::new("2026-06-18T13:00:00Z".parse()?, "Package delivered"),
                     ],
+                    pickup:     None,
                 }),
             },
             Job {
@@ -276,19 +697,11 @@ mod tests {
                         end:   "2026-06-18T14:00:00Z".parse()?,
                     }),
                     events:     vec![
-                        Event {
-                            text:      "Package accepted".into(),
-                            timestamp: "2026-06-16T12:00:00Z".parse()?,
-                        },
-                        Event {
-                            text:      "Package sorted at depot".into(),
-                            timestamp: "2026-06-17T12:00:00Z".parse()?,
-                        },
-                        Event {
-                            text:      "Package out for delivery".into(),
-                            timestamp: "2026-06-18T12:00:00Z".parse()?,
-                        },
+                        Event::new("2026-06-16T12:00:00Z".parse()?, "Package accepted"),
+                        Event::new("2026-06-17T12:00:00Z".parse()?, "Package sorted at depot"),
+                        Event::new("2026-06-18T12:00:00Z".parse()?, "Package out for delivery"),
                     ],
+                    pickup:     None,
                 }),
             },
             Job {
@@ -306,19 +719,11 @@ mod tests {
                         end:   "2026-06-18T14:00:00Z".parse()?,
                     }),
                     events:     vec![
-                        Event {
-                            text:      "Package accepted".into(),
-                            timestamp: "2026-06-16T12:00:00Z".parse()?,
-                        },
-                        Event {
-                            text:      "Package sorted at depot".into(),
-                            timestamp: "2026-06-17T12:00:00Z".parse()?,
-                        },
-                        Event {
-                            text:      "Package out for delivery".into(),
-                            timestamp: "2026-06-18T12:00:00Z".parse()?,
-                        },
+                        Event::new("2026-06-16T12:00:00Z".parse()?, "Package accepted"),
+                        Event::new("2026-06-17T12:00:00Z".parse()?, "Package sorted at depot"),
+                        Event::new("2026-06-18T12:00:00Z".parse()?, "Package out for delivery"),
                     ],
+                    pickup:     None,
                 }),
             },
         ])
@@ -343,10 +748,7 @@ mod tests {
         println!("```");
         println!("❯ packtrack DHL1");
         let mut jobs = get_jobs()?;
-        jobs = jobs
-            .into_iter()
-            .filter(|j| j.result.as_ref().unwrap().barcode == "DHL1")
-            .collect();
+        jobs.retain(|j| j.result.as_ref().unwrap().barcode == "DHL1");
         display_jobs(jobs, false);
         println!("```");
         println!(
@@ -358,10 +760,7 @@ mod tests {
         println!("```");
         println!("❯ packtrack --carrier postnl");
         let mut jobs = get_jobs()?;
-        jobs = jobs
-            .into_iter()
-            .filter(|j| j.result.as_ref().unwrap().channel == "PostNL")
-            .collect();
+        jobs.retain(|j| j.result.as_ref().unwrap().channel == "PostNL");
         display_jobs(jobs, false);
         println!("```");
 
@@ -370,12 +769,9 @@ mod tests {
         println!("```");
         println!("❯ packtrack --sender zalando");
         let mut jobs = get_jobs()?;
-        jobs = jobs
-            .into_iter()
-            .filter(|j| {
-                j.result.as_ref().unwrap().sender == Some("Zalando".into())
-            })
-            .collect();
+        jobs.retain(|j| {
+            j.result.as_ref().unwrap().sender == Some("Zalando".into())
+        });
         display_jobs(jobs, false);
         println!("```");
 
@@ -384,12 +780,9 @@ mod tests {
         println!("```");
         println!("❯ packtrack --recipient zalando");
         let mut jobs = get_jobs()?;
-        jobs = jobs
-            .into_iter()
-            .filter(|j| {
-                j.result.as_ref().unwrap().recipient == Some("Zalando".into())
-            })
-            .collect();
+        jobs.retain(|j| {
+            j.result.as_ref().unwrap().recipient == Some("Zalando".into())
+        });
         display_jobs(jobs, false);
         println!("```");
         Ok(())
@@ -1,20 +1,28 @@
+use chrono::NaiveDate;
 use clap::Args;
 use clap::Subcommand;
 use packtrack::Result;
+use packtrack::barcode;
 use packtrack::settings::Settings;
+use packtrack::tracker::TrackerRegistry;
 use packtrack::url_store::{AnnotatedUrl, FileUrlStore, UrlStore};
-use packtrack::utils::check_path_exists;
+use packtrack::utils::{check_path_exists, parse_date};
 use std::path::PathBuf;
 
 pub async fn handle_url_command(
     command: UrlCommand,
     settings: &Settings,
+    registry: &TrackerRegistry,
 ) -> Result<()> {
     let default_file = &settings.urls_file;
     let file = match &command {
         UrlCommand::Add { args, .. } => args,
         UrlCommand::Remove { args, .. } => args,
         UrlCommand::List { args, .. } => args,
+        UrlCommand::Expect { args, .. } => args,
+        UrlCommand::Attach { args, .. } => args,
+        UrlCommand::Tag { args, .. } => args,
+        UrlCommand::Untag { args, .. } => args,
     }
     .urls_file
     .as_ref()
@@ -23,10 +31,48 @@ pub async fn handle_url_command(
 
     match command {
         UrlCommand::Add {
-            url, description, ..
+            url,
+            barcode,
+            label,
+            description,
+            carrier,
+            language,
+            postcode,
+            ..
         } => {
+            let input = barcode.as_ref().or(url.as_ref()).ok_or(
+                "Pass the tracking URL or number, or --barcode with --carrier",
+            )?;
+            for problem in barcode::check(input) {
+                eprintln!("Warning: {problem}. Is there a typo?");
+            }
+            let url = match barcode {
+                Some(barcode) => {
+                    registry.barcode_url(&barcode, carrier.as_deref())?
+                }
+                None => registry.resolve(input, carrier.as_deref())?,
+            };
+            if let Some((carrier, barcode)) = registry.shipment_key(&url) {
+                let key = Some((carrier.clone(), barcode.clone()));
+                for existing in url_store.filter(None) {
+                    if existing.url != url
+                        && registry.shipment_key(&existing.url) == key
+                    {
+                        eprintln!(
+                            "Warning: {} is the same package ({carrier} \
+                             {barcode})",
+                            existing.url
+                        );
+                    }
+                }
+            }
             let msg = format!("Added {url}");
-            let aurl = AnnotatedUrl::new(url, description);
+            let aurl = AnnotatedUrl {
+                language,
+                postcode,
+                label,
+                ..AnnotatedUrl::new(url, description)
+            };
             url_store.add(aurl)?;
             url_store.save()?;
             println!("{msg}");
@@ -39,6 +85,34 @@ pub async fn handle_url_command(
                 println!("{url}");
             }
         }
+        UrlCommand::Expect { label, date, .. } => {
+            let aurl = AnnotatedUrl::placeholder(label, date);
+            let msg = format!("Added {aurl}");
+            url_store.add(aurl)?;
+            url_store.save()?;
+            println!("{msg}");
+        }
+        UrlCommand::Attach { query, url, .. } => {
+            let attached = url_store.attach(&query, url)?;
+            url_store.save()?;
+            println!("Attached {attached}");
+        }
+        UrlCommand::Tag { query, tags, .. } => {
+            let tagged = url_store.tag(&query, &tags)?;
+            url_store.save()?;
+            println!("Tagged urls:");
+            for url in tagged {
+                println!("{url}");
+            }
+        }
+        UrlCommand::Untag { query, tags, .. } => {
+            let untagged = url_store.untag(&query, &tags)?;
+            url_store.save()?;
+            println!("Untagged urls:");
+            for url in untagged {
+                println!("{url}");
+            }
+        }
         UrlCommand::List { query, .. } => {
             let urls = url_store.filter(query.as_deref());
             for url in urls {
@@ -59,12 +133,71 @@ pub enum UrlCommand {
     },
     /// Add a URL to the urls file
     Add {
-        url:         String,
+        /// Tracking URL, or a bare tracking number
+        #[arg(required_unless_present = "barcode")]
+        url:         Option<String>,
+        /// Tracking number to build the carrier's tracking URL for, e.g. from
+        /// a confirmation email. Use with --carrier if the tracking number
+        /// doesn't tell which carrier it's from.
+        #[arg(short, long, conflicts_with = "url")]
+        barcode:     Option<String>,
+        /// Short name for the package, e.g. "trail shoes", shown in the
+        /// report instead of the tracking number
+        #[arg(long)]
+        label:       Option<String>,
         #[arg(short, long)]
         description: Option<String>,
+        /// Carrier to use if the tracking number is ambiguous, or the one to
+        /// build the tracking URL for with --barcode
+        #[arg(short, long)]
+        carrier:     Option<String>,
+        /// Language to request from the carrier for this package (e.g. "nl"),
+        /// instead of the preferred language
+        #[arg(short, long)]
+        language:    Option<String>,
+        /// Postcode of the recipient, for carriers that need one, if it's
+        /// different from the `postcode` setting
+        #[arg(short, long)]
+        postcode:    Option<String>,
         #[clap(flatten)]
         args:        UrlArgs,
     },
+    /// Add a placeholder for a package that doesn't have a tracking URL yet
+    Expect {
+        /// Short description of the package, e.g. "keyboard from Amazon"
+        label: String,
+        /// When the package is expected, e.g. "2026-10-23" or "friday"
+        #[arg(short, long, value_parser = parse_date)]
+        date:  Option<NaiveDate>,
+        #[clap(flatten)]
+        args:  UrlArgs,
+    },
+    /// Attach a tracking URL to a placeholder
+    Attach {
+        /// Fragment of the placeholder's label
+        query: String,
+        url:   String,
+        #[clap(flatten)]
+        args:  UrlArgs,
+    },
+    /// Tag the URLs matching the query, e.g. with "work" or "gift"
+    Tag {
+        /// Fragment of the URL, label or description
+        query: String,
+        #[arg(required = true)]
+        tags:  Vec<String>,
+        #[clap(flatten)]
+        args:  UrlArgs,
+    },
+    /// Remove tags from the URLs matching the query
+    Untag {
+        /// Fragment of the URL, label or description
+        query: String,
+        #[arg(required = true)]
+        tags:  Vec<String>,
+        #[clap(flatten)]
+        args:  UrlArgs,
+    },
     /// Remove a URL from the urls file
     Remove {
         url:  String,
@@ -0,0 +1,393 @@
+use crate::cli::digest::digest_notification;
+use crate::cli::display::{display_time, line};
+use crate::cli::remind::send_pickup_reminders;
+use crate::cli::track::display_changed_jobs;
+use crate::cli::url::UrlArgs;
+use chrono::{DateTime, Local, NaiveTime, TimeDelta, Utc};
+use clap::Args;
+use packtrack::Result;
+use packtrack::alerts::change_notification;
+use packtrack::api::{Context, Job, track_urls};
+use packtrack::digest::{Digest, DigestOptions};
+use packtrack::notify::{
+    QuietHoursQueue, SentNotifications, get_notifiers,
+    get_sent_notifications_file, notify_all,
+};
+use packtrack::polling::{PollingSettings, next_poll};
+use packtrack::service::{
+    Liveness, run_watchdog, sd_notify, serve_healthz, watchdog_interval,
+};
+use packtrack::settings::Settings;
+use packtrack::url_store::{AnnotatedUrl, FileUrlStore, UrlStore};
+use packtrack::utils::UtcTime;
+use packtrack::verify::cached_packages;
+use std::collections::HashMap;
+use std::io::{IsTerminal, Write};
+use std::net::SocketAddr;
+
+#[derive(Args)]
+pub struct WatchArgs {
+    /// Refresh every package every this many seconds, instead of polling
+    /// each package as often as the `polling` settings say
+    #[arg(short, long)]
+    interval: Option<u64>,
+    /// Run as a service: log the updates instead of drawing the report, and
+    /// tell systemd when it's ready and still alive (see `packtrack service
+    /// install`)
+    #[arg(long)]
+    daemon:   bool,
+    /// Serve a /healthz endpoint on this address, e.g. "127.0.0.1:8787",
+    /// which answers 503 when polling the carriers is stuck
+    #[arg(long, value_name = "ADDR")]
+    healthz:  Option<SocketAddr>,
+    #[clap(flatten)]
+    args:     UrlArgs,
+}
+
+/// The latest job for a URL, and when it was tracked
+struct Polled {
+    job: Job,
+    at:  UtcTime,
+}
+
+/// How long to remember a sent notification, so it isn't sent again
+const REMEMBER_SENT_DAYS: i64 = 30;
+
+/// Keep tracking the packages, and redraw the report whenever one of them is
+/// refreshed. Sends an alert when a package goes out for delivery or is
+/// delivered, unless it was already sent (e.g. before a restart), and
+/// reminds about the packages waiting at a pickup point like `packtrack
+/// remind`.
+pub async fn handle_watch(
+    args: WatchArgs,
+    settings: &Settings,
+    ctx: &Context,
+) -> Result<()> {
+    let file = args
+        .args
+        .urls_file
+        .as_ref()
+        .unwrap_or(&settings.urls_file);
+    let interval = args
+        .interval
+        .map(|seconds| TimeDelta::seconds(seconds as i64));
+    let notifiers = get_notifiers(&settings.notify, &ctx.credentials)?;
+    let mut polled: HashMap<String, Polled> = HashMap::new();
+    let mut quiet = QuietHoursQueue::new(settings.notify.quiet_hours);
+    let liveness = Liveness::default();
+    if let Some(addr) = args.healthz {
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        log::info!("Serving health checks on http://{addr}/healthz");
+        tokio::spawn(serve_healthz(listener, liveness.clone()));
+    }
+    if let Some(interval) = watchdog_interval() {
+        tokio::spawn(run_watchdog(interval, liveness.clone()));
+    }
+    let mut digest_at = settings
+        .notify
+        .digest_at
+        .map(|time| next_digest_at(time, &Local::now()));
+    let screen = (!args.daemon).then(Screen::enter);
+    let mut ready = false;
+    loop {
+        // Read the file every time, so that new URLs are picked up
+        let (placeholders, urls): (Vec<AnnotatedUrl>, Vec<AnnotatedUrl>) = ctx
+            .registry
+            .merge_duplicates(FileUrlStore::new(file.clone())?.filter(None))
+            .into_iter()
+            .partition(|u| u.is_placeholder());
+        polled.retain(|url, _| urls.iter().any(|u| &u.url == url));
+
+        let now = Utc::now();
+        let due = due_urls(&urls, &polled, interval, &settings.polling, now);
+        let cache = settings.open_cache()?;
+        // What the packages looked like before, if this process hasn't
+        // tracked them yet (e.g. after a restart)
+        let cached = cached_packages(cache.as_ref(), &ctx.registry, &due);
+        liveness.poll_started(now);
+        let jobs = track_urls(due, cache, ctx).await?;
+        liveness.poll_finished(Utc::now());
+        let mut previous = HashMap::new();
+        let mut alerts = vec![];
+        for job in jobs {
+            let old = polled
+                .remove(&job.url.url)
+                .and_then(|p| p.job.result.ok())
+                .or_else(|| {
+                    cached
+                        .get(&job.url.url)
+                        .map(|c| c.package.clone())
+                });
+            if let Some(old) = old {
+                if let Ok(package) = &job.result
+                    && let Some(alert) =
+                        change_notification(&job.url, &old, package, now)
+                {
+                    alerts.push(alert);
+                }
+                previous.insert(job.url.url.clone(), old);
+            }
+            polled.insert(job.url.url.clone(), Polled { job, at: now });
+        }
+
+        let sent_file = get_sent_notifications_file()?;
+        let mut sent = SentNotifications::load(&sent_file)?;
+        alerts.retain(|alert| !sent.is_sent(alert));
+        let alerts = quiet.submit(alerts, &Local::now());
+
+        let next = polled
+            .values()
+            .filter_map(|p| next_poll_at(p, interval, &settings.polling))
+            .min()
+            // When all packages are delivered, still look for new URLs
+            .unwrap_or(now + TimeDelta::minutes(settings.polling.max_minutes));
+        // Wake up to send the held notifications when the quiet hours end
+        let next = match quiet.release_at(&Local::now()) {
+            Some(release) => next.min(release.to_utc()),
+            None => next,
+        };
+        let next = match digest_at {
+            Some(at) => next.min(at.to_utc()),
+            None => next,
+        };
+        let status = format!(
+            "Tracking {} packages, next update {}",
+            polled.len(),
+            display_time(next)
+        );
+        match &screen {
+            Some(screen) => {
+                screen.clear();
+                let jobs: Vec<&Job> = urls
+                    .iter()
+                    .filter_map(|u| polled.get(&u.url))
+                    .map(|p| &p.job)
+                    .collect();
+                display_changed_jobs(jobs, false, &previous, false);
+                println!("{}", line());
+                println!(
+                    "Updated {}, next update {}. Press Ctrl-C to stop.",
+                    display_time(now),
+                    display_time(next)
+                );
+            }
+            None => log::info!("{status}"),
+        }
+        let state = match ready {
+            true => format!("STATUS={status}"),
+            false => format!("READY=1\nSTATUS={status}"),
+        };
+        if let Err(err) = sd_notify(&state) {
+            log::warn!("Couldn't notify systemd: {err}");
+        }
+        ready = true;
+        for alert in &alerts {
+            match notify_all(&notifiers, alert).await {
+                Ok(()) => sent.mark_sent(alert, Utc::now()),
+                Err(err) => log::error!("Couldn't send alert: {err}"),
+            }
+        }
+        if !alerts.is_empty() {
+            sent.forget_sent_before(Some(
+                now - TimeDelta::days(REMEMBER_SENT_DAYS),
+            ));
+            sent.save(&sent_file)?;
+        }
+        let jobs = polled.values().map(|p| &p.job);
+        send_pickup_reminders(
+            jobs,
+            settings.pickup_reminder_days,
+            settings,
+            &notifiers,
+        )
+        .await?;
+        if let (Some(at), Some(time)) = (digest_at, settings.notify.digest_at)
+            && at <= Local::now()
+        {
+            let jobs = urls
+                .iter()
+                .filter_map(|u| polled.get(&u.url))
+                .map(|p| &p.job);
+            let digest = Digest::new(
+                jobs,
+                &placeholders,
+                Utc::now(),
+                &DigestOptions::default(),
+            );
+            if !digest.is_empty() {
+                let notification = digest_notification(&digest, Local::now());
+                if let Err(err) = notify_all(&notifiers, &notification).await {
+                    log::error!("Couldn't send the digest: {err}");
+                }
+            }
+            digest_at = Some(next_digest_at(time, &Local::now()));
+        }
+        std::io::stdout().flush()?;
+
+        let wait = (next - Utc::now())
+            .to_std()
+            .unwrap_or_default();
+        tokio::select! {
+            _ = tokio::time::sleep(wait) => {}
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
+
+/// The URLs to track now: new ones, and the ones whose next poll is due
+fn due_urls(
+    urls: &[AnnotatedUrl],
+    polled: &HashMap<String, Polled>,
+    interval: Option<TimeDelta>,
+    settings: &PollingSettings,
+    now: UtcTime,
+) -> Vec<AnnotatedUrl> {
+    urls.iter()
+        .filter(|url| match polled.get(&url.url) {
+            Some(p) => next_poll_at(p, interval, settings)
+                .is_some_and(|next| next <= now),
+            None => true,
+        })
+        .cloned()
+        .collect()
+}
+
+/// After the fixed interval if there is one, otherwise as the polling
+/// settings say. None if the package won't change anymore.
+fn next_poll_at(
+    polled: &Polled,
+    interval: Option<TimeDelta>,
+    settings: &PollingSettings,
+) -> Option<UtcTime> {
+    match interval {
+        Some(interval) => Some(polled.at + interval),
+        None => next_poll(&polled.job, polled.at, settings),
+    }
+}
+
+/// The next time after `now` that the daily digest is due
+fn next_digest_at(time: NaiveTime, now: &DateTime<Local>) -> DateTime<Local> {
+    let today = now.date_naive();
+    let at = match now.time() < time {
+        true => today.and_time(time),
+        false => (today + TimeDelta::days(1)).and_time(time),
+    };
+    at.and_local_timezone(Local)
+        .earliest()
+        // The time falls in a DST gap, so it's an hour later
+        .unwrap_or_else(|| *now + TimeDelta::hours(1))
+}
+
+/// The terminal's alternate screen (like `less` uses), so that the report
+/// can be redrawn in place without filling the scrollback. When the output
+/// isn't a terminal, the reports are printed one after the other.
+struct Screen {
+    alternate: bool,
+}
+impl Screen {
+    fn enter() -> Self {
+        let alternate = std::io::stdout().is_terminal();
+        if alternate {
+            print!("\x1b[?1049h");
+        }
+        Self { alternate }
+    }
+
+    fn clear(&self) {
+        match self.alternate {
+            true => print!("\x1b[2J\x1b[H"),
+            false => println!(),
+        }
+    }
+}
+impl Drop for Screen {
+    fn drop(&mut self) {
+        if self.alternate {
+            print!("\x1b[?1049l");
+            let _ = std::io::stdout().flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use packtrack::tracker::{Package, PackageStatus};
+
+    fn polled(url: &str, status: PackageStatus, at: UtcTime) -> Polled {
+        Polled {
+            job: Job {
+                url:    url.into(),
+                result: Ok(Package {
+                    barcode: "ABC".into(),
+                    channel: "PostNL".into(),
+                    status,
+                    sender: None,
+                    recipient: None,
+                    eta: None,
+                    eta_window: None,
+                    delivered: None,
+                    events: vec![],
+                    pickup: None,
+                }),
+            },
+            at,
+        }
+    }
+
+    #[test]
+    fn test_due_urls() {
+        let now: UtcTime = "2026-10-16T12:00:00Z".parse().unwrap();
+        let settings = PollingSettings::default();
+        let urls: Vec<AnnotatedUrl> = ["new", "delivered", "fresh", "stale"]
+            .map(AnnotatedUrl::from)
+            .into();
+        let polled = HashMap::from([
+            (
+                "delivered".into(),
+                polled("delivered", PackageStatus::Delivered, now),
+            ),
+            (
+                "fresh".into(),
+                polled("fresh", PackageStatus::InTransit, now),
+            ),
+            (
+                "stale".into(),
+                polled(
+                    "stale",
+                    PackageStatus::InTransit,
+                    now - TimeDelta::hours(2),
+                ),
+            ),
+        ]);
+        let due: Vec<String> = due_urls(&urls, &polled, None, &settings, now)
+            .into_iter()
+            .map(|u| u.url)
+            .collect();
+        assert_eq!(due, ["new", "stale"]);
+
+        // With a fixed interval, delivered packages are refreshed too
+        let later = now + TimeDelta::seconds(30);
+        let interval = Some(TimeDelta::seconds(30));
+        let due: Vec<String> =
+            due_urls(&urls, &polled, interval, &settings, later)
+                .into_iter()
+                .map(|u| u.url)
+                .collect();
+        assert_eq!(due, ["new", "delivered", "fresh", "stale"]);
+    }
+
+    #[test]
+    fn test_next_digest_at() {
+        let local = |s: &str| s.parse::<DateTime<Local>>().unwrap();
+        let seven = NaiveTime::from_hms_opt(7, 0, 0).unwrap();
+        assert_eq!(
+            next_digest_at(seven, &local("2026-10-16T06:30:00+02:00")),
+            local("2026-10-16T07:00:00+02:00")
+        );
+        assert_eq!(
+            next_digest_at(seven, &local("2026-10-16T07:00:00+02:00")),
+            local("2026-10-17T07:00:00+02:00")
+        );
+    }
+}
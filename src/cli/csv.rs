@@ -0,0 +1,84 @@
+/// The tracking results as CSV, e.g. to open in a spreadsheet. The rows
+/// are in the same order as the table, and the header has the field names
+/// as passed to `--fields`, so it doesn't change with the language.
+use crate::cli::fields::Field;
+use crate::cli::track::{JobGroups, group_jobs};
+use packtrack::api::Job;
+
+pub fn csv_jobs(jobs: Vec<Job>, fields: &[Field]) -> String {
+    let JobGroups {
+        completed,
+        in_progress,
+        errors,
+    } = group_jobs(jobs);
+    let header = fields
+        .iter()
+        .map(|field| field.name())
+        .collect::<Vec<_>>()
+        .join(",");
+    let mut lines = vec![header];
+    lines.extend(
+        completed
+            .iter()
+            .chain(&in_progress)
+            .chain(&errors)
+            .map(|job| {
+                fields
+                    .iter()
+                    .map(|field| escape(&field.value(job)))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            }),
+    );
+    lines.join("\r\n") + "\r\n"
+}
+
+/// Quote the value if it has commas, quotes or newlines in it (RFC 4180)
+fn escape(value: &str) -> String {
+    match value.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", value.replace('"', "\"\"")),
+        false => value.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use packtrack::tracker::{Package, PackageStatus};
+    use packtrack::url_store::AnnotatedUrl;
+
+    #[test]
+    fn test_csv_jobs() {
+        let jobs = vec![
+            Job {
+                url:    "https://example.com/2".into(),
+                result: Err("HTTP 503".into()),
+            },
+            Job {
+                url:    AnnotatedUrl::new(
+                    "https://example.com/1".into(),
+                    Some("shoes, \"red\"".into()),
+                ),
+                result: Ok(Package {
+                    barcode:    "3SABCD".into(),
+                    channel:    "PostNL".into(),
+                    status:     PackageStatus::InTransit,
+                    sender:     None,
+                    recipient:  None,
+                    eta:        None,
+                    eta_window: None,
+                    delivered:  None,
+                    events:     vec![],
+                    pickup:     None,
+                }),
+            },
+        ];
+        let fields = [Field::Barcode, Field::Description, Field::Status];
+        assert_eq!(
+            csv_jobs(jobs, &fields),
+            "barcode,description,status\r\n\
+             3SABCD,\"shoes, \"\"red\"\"\",In transit\r\n\
+             https://example.com/2,,Error: HTTP 503\r\n"
+        );
+    }
+}
@@ -1,16 +1,31 @@
 /// Functions to display stuff in the CLI
 use std::fmt::Display;
 
+use crate::cli::color::{bold, by_status, dim, highlight, red};
+use crate::cli::i18n::{Label, locale, tr, tr_with};
+use crate::cli::icons::{with_carrier_icon, with_error_icon, with_status_icon};
+
 use byte_unit::{Byte, UnitType};
-use chrono::{DateTime, Datelike, Local, TimeZone};
+use chrono::{DateTime, Datelike, Local, NaiveDate, TimeDelta, TimeZone};
 use packtrack::{
     Result,
     api::Job,
-    tracker::{Event, Package, PackageStatus, TimeWindow},
+    history::Snapshot,
+    tracker::{Event, Package, PackageStatus, PickupPoint, TimeWindow},
+    url_store::AnnotatedUrl,
+    verify::{Discrepancy, Verification},
 };
+use qrcode::QrCode;
+use qrcode::render::unicode::Dense1x2;
 
 use packtrack::settings::Settings;
 
+/// Warn about pickup deadlines that are closer than this
+const PICKUP_WARNING_HOURS: i64 = 48;
+
+/// Events older than this are dimmed, so the recent ones stand out
+const OLD_EVENT_HOURS: i64 = 24;
+
 /// Print a nice heading with the given text like this:
 /// ╭──────────────────────────────────────────────────────────────────────────╮
 /// │                D E L I V E R E D   T O   N E I G H B O U R               │
@@ -19,12 +34,13 @@ pub fn heading(s: &dyn Display) {
     println!("╭{}╮", "─".repeat(78));
     let text = format!(" {s} ");
     let text = spaced(&text).to_uppercase();
-    println!("│{text:^78}│");
+    // Pad before coloring, because the escape codes don't take up space
+    println!("│{}│", bold(&format!("{text:^78}")));
     println!("╰{}╯", "─".repeat(78));
 }
 
 pub fn line() -> String {
-    return "─".repeat(80);
+    "─".repeat(80)
 }
 
 /// "hello" -> "h e l l o"
@@ -32,7 +48,7 @@ pub fn line() -> String {
 pub fn spaced(s: &str) -> String {
     let mut out = String::new();
     for char in s.chars() {
-        if out.len() > 0 && char.is_uppercase() {
+        if !out.is_empty() && char.is_uppercase() {
             out.push(' ');
         }
         out.push(char);
@@ -48,9 +64,11 @@ pub fn display_date<T: TimeZone>(dt: DateTime<T>) -> String {
     let local = dt.with_timezone(&Local);
     let is_today = local.date_naive() == Local::now().date_naive();
     if is_today {
-        "Today".into()
+        tr(Label::Today).into()
     } else {
-        local.format("%a %d %b").to_string()
+        local
+            .format_localized("%a %d %b", locale())
+            .to_string()
     }
 }
 
@@ -75,21 +93,85 @@ pub fn display_timewindow(tw: &TimeWindow) -> String {
     }
 }
 
+/// Display the pickup deadline as "Fri 22 Nov 12:00", with a warning if it is
+/// coming up soon.
+pub fn display_pickup_deadline(pickup: &PickupPoint) -> Option<String> {
+    let deadline = pickup.deadline?;
+    let mut out = display_time(deadline);
+    if pickup.deadline_within(TimeDelta::hours(PICKUP_WARNING_HOURS)) {
+        out += &format!(" ⚠ {}", tr(Label::CollectSoon));
+    }
+    Some(out)
+}
+
 pub fn display_event(event: &Event) -> String {
     format!("[{}] {}", display_time(event.timestamp), event.text)
 }
 
-pub fn display_job(job: &Job, completed_detail: bool) -> String {
+/// The URL as a QR code made of half blocks, two rows per line. The colors
+/// are inverted, so that it scans on a terminal with a dark background.
+pub fn display_qr(url: &str) -> Result<String> {
+    let code = QrCode::new(url.as_bytes())
+        .map_err(|err| format!("Couldn't make a QR code of {url}: {err}"))?;
+    Ok(code
+        .render::<Dense1x2>()
+        .dark_color(Dense1x2::Light)
+        .light_color(Dense1x2::Dark)
+        .build())
+}
+
+/// Display the job. If there is a previous version of the package, the
+/// values that changed since then are highlighted. With `annotate`, new
+/// events are also marked "NEW", and changed values show the old ones, so the
+/// changes stand out without colors too.
+pub fn display_job(
+    job: &Job,
+    completed_detail: bool,
+    previous: Option<&Package>,
+    annotate: bool,
+) -> String {
     match &job.result {
         Ok(package) => match package.status.is_final() {
-            true if !completed_detail => display_job_oneliner(job, package),
-            _ => display_job_full(job, package),
+            true if !completed_detail => {
+                display_job_oneliner(job, package, previous, annotate)
+            }
+            _ => display_job_full(job, package, previous, annotate),
         },
         Err(_) => display_job_error(job),
     }
 }
 
-fn display_job_oneliner(job: &Job, package: &Package) -> String {
+/// Highlight the text if the value changed
+fn mark_changed(text: String, changed: bool) -> String {
+    match changed {
+        true => highlight(&text),
+        false => text,
+    }
+}
+
+/// " (was ...)" with the previous value, if it changed and the changes are
+/// annotated
+fn was(
+    previous: Option<&Package>,
+    package: &Package,
+    annotate: bool,
+    display: impl Fn(&Package) -> Option<String>,
+) -> String {
+    match previous {
+        Some(previous) if annotate && display(previous) != display(package) => {
+            let old = display(previous).unwrap_or("none".into());
+            format!(" (was {old})")
+        }
+        _ => "".into(),
+    }
+}
+
+fn display_job_oneliner(
+    job: &Job,
+    package: &Package,
+    previous: Option<&Package>,
+    annotate: bool,
+) -> String {
     let mut parts: Vec<String> = Vec::new();
     // FIXME: This will become a problem when we introduce a new final status
     // for packages that have reached the international border. They are final
@@ -97,66 +179,296 @@ fn display_job_oneliner(job: &Job, package: &Package) -> String {
     // should probably attempt to use the timestamp from the last update.
     let time = package
         .delivered
-        .map(|dt| display_time(dt))
+        .map(display_time)
         .unwrap_or("????????????????".to_owned());
 
-    parts.push(format!("[{time}] {} {}", package.channel, package.barcode));
+    let status_changed = previous.is_some_and(|p| p.status != package.status);
+    let carrier = with_carrier_icon(&package.channel, &package.channel);
+    parts.push(mark_changed(
+        by_status(
+            &with_status_icon(
+                &package.status,
+                &format!(
+                    "[{time}] {carrier} {}",
+                    job.url
+                        .label
+                        .as_ref()
+                        .unwrap_or(&package.barcode)
+                ),
+            ),
+            &package.status,
+        ),
+        status_changed,
+    ));
     if let Some(sender) = &package.sender {
-        parts.push(format!("from {sender}"));
+        parts.push(tr_with(Label::FromSender, sender));
     }
     if let Some(recipient) = &package.recipient {
-        parts.push(format!("to {recipient}"));
+        parts.push(tr_with(Label::ToRecipient, recipient));
     }
     if let Some(description) = &job.url.description {
         parts.push(format!("({description})"));
     }
     let mut out = parts.join(" ");
-    match &package.status {
-        PackageStatus::DeliveredToNeighbour { .. } => {
-            out += &format!("\n  ╰─ {}", display_status(&package.status));
-        }
-        _ => {}
+    out += &was(previous, package, annotate, |p| {
+        Some(display_status(&p.status))
+    });
+    if let PackageStatus::DeliveredToNeighbour { .. } = &package.status {
+        out += &format!("\n  ╰─ {}", display_status(&package.status));
     }
 
     out
 }
 
-fn display_job_full(job: &Job, package: &Package) -> String {
+fn display_job_full(
+    job: &Job,
+    package: &Package,
+    previous: Option<&Package>,
+    annotate: bool,
+) -> String {
+    let changed = |f: fn(&Package, &Package) -> bool| {
+        previous.is_some_and(|previous| f(previous, package))
+    };
     let mut parts: Vec<String> = Vec::new();
-    parts.push(format!("{} {}", package.channel, package.barcode));
+    parts.push(bold(&with_carrier_icon(
+        &package.channel,
+        &job.url.package_name(package),
+    )));
     if let Some(description) = &job.url.description {
-        parts.push(format!("Description: {}", description));
+        parts.push(format!("{}: {description}", tr(Label::Description)));
+    }
+    for note in &job.url.notes {
+        parts.push(format!("{}: {note}", tr(Label::Note)));
     }
-    parts.push(format!("URL: {}", job.url.url));
-    parts.push(format!("Status: {}", display_status(&package.status)));
+    parts.push(format!("{}: {}", tr(Label::Url), job.url.url));
+    let status =
+        with_status_icon(&package.status, &display_status(&package.status));
+    parts.push(format!(
+        "{}: {}{}",
+        tr(Label::Status),
+        mark_changed(
+            by_status(&status, &package.status),
+            changed(|a, b| a.status != b.status)
+        ),
+        was(previous, package, annotate, |p| Some(display_status(
+            &p.status
+        )))
+    ));
     if let Some(sender) = package.sender.as_ref() {
-        parts.push(format!("From: {sender}"));
+        parts.push(format!("{}: {sender}", tr(Label::From)));
     }
     if let Some(recipient) = package.recipient.as_ref() {
-        parts.push(format!("To: {recipient}"));
+        parts.push(format!("{}: {recipient}", tr(Label::To)));
     }
     if let Some(eta) = package.eta {
-        parts.push(format!("ETA: {}", display_time(eta)));
+        parts.push(format!(
+            "{}: {}{}",
+            tr(Label::Eta),
+            mark_changed(display_time(eta), changed(|a, b| a.eta != b.eta)),
+            was(previous, package, annotate, |p| p.eta.map(display_time))
+        ));
     }
     if let Some(window) = package.eta_window.as_ref() {
-        parts.push(format!("ETA window: {}", display_timewindow(window)));
+        parts.push(format!(
+            "{}: {}{}",
+            tr(Label::EtaWindow),
+            mark_changed(
+                display_timewindow(window),
+                changed(|a, b| a.eta_window != b.eta_window)
+            ),
+            was(previous, package, annotate, |p| p
+                .eta_window
+                .as_ref()
+                .map(display_timewindow))
+        ));
     }
-    parts.push(format!("events:"));
+    if let Some(pickup) = package.pickup.as_ref() {
+        parts.push(format!("{}: {}", tr(Label::PickupPoint), pickup.location));
+        if !package.status.is_final()
+            && let Some(deadline) = display_pickup_deadline(pickup)
+        {
+            parts.push(format!("{}: {deadline}", tr(Label::CollectBefore)));
+        }
+    }
+    parts.push(format!("{}:", tr(Label::Events)));
+    let old = Local::now() - TimeDelta::hours(OLD_EVENT_HOURS);
     for event in package.events.iter() {
-        parts.push(format!("    {}", display_event(event)));
+        let text = display_event(event);
+        let is_new = previous.is_some_and(|p| !p.events.contains(event));
+        let text = match (is_new, event.timestamp < old) {
+            (true, _) => highlight(&text),
+            (false, true) => dim(&text),
+            (false, false) => text,
+        };
+        // "NEW " takes the place of the indent, so the events stay aligned
+        match is_new && annotate {
+            true => parts.push(format!("{} {text}", highlight("NEW"))),
+            false => parts.push(format!("    {text}")),
+        }
     }
 
-    return parts.join("\n");
+    parts.join("\n")
+}
+
+/// Display a placeholder as "[Fri 22 Nov] keyboard from Amazon"
+pub fn display_placeholder(url: &AnnotatedUrl) -> String {
+    let date = url
+        .expected
+        .map(display_expected_date)
+        .unwrap_or("??????????".to_owned());
+    let label = url
+        .description
+        .as_deref()
+        .unwrap_or(&url.url);
+    format!("[{date}] {label}")
+}
+
+/// Like `display_date`, but for dates without a time
+pub fn display_expected_date(date: NaiveDate) -> String {
+    let today = Local::now().date_naive();
+    let formatted = date.format_localized("%a %d %b", locale());
+    if date == today {
+        tr(Label::Today).into()
+    } else if date < today {
+        format!("{formatted} ({})", tr(Label::Overdue))
+    } else {
+        formatted.to_string()
+    }
 }
 
 fn display_job_error(job: &Job) -> String {
     let mut parts: Vec<String> = vec![];
     if let Some(description) = &job.url.description {
-        parts.push(format!("Description: {description}"))
+        parts.push(format!("{}: {description}", tr(Label::Description)))
+    }
+    parts.push(format!("{}: {}", tr(Label::Url), job.url.url));
+    parts.push(red(&with_error_icon(&format!(
+        "{}: {}",
+        tr(Label::Error),
+        job.result.as_ref().err().unwrap()
+    ))));
+    parts.join("\n")
+}
+
+/// The package, how old the cache entry was, and how it differed from the
+/// fresh response
+pub fn display_verification(verification: &Verification) -> String {
+    let job = verification.job;
+    let mut lines = vec![];
+    if let Ok(package) = &job.result {
+        let mut name = job.url.package_name(package);
+        if let Some(description) = &job.url.description {
+            name += &format!(" ({description})");
+        }
+        lines.push(name);
+    }
+    let Some(cached_at) = verification.cached_at else {
+        lines.push("Not in the cache".into());
+        return lines.join("\n");
+    };
+    let age = (Local::now() - cached_at.with_timezone(&Local)).num_minutes();
+    lines.push(format!(
+        "Cache entry from {} ({}h {:02}m old)",
+        display_time(cached_at),
+        age / 60,
+        age % 60
+    ));
+    if verification.discrepancies.is_empty() {
+        lines.push("Matches the cache".into());
+    }
+    lines.extend(display_discrepancies(&verification.discrepancies));
+    lines.join("\n")
+}
+
+fn display_discrepancies(discrepancies: &[Discrepancy]) -> Vec<String> {
+    let mut lines = vec![];
+    let eta = |window: &Option<TimeWindow>| match window {
+        Some(window) => display_timewindow(window),
+        None => "none".into(),
+    };
+    for discrepancy in discrepancies {
+        match discrepancy {
+            Discrepancy::MissedEvents(events) => {
+                lines.push(format!("{} new events:", events.len()));
+                lines.extend(events.iter().map(display_event));
+            }
+            Discrepancy::DroppedEvents(events) => {
+                lines.push(format!(
+                    "{} events the carrier doesn't report anymore:",
+                    events.len()
+                ));
+                lines.extend(events.iter().map(display_event));
+            }
+            Discrepancy::EtaChanged { cached, fresh } => lines.push(format!(
+                "ETA changed from {} to {}",
+                eta(cached),
+                eta(fresh)
+            )),
+            Discrepancy::StatusChanged { cached, fresh } => {
+                lines.push(format!(
+                    "Status changed from {} to {}",
+                    display_status(cached),
+                    display_status(fresh)
+                ))
+            }
+        }
+    }
+    lines
+}
+
+/// The snapshots of a package from the cache: when each was taken, the
+/// status, ETA and number of events then, and what changed since the
+/// previous one. Unless `all`, snapshots without changes are left out.
+pub fn display_history(
+    url: &AnnotatedUrl,
+    snapshots: &[Snapshot],
+    all: bool,
+) -> String {
+    let mut lines = vec![];
+    let mut name = match snapshots.last() {
+        Some(snapshot) => {
+            format!("{} {}", snapshot.package.channel, snapshot.package.barcode)
+        }
+        None => url.url.clone(),
+    };
+    if let Some(description) = &url.description {
+        name += &format!(" ({description})");
+    }
+    lines.push(bold(&name));
+    if snapshots.is_empty() {
+        lines.push("Not in the cache".into());
+    }
+    let mut unchanged = 0;
+    for (i, snapshot) in snapshots.iter().enumerate() {
+        if i > 0 && snapshot.changes.is_empty() && !all {
+            unchanged += 1;
+            continue;
+        }
+        let package = &snapshot.package;
+        let mut summary = vec![display_status(&package.status)];
+        if let Some(window) = &package.eta_window {
+            summary.push(format!("ETA {}", display_timewindow(window)));
+        } else if let Some(eta) = package.eta {
+            summary.push(format!("ETA {}", display_time(eta)));
+        }
+        summary.push(format!("{} events", package.events.len()));
+        lines.push(format!(
+            "{}  {}",
+            dim(&display_time(snapshot.created)),
+            summary.join(", ")
+        ));
+        lines.extend(
+            display_discrepancies(&snapshot.changes)
+                .into_iter()
+                .map(|line| format!("    {line}")),
+        );
     }
-    parts.push(format!("URL: {}", job.url.url.clone()));
-    parts.push(format!("Error: {}", job.result.as_ref().err().unwrap()));
-    return parts.join("\n");
+    if unchanged > 0 {
+        lines.push(dim(&format!(
+            "{unchanged} more snapshots without changes (show them with --all)"
+        )));
+    }
+    lines.join("\n")
 }
 
 pub fn human_readable_bytes(bytes: u64) -> String {
@@ -165,14 +477,15 @@ pub fn human_readable_bytes(bytes: u64) -> String {
     format!("{human_readable:#.1}")
 }
 
-fn display_status(s: &PackageStatus) -> String {
+pub fn display_status(s: &PackageStatus) -> String {
     use PackageStatus::*;
     match s {
-        Delivered => "Delivered".into(),
+        Delivered => tr(Label::Delivered).into(),
         DeliveredToNeighbour { address } => {
-            format!("Delivered to neighbour at {address}")
+            tr_with(Label::DeliveredToNeighbour, address)
         }
-        InTransit => "In transit".into(),
+        ReadyForPickup { location } => tr_with(Label::ReadyForPickup, location),
+        InTransit => tr(Label::InTransit).into(),
     }
 }
 pub fn display_settings(settings: &Settings) -> Result<()> {
@@ -181,6 +494,20 @@ pub fn display_settings(settings: &Settings) -> Result<()> {
         .as_object()
         .ok_or("Couldn't cast settings to dict!")?;
     for (key, value) in dict.iter() {
+        if key == "credentials" {
+            // Don't print secrets, just which ones are set
+            let names: Vec<String> = settings
+                .credentials
+                .iter()
+                .flat_map(|(carrier, values)| {
+                    values
+                        .keys()
+                        .map(move |name| format!("{carrier}.{name}"))
+                })
+                .collect();
+            println!("{key}: {names:?}");
+            continue;
+        }
         println!("{key}: {value}");
     }
     Ok(())
@@ -218,6 +545,197 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_display_pickup_deadline() -> Result<()> {
+        let mut pickup = PickupPoint {
+            location: "Primera".into(),
+            deadline: None,
+        };
+        assert_eq!(display_pickup_deadline(&pickup), None);
+
+        pickup.deadline = Some("2024-11-19T12:00:00Z".parse()?);
+        let display = display_pickup_deadline(&pickup).unwrap();
+        assert!(display.starts_with("Tue 19 Nov 13:00"));
+        assert!(display.contains("collect soon"));
+
+        pickup.deadline = Some(chrono::Utc::now() + TimeDelta::days(5));
+        let display = display_pickup_deadline(&pickup).unwrap();
+        assert!(!display.contains("collect soon"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_display_qr() -> Result<()> {
+        let qr = display_qr("https://jouw.postnl.nl/track-and-trace/3SABCD")?;
+        let lines: Vec<&str> = qr.lines().collect();
+        // Square, with a quiet zone of light (inverted: full) blocks
+        let width = lines[0].chars().count();
+        assert!(
+            lines
+                .iter()
+                .all(|l| l.chars().count() == width)
+        );
+        assert!(lines.len() * 2 >= width);
+        assert!(lines[0].chars().all(|c| c == '█'));
+        Ok(())
+    }
+
+    #[test]
+    fn test_display_placeholder() {
+        let url = AnnotatedUrl::placeholder(
+            "keyboard from Amazon".into(),
+            NaiveDate::from_ymd_opt(2024, 11, 22),
+        );
+        assert_eq!(
+            display_placeholder(&url),
+            "[Fri 22 Nov (overdue)] keyboard from Amazon"
+        );
+        let url = AnnotatedUrl::placeholder("shoes".into(), None);
+        assert_eq!(display_placeholder(&url), "[??????????] shoes");
+    }
+
+    #[test]
+    fn test_display_verification() -> Result<()> {
+        let job = Job {
+            url:    AnnotatedUrl::new("url".into(), Some("shoes".into())),
+            result: Ok(Package {
+                barcode:    "3SABCD".into(),
+                channel:    "PostNL".into(),
+                status:     PackageStatus::InTransit,
+                sender:     None,
+                recipient:  None,
+                eta:        None,
+                eta_window: None,
+                delivered:  None,
+                events:     vec![],
+                pickup:     None,
+            }),
+        };
+        let verification = Verification {
+            job:           &job,
+            cached_at:     Some(chrono::Utc::now() - TimeDelta::minutes(90)),
+            discrepancies: vec![
+                Discrepancy::DroppedEvents(vec![Event::new(
+                    "2024-11-19T12:00:00Z".parse()?,
+                    "Sorted",
+                )]),
+                Discrepancy::StatusChanged {
+                    cached: PackageStatus::InTransit,
+                    fresh:  PackageStatus::Delivered,
+                },
+            ],
+        };
+        let lines: Vec<String> = display_verification(&verification)
+            .lines()
+            .map(String::from)
+            .collect();
+        assert_eq!(lines[0], "PostNL 3SABCD (shoes)");
+        assert!(lines[1].ends_with("(1h 30m old)"));
+        assert_eq!(lines[2], "1 events the carrier doesn't report anymore:");
+        assert_eq!(lines[3], "[Tue 19 Nov 13:00] Sorted");
+        assert_eq!(lines[4], "Status changed from In transit to Delivered");
+        Ok(())
+    }
+
+    #[test]
+    fn test_display_job_annotated() -> Result<()> {
+        let sorted = Event::new("2024-11-19T12:00:00Z".parse()?, "Sorted");
+        let previous = Package {
+            barcode:    "3SABCD".into(),
+            channel:    "PostNL".into(),
+            status:     PackageStatus::InTransit,
+            sender:     None,
+            recipient:  None,
+            eta:        None,
+            eta_window: None,
+            delivered:  None,
+            events:     vec![sorted.clone()],
+            pickup:     None,
+        };
+        let package = Package {
+            eta: Some("2024-11-20T12:00:00Z".parse()?),
+            events: vec![
+                sorted,
+                Event::new("2024-11-20T08:00:00Z".parse()?, "Out for delivery"),
+            ],
+            ..previous.clone()
+        };
+        let job = Job {
+            url:    AnnotatedUrl::new("url".into(), None),
+            result: Ok(package),
+        };
+        let annotated = display_job(&job, false, Some(&previous), true);
+        let lines: Vec<&str> = annotated.lines().collect();
+        assert_eq!(lines[2], "Status: In transit");
+        assert_eq!(lines[3], "ETA: Wed 20 Nov 13:00 (was none)");
+        assert_eq!(lines[5], "    [Tue 19 Nov 13:00] Sorted");
+        assert_eq!(lines[6], "NEW [Wed 20 Nov 09:00] Out for delivery");
+
+        // Without `annotate`, the changes are only highlighted
+        let plain = display_job(&job, false, Some(&previous), false);
+        assert!(!plain.contains("NEW") && !plain.contains("(was"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_display_history() -> Result<()> {
+        let package = Package {
+            barcode:    "3SABCD".into(),
+            channel:    "PostNL".into(),
+            status:     PackageStatus::InTransit,
+            sender:     None,
+            recipient:  None,
+            eta:        None,
+            eta_window: None,
+            delivered:  None,
+            events:     vec![],
+            pickup:     None,
+        };
+        let delivered = Package {
+            status: PackageStatus::Delivered,
+            ..package.clone()
+        };
+        let snapshot = |created: &str, package: &Package, changes| {
+            Ok::<_, packtrack::Error>(Snapshot {
+                created: created.parse()?,
+                package: package.clone(),
+                changes,
+            })
+        };
+        let snapshots = [
+            snapshot("2024-11-19T12:00:00Z", &package, vec![])?,
+            snapshot("2024-11-19T13:00:00Z", &package, vec![])?,
+            snapshot(
+                "2024-11-19T14:00:00Z",
+                &delivered,
+                vec![Discrepancy::StatusChanged {
+                    cached: PackageStatus::InTransit,
+                    fresh:  PackageStatus::Delivered,
+                }],
+            )?,
+        ];
+        let url = AnnotatedUrl::new("url".into(), Some("shoes".into()));
+        let history = display_history(&url, &snapshots, false);
+        let lines: Vec<&str> = history.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "PostNL 3SABCD (shoes)",
+                "Tue 19 Nov 13:00  In transit, 0 events",
+                "Tue 19 Nov 15:00  Delivered, 0 events",
+                "    Status changed from In transit to Delivered",
+                "1 more snapshots without changes (show them with --all)",
+            ]
+        );
+        assert_eq!(
+            display_history(&url, &snapshots, true)
+                .lines()
+                .count(),
+            5
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_spaced() {
         assert_eq!(spaced("hello"), "h e l l o",);
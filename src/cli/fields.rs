@@ -0,0 +1,173 @@
+/// The fields of a package that `--fields` can pick, and their values as
+/// shown in the text report, the table and the CSV
+use crate::cli::display::{
+    display_event, display_status, display_time, display_timewindow,
+};
+use crate::cli::i18n::{Label, tr};
+use clap::ValueEnum;
+use packtrack::api::Job;
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum Field {
+    Carrier,
+    Barcode,
+    Sender,
+    Recipient,
+    Description,
+    /// The delivery window or ETA, or when it was delivered
+    Eta,
+    Status,
+    /// Where to collect the package
+    Pickup,
+    LastEvent,
+    Url,
+}
+
+/// The columns of the table and the CSV, if `--fields` isn't given
+pub const DEFAULT_FIELDS: [Field; 5] = [
+    Field::Carrier,
+    Field::Barcode,
+    Field::Sender,
+    Field::Eta,
+    Field::Status,
+];
+
+impl Field {
+    pub fn label(self) -> Label {
+        match self {
+            Self::Carrier => Label::Carrier,
+            Self::Barcode => Label::Barcode,
+            Self::Sender => Label::Sender,
+            Self::Recipient => Label::Recipient,
+            Self::Description => Label::Description,
+            Self::Eta => Label::Eta,
+            Self::Status => Label::Status,
+            Self::Pickup => Label::PickupPoint,
+            Self::LastEvent => Label::LastEvent,
+            Self::Url => Label::Url,
+        }
+    }
+
+    /// The name to pass to `--fields`, e.g. "last-event"
+    pub fn name(self) -> String {
+        self.to_possible_value()
+            .map(|value| value.get_name().to_owned())
+            .unwrap_or_default()
+    }
+
+    /// The value for the job, or an empty string if the package doesn't have
+    /// one. For errors, the barcode is the description or URL, and the
+    /// status is the error.
+    pub fn value(self, job: &Job) -> String {
+        let package = match &job.result {
+            Ok(package) => package,
+            Err(err) => {
+                return match self {
+                    Self::Barcode => job
+                        .url
+                        .description
+                        .clone()
+                        .unwrap_or(job.url.url.clone()),
+                    Self::Description => job
+                        .url
+                        .description
+                        .clone()
+                        .unwrap_or_default(),
+                    Self::Status => format!("{}: {err}", tr(Label::Error)),
+                    Self::Url => job.url.url.clone(),
+                    _ => String::new(),
+                };
+            }
+        };
+        let value = match self {
+            Self::Carrier => Some(package.channel.clone()),
+            Self::Barcode => Some(package.barcode.clone()),
+            Self::Sender => package.sender.clone(),
+            Self::Recipient => package.recipient.clone(),
+            Self::Description => job.url.description.clone(),
+            Self::Eta => match package.status.is_final() {
+                true => package.delivered.map(display_time),
+                false => package
+                    .eta_window
+                    .as_ref()
+                    .map(display_timewindow)
+                    .or(package.eta.map(display_time)),
+            },
+            Self::Status => Some(display_status(&package.status)),
+            Self::Pickup => package
+                .pickup
+                .as_ref()
+                .map(|pickup| pickup.location.clone()),
+            Self::LastEvent => package
+                .events
+                .iter()
+                .max_by_key(|event| event.timestamp)
+                .map(display_event),
+            Self::Url => Some(job.url.url.clone()),
+        };
+        value.unwrap_or_default()
+    }
+}
+
+/// The job as "Label: value" lines, leaving out the empty values
+pub fn display_fields(job: &Job, fields: &[Field]) -> String {
+    fields
+        .iter()
+        .map(|field| (field.label(), field.value(job)))
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(label, value)| format!("{}: {value}", tr(label)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use packtrack::Result;
+    use packtrack::tracker::{Event, Package, PackageStatus, PickupPoint};
+
+    #[test]
+    fn test_display_fields() -> Result<()> {
+        let job = Job {
+            url:    "demo:dhl".into(),
+            result: Ok(Package {
+                barcode:    "JVGL01".into(),
+                channel:    "DHL".into(),
+                status:     PackageStatus::InTransit,
+                sender:     Some("Bol.com".into()),
+                recipient:  None,
+                eta:        None,
+                eta_window: None,
+                delivered:  None,
+                events:     vec![
+                    Event::new("2024-11-20T12:00:00Z".parse()?, "Sorted"),
+                    Event::new("2024-11-19T12:00:00Z".parse()?, "Registered"),
+                ],
+                pickup:     Some(PickupPoint {
+                    location: "Primera".into(),
+                    deadline: None,
+                }),
+            }),
+        };
+        let fields = [
+            Field::Barcode,
+            Field::Recipient,
+            Field::Pickup,
+            Field::LastEvent,
+        ];
+        assert_eq!(
+            display_fields(&job, &fields),
+            "Barcode: JVGL01\nPickup point: Primera\n\
+             Last event: [Wed 20 Nov 13:00] Sorted"
+        );
+
+        let error = Job {
+            url:    "demo:dhl".into(),
+            result: Err("HTTP 503".into()),
+        };
+        assert_eq!(display_fields(&error, &fields), "Barcode: demo:dhl");
+        assert_eq!(Field::Status.value(&error), "Error: HTTP 503");
+        assert_eq!(Field::LastEvent.name(), "last-event");
+        Ok(())
+    }
+}
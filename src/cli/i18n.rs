@@ -0,0 +1,267 @@
+/// Translations of the labels in the CLI report. The language is chosen
+/// once at startup with `init`, like the colors, so the display functions
+/// don't need to pass it around. The carriers' own texts (e.g. events) are
+/// translated by the carriers.
+use chrono::Locale;
+use std::sync::OnceLock;
+
+static LANGUAGE: OnceLock<Language> = OnceLock::new();
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Language {
+    #[default]
+    En,
+    Nl,
+    De,
+}
+
+impl Language {
+    /// e.g. "nl", "nl-BE" or "de_AT". Languages without a bundle fall back to
+    /// English.
+    pub fn from_code(code: &str) -> Self {
+        let primary = code
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default();
+        match primary.to_lowercase().as_str() {
+            "nl" => Self::Nl,
+            "de" => Self::De,
+            _ => Self::En,
+        }
+    }
+
+    /// For the names of weekdays and months
+    fn locale(self) -> Locale {
+        match self {
+            Self::En => Locale::en_GB,
+            Self::Nl => Locale::nl_NL,
+            Self::De => Locale::de_DE,
+        }
+    }
+}
+
+/// Use this language for the rest of the program
+pub fn init(language: &str) {
+    LANGUAGE.get_or_init(|| Language::from_code(language));
+}
+
+fn language() -> Language {
+    LANGUAGE
+        .get()
+        .copied()
+        .unwrap_or_default()
+}
+
+pub fn locale() -> Locale {
+    language().locale()
+}
+
+#[derive(Clone, Copy)]
+pub enum Label {
+    // headings
+    PendingTracking,
+    Completed,
+    InProgress,
+    Errors,
+    // package details
+    Carrier,
+    Barcode,
+    Sender,
+    Recipient,
+    Description,
+    Note,
+    Url,
+    Status,
+    From,
+    To,
+    Eta,
+    EtaWindow,
+    PickupPoint,
+    CollectBefore,
+    CollectSoon,
+    Events,
+    LastEvent,
+    Error,
+    // in the one-line summary of completed packages
+    FromSender,
+    ToRecipient,
+    // dates
+    Today,
+    Overdue,
+    // statuses
+    Delivered,
+    DeliveredToNeighbour,
+    ReadyForPickup,
+    InTransit,
+}
+
+/// The label in the chosen language
+pub fn tr(label: Label) -> &'static str {
+    text(language(), label)
+}
+
+/// The label in the chosen language, with `{}` replaced by the value
+pub fn tr_with(label: Label, value: &str) -> String {
+    tr(label).replace("{}", value)
+}
+
+fn text(language: Language, label: Label) -> &'static str {
+    match language {
+        Language::En => en(label),
+        Language::Nl => nl(label),
+        Language::De => de(label),
+    }
+}
+
+fn en(label: Label) -> &'static str {
+    use Label::*;
+    match label {
+        PendingTracking => "Pending tracking",
+        Completed => "Completed",
+        InProgress => "In progress",
+        Errors => "Errors",
+        Carrier => "Carrier",
+        Barcode => "Barcode",
+        Sender => "Sender",
+        Recipient => "Recipient",
+        Description => "Description",
+        Note => "Note",
+        Url => "URL",
+        Status => "Status",
+        From => "From",
+        To => "To",
+        Eta => "ETA",
+        EtaWindow => "ETA window",
+        PickupPoint => "Pickup point",
+        CollectBefore => "Collect before",
+        CollectSoon => "collect soon or it will be returned to the sender!",
+        Events => "events",
+        LastEvent => "Last event",
+        Error => "Error",
+        FromSender => "from {}",
+        ToRecipient => "to {}",
+        Today => "Today",
+        Overdue => "overdue",
+        Delivered => "Delivered",
+        DeliveredToNeighbour => "Delivered to neighbour at {}",
+        ReadyForPickup => "Ready for pickup at {}",
+        InTransit => "In transit",
+    }
+}
+
+fn nl(label: Label) -> &'static str {
+    use Label::*;
+    match label {
+        PendingTracking => "Nog zonder track & trace",
+        Completed => "Afgerond",
+        InProgress => "Onderweg",
+        Errors => "Fouten",
+        Carrier => "Vervoerder",
+        Barcode => "Barcode",
+        Sender => "Afzender",
+        Recipient => "Ontvanger",
+        Description => "Omschrijving",
+        Note => "Notitie",
+        Url => "URL",
+        Status => "Status",
+        From => "Van",
+        To => "Aan",
+        Eta => "Verwacht",
+        EtaWindow => "Bezorgvenster",
+        PickupPoint => "Afhaalpunt",
+        CollectBefore => "Ophalen vóór",
+        CollectSoon => {
+            "haal het snel op, anders gaat het terug naar de afzender!"
+        }
+        Events => "gebeurtenissen",
+        LastEvent => "Laatste gebeurtenis",
+        Error => "Fout",
+        FromSender => "van {}",
+        ToRecipient => "aan {}",
+        Today => "Vandaag",
+        Overdue => "te laat",
+        Delivered => "Bezorgd",
+        DeliveredToNeighbour => "Bezorgd bij de buren op {}",
+        ReadyForPickup => "Klaar om op te halen bij {}",
+        InTransit => "Onderweg",
+    }
+}
+
+fn de(label: Label) -> &'static str {
+    use Label::*;
+    match label {
+        PendingTracking => "Noch ohne Sendungsverfolgung",
+        Completed => "Abgeschlossen",
+        InProgress => "Unterwegs",
+        Errors => "Fehler",
+        Carrier => "Paketdienst",
+        Barcode => "Sendungsnummer",
+        Sender => "Absender",
+        Recipient => "Empfänger",
+        Description => "Beschreibung",
+        Note => "Notiz",
+        Url => "URL",
+        Status => "Status",
+        From => "Von",
+        To => "An",
+        Eta => "Voraussichtlich",
+        EtaWindow => "Zustellfenster",
+        PickupPoint => "Abholstelle",
+        CollectBefore => "Abholen bis",
+        CollectSoon => "bald abholen, sonst geht es zurück an den Absender!",
+        Events => "Ereignisse",
+        LastEvent => "Letztes Ereignis",
+        Error => "Fehler",
+        FromSender => "von {}",
+        ToRecipient => "an {}",
+        Today => "Heute",
+        Overdue => "überfällig",
+        Delivered => "Zugestellt",
+        DeliveredToNeighbour => "Beim Nachbarn zugestellt: {}",
+        ReadyForPickup => "Abholbereit bei {}",
+        InTransit => "Unterwegs",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_code() {
+        assert_eq!(Language::from_code("nl"), Language::Nl);
+        assert_eq!(Language::from_code("nl-BE"), Language::Nl);
+        assert_eq!(Language::from_code("de_AT"), Language::De);
+        assert_eq!(Language::from_code("DE"), Language::De);
+        assert_eq!(Language::from_code("en"), Language::En);
+        assert_eq!(Language::from_code("fr"), Language::En);
+        assert_eq!(Language::from_code(""), Language::En);
+    }
+
+    #[test]
+    fn test_text() {
+        assert_eq!(text(Language::En, Label::Completed), "Completed");
+        assert_eq!(text(Language::Nl, Label::Completed), "Afgerond");
+        assert_eq!(text(Language::De, Label::EtaWindow), "Zustellfenster");
+    }
+
+    #[test]
+    fn test_placeholders() {
+        // Every translation of a label with a value needs to show the value
+        for label in [
+            Label::FromSender,
+            Label::ToRecipient,
+            Label::DeliveredToNeighbour,
+            Label::ReadyForPickup,
+        ] {
+            for language in [Language::En, Language::Nl, Language::De] {
+                assert_eq!(
+                    text(language, label)
+                        .matches("{}")
+                        .count(),
+                    1
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,145 @@
+/// Icons for the statuses and carriers in the report, from the `theme`
+/// setting, with the user's own icons from the `icons` setting taking
+/// precedence. Chosen once at startup with `init`, like the colors.
+use packtrack::settings::Theme;
+use packtrack::tracker::PackageStatus;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+static ICONS: OnceLock<Icons> = OnceLock::new();
+
+#[derive(Default)]
+struct Icons {
+    theme:     Theme,
+    overrides: BTreeMap<String, String>,
+}
+
+impl Icons {
+    /// The user's icon for the name, else the theme's
+    fn get(&self, name: &str, themed: impl Fn(Theme) -> &'static str) -> &str {
+        self.overrides
+            .iter()
+            .find(|(key, _)| normalize(key) == normalize(name))
+            .map(|(_, icon)| icon.as_str())
+            .unwrap_or(themed(self.theme))
+    }
+}
+
+/// Use these icons for the rest of the program
+pub fn init(theme: Theme, overrides: &BTreeMap<String, String>) {
+    ICONS.get_or_init(|| Icons {
+        theme,
+        overrides: overrides.clone(),
+    });
+}
+
+fn icons() -> &'static Icons {
+    ICONS.get_or_init(Icons::default)
+}
+
+/// "Royal Mail" -> "royalmail", "ready_for_pickup" -> "readyforpickup"
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// e.g. "delivered_to_neighbour"
+fn status_name(status: &PackageStatus) -> &'static str {
+    match status {
+        PackageStatus::Delivered => "delivered",
+        PackageStatus::DeliveredToNeighbour { .. } => "delivered_to_neighbour",
+        PackageStatus::ReadyForPickup { .. } => "ready_for_pickup",
+        PackageStatus::InTransit => "in_transit",
+    }
+}
+
+fn themed_status(status: &PackageStatus, theme: Theme) -> &'static str {
+    use PackageStatus::*;
+    match (theme, status) {
+        (Theme::Plain, _) => "",
+        (Theme::Emoji, Delivered) => "📬",
+        (Theme::Emoji, DeliveredToNeighbour { .. }) => "🏠",
+        (Theme::Emoji, ReadyForPickup { .. }) => "🏪",
+        (Theme::Emoji, InTransit) => "🚚",
+        (Theme::NerdFont, Delivered) => "\u{f00c}", // nf-fa-check
+        (Theme::NerdFont, DeliveredToNeighbour { .. }) => "\u{f015}", /* nf-fa-home */
+        (Theme::NerdFont, ReadyForPickup { .. }) => "\u{f041}", /* nf-fa-map_marker */
+        (Theme::NerdFont, InTransit) => "\u{f0d1}",             // nf-fa-truck
+    }
+}
+
+fn themed_error(theme: Theme) -> &'static str {
+    match theme {
+        Theme::Plain => "",
+        Theme::Emoji => "❌",
+        Theme::NerdFont => "\u{f071}", // nf-fa-warning
+    }
+}
+
+/// The themes don't have logos for the carriers, so carriers only get an
+/// icon if the user chose one
+fn themed_carrier(_theme: Theme) -> &'static str {
+    ""
+}
+
+/// Put the icon in front of the text, if there is one
+fn prefix(icon: &str, text: &str) -> String {
+    match icon.is_empty() {
+        true => text.to_owned(),
+        false => format!("{icon} {text}"),
+    }
+}
+
+/// e.g. "🚚 In transit"
+pub fn with_status_icon(status: &PackageStatus, text: &str) -> String {
+    let icon =
+        icons().get(status_name(status), |theme| themed_status(status, theme));
+    prefix(icon, text)
+}
+
+/// e.g. "🟧 PostNL"
+pub fn with_carrier_icon(carrier: &str, text: &str) -> String {
+    prefix(icons().get(carrier, themed_carrier), text)
+}
+
+/// e.g. "❌ Error: HTTP 503"
+pub fn with_error_icon(text: &str) -> String {
+    prefix(icons().get("error", themed_error), text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get() {
+        let icons = Icons {
+            theme:     Theme::Emoji,
+            overrides: BTreeMap::from([
+                ("delivered".into(), "✔".into()),
+                ("royalmail".into(), "👑".into()),
+            ]),
+        };
+        let status = |theme| themed_status(&PackageStatus::Delivered, theme);
+        assert_eq!(icons.get("delivered", status), "✔");
+        assert_eq!(icons.get("Royal Mail", themed_carrier), "👑");
+        assert_eq!(icons.get("PostNL", themed_carrier), "");
+        assert_eq!(icons.get("error", themed_error), "❌");
+    }
+
+    #[test]
+    fn test_themes() {
+        let status = PackageStatus::InTransit;
+        assert_eq!(themed_status(&status, Theme::Plain), "");
+        assert_eq!(themed_status(&status, Theme::Emoji), "🚚");
+        assert_eq!(themed_status(&status, Theme::NerdFont), "\u{f0d1}");
+    }
+
+    #[test]
+    fn test_prefix() {
+        assert_eq!(prefix("", "In transit"), "In transit");
+        assert_eq!(prefix("🚚", "In transit"), "🚚 In transit");
+    }
+}
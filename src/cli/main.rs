@@ -1,14 +1,44 @@
 use crate::cli::cache::{CacheCommand, handle_cache_command};
+use crate::cli::carriers::{CarriersCommand, handle_carriers_command};
+use crate::cli::color::{self, ColorChoice};
 use crate::cli::config::{ConfigCommand, handle_config_command};
+use crate::cli::dev::{DevCommand, handle_dev_command};
+use crate::cli::digest::{DigestArgs, handle_digest};
+use crate::cli::export::{ExportCommand, handle_export_command};
+use crate::cli::history::{HistoryArgs, handle_history};
+use crate::cli::i18n;
+use crate::cli::icons;
+use crate::cli::import::{ImportCommand, handle_import_command};
+use crate::cli::ingest::{IngestCommand, handle_ingest_command};
+use crate::cli::note::{NoteArgs, handle_note};
+use crate::cli::prompt::handle_prompt;
+use crate::cli::purge::{PurgeArgs, handle_purge};
+use crate::cli::remind::{RemindArgs, handle_remind};
+use crate::cli::schema::handle_schema;
+use crate::cli::serve::{ServeArgs, handle_serve};
+use crate::cli::service::{ServiceCommand, handle_service_command};
+use crate::cli::stats::{StatsCommand, handle_stats_command};
+use crate::cli::statusbar::handle_statusbar;
+use crate::cli::sync::handle_sync;
 use crate::cli::track::{TrackArgs, track};
-use crate::cli::url::{UrlCommand, handle_url_command};
+use crate::cli::tui::handle_tui;
+use crate::cli::url::{UrlArgs, UrlCommand, handle_url_command};
+use crate::cli::watch::{WatchArgs, handle_watch};
 use clap::Args;
 use clap::{Parser, Subcommand};
 use log::{self, LevelFilter};
 use packtrack::Result;
 use packtrack::api::Context;
 use packtrack::api::Filters;
-use packtrack::settings::{FileSettingsManager, get_settings_file};
+use packtrack::settings::{
+    FileSettingsManager, SettingsManager, get_settings_file,
+};
+use packtrack::tracker::{
+    Credentials, MockTracker, SeventeenTrackTracker, TrackerRegistry,
+    get_scrapers_file, load_scrapers,
+};
+use packtrack::url_store::file_url_store::{legacy_urls_file, migrate_to_json};
+use packtrack::utils::get_home_dir;
 
 pub async fn main() -> Result<()> {
     let args = Cli::parse();
@@ -26,23 +56,54 @@ pub async fn main() -> Result<()> {
         .filter(None, verbosity)
         .init();
     log::debug!("Verbosity {verbosity}");
+    color::init(args.globals.color);
 
     let settings_file = get_settings_file()?;
     let mut settings_manager = FileSettingsManager::new(settings_file)?;
+    if let Err(err) = migrate_urls_file(&mut settings_manager) {
+        eprintln!("Warning: {err}");
+    }
     let settings = &settings_manager.settings;
+
+    let mut registry = TrackerRegistry::default();
+    #[cfg(feature = "wasm-plugins")]
+    register_plugins(&mut registry)?;
+    for scraper in load_scrapers(&get_scrapers_file()?)? {
+        registry.add(Box::new(scraper));
+    }
+    // 17TRACK can't do anything without an API key, and would otherwise claim
+    // every tracking number the other trackers don't recognise
+    if Credentials::new(settings.credentials.clone())
+        .get("17track", "api_key")
+        .is_some()
+    {
+        registry.add(Box::new(SeventeenTrackTracker));
+    }
+    let registry = match args.tracking.demo {
+        true => TrackerRegistry::empty().with(MockTracker),
+        false => registry.configure(&settings.trackers),
+    };
+    log::debug!("Trackers: {}", registry.names().join(", "));
+
     let ctx = Context {
-        cache_seconds:      args
+        cache_seconds: args
             .tracking
             .cache_seconds
-            .unwrap_or(settings.cache_seconds.clone()),
-        use_cache:          !args.tracking.no_cache,
-        filters:            Filters {
+            .unwrap_or(settings.cache_seconds),
+        use_cache: !args.tracking.no_cache && !args.tracking.verify,
+        cache_max_bytes: settings.cache_max_bytes,
+        // Only the report refreshes the stale packages afterwards
+        stale_while_revalidate: settings.stale_while_revalidate
+            && args.subcommand.is_none(),
+        filters: Filters {
             url:       args.tracking.url.clone(),
             sender:    args.tracking.sender.clone(),
             recipient: args.tracking.recipient.clone(),
             carrier:   args.tracking.carrier.clone(),
+            tag:       args.tracking.tag.clone(),
         },
-        default_postcode:   args
+        registry,
+        default_postcode: args
             .tracking
             .postcode
             .clone()
@@ -53,25 +114,111 @@ pub async fn main() -> Result<()> {
             .clone()
             .or(settings.language.clone())
             .unwrap_or(Context::default().preferred_language),
+        browser_path: settings.browser_path.clone(),
+        api_origin: None,
+        credentials: Credentials::new(settings.credentials.clone()),
+        languages: settings.languages.clone(),
     };
     log::debug!("Cache seconds: {}", ctx.cache_seconds);
+    i18n::init(&ctx.preferred_language);
+    icons::init(settings.theme, &settings.icons);
 
     // Handle subcommands
     match args.subcommand {
-        None => track(&settings, &ctx, args.tracking).await?,
+        None => track(settings, &ctx, args.tracking).await?,
         Some(Command::Url { command }) => {
-            handle_url_command(command, &settings).await?
+            handle_url_command(command, settings, &ctx.registry).await?
         }
         Some(Command::Config { command }) => {
             handle_config_command(command, &mut settings_manager)?
         }
         Some(Command::Cache { command }) => {
-            handle_cache_command(command, &settings).await?
+            handle_cache_command(command, settings, &ctx).await?
+        }
+        Some(Command::Ingest { command }) => {
+            handle_ingest_command(command, settings, &ctx).await?
+        }
+        Some(Command::Import { command }) => {
+            handle_import_command(command, settings, &ctx).await?
+        }
+        Some(Command::Carriers { command }) => {
+            handle_carriers_command(command, &ctx).await?
+        }
+        Some(Command::Digest(args)) => {
+            handle_digest(args, settings, &ctx).await?
+        }
+        Some(Command::Prompt(args)) => handle_prompt(args, settings, &ctx),
+        Some(Command::History(args)) => handle_history(args, settings, &ctx)?,
+        Some(Command::Statusbar(args)) => {
+            handle_statusbar(args, settings, &ctx)
+        }
+        Some(Command::Note(args)) => handle_note(args, settings)?,
+        Some(Command::Remind(args)) => {
+            handle_remind(args, settings, &ctx).await?
+        }
+        Some(Command::Stats { command }) => {
+            handle_stats_command(command, settings, &ctx)?
+        }
+        Some(Command::Schema) => handle_schema()?,
+        Some(Command::Service { command }) => handle_service_command(command)?,
+        Some(Command::Serve(args)) => handle_serve(args, settings, ctx).await?,
+        Some(Command::Purge(args)) => handle_purge(args, settings)?,
+        Some(Command::Export { command }) => {
+            handle_export_command(command, settings, &ctx)?
+        }
+        Some(Command::Sync(args)) => handle_sync(args, settings, &ctx).await?,
+        Some(Command::Tui(args)) => handle_tui(args, settings, &ctx).await?,
+        Some(Command::Watch(args)) => {
+            handle_watch(args, settings, &ctx).await?
+        }
+        Some(Command::Dev { command }) => {
+            handle_dev_command(command, &ctx).await?
         }
     }
     Ok(())
 }
 
+/// The urls file used to have one URL per line, which can't hold everything
+/// about a URL. Convert it (and the server users' copies) to JSON, and point
+/// the settings at the new file. Users who never set `urls_file` have the old
+/// default, "packtrack.urls", while the settings now default to the JSON file.
+fn migrate_urls_file(settings_manager: &mut FileSettingsManager) -> Result<()> {
+    let Some(old) = legacy_urls_file(
+        &settings_manager.settings.urls_file,
+        &get_home_dir()?,
+    ) else {
+        return Ok(());
+    };
+    let new = migrate_to_json(&old)?;
+    #[cfg(feature = "server")]
+    for name in &settings_manager.settings.server.users {
+        let user_urls = packtrack::server::user_file(&old, name);
+        if user_urls.try_exists()? {
+            migrate_to_json(&user_urls)?;
+        }
+    }
+    settings_manager.settings.urls_file = new.clone();
+    settings_manager.save()?;
+    eprintln!(
+        "Converted your urls file to {}. The old one is kept as {}.bak",
+        new.display(),
+        old.display()
+    );
+    Ok(())
+}
+
+/// Make the WASM plugins in the config dir available as trackers
+#[cfg(feature = "wasm-plugins")]
+fn register_plugins(registry: &mut TrackerRegistry) -> Result<()> {
+    use packtrack::tracker::{get_plugins_dir, load_plugins};
+    let dir = get_plugins_dir()?;
+    log::debug!("Loading plugins from {dir:?}");
+    for plugin in load_plugins(&dir)? {
+        registry.add(Box::new(plugin));
+    }
+    Ok(())
+}
+
 #[derive(Parser)]
 // `args_conflicts_with_subcommands` makes non-global args only accessible for
 // the default subcommand. So all the options related to tracking (sender, etc)
@@ -100,6 +247,10 @@ struct GlobalArgs {
         default_value = "error"
     )]
     verbosity: String,
+
+    /// When to use colors
+    #[arg(long, global = true, value_enum, default_value_t)]
+    color: ColorChoice,
 }
 
 #[derive(Subcommand)]
@@ -119,4 +270,68 @@ enum Command {
         #[command(subcommand)]
         command: CacheCommand,
     },
+    /// Find tracking links in other places, e.g. your emails or clipboard
+    Ingest {
+        #[command(subcommand)]
+        command: IngestCommand,
+    },
+    /// Import shipments from other tracking services
+    Import {
+        #[command(subcommand)]
+        command: ImportCommand,
+    },
+    /// Carrier health checks
+    Carriers {
+        #[command(subcommand)]
+        command: CarriersCommand,
+    },
+    /// Summary of what needs attention today, e.g. for a daily cron job
+    Digest(DigestArgs),
+    /// Very short summary from the cache, for a shell prompt, e.g. "📦2↓1"
+    Prompt(UrlArgs),
+    /// Add a note to a package, e.g. "left with the neighbours last time",
+    /// or print its notes
+    Note(NoteArgs),
+    /// Remind about packages that need to be collected from a pickup point
+    /// soon, e.g. from a cron job
+    Remind(RemindArgs),
+    /// Statistics about past deliveries
+    Stats {
+        #[command(subcommand)]
+        command: StatsCommand,
+    },
+    /// Summary of the packages arriving today from the cache, as JSON for a
+    /// waybar module
+    Statusbar(UrlArgs),
+    /// How a package's status, ETA and events changed over time, from the
+    /// responses in the cache
+    History(HistoryArgs),
+    /// Print the JSON Schema of the machine-readable output
+    Schema,
+    /// Delete cached tracking data and notification state, e.g. to remove
+    /// personal data from disk
+    Purge(PurgeArgs),
+    /// Export the tracking history in the cache to other formats
+    Export {
+        #[command(subcommand)]
+        command: ExportCommand,
+    },
+    /// Merge the urls file with the copy shared between your machines
+    Sync(UrlArgs),
+    /// Browse the packages in a terminal UI
+    Tui(UrlArgs),
+    /// Keep tracking, and redraw the report whenever a package is refreshed
+    Watch(WatchArgs),
+    /// Serve the packages over HTTP, for phones, dashboards and other tools
+    Serve(ServeArgs),
+    /// Run packtrack as a systemd service
+    Service {
+        #[command(subcommand)]
+        command: ServiceCommand,
+    },
+    /// Tools for packtrack developers
+    Dev {
+        #[command(subcommand)]
+        command: DevCommand,
+    },
 }
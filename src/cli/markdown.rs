@@ -0,0 +1,293 @@
+/// Display the tracking results as Markdown, to paste into notes, issues
+/// or chat tools
+use crate::cli::display::{
+    display_event, display_pickup_deadline, display_placeholder,
+    display_status, display_time, display_timewindow,
+};
+use crate::cli::i18n::{Label, tr};
+use crate::cli::track::{JobGroups, group_jobs};
+use packtrack::api::Job;
+use packtrack::tracker::Package;
+use packtrack::url_store::AnnotatedUrl;
+
+/// The jobs as Markdown: a table of completed packages, a section per package
+/// in progress, and a list of errors. With `completed_detail`, completed
+/// packages get a section each too.
+pub fn markdown_jobs(
+    jobs: Vec<Job>,
+    mut pending: Vec<AnnotatedUrl>,
+    completed_detail: bool,
+) -> String {
+    let JobGroups {
+        completed,
+        in_progress,
+        errors,
+    } = group_jobs(jobs);
+    let mut sections = vec![];
+    if !pending.is_empty() {
+        pending.sort_by_key(|u| (u.expected.is_none(), u.expected));
+        let items = pending
+            .iter()
+            .map(|u| format!("- {}", escape(&display_placeholder(u))))
+            .collect::<Vec<_>>()
+            .join("\n");
+        sections.push(format!("## {}\n\n{items}", tr(Label::PendingTracking)));
+    }
+    if !completed.is_empty() {
+        let body = match completed_detail {
+            true => package_sections(&completed),
+            false => completed_table(&completed),
+        };
+        sections.push(format!("## {}\n\n{body}", tr(Label::Completed)));
+    }
+    if !in_progress.is_empty() {
+        sections.push(format!(
+            "## {}\n\n{}",
+            tr(Label::InProgress),
+            package_sections(&in_progress)
+        ));
+    }
+    if !errors.is_empty() {
+        let items = errors
+            .iter()
+            .map(|job| {
+                let mut item = format!("- <{}>", job.url.url);
+                if let Some(description) = &job.url.description {
+                    item += &format!(" ({})", escape(description));
+                }
+                if let Err(err) = &job.result {
+                    item += &format!(": {}", escape(&err.to_string()));
+                }
+                item
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        sections.push(format!("## {}\n\n{items}", tr(Label::Errors)));
+    }
+    sections
+        .into_iter()
+        .map(|section| section + "\n")
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// One row per package
+fn completed_table(jobs: &[Job]) -> String {
+    let header = [
+        Label::Delivered,
+        Label::Carrier,
+        Label::Barcode,
+        Label::From,
+        Label::To,
+        Label::Description,
+        Label::Status,
+    ]
+    .map(tr);
+    let mut rows = vec![
+        format!("| {} |", header.join(" | ")),
+        "|---|---|---|---|---|---|---|".into(),
+    ];
+    for job in jobs {
+        let Ok(package) = &job.result else {
+            continue;
+        };
+        let cells = [
+            package
+                .delivered
+                .map(display_time)
+                .unwrap_or_default(),
+            escape(&package.channel),
+            link(&package.barcode, &job.url.url),
+            escape(
+                package
+                    .sender
+                    .as_deref()
+                    .unwrap_or_default(),
+            ),
+            escape(
+                package
+                    .recipient
+                    .as_deref()
+                    .unwrap_or_default(),
+            ),
+            escape(
+                job.url
+                    .description
+                    .as_deref()
+                    .unwrap_or_default(),
+            ),
+            escape(&display_status(&package.status)),
+        ];
+        let cells = cells
+            .iter()
+            .map(|cell| {
+                cell.replace('|', "\\|")
+                    .replace('\n', " ")
+            })
+            .collect::<Vec<_>>();
+        rows.push(format!("| {} |", cells.join(" | ")));
+    }
+    rows.join("\n")
+}
+
+/// A subsection per package, with its details and events
+fn package_sections(jobs: &[Job]) -> String {
+    jobs.iter()
+        .filter_map(|job| {
+            let package = job.result.as_ref().ok()?;
+            Some(package_section(job, package))
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn package_section(job: &Job, package: &Package) -> String {
+    let mut title = format!(
+        "### {} {}",
+        escape(&package.channel),
+        link(&package.barcode, &job.url.url)
+    );
+    if let Some(description) = &job.url.description {
+        title += &format!(" ({})", escape(description));
+    }
+    let mut details = vec![(Label::Status, display_status(&package.status))];
+    if let Some(sender) = &package.sender {
+        details.push((Label::From, sender.clone()));
+    }
+    if let Some(recipient) = &package.recipient {
+        details.push((Label::To, recipient.clone()));
+    }
+    if let Some(eta) = package.eta {
+        details.push((Label::Eta, display_time(eta)));
+    }
+    if let Some(window) = &package.eta_window {
+        details.push((Label::EtaWindow, display_timewindow(window)));
+    }
+    if let Some(pickup) = &package.pickup {
+        details.push((Label::PickupPoint, pickup.location.clone()));
+        if !package.status.is_final()
+            && let Some(deadline) = display_pickup_deadline(pickup)
+        {
+            details.push((Label::CollectBefore, deadline));
+        }
+    }
+    let mut lines = vec![title, String::new()];
+    lines.extend(
+        details
+            .into_iter()
+            .map(|(label, value)| {
+                format!("- **{}:** {}", tr(label), escape(&value))
+            }),
+    );
+    if !package.events.is_empty() {
+        lines.push(String::new());
+        lines.extend(
+            package
+                .events
+                .iter()
+                .map(|event| format!("1. {}", escape(&display_event(event)))),
+        );
+    }
+    lines.join("\n")
+}
+
+/// e.g. "[3SABCD1234567](https://jouw.postnl.nl/...)"
+fn link(text: &str, url: &str) -> String {
+    // Only web URLs make sense as links, e.g. not "demo:postnl"
+    match url.starts_with("http") {
+        true => format!(
+            "[{}]({})",
+            escape(text),
+            url.replace('(', "%28")
+                .replace(')', "%29")
+        ),
+        false => escape(text),
+    }
+}
+
+/// Escape the characters that would otherwise be read as Markdown
+fn escape(text: &str) -> String {
+    let mut out = String::new();
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '`' | '[' | ']' | '<' | '>' | '#') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use packtrack::Result;
+    use packtrack::tracker::{Event, PackageStatus};
+
+    fn package(barcode: &str, status: PackageStatus) -> Result<Package> {
+        Ok(Package {
+            barcode: barcode.into(),
+            channel: "PostNL".into(),
+            status,
+            sender: Some("Zalando".into()),
+            recipient: None,
+            eta: None,
+            eta_window: None,
+            delivered: Some("2026-06-18T12:00:00Z".parse()?),
+            events: vec![Event::new(
+                "2026-06-16T12:00:00Z".parse()?,
+                "Package *accepted*",
+            )],
+            pickup: None,
+        })
+    }
+
+    #[test]
+    fn test_markdown_jobs() -> Result<()> {
+        let jobs = vec![
+            Job {
+                url:    AnnotatedUrl::new(
+                    "https://jouw.postnl.nl/track-and-trace/POSTNL1".into(),
+                    Some("shoes | socks".into()),
+                ),
+                result: Ok(package("POSTNL1", PackageStatus::Delivered)?),
+            },
+            Job {
+                url:    "https://jouw.postnl.nl/track-and-trace/POSTNL2".into(),
+                result: Ok(package("POSTNL2", PackageStatus::InTransit)?),
+            },
+            Job {
+                url:    "https://example.com/1".into(),
+                result: Err("HTTP 503".into()),
+            },
+        ];
+        let markdown = markdown_jobs(jobs, vec![], false);
+        assert_eq!(
+            markdown,
+            "## Completed
+
+| Delivered | Carrier | Barcode | From | To | Description | Status |
+|---|---|---|---|---|---|---|
+| Thu 18 Jun 14:00 | PostNL | [POSTNL1](https://jouw.postnl.nl/track-and-trace/POSTNL1) | Zalando |  | shoes \\| socks | Delivered |
+
+## In progress
+
+### PostNL [POSTNL2](https://jouw.postnl.nl/track-and-trace/POSTNL2)
+
+- **Status:** In transit
+- **From:** Zalando
+
+1. \\[Tue 16 Jun 14:00\\] Package \\*accepted\\*
+
+## Errors
+
+- <https://example.com/1>: HTTP 503
+"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_markdown_jobs_empty() {
+        assert_eq!(markdown_jobs(vec![], vec![], false), "");
+    }
+}
@@ -1,10 +1,36 @@
+mod color;
 mod commands;
+mod csv;
 mod display;
+mod fields;
+mod i18n;
+mod icons;
 mod main;
+mod markdown;
+mod table;
+mod tui;
 
 pub use main::*;
 
 pub use commands::cache;
+pub use commands::carriers;
 pub use commands::config;
+pub use commands::dev;
+pub use commands::digest;
+pub use commands::export;
+pub use commands::history;
+pub use commands::import;
+pub use commands::ingest;
+pub use commands::note;
+pub use commands::prompt;
+pub use commands::purge;
+pub use commands::remind;
+pub use commands::schema;
+pub use commands::serve;
+pub use commands::service;
+pub use commands::stats;
+pub use commands::statusbar;
+pub use commands::sync;
 pub use commands::track;
 pub use commands::url;
+pub use commands::watch;
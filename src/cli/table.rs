@@ -0,0 +1,171 @@
+/// Display the tracking results as one aligned table, to scan many
+/// packages at once
+use crate::cli::fields::Field;
+use crate::cli::i18n::tr;
+use crate::cli::track::{JobGroups, group_jobs};
+use packtrack::api::Job;
+
+/// Longer cells are cut off, so that one long status doesn't push the table
+/// off the screen
+const MAX_CELL_CHARS: usize = 40;
+
+/// A row per job with a column per field: completed packages first, then the
+/// ones in progress, then the errors
+pub fn table_jobs(jobs: Vec<Job>, fields: &[Field]) -> String {
+    let JobGroups {
+        completed,
+        in_progress,
+        errors,
+    } = group_jobs(jobs);
+    let rows: Vec<Vec<String>> = completed
+        .iter()
+        .chain(&in_progress)
+        .chain(&errors)
+        .map(|job| {
+            fields
+                .iter()
+                .map(|field| truncate(&field.value(job)))
+                .collect()
+        })
+        .collect();
+    if rows.is_empty() {
+        return String::new();
+    }
+    let header: Vec<String> = fields
+        .iter()
+        .map(|field| tr(field.label()).to_owned())
+        .collect();
+    let mut widths = vec![0; fields.len()];
+    for row in std::iter::once(&header).chain(&rows) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let format_row = |row: &Vec<String>| {
+        row.iter()
+            .zip(&widths)
+            .map(|(cell, &width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_owned()
+    };
+    let mut lines = vec![format_row(&header)];
+    lines.push(
+        widths
+            .iter()
+            .map(|width| "─".repeat(*width))
+            .collect::<Vec<_>>()
+            .join("  "),
+    );
+    lines.extend(rows.iter().map(format_row));
+    lines.join("\n") + "\n"
+}
+
+/// Cut off the text at `MAX_CELL_CHARS`, on one line
+fn truncate(text: &str) -> String {
+    let text = text.replace('\n', " ");
+    match text.chars().count() > MAX_CELL_CHARS {
+        true => {
+            let cut: String = text
+                .chars()
+                .take(MAX_CELL_CHARS - 1)
+                .collect();
+            format!("{}…", cut.trim_end())
+        }
+        false => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::fields::DEFAULT_FIELDS;
+    use packtrack::Result;
+    use packtrack::tracker::{Package, PackageStatus, TimeWindow};
+
+    fn package(
+        channel: &str,
+        barcode: &str,
+        status: PackageStatus,
+    ) -> Result<Package> {
+        Ok(Package {
+            barcode: barcode.into(),
+            channel: channel.into(),
+            status,
+            sender: Some("Zalando".into()),
+            recipient: None,
+            eta: None,
+            eta_window: Some(TimeWindow {
+                start: "2026-06-18T10:00:00Z".parse()?,
+                end:   "2026-06-18T12:00:00Z".parse()?,
+            }),
+            delivered: Some("2026-06-17T12:00:00Z".parse()?),
+            events: vec![],
+            pickup: None,
+        })
+    }
+
+    #[test]
+    fn test_table_jobs() -> Result<()> {
+        let jobs = vec![
+            Job {
+                url:    "https://example.com/1".into(),
+                result: Ok(package(
+                    "PostNL",
+                    "3SABCD1234567",
+                    PackageStatus::InTransit,
+                )?),
+            },
+            Job {
+                url:    "https://example.com/2".into(),
+                result: Ok(package(
+                    "DHL",
+                    "JVGL01",
+                    PackageStatus::DeliveredToNeighbour {
+                        address: "Hoofdstraat 1, 1234 AB Amsterdam, the \
+                                  Netherlands"
+                            .into(),
+                    },
+                )?),
+            },
+            Job {
+                url:    "https://example.com/3".into(),
+                result: Err("HTTP 503".into()),
+            },
+        ];
+        assert_eq!(
+            table_jobs(jobs, &DEFAULT_FIELDS),
+            "\
+Carrier  Barcode                Sender   ETA                        Status
+───────  ─────────────────────  ───────  ─────────────────────────  ────────────────────────────────────────
+DHL      JVGL01                 Zalando  Wed 17 Jun 14:00           Delivered to neighbour at Hoofdstraat 1…
+PostNL   3SABCD1234567          Zalando  Thu 18 Jun 12:00 -- 14:00  In transit
+         https://example.com/3                                      Error: HTTP 503
+"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_table_jobs_empty() {
+        assert_eq!(table_jobs(vec![], &DEFAULT_FIELDS), "");
+    }
+
+    #[test]
+    fn test_table_jobs_fields() -> Result<()> {
+        let jobs = vec![Job {
+            url:    "https://example.com/1".into(),
+            result: Ok(package("PostNL", "3SABCD", PackageStatus::InTransit)?),
+        }];
+        assert_eq!(
+            table_jobs(jobs, &[Field::Barcode, Field::Url]),
+            "\
+Barcode  URL
+───────  ─────────────────────
+3SABCD   https://example.com/1
+"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,297 @@
+/// The state of the terminal UI, and what the keys do to it. Drawing is in
+/// `ui`, and the side effects (tracking, archiving, opening URLs) are in
+/// the event loop.
+use crate::cli::track::{JobGroups, group_jobs};
+use packtrack::api::Job;
+use ratatui::crossterm::event::KeyCode;
+use ratatui::widgets::ListState;
+
+#[derive(Debug, PartialEq)]
+pub enum Mode {
+    Normal,
+    /// Typing a filter
+    Filter,
+    /// Asked whether to archive the selected URL
+    ConfirmArchive,
+}
+
+/// What the event loop should do after a key press
+#[derive(Debug, PartialEq)]
+pub enum Action {
+    Nothing,
+    Quit,
+    Refresh,
+    /// Remove the URL from the urls file
+    Archive(String),
+    Open(String),
+}
+
+pub struct App {
+    /// In progress first, then completed, then errors
+    jobs:        Vec<Job>,
+    pub filter:  String,
+    pub mode:    Mode,
+    pub list:    ListState,
+    /// Shown in the status bar until the next key press
+    pub message: Option<String>,
+}
+
+impl App {
+    pub fn new(jobs: Vec<Job>) -> Self {
+        let mut app = Self {
+            jobs:    vec![],
+            filter:  String::new(),
+            mode:    Mode::Normal,
+            list:    ListState::default(),
+            message: None,
+        };
+        app.set_jobs(jobs);
+        app
+    }
+
+    /// Replace the jobs, e.g. after refreshing, and keep the same URL
+    /// selected if it's still there
+    pub fn set_jobs(&mut self, jobs: Vec<Job>) {
+        let selected_url = self
+            .selected()
+            .map(|job| job.url.url.clone());
+        let JobGroups {
+            completed,
+            in_progress,
+            errors,
+        } = group_jobs(jobs);
+        self.jobs = in_progress
+            .into_iter()
+            .chain(completed)
+            .chain(errors)
+            .collect();
+        let index = selected_url.and_then(|url| {
+            self.visible()
+                .iter()
+                .position(|job| job.url.url == url)
+        });
+        self.select(index.unwrap_or(0));
+    }
+
+    /// The jobs that match the filter
+    pub fn visible(&self) -> Vec<&Job> {
+        let filter = self.filter.to_lowercase();
+        self.jobs
+            .iter()
+            .filter(|job| filter.is_empty() || matches(job, &filter))
+            .collect()
+    }
+
+    pub fn selected(&self) -> Option<&Job> {
+        let index = self.list.selected()?;
+        self.visible().get(index).copied()
+    }
+
+    /// Select the job at the index, or the nearest one
+    fn select(&mut self, index: usize) {
+        let count = self.visible().len();
+        self.list.select(match count {
+            0 => None,
+            _ => Some(index.min(count - 1)),
+        });
+    }
+
+    pub fn handle_key(&mut self, key: KeyCode) -> Action {
+        self.message = None;
+        match self.mode {
+            Mode::Normal => self.handle_normal_key(key),
+            Mode::Filter => {
+                match key {
+                    KeyCode::Char(c) => self.filter.push(c),
+                    KeyCode::Backspace => {
+                        self.filter.pop();
+                    }
+                    KeyCode::Esc => {
+                        self.filter.clear();
+                        self.mode = Mode::Normal;
+                    }
+                    KeyCode::Enter => self.mode = Mode::Normal,
+                    _ => {}
+                }
+                self.select(0);
+                Action::Nothing
+            }
+            Mode::ConfirmArchive => {
+                self.mode = Mode::Normal;
+                match (key, self.selected()) {
+                    (KeyCode::Char('y'), Some(job)) => {
+                        Action::Archive(job.url.url.clone())
+                    }
+                    _ => Action::Nothing,
+                }
+            }
+        }
+    }
+
+    fn handle_normal_key(&mut self, key: KeyCode) -> Action {
+        let index = self.list.selected().unwrap_or(0);
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return Action::Quit,
+            KeyCode::Char('r') => return Action::Refresh,
+            KeyCode::Down | KeyCode::Char('j') => self.select(index + 1),
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.select(index.saturating_sub(1))
+            }
+            KeyCode::Home | KeyCode::Char('g') => self.select(0),
+            KeyCode::End | KeyCode::Char('G') => self.select(usize::MAX),
+            KeyCode::Char('/') => self.mode = Mode::Filter,
+            KeyCode::Char('a') if self.selected().is_some() => {
+                self.mode = Mode::ConfirmArchive
+            }
+            KeyCode::Char('o') | KeyCode::Enter => {
+                if let Some(job) = self.selected() {
+                    return Action::Open(job.url.url.clone());
+                }
+            }
+            _ => {}
+        }
+        Action::Nothing
+    }
+
+    /// Forget the archived URL
+    pub fn remove(&mut self, url: &str) {
+        let index = self.list.selected().unwrap_or(0);
+        self.jobs
+            .retain(|job| job.url.url != url);
+        self.select(index);
+    }
+}
+
+/// Whether the (lowercase) filter occurs in the URL, its label or description,
+/// or the package's carrier, barcode or sender
+fn matches(job: &Job, filter: &str) -> bool {
+    let mut fields = vec![
+        Some(&job.url.url),
+        job.url.label.as_ref(),
+        job.url.description.as_ref(),
+    ];
+    if let Ok(package) = &job.result {
+        fields.extend([
+            Some(&package.channel),
+            Some(&package.barcode),
+            package.sender.as_ref(),
+        ]);
+    }
+    fields
+        .into_iter()
+        .flatten()
+        .any(|field| field.to_lowercase().contains(filter))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use packtrack::tracker::{Package, PackageStatus};
+    use packtrack::url_store::AnnotatedUrl;
+
+    fn job(url: &str, sender: &str, status: PackageStatus) -> Job {
+        Job {
+            url:    AnnotatedUrl::new(url.into(), None),
+            result: Ok(Package {
+                barcode: url.to_uppercase(),
+                channel: "PostNL".into(),
+                status,
+                sender: Some(sender.into()),
+                recipient: None,
+                eta: None,
+                eta_window: None,
+                delivered: None,
+                events: vec![],
+                pickup: None,
+            }),
+        }
+    }
+
+    fn app() -> App {
+        App::new(vec![
+            job("a", "Zalando", PackageStatus::Delivered),
+            job("b", "Bol.com", PackageStatus::InTransit),
+            Job {
+                url:    "c".into(),
+                result: Err("HTTP 503".into()),
+            },
+        ])
+    }
+
+    fn urls(app: &App) -> Vec<&str> {
+        app.visible()
+            .iter()
+            .map(|job| job.url.url.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_order_and_navigation() {
+        let mut app = app();
+        // In progress first, errors last
+        assert_eq!(urls(&app), ["b", "a", "c"]);
+        assert_eq!(app.list.selected(), Some(0));
+        app.handle_key(KeyCode::Up);
+        assert_eq!(app.list.selected(), Some(0));
+        app.handle_key(KeyCode::Char('G'));
+        assert_eq!(app.list.selected(), Some(2));
+        app.handle_key(KeyCode::Down);
+        assert_eq!(app.list.selected(), Some(2));
+        assert_eq!(
+            app.handle_key(KeyCode::Char('o')),
+            Action::Open("c".into())
+        );
+        assert_eq!(app.handle_key(KeyCode::Char('q')), Action::Quit);
+    }
+
+    #[test]
+    fn test_filter() {
+        let mut app = app();
+        app.handle_key(KeyCode::Char('/'));
+        assert_eq!(app.mode, Mode::Filter);
+        for c in "zal".chars() {
+            app.handle_key(KeyCode::Char(c));
+        }
+        assert_eq!(urls(&app), ["a"]);
+        // Keys are typed into the filter, not handled as commands
+        assert_eq!(app.handle_key(KeyCode::Char('q')), Action::Nothing);
+        assert_eq!(urls(&app), Vec::<&str>::new());
+        assert_eq!(app.list.selected(), None);
+        app.handle_key(KeyCode::Esc);
+        assert_eq!(app.mode, Mode::Normal);
+        assert_eq!(urls(&app), ["b", "a", "c"]);
+    }
+
+    #[test]
+    fn test_archive() {
+        let mut app = app();
+        app.handle_key(KeyCode::Down);
+        assert_eq!(app.handle_key(KeyCode::Char('a')), Action::Nothing);
+        assert_eq!(app.mode, Mode::ConfirmArchive);
+        assert_eq!(
+            app.handle_key(KeyCode::Char('y')),
+            Action::Archive("a".into())
+        );
+        app.remove("a");
+        assert_eq!(urls(&app), ["b", "c"]);
+        assert_eq!(app.list.selected(), Some(1));
+
+        // Anything but "y" cancels
+        app.handle_key(KeyCode::Char('a'));
+        assert_eq!(app.handle_key(KeyCode::Char('n')), Action::Nothing);
+        assert_eq!(app.mode, Mode::Normal);
+    }
+
+    #[test]
+    fn test_set_jobs_keeps_selection() {
+        let mut app = app();
+        app.handle_key(KeyCode::Down);
+        assert_eq!(app.selected().unwrap().url.url, "a");
+        app.set_jobs(vec![
+            job("d", "Coolblue", PackageStatus::InTransit),
+            job("a", "Zalando", PackageStatus::Delivered),
+            job("b", "Bol.com", PackageStatus::InTransit),
+        ]);
+        assert_eq!(app.selected().unwrap().url.url, "a");
+    }
+}
@@ -0,0 +1,25 @@
+/// A terminal UI to browse the packages: a list of packages, the events of the
+/// selected one, and keys to refresh, filter, archive and open them. Behind
+/// the `tui` feature.
+#[cfg(feature = "tui")]
+mod app;
+#[cfg(feature = "tui")]
+mod run;
+#[cfg(feature = "tui")]
+mod ui;
+
+#[cfg(feature = "tui")]
+pub use run::handle_tui;
+
+#[cfg(not(feature = "tui"))]
+pub async fn handle_tui(
+    _args: crate::cli::url::UrlArgs,
+    _settings: &packtrack::settings::Settings,
+    _ctx: &packtrack::api::Context,
+) -> packtrack::Result<()> {
+    Err(
+        "The terminal UI needs the `tui` feature. Reinstall packtrack with \
+         `--features tui` to enable this."
+            .into(),
+    )
+}
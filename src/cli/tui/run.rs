@@ -0,0 +1,126 @@
+use crate::cli::tui::app::{Action, App};
+use crate::cli::tui::ui;
+use crate::cli::url::UrlArgs;
+use chrono::Utc;
+use packtrack::Result;
+use packtrack::api::{Context, Job, track_urls};
+use packtrack::archive::{archive, remember_archived};
+use packtrack::settings::Settings;
+use packtrack::url_store::{FileUrlStore, UrlStore};
+use ratatui::DefaultTerminal;
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+pub async fn handle_tui(
+    args: UrlArgs,
+    settings: &Settings,
+    ctx: &Context,
+) -> Result<()> {
+    let file = args
+        .urls_file
+        .as_ref()
+        .unwrap_or(&settings.urls_file);
+    let mut app = App::new(track(file, settings, ctx).await?);
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, &mut app, file, settings, ctx).await;
+    ratatui::restore();
+    result
+}
+
+/// Draw the UI and handle key presses until the user quits
+async fn run(
+    terminal: &mut DefaultTerminal,
+    app: &mut App,
+    file: &Path,
+    settings: &Settings,
+    ctx: &Context,
+) -> Result<()> {
+    loop {
+        terminal.draw(|frame| ui::draw(frame, app))?;
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match app.handle_key(key.code) {
+            Action::Nothing => {}
+            Action::Quit => return Ok(()),
+            Action::Refresh => {
+                app.message = Some("Refreshing…".into());
+                terminal.draw(|frame| ui::draw(frame, app))?;
+                match track(file, settings, ctx).await {
+                    Ok(jobs) => {
+                        app.set_jobs(jobs);
+                        app.message = Some("Refreshed".into());
+                    }
+                    Err(err) => {
+                        app.message = Some(format!("Couldn't refresh: {err}"))
+                    }
+                }
+            }
+            Action::Archive(url) => {
+                let mut url_store = FileUrlStore::new(file.to_path_buf())?;
+                let mut cache = settings.open_cache()?;
+                let archived = archive(
+                    &mut url_store,
+                    cache.as_mut(),
+                    &ctx.registry,
+                    std::slice::from_ref(&url),
+                )?;
+                url_store.save()?;
+                cache.save()?;
+                remember_archived(&archived, Utc::now())?;
+                app.remove(&url);
+                app.message = Some(format!("Removed {url}"));
+            }
+            Action::Open(url) => {
+                app.message = Some(match open_in_browser(&url) {
+                    Ok(()) => format!("Opened {url}"),
+                    Err(err) => err.to_string(),
+                })
+            }
+        }
+    }
+}
+
+/// Track all the URLs in the file
+async fn track(
+    file: &Path,
+    settings: &Settings,
+    ctx: &Context,
+) -> Result<Vec<Job>> {
+    let urls = ctx
+        .registry
+        .merge_duplicates(FileUrlStore::new(file.to_path_buf())?.filter(None))
+        .into_iter()
+        .filter(|u| !u.is_placeholder())
+        .collect();
+    let cache = settings.open_cache()?;
+    track_urls(urls, cache, ctx).await
+}
+
+/// Programs that open a URL in the default browser
+#[cfg(target_os = "macos")]
+const OPENER: &[&str] = &["open"];
+#[cfg(target_os = "windows")]
+const OPENER: &[&str] = &["cmd", "/C", "start", ""];
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const OPENER: &[&str] = &["xdg-open"];
+
+fn open_in_browser(url: &str) -> Result<()> {
+    // Only web URLs can be opened, e.g. not "demo:postnl"
+    if !url.starts_with("http") {
+        return Err(format!("Can't open {url} in a browser").into());
+    }
+    Command::new(OPENER[0])
+        .args(&OPENER[1..])
+        .arg(url)
+        // Don't let the browser's output mess up the UI
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|err| format!("Couldn't run {}: {err}", OPENER[0]))?;
+    Ok(())
+}
@@ -0,0 +1,208 @@
+/// Drawing the terminal UI: the packages on the left, the details and
+/// events of the selected one on the right, and a status bar with the keys
+use crate::cli::display::{
+    display_event, display_pickup_deadline, display_status, display_time,
+    display_timewindow,
+};
+use crate::cli::i18n::{Label, tr};
+use crate::cli::tui::app::{App, Mode};
+use packtrack::api::Job;
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, Paragraph, Wrap};
+
+const KEYS: &str =
+    "↑↓ select  r refresh  / filter  a archive  o open in browser  q quit";
+
+pub fn draw(frame: &mut Frame, app: &mut App) {
+    let [main, status] =
+        Layout::vertical([Constraint::Fill(1), Constraint::Length(1)])
+            .areas(frame.area());
+    let [left, right] = Layout::horizontal([
+        Constraint::Percentage(40),
+        Constraint::Percentage(60),
+    ])
+    .areas(main);
+
+    let items: Vec<ListItem> = app
+        .visible()
+        .into_iter()
+        .map(list_item)
+        .collect();
+    let mut title = " Packages ".to_owned();
+    if !app.filter.is_empty() {
+        title = format!(" Packages: \"{}\" ", app.filter);
+    }
+    let list = List::new(items)
+        .block(Block::bordered().title(title))
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+        .highlight_symbol("> ");
+    frame.render_stateful_widget(list, left, &mut app.list);
+
+    let details = app
+        .selected()
+        .map(details)
+        .unwrap_or_default();
+    frame.render_widget(
+        Paragraph::new(details)
+            .block(Block::bordered())
+            .wrap(Wrap { trim: false }),
+        right,
+    );
+
+    let status_line = match (&app.mode, &app.message) {
+        (Mode::Filter, _) => {
+            format!("Filter: {}▏ (Enter to keep, Esc to clear)", app.filter)
+        }
+        (Mode::ConfirmArchive, _) => {
+            "Remove the selected URL from the urls file? (y/n)".into()
+        }
+        (Mode::Normal, Some(message)) => message.clone(),
+        (Mode::Normal, None) => KEYS.into(),
+    };
+    frame.render_widget(Line::from(status_line).dim(), status);
+}
+
+/// "PostNL 3SABCD1234567 (shoes)", colored by status
+fn list_item(job: &Job) -> ListItem<'static> {
+    let (name, color) = match &job.result {
+        Ok(package) => {
+            let color = match package.status.is_final() {
+                true => Color::Green,
+                false => Color::Yellow,
+            };
+            (job.url.package_name(package), color)
+        }
+        Err(_) => (job.url.url.clone(), Color::Red),
+    };
+    let mut spans = vec![Span::styled(name, Style::new().fg(color))];
+    if let Some(description) = &job.url.description {
+        spans.push(Span::raw(format!(" ({description})")));
+    }
+    ListItem::new(Line::from(spans))
+}
+
+/// The details of the package, and its events, newest first
+fn details(job: &Job) -> Vec<Line<'_>> {
+    let package = match &job.result {
+        Ok(package) => package,
+        Err(err) => {
+            return vec![
+                Line::from(job.url.url.as_str()),
+                Line::from(""),
+                Line::from(format!("{}: {err}", tr(Label::Error))).red(),
+            ];
+        }
+    };
+    let mut lines = vec![Line::from(job.url.package_name(package)).bold()];
+    let mut field = |label: Label, value: String| {
+        lines.push(Line::from(vec![
+            Span::raw(format!("{}: ", tr(label))).dim(),
+            Span::raw(value),
+        ]));
+    };
+    if let Some(description) = &job.url.description {
+        field(Label::Description, description.clone());
+    }
+    for note in &job.url.notes {
+        field(Label::Note, note.clone());
+    }
+    field(Label::Status, display_status(&package.status));
+    if let Some(sender) = &package.sender {
+        field(Label::From, sender.clone());
+    }
+    if let Some(recipient) = &package.recipient {
+        field(Label::To, recipient.clone());
+    }
+    if let Some(eta) = package.eta {
+        field(Label::Eta, display_time(eta));
+    }
+    if let Some(window) = &package.eta_window {
+        field(Label::EtaWindow, display_timewindow(window));
+    }
+    if let Some(pickup) = &package.pickup {
+        field(Label::PickupPoint, pickup.location.clone());
+        if !package.status.is_final()
+            && let Some(deadline) = display_pickup_deadline(pickup)
+        {
+            field(Label::CollectBefore, deadline);
+        }
+    }
+    field(Label::Url, job.url.url.clone());
+    lines.push(Line::from(""));
+    lines.push(Line::from(format!("{}:", tr(Label::Events))).bold());
+    let mut events: Vec<_> = package.events.iter().collect();
+    events.sort_by_key(|event| std::cmp::Reverse(event.timestamp));
+    lines.extend(
+        events
+            .into_iter()
+            .map(|event| Line::from(display_event(event))),
+    );
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use packtrack::tracker::{Event, Package, PackageStatus};
+    use packtrack::url_store::AnnotatedUrl;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    #[test]
+    fn test_draw() -> packtrack::Result<()> {
+        let mut app = App::new(vec![Job {
+            url:    AnnotatedUrl::new(
+                "demo:postnl".into(),
+                Some("shoes".into()),
+            ),
+            result: Ok(Package {
+                barcode:    "3SABCD1234567".into(),
+                channel:    "PostNL".into(),
+                status:     PackageStatus::InTransit,
+                sender:     Some("Zalando".into()),
+                recipient:  None,
+                eta:        None,
+                eta_window: None,
+                delivered:  None,
+                events:     vec![
+                    Event::new("2024-11-19T12:00:00Z".parse()?, "Sorted"),
+                    Event::new(
+                        "2024-11-20T12:00:00Z".parse()?,
+                        "Out for delivery",
+                    ),
+                ],
+                pickup:     None,
+            }),
+        }]);
+        // The test backend can't fail
+        let Ok(mut terminal) = Terminal::new(TestBackend::new(100, 12));
+        let Ok(_) = terminal.draw(|frame| draw(frame, &mut app));
+        let screen: Vec<String> = terminal
+            .backend()
+            .buffer()
+            .content
+            .chunks(100)
+            .map(|row| {
+                row.iter()
+                    .map(|cell| cell.symbol())
+                    .collect()
+            })
+            .collect();
+        assert!(screen[1].contains("> PostNL 3SABCD1234567 (shoes)"));
+        assert!(screen[1].contains("PostNL 3SABCD1234567"));
+        assert!(screen[3].contains("Status: In transit"));
+        // Newest event first
+        let out = screen
+            .iter()
+            .position(|row| row.contains("Out for delivery"));
+        let sorted = screen
+            .iter()
+            .position(|row| row.contains("Sorted"));
+        assert!(out < sorted);
+        assert!(screen[11].starts_with("↑↓ select"));
+        Ok(())
+    }
+}
@@ -0,0 +1,180 @@
+// Alerts for the moments people wait for: a package going out for delivery,
+// and a package being delivered, plus low priority notifications for any
+// other change (for webhooks and the like). Meant for modes where packtrack
+// keeps running (e.g. watch mode), which compare each fresh package with the
+// previous version of it.
+
+use crate::notify::{Notification, PackageUpdate, Priority};
+use crate::tracker::Package;
+use crate::url_store::AnnotatedUrl;
+use crate::utils::UtcTime;
+use crate::verify::compare;
+use chrono::TimeDelta;
+
+/// Carriers don't have a common "out for delivery" status, so a package
+/// counts as out for delivery once its ETA (window) is less than this far
+/// away.
+const OUT_FOR_DELIVERY_WITHIN: TimeDelta = TimeDelta::hours(1);
+
+/// True if the package isn't delivered yet, but should be within the hour
+pub fn is_out_for_delivery(package: &Package, now: UtcTime) -> bool {
+    if package.status.is_final() {
+        return false;
+    }
+    let start = package.eta.or(package
+        .eta_window
+        .as_ref()
+        .map(|w| w.start));
+    start.is_some_and(|start| start - now < OUT_FOR_DELIVERY_WITHIN)
+}
+
+/// An alert if the package went out for delivery or was delivered since the
+/// previous version of it (if there was one)
+pub fn delivery_alert(
+    url: &AnnotatedUrl,
+    previous: Option<&Package>,
+    current: &Package,
+    now: UtcTime,
+) -> Option<Notification> {
+    let was_final = previous.is_some_and(|p| p.status.is_final());
+    // Out for delivery is the more urgent one: someone should be home
+    let (title, priority) = if current.status.is_final() && !was_final {
+        ("Package delivered", Priority::Normal)
+    } else if is_out_for_delivery(current, now)
+        && !previous.is_some_and(|p| is_out_for_delivery(p, now))
+    {
+        ("Package out for delivery", Priority::High)
+    } else {
+        return None;
+    };
+    Some(Notification {
+        title: title.into(),
+        body: summary(url, current),
+        url: Some(url.url.clone()),
+        priority,
+        update: None,
+    })
+}
+
+/// A notification for any change since the previous version of the package,
+/// with the changes attached: the delivery alert if there is one, otherwise
+/// a low priority "Package updated".
+pub fn change_notification(
+    url: &AnnotatedUrl,
+    previous: &Package,
+    current: &Package,
+    now: UtcTime,
+) -> Option<Notification> {
+    let changes = compare(previous, current);
+    let alert = delivery_alert(url, Some(previous), current, now);
+    if changes.is_empty() && alert.is_none() {
+        return None;
+    }
+    let notification = alert.unwrap_or_else(|| Notification {
+        title:    "Package updated".into(),
+        body:     summary(url, current),
+        url:      Some(url.url.clone()),
+        priority: Priority::Low,
+        update:   None,
+    });
+    Some(Notification {
+        update: Some(PackageUpdate {
+            package: current.clone(),
+            changes,
+        }),
+        ..notification
+    })
+}
+
+/// e.g. "PostNL ABC (shoes): Delivered"
+fn summary(url: &AnnotatedUrl, package: &Package) -> String {
+    let mut name = url.package_name(package);
+    if let Some(description) = &url.description {
+        name += &format!(" ({description})");
+    }
+    format!("{name}: {}", package.status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tracker::PackageStatus;
+
+    fn package(status: PackageStatus, eta: Option<&str>) -> Package {
+        Package {
+            barcode: "ABC".into(),
+            channel: "PostNL".into(),
+            status,
+            sender: None,
+            recipient: None,
+            eta: eta.map(|eta| eta.parse().unwrap()),
+            eta_window: None,
+            delivered: None,
+            events: vec![],
+            pickup: None,
+        }
+    }
+
+    #[test]
+    fn test_delivery_alert() {
+        use PackageStatus::*;
+        let now: UtcTime = "2026-10-16T10:00:00Z".parse().unwrap();
+        let url = AnnotatedUrl::new("url".into(), Some("shoes".into()));
+        let tomorrow = package(InTransit, Some("2026-10-17T10:00:00Z"));
+        let soon = package(InTransit, Some("2026-10-16T10:30:00Z"));
+        let delivered = package(Delivered, None);
+        let alert = |previous: Option<&Package>, current: &Package| {
+            delivery_alert(&url, previous, current, now).map(|n| n.title)
+        };
+
+        assert_eq!(alert(Some(&tomorrow), &tomorrow), None);
+        assert_eq!(
+            alert(Some(&tomorrow), &soon).as_deref(),
+            Some("Package out for delivery")
+        );
+        assert_eq!(alert(Some(&soon), &soon), None);
+        assert_eq!(
+            alert(Some(&soon), &delivered).as_deref(),
+            Some("Package delivered")
+        );
+        assert_eq!(alert(Some(&delivered), &delivered), None);
+        // A package seen for the first time
+        assert_eq!(
+            alert(None, &soon).as_deref(),
+            Some("Package out for delivery")
+        );
+        let notification = delivery_alert(&url, None, &delivered, now).unwrap();
+        assert_eq!(notification.body, "PostNL ABC (shoes): Delivered");
+        assert_eq!(notification.priority, Priority::Normal);
+        let notification = delivery_alert(&url, None, &soon, now).unwrap();
+        assert_eq!(notification.priority, Priority::High);
+    }
+
+    #[test]
+    fn test_change_notification() {
+        use PackageStatus::*;
+        let now: UtcTime = "2026-10-16T10:00:00Z".parse().unwrap();
+        let url = AnnotatedUrl::from("url");
+        let tomorrow = package(InTransit, Some("2026-10-17T10:00:00Z"));
+        let later = package(InTransit, Some("2026-10-17T14:00:00Z"));
+        let delivered = package(Delivered, None);
+
+        assert!(change_notification(&url, &tomorrow, &tomorrow, now).is_none());
+
+        let updated =
+            change_notification(&url, &tomorrow, &later, now).unwrap();
+        assert_eq!(updated.title, "Package updated");
+        assert_eq!(updated.priority, Priority::Low);
+        let json = serde_json::to_value(&updated).unwrap();
+        assert_eq!(json["priority"], "low");
+        assert_eq!(
+            json["update"]["changes"][0]["eta_changed"]["fresh"]["start"],
+            "2026-10-17T14:00:00Z"
+        );
+
+        let alert = change_notification(&url, &later, &delivered, now).unwrap();
+        assert_eq!(alert.title, "Package delivered");
+        assert_eq!(alert.priority, Priority::Normal);
+        assert!(alert.update.is_some());
+    }
+}
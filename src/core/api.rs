@@ -1,37 +1,112 @@
-use crate::cache::{Cache, FileCache};
+use crate::cache::Cache;
 
 use crate::cached_tracker::CachedTracker;
 use crate::error::Result;
+use crate::tracker::Credentials;
 use crate::tracker::Package;
+use crate::tracker::Tracker;
 use crate::tracker::TrackerContext;
-use crate::tracker::get_handler;
+use crate::tracker::TrackerRegistry;
 use crate::url_store::AnnotatedUrl;
+use crate::verify::cached_packages;
+use chrono::{TimeDelta, Utc};
+use futures::StreamExt;
+use futures::stream::{self, FuturesUnordered};
+use std::path::PathBuf;
 use tokio::sync::Mutex;
 
 /// Container for settings and runtime flags
 pub struct Context {
     /// Max age for cache entries to be reused
-    pub cache_seconds:      usize,
+    pub cache_seconds:          usize,
     /// If false, don't use the cache at all, even for delivered packages
-    pub use_cache:          bool,
-    pub filters:            Filters,
+    pub use_cache:              bool,
+    /// Before saving the cache, evict the least recently used URLs until the
+    /// entries take up at most this many bytes
+    pub cache_max_bytes:        Option<u64>,
+    /// Reuse cache entries however old they are, so the results are instant.
+    /// The caller should refresh the `stale_urls` afterwards.
+    pub stale_while_revalidate: bool,
+    pub filters:                Filters,
+    /// The trackers to choose from when tracking a URL
+    pub registry:               TrackerRegistry,
     // ----- user preferences -----
-    pub default_postcode:   Option<String>,
-    pub preferred_language: String,
+    pub default_postcode:       Option<String>,
+    pub preferred_language:     String,
+    /// Chrome/Chromium executable for trackers that need a headless browser
+    pub browser_path:           Option<PathBuf>,
+    /// Send carrier API requests to this origin instead (for testing)
+    pub api_origin:             Option<String>,
+    /// API keys and tokens for carriers that need them
+    pub credentials:            Credentials,
+    /// Also fetch the events in these languages, for carriers that support
+    /// them, so that the language can be switched without fetching again
+    pub languages:              Vec<String>,
 }
 impl Default for Context {
     fn default() -> Self {
         Self {
             // TODO: Maybe make a separate Language enum which implements
             // default = "en"
-            preferred_language: "en".to_string(),
-            cache_seconds:      0,
-            use_cache:          true,
-            filters:            Filters::default(),
-            default_postcode:   None,
+            preferred_language:     "en".to_string(),
+            cache_seconds:          0,
+            use_cache:              true,
+            cache_max_bytes:        None,
+            stale_while_revalidate: false,
+            filters:                Filters::default(),
+            registry:               TrackerRegistry::default(),
+            default_postcode:       None,
+            browser_path:           None,
+            api_origin:             None,
+            credentials:            Credentials::default(),
+            languages:              vec![],
         }
     }
 }
+impl Context {
+    /// The settings that trackers need, in the user's preferred language
+    pub fn tracker_context(&self) -> TrackerContext<'_> {
+        TrackerContext {
+            recipient_postcode: self.default_postcode.as_deref(),
+            language:           &self.preferred_language,
+            browser_path:       self.browser_path.as_deref(),
+            api_origin:         self.api_origin.as_deref(),
+            credentials:        &self.credentials,
+        }
+    }
+
+    /// Max age for cache entries to be reused, taking
+    /// `stale_while_revalidate` into account
+    fn reuse_seconds(&self) -> usize {
+        match self.stale_while_revalidate {
+            true => usize::MAX,
+            false => self.cache_seconds,
+        }
+    }
+}
+
+/// The URLs whose newest cache entry is too old to be reused, for packages
+/// that can still change. With `stale_while_revalidate` these are shown from
+/// the cache anyway, and should be refreshed afterwards.
+pub fn stale_urls(
+    cache: &dyn Cache,
+    urls: &[AnnotatedUrl],
+    ctx: &Context,
+) -> Vec<String> {
+    let max_age = TimeDelta::seconds(ctx.cache_seconds as i64);
+    let cached = cached_packages(cache, &ctx.registry, urls);
+    urls.iter()
+        .filter(|url| {
+            cached
+                .get(&url.url)
+                .is_some_and(|cached| {
+                    !cached.package.status.is_final()
+                        && Utc::now() - cached.created > max_age
+                })
+        })
+        .map(|url| url.url.clone())
+        .collect()
+}
 
 #[derive(Default)]
 pub struct Filters {
@@ -41,6 +116,41 @@ pub struct Filters {
     /// postal carrier e.g. DHL
     pub carrier:   Option<String>,
     pub recipient: Option<String>,
+    /// Only the URLs with this tag
+    pub tag:       Option<String>,
+}
+impl Filters {
+    /// Whether the URL has the tag, if one is filtered on
+    pub fn matches_url(&self, url: &AnnotatedUrl) -> bool {
+        self.tag
+            .as_ref()
+            .is_none_or(|tag| url.has_tag(tag))
+    }
+
+    /// Whether the job's package matches the sender, carrier and recipient
+    /// filters, and its URL the tag. Errors always match the package filters,
+    /// so that they aren't hidden.
+    pub fn matches(&self, job: &Job) -> bool {
+        if !self.matches_url(&job.url) {
+            return false;
+        }
+        let Ok(package) = &job.result else {
+            return true;
+        };
+        let contains = |value: Option<&str>, query: &Option<String>| {
+            let Some(query) = query else {
+                return true;
+            };
+            value.is_some_and(|value| {
+                value
+                    .to_lowercase()
+                    .contains(&query.to_lowercase())
+            })
+        };
+        contains(package.recipient.as_deref(), &self.recipient)
+            && contains(package.sender.as_deref(), &self.sender)
+            && contains(Some(&package.channel), &self.carrier)
+    }
 }
 
 // TODO: This should probably be a custom error
@@ -49,13 +159,31 @@ pub struct Job {
     pub result: Result<Package>,
 }
 
+/// The language to request the package in. The package's own language takes
+/// precedence over the preferred one.
+pub(crate) fn choose_language<'a>(
+    tracker: &'a dyn Tracker,
+    url: &'a AnnotatedUrl,
+    ctx: &'a Context,
+) -> &'a str {
+    let wanted: Vec<&str> = url
+        .language
+        .iter()
+        .chain([&ctx.preferred_language])
+        .map(String::as_str)
+        .collect();
+    tracker
+        .choose_language(&wanted)
+        .unwrap_or(&ctx.preferred_language)
+}
+
 /// Get the Tracker implementation for the given URL, and track the package.
 pub async fn track_url(
     url: &AnnotatedUrl,
     cache: &Mutex<dyn Cache>,
     ctx: &Context,
 ) -> Job {
-    let tracker = match get_handler(&url.url) {
+    let tracker = match ctx.registry.get_handler(&url.url) {
         Ok(tracker) => tracker,
         Err(err) => {
             return Job {
@@ -64,82 +192,258 @@ pub async fn track_url(
             };
         }
     };
+    let language = choose_language(tracker, url, ctx);
+    log::debug!("Requesting {} in language {language}", url.url);
     let mut tracker = CachedTracker {
-        tracker: tracker,
-        cache:   cache,
+        tracker,
+        cache,
+        languages: &ctx.languages,
     };
     let tracker_context = TrackerContext {
-        recipient_postcode: ctx.default_postcode.as_deref(),
-        language:           &ctx.preferred_language,
+        language,
+        recipient_postcode: url
+            .postcode
+            .as_deref()
+            .or(ctx.default_postcode.as_deref()),
+        ..ctx.tracker_context()
     };
     let result = tracker
-        .track(&url.url, ctx.cache_seconds, ctx.use_cache, &tracker_context)
-        .await;
+        .track(
+            &url.url,
+            ctx.reuse_seconds(),
+            ctx.use_cache,
+            &tracker_context,
+        )
+        .await
+        .map(|mut package| {
+            package.localize(language);
+            package
+        });
     Job {
         url: url.clone(),
         result,
     }
 }
 
+/// Track several URLs that use the same tracker and language with as few
+/// requests as possible (see `Tracker::get_raw_batch`). Returns the jobs
+/// with the indices of their URLs.
+async fn track_batch(
+    tracker: &dyn Tracker,
+    language: &str,
+    indices: &[usize],
+    urls: &[AnnotatedUrl],
+    cache: &Mutex<dyn Cache>,
+    ctx: &Context,
+) -> Vec<(usize, Job)> {
+    let batch_urls: Vec<&str> = indices
+        .iter()
+        .map(|&i| urls[i].url.as_str())
+        .collect();
+    log::debug!(
+        "Requesting {} {} URLs in language {language}",
+        batch_urls.len(),
+        tracker.name()
+    );
+    let mut tracker = CachedTracker {
+        tracker,
+        cache,
+        languages: &ctx.languages,
+    };
+    let tracker_context = TrackerContext {
+        language,
+        ..ctx.tracker_context()
+    };
+    let results = tracker
+        .track_batch(
+            &batch_urls,
+            ctx.reuse_seconds(),
+            ctx.use_cache,
+            &tracker_context,
+        )
+        .await;
+    indices
+        .iter()
+        .zip(results)
+        .map(|(&i, result)| {
+            let result = result.map(|mut package| {
+                package.localize(language);
+                package
+            });
+            let job = Job {
+                url: urls[i].clone(),
+                result,
+            };
+            (i, job)
+        })
+        .collect()
+}
+
 /// Track all the given URLs asynchronously
 pub async fn track_urls(
     urls: Vec<AnnotatedUrl>,
-    cache: FileCache,
+    cache: impl Cache + 'static,
     ctx: &Context,
 ) -> Result<Vec<Job>> {
-    // fire off all the tasks in parallel
+    let mut jobs = vec![];
+    track_urls_with(urls, cache, ctx, |i, job| jobs.push((i, job))).await?;
+    jobs.sort_by_key(|(i, _)| *i);
+    Ok(jobs
+        .into_iter()
+        .map(|(_, job)| job)
+        .collect())
+}
+
+/// Track all the given URLs asynchronously, and call `on_job` with each job
+/// (and the index of its URL) as soon as it's finished, so that the results
+/// can be shown before the slowest carrier has answered. Jobs that don't
+/// match the filters are skipped.
+pub async fn track_urls_with(
+    urls: Vec<AnnotatedUrl>,
+    cache: impl Cache + 'static,
+    ctx: &Context,
+    mut on_job: impl FnMut(usize, Job),
+) -> Result<()> {
+    // URLs for trackers that support batch requests are grouped per tracker
+    // and language; the rest are tracked one by one, as are the URLs with
+    // their own postcode, because a batch is requested with one postcode.
+    let mut batches: Vec<(&dyn Tracker, &str, Vec<usize>)> = vec![];
+    let mut single = vec![];
+    for (i, url) in urls.iter().enumerate() {
+        match ctx.registry.get_handler(&url.url) {
+            Ok(tracker)
+                if tracker.batch_size() > 1 && url.postcode.is_none() =>
+            {
+                let language = choose_language(tracker, url, ctx);
+                match batches.iter_mut().find(|(t, l, _)| {
+                    t.name() == tracker.name() && *l == language
+                }) {
+                    Some((_, _, indices)) => indices.push(i),
+                    None => batches.push((tracker, language, vec![i])),
+                }
+            }
+            _ => single.push(i),
+        }
+    }
+
+    // fire off all the tasks in parallel, and handle them in the order they
+    // finish
     let cache = Mutex::new(cache);
-    let tasks: Vec<_> = urls
+    let (cache, urls) = (&cache, &urls);
+    let single_tasks: FuturesUnordered<_> =
+        single
+            .iter()
+            .map(|&i| async move {
+                vec![(i, track_url(&urls[i], cache, ctx).await)]
+            })
+            .collect();
+    let batch_tasks: FuturesUnordered<_> = batches
         .iter()
-        .map(|url| track_url(url, &cache, ctx))
+        .map(|(tracker, language, indices)| {
+            track_batch(*tracker, language, indices, urls, cache, ctx)
+        })
         .collect();
-    let mut jobs = futures::future::join_all(tasks).await;
+    let mut finished = stream::select(single_tasks, batch_tasks);
+    while let Some(jobs) = finished.next().await {
+        for (i, job) in jobs {
+            if ctx.filters.matches(&job) {
+                on_job(i, job);
+            }
+        }
+    }
     {
-        let cache = cache.lock().await;
-        if cache.modified {
+        let mut cache = cache.lock().await;
+        if let Some(max_bytes) = ctx.cache_max_bytes {
+            let evicted = cache.evict_least_recently_used(max_bytes);
+            if !evicted.is_empty() {
+                log::info!(
+                    "Evicted {} URLs from the cache to keep it under {max_bytes} bytes",
+                    evicted.len()
+                );
+            }
+        }
+        if cache.is_modified() {
             cache.save()?;
         }
     }
+    Ok(())
+}
 
-    if let Some(query) = &ctx.filters.recipient {
-        jobs = jobs
-            .into_iter()
-            .filter(|job| match &job.result {
-                Ok(package) => match package.recipient.as_ref() {
-                    Some(recipient) => recipient
-                        .to_lowercase()
-                        .contains(&query.to_lowercase()),
-                    None => false,
-                },
-                Err(_) => true, // don't remove errors
-            })
-            .collect();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::{CacheEntry, MemoryCache};
+    use crate::mocks::load_text;
+
+    #[test]
+    fn test_filters_tag() {
+        let filters = Filters {
+            tag: Some("gift".into()),
+            ..Default::default()
+        };
+        let job = |tags: &[&str]| Job {
+            url:    AnnotatedUrl {
+                tags: tags
+                    .iter()
+                    .map(|t| t.to_string())
+                    .collect(),
+                ..AnnotatedUrl::from("example.com")
+            },
+            result: Err("HTTP 503".into()),
+        };
+        assert!(filters.matches(&job(&["work", "Gift"])));
+        // Errors are hidden too, because the tag is on the URL
+        assert!(!filters.matches(&job(&["work"])));
+        assert!(Filters::default().matches(&job(&[])));
     }
-    if let Some(query) = &ctx.filters.sender {
-        jobs = jobs
-            .into_iter()
-            .filter(|job| match &job.result {
-                Ok(package) => match package.sender.as_ref() {
-                    Some(sender) => sender
-                        .to_lowercase()
-                        .contains(&query.to_lowercase()),
-                    None => false,
-                },
-                Err(_) => true, // don't remove errors
-            })
-            .collect();
+
+    #[test]
+    fn test_choose_language() {
+        use crate::tracker::{PostNLTracker, TntTracker};
+        let ctx = Context::default();
+        let forwarded = AnnotatedUrl {
+            language: Some("de".into()),
+            ..AnnotatedUrl::from("tnt.com/GE123456789WW")
+        };
+        let own = AnnotatedUrl::from("tnt.com/GE987654321WW");
+        // The package's language wins, and the others keep the preferred one
+        assert_eq!(choose_language(&TntTracker, &forwarded, &ctx), "de");
+        assert_eq!(choose_language(&TntTracker, &own, &ctx), "en");
+        // PostNL has no German, so it falls back to the preferred language
+        assert_eq!(choose_language(&PostNLTracker, &forwarded, &ctx), "en");
     }
-    if let Some(query) = &ctx.filters.carrier {
-        jobs = jobs
+
+    #[test]
+    fn test_stale_urls() -> Result<()> {
+        let url = |barcode: &str| {
+            format!("https://jouw.postnl.nl/track-and-trace/{barcode}")
+        };
+        let (fresh, stale, delivered, uncached) =
+            (url("3SAAAA"), url("3SBBBB"), url("3SCCCC"), url("3SDDDD"));
+        let hour_ago = Utc::now() - TimeDelta::hours(1);
+        let mut cache = MemoryCache::default();
+        cache.insert(fresh.clone(), load_text("postnl_undelivered.json")?);
+        for (url, file) in [
+            (&stale, "postnl_undelivered.json"),
+            (&delivered, "postnl_delivered.json"),
+        ] {
+            cache.insert_entry(
+                url.clone(),
+                CacheEntry {
+                    created: hour_ago,
+                    ..CacheEntry::new(load_text(file)?)
+                },
+            );
+        }
+        let urls: Vec<AnnotatedUrl> = [&fresh, &stale, &delivered, &uncached]
             .into_iter()
-            .filter(|job| match &job.result {
-                Ok(package) => package
-                    .channel
-                    .to_lowercase()
-                    .contains(&query.to_lowercase()),
-                Err(_) => true, // don't remove errors
-            })
+            .map(|url| url.as_str().into())
             .collect();
+        let ctx = Context {
+            cache_seconds: 60,
+            ..Context::default()
+        };
+        assert_eq!(stale_urls(&cache, &urls, &ctx), [stale]);
+        Ok(())
     }
-    Ok(jobs)
 }
@@ -0,0 +1,236 @@
+// Archiving packages that were delivered long ago (the `auto_archive_days`
+// setting), so that the urls file doesn't fill up with dead entries. Archived
+// URLs are removed from the urls file, and their responses from the cache.
+// They are also remembered in a file of their own, so that `packtrack sync`
+// can remove them on the other machines too, instead of bringing them back.
+
+use crate::Result;
+use crate::api::Job;
+use crate::cache::{Cache, get_cache_dir};
+use crate::tracker::TrackerRegistry;
+use crate::url_store::{AnnotatedUrl, UrlStore};
+use crate::utils::UtcTime;
+use chrono::TimeDelta;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// The URLs that were archived, and when
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct ArchivedUrls {
+    archived: BTreeMap<String, UtcTime>,
+}
+
+impl ArchivedUrls {
+    /// Load the archived URLs, or start afresh if there aren't any yet
+    pub fn load(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Ok(serde_json::from_str(&text)?),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                Ok(Self::default())
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn mark_archived(&mut self, url: &str, now: UtcTime) {
+        self.archived
+            .insert(url.to_owned(), now);
+    }
+
+    /// Whether the entry was archived. An entry that was (re)added after it
+    /// was archived doesn't count.
+    pub fn is_archived(&self, entry: &AnnotatedUrl) -> bool {
+        self.archived
+            .get(&entry.url)
+            .is_some_and(|archived| {
+                entry
+                    .created
+                    .is_none_or(|created| created <= *archived)
+            })
+    }
+
+    /// Add the URLs archived in another copy (e.g. from another machine),
+    /// returning how many were added or archived again since
+    pub fn merge(&mut self, other: Self) -> usize {
+        let mut changed = 0;
+        for (url, archived) in other.archived {
+            if self
+                .archived
+                .get(&url)
+                .is_none_or(|ours| *ours < archived)
+            {
+                self.archived.insert(url, archived);
+                changed += 1;
+            }
+        }
+        changed
+    }
+}
+
+/// Where the archived URLs are kept
+pub fn get_archived_urls_file() -> Result<PathBuf> {
+    Ok(get_cache_dir()?.join("packtrack-archived.json"))
+}
+
+/// Add the entries to the archived URLs file
+pub fn remember_archived(entries: &[AnnotatedUrl], now: UtcTime) -> Result<()> {
+    let path = get_archived_urls_file()?;
+    let mut archived = ArchivedUrls::load(&path)?;
+    for entry in entries {
+        archived.mark_archived(&entry.url, now);
+    }
+    archived.save(&path)
+}
+
+/// The URLs of the packages that were delivered more than `days` days before
+/// `now`. Packages without a delivery time count as delivered at their last
+/// event.
+pub fn expired_urls(jobs: &[Job], days: u32, now: UtcTime) -> Vec<String> {
+    let cutoff = now - TimeDelta::days(days.into());
+    jobs.iter()
+        .filter(|job| {
+            let Ok(package) = &job.result else {
+                return false;
+            };
+            let delivered = package.delivered.or(package
+                .events
+                .iter()
+                .map(|e| e.timestamp)
+                .max());
+            package.status.is_final() && delivered.is_some_and(|t| t < cutoff)
+        })
+        .map(|job| job.url.url.clone())
+        .collect()
+}
+
+/// Remove the URLs from the url store and the cache, returning the entries
+/// that were removed from the store. The other entries for the same package
+/// (see `TrackerRegistry::shipment_key`), which were merged into the tracked
+/// one, go too. URLs that aren't in the store are skipped.
+pub fn archive(
+    url_store: &mut dyn UrlStore,
+    cache: &mut dyn Cache,
+    registry: &TrackerRegistry,
+    urls: &[String],
+) -> Result<Vec<AnnotatedUrl>> {
+    let mut archived = vec![];
+    for url in urls {
+        let key = registry.shipment_key(url);
+        let same_package: Vec<String> = url_store
+            .filter(None)
+            .into_iter()
+            .map(|u| u.url)
+            .filter(|u| {
+                u == url || key.is_some() && registry.shipment_key(u) == key
+            })
+            .collect();
+        for url in same_package {
+            archived.extend(url_store.remove_url(&url));
+            cache.remove(&url);
+        }
+    }
+    Ok(archived)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{MemoryCache, MemoryUrlStore};
+    use crate::tracker::{Event, Package, PackageStatus};
+
+    fn job(url: &str, status: PackageStatus, last_event: &str) -> Job {
+        Job {
+            url:    url.into(),
+            result: Ok(Package {
+                barcode: "ABC".into(),
+                channel: "PostNL".into(),
+                status,
+                sender: None,
+                recipient: None,
+                eta: None,
+                eta_window: None,
+                delivered: None,
+                events: vec![Event::new(
+                    last_event.parse().unwrap(),
+                    "Something happened",
+                )],
+                pickup: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_archive() -> Result<()> {
+        use PackageStatus::*;
+        let postnl = "https://jouw.postnl.nl/track-and-trace/3SABCD1234567";
+        let with_postcode = format!("{postnl}-NL-1234AB");
+        let now: UtcTime = "2026-10-16T07:00:00Z".parse()?;
+        let jobs = vec![
+            job("url/o", Delivered, "2026-09-01T12:00:00Z"),
+            job("url/old", Delivered, "2026-10-14T12:00:00Z"),
+            job("url/stuck", InTransit, "2026-09-01T12:00:00Z"),
+            // The two entries for this package were merged into this one
+            job(&with_postcode, Delivered, "2026-09-01T12:00:00Z"),
+            Job {
+                url:    "url/broken".into(),
+                result: Err("HTTP 404".into()),
+            },
+        ];
+        let mut expired = expired_urls(&jobs, 30, now);
+        assert_eq!(expired, ["url/o", with_postcode.as_str()]);
+        assert!(expired_urls(&jobs, 60, now).is_empty());
+        // e.g. tracked without adding it to the urls file
+        expired.push("url/dynamic".into());
+
+        let mut store = MemoryUrlStore::new(
+            ["url/o", "url/old", "url/stuck", postnl, &with_postcode]
+                .into_iter()
+                .map(AnnotatedUrl::from)
+                .collect(),
+        );
+        let mut cache = MemoryCache::default();
+        cache.insert("url/o".into(), "o".into());
+        cache.insert("url/old".into(), "old".into());
+        cache.insert(postnl.into(), "postnl".into());
+        let registry = TrackerRegistry::default();
+        let archived = archive(&mut store, &mut cache, &registry, &expired)?;
+        let archived: Vec<_> = archived
+            .iter()
+            .map(|u| u.url.as_str())
+            .collect();
+        // "url/o" is part of "url/old", which stays
+        assert_eq!(archived, ["url/o", postnl, with_postcode.as_str()]);
+        assert_eq!(cache.get_all_urls(), ["url/old"]);
+        assert_eq!(store.urls(), ["url/old", "url/stuck"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_archived_urls() -> Result<()> {
+        let now: UtcTime = "2026-10-16T07:00:00Z".parse()?;
+        let mut archived = ArchivedUrls::default();
+        archived.mark_archived("url/old", now);
+        assert!(archived.is_archived(&"url/old".into()));
+        assert!(!archived.is_archived(&"url/other".into()));
+        let mut added_again = AnnotatedUrl::from("url/old");
+        added_again.created = Some(now + TimeDelta::days(1));
+        assert!(!archived.is_archived(&added_again));
+
+        let mut other = ArchivedUrls::default();
+        other.mark_archived("url/old", now - TimeDelta::days(1));
+        other.mark_archived("url/gone", now);
+        assert_eq!(archived.merge(other.clone()), 1);
+        assert_eq!(other.merge(archived.clone()), 1);
+        assert_eq!(archived, other);
+        Ok(())
+    }
+}
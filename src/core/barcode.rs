@@ -0,0 +1,288 @@
+// Validation of tracking numbers, so that we can warn the user about typos
+// before they end up with a URL that never returns any tracking info.
+//
+// Most carriers don't publish their barcode formats, but two widespread
+// formats have check digits:
+//
+// - S10 (UPU standard, used by national postal services for international
+//   mail), e.g. `RR123456785NL`: 2 letters for the service, an 8 digit serial
+//   number, a check digit, and the ISO country code of the issuing post.
+// - UPS, e.g. `1Z999AA10123456784`: "1Z" followed by 15 characters and a check
+//   digit.
+
+use derive_more::Display;
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// ISO 3166-1 alpha-2 country codes, used as the suffix of S10 barcodes
+const COUNTRY_CODES: &str = "
+    AD AE AF AG AI AL AM AO AQ AR AS AT AU AW AX AZ BA BB BD BE BF BG BH BI BJ
+    BL BM BN BO BQ BR BS BT BV BW BY BZ CA CC CD CF CG CH CI CK CL CM CN CO CR
+    CU CV CW CX CY CZ DE DJ DK DM DO DZ EC EE EG EH ER ES ET FI FJ FK FM FO FR
+    GA GB GD GE GF GG GH GI GL GM GN GP GQ GR GS GT GU GW GY HK HM HN HR HT HU
+    ID IE IL IM IN IO IQ IR IS IT JE JM JO JP KE KG KH KI KM KN KP KR KW KY KZ
+    LA LB LC LI LK LR LS LT LU LV LY MA MC MD ME MF MG MH MK ML MM MN MO MP MQ
+    MR MS MT MU MV MW MX MY MZ NA NC NE NF NG NI NL NO NP NR NU NZ OM PA PE PF
+    PG PH PK PL PM PN PR PS PT PW PY QA RE RO RS RU RW SA SB SC SD SE SG SH SI
+    SJ SK SL SM SN SO SR SS ST SV SX SY SZ TC TD TF TG TH TJ TK TL TM TN TO TR
+    TT TV TW TZ UA UG UM US UY UZ VA VC VE VG VI VN VU WF WS YE YT ZA ZM ZW
+";
+
+/// An S10 barcode, capturing its digits and country code. The number of
+/// digits is checked separately, to report a typo as such.
+static S10: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[A-Z]{2}([0-9]+)([A-Z]{2})$").expect("Invalid regex")
+});
+
+/// The "words" of a URL that may be a barcode
+static WORD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[A-Za-z0-9]+").expect("Invalid regex"));
+
+/// Weights for the 8 serial number digits of an S10 barcode
+const S10_WEIGHTS: [u32; 8] = [8, 6, 4, 2, 3, 5, 9, 7];
+
+/// The barcode formats we know how to validate
+#[derive(Debug, PartialEq, Display)]
+pub enum BarcodeFormat {
+    #[display("S10")]
+    S10,
+    #[display("UPS")]
+    Ups,
+    /// Anything we don't have rules for
+    #[display("unknown")]
+    Unknown,
+}
+
+/// Reasons a barcode looks mistyped
+#[derive(Debug, PartialEq, Display)]
+pub enum BarcodeError {
+    #[display(
+        "{barcode} has an invalid check digit (expected {expected}, found {found})"
+    )]
+    CheckDigit {
+        barcode:  String,
+        expected: u32,
+        found:    u32,
+    },
+
+    #[display("{barcode} ends in {country}, which is not a country code")]
+    Country { barcode: String, country: String },
+
+    #[display(
+        "{barcode} looks like an S10 barcode, but has {digits} digits instead of 9"
+    )]
+    S10Length { barcode: String, digits: usize },
+
+    #[display(
+        "{barcode} looks like a UPS barcode, but has {length} characters instead of 18"
+    )]
+    UpsLength { barcode: String, length: usize },
+}
+
+/// Uppercase the barcode and remove any whitespace, so that e.g.
+/// "rr 1234 5678 5nl" becomes "RR123456785NL"
+pub fn normalize(barcode: &str) -> String {
+    barcode
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_uppercase()
+}
+
+/// Tracking numbers are a single "word" of letters and digits; anything else
+/// is assumed to be a URL.
+pub fn is_barcode(input: &str) -> bool {
+    !input.is_empty()
+        && input
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric())
+}
+
+/// Check a normalized barcode against the formats we know. Barcodes in an
+/// unknown format are assumed to be fine.
+pub fn validate(barcode: &str) -> Result<BarcodeFormat, BarcodeError> {
+    if let Some(caps) = S10.captures(barcode) {
+        return validate_s10(barcode, &caps[1], &caps[2]);
+    }
+    if barcode.starts_with("1Z") {
+        return validate_ups(barcode);
+    }
+    Ok(BarcodeFormat::Unknown)
+}
+
+/// Find the barcode-like parts of the user's input (which may be a bare
+/// barcode or a URL), and return the problems with any of them.
+pub fn check(input: &str) -> Vec<BarcodeError> {
+    let normalized = normalize(input);
+    let candidates: Vec<String> = if is_barcode(&normalized) {
+        vec![normalized]
+    } else {
+        // Don't uppercase URLs: that would make every word look like a barcode
+        WORD.find_iter(input)
+            .map(|m| m.as_str().to_uppercase())
+            .filter(|word| word.chars().any(|c| c.is_ascii_digit()))
+            .collect()
+    };
+    candidates
+        .iter()
+        .filter_map(|barcode| validate(barcode).err())
+        .collect()
+}
+
+fn validate_s10(
+    barcode: &str,
+    digits: &str,
+    country: &str,
+) -> Result<BarcodeFormat, BarcodeError> {
+    if digits.len() != 9 {
+        return Err(BarcodeError::S10Length {
+            barcode: barcode.into(),
+            digits:  digits.len(),
+        });
+    }
+    if !COUNTRY_CODES
+        .split_whitespace()
+        .any(|c| c == country)
+    {
+        return Err(BarcodeError::Country {
+            barcode: barcode.into(),
+            country: country.into(),
+        });
+    }
+    let digits: Vec<u32> = digits
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .collect();
+    let sum: u32 = digits
+        .iter()
+        .zip(S10_WEIGHTS)
+        .map(|(d, w)| d * w)
+        .sum();
+    let expected = match 11 - sum % 11 {
+        10 => 0,
+        11 => 5,
+        n => n,
+    };
+    let found = digits[8];
+    if found != expected {
+        return Err(BarcodeError::CheckDigit {
+            barcode: barcode.into(),
+            expected,
+            found,
+        });
+    }
+    Ok(BarcodeFormat::S10)
+}
+
+fn validate_ups(barcode: &str) -> Result<BarcodeFormat, BarcodeError> {
+    // Not a UPS barcode, and the lengths and indices below count bytes
+    if !barcode.is_ascii() {
+        return Ok(BarcodeFormat::Unknown);
+    }
+    if barcode.len() != 18 {
+        return Err(BarcodeError::UpsLength {
+            barcode: barcode.into(),
+            length:  barcode.len(),
+        });
+    }
+    // Letters count as digits: A=2, B=3, ... J=1, K=2, etc.
+    let values: Vec<u32> = barcode[2..]
+        .chars()
+        .map(|c| {
+            c.to_digit(10)
+                .unwrap_or((c as u32 + 7) % 10)
+        })
+        .collect();
+    let sum: u32 = values[..15]
+        .iter()
+        .enumerate()
+        .map(|(i, v)| if i % 2 == 1 { v * 2 } else { *v })
+        .sum();
+    let expected = (10 - sum % 10) % 10;
+    let found = values[15];
+    if found != expected {
+        return Err(BarcodeError::CheckDigit {
+            barcode: barcode.into(),
+            expected,
+            found,
+        });
+    }
+    Ok(BarcodeFormat::Ups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize(" rr 1234 5678 5nl\n"), "RR123456785NL");
+    }
+
+    #[test]
+    fn test_validate_s10() {
+        assert_eq!(validate("RR123456785NL"), Ok(BarcodeFormat::S10));
+        assert_eq!(validate("LA000000005NL"), Ok(BarcodeFormat::S10));
+        assert_eq!(
+            validate("RR123456784NL"),
+            Err(BarcodeError::CheckDigit {
+                barcode:  "RR123456784NL".into(),
+                expected: 5,
+                found:    4,
+            })
+        );
+        assert_eq!(
+            validate("RR123456785XX"),
+            Err(BarcodeError::Country {
+                barcode: "RR123456785XX".into(),
+                country: "XX".into(),
+            })
+        );
+        assert_eq!(
+            validate("RR12345678NL"),
+            Err(BarcodeError::S10Length {
+                barcode: "RR12345678NL".into(),
+                digits:  8,
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_ups() {
+        assert_eq!(validate("1Z999AA10123456784"), Ok(BarcodeFormat::Ups));
+        assert!(matches!(
+            validate("1Z999AA10123456785"),
+            Err(BarcodeError::CheckDigit { .. })
+        ));
+        assert!(matches!(
+            validate("1Z999AA1012345678"),
+            Err(BarcodeError::UpsLength { length: 17, .. })
+        ));
+        // 18 bytes, but only 16 characters
+        assert_eq!(validate("1Z999AA1012345éé"), Ok(BarcodeFormat::Unknown));
+    }
+
+    #[test]
+    fn test_validate_unknown() {
+        assert_eq!(validate("3SABCD1234567"), Ok(BarcodeFormat::Unknown));
+    }
+
+    #[test]
+    fn test_check() {
+        assert!(check("rr123456785nl").is_empty());
+        assert_eq!(check("RR123456784NL").len(), 1);
+        assert!(
+            check("https://jouw.postnl.nl/track-and-trace/3SABCD1234567")
+                .is_empty()
+        );
+        assert_eq!(
+            check("https://www.postnl.nl/track?barcode=RR123456784NL")
+                .iter()
+                .map(|e| e.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "RR123456784NL has an invalid check digit (expected 5, found 4)"
+            ]
+        );
+    }
+}
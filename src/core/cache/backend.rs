@@ -0,0 +1,177 @@
+use crate::Result;
+use crate::cache::{Cache, FileCache, MemoryCache};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Where the cache is kept. Stored in the settings as a string: "json",
+/// "sqlite", "memory", or a redis:// URL.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(try_from = "String", into = "String")]
+pub enum CacheBackend {
+    /// A JSON file (the `cache_file` setting)
+    #[default]
+    Json,
+    /// An SQLite database next to the JSON file. Needs the `sqlite` feature.
+    Sqlite,
+    /// Nothing is kept between runs
+    Memory,
+    /// A Redis server, e.g. "redis://localhost:6379". Needs the `redis`
+    /// feature.
+    Redis(String),
+}
+
+impl CacheBackend {
+    /// Open the cache. `cache_file` is the JSON file, which the SQLite
+    /// database is kept next to.
+    pub fn open(&self, cache_file: &Path) -> Result<Box<dyn Cache>> {
+        Ok(match self {
+            Self::Json => Box::new(FileCache::new(cache_file.to_owned())?),
+            Self::Sqlite => open_sqlite(&sqlite_file(cache_file))?,
+            Self::Memory => Box::new(MemoryCache::default()),
+            Self::Redis(url) => open_redis(url, None)?,
+        })
+    }
+
+    /// Like `open`, for one of the users of a multi-user server. A Redis
+    /// cache keeps their entries apart from the other users'; for the other
+    /// backends, `cache_file` is already their own.
+    pub fn open_namespaced(
+        &self,
+        cache_file: &Path,
+        namespace: &str,
+    ) -> Result<Box<dyn Cache>> {
+        match self {
+            Self::Redis(url) => open_redis(url, Some(namespace)),
+            _ => self.open(cache_file),
+        }
+    }
+
+    /// Where the entries are, for `packtrack cache location`
+    pub fn location(&self, cache_file: &Path) -> String {
+        match self {
+            Self::Json => cache_file.display().to_string(),
+            Self::Sqlite => sqlite_file(cache_file)
+                .display()
+                .to_string(),
+            Self::Memory => "In memory (not kept between runs)".into(),
+            Self::Redis(url) => url.clone(),
+        }
+    }
+}
+
+/// "packtrack-cache.json" -> "packtrack-cache.sqlite"
+fn sqlite_file(cache_file: &Path) -> PathBuf {
+    cache_file.with_extension("sqlite")
+}
+
+#[cfg(feature = "sqlite")]
+fn open_sqlite(path: &Path) -> Result<Box<dyn Cache>> {
+    Ok(Box::new(crate::cache::SqliteCache::new(path)?))
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn open_sqlite(_path: &Path) -> Result<Box<dyn Cache>> {
+    Err(
+        "The sqlite cache backend needs the `sqlite` feature. Reinstall \
+         packtrack with `--features sqlite` to enable this."
+            .into(),
+    )
+}
+
+#[cfg(feature = "redis")]
+fn open_redis(url: &str, namespace: Option<&str>) -> Result<Box<dyn Cache>> {
+    use crate::cache::RedisCache;
+    Ok(Box::new(match namespace {
+        Some(namespace) => RedisCache::namespaced(url, namespace)?,
+        None => RedisCache::new(url)?,
+    }))
+}
+
+#[cfg(not(feature = "redis"))]
+fn open_redis(_url: &str, _namespace: Option<&str>) -> Result<Box<dyn Cache>> {
+    Err(
+        "The redis cache backend needs the `redis` feature. Reinstall \
+         packtrack with `--features redis` to enable this."
+            .into(),
+    )
+}
+
+impl FromStr for CacheBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Self::Json),
+            "sqlite" => Ok(Self::Sqlite),
+            "memory" => Ok(Self::Memory),
+            _ if s.starts_with("redis://") || s.starts_with("rediss://") => {
+                Ok(Self::Redis(s.into()))
+            }
+            _ => Err(format!(
+                "Invalid cache backend: {s}. Expected json, sqlite, memory or \
+                 a redis:// URL"
+            )),
+        }
+    }
+}
+
+impl TryFrom<String> for CacheBackend {
+    type Error = String;
+
+    fn try_from(s: String) -> std::result::Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl Display for CacheBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Json => write!(f, "json"),
+            Self::Sqlite => write!(f, "sqlite"),
+            Self::Memory => write!(f, "memory"),
+            Self::Redis(url) => write!(f, "{url}"),
+        }
+    }
+}
+
+impl From<CacheBackend> for String {
+    fn from(backend: CacheBackend) -> Self {
+        backend.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cache_backend() {
+        for s in ["json", "sqlite", "memory", "redis://localhost:6379"] {
+            let backend: CacheBackend = s.parse().unwrap();
+            assert_eq!(backend.to_string(), s);
+        }
+        assert_eq!(
+            "redis://localhost".parse(),
+            Ok(CacheBackend::Redis("redis://localhost".into()))
+        );
+        assert!(
+            "mongodb"
+                .parse::<CacheBackend>()
+                .is_err()
+        );
+        assert_eq!(
+            serde_json::from_str::<CacheBackend>("\"memory\"").unwrap(),
+            CacheBackend::Memory
+        );
+    }
+
+    #[test]
+    fn test_open_memory() -> Result<()> {
+        let mut cache = CacheBackend::Memory.open(Path::new("unused.json"))?;
+        cache.insert("url".into(), "text".into());
+        assert_eq!(cache.get("url").unwrap().text, "text");
+        Ok(())
+    }
+}
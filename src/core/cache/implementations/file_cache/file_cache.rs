@@ -1,8 +1,9 @@
 use std::fs::metadata;
 use std::os::unix::fs::MetadataExt;
-use std::{collections::HashMap, path::PathBuf};
-
-use chrono::Utc;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 use crate::{
     Result,
@@ -12,8 +13,10 @@ use crate::{
             CacheEntrySerializer, JsonCacheEntrySerializer,
         },
         models::CacheEntry,
+        utils::{remove_entries_older_than, touch_latest_entry},
     },
     file_handler::{FileHandler, TextFileHandler},
+    utils::UtcTime,
 };
 
 /// Cache which stores its entries as a `HashMap<String, CacheEntry>` in memory,
@@ -30,7 +33,7 @@ pub struct FileCache {
 impl FileCache {
     /// RAII -- instantiating the struct also loads the cache from file.
     pub fn new(path: PathBuf) -> Result<Self> {
-        #[allow(unused_mut)]
+        #[allow(unused_mut, unused_assignments)]
         let mut file_handler: Box<dyn FileHandler> = Box::new(TextFileHandler);
 
         // Use a mock file handler in tests to prevent tests doing IO
@@ -52,9 +55,7 @@ impl FileCache {
             modified: false,
         })
     }
-    fn select_serializer(
-        file: &PathBuf,
-    ) -> Result<Box<dyn CacheEntrySerializer>> {
+    fn select_serializer(file: &Path) -> Result<Box<dyn CacheEntrySerializer>> {
         if !file.exists() {
             return Err(
                 format!("File {} does not exist!", file.display()).into()
@@ -93,11 +94,7 @@ impl Cache for FileCache {
             .map(|v| v.iter().collect())
             .unwrap_or_default()
     }
-    fn insert(&mut self, url: String, text: String) {
-        let entry = CacheEntry {
-            created: Utc::now(),
-            text,
-        };
+    fn insert_entry(&mut self, url: String, entry: CacheEntry) {
         self.contents
             .entry(url.clone())
             .and_modify(|e| {
@@ -120,7 +117,7 @@ impl Cache for FileCache {
             .contents
             .remove(url)
             .unwrap_or_default();
-        if removed.len() > 0 {
+        if !removed.is_empty() {
             log::info!(
                 "Removed {} from cache which had {} entries",
                 url,
@@ -130,6 +127,20 @@ impl Cache for FileCache {
         }
         removed
     }
+    fn remove_older_than(&mut self, cutoff: UtcTime) -> usize {
+        let removed = remove_entries_older_than(&mut self.contents, cutoff);
+        if removed > 0 {
+            log::info!("Removed {removed} cache entries from before {cutoff}");
+            self.modified = true;
+        }
+        removed
+    }
+    fn touch(&mut self, url: &str) {
+        self.modified |= touch_latest_entry(&mut self.contents, url);
+    }
+    fn is_modified(&self) -> bool {
+        self.modified
+    }
     fn save(&self) -> Result<()> {
         let path = &self.path.display();
         self.serializer
@@ -157,6 +168,8 @@ impl Cache for FileCache {
 mod tests {
     use std::time::Duration;
 
+    use chrono::Utc;
+
     use crate::file_handler::MockFileHandler;
 
     use super::*;
@@ -265,7 +278,7 @@ mod tests {
         assert_eq!(removed, ["url"]);
         assert_eq!(cache.contents.len(), 2);
 
-        let mut removed = cache.prune(&vec![]);
+        let mut removed = cache.prune(&[]);
         removed.sort();
         assert_eq!(removed, ["url2", "url3"]);
     }
@@ -278,7 +291,7 @@ mod tests {
                 .iter()
                 .map(|delta| CacheEntry {
                     created: now - Duration::from_secs(*delta),
-                    text:    format!("{delta}s ago"),
+                    ..CacheEntry::new(format!("{delta}s ago"))
                 })
                 .collect(),
         )]);
@@ -314,8 +327,8 @@ mod tests {
     #[test]
     fn test_is_modified() {
         let mut cache = FileCache::for_test();
-        assert_eq!(cache.modified, false);
+        assert!(!cache.modified);
         cache.insert("url".into(), "foo".into());
-        assert_eq!(cache.modified, true);
+        assert!(cache.modified);
     }
 }
@@ -1,3 +1,4 @@
+#[allow(clippy::module_inception)]
 mod file_cache;
 mod serializer;
 
@@ -2,7 +2,7 @@ use crate::Result;
 use crate::cache::models::CacheEntry;
 use std::collections::HashMap;
 
-pub trait CacheEntrySerializer {
+pub trait CacheEntrySerializer: Send {
     fn serialize(
         &self,
         entries: &HashMap<String, Vec<CacheEntry>>,
@@ -20,7 +20,8 @@ pub trait CacheEntrySerializer {
 ///  "https://www.dhl.com/tracking/foo": [
 ///    {
 ///      "text": "some stringified JSON",
-///      "created": "2025-05-17T08:01:05.307751675Z"
+///      "created": "2025-05-17T08:01:05.307751675Z",
+///      "version": 2
 ///    }
 ///  ],
 ///  "https://jouw.postnl.nl/track-and-trace/bar": [
@@ -70,12 +71,12 @@ mod tests {
             "url1".into(),
             vec![
                 CacheEntry {
-                    text:    "a".into(),
                     created: utc("2025-05-17T08:01:05.307751675Z"),
+                    ..CacheEntry::new("a".into())
                 },
                 CacheEntry {
-                    text:    "b".into(),
                     created: utc("2025-05-18T08:01:05.307751675Z"),
+                    ..CacheEntry::new("b".into())
                 },
             ],
         )]);
@@ -84,11 +85,13 @@ mod tests {
   "url1": [
     {
       "text": "a",
-      "created": "2025-05-17T08:01:05.307751675Z"
+      "created": "2025-05-17T08:01:05.307751675Z",
+      "version": 2
     },
     {
       "text": "b",
-      "created": "2025-05-18T08:01:05.307751675Z"
+      "created": "2025-05-18T08:01:05.307751675Z",
+      "version": 2
     }
   ]
 }"#
@@ -103,4 +106,22 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_deserialize_legacy_entry() -> Result<()> {
+        let serialized = r#"
+{
+  "url1": [
+    {
+      "text": "a",
+      "created": "2025-05-17T08:01:05.307751675Z"
+    }
+  ]
+}"#;
+        let deserialized = JsonCacheEntrySerializer.deserialize(serialized)?;
+        let entry = &deserialized["url1"][0];
+        assert_eq!(entry.version, 1);
+        assert_eq!(entry.tracker, None);
+        Ok(())
+    }
 }
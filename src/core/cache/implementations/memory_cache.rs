@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+
+use crate::{
+    Result,
+    cache::{
+        Cache,
+        models::CacheEntry,
+        utils::{remove_entries_older_than, touch_latest_entry},
+    },
+    utils::UtcTime,
+};
+
+/// Cache which only lives in memory, and is lost at the end of the run. Useful
+/// when the user's cache file shouldn't be touched, e.g. in demo mode.
+#[derive(Default)]
+pub struct MemoryCache {
+    contents: HashMap<String, Vec<CacheEntry>>,
+}
+
+impl Cache for MemoryCache {
+    fn get_all_urls(&self) -> Vec<String> {
+        self.contents.keys().cloned().collect()
+    }
+    fn get_all(&self, url: &str) -> Vec<&CacheEntry> {
+        self.contents
+            .get(url)
+            .map(|v| v.iter().collect())
+            .unwrap_or_default()
+    }
+    fn insert_entry(&mut self, url: String, entry: CacheEntry) {
+        self.contents
+            .entry(url)
+            .or_default()
+            .push(entry);
+    }
+    fn remove(&mut self, url: &str) -> Vec<CacheEntry> {
+        self.contents
+            .remove(url)
+            .unwrap_or_default()
+    }
+    fn remove_older_than(&mut self, cutoff: UtcTime) -> usize {
+        remove_entries_older_than(&mut self.contents, cutoff)
+    }
+    fn touch(&mut self, url: &str) {
+        touch_latest_entry(&mut self.contents, url);
+    }
+    fn is_modified(&self) -> bool {
+        // There's nothing to save
+        false
+    }
+    fn save(&self) -> Result<()> {
+        Ok(())
+    }
+    fn size_bytes(&self) -> Result<u64> {
+        Ok(self
+            .contents
+            .values()
+            .flatten()
+            .map(|entry| entry.text.len() as u64)
+            .sum())
+    }
+    fn clear(&mut self) {
+        self.contents.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeDelta, Utc};
+
+    #[test]
+    fn test_memory_cache() -> Result<()> {
+        let mut cache = MemoryCache::default();
+        assert!(cache.get("url").is_none());
+        cache.insert("url".into(), "0".into());
+        cache.insert("url".into(), "12".into());
+        assert_eq!(cache.get("url").unwrap().text, "12");
+        assert_eq!(cache.size_bytes()?, 3);
+        assert_eq!(cache.remove("url").len(), 2);
+        assert!(cache.get_all_urls().is_empty());
+
+        cache.insert("url".into(), "0".into());
+        assert_eq!(
+            cache.remove_older_than(Utc::now() - TimeDelta::hours(1)),
+            0
+        );
+        assert_eq!(
+            cache.remove_older_than(Utc::now() + TimeDelta::hours(1)),
+            1
+        );
+        assert!(cache.get_all_urls().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_evict_least_recently_used() {
+        let mut cache = MemoryCache::default();
+        let now = Utc::now();
+        for (url, hours_ago) in [("old", 3), ("reused", 2), ("new", 1)] {
+            cache.insert_entry(
+                url.into(),
+                CacheEntry {
+                    created: now - TimeDelta::hours(hours_ago),
+                    ..CacheEntry::new("1234".into())
+                },
+            );
+        }
+        // Reusing an entry makes it the most recently used
+        cache.touch("reused");
+
+        assert!(
+            cache
+                .evict_least_recently_used(12)
+                .is_empty()
+        );
+        assert_eq!(cache.evict_least_recently_used(11), ["old"]);
+        assert_eq!(cache.evict_least_recently_used(4), ["new"]);
+        assert_eq!(cache.get_all_urls(), ["reused"]);
+        assert_eq!(cache.evict_least_recently_used(0), ["reused"]);
+    }
+}
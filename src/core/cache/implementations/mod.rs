@@ -1 +1,7 @@
 pub mod file_cache;
+pub mod memory_cache;
+pub mod noop_cache;
+#[cfg(feature = "redis")]
+pub mod redis_cache;
+#[cfg(feature = "sqlite")]
+pub mod sqlite_cache;
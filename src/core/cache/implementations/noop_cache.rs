@@ -0,0 +1,53 @@
+use crate::{
+    Result,
+    cache::{Cache, models::CacheEntry},
+    utils::UtcTime,
+};
+
+/// Cache which keeps nothing, so every package is fetched from the carrier.
+/// For applications that embed packtrack and must not keep any state.
+#[derive(Default)]
+pub struct NoopCache;
+
+impl Cache for NoopCache {
+    fn get_all_urls(&self) -> Vec<String> {
+        vec![]
+    }
+    fn get_all(&self, _url: &str) -> Vec<&CacheEntry> {
+        vec![]
+    }
+    fn insert_entry(&mut self, _url: String, _entry: CacheEntry) {}
+    fn remove(&mut self, _url: &str) -> Vec<CacheEntry> {
+        vec![]
+    }
+    fn remove_older_than(&mut self, _cutoff: UtcTime) -> usize {
+        0
+    }
+    fn touch(&mut self, _url: &str) {}
+    fn is_modified(&self) -> bool {
+        false
+    }
+    fn save(&self) -> Result<()> {
+        Ok(())
+    }
+    fn size_bytes(&self) -> Result<u64> {
+        Ok(0)
+    }
+    fn clear(&mut self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_noop_cache() -> Result<()> {
+        let mut cache = NoopCache;
+        cache.insert("url".into(), "text".into());
+        assert!(cache.get("url").is_none());
+        assert!(cache.get_all_urls().is_empty());
+        assert!(!cache.is_modified());
+        assert_eq!(cache.size_bytes()?, 0);
+        Ok(())
+    }
+}
@@ -0,0 +1,159 @@
+use crate::{
+    Result,
+    cache::{
+        Cache,
+        models::CacheEntry,
+        utils::{
+            remove_entries_older_than, touch_latest_entry,
+            urls_with_entries_older_than,
+        },
+    },
+    utils::UtcTime,
+};
+use redis::{Client, Commands};
+use std::collections::{HashMap, HashSet};
+
+/// The Redis hash with the entries: a field per URL, with a JSON list of its
+/// entries as the value. Namespaced caches add ":<namespace>".
+const KEY: &str = "packtrack:cache";
+
+/// Cache which keeps its entries in a Redis server, so that several machines
+/// can share it. Like the `FileCache`, the entries are loaded into memory
+/// when it's opened. Only the URLs that changed are written back when it's
+/// saved, so that it doesn't overwrite what other machines saved meanwhile.
+pub struct RedisCache {
+    client:   Client,
+    key:      String,
+    contents: HashMap<String, Vec<CacheEntry>>,
+    /// The URLs whose entries changed since the cache was opened
+    changed:  HashSet<String>,
+}
+
+fn redis_error(err: redis::RedisError) -> crate::Error {
+    format!("Redis error: {err}").into()
+}
+
+impl RedisCache {
+    /// Connect to the server at the URL, e.g. "redis://localhost:6379"
+    pub fn new(url: &str) -> Result<Self> {
+        Self::with_key(url, KEY.into())
+    }
+
+    /// Like `new`, with the entries kept apart from those of the other
+    /// namespaces, e.g. for each user of a multi-user server
+    pub fn namespaced(url: &str, namespace: &str) -> Result<Self> {
+        Self::with_key(url, format!("{KEY}:{namespace}"))
+    }
+
+    fn with_key(url: &str, key: String) -> Result<Self> {
+        let client = Client::open(url).map_err(redis_error)?;
+        let mut connection = client
+            .get_connection()
+            .map_err(redis_error)?;
+        let fields: HashMap<String, String> = connection
+            .hgetall(&key)
+            .map_err(redis_error)?;
+        let mut contents = HashMap::new();
+        for (url, json) in fields {
+            match serde_json::from_str(&json) {
+                Ok(entries) => {
+                    contents.insert(url, entries);
+                }
+                Err(err) => {
+                    log::warn!("Skipping cache entries for {url}: {err}")
+                }
+            }
+        }
+        Ok(Self {
+            client,
+            key,
+            contents,
+            changed: HashSet::new(),
+        })
+    }
+}
+
+impl Cache for RedisCache {
+    fn get_all_urls(&self) -> Vec<String> {
+        self.contents.keys().cloned().collect()
+    }
+    fn get_all(&self, url: &str) -> Vec<&CacheEntry> {
+        self.contents
+            .get(url)
+            .map(|v| v.iter().collect())
+            .unwrap_or_default()
+    }
+    fn insert_entry(&mut self, url: String, entry: CacheEntry) {
+        self.changed.insert(url.clone());
+        self.contents
+            .entry(url)
+            .or_default()
+            .push(entry);
+    }
+    fn remove(&mut self, url: &str) -> Vec<CacheEntry> {
+        let removed = self
+            .contents
+            .remove(url)
+            .unwrap_or_default();
+        if !removed.is_empty() {
+            self.changed.insert(url.to_string());
+        }
+        removed
+    }
+    fn remove_older_than(&mut self, cutoff: UtcTime) -> usize {
+        self.changed
+            .extend(urls_with_entries_older_than(&self.contents, cutoff));
+        remove_entries_older_than(&mut self.contents, cutoff)
+    }
+    fn touch(&mut self, url: &str) {
+        if touch_latest_entry(&mut self.contents, url) {
+            self.changed.insert(url.to_string());
+        }
+    }
+    fn is_modified(&self) -> bool {
+        !self.changed.is_empty()
+    }
+    /// Write the fields of the URLs that changed, in one transaction. The
+    /// other fields are left alone, because another machine may have saved
+    /// them since this cache was opened.
+    fn save(&self) -> Result<()> {
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        for url in &self.changed {
+            match self.contents.get(url) {
+                Some(entries) => {
+                    pipe.hset(&self.key, url, serde_json::to_string(entries)?)
+                        .ignore();
+                }
+                None => {
+                    pipe.hdel(&self.key, url).ignore();
+                }
+            }
+        }
+        let mut connection = self
+            .client
+            .get_connection()
+            .map_err(redis_error)?;
+        pipe.query::<()>(&mut connection)
+            .map_err(redis_error)?;
+        log::info!("Saved cache to Redis");
+        Ok(())
+    }
+    /// The size of the entries' JSON, which is roughly what Redis stores
+    fn size_bytes(&self) -> Result<u64> {
+        let mut size = 0;
+        for entries in self.contents.values() {
+            size += serde_json::to_string(entries)?.len() as u64;
+        }
+        Ok(size)
+    }
+    /// Only removes the URLs this cache knows about, so entries that another
+    /// machine saved since it was opened survive
+    fn clear(&mut self) {
+        self.changed.extend(
+            self.contents
+                .drain()
+                .map(|(url, _)| url),
+        );
+    }
+}
@@ -0,0 +1,329 @@
+use crate::{
+    Result,
+    cache::{
+        Cache,
+        models::CacheEntry,
+        utils::{
+            remove_entries_older_than, touch_latest_entry,
+            urls_with_entries_older_than,
+        },
+    },
+    utils::UtcTime,
+};
+use rusqlite::{Connection, params};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS cache_entries (
+        url          TEXT NOT NULL,
+        created      TEXT NOT NULL,
+        text         TEXT NOT NULL,
+        translations TEXT NOT NULL DEFAULT '{}',
+        last_used    TEXT,
+        failure      TEXT,
+        version      INTEGER NOT NULL DEFAULT 1,
+        tracker      TEXT
+    );
+    CREATE INDEX IF NOT EXISTS cache_entries_url ON cache_entries(url);
+";
+
+/// Columns added after the table was first created, which databases created
+/// by older versions of packtrack don't have yet
+const ADDED_COLUMNS: [(&str, &str); 4] = [
+    ("last_used", "TEXT"),
+    ("failure", "TEXT"),
+    ("version", "INTEGER NOT NULL DEFAULT 1"),
+    ("tracker", "TEXT"),
+];
+
+/// Add the columns that are missing from a database created by an older
+/// version of packtrack
+fn migrate(db: &Connection) -> rusqlite::Result<()> {
+    let existing: Vec<String> = db
+        .prepare("SELECT name FROM pragma_table_info('cache_entries')")?
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    for (column, definition) in ADDED_COLUMNS {
+        if !existing
+            .iter()
+            .any(|name| name == column)
+        {
+            db.execute_batch(&format!(
+                "ALTER TABLE cache_entries ADD COLUMN {column} {definition}"
+            ))?;
+        }
+    }
+    Ok(())
+}
+
+/// Cache which keeps its entries in an SQLite database. Like the `FileCache`,
+/// the entries are loaded into memory when it's opened. Only the URLs that
+/// changed are written back when it's saved, so that it doesn't overwrite what
+/// other processes saved meanwhile.
+pub struct SqliteCache {
+    path:     PathBuf,
+    contents: HashMap<String, Vec<CacheEntry>>,
+    /// The URLs whose entries changed since the cache was opened
+    changed:  HashSet<String>,
+}
+
+fn sql_error(path: &Path) -> impl Fn(rusqlite::Error) -> crate::Error {
+    move |err| format!("SQLite error in {}: {err}", path.display()).into()
+}
+
+impl SqliteCache {
+    /// Open the database, creating it if it doesn't exist yet
+    pub fn new(path: &Path) -> Result<Self> {
+        let sql_error = sql_error(path);
+        let db = Connection::open(path).map_err(&sql_error)?;
+        db.execute_batch(SCHEMA)
+            .map_err(&sql_error)?;
+        migrate(&db).map_err(&sql_error)?;
+        let mut statement = db
+            .prepare(
+                "SELECT url, created, text, translations, last_used, failure, \
+                 version, tracker FROM cache_entries ORDER BY created",
+            )
+            .map_err(&sql_error)?;
+        let rows = statement
+            .query_map([], |row| {
+                let translations: String = row.get(3)?;
+                let failure: Option<String> = row.get(5)?;
+                Ok((
+                    row.get::<_, String>(0)?,
+                    CacheEntry {
+                        created:      row.get(1)?,
+                        text:         row.get(2)?,
+                        version:      row.get(6)?,
+                        tracker:      row.get(7)?,
+                        translations: serde_json::from_str(&translations)
+                            .unwrap_or_default(),
+                        last_used:    row.get(4)?,
+                        failure:      failure
+                            .and_then(|json| serde_json::from_str(&json).ok()),
+                    },
+                ))
+            })
+            .map_err(&sql_error)?;
+        let mut contents: HashMap<String, Vec<CacheEntry>> = HashMap::new();
+        for row in rows {
+            let (url, entry) = row.map_err(&sql_error)?;
+            contents
+                .entry(url)
+                .or_default()
+                .push(entry);
+        }
+        Ok(Self {
+            path: path.to_owned(),
+            contents,
+            changed: HashSet::new(),
+        })
+    }
+}
+
+impl Cache for SqliteCache {
+    fn get_all_urls(&self) -> Vec<String> {
+        self.contents.keys().cloned().collect()
+    }
+    fn get_all(&self, url: &str) -> Vec<&CacheEntry> {
+        self.contents
+            .get(url)
+            .map(|v| v.iter().collect())
+            .unwrap_or_default()
+    }
+    fn insert_entry(&mut self, url: String, entry: CacheEntry) {
+        self.changed.insert(url.clone());
+        self.contents
+            .entry(url)
+            .or_default()
+            .push(entry);
+    }
+    fn remove(&mut self, url: &str) -> Vec<CacheEntry> {
+        let removed = self
+            .contents
+            .remove(url)
+            .unwrap_or_default();
+        if !removed.is_empty() {
+            self.changed.insert(url.to_string());
+        }
+        removed
+    }
+    fn remove_older_than(&mut self, cutoff: UtcTime) -> usize {
+        self.changed
+            .extend(urls_with_entries_older_than(&self.contents, cutoff));
+        remove_entries_older_than(&mut self.contents, cutoff)
+    }
+    fn touch(&mut self, url: &str) {
+        if touch_latest_entry(&mut self.contents, url) {
+            self.changed.insert(url.to_string());
+        }
+    }
+    fn is_modified(&self) -> bool {
+        !self.changed.is_empty()
+    }
+    /// Replace the rows of the URLs that changed with the entries in memory,
+    /// in one transaction. The other rows are left alone, because another
+    /// process may have saved them since this cache was opened.
+    fn save(&self) -> Result<()> {
+        let sql_error = sql_error(&self.path);
+        let mut db = Connection::open(&self.path).map_err(&sql_error)?;
+        let tx = db.transaction().map_err(&sql_error)?;
+        {
+            let mut delete = tx
+                .prepare("DELETE FROM cache_entries WHERE url = ?1")
+                .map_err(&sql_error)?;
+            let mut insert = tx
+                .prepare(
+                    "INSERT INTO cache_entries \
+                     (url, created, text, translations, last_used, failure, \
+                     version, tracker) \
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                )
+                .map_err(&sql_error)?;
+            for url in &self.changed {
+                delete
+                    .execute(params![url])
+                    .map_err(&sql_error)?;
+                let entries = self
+                    .contents
+                    .get(url)
+                    .into_iter()
+                    .flatten();
+                for entry in entries {
+                    let translations: &BTreeMap<_, _> = &entry.translations;
+                    insert
+                        .execute(params![
+                            url,
+                            entry.created,
+                            entry.text,
+                            serde_json::to_string(translations)?,
+                            entry.last_used,
+                            entry
+                                .failure
+                                .as_ref()
+                                .map(serde_json::to_string)
+                                .transpose()?,
+                            entry.version,
+                            entry.tracker,
+                        ])
+                        .map_err(&sql_error)?;
+                }
+            }
+        }
+        tx.commit().map_err(&sql_error)?;
+        log::info!("Saved cache to {}", self.path.display());
+        Ok(())
+    }
+    fn size_bytes(&self) -> Result<u64> {
+        match self.path.exists() {
+            true => Ok(std::fs::metadata(&self.path)?.len()),
+            false => Ok(0),
+        }
+    }
+    /// Only removes the URLs this cache knows about, so entries that another
+    /// process saved since it was opened survive
+    fn clear(&mut self) {
+        self.changed.extend(
+            self.contents
+                .drain()
+                .map(|(url, _)| url),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sqlite_cache() -> Result<()> {
+        let path = std::env::temp_dir().join(format!(
+            "packtrack-cache-test-{}.sqlite",
+            std::process::id()
+        ));
+        let mut cache = SqliteCache::new(&path)?;
+        assert!(cache.get("url").is_none());
+        cache.insert("url".into(), "0".into());
+        let mut entry = CacheEntry::new("1".into());
+        entry
+            .translations
+            .insert("nl".into(), "een".into());
+        cache.insert_entry("url".into(), entry.clone());
+        assert!(cache.is_modified());
+        cache.save()?;
+
+        // The entries are still there when it's opened again
+        let mut cache = SqliteCache::new(&path)?;
+        assert_eq!(cache.get_all("url").len(), 2);
+        assert_eq!(cache.get("url"), Some(&entry));
+        assert!(!cache.is_modified());
+
+        cache.clear();
+        cache.save()?;
+        assert!(
+            SqliteCache::new(&path)?
+                .get_all_urls()
+                .is_empty()
+        );
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_keeps_entries_saved_by_others() -> Result<()> {
+        let path = std::env::temp_dir().join(format!(
+            "packtrack-cache-shared-test-{}.sqlite",
+            std::process::id()
+        ));
+        let mut first = SqliteCache::new(&path)?;
+        first.insert("a".into(), "0".into());
+        first.insert("b".into(), "0".into());
+        first.save()?;
+
+        // Two processes open the cache at the same time
+        let mut first = SqliteCache::new(&path)?;
+        let mut second = SqliteCache::new(&path)?;
+        first.insert("a".into(), "1".into());
+        first.remove("b");
+        second.insert("c".into(), "0".into());
+        first.save()?;
+        second.save()?;
+
+        // Neither overwrote the other's changes
+        let cache = SqliteCache::new(&path)?;
+        assert_eq!(cache.get_all("a").len(), 2);
+        assert!(cache.get_all("b").is_empty());
+        assert_eq!(cache.get_all("c").len(), 1);
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate() -> Result<()> {
+        let path = std::env::temp_dir().join(format!(
+            "packtrack-cache-migrate-test-{}.sqlite",
+            std::process::id()
+        ));
+        let db = Connection::open(&path).map_err(sql_error(&path))?;
+        db.execute_batch(
+            "CREATE TABLE cache_entries (
+                url          TEXT NOT NULL,
+                created      TEXT NOT NULL,
+                text         TEXT NOT NULL,
+                translations TEXT NOT NULL DEFAULT '{}'
+            );
+            INSERT INTO cache_entries (url, created, text)
+            VALUES ('url', '2025-05-17T08:01:05Z', 'a');",
+        )
+        .map_err(sql_error(&path))?;
+        drop(db);
+
+        let cache = SqliteCache::new(&path)?;
+        let entry = cache.get("url").unwrap();
+        assert_eq!(entry.version, 1);
+        assert_eq!(entry.tracker, None);
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+}
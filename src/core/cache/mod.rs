@@ -1,8 +1,17 @@
+mod backend;
 mod implementations;
 mod models;
 mod traits;
 mod utils;
 
+pub use backend::CacheBackend;
 pub use implementations::file_cache::FileCache;
+pub use implementations::memory_cache::MemoryCache;
+pub use implementations::noop_cache::NoopCache;
+#[cfg(feature = "redis")]
+pub use implementations::redis_cache::RedisCache;
+#[cfg(feature = "sqlite")]
+pub use implementations::sqlite_cache::SqliteCache;
+pub use models::{CACHE_ENTRY_VERSION, CacheEntry, Failure};
 pub use traits::Cache;
 pub use utils::get_cache_dir;
@@ -1,14 +1,92 @@
 use crate::utils::UtcTime;
 use chrono::{TimeDelta, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The format of the cache entries written by this version of packtrack.
+/// Bump it when the format changes, so that older entries can be recognised
+/// and migrated.
+pub const CACHE_ENTRY_VERSION: u32 = 2;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct CacheEntry {
-    pub text:    String,
-    pub created: UtcTime,
+    pub text:         String,
+    pub created:      UtcTime,
+    /// The format the entry was written in (see `CACHE_ENTRY_VERSION`).
+    /// Entries from before it was recorded are version 1.
+    #[serde(default = "first_version")]
+    pub version:      u32,
+    /// The name of the tracker that fetched the response, e.g. "postnl"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tracker:      Option<String>,
+    /// The same response in other languages, by language, for trackers that
+    /// fetch several (see `Settings.languages`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub translations: BTreeMap<String, String>,
+    /// When the entry was last reused from the cache. Used to evict the least
+    /// recently used URLs when the cache gets too big (`cache_max_bytes`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used:    Option<UtcTime>,
+    /// Set if this entry records a failed lookup instead of a response (see
+    /// `Failure`). The text is empty then.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure:      Option<Failure>,
+}
+
+fn first_version() -> u32 {
+    1
+}
+
+/// A lookup that failed in a way that retrying right away won't fix, e.g. a
+/// barcode the carrier doesn't know (yet). Cached so that the carrier isn't
+/// asked again on every run.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Failure {
+    /// e.g. "HTTP 404 from https://..."
+    pub error:       String,
+    /// How many times in a row the lookup failed
+    pub attempts:    u32,
+    /// Don't ask the carrier again before this
+    pub retry_after: UtcTime,
 }
 impl CacheEntry {
+    /// An entry created now
+    pub fn new(text: String) -> Self {
+        Self {
+            text,
+            created: Utc::now(),
+            version: CACHE_ENTRY_VERSION,
+            tracker: None,
+            translations: BTreeMap::new(),
+            last_used: None,
+            failure: None,
+        }
+    }
+
+    /// An entry recording a failed lookup, created now
+    pub fn failed(failure: Failure) -> Self {
+        Self {
+            failure: Some(failure),
+            ..Self::new(String::new())
+        }
+    }
+
     pub fn age(&self) -> TimeDelta {
         Utc::now() - self.created
     }
+
+    /// When the entry was last reused, or created if it never was
+    pub fn last_used(&self) -> UtcTime {
+        self.last_used.unwrap_or(self.created)
+    }
+
+    /// The size of the responses it holds, in bytes
+    pub fn size_bytes(&self) -> u64 {
+        let translations: usize = self
+            .translations
+            .values()
+            .map(String::len)
+            .sum();
+        (self.text.len() + translations) as u64
+    }
 }
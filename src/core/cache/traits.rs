@@ -1,10 +1,12 @@
 use crate::Result;
 use crate::cache::models::CacheEntry;
 use crate::cache::utils::log_hit;
+use crate::utils::UtcTime;
 use chrono::Utc;
 use std::time::Duration;
 
-pub trait Cache {
+/// Send, so that tracking can run in a spawned task, e.g. in `packtrack serve`
+pub trait Cache: Send {
     /// Get all the URLs in the cache
     fn get_all_urls(&self) -> Vec<String>;
 
@@ -12,10 +14,11 @@ pub trait Cache {
     fn get_all(&self, url: &str) -> Vec<&CacheEntry>;
 
     /// Get the latest cached response.text for the given URL.
-    /// Ignores the age of the entry.
+    /// Ignores the age of the entry, and failed lookups.
     fn get(&self, url: &str) -> Option<&CacheEntry> {
         self.get_all(url)
             .into_iter()
+            .filter(|entry| entry.failure.is_none())
             .max_by(|a, b| a.created.cmp(&b.created))
             .inspect(|entry| log_hit(url, entry))
     }
@@ -31,13 +34,35 @@ pub trait Cache {
         self.get_all(url)
             .into_iter()
             .filter(|entry| entry.created >= min_created)
+            .filter(|entry| entry.failure.is_none())
             .max_by(|a, b| a.created.cmp(&b.created))
             .inspect(|entry| log_hit(url, entry))
     }
 
+    /// The newest entry for the URL, if it records a failed lookup. A failure
+    /// followed by a successful one doesn't count anymore.
+    fn get_failure(&self, url: &str) -> Option<&CacheEntry> {
+        self.get_all(url)
+            .into_iter()
+            .max_by(|a, b| a.created.cmp(&b.created))
+            .filter(|entry| entry.failure.is_some())
+    }
+
     /// Insert a cached response.text for the given URL.
     /// `mut` because the implementation must store its state in memory.
-    fn insert(&mut self, url: String, text: String);
+    fn insert(&mut self, url: String, text: String) {
+        self.insert_entry(url, CacheEntry::new(text));
+    }
+
+    /// Insert a cache entry for the given URL
+    fn insert_entry(&mut self, url: String, entry: CacheEntry);
+
+    /// Record that the latest entry for the URL was reused, for
+    /// `evict_least_recently_used`
+    fn touch(&mut self, url: &str);
+
+    /// True if there are changes that haven't been saved yet
+    fn is_modified(&self) -> bool;
 
     /// Save the cache to preserve it between runs
     /// `Result` so the implementation can do IO.
@@ -50,9 +75,13 @@ pub trait Cache {
     /// entries.
     fn remove(&mut self, url: &str) -> Vec<CacheEntry>;
 
+    /// Remove the entries created before `cutoff`, and any URLs left without
+    /// entries. Return the number of entries removed.
+    fn remove_older_than(&mut self, cutoff: UtcTime) -> usize;
+
     /// Remove any entries that are not associated with the given list of URLs.
     /// Return the URLs that were removed.
-    fn prune(&mut self, keep: &Vec<String>) -> Vec<String> {
+    fn prune(&mut self, keep: &[String]) -> Vec<String> {
         // Make a list of the URLs to remove
         let remove: Vec<String> = self
             .get_all_urls()
@@ -68,6 +97,135 @@ pub trait Cache {
         remove
     }
 
+    /// Keep only the entries for which `keep` returns true, and remove any
+    /// URLs left without entries. Return the number of entries removed.
+    fn retain(
+        &mut self,
+        keep: &mut dyn FnMut(&str, &CacheEntry) -> bool,
+    ) -> usize {
+        let mut removed = 0;
+        for url in self.get_all_urls() {
+            let drop = self
+                .get_all(&url)
+                .into_iter()
+                .filter(|entry| !keep(&url, entry))
+                .count();
+            if drop == 0 {
+                continue;
+            }
+            // Put back the ones to keep, in the same order
+            for entry in self.remove(&url) {
+                match keep(&url, &entry) {
+                    true => self.insert_entry(url.clone(), entry),
+                    false => removed += 1,
+                }
+            }
+        }
+        removed
+    }
+
+    /// Remove the least recently used URLs (by when their entries were last
+    /// created or reused) until the entries take up at most `max_bytes`.
+    /// Return the URLs that were removed.
+    fn evict_least_recently_used(&mut self, max_bytes: u64) -> Vec<String> {
+        let mut urls: Vec<(UtcTime, u64, String)> = self
+            .get_all_urls()
+            .into_iter()
+            .map(|url| {
+                let entries = self.get_all(&url);
+                let last_used = entries
+                    .iter()
+                    .map(|entry| entry.last_used())
+                    .max()
+                    .unwrap_or(UtcTime::MIN_UTC);
+                let size = entries
+                    .iter()
+                    .map(|entry| entry.size_bytes())
+                    .sum();
+                (last_used, size, url)
+            })
+            .collect();
+        let mut total: u64 = urls
+            .iter()
+            .map(|(_, size, _)| size)
+            .sum();
+        // Least recently used first
+        urls.sort();
+        let mut evicted = vec![];
+        for (_, size, url) in urls {
+            if total <= max_bytes {
+                break;
+            }
+            self.remove(&url);
+            total -= size;
+            evicted.push(url);
+        }
+        evicted
+    }
+
     /// Remove all entries from the cache
     fn clear(&mut self);
 }
+
+/// So that a cache chosen at runtime (e.g. by `CacheBackend::open`) can be
+/// passed to anything that takes an `impl Cache`
+impl<C: Cache + ?Sized> Cache for Box<C> {
+    fn get_all_urls(&self) -> Vec<String> {
+        (**self).get_all_urls()
+    }
+    fn get_all(&self, url: &str) -> Vec<&CacheEntry> {
+        (**self).get_all(url)
+    }
+    fn get(&self, url: &str) -> Option<&CacheEntry> {
+        (**self).get(url)
+    }
+    fn get_younger_than(
+        &self,
+        url: &str,
+        max_age: Duration,
+    ) -> Option<&CacheEntry> {
+        (**self).get_younger_than(url, max_age)
+    }
+    fn get_failure(&self, url: &str) -> Option<&CacheEntry> {
+        (**self).get_failure(url)
+    }
+    fn insert(&mut self, url: String, text: String) {
+        (**self).insert(url, text)
+    }
+    fn insert_entry(&mut self, url: String, entry: CacheEntry) {
+        (**self).insert_entry(url, entry)
+    }
+    fn touch(&mut self, url: &str) {
+        (**self).touch(url)
+    }
+    fn is_modified(&self) -> bool {
+        (**self).is_modified()
+    }
+    fn save(&self) -> Result<()> {
+        (**self).save()
+    }
+    fn size_bytes(&self) -> Result<u64> {
+        (**self).size_bytes()
+    }
+    fn remove(&mut self, url: &str) -> Vec<CacheEntry> {
+        (**self).remove(url)
+    }
+    fn remove_older_than(&mut self, cutoff: UtcTime) -> usize {
+        (**self).remove_older_than(cutoff)
+    }
+    fn prune(&mut self, keep: &[String]) -> Vec<String> {
+        (**self).prune(keep)
+    }
+    fn retain(
+        &mut self,
+        keep: &mut dyn FnMut(&str, &CacheEntry) -> bool,
+    ) -> usize {
+        (**self).retain(keep)
+    }
+    fn evict_least_recently_used(&mut self, max_bytes: u64) -> Vec<String> {
+        (**self).evict_least_recently_used(max_bytes)
+    }
+    fn clear(&mut self) {
+        (**self).clear()
+    }
+}
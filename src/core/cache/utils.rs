@@ -1,5 +1,8 @@
 use crate::cache::models::CacheEntry;
+use crate::utils::UtcTime;
 use crate::{Result, utils};
+use chrono::Utc;
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 pub fn log_hit(url: &str, entry: &CacheEntry) {
@@ -15,3 +18,60 @@ pub fn get_cache_dir() -> Result<PathBuf> {
     let cache_dir = dirs.cache_dir();
     Ok(cache_dir.to_owned())
 }
+
+/// Shared by the `Cache` implementations that keep their entries in a map.
+/// The URLs that `remove_entries_older_than` would change.
+#[cfg(any(feature = "sqlite", feature = "redis"))]
+pub(crate) fn urls_with_entries_older_than(
+    contents: &HashMap<String, Vec<CacheEntry>>,
+    cutoff: UtcTime,
+) -> Vec<String> {
+    contents
+        .iter()
+        .filter(|(_, entries)| {
+            entries
+                .iter()
+                .any(|entry| entry.created < cutoff)
+        })
+        .map(|(url, _)| url.clone())
+        .collect()
+}
+
+/// Shared by the `Cache` implementations that keep their entries in a map
+pub(crate) fn remove_entries_older_than(
+    contents: &mut HashMap<String, Vec<CacheEntry>>,
+    cutoff: UtcTime,
+) -> usize {
+    let mut removed = 0;
+    contents.retain(|_, entries| {
+        let before = entries.len();
+        entries.retain(|entry| entry.created >= cutoff);
+        removed += before - entries.len();
+        !entries.is_empty()
+    });
+    removed
+}
+
+/// Shared by the `Cache` implementations that keep their entries in a map.
+/// Mark the latest response for the URL as used now. Return false if there
+/// are none for it.
+pub(crate) fn touch_latest_entry(
+    contents: &mut HashMap<String, Vec<CacheEntry>>,
+    url: &str,
+) -> bool {
+    let latest = contents
+        .get_mut(url)
+        .and_then(|entries| {
+            entries
+                .iter_mut()
+                .filter(|e| e.failure.is_none())
+                .max_by_key(|e| e.created)
+        });
+    match latest {
+        Some(entry) => {
+            entry.last_used = Some(Utc::now());
+            true
+        }
+        None => false,
+    }
+}
@@ -1,14 +1,37 @@
-use crate::cache::Cache;
-use crate::tracker::{Package, Tracker, TrackerContext};
+use crate::cache::{Cache, CacheEntry, Failure};
+use crate::tracker::{Package, RawResponse, Tracker, TrackerContext};
 use crate::{Error, Result};
+use chrono::{Local, TimeDelta, Utc};
+use std::collections::BTreeMap;
 use tokio::sync::Mutex;
 
+/// How long to wait before asking the carrier about a URL again after a
+/// failed lookup. Doubles with every failure in a row, up to
+/// `MAX_RETRY_INTERVAL`.
+const FIRST_RETRY_INTERVAL: TimeDelta = TimeDelta::minutes(5);
+const MAX_RETRY_INTERVAL: TimeDelta = TimeDelta::days(1);
+
+fn retry_interval(attempts: u32) -> TimeDelta {
+    let factor = 2_i32.saturating_pow(attempts.saturating_sub(1).min(16));
+    (FIRST_RETRY_INTERVAL * factor).min(MAX_RETRY_INTERVAL)
+}
+
+/// Client errors mean the carrier doesn't know the barcode (yet), which
+/// retrying right away won't fix. Timeouts and rate limiting are temporary.
+fn is_lasting_failure(status: u16) -> bool {
+    (400..500).contains(&status) && !matches!(status, 408 | 429)
+}
+
 /// Composed type with pluggable tracker + cache handlers. Orchestrates:
 /// - Fetching a raw value from either the Tracker or the Cache
 /// - Parsing the raw value with Tracker
 pub struct CachedTracker<'a> {
-    pub tracker: Box<dyn Tracker>,
-    pub cache:   &'a Mutex<dyn Cache>,
+    pub tracker:   &'a dyn Tracker,
+    pub cache:     &'a Mutex<dyn Cache>,
+    /// Other languages to fetch the events in, if the tracker supports them.
+    /// They are cached with the response, so the language can be switched
+    /// without fetching again.
+    pub languages: &'a [String],
 }
 impl<'a> CachedTracker<'a> {
     pub async fn track(
@@ -19,6 +42,9 @@ impl<'a> CachedTracker<'a> {
         ctx: &'a TrackerContext<'_>,
     ) -> Result<Package> {
         if use_cache {
+            if let Some(err) = self.known_failure(url).await {
+                return Err(err);
+            }
             match self
                 .get_cached(url, cache_seconds)
                 .await
@@ -35,41 +61,279 @@ impl<'a> CachedTracker<'a> {
         self.get_fresh(url, ctx).await
     }
 
+    /// Track several URLs, fetching the ones that aren't cached in batches
+    /// of the tracker's `batch_size`. Returns a result per URL, in the same
+    /// order. URLs that a batch request didn't work for are fetched on their
+    /// own, so that e.g. the retry without the postcode still happens.
+    pub async fn track_batch(
+        &mut self,
+        urls: &[&str],
+        cache_seconds: usize,
+        use_cache: bool,
+        ctx: &'a TrackerContext<'_>,
+    ) -> Vec<Result<Package>> {
+        let mut results: Vec<Option<Result<Package>>> =
+            urls.iter().map(|_| None).collect();
+        let mut fresh = vec![];
+        for (i, url) in urls.iter().enumerate() {
+            if use_cache {
+                if let Some(err) = self.known_failure(url).await {
+                    results[i] = Some(Err(err));
+                    continue;
+                }
+                match self
+                    .get_cached(url, cache_seconds)
+                    .await
+                {
+                    Ok(Some(package)) => {
+                        results[i] = Some(Ok(package));
+                        continue;
+                    }
+                    Err(err) => log::warn!(
+                        "Error loading from cache: {err}. Getting a fresh value."
+                    ),
+                    Ok(None) => log::info!(
+                        "No cache entry found for {url}. Getting a fresh value."
+                    ),
+                }
+            }
+            fresh.push(i);
+        }
+
+        for chunk in fresh.chunks(self.tracker.batch_size().max(1)) {
+            let chunk_urls: Vec<&str> =
+                chunk.iter().map(|&i| urls[i]).collect();
+            let responses = self
+                .get_raw_batch(&chunk_urls, ctx)
+                .await;
+            let mut translations = self
+                .get_batch_translations(&chunk_urls, ctx)
+                .await;
+            for (j, &i) in chunk.iter().enumerate() {
+                let url = urls[i];
+                results[i] = Some(match &responses[j] {
+                    Some(raw) if raw.is_success() => {
+                        let entry = CacheEntry {
+                            tracker: Some(self.tracker.name().into()),
+                            translations: std::mem::take(&mut translations[j]),
+                            ..CacheEntry::new(raw.body.clone())
+                        };
+                        self.cache
+                            .lock()
+                            .await
+                            .insert_entry(url.to_owned(), entry.clone());
+                        self.parse(&entry)
+                    }
+                    _ => self.get_fresh(url, ctx).await,
+                });
+            }
+        }
+        results
+            .into_iter()
+            .map(|result| result.expect("every URL has a result"))
+            .collect()
+    }
+
+    /// One response per URL, or None where the batch request failed
+    async fn get_raw_batch(
+        &self,
+        urls: &[&str],
+        ctx: &TrackerContext<'_>,
+    ) -> Vec<Option<RawResponse>> {
+        match self
+            .tracker
+            .get_raw_batch(urls, ctx)
+            .await
+        {
+            Ok(responses) if responses.len() == urls.len() => responses
+                .into_iter()
+                .map(Some)
+                .collect(),
+            Ok(responses) => {
+                log::warn!(
+                    "Batch request for {} URLs returned {} responses",
+                    urls.len(),
+                    responses.len()
+                );
+                vec![None; urls.len()]
+            }
+            Err(err) => {
+                log::warn!(
+                    "Batch request for {} URLs failed: {err}",
+                    urls.len()
+                );
+                vec![None; urls.len()]
+            }
+        }
+    }
+
+    /// Like `get_translations`, for a batch of URLs
+    async fn get_batch_translations(
+        &self,
+        urls: &[&str],
+        ctx: &TrackerContext<'_>,
+    ) -> Vec<BTreeMap<String, String>> {
+        let mut translations = vec![BTreeMap::new(); urls.len()];
+        for language in self.other_languages(ctx) {
+            let ctx = TrackerContext {
+                language,
+                ..ctx.clone()
+            };
+            let responses = self.get_raw_batch(urls, &ctx).await;
+            for (translations, raw) in translations.iter_mut().zip(responses) {
+                if let Some(raw) = raw.filter(|raw| raw.is_success()) {
+                    translations.insert(language.clone(), raw.body);
+                }
+            }
+        }
+        translations
+    }
+
+    /// The languages to fetch translations in
+    fn other_languages<'b>(
+        &'b self,
+        ctx: &'b TrackerContext<'_>,
+    ) -> impl Iterator<Item = &'a String> + 'b {
+        let supported = self.tracker.languages();
+        self.languages
+            .iter()
+            .filter(move |language| {
+                *language != ctx.language && supported.contains(&&***language)
+            })
+    }
+
     async fn get_fresh(
         &mut self,
         url: &str,
         ctx: &'a TrackerContext<'_>,
     ) -> Result<Package> {
-        let text = match self.tracker.get_raw(url, ctx).await {
-            Ok(text) => text,
-            // If we receive a client error (4xx) it is sometimes because we
-            // tried to use the user's home postcode on a package for which the
-            // user is not the recipient (for example, a return). This results
-            // in a 404 from the carrier API because the postcodes don't match.
-            // In this case, we want to retry _without_ the user's default
-            // postcode, because then we will at least get a response.
-            Err(Error::Reqwest(err))
-                if err
-                    .status()
-                    .is_some_and(|s| s.is_client_error()) =>
-            {
-                log::warn!(
-                    "Bad response: {err}, trying again without default postcode..."
-                );
-                let mut ctx = ctx.clone();
-                ctx.recipient_postcode = None;
-                self.tracker.get_raw(url, &ctx).await?
+        let mut raw = self.tracker.get_raw(url, ctx).await?;
+        // If we receive a client error (4xx) it is sometimes because we tried
+        // to use the user's home postcode on a package for which the user is
+        // not the recipient (for example, a return). This results in a 404
+        // from the carrier API because the postcodes don't match. In this
+        // case, we want to retry _without_ the user's default postcode,
+        // because then we will at least get a response.
+        if raw.is_client_error() && ctx.recipient_postcode.is_some() {
+            log::warn!(
+                "Bad response: HTTP {} from {url}, trying again without default postcode...",
+                raw.status
+            );
+            let mut ctx = ctx.clone();
+            ctx.recipient_postcode = None;
+            raw = self.tracker.get_raw(url, &ctx).await?;
+        }
+        if !raw.is_success() {
+            let error = format!("HTTP {} from {url}", raw.status);
+            if is_lasting_failure(raw.status) {
+                self.record_failure(url, &error).await;
             }
-            Err(err) => return Err(err),
+            return Err(error.into());
+        }
+        let entry = CacheEntry {
+            tracker: Some(self.tracker.name().into()),
+            translations: self.get_translations(url, ctx).await,
+            ..CacheEntry::new(raw.body)
         };
         self.cache
             .lock()
             .await
-            .insert(url.to_owned(), text.clone());
-        let package = self.tracker.parse(text)?;
+            .insert_entry(url.to_owned(), entry.clone());
+        self.parse(&entry)
+    }
+
+    /// The responses in the other languages. These are optional, so failures
+    /// are only logged.
+    async fn get_translations(
+        &self,
+        url: &str,
+        ctx: &TrackerContext<'_>,
+    ) -> BTreeMap<String, String> {
+        let mut translations = BTreeMap::new();
+        for language in self.other_languages(ctx) {
+            let ctx = TrackerContext {
+                language,
+                ..ctx.clone()
+            };
+            match self.tracker.get_raw(url, &ctx).await {
+                Ok(raw) if raw.is_success() => {
+                    translations.insert(language.clone(), raw.body);
+                }
+                Ok(raw) => log::warn!(
+                    "HTTP {} fetching {url} in language {language}",
+                    raw.status
+                ),
+                Err(err) => log::warn!(
+                    "Couldn't fetch {url} in language {language}: {err}"
+                ),
+            }
+        }
+        translations
+    }
+
+    /// Parse the entry, with the translations of its events
+    fn parse(&self, entry: &CacheEntry) -> Result<Package> {
+        let mut package = self.tracker.parse(entry.text.clone())?;
+        for (language, text) in &entry.translations {
+            match self.tracker.parse(text.clone()) {
+                Ok(translated) => {
+                    package.add_translations(language, &translated)
+                }
+                Err(err) => log::warn!(
+                    "Couldn't parse the {language} translation: {err}"
+                ),
+            }
+        }
         Ok(package)
     }
 
+    /// The error of a failed lookup for the URL that shouldn't be retried yet
+    async fn known_failure(&self, url: &str) -> Option<Error> {
+        let cache = self.cache.lock().await;
+        let entry = cache.get_failure(url)?;
+        let failure = entry.failure.as_ref()?;
+        if Utc::now() >= failure.retry_after {
+            return None;
+        }
+        let format = |time: crate::utils::UtcTime| {
+            time.with_timezone(&Local)
+                .format("%a %d %b %H:%M")
+                .to_string()
+        };
+        log::info!("Not asking for {url} again before {}", failure.retry_after);
+        Some(
+            format!(
+                "{} (last failed at {}, not retrying before {})",
+                failure.error,
+                format(entry.created),
+                format(failure.retry_after)
+            )
+            .into(),
+        )
+    }
+
+    /// Cache a failed lookup, so that the carrier isn't asked again until the
+    /// retry interval has passed. Replaces the URL's previous failure.
+    async fn record_failure(&self, url: &str, error: &str) {
+        let mut cache = self.cache.lock().await;
+        let attempts = cache
+            .get_failure(url)
+            .and_then(|entry| entry.failure.as_ref())
+            .map_or(0, |failure| failure.attempts)
+            + 1;
+        cache.retain(&mut |u, entry| u != url || entry.failure.is_none());
+        let failure = Failure {
+            error: error.into(),
+            attempts,
+            retry_after: Utc::now() + retry_interval(attempts),
+        };
+        let entry = CacheEntry {
+            tracker: Some(self.tracker.name().into()),
+            ..CacheEntry::failed(failure)
+        };
+        cache.insert_entry(url.to_owned(), entry);
+    }
+
     async fn get_cached(
         &mut self,
         url: &str,
@@ -80,7 +344,7 @@ impl<'a> CachedTracker<'a> {
         drop(cache); // allows other async threads to use it
 
         if let Some(entry) = cached {
-            match self.tracker.parse(entry.text.clone()) {
+            match self.parse(&entry) {
                 Err(err) => {
                     return Err(
                         format!(
@@ -98,6 +362,7 @@ impl<'a> CachedTracker<'a> {
                             package.channel,
                             package.barcode,
                         );
+                        self.cache.lock().await.touch(url);
                         return Ok(Some(package));
                     }
 
@@ -108,6 +373,7 @@ impl<'a> CachedTracker<'a> {
                             package.channel,
                             package.barcode,
                         );
+                        self.cache.lock().await.touch(url);
                         return Ok(Some(package));
                     }
                 }
@@ -116,3 +382,168 @@ impl<'a> CachedTracker<'a> {
         Ok(None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::MemoryCache;
+    use crate::test_utils::fixtures::POSTNL_DELIVERED;
+    use crate::tracker::{Credentials, PostNLTracker};
+    use wiremock::matchers::query_param;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_translations() -> Result<()> {
+        let server = MockServer::start().await;
+        for (language, body) in [
+            ("en", POSTNL_DELIVERED.to_string()),
+            (
+                "nl",
+                POSTNL_DELIVERED.replace(
+                    "Shipment has been sorted",
+                    "Zending is gesorteerd",
+                ),
+            ),
+        ] {
+            Mock::given(query_param("language", language))
+                .respond_with(ResponseTemplate::new(200).set_body_string(body))
+                .mount(&server)
+                .await;
+        }
+        let origin = server.uri();
+        let credentials = Credentials::default();
+        let ctx = TrackerContext {
+            recipient_postcode: None,
+            language:           "en",
+            browser_path:       None,
+            api_origin:         Some(&origin),
+            credentials:        &credentials,
+        };
+        let cache = Mutex::new(MemoryCache::default());
+        let languages = vec!["en".to_string(), "nl".to_string(), "fr".into()];
+        let mut tracker = CachedTracker {
+            tracker:   &PostNLTracker,
+            cache:     &cache,
+            languages: &languages,
+        };
+        let url = "https://jouw.postnl.nl/track-and-trace/3SABCD1234567";
+
+        let package = tracker
+            .track(url, 0, false, &ctx)
+            .await?;
+        let sorted = |package: &Package| {
+            package
+                .events
+                .iter()
+                .find(|e| e.text == "Shipment has been sorted")
+                .cloned()
+        };
+        let event = sorted(&package).unwrap();
+        // Only the other languages the carrier supports are fetched
+        assert_eq!(
+            event.translations,
+            BTreeMap::from([("nl".into(), "Zending is gesorteerd".into())])
+        );
+
+        // The translations are cached
+        drop(server);
+        let mut package = tracker
+            .track(url, 60, true, &ctx)
+            .await?;
+        assert!(sorted(&package).is_some());
+        package.localize("nl");
+        assert!(sorted(&package).is_none());
+        assert!(
+            package
+                .events
+                .iter()
+                .any(|e| e.text == "Zending is gesorteerd")
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_failure_backoff() -> Result<()> {
+        let server = MockServer::start().await;
+        Mock::given(query_param("language", "en"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        let origin = server.uri();
+        let credentials = Credentials::default();
+        let ctx = TrackerContext {
+            recipient_postcode: None,
+            language:           "en",
+            browser_path:       None,
+            api_origin:         Some(&origin),
+            credentials:        &credentials,
+        };
+        let cache = Mutex::new(MemoryCache::default());
+        let mut tracker = CachedTracker {
+            tracker:   &PostNLTracker,
+            cache:     &cache,
+            languages: &[],
+        };
+        let url = "https://jouw.postnl.nl/track-and-trace/3SABCD1234567";
+        let failure = |cache: &MemoryCache| {
+            cache
+                .get_failure(url)
+                .and_then(|entry| entry.failure.clone())
+        };
+
+        let err = tracker
+            .track(url, 0, true, &ctx)
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), format!("HTTP 404 from {url}"));
+        assert_eq!(
+            failure(&*cache.lock().await)
+                .unwrap()
+                .attempts,
+            1
+        );
+
+        // The carrier isn't asked again until the retry interval has passed
+        let err = tracker
+            .track(url, 0, true, &ctx)
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("(last failed at ")
+        );
+
+        // ... and then it waits twice as long
+        let mut entry = cache.lock().await.remove(url).remove(0);
+        entry
+            .failure
+            .as_mut()
+            .unwrap()
+            .retry_after = Utc::now();
+        cache
+            .lock()
+            .await
+            .insert_entry(url.into(), entry);
+        assert!(
+            tracker
+                .track(url, 0, true, &ctx)
+                .await
+                .is_err()
+        );
+        let failure = failure(&*cache.lock().await).unwrap();
+        assert_eq!(failure.attempts, 2);
+        assert!(failure.retry_after > Utc::now() + TimeDelta::minutes(9));
+        assert_eq!(cache.lock().await.get_all(url).len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_retry_interval() {
+        assert_eq!(retry_interval(1), TimeDelta::minutes(5));
+        assert_eq!(retry_interval(3), TimeDelta::minutes(20));
+        assert_eq!(retry_interval(100), TimeDelta::days(1));
+        assert!(is_lasting_failure(404));
+        assert!(!is_lasting_failure(429));
+        assert!(!is_lasting_failure(503));
+    }
+}
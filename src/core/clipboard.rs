@@ -0,0 +1,144 @@
+// Watching the clipboard for tracking URLs and numbers, so that the user can
+// add a package by copying its tracking link. Reading the system clipboard is
+// behind the `clipboard` feature; the rest works with any `ClipboardSource`.
+
+use crate::Result;
+use crate::tracker::TrackerRegistry;
+
+/// Something we can read text from, like the system clipboard
+pub trait ClipboardSource {
+    /// The current text contents, if any
+    fn read(&mut self) -> Option<String>;
+}
+
+/// Reports tracking URLs as they are copied
+pub struct ClipboardWatcher<C: ClipboardSource> {
+    source: C,
+    last:   Option<String>,
+}
+
+impl<C: ClipboardSource> ClipboardWatcher<C> {
+    /// Whatever is on the clipboard when the watcher is created is ignored;
+    /// only things copied afterwards are reported.
+    pub fn new(mut source: C) -> Self {
+        let last = source.read();
+        Self { source, last }
+    }
+
+    /// Check the clipboard once. Returns the tracking URL if something new
+    /// was copied that one of the trackers recognises.
+    pub fn poll(&mut self, registry: &TrackerRegistry) -> Option<String> {
+        let text = self.source.read()?;
+        if self.last.as_ref() == Some(&text) {
+            return None;
+        }
+        self.last = Some(text.clone());
+        recognise(&text, registry)
+    }
+}
+
+/// The tracking URL for a copied tracking URL or tracking number, if one of
+/// the trackers recognises it.
+pub fn recognise(text: &str, registry: &TrackerRegistry) -> Option<String> {
+    let text = text.trim();
+    if text.is_empty() || text.contains(char::is_whitespace) {
+        return None;
+    }
+    let url = registry.resolve(text, None).ok()?;
+    registry
+        .get_handler(&url)
+        .is_ok()
+        .then_some(url)
+}
+
+/// The system clipboard
+#[cfg(feature = "clipboard")]
+pub struct SystemClipboard(arboard::Clipboard);
+
+#[cfg(feature = "clipboard")]
+impl SystemClipboard {
+    pub fn new() -> Result<Self> {
+        arboard::Clipboard::new()
+            .map(Self)
+            .map_err(|e| format!("Couldn't access the clipboard: {e}").into())
+    }
+}
+
+#[cfg(feature = "clipboard")]
+impl ClipboardSource for SystemClipboard {
+    fn read(&mut self) -> Option<String> {
+        // Errors here usually just mean there's no text on the clipboard
+        self.0.get_text().ok()
+    }
+}
+
+/// The system clipboard (not available without the `clipboard` feature)
+#[cfg(not(feature = "clipboard"))]
+pub struct SystemClipboard;
+
+#[cfg(not(feature = "clipboard"))]
+impl SystemClipboard {
+    pub fn new() -> Result<Self> {
+        Err("Watching the clipboard needs the `clipboard` feature. \
+             Reinstall packtrack with `--features clipboard` to enable this."
+            .into())
+    }
+}
+
+#[cfg(not(feature = "clipboard"))]
+impl ClipboardSource for SystemClipboard {
+    fn read(&mut self) -> Option<String> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    /// Returns a sequence of clipboard contents
+    struct FakeClipboard(VecDeque<&'static str>);
+    impl ClipboardSource for FakeClipboard {
+        fn read(&mut self) -> Option<String> {
+            self.0.pop_front().map(String::from)
+        }
+    }
+
+    #[test]
+    fn test_recognise() {
+        let registry = TrackerRegistry::default();
+        let url = "https://jouw.postnl.nl/track-and-trace/3SABCD1234567";
+        assert_eq!(recognise(url, &registry).as_deref(), Some(url));
+        assert_eq!(
+            recognise(" 3SABCD1234567\n", &registry).as_deref(),
+            Some(url)
+        );
+        assert_eq!(recognise("https://example.com/123456789", &registry), None);
+        assert_eq!(recognise("see 3SABCD1234567", &registry), None);
+        assert_eq!(recognise("hello", &registry), None);
+    }
+
+    #[test]
+    fn test_clipboard_watcher() {
+        let registry = TrackerRegistry::default();
+        let url = "https://jouw.postnl.nl/track-and-trace/3SABCD1234567";
+        let mut watcher = ClipboardWatcher::new(FakeClipboard(
+            [url, url, "hello", url, "JVGL0123456789012345"].into(),
+        ));
+        // The initial contents are ignored
+        assert_eq!(watcher.poll(&registry), None);
+        // Not a tracking URL
+        assert_eq!(watcher.poll(&registry), None);
+        // Copying the URL again counts, because something else was copied in
+        // between
+        assert_eq!(watcher.poll(&registry).as_deref(), Some(url));
+        assert!(
+            watcher
+                .poll(&registry)
+                .unwrap()
+                .contains("dhlecommerce")
+        );
+        assert_eq!(watcher.poll(&registry), None);
+    }
+}
@@ -0,0 +1,96 @@
+// What changed since the last run: the tracked packages compared with the
+// newest cache entries from before they were tracked. Unlike `verify`, the
+// cache is used as normal, so a package that was reused from the cache
+// hasn't changed.
+
+use crate::api::Job;
+use crate::verify::{CachedPackage, Discrepancy, compare};
+use std::collections::HashMap;
+
+/// A package that changed since the previous cache entry
+pub struct PackageDiff<'a> {
+    pub job:      &'a Job,
+    /// The previous version, or None if the package wasn't in the cache
+    pub previous: Option<&'a CachedPackage>,
+    pub changes:  Vec<Discrepancy>,
+}
+
+/// The jobs whose package changed since the `previous` versions, e.g. from
+/// `verify::cached_packages` before tracking. Packages that weren't in the
+/// cache count as changed. Jobs that couldn't be tracked are left out.
+pub fn diff<'a>(
+    jobs: &'a [Job],
+    previous: &'a HashMap<String, CachedPackage>,
+) -> Vec<PackageDiff<'a>> {
+    jobs.iter()
+        .filter_map(|job| {
+            let package = job.result.as_ref().ok()?;
+            let previous = previous.get(&job.url.url);
+            let changes = previous
+                .map(|p| compare(&p.package, package))
+                .unwrap_or_default();
+            if previous.is_some() && changes.is_empty() {
+                return None;
+            }
+            Some(PackageDiff {
+                job,
+                previous,
+                changes,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks::load_text;
+    use crate::tracker::{PackageStatus, TrackerRegistry};
+    use chrono::Utc;
+
+    #[test]
+    fn test_diff() -> crate::Result<()> {
+        let registry = TrackerRegistry::default();
+        let changed = "https://jouw.postnl.nl/track-and-trace/3SABCD1234567";
+        let unchanged = "https://jouw.postnl.nl/track-and-trace/3SABCD7654321";
+        let new = "https://jouw.postnl.nl/track-and-trace/3SABCD0000000";
+        let tracker = registry.get_handler(changed)?;
+        let undelivered =
+            tracker.parse(load_text("postnl_undelivered.json")?)?;
+        let delivered = tracker.parse(load_text("postnl_delivered.json")?)?;
+        let jobs: Vec<Job> = [changed, unchanged, new]
+            .into_iter()
+            .map(|url| Job {
+                url:    url.into(),
+                result: Ok(delivered.clone()),
+            })
+            .chain([Job {
+                url:    "https://example.com/track/123".into(),
+                result: Err("HTTP 500".into()),
+            }])
+            .collect();
+        let cached = |package: &crate::tracker::Package| CachedPackage {
+            created: Utc::now(),
+            package: package.clone(),
+        };
+        let previous = HashMap::from([
+            (changed.to_owned(), cached(&undelivered)),
+            (unchanged.to_owned(), cached(&delivered)),
+        ]);
+
+        let diffs = diff(&jobs, &previous);
+        assert_eq!(diffs.len(), 2);
+        assert_eq!(diffs[0].job.url.url, changed);
+        assert!(
+            diffs[0]
+                .changes
+                .contains(&Discrepancy::StatusChanged {
+                    cached: PackageStatus::InTransit,
+                    fresh:  PackageStatus::Delivered,
+                })
+        );
+        assert_eq!(diffs[1].job.url.url, new);
+        assert!(diffs[1].previous.is_none());
+        Ok(())
+    }
+}
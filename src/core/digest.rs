@@ -0,0 +1,276 @@
+// A daily summary of the packages that need attention, meant to be run from
+// cron in the morning, or sent by `packtrack watch` at a set time: what's
+// arriving today, what was delivered yesterday, what happened since yesterday,
+// what seems to be stuck, and what needs to be collected before it's returned.
+
+use crate::api::Job;
+use crate::tracker::{Package, PackageStatus};
+use crate::url_store::AnnotatedUrl;
+use crate::utils::UtcTime;
+use chrono::{Local, TimeDelta};
+
+/// What counts as "recent" and "stalled"
+pub struct DigestOptions {
+    /// Events newer than this are reported as changes
+    pub since:         TimeDelta,
+    /// Packages without events for this long are reported as stalled
+    pub stalled_after: TimeDelta,
+    /// Pickup deadlines closer than this are reported
+    pub pickup_within: TimeDelta,
+}
+impl Default for DigestOptions {
+    fn default() -> Self {
+        Self {
+            since:         TimeDelta::hours(24),
+            stalled_after: TimeDelta::days(3),
+            pickup_within: TimeDelta::days(3),
+        }
+    }
+}
+
+/// A tracked package and the URL entry it belongs to
+pub struct DigestItem<'a> {
+    pub url:     &'a AnnotatedUrl,
+    pub package: &'a Package,
+}
+
+/// The packages that need attention, by reason. A package can appear in more
+/// than one section.
+#[derive(Default)]
+pub struct Digest<'a> {
+    /// Packages arriving today
+    pub arriving:  Vec<DigestItem<'a>>,
+    /// Placeholders expected today (or earlier) that still have no tracking
+    /// URL
+    pub pending:   Vec<&'a AnnotatedUrl>,
+    /// Packages delivered yesterday
+    pub delivered: Vec<DigestItem<'a>>,
+    /// Packages with new events
+    pub changed:   Vec<DigestItem<'a>>,
+    /// Packages that haven't moved for a while
+    pub stalled:   Vec<DigestItem<'a>>,
+    /// Packages waiting at a pickup point, soonest deadline first
+    pub pickups:   Vec<DigestItem<'a>>,
+    /// URLs that couldn't be tracked
+    pub errors:    Vec<&'a Job>,
+}
+
+impl<'a> Digest<'a> {
+    pub fn new(
+        jobs: impl IntoIterator<Item = &'a Job>,
+        placeholders: &'a [AnnotatedUrl],
+        now: UtcTime,
+        options: &DigestOptions,
+    ) -> Self {
+        let today = now.with_timezone(&Local).date_naive();
+        let mut digest = Self::default();
+        for job in jobs {
+            let Ok(package) = &job.result else {
+                digest.errors.push(job);
+                continue;
+            };
+            let item = || DigestItem {
+                url: &job.url,
+                package,
+            };
+            let last_event = package
+                .events
+                .iter()
+                .map(|e| e.timestamp)
+                .max();
+            if last_event.is_some_and(|t| t > now - options.since) {
+                digest.changed.push(item());
+            }
+            let delivered_yesterday = package
+                .delivered
+                .or(last_event)
+                .is_some_and(|t| {
+                    t.with_timezone(&Local).date_naive() + TimeDelta::days(1)
+                        == today
+                });
+            if package.status.is_final() && delivered_yesterday {
+                digest.delivered.push(item());
+            }
+            if package.status.is_final() {
+                continue;
+            }
+            let expected = package.eta.or(package
+                .eta_window
+                .as_ref()
+                .map(|w| w.start));
+            if expected
+                .is_some_and(|t| t.with_timezone(&Local).date_naive() == today)
+            {
+                digest.arriving.push(item());
+            }
+            let waiting_for_pickup =
+                matches!(package.status, PackageStatus::ReadyForPickup { .. });
+            if waiting_for_pickup
+                && package
+                    .pickup
+                    .as_ref()
+                    .is_none_or(|p| p.deadline_within(options.pickup_within))
+            {
+                digest.pickups.push(item());
+            }
+            // A package with an ETA in the future isn't stuck, just slow
+            let stalled = last_event
+                .is_some_and(|t| t < now - options.stalled_after)
+                && expected.is_none_or(|t| t < now);
+            if stalled && !waiting_for_pickup {
+                digest.stalled.push(item());
+            }
+        }
+        digest.pickups.sort_by_key(|item| {
+            item.package
+                .pickup
+                .as_ref()
+                .and_then(|p| p.deadline)
+        });
+        digest.pending = placeholders
+            .iter()
+            .filter(|u| {
+                u.expected
+                    .is_some_and(|date| date <= today)
+            })
+            .collect();
+        digest
+    }
+
+    /// True if there's nothing to report
+    pub fn is_empty(&self) -> bool {
+        self.arriving.is_empty()
+            && self.pending.is_empty()
+            && self.delivered.is_empty()
+            && self.changed.is_empty()
+            && self.stalled.is_empty()
+            && self.pickups.is_empty()
+            && self.errors.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tracker::{Event, PickupPoint, TimeWindow};
+
+    fn job(
+        name: &str,
+        status: PackageStatus,
+        eta: Option<&str>,
+        last_event: &str,
+    ) -> Job {
+        Job {
+            url:    name.into(),
+            result: Ok(Package {
+                barcode: "ABC".into(),
+                channel: "PostNL".into(),
+                status,
+                sender: None,
+                recipient: None,
+                eta: None,
+                eta_window: eta.map(|eta| TimeWindow {
+                    start: eta.parse().unwrap(),
+                    end:   eta.parse().unwrap(),
+                }),
+                delivered: None,
+                events: vec![Event::new(
+                    last_event.parse().unwrap(),
+                    "Something happened",
+                )],
+                pickup: None,
+            }),
+        }
+    }
+
+    fn names(items: &[DigestItem]) -> Vec<String> {
+        items
+            .iter()
+            .map(|item| item.url.url.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_digest() {
+        use PackageStatus::*;
+        let now: UtcTime = "2026-10-16T07:00:00Z".parse().unwrap();
+        let mut pickup = job(
+            "pickup",
+            ReadyForPickup {
+                location: "Bookshop".into(),
+            },
+            None,
+            "2026-10-10T10:00:00Z",
+        );
+        if let Ok(package) = &mut pickup.result {
+            package.pickup = Some(PickupPoint {
+                location: "Bookshop".into(),
+                deadline: Some("2026-10-17T18:00:00Z".parse().unwrap()),
+            });
+        }
+        let jobs = vec![
+            job(
+                "today",
+                InTransit,
+                Some("2026-10-16T12:00:00Z"),
+                "2026-10-15T22:00:00Z",
+            ),
+            job("yesterday", Delivered, None, "2026-10-15T15:00:00Z"),
+            job("long ago", Delivered, None, "2026-10-01T15:00:00Z"),
+            job("stuck", InTransit, None, "2026-10-09T10:00:00Z"),
+            // Not stuck, because it isn't expected until next week
+            job(
+                "slow",
+                InTransit,
+                Some("2026-10-20T12:00:00Z"),
+                "2026-10-09T10:00:00Z",
+            ),
+            pickup,
+            Job {
+                url:    "broken".into(),
+                result: Err("HTTP 503".into()),
+            },
+        ];
+        let placeholders = vec![
+            AnnotatedUrl::placeholder(
+                "Shoes".into(),
+                Some("2026-10-16".parse().unwrap()),
+            ),
+            AnnotatedUrl::placeholder(
+                "Lamp".into(),
+                Some("2026-10-18".parse().unwrap()),
+            ),
+        ];
+
+        let digest =
+            Digest::new(&jobs, &placeholders, now, &DigestOptions::default());
+        assert_eq!(names(&digest.arriving), vec!["today"]);
+        assert_eq!(names(&digest.delivered), vec!["yesterday"]);
+        assert_eq!(names(&digest.changed), vec!["today", "yesterday"]);
+        assert_eq!(names(&digest.stalled), vec!["stuck"]);
+        assert_eq!(names(&digest.pickups), vec!["pickup"]);
+        assert_eq!(digest.errors.len(), 1);
+        assert_eq!(digest.pending.len(), 1);
+        assert_eq!(digest.pending[0].description.as_deref(), Some("Shoes"));
+        assert!(!digest.is_empty());
+        assert!(
+            Digest::new(&[], &[], now, &DigestOptions::default()).is_empty()
+        );
+    }
+
+    #[test]
+    fn test_digest_delivered_to_neighbour() {
+        let now: UtcTime = "2026-10-16T07:00:00Z".parse().unwrap();
+        let jobs = vec![job(
+            "neighbour",
+            PackageStatus::DeliveredToNeighbour {
+                address: "Nr. 12".into(),
+            },
+            None,
+            "2026-10-15T15:00:00Z",
+        )];
+        let digest = Digest::new(&jobs, &[], now, &DigestOptions::default());
+        assert_eq!(names(&digest.delivered), vec!["neighbour"]);
+        assert!(digest.arriving.is_empty());
+    }
+}
@@ -0,0 +1,104 @@
+// Fetching emails from an IMAP server. This is behind the `imap` feature,
+// because most users don't need it.
+
+use crate::Result;
+use crate::settings::ImapSettings;
+
+/// Fetch the raw (RFC 822) emails received in the last `days` days, without
+/// marking them as read.
+#[cfg(feature = "imap")]
+pub async fn fetch_recent_emails(
+    settings: &ImapSettings,
+    password: &str,
+    days: u32,
+) -> Result<Vec<Vec<u8>>> {
+    use chrono::{Local, TimeDelta};
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+    use tokio::net::TcpStream;
+    use tokio_rustls::TlsConnector;
+    use tokio_rustls::rustls::{ClientConfig, RootCertStore, pki_types};
+
+    let err = |e: async_imap::error::Error| format!("IMAP error: {e}");
+    let server = settings
+        .server
+        .as_deref()
+        .ok_or("No IMAP server configured. Set it with `packtrack config set imap.server <server>`")?;
+    let username = settings
+        .username
+        .as_deref()
+        .ok_or("No IMAP username configured. Set it with `packtrack config set imap.username <username>`")?;
+
+    let roots = RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.into(),
+    };
+    let config = ClientConfig::builder()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    let domain = pki_types::ServerName::try_from(server.to_owned())
+        .map_err(|e| format!("Invalid IMAP server {server:?}: {e}"))?;
+    log::debug!("Connecting to {server}:{}", settings.port);
+    let tcp = TcpStream::connect((server, settings.port)).await?;
+    let tls = TlsConnector::from(Arc::new(config))
+        .connect(domain, tcp)
+        .await?;
+
+    let mut client = async_imap::Client::new(tls);
+    client
+        .read_response()
+        .await?
+        .ok_or("IMAP server closed the connection")?;
+    let mut session = client
+        .login(username, password)
+        .await
+        .map_err(|(e, _)| err(e))?;
+    session
+        .select(&settings.mailbox)
+        .await
+        .map_err(err)?;
+
+    let since = Local::now().date_naive() - TimeDelta::days(days.into());
+    let ids = session
+        .search(format!("SINCE {}", since.format("%d-%b-%Y")))
+        .await
+        .map_err(err)?;
+    log::debug!("Found {} emails since {since}", ids.len());
+    let mut emails = vec![];
+    if !ids.is_empty() {
+        let sequence_set = ids
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        // PEEK, so that the emails aren't marked as read
+        let fetches: Vec<_> = session
+            .fetch(sequence_set, "BODY.PEEK[]")
+            .await
+            .map_err(err)?
+            .try_collect()
+            .await
+            .map_err(err)?;
+        emails = fetches
+            .iter()
+            .filter_map(|fetch| fetch.body())
+            .map(|body| body.to_vec())
+            .collect();
+    }
+    if let Err(e) = session.logout().await {
+        log::warn!("Couldn't log out of the IMAP server: {e}");
+    }
+    Ok(emails)
+}
+
+#[cfg(not(feature = "imap"))]
+pub async fn fetch_recent_emails(
+    _settings: &ImapSettings,
+    _password: &str,
+    _days: u32,
+) -> Result<Vec<Vec<u8>>> {
+    Err(
+        "Reading emails over IMAP needs the `imap` feature. Reinstall \
+         packtrack with `--features imap` to enable this."
+            .into(),
+    )
+}
@@ -0,0 +1,222 @@
+// Finding tracking links in emails, e.g. shipping confirmations from shops,
+// so that users don't have to copy-paste them into the URLs file.
+
+mod imap;
+
+pub use imap::fetch_recent_emails;
+
+use crate::Result;
+use crate::tracker::TrackerRegistry;
+use mailparse::{MailHeaderMap, ParsedMail, addrparse_header};
+use regex::Regex;
+
+/// A tracking URL found in an email
+#[derive(Debug, PartialEq)]
+pub struct FoundUrl {
+    pub url:  String,
+    /// Name of the sender, e.g. "Zalando". Useful as a description.
+    pub shop: Option<String>,
+}
+
+/// Parse a raw email (RFC 822) and find the tracking URLs in it, or the
+/// tracking numbers that unambiguously belong to one carrier.
+pub fn find_tracking_urls(
+    raw: &[u8],
+    registry: &TrackerRegistry,
+) -> Result<Vec<FoundUrl>> {
+    let mail = mailparse::parse_mail(raw)
+        .map_err(|e| format!("Couldn't parse email: {e}"))?;
+    let shop = mail
+        .headers
+        .get_first_header("From")
+        .and_then(|header| shop_name(&addrparse_header(header).ok()?));
+
+    let texts = body_texts(&mail);
+    let mut urls: Vec<String> = vec![];
+    for url in texts
+        .iter()
+        .flat_map(|text| tracking_links(text, registry))
+    {
+        if !urls.contains(&url) {
+            urls.push(url);
+        }
+    }
+    // Tracking numbers are often mentioned next to the link, so skip the ones
+    // we already have a link for.
+    for (barcode, url) in texts
+        .iter()
+        .flat_map(|text| tracking_numbers(text, registry))
+    {
+        if !urls
+            .iter()
+            .any(|u| u.contains(&barcode))
+        {
+            urls.push(url);
+        }
+    }
+    Ok(urls
+        .into_iter()
+        .map(|url| FoundUrl {
+            url,
+            shop: shop.clone(),
+        })
+        .collect())
+}
+
+/// The emails in a saved file: an mbox archive, where each email starts with a
+/// "From " line, or else a single email (e.g. an .eml file)
+pub fn split_mbox(raw: &[u8]) -> Vec<&[u8]> {
+    if !raw.starts_with(b"From ") {
+        return vec![raw];
+    }
+    let separator =
+        regex::bytes::Regex::new(r"(?m)^From .*\r?\n").expect("Invalid regex");
+    let separators: Vec<_> = separator.find_iter(raw).collect();
+    separators
+        .iter()
+        .enumerate()
+        .map(|(i, m)| {
+            let end = separators
+                .get(i + 1)
+                .map_or(raw.len(), |next| next.start());
+            &raw[m.end()..end]
+        })
+        .collect()
+}
+
+/// The display name of the sender, or else the name of their domain:
+/// "Zalando <info@zalando.nl>" -> "Zalando", "info@bol.com" -> "bol"
+fn shop_name(from: &mailparse::MailAddrList) -> Option<String> {
+    let mailparse::MailAddr::Single(sender) = from.first()? else {
+        return None;
+    };
+    if let Some(name) = &sender.display_name {
+        return Some(name.trim().to_owned());
+    }
+    // "mail.coolblue.nl" -> "coolblue"
+    let domain = sender.addr.split_once('@')?.1;
+    domain
+        .rsplit('.')
+        .nth(1)
+        .map(String::from)
+}
+
+/// The decoded text of all the text/plain and text/html parts
+fn body_texts(mail: &ParsedMail) -> Vec<String> {
+    if mail.subparts.is_empty() {
+        let is_text = mail.ctype.mimetype.starts_with("text/");
+        return match is_text {
+            true => mail
+                .get_body()
+                .ok()
+                .into_iter()
+                .collect(),
+            false => vec![],
+        };
+    }
+    mail.subparts
+        .iter()
+        .flat_map(body_texts)
+        .collect()
+}
+
+/// Links that one of the trackers can handle, and that contain something
+/// that looks like a barcode (to skip e.g. links to the carrier's homepage).
+fn tracking_links(text: &str, registry: &TrackerRegistry) -> Vec<String> {
+    let link = Regex::new(r#"https?://[^\s"'<>()]+"#).expect("Invalid regex");
+    let barcode = Regex::new("[A-Za-z0-9]{8,}").expect("Invalid regex");
+    link.find_iter(text)
+        .map(|m| {
+            m.as_str()
+                .replace("&amp;", "&")
+                .trim_end_matches(['.', ',', ';'])
+                .to_owned()
+        })
+        .filter(|url| registry.get_handler(url).is_ok())
+        .filter(|url| {
+            barcode.find_iter(url).any(|m| {
+                m.as_str()
+                    .chars()
+                    .any(|c| c.is_ascii_digit())
+            })
+        })
+        .collect()
+}
+
+/// Bare tracking numbers that match exactly one carrier, with their URLs. Only
+/// numbers with letters in them are considered, because all-digit numbers are
+/// too often order numbers or phone numbers.
+fn tracking_numbers(
+    text: &str,
+    registry: &TrackerRegistry,
+) -> Vec<(String, String)> {
+    let token = Regex::new(r"\b[A-Z0-9]{10,30}\b").expect("Invalid regex");
+    token
+        .find_iter(text)
+        .map(|m| m.as_str())
+        .filter(|t| {
+            t.chars()
+                .any(|c| c.is_ascii_alphabetic())
+        })
+        .filter(|t| t.chars().any(|c| c.is_ascii_digit()))
+        .filter_map(|t| match registry.detect(t, None).as_slice() {
+            [(_, url)] => Some((t.to_owned(), url.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks;
+
+    #[test]
+    fn test_find_tracking_urls() -> Result<()> {
+        let raw = mocks::load_text("email_shipped.eml")?;
+        let found = find_tracking_urls(raw.as_bytes(), &Default::default())?;
+        let shop = Some("Zalando".to_string());
+        assert_eq!(
+            found,
+            vec![
+                FoundUrl {
+                    url:  "https://jouw.postnl.nl/track-and-trace/3SABCD1234567-NL-1234AB".into(),
+                    shop: shop.clone(),
+                },
+                FoundUrl {
+                    url:  "https://my.dhlecommerce.nl/home/tracktrace/JVGL0123456789012345".into(),
+                    shop: shop.clone(),
+                },
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_mbox() -> Result<()> {
+        let eml = mocks::load_text("email_shipped.eml")?;
+        assert_eq!(split_mbox(eml.as_bytes()), [eml.as_bytes()]);
+
+        let mbox = format!(
+            "From info@zalando.nl Mon Oct 12 09:30:00 2026\n{eml}\n\
+             From info@bol.com Tue Oct 13 10:00:00 2026\nFrom: \
+             info@bol.com\n\nNothing to track\n"
+        );
+        let emails = split_mbox(mbox.as_bytes());
+        assert_eq!(emails.len(), 2);
+        assert_eq!(
+            find_tracking_urls(emails[0], &Default::default())?.len(),
+            2
+        );
+        assert_eq!(emails[1], b"From: info@bol.com\n\nNothing to track\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_shop_name() {
+        let name = |from: &str| shop_name(&mailparse::addrparse(from).unwrap());
+        assert_eq!(name("Zalando <info@zalando.nl>").unwrap(), "Zalando");
+        assert_eq!(name("info@bol.com").unwrap(), "bol");
+        assert_eq!(name("noreply@mail.coolblue.nl").unwrap(), "coolblue");
+    }
+}
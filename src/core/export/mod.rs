@@ -0,0 +1,128 @@
+// Exporting the tracking history in the cache to other formats, so users can
+// analyse it with their own tools.
+
+mod sqlite;
+
+pub use sqlite::export_sqlite;
+
+use crate::cache::Cache;
+use crate::tracker::{Event, Package, TimeWindow, TrackerRegistry};
+use crate::url_store::AnnotatedUrl;
+use crate::utils::UtcTime;
+
+/// Everything the cache knows about one package
+pub struct PackageHistory {
+    pub url:         String,
+    /// From the URLs file, if the URL is still in there
+    pub description: Option<String>,
+    /// The package as of the latest cached response
+    pub latest:      Package,
+    /// The events from all the cached responses, oldest first. Carriers
+    /// sometimes drop old events, so this can be more than `latest.events`.
+    pub events:      Vec<Event>,
+    /// What the ETA was each time the package was tracked, oldest first
+    pub snapshots:   Vec<EtaSnapshot>,
+}
+
+/// The ETA at one point in time
+pub struct EtaSnapshot {
+    /// When the carrier's response was received
+    pub observed: UtcTime,
+    pub eta:      Option<UtcTime>,
+    pub window:   Option<TimeWindow>,
+}
+
+/// Collect the history of every URL in the cache whose responses can be
+/// parsed. `urls` provides the descriptions.
+pub fn histories_from_cache(
+    cache: &dyn Cache,
+    registry: &TrackerRegistry,
+    urls: &[AnnotatedUrl],
+) -> Vec<PackageHistory> {
+    let mut cached_urls = cache.get_all_urls();
+    cached_urls.sort();
+    let mut histories = vec![];
+    for url in cached_urls {
+        let Ok(tracker) = registry.get_handler(&url) else {
+            continue;
+        };
+        let mut entries = cache.get_all(&url);
+        entries.sort_by_key(|entry| entry.created);
+        let parsed: Vec<(UtcTime, Package)> = entries
+            .into_iter()
+            .filter_map(|entry| {
+                let package = tracker.parse(entry.text.clone()).ok()?;
+                Some((entry.created, package))
+            })
+            .collect();
+        let Some((_, latest)) = parsed.last().cloned() else {
+            continue;
+        };
+
+        let mut events: Vec<Event> = vec![];
+        for (_, package) in &parsed {
+            for event in &package.events {
+                let seen = events.iter().any(|e| {
+                    e.timestamp == event.timestamp && e.text == event.text
+                });
+                if !seen {
+                    events.push(event.clone());
+                }
+            }
+        }
+        events.sort_by_key(|e| e.timestamp);
+
+        let snapshots = parsed
+            .iter()
+            .map(|(observed, package)| EtaSnapshot {
+                observed: *observed,
+                eta:      package.eta,
+                window:   package.eta_window.clone(),
+            })
+            .collect();
+        let description = urls
+            .iter()
+            .find(|u| u.url == url)
+            .and_then(|u| u.description.clone());
+        histories.push(PackageHistory {
+            url,
+            description,
+            latest,
+            events,
+            snapshots,
+        });
+    }
+    histories
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::MemoryCache;
+    use crate::mocks::load_text;
+
+    #[test]
+    fn test_histories_from_cache() -> crate::Result<()> {
+        let url = "https://jouw.postnl.nl/track-and-trace/3SABCD1234567";
+        let mut cache = MemoryCache::default();
+        cache.insert(url.into(), load_text("postnl_undelivered.json")?);
+        cache.insert(url.into(), load_text("postnl_delivered.json")?);
+        cache.insert("https://example.com".into(), "{}".into());
+        let urls = vec![AnnotatedUrl::new(url.into(), Some("shoes".into()))];
+
+        let histories =
+            histories_from_cache(&cache, &TrackerRegistry::default(), &urls);
+        assert_eq!(histories.len(), 1);
+        let history = &histories[0];
+        assert_eq!(history.description.as_deref(), Some("shoes"));
+        assert!(history.latest.status.is_final());
+        assert_eq!(history.snapshots.len(), 2);
+        assert!(history.events.len() >= history.latest.events.len());
+        assert!(
+            history
+                .events
+                .is_sorted_by_key(|e| e.timestamp)
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,175 @@
+// The SQLite schema is documented in docs/how-to/export.md; keep them in sync.
+
+use crate::Result;
+use crate::export::PackageHistory;
+use std::path::Path;
+
+#[cfg(feature = "sqlite")]
+const SCHEMA: &str = "
+    DROP TABLE IF EXISTS eta_snapshots;
+    DROP TABLE IF EXISTS events;
+    DROP TABLE IF EXISTS packages;
+    CREATE TABLE packages (
+        id              INTEGER PRIMARY KEY,
+        url             TEXT NOT NULL UNIQUE,
+        description     TEXT,
+        carrier         TEXT NOT NULL,
+        barcode         TEXT NOT NULL,
+        sender          TEXT,
+        recipient       TEXT,
+        status          TEXT NOT NULL,
+        status_detail   TEXT,
+        delivered       TEXT,
+        pickup_location TEXT,
+        pickup_deadline TEXT
+    );
+    CREATE TABLE events (
+        package_id INTEGER NOT NULL REFERENCES packages(id),
+        timestamp  TEXT NOT NULL,
+        text       TEXT NOT NULL
+    );
+    CREATE TABLE eta_snapshots (
+        package_id   INTEGER NOT NULL REFERENCES packages(id),
+        observed     TEXT NOT NULL,
+        eta          TEXT,
+        window_start TEXT,
+        window_end   TEXT
+    );
+    CREATE INDEX events_package ON events(package_id);
+    CREATE INDEX eta_snapshots_package ON eta_snapshots(package_id);
+";
+
+/// Write the histories to an SQLite database, replacing any packtrack tables
+/// that are already in it.
+#[cfg(feature = "sqlite")]
+pub fn export_sqlite(path: &Path, histories: &[PackageHistory]) -> Result<()> {
+    use crate::tracker::PackageStatus;
+    use rusqlite::{Connection, params};
+
+    let sql_error = |e: rusqlite::Error| -> crate::Error {
+        format!("SQLite error in {}: {e}", path.display()).into()
+    };
+    let mut db = Connection::open(path).map_err(sql_error)?;
+    let tx = db.transaction().map_err(sql_error)?;
+    tx.execute_batch(SCHEMA)
+        .map_err(sql_error)?;
+    for history in histories {
+        let package = &history.latest;
+        let (status, detail) = match &package.status {
+            PackageStatus::Delivered => ("Delivered", None),
+            PackageStatus::DeliveredToNeighbour { address } => {
+                ("DeliveredToNeighbour", Some(address))
+            }
+            PackageStatus::ReadyForPickup { location } => {
+                ("ReadyForPickup", Some(location))
+            }
+            PackageStatus::InTransit => ("InTransit", None),
+        };
+        tx.execute(
+            "INSERT INTO packages (url, description, carrier, barcode, \
+             sender, recipient, status, status_detail, delivered, \
+             pickup_location, pickup_deadline) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                history.url,
+                history.description,
+                package.channel,
+                package.barcode,
+                package.sender,
+                package.recipient,
+                status,
+                detail,
+                package.delivered,
+                package
+                    .pickup
+                    .as_ref()
+                    .map(|p| &p.location),
+                package
+                    .pickup
+                    .as_ref()
+                    .and_then(|p| p.deadline),
+            ],
+        )
+        .map_err(sql_error)?;
+        let id = tx.last_insert_rowid();
+        for event in &history.events {
+            tx.execute(
+                "INSERT INTO events (package_id, timestamp, text) \
+                 VALUES (?1, ?2, ?3)",
+                params![id, event.timestamp, event.text],
+            )
+            .map_err(sql_error)?;
+        }
+        for snapshot in &history.snapshots {
+            tx.execute(
+                "INSERT INTO eta_snapshots (package_id, observed, eta, \
+                 window_start, window_end) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    id,
+                    snapshot.observed,
+                    snapshot.eta,
+                    snapshot
+                        .window
+                        .as_ref()
+                        .map(|w| w.start),
+                    snapshot.window.as_ref().map(|w| w.end),
+                ],
+            )
+            .map_err(sql_error)?;
+        }
+    }
+    tx.commit().map_err(sql_error)
+}
+
+#[cfg(not(feature = "sqlite"))]
+pub fn export_sqlite(
+    _path: &Path,
+    _histories: &[PackageHistory],
+) -> Result<()> {
+    Err("Exporting to SQLite needs the `sqlite` feature. Reinstall \
+         packtrack with `--features sqlite` to enable this."
+        .into())
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use crate::cache::{Cache, MemoryCache};
+    use crate::export::histories_from_cache;
+    use crate::mocks::load_text;
+    use crate::tracker::TrackerRegistry;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_export_sqlite() -> Result<()> {
+        let url = "https://jouw.postnl.nl/track-and-trace/3SABCD1234567";
+        let mut cache = MemoryCache::default();
+        cache.insert(url.into(), load_text("postnl_undelivered.json")?);
+        cache.insert(url.into(), load_text("postnl_delivered.json")?);
+        let histories =
+            histories_from_cache(&cache, &TrackerRegistry::default(), &[]);
+
+        let path = std::env::temp_dir()
+            .join(format!("packtrack-export-test-{}.db", std::process::id()));
+        // Exporting twice replaces the tables instead of adding to them
+        export_sqlite(&path, &histories)?;
+        export_sqlite(&path, &histories)?;
+
+        let db = Connection::open(&path).unwrap();
+        let count = |table: &str| -> i64 {
+            db.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+                row.get(0)
+            })
+            .unwrap()
+        };
+        assert_eq!(count("packages"), 1);
+        assert_eq!(count("events") as usize, histories[0].events.len());
+        assert_eq!(count("eta_snapshots"), 2);
+        let status: String = db
+            .query_row("SELECT status FROM packages", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(status, "Delivered");
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+}
@@ -1,21 +1,21 @@
-use std::{fs, path::PathBuf};
+use std::{fs, path::Path};
 
 use crate::Result;
 
 /// Small trait that can be mocked in tests to prevent tests from doing IO.
-pub trait FileHandler {
+pub trait FileHandler: Send {
     /// Read the contents of the file
-    fn load(&self, path: &PathBuf) -> Result<String>;
+    fn load(&self, path: &Path) -> Result<String>;
     /// Save to disk
-    fn save(&self, path: &PathBuf, text: String) -> Result<()>;
+    fn save(&self, path: &Path, text: String) -> Result<()>;
 }
 
 pub struct TextFileHandler;
 impl FileHandler for TextFileHandler {
-    fn load(&self, path: &PathBuf) -> Result<String> {
+    fn load(&self, path: &Path) -> Result<String> {
         Ok(fs::read_to_string(path)?)
     }
-    fn save(&self, path: &PathBuf, text: String) -> Result<()> {
+    fn save(&self, path: &Path, text: String) -> Result<()> {
         Ok(fs::write(path, text)?)
     }
 }
@@ -23,10 +23,10 @@ impl FileHandler for TextFileHandler {
 // TODO: make it track number of calls, args, etc.
 pub struct MockFileHandler;
 impl FileHandler for MockFileHandler {
-    fn load(&self, _path: &PathBuf) -> Result<String> {
+    fn load(&self, _path: &Path) -> Result<String> {
         Ok("".into())
     }
-    fn save(&self, _path: &PathBuf, _text: String) -> Result<()> {
+    fn save(&self, _path: &Path, _text: String) -> Result<()> {
         Ok(())
     }
 }
@@ -0,0 +1,190 @@
+// Garbage collection for the cache: entries that can't be used anymore only
+// take up space, and an unparseable entry can shadow older good ones.
+
+use crate::cache::{Cache, CacheEntry};
+use crate::tracker::TrackerRegistry;
+use std::cmp::Reverse;
+use std::collections::HashSet;
+
+/// How many entries were removed, and why
+#[derive(Debug, Default, PartialEq)]
+pub struct GcReport {
+    /// Entries whose text doesn't parse to a package, e.g. `{"colli":{}}`
+    pub unparseable: usize,
+    /// Entries for URLs that aren't in the urls file anymore
+    pub unknown_url: usize,
+}
+
+impl GcReport {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn total(&self) -> usize {
+        self.unparseable + self.unknown_url
+    }
+}
+
+/// Remove the entries that don't parse to a package, and, if `urls` is given,
+/// the entries for URLs that aren't in it. Entries for URLs without a tracker
+/// (e.g. from a disabled carrier) are kept, because they can't be checked.
+pub fn gc(
+    cache: &mut dyn Cache,
+    registry: &TrackerRegistry,
+    urls: Option<&[String]>,
+) -> GcReport {
+    let unknown_url = match urls {
+        Some(urls) => {
+            let urls: HashSet<&str> = urls
+                .iter()
+                .map(String::as_str)
+                .collect();
+            cache.retain(&mut |url, _| urls.contains(url))
+        }
+        None => 0,
+    };
+    let unparseable =
+        cache.retain(&mut |url, entry| match registry.get_handler(url) {
+            // Failed lookups aren't responses, but are needed for the backoff
+            Ok(_) if entry.failure.is_some() => true,
+            Ok(tracker) => tracker
+                .parse(entry.text.clone())
+                .is_ok(),
+            Err(_) => true,
+        });
+    GcReport {
+        unparseable,
+        unknown_url,
+    }
+}
+
+/// The quick pass done whenever the cache is loaded: only the newest
+/// responses of each URL are parsed, and the unparseable ones removed, down to
+/// the first one that parses. The older responses are shadowed by that one, so
+/// they can't do any harm until `gc` gets to them. Entries for URLs that aren't
+/// in the urls file are kept, because a shared cache also holds those of the
+/// other machines and users.
+pub fn gc_newest(
+    cache: &mut dyn Cache,
+    registry: &TrackerRegistry,
+) -> GcReport {
+    let mut unparseable = 0;
+    for url in cache.get_all_urls() {
+        let Ok(tracker) = registry.get_handler(&url) else {
+            continue;
+        };
+        let mut responses: Vec<&CacheEntry> = cache
+            .get_all(&url)
+            .into_iter()
+            .filter(|entry| entry.failure.is_none())
+            .collect();
+        responses.sort_by_key(|entry| Reverse(entry.created));
+        let bad: Vec<CacheEntry> = responses
+            .into_iter()
+            .take_while(|entry| {
+                tracker
+                    .parse(entry.text.clone())
+                    .is_err()
+            })
+            .cloned()
+            .collect();
+        if !bad.is_empty() {
+            unparseable +=
+                cache.retain(&mut |u, entry| u != url || !bad.contains(entry));
+        }
+    }
+    GcReport {
+        unparseable,
+        unknown_url: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::{CacheEntry, Failure, MemoryCache};
+    use crate::mocks::load_text;
+
+    #[test]
+    fn test_gc() -> crate::Result<()> {
+        let url = "https://jouw.postnl.nl/track-and-trace/3SABCD1234567";
+        let removed = "https://jouw.postnl.nl/track-and-trace/3SABCD7654321";
+        let unknown = "https://example.com/track/123";
+        let mut cache = MemoryCache::default();
+        cache.insert(url.into(), load_text("postnl_undelivered.json")?);
+        cache.insert(url.into(), r#"{"colli":{}}"#.into());
+        cache.insert(removed.into(), load_text("postnl_delivered.json")?);
+        cache.insert(unknown.into(), "not a package".into());
+        cache.insert_entry(
+            url.into(),
+            CacheEntry::failed(Failure {
+                error:       "HTTP 404".into(),
+                attempts:    1,
+                retry_after: chrono::Utc::now(),
+            }),
+        );
+        let registry = TrackerRegistry::default();
+
+        let keep = [url.to_owned(), unknown.to_owned()];
+        let report = gc(&mut cache, &registry, Some(&keep));
+        assert_eq!(
+            report,
+            GcReport {
+                unparseable: 1,
+                unknown_url: 1,
+            }
+        );
+        assert_eq!(cache.get_all(url).len(), 2);
+        assert!(cache.get_all(removed).is_empty());
+        // No tracker for it, so it can't be checked
+        assert_eq!(cache.get_all(unknown).len(), 1);
+
+        assert!(gc(&mut cache, &registry, None).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_gc_newest() -> crate::Result<()> {
+        let url = "https://jouw.postnl.nl/track-and-trace/3SABCD1234567";
+        let other = "https://jouw.postnl.nl/track-and-trace/3SABCD7654321";
+        let mut cache = MemoryCache::default();
+        let mut insert = |url: &str, text: String, created: &str| {
+            let entry = CacheEntry {
+                created: created.parse().unwrap(),
+                ..CacheEntry::new(text)
+            };
+            cache.insert_entry(url.into(), entry);
+        };
+        insert(url, r#"{"colli":{}}"#.into(), "2026-10-13T08:00:00Z");
+        insert(
+            url,
+            load_text("postnl_undelivered.json")?,
+            "2026-10-14T08:00:00Z",
+        );
+        insert(url, r#"{"colli":{}}"#.into(), "2026-10-15T08:00:00Z");
+        insert(url, "".into(), "2026-10-16T08:00:00Z");
+        insert(
+            other,
+            load_text("postnl_delivered.json")?,
+            "2026-10-14T08:00:00Z",
+        );
+        let registry = TrackerRegistry::default();
+
+        let report = gc_newest(&mut cache, &registry);
+        assert_eq!(report.unparseable, 2);
+        // The old unparseable one is shadowed by the good one, so it's left
+        // for `gc`
+        let created: Vec<String> = cache
+            .get_all(url)
+            .iter()
+            .map(|entry| entry.created.to_rfc3339())
+            .collect();
+        assert_eq!(
+            created,
+            ["2026-10-13T08:00:00+00:00", "2026-10-14T08:00:00+00:00"]
+        );
+        // URLs that aren't in any urls file are kept
+        assert_eq!(cache.get_all(other).len(), 1);
+        Ok(())
+    }
+}
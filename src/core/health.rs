@@ -0,0 +1,104 @@
+// Checks that the carriers are reachable and still respond the way the
+// trackers expect, as an early warning for carrier API changes. Each tracker
+// is probed with a barcode that doesn't exist, so no real package is looked up.
+
+use crate::Result;
+use crate::api::Context;
+use crate::tracker::{Tracker, TrackerContext};
+use std::time::{Duration, Instant};
+
+/// The outcome of probing one tracker
+pub struct ProbeReport {
+    /// Name of the tracker
+    pub tracker: String,
+    pub url:     String,
+    /// How long the request took
+    pub latency: Duration,
+    /// The HTTP status, or why the carrier is unreachable or the response
+    /// doesn't look as expected
+    pub result:  Result<u16>,
+}
+impl ProbeReport {
+    pub fn is_ok(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+/// Probe all the trackers in the registry in parallel. Trackers without a
+/// `probe_url` are skipped.
+pub async fn check_carriers(ctx: &Context) -> Vec<ProbeReport> {
+    let tracker_context = ctx.tracker_context();
+    let probes = ctx
+        .registry
+        .trackers()
+        .filter_map(|tracker| {
+            let url = tracker.probe_url()?;
+            Some(probe(tracker, url, &tracker_context))
+        });
+    futures::future::join_all(probes).await
+}
+
+async fn probe(
+    tracker: &dyn Tracker,
+    url: String,
+    ctx: &TrackerContext<'_>,
+) -> ProbeReport {
+    let start = Instant::now();
+    let raw = tracker.get_raw(&url, ctx).await;
+    let latency = start.elapsed();
+    let result = raw.and_then(|raw| {
+        tracker.check_probe(&raw)?;
+        Ok(raw.status)
+    });
+    ProbeReport {
+        tracker: tracker.name().into(),
+        url,
+        latency,
+        result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::FakeCarrierServer;
+    use crate::tracker::{
+        DpdTracker, GlsTracker, PostNLTracker, TrackerRegistry,
+    };
+
+    #[tokio::test]
+    async fn test_check_carriers() {
+        let server = FakeCarrierServer::start().await;
+        server
+            .respond("3SXXXX0000000", r#"{"colli": {}}"#)
+            .await;
+        // GLS started returning HTML instead of JSON
+        server
+            .respond("000000000000", "<html>Moved</html>")
+            .await;
+        server
+            .respond_status("99999999999999", 503)
+            .await;
+        let ctx = Context {
+            registry: TrackerRegistry::empty()
+                .with(PostNLTracker)
+                .with(GlsTracker)
+                .with(DpdTracker),
+            ..server.context()
+        };
+        let reports = check_carriers(&ctx).await;
+        let results: Vec<_> = reports
+            .iter()
+            .map(|r| (r.tracker.as_str(), r.is_ok()))
+            .collect();
+        assert_eq!(results, [("postnl", true), ("gls", false), ("dpd", false)]);
+        assert!(
+            reports[2]
+                .result
+                .as_ref()
+                .unwrap_err()
+                .to_string()
+                .contains("HTTP 503")
+        );
+    }
+}
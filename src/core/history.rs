@@ -0,0 +1,179 @@
+// The history of a package, from the cache entries kept for its URL. Each
+// entry is a snapshot of what the carrier reported at the time, so comparing
+// consecutive entries shows when the status, ETA and events changed, and the
+// newest entry before a given time shows how the package looked back then.
+
+use crate::Result;
+use crate::api::Job;
+use crate::cache::{Cache, CacheEntry};
+use crate::tracker::{Package, TrackerRegistry};
+use crate::url_store::AnnotatedUrl;
+use crate::utils::UtcTime;
+use crate::verify::{Discrepancy, compare};
+
+/// A cached version of a package
+pub struct Snapshot {
+    /// When the cache entry was created
+    pub created: UtcTime,
+    pub package: Package,
+    /// How it differs from the previous snapshot. Empty for the first one.
+    pub changes: Vec<Discrepancy>,
+}
+
+/// The snapshots of the package at the URL, oldest first. Entries that don't
+/// parse to a package are skipped.
+pub fn history(
+    cache: &dyn Cache,
+    registry: &TrackerRegistry,
+    url: &str,
+) -> Result<Vec<Snapshot>> {
+    let tracker = registry.get_handler(url)?;
+    let mut entries: Vec<&CacheEntry> = cache.get_all(url);
+    entries.sort_by_key(|entry| entry.created);
+    let mut snapshots: Vec<Snapshot> = vec![];
+    for entry in entries {
+        let Ok(package) = tracker.parse(entry.text.clone()) else {
+            log::debug!("Skipping unparseable cache entry for {url}");
+            continue;
+        };
+        let changes = match snapshots.last() {
+            Some(previous) => compare(&previous.package, &package),
+            None => vec![],
+        };
+        snapshots.push(Snapshot {
+            created: entry.created,
+            package,
+            changes,
+        });
+    }
+    Ok(snapshots)
+}
+
+/// The jobs as they would have been reported at `time`: the package from the
+/// newest cache entry created before it, for each of the URLs. URLs without
+/// such an entry get an error.
+pub fn jobs_as_of(
+    cache: &dyn Cache,
+    registry: &TrackerRegistry,
+    urls: Vec<AnnotatedUrl>,
+    time: UtcTime,
+) -> Vec<Job> {
+    urls.into_iter()
+        .map(|url| {
+            let result = package_as_of(cache, registry, &url.url, time);
+            Job { url, result }
+        })
+        .collect()
+}
+
+fn package_as_of(
+    cache: &dyn Cache,
+    registry: &TrackerRegistry,
+    url: &str,
+    time: UtcTime,
+) -> Result<Package> {
+    let tracker = registry.get_handler(url)?;
+    cache
+        .get_all(url)
+        .into_iter()
+        .filter(|entry| entry.created <= time && entry.failure.is_none())
+        .max_by_key(|entry| entry.created)
+        .ok_or(format!("No cache entry from before {time} for {url}"))
+        .and_then(|entry| {
+            tracker
+                .parse(entry.text.clone())
+                .map_err(|err| format!("Couldn't parse cache entry: {err}"))
+        })
+        .map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::MemoryCache;
+    use crate::mocks::load_text;
+    use crate::tracker::PackageStatus;
+    use chrono::{TimeDelta, Utc};
+
+    #[test]
+    fn test_history() -> Result<()> {
+        let url = "https://jouw.postnl.nl/track-and-trace/3SABCD1234567";
+        let now = Utc::now();
+        let mut cache = MemoryCache::default();
+        for (hours_ago, file) in [
+            (2, "postnl_undelivered.json"),
+            (3, "postnl_undelivered.json"),
+            (1, "postnl_delivered.json"),
+        ] {
+            cache.insert_entry(
+                url.into(),
+                CacheEntry {
+                    created: now - TimeDelta::hours(hours_ago),
+                    ..CacheEntry::new(load_text(file)?)
+                },
+            );
+        }
+        cache.insert(url.into(), r#"{"colli":{}}"#.into());
+        let registry = TrackerRegistry::default();
+
+        let snapshots = history(&cache, &registry, url)?;
+        assert_eq!(snapshots.len(), 3);
+        assert_eq!(snapshots[0].created, now - TimeDelta::hours(3));
+        assert!(snapshots[0].changes.is_empty());
+        assert!(snapshots[1].changes.is_empty());
+        assert!(
+            snapshots[2]
+                .changes
+                .contains(&Discrepancy::StatusChanged {
+                    cached: PackageStatus::InTransit,
+                    fresh:  PackageStatus::Delivered,
+                })
+        );
+
+        assert!(
+            history(&cache, &registry, "https://example.com/track/123")
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_jobs_as_of() -> Result<()> {
+        let url = "https://jouw.postnl.nl/track-and-trace/3SABCD1234567";
+        let uncached = "https://jouw.postnl.nl/track-and-trace/3SABCD7654321";
+        let now = Utc::now();
+        let mut cache = MemoryCache::default();
+        for (hours_ago, file) in
+            [(3, "postnl_undelivered.json"), (1, "postnl_delivered.json")]
+        {
+            cache.insert_entry(
+                url.into(),
+                CacheEntry {
+                    created: now - TimeDelta::hours(hours_ago),
+                    ..CacheEntry::new(load_text(file)?)
+                },
+            );
+        }
+        let registry = TrackerRegistry::default();
+        let urls = || vec![url.into(), uncached.into()];
+
+        let jobs = jobs_as_of(&cache, &registry, urls(), now);
+        assert_eq!(
+            jobs[0].result.as_ref().unwrap().status,
+            PackageStatus::Delivered
+        );
+        assert!(jobs[1].result.is_err());
+
+        let earlier = now - TimeDelta::hours(2);
+        let jobs = jobs_as_of(&cache, &registry, urls(), earlier);
+        assert_eq!(
+            jobs[0].result.as_ref().unwrap().status,
+            PackageStatus::InTransit
+        );
+
+        let earliest = now - TimeDelta::hours(4);
+        let jobs = jobs_as_of(&cache, &registry, urls(), earliest);
+        assert!(jobs[0].result.is_err());
+        Ok(())
+    }
+}
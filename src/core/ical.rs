@@ -0,0 +1,201 @@
+// Upcoming deliveries as an iCalendar (RFC 5545) file, so that a calendar app
+// can subscribe to them. Each in-transit package with a delivery window
+// becomes one event. The UIDs are stable, so a calendar that re-reads the
+// file moves the event when the carrier changes the window, instead of
+// adding another one.
+
+use crate::api::Job;
+use crate::tracker::Package;
+use crate::url_store::AnnotatedUrl;
+use crate::utils::UtcTime;
+
+/// iCalendar lines shouldn't be longer than this many bytes
+const MAX_LINE_BYTES: usize = 75;
+
+/// A VCALENDAR with a VEVENT for the delivery window of each in-transit
+/// package. `now` is the DTSTAMP of the events.
+pub fn jobs_to_ical(jobs: &[Job], now: UtcTime) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_owned(),
+        "VERSION:2.0".into(),
+        "PRODID:-//packtrack//packtrack//EN".into(),
+        "CALSCALE:GREGORIAN".into(),
+        "X-WR-CALNAME:Deliveries".into(),
+    ];
+    for job in jobs {
+        if let Ok(package) = &job.result
+            && !package.status.is_final()
+        {
+            lines.extend(delivery_event(&job.url, package, now));
+        }
+    }
+    lines.push("END:VCALENDAR".into());
+    lines
+        .iter()
+        .map(|line| fold(line) + "\r\n")
+        .collect()
+}
+
+/// The VEVENT lines for a package, if it has a delivery window
+fn delivery_event(
+    url: &AnnotatedUrl,
+    package: &Package,
+    now: UtcTime,
+) -> Vec<String> {
+    let Some(window) = &package.eta_window else {
+        return vec![];
+    };
+    let what = url
+        .label
+        .as_deref()
+        .or(url.description.as_deref())
+        .unwrap_or(&package.barcode);
+    let mut description =
+        vec![format!("{} {}", package.channel, package.barcode)];
+    if let Some(sender) = &package.sender {
+        description.push(format!("From: {sender}"));
+    }
+    if let Some(event) = package
+        .events
+        .iter()
+        .max_by_key(|e| e.timestamp)
+    {
+        description.push(format!("Latest: {}", event.text));
+    }
+    description.push(url.url.clone());
+    vec![
+        "BEGIN:VEVENT".into(),
+        format!(
+            "UID:{}-{}@packtrack",
+            package
+                .channel
+                .to_lowercase()
+                .replace(' ', "-"),
+            package.barcode
+        ),
+        format!("DTSTAMP:{}", format_time(now)),
+        format!("DTSTART:{}", format_time(window.start)),
+        format!("DTEND:{}", format_time(window.end)),
+        format!(
+            "SUMMARY:{}",
+            escape(&format!("{} delivery: {what}", package.channel))
+        ),
+        format!("DESCRIPTION:{}", escape(&description.join("\n"))),
+        format!("URL:{}", url.url),
+        "TRANSP:TRANSPARENT".into(),
+        "END:VEVENT".into(),
+    ]
+}
+
+/// e.g. "20261016T120000Z"
+fn format_time(time: UtcTime) -> String {
+    time.format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+/// Escape a TEXT value
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Split long lines into continuation lines (starting with a space), without
+/// splitting characters
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > MAX_LINE_BYTES {
+            folded.push_str("\r\n ");
+            // The space counts towards the length
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::utc;
+    use crate::tracker::{Event, PackageStatus, TimeWindow};
+
+    fn package(status: PackageStatus) -> Package {
+        Package {
+            barcode: "3SABCD1234567".into(),
+            channel: "PostNL".into(),
+            status,
+            sender: Some("Zalando, Berlin".into()),
+            recipient: None,
+            eta: None,
+            eta_window: Some(TimeWindow {
+                start: utc("2026-10-16T10:00:00Z"),
+                end:   utc("2026-10-16T12:30:00Z"),
+            }),
+            delivered: None,
+            events: vec![Event::new(
+                utc("2026-10-16T06:00:00Z"),
+                "Out for delivery",
+            )],
+            pickup: None,
+        }
+    }
+
+    #[test]
+    fn test_jobs_to_ical() {
+        let url = "https://jouw.postnl.nl/track-and-trace/3SABCD1234567";
+        let jobs = vec![
+            Job {
+                url:    AnnotatedUrl::new(url.into(), Some("shoes".into())),
+                result: Ok(package(PackageStatus::InTransit)),
+            },
+            Job {
+                url:    "https://example.com/delivered".into(),
+                result: Ok(package(PackageStatus::Delivered)),
+            },
+            Job {
+                url:    "https://example.com/error".into(),
+                result: Err("HTTP 503".into()),
+            },
+        ];
+        let ical = jobs_to_ical(&jobs, utc("2026-10-16T07:00:00Z"));
+        assert!(ical.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ical.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ical.matches("BEGIN:VEVENT").count(), 1);
+        assert!(ical.contains("UID:postnl-3SABCD1234567@packtrack\r\n"));
+        assert!(ical.contains("DTSTAMP:20261016T070000Z\r\n"));
+        assert!(ical.contains("DTSTART:20261016T100000Z\r\n"));
+        assert!(ical.contains("DTEND:20261016T123000Z\r\n"));
+        assert!(ical.contains("SUMMARY:PostNL delivery: shoes\r\n"));
+        assert!(ical.contains("From: Zalando\\, Berlin\\n"));
+        for line in ical.split("\r\n") {
+            assert!(line.len() <= MAX_LINE_BYTES, "{line}");
+        }
+    }
+
+    #[test]
+    fn test_no_window() {
+        let mut package = package(PackageStatus::InTransit);
+        package.eta_window = None;
+        let jobs = vec![Job {
+            url:    "https://example.com/1".into(),
+            result: Ok(package),
+        }];
+        let ical = jobs_to_ical(&jobs, utc("2026-10-16T07:00:00Z"));
+        assert!(!ical.contains("BEGIN:VEVENT"));
+    }
+
+    #[test]
+    fn test_fold() {
+        let line = format!("DESCRIPTION:{}", "é".repeat(40));
+        let folded = fold(&line);
+        assert_eq!(folded.replace("\r\n ", ""), line);
+        for part in folded.split("\r\n") {
+            assert!(part.len() <= MAX_LINE_BYTES);
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use crate::Result;
+use crate::import::{Aggregator, Shipment};
+use crate::utils::UtcTime;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+
+const ORIGIN: &str = "https://api.aftership.com";
+
+/// AfterShip's tracking API. Needs an API key from the AfterShip admin.
+pub struct AfterShip {
+    api_key: String,
+    origin:  String,
+    client:  Client,
+}
+
+impl AfterShip {
+    pub fn new(api_key: String) -> Self {
+        Self {
+            api_key,
+            origin: ORIGIN.into(),
+            client: Client::new(),
+        }
+    }
+
+    /// Send requests somewhere else, e.g. to a mock server in tests
+    pub fn with_origin(self, origin: &str) -> Self {
+        Self {
+            origin: origin.into(),
+            ..self
+        }
+    }
+}
+
+#[async_trait]
+impl Aggregator for AfterShip {
+    fn name(&self) -> &str {
+        "AfterShip"
+    }
+
+    async fn shipments(&self) -> Result<Vec<Shipment>> {
+        let url = format!("{}/tracking/2024-04/trackings", self.origin);
+        let mut shipments = vec![];
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut request = self
+                .client
+                .get(&url)
+                .header("as-api-key", &self.api_key)
+                .query(&[("limit", "200")]);
+            if let Some(cursor) = &cursor {
+                request = request.query(&[("cursor", cursor)]);
+            }
+            let response = request.send().await?;
+            let status = response.status();
+            if !status.is_success() {
+                return Err(format!("HTTP {status} from AfterShip").into());
+            }
+            let page: Response = response.json().await?;
+            shipments.extend(
+                page.data
+                    .trackings
+                    .into_iter()
+                    .map(Shipment::from),
+            );
+            match page.data.pagination {
+                Some(Pagination {
+                    has_next_page: true,
+                    next_cursor: Some(next),
+                }) => cursor = Some(next),
+                _ => break,
+            }
+        }
+        Ok(shipments)
+    }
+}
+
+#[derive(Deserialize)]
+struct Response {
+    data: Data,
+}
+
+#[derive(Deserialize)]
+struct Data {
+    trackings:  Vec<Tracking>,
+    pagination: Option<Pagination>,
+}
+
+#[derive(Deserialize)]
+struct Pagination {
+    #[serde(default)]
+    has_next_page: bool,
+    next_cursor:   Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Tracking {
+    tracking_number: String,
+    slug:            Option<String>,
+    /// Defaults to the tracking number if the user didn't set one
+    title:           Option<String>,
+    order_number:    Option<String>,
+    created_at:      Option<UtcTime>,
+}
+
+impl From<Tracking> for Shipment {
+    fn from(tracking: Tracking) -> Self {
+        let title = tracking
+            .title
+            .filter(|title| *title != tracking.tracking_number);
+        Self {
+            label:           title.or(tracking.order_number),
+            tracking_number: tracking.tracking_number,
+            carrier:         tracking.slug,
+            created:         tracking.created_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks::load_text;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_shipments() -> Result<()> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/tracking/2024-04/trackings"))
+            .and(header("as-api-key", "secret"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                load_text("aftership_trackings.json")?,
+                "application/json",
+            ))
+            .mount(&server)
+            .await;
+
+        let aftership =
+            AfterShip::new("secret".into()).with_origin(&server.uri());
+        let shipments = aftership.shipments().await?;
+        assert_eq!(shipments.len(), 3);
+        assert_eq!(
+            shipments[0],
+            Shipment {
+                tracking_number: "3SABCD1234567".into(),
+                carrier:         Some("postnl-3s".into()),
+                label:           Some("Running shoes".into()),
+                created:         Some("2026-10-12T09:14:02Z".parse().unwrap()),
+            }
+        );
+        // The title is just the tracking number, so use the order number
+        assert_eq!(shipments[1].label.as_deref(), Some("ORD-1002"));
+        assert_eq!(shipments[2].label.as_deref(), Some("Phone case"));
+
+        let wrong_key =
+            AfterShip::new("wrong".into()).with_origin(&server.uri());
+        assert!(
+            wrong_key
+                .shipments()
+                .await
+                .unwrap_err()
+                .to_string()
+                .contains("HTTP 404")
+        );
+        Ok(())
+    }
+}
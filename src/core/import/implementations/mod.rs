@@ -0,0 +1,2 @@
+pub mod aftership;
+pub mod seventeen_track;
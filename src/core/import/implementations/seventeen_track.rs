@@ -0,0 +1,160 @@
+use crate::Result;
+use crate::import::{Aggregator, Shipment};
+use crate::utils::UtcTime;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::json;
+
+const ORIGIN: &str = "https://api.17track.net";
+
+/// 17TRACK's tracking API. Needs the API key ("security key") from the 17TRACK
+/// API dashboard.
+pub struct SeventeenTrack {
+    api_key: String,
+    origin:  String,
+    client:  Client,
+}
+
+impl SeventeenTrack {
+    pub fn new(api_key: String) -> Self {
+        Self {
+            api_key,
+            origin: ORIGIN.into(),
+            client: Client::new(),
+        }
+    }
+
+    /// Send requests somewhere else, e.g. to a mock server in tests
+    pub fn with_origin(self, origin: &str) -> Self {
+        Self {
+            origin: origin.into(),
+            ..self
+        }
+    }
+}
+
+#[async_trait]
+impl Aggregator for SeventeenTrack {
+    fn name(&self) -> &str {
+        "17TRACK"
+    }
+
+    async fn shipments(&self) -> Result<Vec<Shipment>> {
+        let url = format!("{}/track/v2.2/gettracklist", self.origin);
+        let mut shipments = vec![];
+        let mut page_no = 1;
+        loop {
+            let response = self
+                .client
+                .post(&url)
+                .header("17token", &self.api_key)
+                .json(&json!({ "page_no": page_no }))
+                .send()
+                .await?;
+            let status = response.status();
+            if !status.is_success() {
+                return Err(format!("HTTP {status} from 17TRACK").into());
+            }
+            let response: Response = response.json().await?;
+            if response.code != 0 {
+                return Err(format!(
+                    "17TRACK returned error code {}",
+                    response.code
+                )
+                .into());
+            }
+            let accepted = response
+                .data
+                .map(|data| data.accepted)
+                .unwrap_or_default();
+            shipments.extend(accepted.into_iter().map(Shipment::from));
+            match response.page {
+                Some(page) if page.page_no < page.page_total => {
+                    page_no = page.page_no + 1
+                }
+                _ => break,
+            }
+        }
+        Ok(shipments)
+    }
+}
+
+#[derive(Deserialize)]
+struct Response {
+    code: i64,
+    data: Option<Data>,
+    page: Option<Page>,
+}
+
+#[derive(Deserialize)]
+struct Data {
+    #[serde(default)]
+    accepted: Vec<Tracking>,
+}
+
+#[derive(Deserialize)]
+struct Page {
+    page_no:    u32,
+    page_total: u32,
+}
+
+#[derive(Deserialize)]
+struct Tracking {
+    number:        String,
+    tag:           Option<String>,
+    remark:        Option<String>,
+    register_time: Option<UtcTime>,
+}
+
+impl From<Tracking> for Shipment {
+    fn from(tracking: Tracking) -> Self {
+        Self {
+            tracking_number: tracking.number,
+            // 17TRACK's carriers are numeric codes, which don't tell us much
+            carrier:         None,
+            label:           tracking.tag.or(tracking.remark),
+            created:         tracking.register_time,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks::load_text;
+    use wiremock::matchers::{body_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_shipments() -> Result<()> {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/track/v2.2/gettracklist"))
+            .and(header("17token", "secret"))
+            .and(body_json(json!({ "page_no": 1 })))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                load_text("17track_tracklist.json")?,
+                "application/json",
+            ))
+            .mount(&server)
+            .await;
+
+        let shipments = SeventeenTrack::new("secret".into())
+            .with_origin(&server.uri())
+            .shipments()
+            .await?;
+        let labels: Vec<_> = shipments
+            .iter()
+            .map(|s| (s.tracking_number.as_str(), s.label.as_deref()))
+            .collect();
+        assert_eq!(
+            labels,
+            vec![
+                ("3SABCD1234567", Some("Keyboard")),
+                ("JVGL0123456789012345", Some("Birthday present")),
+            ]
+        );
+        Ok(())
+    }
+}
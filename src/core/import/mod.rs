@@ -0,0 +1,14 @@
+// Importing shipments that users already track with an aggregator service
+// (AfterShip, 17TRACK), so that they can switch to packtrack without adding
+// all their packages again by hand.
+
+mod implementations;
+mod models;
+mod traits;
+mod utils;
+
+pub use implementations::aftership::AfterShip;
+pub use implementations::seventeen_track::SeventeenTrack;
+pub use models::{ImportResult, Shipment};
+pub use traits::Aggregator;
+pub use utils::to_urls;
@@ -0,0 +1,23 @@
+use crate::url_store::AnnotatedUrl;
+use crate::utils::UtcTime;
+
+/// A shipment in an aggregator account
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shipment {
+    pub tracking_number: String,
+    /// The aggregator's name for the carrier, e.g. "postnl-3s". Used as a hint
+    /// when the tracking number could belong to several carriers.
+    pub carrier:         Option<String>,
+    /// The user's name for the shipment, e.g. "Running shoes"
+    pub label:           Option<String>,
+    /// When the shipment was added to the aggregator account
+    pub created:         Option<UtcTime>,
+}
+
+/// Shipments converted to URLs
+#[derive(Debug, Default)]
+pub struct ImportResult {
+    pub urls:    Vec<AnnotatedUrl>,
+    /// Shipments that none of the trackers recognise, and why
+    pub skipped: Vec<(Shipment, String)>,
+}
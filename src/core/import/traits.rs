@@ -0,0 +1,13 @@
+use crate::Result;
+use crate::import::Shipment;
+use async_trait::async_trait;
+
+/// A service that tracks shipments from many carriers for the user
+#[async_trait]
+pub trait Aggregator: Send + Sync {
+    /// e.g. "AfterShip"
+    fn name(&self) -> &str;
+
+    /// Get all the shipments in the user's account
+    async fn shipments(&self) -> Result<Vec<Shipment>>;
+}
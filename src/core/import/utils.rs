@@ -0,0 +1,87 @@
+use crate::import::{ImportResult, Shipment};
+use crate::tracker::TrackerRegistry;
+use crate::url_store::AnnotatedUrl;
+
+/// Turn the shipments into tracking URLs, using the aggregator's carrier as a
+/// hint. The shipment's label becomes the description.
+pub fn to_urls(
+    shipments: Vec<Shipment>,
+    registry: &TrackerRegistry,
+) -> ImportResult {
+    let mut result = ImportResult::default();
+    for shipment in shipments {
+        match to_url(&shipment, registry) {
+            Ok(url) => result.urls.push(AnnotatedUrl {
+                created: shipment
+                    .created
+                    .or(Some(chrono::Utc::now())),
+                ..AnnotatedUrl::new(url, shipment.label)
+            }),
+            Err(err) => result.skipped.push((shipment, err)),
+        }
+    }
+    result
+}
+
+fn to_url(
+    shipment: &Shipment,
+    registry: &TrackerRegistry,
+) -> Result<String, String> {
+    // Aggregators have their own carrier names, like "dhl-benelux"
+    let hint = shipment
+        .carrier
+        .as_deref()
+        .and_then(|carrier| {
+            registry
+                .names()
+                .into_iter()
+                .find(|name| carrier.to_lowercase().contains(name))
+        });
+    let number = &shipment.tracking_number;
+    let url = registry
+        .resolve(number, hint)
+        .or_else(|_| registry.resolve(number, None))
+        .map_err(|err| err.to_string())?;
+    registry
+        .get_handler(&url)
+        .map_err(|_| format!("None of the trackers support {number}"))?;
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shipment(number: &str, carrier: Option<&str>) -> Shipment {
+        Shipment {
+            tracking_number: number.into(),
+            carrier:         carrier.map(String::from),
+            label:           Some("Shoes".into()),
+            created:         None,
+        }
+    }
+
+    #[test]
+    fn test_to_urls() {
+        let registry = TrackerRegistry::default();
+        let result = to_urls(
+            vec![
+                shipment("3SABCD1234567", Some("postnl-3s")),
+                shipment("JVGL0123456789012345", Some("some-other-carrier")),
+                shipment("LX123456789", Some("yunexpress")),
+            ],
+            &registry,
+        );
+        let urls: Vec<_> = result
+            .urls
+            .iter()
+            .map(|u| u.url.as_str())
+            .collect();
+        assert_eq!(urls.len(), 2);
+        assert!(urls[0].contains("postnl"));
+        assert!(urls[1].contains("dhlecommerce"));
+        assert_eq!(result.urls[0].description.as_deref(), Some("Shoes"));
+        assert_eq!(result.skipped.len(), 1);
+        assert_eq!(result.skipped[0].0.tracking_number, "LX123456789");
+    }
+}
@@ -12,9 +12,11 @@ pub fn load_json(filename: &str) -> Result<Value> {
 
 pub fn load_text(filename: &str) -> Result<String> {
     let path = Path::new("mocks").join(filename);
-    let text = fs::read_to_string(&path).expect(&format!(
-        "Couldn't load mock {path:?}. Working directory is {:?}",
-        std::env::current_dir().unwrap()
-    ));
+    let text = fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "Couldn't load mock {path:?}. Working directory is {:?}",
+            std::env::current_dir().unwrap()
+        )
+    });
     Ok(text)
 }
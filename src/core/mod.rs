@@ -1,9 +1,38 @@
+pub mod alerts;
 pub mod api;
+pub mod archive;
+pub mod barcode;
 pub mod cache;
 pub mod cached_tracker;
+pub mod clipboard;
+pub mod diff;
+pub mod digest;
+pub mod email;
+pub mod export;
 pub mod file_handler;
+pub mod gc;
+pub mod health;
+pub mod history;
+pub mod ical;
+pub mod import;
 pub mod mocks;
+pub mod notify;
+pub mod output;
+pub mod polling;
+pub mod prompt;
+pub mod purge;
+pub mod reminders;
+pub mod sanitize;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod service;
 pub mod settings;
+pub mod stats;
+pub mod statusbar;
+pub mod sync;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
 pub mod tracker;
 pub mod url_store;
 pub mod utils;
+pub mod verify;
@@ -0,0 +1,130 @@
+use crate::Result;
+use crate::notify::{Notification, Notifier};
+use async_trait::async_trait;
+use std::io::Write;
+use std::path::PathBuf;
+use tokio::process::Command;
+
+/// Programs that can play a sound file, tried in this order
+#[cfg(target_os = "macos")]
+const PLAYERS: &[&str] = &["afplay"];
+#[cfg(not(target_os = "macos"))]
+const PLAYERS: &[&str] = &["paplay", "aplay"];
+
+/// Rings the terminal bell, and plays a sound file if one is configured. For
+/// people who keep a terminal running packtrack on a side monitor.
+pub struct BellNotifier {
+    sound:   Option<PathBuf>,
+    players: Vec<String>,
+}
+
+impl BellNotifier {
+    pub fn new(sound: Option<PathBuf>) -> Self {
+        Self {
+            sound,
+            players: PLAYERS
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+        }
+    }
+
+    /// Play sounds with these programs instead of the default ones
+    pub fn with_players(mut self, players: &[&str]) -> Self {
+        self.players = players
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+        self
+    }
+
+    async fn play(&self, sound: &PathBuf) -> Result<()> {
+        for player in &self.players {
+            match Command::new(player)
+                .arg(sound)
+                .status()
+                .await
+            {
+                Ok(status) if status.success() => return Ok(()),
+                Ok(status) => {
+                    return Err(format!(
+                        "{player} couldn't play {}: {status}",
+                        sound.display()
+                    )
+                    .into());
+                }
+                // Not installed, try the next one
+                Err(err) => log::debug!("Couldn't run {player}: {err}"),
+            }
+        }
+        Err(format!(
+            "Couldn't find a program to play sounds. Tried: {}",
+            self.players.join(", ")
+        )
+        .into())
+    }
+}
+
+#[async_trait]
+impl Notifier for BellNotifier {
+    fn name(&self) -> &str {
+        "bell"
+    }
+
+    async fn send(&self, _notification: &Notification) -> Result<()> {
+        let mut stdout = std::io::stdout();
+        stdout.write_all(b"\x07")?;
+        stdout.flush()?;
+        if let Some(sound) = &self.sound {
+            self.play(sound).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notify::Priority;
+
+    #[tokio::test]
+    async fn test_bell_notifier() {
+        let notification = Notification {
+            title:    "Delivered".into(),
+            body:     "".into(),
+            url:      None,
+            priority: Priority::Normal,
+            update:   None,
+        };
+        assert!(
+            BellNotifier::new(None)
+                .send(&notification)
+                .await
+                .is_ok()
+        );
+        let sound = Some("ding.wav".into());
+        assert!(
+            BellNotifier::new(sound.clone())
+                .with_players(&["true"])
+                .send(&notification)
+                .await
+                .is_ok()
+        );
+        let err = BellNotifier::new(sound.clone())
+            .with_players(&["packtrack-no-such-player"])
+            .send(&notification)
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Couldn't find a program")
+        );
+        assert!(
+            BellNotifier::new(sound)
+                .with_players(&["false"])
+                .send(&notification)
+                .await
+                .is_err()
+        );
+    }
+}
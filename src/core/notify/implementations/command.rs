@@ -0,0 +1,215 @@
+use crate::Result;
+use crate::notify::{Notification, Notifier, Priority};
+use crate::tracker::PackageStatus;
+use async_trait::async_trait;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+/// How long the command may run before it's killed, so that a hanging
+/// script doesn't stop watch mode
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Runs a shell command for every notification, like the webhook, for
+/// integrations packtrack doesn't support itself. The command gets the
+/// notification as JSON on stdin, and the main fields in `PACKTRACK_*`
+/// environment variables.
+pub struct CommandNotifier {
+    command: String,
+    timeout: Duration,
+}
+
+impl CommandNotifier {
+    pub fn new(command: String) -> Self {
+        Self {
+            command,
+            timeout: TIMEOUT,
+        }
+    }
+
+    fn shell(&self) -> Command {
+        #[cfg(windows)]
+        let mut shell = {
+            let mut shell = Command::new("cmd");
+            shell.arg("/C");
+            shell
+        };
+        #[cfg(not(windows))]
+        let mut shell = {
+            let mut shell = Command::new("sh");
+            shell.arg("-c");
+            shell
+        };
+        shell.arg(&self.command);
+        shell
+    }
+}
+
+/// The environment variables describing the notification
+fn environment(notification: &Notification) -> Vec<(&'static str, String)> {
+    let text = |value: serde_json::Value| match value {
+        serde_json::Value::String(s) => s,
+        value => value.to_string(),
+    };
+    let mut env = vec![
+        ("PACKTRACK_TITLE", notification.title.clone()),
+        ("PACKTRACK_BODY", notification.body.clone()),
+        (
+            "PACKTRACK_URL",
+            notification
+                .url
+                .clone()
+                .unwrap_or_default(),
+        ),
+        (
+            "PACKTRACK_PRIORITY",
+            text(serde_json::json!(notification.priority)),
+        ),
+    ];
+    if let Some(update) = &notification.update {
+        let package = &update.package;
+        env.extend([
+            ("PACKTRACK_BARCODE", package.barcode.clone()),
+            ("PACKTRACK_CARRIER", package.channel.clone()),
+            ("PACKTRACK_STATUS", status_name(&package.status).into()),
+        ]);
+    }
+    env
+}
+
+/// The status without its details, e.g. the pickup location, which are in the
+/// JSON
+fn status_name(status: &PackageStatus) -> &'static str {
+    match status {
+        PackageStatus::Delivered => "Delivered",
+        PackageStatus::DeliveredToNeighbour { .. } => "DeliveredToNeighbour",
+        PackageStatus::ReadyForPickup { .. } => "ReadyForPickup",
+        PackageStatus::InTransit => "InTransit",
+    }
+}
+
+#[async_trait]
+impl Notifier for CommandNotifier {
+    fn name(&self) -> &str {
+        "on_change"
+    }
+
+    fn min_priority(&self) -> Priority {
+        Priority::Low
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        let mut child = self
+            .shell()
+            .envs(environment(notification))
+            .stdin(std::process::Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|err| format!("Couldn't run {}: {err}", self.command))?;
+        let json = serde_json::to_vec(notification)?;
+        let mut stdin = child.stdin.take();
+        // Writing counts towards the timeout too: a command that doesn't read
+        // stdin blocks the write once the pipe is full
+        let run = async {
+            if let Some(stdin) = stdin.as_mut() {
+                match stdin.write_all(&json).await {
+                    // The command doesn't have to read it
+                    Err(err)
+                        if err.kind() == std::io::ErrorKind::BrokenPipe => {}
+                    result => result?,
+                }
+            }
+            // Close stdin, so that the command sees the end of the JSON
+            drop(stdin.take());
+            child.wait().await
+        };
+        let Ok(status) = tokio::time::timeout(self.timeout, run).await else {
+            child.kill().await?;
+            return Err(format!(
+                "{} took longer than {:?}",
+                self.command, self.timeout
+            )
+            .into());
+        };
+        let status = status?;
+        if !status.success() {
+            return Err(format!("{} failed: {status}", self.command).into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_command_notifier() -> Result<()> {
+        let out = std::env::temp_dir().join(format!(
+            "packtrack-on-change-test-{}.txt",
+            std::process::id()
+        ));
+        let notification = Notification {
+            title:    "Package updated".into(),
+            body:     "PostNL ABC: In transit".into(),
+            url:      Some("url".into()),
+            priority: Priority::Low,
+            update:   None,
+        };
+        let command = format!(
+            "(echo \"$PACKTRACK_PRIORITY $PACKTRACK_URL\"; cat) > {}",
+            out.display()
+        );
+        CommandNotifier::new(command)
+            .send(&notification)
+            .await?;
+        let written = std::fs::read_to_string(&out)?;
+        std::fs::remove_file(&out)?;
+        let (env, stdin) = written.split_once('\n').unwrap();
+        assert_eq!(env, "low url");
+        let json: serde_json::Value = serde_json::from_str(stdin)?;
+        assert_eq!(json["title"], "Package updated");
+
+        // Commands that ignore stdin are fine, failing ones aren't
+        assert!(
+            CommandNotifier::new("true".into())
+                .send(&notification)
+                .await
+                .is_ok()
+        );
+        assert!(
+            CommandNotifier::new("exit 3".into())
+                .send(&notification)
+                .await
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_command_notifier_timeout() {
+        // More JSON than fits in the pipe, for a command that never reads it,
+        // but not more than fits in its environment
+        let notification = Notification {
+            title:    "Package updated".into(),
+            body:     "x".repeat(100_000),
+            url:      None,
+            priority: Priority::Low,
+            update:   None,
+        };
+        let notifier = CommandNotifier {
+            command: "sleep 10".into(),
+            timeout: Duration::from_millis(200),
+        };
+        let start = std::time::Instant::now();
+        let err = notifier
+            .send(&notification)
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("took longer than")
+        );
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,130 @@
+use crate::Result;
+use crate::notify::{EmailSettings, Notification, Notifier, Priority};
+use async_trait::async_trait;
+use lettre::message::header::ContentType;
+use lettre::message::{Mailbox, Message};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Tokio1Executor};
+
+/// Sends notifications by email, through the user's own SMTP server. Handy
+/// for the daily digest, which is too long for a push notification.
+pub struct EmailNotifier {
+    from:      Mailbox,
+    to:        Mailbox,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl EmailNotifier {
+    pub fn new(
+        settings: &EmailSettings,
+        password: Option<String>,
+    ) -> Result<Self> {
+        let address = |key: &str, value: &str| -> Result<Mailbox> {
+            value.parse().map_err(|err| {
+                format!("Invalid notify.email.{key} {value:?}: {err}").into()
+            })
+        };
+        let to = settings
+            .to
+            .as_deref()
+            .ok_or("Set notify.email.to to send emails")?;
+        let from = settings.from.as_deref().unwrap_or(to);
+        let server = settings
+            .server
+            .as_deref()
+            .ok_or("Set notify.email.server to send emails")?;
+        // Port 465 speaks TLS from the start, the others upgrade with STARTTLS
+        let builder = match settings.port {
+            465 => AsyncSmtpTransport::<Tokio1Executor>::relay(server),
+            _ => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(server),
+        }
+        .map_err(|err| format!("Invalid SMTP server {server:?}: {err}"))?
+        .port(settings.port);
+        let transport = match password {
+            Some(password) => {
+                let username = settings
+                    .username
+                    .clone()
+                    .unwrap_or(from.to_owned());
+                builder.credentials(Credentials::new(username, password))
+            }
+            None => builder,
+        }
+        .build();
+        Ok(Self {
+            from: address("from", from)?,
+            to: address("to", to)?,
+            transport,
+        })
+    }
+
+    fn message(&self, notification: &Notification) -> Result<Message> {
+        let mut body = notification.body.clone();
+        if let Some(url) = &notification.url {
+            body += &format!("\n\n{url}");
+        }
+        let subject = match notification.priority {
+            Priority::High => format!("⚠ {}", notification.title),
+            _ => notification.title.clone(),
+        };
+        Ok(Message::builder()
+            .from(self.from.clone())
+            .to(self.to.clone())
+            .subject(subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(body)
+            .map_err(|err| format!("Couldn't build the email: {err}"))?)
+    }
+}
+
+#[async_trait]
+impl Notifier for EmailNotifier {
+    fn name(&self) -> &str {
+        "email"
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        let message = self.message(notification)?;
+        self.transport
+            .send(message)
+            .await
+            .map_err(|err| format!("Couldn't send the email: {err}"))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_email_message() -> Result<()> {
+        let settings = EmailSettings {
+            to: Some("me@example.com".into()),
+            server: Some("smtp.example.com".into()),
+            ..Default::default()
+        };
+        let notifier = EmailNotifier::new(&settings, Some("secret".into()))?;
+        let notification = Notification {
+            title:    "Collect your package by Saturday".into(),
+            body:     "PostNL ABC is waiting at the Bookshop".into(),
+            url:      Some("https://example.com/ABC".into()),
+            priority: Priority::High,
+            update:   None,
+        };
+        let message = notifier.message(&notification)?;
+        let text = String::from_utf8(message.formatted()).unwrap();
+        assert!(text.contains("From: me@example.com\r\n"));
+        assert!(text.contains("To: me@example.com\r\n"));
+        assert!(text.contains(
+            "PostNL ABC is waiting at the Bookshop\r\n\r\nhttps://example.com/ABC"
+        ));
+
+        let bad = EmailSettings {
+            to: Some("not an address".into()),
+            ..settings
+        };
+        assert!(EmailNotifier::new(&bad, None).is_err());
+        Ok(())
+    }
+}
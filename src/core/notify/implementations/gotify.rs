@@ -0,0 +1,116 @@
+use crate::Result;
+use crate::notify::{Notification, Notifier, Priority};
+use async_trait::async_trait;
+use serde_json::json;
+
+/// Sends notifications to a self-hosted Gotify server, with the token of an
+/// application created there
+pub struct GotifyNotifier {
+    server: String,
+    token:  String,
+    client: reqwest::Client,
+}
+
+impl GotifyNotifier {
+    pub fn new(server: String, token: String) -> Self {
+        Self {
+            server,
+            token,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+/// Gotify's priorities go from 0 to 10. The Android app doesn't notify for
+/// 0, notifies silently up to 3, with a sound from 4, and pops up from 8.
+fn gotify_priority(priority: Priority) -> u8 {
+    match priority {
+        Priority::Low => 2,
+        Priority::Normal => 5,
+        Priority::High => 8,
+    }
+}
+
+#[async_trait]
+impl Notifier for GotifyNotifier {
+    fn name(&self) -> &str {
+        "gotify"
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        let url = format!("{}/message", self.server.trim_end_matches('/'));
+        let mut message = json!({
+            "title": notification.title,
+            "message": notification.body,
+            "priority": gotify_priority(notification.priority),
+        });
+        if let Some(click) = &notification.url {
+            // Opens the tracking page when the notification is tapped
+            message["extras"] = json!({
+                "client::notification": {"click": {"url": click}}
+            });
+        }
+        let response = self
+            .client
+            .post(&url)
+            .header("X-Gotify-Key", &self.token)
+            .json(&message)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(format!("HTTP {} from {url}", response.status()).into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_partial_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_gotify_notifier() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/gotify/message"))
+            .and(header("X-Gotify-Key", "app-token"))
+            .and(body_partial_json(json!({
+                "title": "Package delivered",
+                "priority": 5,
+                "extras": {
+                    "client::notification": {"click": {"url": "url"}}
+                },
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let notification = Notification {
+            title:    "Package delivered".into(),
+            body:     "PostNL ABC: Delivered".into(),
+            url:      Some("url".into()),
+            priority: Priority::Normal,
+            update:   None,
+        };
+        let notifier = GotifyNotifier::new(
+            format!("{}/gotify/", server.uri()),
+            "app-token".into(),
+        );
+        assert!(
+            notifier
+                .send(&notification)
+                .await
+                .is_ok()
+        );
+
+        let notifier = GotifyNotifier::new(server.uri(), "wrong-token".into());
+        assert!(
+            notifier
+                .send(&notification)
+                .await
+                .is_err()
+        );
+    }
+}
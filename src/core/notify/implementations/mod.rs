@@ -0,0 +1,10 @@
+pub mod bell;
+pub mod command;
+#[cfg(feature = "email")]
+pub mod email;
+pub mod gotify;
+pub mod ntfy;
+pub mod pushover;
+pub mod signal;
+pub mod stdout;
+pub mod webhook;
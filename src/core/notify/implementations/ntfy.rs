@@ -0,0 +1,123 @@
+use crate::Result;
+use crate::notify::{Notification, Notifier, Priority};
+use async_trait::async_trait;
+use serde_json::json;
+
+/// Publishes notifications to an ntfy topic, which the ntfy app on your phone
+/// can subscribe to. Works with the public server at ntfy.sh, so there's
+/// nothing to host.
+pub struct NtfyNotifier {
+    server: String,
+    topic:  String,
+    /// Access token, for servers that restrict the topic
+    token:  Option<String>,
+    client: reqwest::Client,
+}
+
+impl NtfyNotifier {
+    pub fn new(server: String, topic: String, token: Option<String>) -> Self {
+        Self {
+            server,
+            topic,
+            token,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+/// ntfy's priorities go from 1 (min) to 5 (urgent). 3 is the default.
+fn ntfy_priority(priority: Priority) -> u8 {
+    match priority {
+        Priority::Low => 2,
+        Priority::Normal => 3,
+        Priority::High => 4,
+    }
+}
+
+#[async_trait]
+impl Notifier for NtfyNotifier {
+    fn name(&self) -> &str {
+        "ntfy"
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        // Publishing as JSON to the server root allows any characters in the
+        // title, unlike the headers
+        let mut request = self
+            .client
+            .post(&self.server)
+            .json(&json!({
+                "topic": self.topic,
+                "title": notification.title,
+                "message": notification.body,
+                "priority": ntfy_priority(notification.priority),
+                "click": notification.url,
+            }));
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "HTTP {} from {}",
+                response.status(),
+                self.server
+            )
+            .into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_partial_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_ntfy_notifier() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/"))
+            .and(header("Authorization", "Bearer tk_secret"))
+            .and(body_partial_json(json!({
+                "topic": "my-packages",
+                "title": "⚠ Collect your package today",
+                "priority": 4,
+                "click": "https://jouw.postnl.nl/track-and-trace/ABC",
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let notification = Notification {
+            title:    "⚠ Collect your package today".into(),
+            body:     "PostNL ABC is waiting at the post office".into(),
+            url:      Some("https://jouw.postnl.nl/track-and-trace/ABC".into()),
+            priority: Priority::High,
+            update:   None,
+        };
+        let notifier = NtfyNotifier::new(
+            server.uri(),
+            "my-packages".into(),
+            Some("tk_secret".into()),
+        );
+        assert!(
+            notifier
+                .send(&notification)
+                .await
+                .is_ok()
+        );
+
+        // Without the token, the mock doesn't match
+        let notifier =
+            NtfyNotifier::new(server.uri(), "my-packages".into(), None);
+        assert!(
+            notifier
+                .send(&notification)
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use crate::Result;
+use crate::notify::{Notification, Notifier, Priority};
+use async_trait::async_trait;
+use serde_json::json;
+
+const PUSHOVER_API_URL: &str = "https://api.pushover.net/1/messages.json";
+
+/// Sends push notifications through Pushover, with the app token and user
+/// key from pushover.net
+pub struct PushoverNotifier {
+    token:   String,
+    user:    String,
+    api_url: String,
+    client:  reqwest::Client,
+}
+
+impl PushoverNotifier {
+    pub fn new(token: String, user: String) -> Self {
+        Self {
+            token,
+            user,
+            api_url: PUSHOVER_API_URL.into(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Send the messages to this URL instead of Pushover's
+    pub fn with_api_url(mut self, api_url: String) -> Self {
+        self.api_url = api_url;
+        self
+    }
+}
+
+/// Pushover's priorities go from -2 (no notification) to 2 (repeated until
+/// acknowledged). 0 is the default, and 1 bypasses the user's quiet hours.
+fn pushover_priority(priority: Priority) -> i8 {
+    match priority {
+        Priority::Low => -1,
+        Priority::Normal => 0,
+        Priority::High => 1,
+    }
+}
+
+#[async_trait]
+impl Notifier for PushoverNotifier {
+    fn name(&self) -> &str {
+        "pushover"
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        let response = self
+            .client
+            .post(&self.api_url)
+            .json(&json!({
+                "token": self.token,
+                "user": self.user,
+                "title": notification.title,
+                "message": notification.body,
+                "priority": pushover_priority(notification.priority),
+                "url": notification.url,
+            }))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            // Pushover explains what's wrong in the body, e.g. an invalid
+            // user key
+            let status = response.status();
+            let body = response
+                .text()
+                .await
+                .unwrap_or_default();
+            return Err(format!("HTTP {status} from Pushover: {body}").into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_partial_json, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_pushover_notifier() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({
+                "token": "app-token",
+                "user": "user-key",
+                "title": "Package out for delivery",
+                "priority": 1,
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(400)
+                    .set_body_string(r#"{"user":"invalid","status":0}"#),
+            )
+            .mount(&server)
+            .await;
+        let mut notification = Notification {
+            title:    "Package out for delivery".into(),
+            body:     "PostNL ABC: In transit".into(),
+            url:      None,
+            priority: Priority::High,
+            update:   None,
+        };
+        let notifier =
+            PushoverNotifier::new("app-token".into(), "user-key".into())
+                .with_api_url(server.uri());
+        assert!(
+            notifier
+                .send(&notification)
+                .await
+                .is_ok()
+        );
+
+        notification.priority = Priority::Normal;
+        let err = notifier
+            .send(&notification)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("invalid"));
+    }
+}
@@ -0,0 +1,184 @@
+use crate::Result;
+use crate::notify::{Notification, Notifier};
+use async_trait::async_trait;
+use serde_json::json;
+use tokio::process::Command;
+
+/// Sends notifications as Signal messages, from an account registered with
+/// signal-cli. Either runs signal-cli itself, or goes through the
+/// signal-cli REST API (e.g. in a container), so no push service sees the
+/// delivery data.
+pub struct SignalNotifier {
+    /// The sending account's phone number, e.g. "+31612345678"
+    account:   String,
+    recipient: String,
+    /// URL of the signal-cli REST API. Without it, signal-cli is run.
+    api:       Option<String>,
+    program:   String,
+    client:    reqwest::Client,
+}
+
+impl SignalNotifier {
+    pub fn new(
+        account: String,
+        recipient: String,
+        api: Option<String>,
+    ) -> Self {
+        Self {
+            account,
+            recipient,
+            api,
+            program: "signal-cli".into(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Run this program instead of signal-cli
+    pub fn with_program(mut self, program: &str) -> Self {
+        self.program = program.into();
+        self
+    }
+
+    async fn send_api(&self, api: &str, message: &str) -> Result<()> {
+        let url = format!("{}/v2/send", api.trim_end_matches('/'));
+        let response = self
+            .client
+            .post(&url)
+            .json(&json!({
+                "number": self.account,
+                "recipients": [self.recipient],
+                "message": message,
+            }))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response
+                .text()
+                .await
+                .unwrap_or_default();
+            return Err(format!("HTTP {status} from {url}: {body}").into());
+        }
+        Ok(())
+    }
+
+    async fn send_cli(&self, message: &str) -> Result<()> {
+        let output = Command::new(&self.program)
+            .args(["-a", &self.account, "send", "-m", message, "--"])
+            .arg(&self.recipient)
+            .output()
+            .await
+            .map_err(|err| format!("Couldn't run {}: {err}", self.program))?;
+        if !output.status.success() {
+            return Err(format!(
+                "{} failed ({}): {}",
+                self.program,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )
+            .into());
+        }
+        Ok(())
+    }
+}
+
+/// Signal messages have no title, so it goes on the first line
+fn signal_message(notification: &Notification) -> String {
+    let mut message = format!("{}\n{}", notification.title, notification.body);
+    if let Some(url) = &notification.url {
+        message += &format!("\n{url}");
+    }
+    message
+}
+
+#[async_trait]
+impl Notifier for SignalNotifier {
+    fn name(&self) -> &str {
+        "signal"
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        let message = signal_message(notification);
+        match &self.api {
+            Some(api) => self.send_api(api, &message).await,
+            None => self.send_cli(&message).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notify::Priority;
+    use wiremock::matchers::{body_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn notification() -> Notification {
+        Notification {
+            title:    "Package delivered".into(),
+            body:     "PostNL ABC: Delivered".into(),
+            url:      Some("url".into()),
+            priority: Priority::Normal,
+            update:   None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_signal_notifier_api() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v2/send"))
+            .and(body_json(json!({
+                "number": "+31600000001",
+                "recipients": ["+31600000002"],
+                "message": "Package delivered\nPostNL ABC: Delivered\nurl",
+            })))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let notifier = SignalNotifier::new(
+            "+31600000001".into(),
+            "+31600000002".into(),
+            Some(server.uri()),
+        );
+        assert!(
+            notifier
+                .send(&notification())
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_signal_notifier_cli() {
+        let notifier = |program| {
+            SignalNotifier::new(
+                "+31600000001".into(),
+                "+31600000002".into(),
+                None,
+            )
+            .with_program(program)
+        };
+        assert!(
+            notifier("true")
+                .send(&notification())
+                .await
+                .is_ok()
+        );
+        assert!(
+            notifier("false")
+                .send(&notification())
+                .await
+                .is_err()
+        );
+        let err = notifier("packtrack-no-such-program")
+            .send(&notification())
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Couldn't run packtrack-no-such-program")
+        );
+    }
+}
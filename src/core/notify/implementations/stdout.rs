@@ -0,0 +1,33 @@
+use crate::Result;
+use crate::notify::{Notification, Notifier, Priority};
+use async_trait::async_trait;
+
+/// Prints notifications to stdout
+pub struct StdoutNotifier;
+
+impl StdoutNotifier {
+    pub fn format(notification: &Notification) -> String {
+        let marker = match notification.priority {
+            Priority::High => "⚠ ",
+            _ => "",
+        };
+        let mut out =
+            format!("{marker}{}\n{}", notification.title, notification.body);
+        if let Some(url) = &notification.url {
+            out += &format!("\n{url}");
+        }
+        out
+    }
+}
+
+#[async_trait]
+impl Notifier for StdoutNotifier {
+    fn name(&self) -> &str {
+        "stdout"
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        println!("{}\n", Self::format(notification));
+        Ok(())
+    }
+}
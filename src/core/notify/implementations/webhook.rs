@@ -0,0 +1,93 @@
+use crate::Result;
+use crate::notify::{Notification, Notifier, Priority};
+use async_trait::async_trait;
+
+/// POSTs every notification to a URL as JSON, including the package and what
+/// changed about it, so it can be wired into home automation and the like.
+/// Gets all package updates, not just the ones worth a push notification.
+pub struct WebhookNotifier {
+    url:    String,
+    client: reqwest::Client,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: String) -> Self {
+        Self {
+            url,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    fn name(&self) -> &str {
+        "webhook"
+    }
+
+    fn min_priority(&self) -> Priority {
+        Priority::Low
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        let response = self
+            .client
+            .post(&self.url)
+            .json(notification)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "HTTP {} from {}",
+                response.status(),
+                self.url
+            )
+            .into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_partial_json, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_webhook_notifier() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(serde_json::json!({
+                "title": "Package updated",
+                "priority": "low",
+                "update": null,
+            })))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let mut notification = Notification {
+            title:    "Package updated".into(),
+            body:     "PostNL ABC: In transit".into(),
+            url:      Some("https://jouw.postnl.nl/track-and-trace/ABC".into()),
+            priority: Priority::Low,
+            update:   None,
+        };
+        let notifier = WebhookNotifier::new(server.uri());
+        assert!(
+            notifier
+                .send(&notification)
+                .await
+                .is_ok()
+        );
+
+        // The mock doesn't match anymore, so the server answers 404
+        notification.title = "Package delivered".into();
+        let err = notifier
+            .send(&notification)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().starts_with("HTTP 404"));
+    }
+}
@@ -0,0 +1,29 @@
+// Sending notifications about packages, e.g. reminders to collect a package
+// before it goes back to the sender. Each channel (stdout, push services, ...)
+// is a `Notifier`; the user's settings decide which ones are used.
+
+mod implementations;
+mod models;
+mod quiet_hours;
+mod sent;
+mod traits;
+mod utils;
+
+pub use implementations::bell::BellNotifier;
+pub use implementations::command::CommandNotifier;
+#[cfg(feature = "email")]
+pub use implementations::email::EmailNotifier;
+pub use implementations::gotify::GotifyNotifier;
+pub use implementations::ntfy::NtfyNotifier;
+pub use implementations::pushover::PushoverNotifier;
+pub use implementations::signal::SignalNotifier;
+pub use implementations::stdout::StdoutNotifier;
+pub use implementations::webhook::WebhookNotifier;
+pub use models::{
+    EmailSettings, Notification, NotifySettings, NtfySettings, PackageUpdate,
+    Priority, SignalSettings,
+};
+pub use quiet_hours::{QuietHours, QuietHoursQueue};
+pub use sent::{SentNotifications, get_sent_notifications_file};
+pub use traits::Notifier;
+pub use utils::{get_notifiers, notify_all};
@@ -0,0 +1,213 @@
+use crate::notify::QuietHours;
+use crate::notify::sent::stable_hash;
+use crate::tracker::Package;
+use crate::verify::Discrepancy;
+use chrono::NaiveTime;
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// How urgent a notification is. Channels that support priorities map this
+/// onto their own levels.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Display, Serialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    /// Any change to a package. Only sent to channels meant for automation,
+    /// like webhooks.
+    Low,
+    Normal,
+    High,
+}
+
+/// A message about a package
+#[derive(Debug, Clone, Serialize)]
+pub struct Notification {
+    /// Short summary, e.g. "Collect your package by Saturday"
+    pub title:    String,
+    pub body:     String,
+    /// Tracking URL of the package, if the notification is about one
+    pub url:      Option<String>,
+    pub priority: Priority,
+    /// The package and what changed, if the notification is about a change
+    pub update:   Option<PackageUpdate>,
+}
+
+impl Notification {
+    /// Identifies the news in the notification, so that it isn't sent twice:
+    /// the URL, the title, and the package's status, ETA and newest event (or
+    /// else the body)
+    pub fn key(&self) -> String {
+        let mut text = self.title.clone();
+        match &self.update {
+            Some(PackageUpdate { package, .. }) => {
+                text += &format!(
+                    "\n{:?}\n{:?}\n{:?}\n{:?}",
+                    package.status,
+                    package.eta,
+                    package.eta_window,
+                    package
+                        .events
+                        .iter()
+                        .max_by_key(|event| event.timestamp)
+                        .map(|event| (event.timestamp, &event.text))
+                );
+            }
+            None => text += &format!("\n{}", self.body),
+        }
+        let url = self.url.as_deref().unwrap_or_default();
+        format!("{url}#{:016x}", stable_hash(&text))
+    }
+}
+
+/// A package that changed since packtrack last saw it
+#[derive(Debug, Clone, Serialize)]
+pub struct PackageUpdate {
+    pub package: Package,
+    /// How it differs from the previous version
+    pub changes: Vec<Discrepancy>,
+}
+
+/// Which notification channels to use
+#[derive(Serialize, Deserialize)]
+pub struct NotifySettings {
+    /// Print notifications to stdout. Useful from cron, which emails the
+    /// output.
+    #[serde(default = "default_stdout")]
+    pub stdout:      bool,
+    /// Ring the terminal bell
+    #[serde(default)]
+    pub bell:        bool,
+    /// Sound file to play (also rings the bell)
+    #[serde(default)]
+    pub sound:       Option<PathBuf>,
+    /// URL to POST every package update to, as JSON
+    #[serde(default)]
+    pub webhook:     Option<String>,
+    /// Shell command to run for every package update, with the notification
+    /// as JSON on stdin
+    #[serde(default)]
+    pub on_change:   Option<String>,
+    /// Push notifications to a phone through ntfy
+    #[serde(default)]
+    pub ntfy:        NtfySettings,
+    /// Push notifications through Pushover. The app token and user key are
+    /// credentials (`credentials.pushover.token` and `.user`).
+    #[serde(default)]
+    pub pushover:    bool,
+    /// URL of a Gotify server to send notifications to. The application's
+    /// token is a credential (`credentials.gotify.token`).
+    #[serde(default)]
+    pub gotify:      Option<String>,
+    /// Signal messages, sent with signal-cli
+    #[serde(default)]
+    pub signal:      SignalSettings,
+    /// Emails, sent over SMTP. Needs the `email` feature.
+    #[serde(default)]
+    pub email:       EmailSettings,
+    /// When to send the daily digest, in local time, if `packtrack watch` is
+    /// running
+    #[serde(default)]
+    pub digest_at:   Option<NaiveTime>,
+    /// When to hold back notifications that aren't urgent, e.g. at night
+    #[serde(default)]
+    pub quiet_hours: Option<QuietHours>,
+}
+impl Default for NotifySettings {
+    fn default() -> Self {
+        Self {
+            stdout:      default_stdout(),
+            bell:        false,
+            sound:       None,
+            webhook:     None,
+            on_change:   None,
+            ntfy:        NtfySettings::default(),
+            pushover:    false,
+            gotify:      None,
+            signal:      SignalSettings::default(),
+            email:       EmailSettings::default(),
+            digest_at:   None,
+            quiet_hours: None,
+        }
+    }
+}
+fn default_stdout() -> bool {
+    true
+}
+
+/// Where to publish ntfy notifications. An access token for servers that need
+/// one is a credential (`credentials.ntfy.token`), so that it can be kept in
+/// the OS keyring.
+#[derive(Serialize, Deserialize)]
+pub struct NtfySettings {
+    /// ntfy is only used if a topic is set. On the public server, anyone who
+    /// knows the topic can read it, so pick one that's hard to guess.
+    #[serde(default)]
+    pub topic:  Option<String>,
+    #[serde(default = "default_ntfy_server")]
+    pub server: String,
+}
+impl Default for NtfySettings {
+    fn default() -> Self {
+        Self {
+            topic:  None,
+            server: default_ntfy_server(),
+        }
+    }
+}
+fn default_ntfy_server() -> String {
+    "https://ntfy.sh".into()
+}
+
+/// Who sends and receives the Signal messages, and how
+#[derive(Serialize, Deserialize, Default)]
+pub struct SignalSettings {
+    /// The phone number of the account registered with signal-cli, e.g.
+    /// "+31612345678"
+    #[serde(default)]
+    pub account:   Option<String>,
+    /// Signal is only used if a recipient is set: a phone number, which may
+    /// be the account's own number
+    #[serde(default)]
+    pub recipient: Option<String>,
+    /// URL of the signal-cli REST API. If it isn't set, signal-cli is run.
+    #[serde(default)]
+    pub api:       Option<String>,
+}
+
+/// Where to send the emails, and through which SMTP server. The password is
+/// a credential (`credentials.email.password`), so that it can be kept in the
+/// OS keyring.
+#[derive(Serialize, Deserialize)]
+pub struct EmailSettings {
+    /// Email is only used if a recipient is set
+    #[serde(default)]
+    pub to:       Option<String>,
+    /// The sender's address. Defaults to the recipient.
+    #[serde(default)]
+    pub from:     Option<String>,
+    /// e.g. "smtp.gmail.com"
+    #[serde(default)]
+    pub server:   Option<String>,
+    /// 587 for STARTTLS, or 465 for TLS from the start
+    #[serde(default = "default_smtp_port")]
+    pub port:     u16,
+    /// Defaults to the sender's address
+    #[serde(default)]
+    pub username: Option<String>,
+}
+impl Default for EmailSettings {
+    fn default() -> Self {
+        Self {
+            to:       None,
+            from:     None,
+            server:   None,
+            port:     default_smtp_port(),
+            username: None,
+        }
+    }
+}
+fn default_smtp_port() -> u16 {
+    587
+}
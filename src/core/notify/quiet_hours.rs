@@ -0,0 +1,232 @@
+use crate::error::Error;
+use crate::notify::{Notification, Priority};
+use chrono::{DateTime, Local, NaiveTime, TimeDelta, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// A daily period in local time during which only urgent notifications are
+/// sent, e.g. "22:00-08:00". It may cross midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end:   NaiveTime,
+}
+
+impl QuietHours {
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        match self.start <= self.end {
+            true => self.start <= time && time < self.end,
+            false => time >= self.start || time < self.end,
+        }
+    }
+
+    /// When the quiet hours that `now` falls in end
+    pub fn end_after<Tz: TimeZone>(&self, now: &DateTime<Tz>) -> DateTime<Tz> {
+        let today = now.date_naive();
+        let end = match now.time() < self.end {
+            true => today.and_time(self.end),
+            false => (today + TimeDelta::days(1)).and_time(self.end),
+        };
+        end.and_local_timezone(now.timezone())
+            .earliest()
+            // The end falls in a DST gap, so it's an hour later
+            .unwrap_or_else(|| now.clone() + TimeDelta::hours(1))
+    }
+}
+
+impl FromStr for QuietHours {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        let error =
+            || format!("Couldn't parse quiet hours: {s}. Try e.g. 22:00-08:00");
+        let (start, end) = s.split_once('-').ok_or_else(error)?;
+        let parse = |time: &str| {
+            NaiveTime::parse_from_str(time.trim(), "%H:%M").map_err(|_| error())
+        };
+        Ok(Self {
+            start: parse(start)?,
+            end:   parse(end)?,
+        })
+    }
+}
+
+impl TryFrom<String> for QuietHours {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Self, Error> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for QuietHours {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}-{}",
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M")
+        )
+    }
+}
+
+impl From<QuietHours> for String {
+    fn from(hours: QuietHours) -> Self {
+        hours.to_string()
+    }
+}
+
+/// Holds back the normal priority notifications during quiet hours, for
+/// modes where packtrack keeps running. They're sent as one digest once the
+/// quiet hours are over. Urgent notifications, and low priority ones (which
+/// only go to channels for automation), are never held.
+pub struct QuietHoursQueue {
+    hours: Option<QuietHours>,
+    held:  Vec<Notification>,
+}
+
+impl QuietHoursQueue {
+    pub fn new(hours: Option<QuietHours>) -> Self {
+        Self {
+            hours,
+            held: vec![],
+        }
+    }
+
+    fn is_quiet(&self, now: &DateTime<Local>) -> bool {
+        self.hours
+            .is_some_and(|hours| hours.contains(now.time()))
+    }
+
+    /// The notifications to send now: the digest of the held ones if the
+    /// quiet hours are over, and the new ones that don't have to wait
+    pub fn submit(
+        &mut self,
+        notifications: Vec<Notification>,
+        now: &DateTime<Local>,
+    ) -> Vec<Notification> {
+        let quiet = self.is_quiet(now);
+        let mut send: Vec<Notification> =
+            self.digest(now).into_iter().collect();
+        for notification in notifications {
+            match quiet && notification.priority == Priority::Normal {
+                true => self.held.push(notification),
+                false => send.push(notification),
+            }
+        }
+        send
+    }
+
+    /// When the held notifications can be sent, if any are held
+    pub fn release_at(&self, now: &DateTime<Local>) -> Option<DateTime<Local>> {
+        match self.held.is_empty() {
+            true => None,
+            false => self
+                .hours
+                .map(|hours| hours.end_after(now)),
+        }
+    }
+
+    /// One notification summarizing the held ones, unless it's still quiet
+    fn digest(&mut self, now: &DateTime<Local>) -> Option<Notification> {
+        if self.held.is_empty() || self.is_quiet(now) {
+            return None;
+        }
+        let held = std::mem::take(&mut self.held);
+        let body = held
+            .iter()
+            .map(|n| format!("{}: {}", n.title, n.body))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let title = match held.len() {
+            1 => "1 update during quiet hours".into(),
+            n => format!("{n} updates during quiet hours"),
+        };
+        Some(Notification {
+            title,
+            body,
+            url: None,
+            priority: Priority::Normal,
+            update: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notification(title: &str, priority: Priority) -> Notification {
+        Notification {
+            title: title.into(),
+            body: "PostNL ABC".into(),
+            url: None,
+            priority,
+            update: None,
+        }
+    }
+
+    fn local(s: &str) -> DateTime<Local> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_quiet_hours() -> crate::Result<()> {
+        let night: QuietHours = "22:00-08:00".parse()?;
+        assert_eq!(night.to_string(), "22:00-08:00");
+        let time = |s| NaiveTime::parse_from_str(s, "%H:%M").unwrap();
+        assert!(night.contains(time("23:30")));
+        assert!(night.contains(time("07:59")));
+        assert!(!night.contains(time("08:00")));
+        assert!(!night.contains(time("12:00")));
+        let lunch: QuietHours = "12:00-13:00".parse()?;
+        assert!(lunch.contains(time("12:30")));
+        assert!(!lunch.contains(time("23:30")));
+        for bad in ["", "22:00", "22-08", "22:00-8"] {
+            assert!(bad.parse::<QuietHours>().is_err(), "{bad}");
+        }
+
+        let now = local("2026-10-16T23:00:00+02:00");
+        assert_eq!(night.end_after(&now), local("2026-10-17T08:00:00+02:00"));
+        let now = local("2026-10-17T03:00:00+02:00");
+        assert_eq!(night.end_after(&now), local("2026-10-17T08:00:00+02:00"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_quiet_hours_queue() -> crate::Result<()> {
+        let mut queue = QuietHoursQueue::new(Some("22:00-08:00".parse()?));
+        let night = local("2026-10-16T23:00:00+02:00");
+        let sent = queue.submit(
+            vec![
+                notification("Package delivered", Priority::Normal),
+                notification("Package updated", Priority::Low),
+                notification("Collect your package today", Priority::High),
+            ],
+            &night,
+        );
+        let titles: Vec<_> = sent
+            .iter()
+            .map(|n| n.title.as_str())
+            .collect();
+        assert_eq!(titles, ["Package updated", "Collect your package today"]);
+        assert_eq!(
+            queue.release_at(&night),
+            Some(local("2026-10-17T08:00:00+02:00"))
+        );
+        assert!(queue.submit(vec![], &night).is_empty());
+
+        let morning = local("2026-10-17T08:00:00+02:00");
+        let sent = queue.submit(
+            vec![notification("Package out for delivery", Priority::Normal)],
+            &morning,
+        );
+        assert_eq!(sent.len(), 2);
+        assert_eq!(sent[0].title, "1 update during quiet hours");
+        assert_eq!(sent[0].body, "Package delivered: PostNL ABC");
+        assert_eq!(queue.release_at(&morning), None);
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+use crate::Result;
+use crate::cache::get_cache_dir;
+use crate::notify::Notification;
+use crate::utils::UtcTime;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// The notifications that were already sent, by key (see
+/// `Notification::key`), and when. Kept in a file, so that a restarted
+/// `packtrack watch`, or another one running alongside it, doesn't send
+/// them again.
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct SentNotifications {
+    sent: BTreeMap<String, UtcTime>,
+}
+
+impl SentNotifications {
+    /// Load the state, or start afresh if there isn't any yet
+    pub fn load(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Ok(serde_json::from_str(&text)?),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                Ok(Self::default())
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn is_sent(&self, notification: &Notification) -> bool {
+        self.sent
+            .contains_key(&notification.key())
+    }
+
+    pub fn mark_sent(&mut self, notification: &Notification, now: UtcTime) {
+        self.sent
+            .insert(notification.key(), now);
+    }
+
+    /// Forget the notifications sent before `cutoff` (or all of them),
+    /// returning how many were forgotten
+    pub fn forget_sent_before(&mut self, cutoff: Option<UtcTime>) -> usize {
+        let before = self.sent.len();
+        self.sent
+            .retain(|_, sent| cutoff.is_some_and(|cutoff| *sent >= cutoff));
+        before - self.sent.len()
+    }
+
+    /// Add the notifications sent by another copy (e.g. from another
+    /// machine), returning how many were new
+    pub fn merge(&mut self, other: Self) -> usize {
+        let before = self.sent.len();
+        for (key, sent) in other.sent {
+            self.sent
+                .entry(key)
+                .and_modify(|ours| *ours = (*ours).min(sent))
+                .or_insert(sent);
+        }
+        self.sent.len() - before
+    }
+}
+
+/// Where the sent notifications are kept
+pub fn get_sent_notifications_file() -> Result<PathBuf> {
+    Ok(get_cache_dir()?.join("packtrack-notified.json"))
+}
+
+/// FNV-1a, because the keys are stored, and the standard library's hashers
+/// may change between Rust versions
+pub(crate) fn stable_hash(text: &str) -> u64 {
+    text.bytes()
+        .fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notify::Priority;
+    use chrono::{TimeDelta, Utc};
+
+    #[test]
+    fn test_sent_notifications() -> Result<()> {
+        let now = Utc::now();
+        let notification = Notification {
+            title:    "Package delivered".into(),
+            body:     "PostNL ABC: Delivered".into(),
+            url:      Some("url".into()),
+            priority: Priority::Normal,
+            update:   None,
+        };
+        let mut sent = SentNotifications::default();
+        assert!(!sent.is_sent(&notification));
+        sent.mark_sent(&notification, now - TimeDelta::days(40));
+        assert!(sent.is_sent(&notification));
+
+        let path = std::env::temp_dir().join(format!(
+            "packtrack-notified-test-{}.json",
+            std::process::id()
+        ));
+        sent.save(&path)?;
+        let mut loaded = SentNotifications::load(&path)?;
+        assert_eq!(loaded, sent);
+        std::fs::remove_file(&path)?;
+
+        assert_eq!(
+            loaded.forget_sent_before(Some(now - TimeDelta::days(30))),
+            1
+        );
+        assert!(!loaded.is_sent(&notification));
+        assert_eq!(stable_hash("packtrack"), 0x3afc3d3518efa367);
+        Ok(())
+    }
+}
@@ -0,0 +1,17 @@
+use crate::Result;
+use crate::notify::{Notification, Priority};
+use async_trait::async_trait;
+
+/// A channel that notifications can be sent through
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    /// e.g. "stdout"
+    fn name(&self) -> &str;
+
+    /// Notifications below this priority aren't sent through this channel
+    fn min_priority(&self) -> Priority {
+        Priority::Normal
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<()>;
+}
@@ -0,0 +1,167 @@
+use crate::Result;
+use crate::notify::{
+    BellNotifier, CommandNotifier, GotifyNotifier, Notification, Notifier,
+    NotifySettings, NtfyNotifier, PushoverNotifier, SignalNotifier,
+    StdoutNotifier, WebhookNotifier,
+};
+use crate::tracker::Credentials;
+
+/// Create the notifiers enabled in the settings. Channels that need secrets
+/// get them from the `credentials`, and fail if they're missing.
+pub fn get_notifiers(
+    settings: &NotifySettings,
+    credentials: &Credentials,
+) -> Result<Vec<Box<dyn Notifier>>> {
+    let mut notifiers: Vec<Box<dyn Notifier>> = vec![];
+    if settings.stdout {
+        notifiers.push(Box::new(StdoutNotifier));
+    }
+    if settings.bell || settings.sound.is_some() {
+        notifiers.push(Box::new(BellNotifier::new(settings.sound.clone())));
+    }
+    if let Some(url) = &settings.webhook {
+        notifiers.push(Box::new(WebhookNotifier::new(url.clone())));
+    }
+    if let Some(command) = &settings.on_change {
+        notifiers.push(Box::new(CommandNotifier::new(command.clone())));
+    }
+    if let Some(topic) = &settings.ntfy.topic {
+        notifiers.push(Box::new(NtfyNotifier::new(
+            settings.ntfy.server.clone(),
+            topic.clone(),
+            credentials.get("ntfy", "token"),
+        )));
+    }
+    if settings.pushover {
+        notifiers.push(Box::new(PushoverNotifier::new(
+            credentials.require("pushover", "token")?,
+            credentials.require("pushover", "user")?,
+        )));
+    }
+    if let Some(server) = &settings.gotify {
+        notifiers.push(Box::new(GotifyNotifier::new(
+            server.clone(),
+            credentials.require("gotify", "token")?,
+        )));
+    }
+    if let Some(recipient) = &settings.signal.recipient {
+        let account = settings
+            .signal
+            .account
+            .clone()
+            .ok_or("Set notify.signal.account to send Signal messages")?;
+        notifiers.push(Box::new(SignalNotifier::new(
+            account,
+            recipient.clone(),
+            settings.signal.api.clone(),
+        )));
+    }
+    if settings.email.to.is_some() {
+        #[cfg(feature = "email")]
+        notifiers.push(Box::new(crate::notify::EmailNotifier::new(
+            &settings.email,
+            credentials.get("email", "password"),
+        )?));
+        #[cfg(not(feature = "email"))]
+        return Err("Sending emails needs the `email` feature. Reinstall \
+                    packtrack with `--features email` to enable this."
+            .into());
+    }
+    Ok(notifiers)
+}
+
+/// Send the notification through all the notifiers that take its priority.
+/// A failing notifier doesn't stop the others; the notification counts as
+/// sent if any of them succeeded.
+pub async fn notify_all(
+    notifiers: &[Box<dyn Notifier>],
+    notification: &Notification,
+) -> Result<()> {
+    if notifiers.is_empty() {
+        return Err("No notifiers are enabled".into());
+    }
+    let notifiers: Vec<&Box<dyn Notifier>> = notifiers
+        .iter()
+        .filter(|notifier| notification.priority >= notifier.min_priority())
+        .collect();
+    if notifiers.is_empty() {
+        return Ok(());
+    }
+    let results = futures::future::join_all(
+        notifiers
+            .iter()
+            .map(|notifier| notifier.send(notification)),
+    )
+    .await;
+    let mut errors = vec![];
+    for (notifier, result) in notifiers.iter().zip(results) {
+        if let Err(err) = result {
+            log::warn!("Couldn't notify via {}: {err}", notifier.name());
+            errors.push(format!("{}: {err}", notifier.name()));
+        }
+    }
+    match errors.len() < notifiers.len() {
+        true => Ok(()),
+        false => {
+            Err(format!("Couldn't send notification: {}", errors.join(", "))
+                .into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notify::Priority;
+    use async_trait::async_trait;
+
+    struct FailingNotifier;
+    #[async_trait]
+    impl Notifier for FailingNotifier {
+        fn name(&self) -> &str {
+            "failing"
+        }
+        async fn send(&self, _notification: &Notification) -> Result<()> {
+            Err("offline".into())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_notify_all() {
+        let notification = Notification {
+            title:    "Title".into(),
+            body:     "Body".into(),
+            url:      None,
+            priority: Priority::Normal,
+            update:   None,
+        };
+        let failing: Vec<Box<dyn Notifier>> = vec![Box::new(FailingNotifier)];
+        let err = notify_all(&failing, &notification)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Couldn't send notification: failing: offline"
+        );
+
+        let mixed: Vec<Box<dyn Notifier>> =
+            vec![Box::new(FailingNotifier), Box::new(StdoutNotifier)];
+        assert!(
+            notify_all(&mixed, &notification)
+                .await
+                .is_ok()
+        );
+        assert!(
+            notify_all(&[], &notification)
+                .await
+                .is_err()
+        );
+
+        // Low priority notifications skip the notifiers that don't take them
+        let low = Notification {
+            priority: Priority::Low,
+            ..notification
+        };
+        assert!(notify_all(&failing, &low).await.is_ok());
+    }
+}
@@ -0,0 +1,220 @@
+// The machine-readable representation of tracking results, and its JSON
+// Schema. Integrations pin themselves to `SCHEMA_VERSION`, so any change to
+// the serialized shape of `JobOutput` (or the models it contains) that isn't
+// backwards compatible must bump it. The published copy of the schema in
+// docs/schema is checked against the generated one in the tests.
+
+use crate::Result;
+use crate::api::Job;
+use crate::error::Error;
+use crate::tracker::Package;
+use schemars::{JsonSchema, Schema, schema_for};
+use serde::Serialize;
+
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// The result of tracking one URL
+#[derive(Serialize, JsonSchema)]
+#[schemars(title = "packtrack job")]
+pub struct JobOutput {
+    /// Version of this schema
+    pub schema_version: u32,
+    /// Tracking URL
+    pub url:            String,
+    /// Short name for the package from the urls file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label:          Option<String>,
+    /// Tags from the urls file
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags:           Vec<String>,
+    /// Notes from the urls file
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub notes:          Vec<String>,
+    /// Description from the urls file
+    pub description:    Option<String>,
+    /// The tracked package, unless tracking failed
+    pub package:        Option<Package>,
+    /// Why tracking failed, if it did
+    pub error:          Option<String>,
+    /// What kind of failure the `error` is, for scripts to act on
+    pub error_kind:     Option<ErrorKind>,
+}
+
+/// The broad kinds of tracking failures
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// The carrier couldn't be reached, or answered with an HTTP error
+    Network,
+    /// The carrier's response couldn't be understood
+    Parse,
+    /// The URL isn't one packtrack can track
+    Url,
+    /// Reading or writing a local file failed
+    Io,
+    /// Anything else, e.g. the carrier doesn't know the package
+    Other,
+}
+
+impl From<&Error> for ErrorKind {
+    fn from(err: &Error) -> Self {
+        match err {
+            Error::Reqwest(_) => Self::Network,
+            Error::SerdeJson(_) | Error::Chrono(_) | Error::Parse(_) => {
+                Self::Parse
+            }
+            Error::Url(_) => Self::Url,
+            Error::Io(_) => Self::Io,
+            Error::Custom(_) | Error::Regex(_) => Self::Other,
+        }
+    }
+}
+
+impl From<&Job> for JobOutput {
+    fn from(job: &Job) -> Self {
+        let (package, error, error_kind) = match &job.result {
+            Ok(package) => (Some(package.clone()), None, None),
+            Err(err) => (None, Some(err.to_string()), Some(err.into())),
+        };
+        Self {
+            schema_version: SCHEMA_VERSION,
+            url: job.url.url.clone(),
+            label: job.url.label.clone(),
+            tags: job.url.tags.clone(),
+            notes: job.url.notes.clone(),
+            description: job.url.description.clone(),
+            package,
+            error,
+            error_kind,
+        }
+    }
+}
+
+/// The jobs as a pretty-printed JSON array of `JobOutput`s
+pub fn jobs_to_json(jobs: &[Job]) -> Result<String> {
+    let outputs: Vec<JobOutput> = jobs
+        .iter()
+        .map(JobOutput::from)
+        .collect();
+    Ok(serde_json::to_string_pretty(&outputs)?)
+}
+
+/// The job as a `JobOutput` on a single line, for streaming JSON Lines
+pub fn job_to_json_line(job: &Job) -> Result<String> {
+    Ok(serde_json::to_string(&JobOutput::from(job))?)
+}
+
+/// The jobs as a YAML list of `JobOutput`s, with the same fields as the JSON
+pub fn jobs_to_yaml(jobs: &[Job]) -> Result<String> {
+    let outputs: Vec<JobOutput> = jobs
+        .iter()
+        .map(JobOutput::from)
+        .collect();
+    serde_norway::to_string(&outputs)
+        .map_err(|err| format!("Couldn't write YAML: {err}").into())
+}
+
+/// The JSON Schema for one `JobOutput`
+pub fn job_schema() -> Schema {
+    let mut schema = schema_for!(JobOutput);
+    schema.insert(
+        "$id".into(),
+        format!(
+            "https://binnev.github.io/packtrack/schema/job-v{SCHEMA_VERSION}\
+             .schema.json"
+        )
+        .into(),
+    );
+    schema
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tracker::PackageStatus;
+
+    #[test]
+    fn test_published_schema_is_up_to_date() {
+        let published: serde_json::Value = serde_json::from_str(include_str!(
+            "../../docs/schema/job-v1.schema.json"
+        ))
+        .unwrap();
+        assert_eq!(
+            published,
+            job_schema().to_value(),
+            "Regenerate docs/schema with `packtrack schema`"
+        );
+    }
+
+    #[test]
+    fn test_job_output() {
+        let job = Job {
+            url:    "https://example.com/123".into(),
+            result: Err("HTTP 503".into()),
+        };
+        let output = serde_json::to_value(JobOutput::from(&job)).unwrap();
+        assert_eq!(output["schema_version"], SCHEMA_VERSION);
+        assert_eq!(output["url"], "https://example.com/123");
+        assert_eq!(output["error"], "HTTP 503");
+        assert_eq!(output["error_kind"], "other");
+        assert!(output["package"].is_null());
+
+        let status = serde_json::to_value(PackageStatus::ReadyForPickup {
+            location: "Bookshop".into(),
+        })
+        .unwrap();
+        assert_eq!(status["ReadyForPickup"]["location"], "Bookshop");
+    }
+
+    #[test]
+    fn test_jobs_to_json() -> Result<()> {
+        let jobs = vec![
+            Job {
+                url:    "https://example.com/123".into(),
+                result: Err(serde_json::from_str::<u32>("{")
+                    .unwrap_err()
+                    .into()),
+            },
+            Job {
+                url:    "https://example.com/456".into(),
+                result: Err("Package not found".into()),
+            },
+        ];
+        let output: serde_json::Value =
+            serde_json::from_str(&jobs_to_json(&jobs)?)?;
+        let outputs = output.as_array().unwrap();
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(outputs[0]["url"], "https://example.com/123");
+        assert_eq!(outputs[0]["error_kind"], "parse");
+        assert_eq!(outputs[1]["error_kind"], "other");
+        Ok(())
+    }
+
+    #[test]
+    fn test_job_to_json_line() -> Result<()> {
+        let job = Job {
+            url:    "https://example.com/456".into(),
+            result: Err("Package not found".into()),
+        };
+        let line = job_to_json_line(&job)?;
+        assert!(!line.contains('\n'));
+        let output: serde_json::Value = serde_json::from_str(&line)?;
+        assert_eq!(output["url"], "https://example.com/456");
+        assert_eq!(output["error"], "Package not found");
+        Ok(())
+    }
+
+    #[test]
+    fn test_jobs_to_yaml() -> Result<()> {
+        let jobs = vec![Job {
+            url:    "https://example.com/456".into(),
+            result: Err("Package not found".into()),
+        }];
+        let yaml = jobs_to_yaml(&jobs)?;
+        assert!(yaml.starts_with("- schema_version: 1\n"));
+        assert!(yaml.contains("  url: https://example.com/456\n"));
+        assert!(yaml.contains("  error: Package not found\n"));
+        assert!(yaml.contains("  error_kind: other\n"));
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+// How often to poll each package when packtrack keeps running, e.g. in watch
+// mode. Packages are polled more often as their delivery gets closer, and
+// not at all once they're delivered, so that the carriers aren't hammered
+// for packages that won't change for days.
+
+use crate::api::Job;
+use crate::tracker::Package;
+use crate::utils::UtcTime;
+use chrono::TimeDelta;
+use serde::{Deserialize, Serialize};
+
+/// Limits on the polling interval
+#[derive(Serialize, Deserialize)]
+pub struct PollingSettings {
+    /// Shortest interval, used while a package is being delivered
+    #[serde(default = "default_min_minutes")]
+    pub min_minutes: i64,
+    /// Longest interval, used for packages that aren't expected soon
+    #[serde(default = "default_max_minutes")]
+    pub max_minutes: i64,
+}
+impl Default for PollingSettings {
+    fn default() -> Self {
+        Self {
+            min_minutes: default_min_minutes(),
+            max_minutes: default_max_minutes(),
+        }
+    }
+}
+fn default_min_minutes() -> i64 {
+    5
+}
+fn default_max_minutes() -> i64 {
+    60
+}
+
+impl PollingSettings {
+    fn min(&self) -> TimeDelta {
+        TimeDelta::minutes(self.min_minutes)
+    }
+
+    pub(crate) fn max(&self) -> TimeDelta {
+        TimeDelta::minutes(self.max_minutes.max(self.min_minutes))
+    }
+}
+
+/// How long to wait before polling the package again, or None if it will
+/// not change anymore.
+///
+/// Within the delivery window (or after it, if the package is late) this is
+/// the minimum interval. Before the window it's a quarter of the time left
+/// until the window starts, so the package is polled more and more often as
+/// the window approaches. Packages without an ETA use the maximum interval.
+pub fn poll_interval(
+    package: &Package,
+    now: UtcTime,
+    settings: &PollingSettings,
+) -> Option<TimeDelta> {
+    if package.status.is_final() {
+        return None;
+    }
+    let start = package.eta.or(package
+        .eta_window
+        .as_ref()
+        .map(|w| w.start));
+    let interval = match start {
+        Some(start) if start > now => (start - now) / 4,
+        Some(_) => settings.min(),
+        None => settings.max(),
+    };
+    Some(interval.clamp(settings.min(), settings.max()))
+}
+
+/// When the job should be polled next, given when it was last polled. Jobs
+/// that failed are retried after the maximum interval.
+pub fn next_poll(
+    job: &Job,
+    last_polled: UtcTime,
+    settings: &PollingSettings,
+) -> Option<UtcTime> {
+    let interval = match &job.result {
+        Ok(package) => poll_interval(package, last_polled, settings)?,
+        Err(_) => settings.max(),
+    };
+    Some(last_polled + interval)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tracker::{PackageStatus, TimeWindow};
+
+    fn package(status: PackageStatus, window_start: Option<&str>) -> Package {
+        Package {
+            barcode: "ABC".into(),
+            channel: "PostNL".into(),
+            status,
+            sender: None,
+            recipient: None,
+            eta: None,
+            eta_window: window_start.map(|start| {
+                let start: UtcTime = start.parse().unwrap();
+                TimeWindow {
+                    start,
+                    end: start + TimeDelta::hours(2),
+                }
+            }),
+            delivered: None,
+            events: vec![],
+            pickup: None,
+        }
+    }
+
+    #[test]
+    fn test_poll_interval() {
+        use PackageStatus::*;
+        let now: UtcTime = "2026-10-16T10:00:00Z".parse().unwrap();
+        let settings = PollingSettings::default();
+        let interval = |package: Package| {
+            poll_interval(&package, now, &settings).map(|i| i.num_minutes())
+        };
+        assert_eq!(interval(package(Delivered, None)), None);
+        assert_eq!(interval(package(InTransit, None)), Some(60));
+        // Tomorrow
+        assert_eq!(
+            interval(package(InTransit, Some("2026-10-17T10:00:00Z"))),
+            Some(60)
+        );
+        // In two hours
+        assert_eq!(
+            interval(package(InTransit, Some("2026-10-16T12:00:00Z"))),
+            Some(30)
+        );
+        // In 10 minutes
+        assert_eq!(
+            interval(package(InTransit, Some("2026-10-16T10:10:00Z"))),
+            Some(5)
+        );
+        // Being delivered now
+        assert_eq!(
+            interval(package(InTransit, Some("2026-10-16T09:30:00Z"))),
+            Some(5)
+        );
+    }
+
+    #[test]
+    fn test_next_poll() {
+        let now: UtcTime = "2026-10-16T10:00:00Z".parse().unwrap();
+        let settings = PollingSettings {
+            min_minutes: 2,
+            max_minutes: 20,
+        };
+        let job = |result| Job {
+            url: "url".into(),
+            result,
+        };
+        let failed = job(Err("HTTP 503".into()));
+        assert_eq!(
+            next_poll(&failed, now, &settings),
+            Some(now + TimeDelta::minutes(20))
+        );
+        let delivered = job(Ok(package(PackageStatus::Delivered, None)));
+        assert_eq!(next_poll(&delivered, now, &settings), None);
+    }
+}
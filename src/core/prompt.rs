@@ -0,0 +1,128 @@
+// A very short summary for shell prompts (e.g. a Starship custom module).
+// Prompts are drawn before every command, so this only looks at the cache and
+// never goes to the network.
+
+use crate::cache::Cache;
+use crate::tracker::TrackerRegistry;
+use crate::url_store::AnnotatedUrl;
+use crate::utils::UtcTime;
+use chrono::Local;
+use std::fmt::Display;
+
+/// Counts of the packages that are still on their way
+#[derive(Debug, Default, PartialEq)]
+pub struct PromptSummary {
+    /// Packages that haven't been delivered yet
+    pub in_progress:    usize,
+    /// Packages expected to arrive today
+    pub arriving_today: usize,
+}
+
+impl PromptSummary {
+    /// Summarise the URLs using the latest cached response for each, however
+    /// old it is. URLs that aren't in the cache are left out.
+    pub fn from_cache(
+        urls: &[AnnotatedUrl],
+        cache: &dyn Cache,
+        registry: &TrackerRegistry,
+        now: UtcTime,
+    ) -> Self {
+        let today = now.with_timezone(&Local).date_naive();
+        let mut summary = Self::default();
+        for url in urls {
+            let Some(entry) = cache.get(&url.url) else {
+                continue;
+            };
+            let Ok(package) = registry
+                .get_handler(&url.url)
+                .and_then(|tracker| tracker.parse(entry.text.clone()))
+            else {
+                continue;
+            };
+            if package.status.is_final() {
+                continue;
+            }
+            summary.in_progress += 1;
+            let expected = package.eta.or(package
+                .eta_window
+                .as_ref()
+                .map(|w| w.start));
+            if expected
+                .is_some_and(|t| t.with_timezone(&Local).date_naive() == today)
+            {
+                summary.arriving_today += 1;
+            }
+        }
+        summary
+    }
+}
+
+/// "📦2↓1": 2 packages on their way, 1 of them arriving today. Empty if there
+/// are no packages on their way, so the prompt segment disappears.
+impl Display for PromptSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.in_progress == 0 {
+            return Ok(());
+        }
+        write!(f, "📦{}", self.in_progress)?;
+        if self.arriving_today > 0 {
+            write!(f, "↓{}", self.arriving_today)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::MemoryCache;
+    use crate::mocks::load_text;
+
+    #[test]
+    fn test_prompt_summary() -> crate::Result<()> {
+        let in_transit = "https://jouw.postnl.nl/track-and-trace/3SABCD1234567";
+        let delivered = "https://jouw.postnl.nl/track-and-trace/3SABCD7654321";
+        let uncached = "https://jouw.postnl.nl/track-and-trace/3SABCD0000000";
+        let mut cache = MemoryCache::default();
+        cache.insert(in_transit.into(), load_text("postnl_undelivered.json")?);
+        cache.insert(delivered.into(), load_text("postnl_delivered.json")?);
+        let urls: Vec<AnnotatedUrl> =
+            vec![in_transit.into(), delivered.into(), uncached.into()];
+
+        let registry = TrackerRegistry::default();
+        let summary = PromptSummary::from_cache(
+            &urls,
+            &cache,
+            &registry,
+            "2000-01-01T12:00:00Z".parse().unwrap(),
+        );
+        assert_eq!(
+            summary,
+            PromptSummary {
+                in_progress:    1,
+                arriving_today: 0,
+            }
+        );
+        assert_eq!(summary.to_string(), "📦1");
+
+        // On the day it's expected
+        let package = registry
+            .get_handler(in_transit)?
+            .parse(load_text("postnl_undelivered.json")?)?;
+        let expected = package
+            .eta
+            .or(package.eta_window.map(|w| w.start))
+            .unwrap();
+        let summary =
+            PromptSummary::from_cache(&urls, &cache, &registry, expected);
+        assert_eq!(summary.to_string(), "📦1↓1");
+
+        let summary = PromptSummary {
+            in_progress:    2,
+            arriving_today: 1,
+        };
+        assert_eq!(summary.to_string(), "📦2↓1");
+        assert_eq!(PromptSummary::default().to_string(), "");
+        Ok(())
+    }
+}
@@ -0,0 +1,109 @@
+// Deleting the personal data packtrack keeps on disk: the cached carrier
+// responses (which contain names and addresses) and the notification state.
+// The urls file is left alone, because the user manages that themselves.
+
+use crate::cache::Cache;
+use crate::notify::SentNotifications;
+use crate::reminders::ReminderState;
+use crate::utils::UtcTime;
+
+/// What was deleted
+#[derive(Debug, Default, PartialEq)]
+pub struct PurgeReport {
+    pub cache_entries: usize,
+    pub reminders:     usize,
+    pub notifications: usize,
+}
+
+impl PurgeReport {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Delete everything recorded before `cutoff`, or everything if there is no
+/// cutoff
+pub fn purge(
+    cache: &mut dyn Cache,
+    reminders: &mut ReminderState,
+    notifications: &mut SentNotifications,
+    cutoff: Option<UtcTime>,
+) -> PurgeReport {
+    let cache_entries = match cutoff {
+        Some(cutoff) => cache.remove_older_than(cutoff),
+        None => {
+            let count = cache
+                .get_all_urls()
+                .iter()
+                .map(|url| cache.get_all(url).len())
+                .sum();
+            cache.clear();
+            count
+        }
+    };
+    PurgeReport {
+        cache_entries,
+        reminders: reminders.forget_sent_before(cutoff),
+        notifications: notifications.forget_sent_before(cutoff),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::MemoryCache;
+    use crate::notify::{Notification, Priority};
+    use chrono::{TimeDelta, Utc};
+
+    #[test]
+    fn test_purge() {
+        let now = Utc::now();
+        let mut cache = MemoryCache::default();
+        cache.insert("a".into(), "1".into());
+        cache.insert("a".into(), "2".into());
+        cache.insert("b".into(), "3".into());
+        let mut reminders = ReminderState::default();
+        reminders.mark_sent("a", now - TimeDelta::days(100));
+        reminders.mark_sent("b", now - TimeDelta::days(1));
+        let mut notifications = SentNotifications::default();
+        notifications.mark_sent(
+            &Notification {
+                title:    "Package delivered".into(),
+                body:     "PostNL ABC: Delivered".into(),
+                url:      Some("a".into()),
+                priority: Priority::Normal,
+                update:   None,
+            },
+            now - TimeDelta::days(1),
+        );
+
+        let cutoff = now - TimeDelta::days(90);
+        let report =
+            purge(&mut cache, &mut reminders, &mut notifications, Some(cutoff));
+        assert_eq!(
+            report,
+            PurgeReport {
+                cache_entries: 0,
+                reminders:     1,
+                notifications: 0,
+            }
+        );
+        assert_eq!(cache.get_all_urls().len(), 2);
+
+        let report =
+            purge(&mut cache, &mut reminders, &mut notifications, None);
+        assert_eq!(
+            report,
+            PurgeReport {
+                cache_entries: 3,
+                reminders:     1,
+                notifications: 1,
+            }
+        );
+        assert!(cache.get_all_urls().is_empty());
+        assert!(
+            purge(&mut cache, &mut reminders, &mut notifications, None)
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,240 @@
+// Reminders to collect packages from pickup points before they are returned
+// to the sender. Meant to be run regularly (e.g. from cron), so we remember
+// when each package was last reminded about, and don't nag more than once per
+// `repeat_after`.
+
+use crate::Result;
+use crate::api::Job;
+use crate::cache::get_cache_dir;
+use crate::notify::{Notification, Priority};
+use crate::tracker::PackageStatus;
+use crate::utils::UtcTime;
+use chrono::{Local, TimeDelta};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// When to send pickup reminders
+pub struct ReminderOptions {
+    /// Remind when the deadline is closer than this
+    pub within:       TimeDelta,
+    /// Remind about the same package again after this long
+    pub repeat_after: TimeDelta,
+}
+impl Default for ReminderOptions {
+    fn default() -> Self {
+        Self {
+            within:       TimeDelta::days(2),
+            repeat_after: TimeDelta::hours(20),
+        }
+    }
+}
+
+/// When each package (by URL) was last reminded about
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+pub struct ReminderState {
+    sent: BTreeMap<String, UtcTime>,
+}
+
+impl ReminderState {
+    /// Load the state, or start afresh if there isn't any yet
+    pub fn load(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Ok(serde_json::from_str(&text)?),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                Ok(Self::default())
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn mark_sent(&mut self, url: &str, now: UtcTime) {
+        self.sent.insert(url.into(), now);
+    }
+
+    /// Forget the reminders sent before `cutoff` (or all of them), returning
+    /// how many were forgotten
+    pub fn forget_sent_before(&mut self, cutoff: Option<UtcTime>) -> usize {
+        let before = self.sent.len();
+        self.sent
+            .retain(|_, sent| cutoff.is_some_and(|cutoff| *sent >= cutoff));
+        before - self.sent.len()
+    }
+}
+
+/// Where the reminder state is kept
+pub fn get_reminder_state_file() -> Result<PathBuf> {
+    Ok(get_cache_dir()?.join("packtrack-reminders.json"))
+}
+
+/// The reminders that are due for packages waiting at a pickup point, as
+/// (url, notification) pairs. Packages that are no longer waiting are
+/// forgotten, so they are reminded about again if they end up at a pickup
+/// point a second time.
+pub fn due_pickup_reminders<'j>(
+    jobs: impl IntoIterator<Item = &'j Job>,
+    state: &mut ReminderState,
+    options: &ReminderOptions,
+    now: UtcTime,
+) -> Vec<(String, Notification)> {
+    let mut due = vec![];
+    let mut waiting = vec![];
+    for job in jobs {
+        let Ok(package) = &job.result else {
+            continue;
+        };
+        if !matches!(package.status, PackageStatus::ReadyForPickup { .. }) {
+            continue;
+        }
+        let Some(pickup) = &package.pickup else {
+            continue;
+        };
+        let Some(deadline) = pickup.deadline else {
+            continue;
+        };
+        let url = &job.url.url;
+        waiting.push(url.clone());
+        let left = deadline - now;
+        if left > options.within {
+            continue;
+        }
+        if state
+            .sent
+            .get(url)
+            .is_some_and(|sent| now - *sent < options.repeat_after)
+        {
+            continue;
+        }
+
+        let mut name = job.url.package_name(package);
+        if let Some(description) = &job.url.description {
+            name += &format!(" ({description})");
+        }
+        let deadline_text = deadline
+            .with_timezone(&Local)
+            .format("%a %d %b %H:%M");
+        let (title, priority) = match left {
+            left if left < TimeDelta::zero() => {
+                ("Pickup deadline has passed".to_string(), Priority::High)
+            }
+            left if left < TimeDelta::days(1) => {
+                ("Collect your package today".to_string(), Priority::High)
+            }
+            _ => (
+                format!(
+                    "Collect your package by {}",
+                    deadline
+                        .with_timezone(&Local)
+                        .format("%A")
+                ),
+                Priority::Normal,
+            ),
+        };
+        let consequence = match left < TimeDelta::zero() {
+            true => format!(
+                "It was due to be collected by {deadline_text}, and may be \
+                 returned to the sender any moment."
+            ),
+            false => format!(
+                "It will be returned to the sender if it isn't collected by \
+                 {deadline_text}."
+            ),
+        };
+        let notification = Notification {
+            title,
+            body: format!(
+                "{name} is waiting at {}. {consequence}",
+                pickup.location
+            ),
+            url: Some(url.clone()),
+            priority,
+            update: None,
+        };
+        due.push((url.clone(), notification));
+    }
+    state
+        .sent
+        .retain(|url, _| waiting.contains(url));
+    due
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tracker::{Package, PickupPoint};
+    use crate::url_store::AnnotatedUrl;
+
+    fn job(url: &str, status: PackageStatus, deadline: &str) -> Job {
+        Job {
+            url:    AnnotatedUrl::new(url.into(), Some("books".into())),
+            result: Ok(Package {
+                barcode: "JVGL0123456789012345".into(),
+                channel: "DHL".into(),
+                status,
+                sender: None,
+                recipient: None,
+                eta: None,
+                eta_window: None,
+                delivered: None,
+                events: vec![],
+                pickup: Some(PickupPoint {
+                    location: "Bookshop".into(),
+                    deadline: Some(deadline.parse().unwrap()),
+                }),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_due_pickup_reminders() {
+        let waiting = || PackageStatus::ReadyForPickup {
+            location: "Bookshop".into(),
+        };
+        let jobs = vec![
+            job("soon", waiting(), "2026-10-17T18:00:00Z"),
+            job("later", waiting(), "2026-10-25T18:00:00Z"),
+            job(
+                "collected",
+                PackageStatus::Delivered,
+                "2026-10-17T18:00:00Z",
+            ),
+        ];
+        let now: UtcTime = "2026-10-16T07:00:00Z".parse().unwrap();
+        let options = ReminderOptions::default();
+        let mut state = ReminderState::default();
+        state.mark_sent("collected", now - TimeDelta::days(3));
+
+        let due = due_pickup_reminders(&jobs, &mut state, &options, now);
+        assert_eq!(due.len(), 1);
+        let (url, notification) = &due[0];
+        assert_eq!(url, "soon");
+        assert_eq!(notification.title, "Collect your package by Saturday");
+        assert_eq!(notification.priority, Priority::Normal);
+        assert!(
+            notification
+                .body
+                .starts_with("DHL JVGL0123456789012345 (books) is waiting at")
+        );
+        // Packages that aren't waiting anymore are forgotten
+        assert_eq!(state, ReminderState::default());
+
+        // Not again until `repeat_after` has passed
+        state.mark_sent("soon", now);
+        let later = now + TimeDelta::hours(2);
+        assert!(
+            due_pickup_reminders(&jobs, &mut state, &options, later).is_empty()
+        );
+        let tomorrow = now + TimeDelta::hours(24);
+        let due = due_pickup_reminders(&jobs, &mut state, &options, tomorrow);
+        assert_eq!(due[0].1.title, "Collect your package today");
+        assert_eq!(due[0].1.priority, Priority::High);
+    }
+}
@@ -0,0 +1,70 @@
+// The web dashboard at /. Its files are compiled into the binary, so that
+// `packtrack serve` is still a single file to deploy. It only uses the REST
+// API, like any other client.
+
+use axum::Router;
+use axum::http::header::CONTENT_TYPE;
+use axum::response::IntoResponse;
+use axum::routing::get;
+
+const INDEX: &str = include_str!("dashboard/index.html");
+const SCRIPT: &str = include_str!("dashboard/app.js");
+const STYLE: &str = include_str!("dashboard/style.css");
+
+/// Any state will do, as the dashboard itself is served without a token. It
+/// asks for one when the API does.
+pub fn routes<S: Clone + Send + Sync + 'static>() -> Router<S> {
+    Router::new()
+        .route("/", get(|| asset(INDEX, "text/html; charset=utf-8")))
+        .route(
+            "/dashboard/app.js",
+            get(|| asset(SCRIPT, "text/javascript; charset=utf-8")),
+        )
+        .route(
+            "/dashboard/style.css",
+            get(|| asset(STYLE, "text/css; charset=utf-8")),
+        )
+}
+
+async fn asset(
+    body: &'static str,
+    content_type: &'static str,
+) -> impl IntoResponse {
+    ([(CONTENT_TYPE, content_type)], body)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::server::test_utils::{demo_state, serve_state};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_dashboard() -> crate::Result<()> {
+        let (state, _) = demo_state(&[]);
+        let server = serve_state(Arc::new(state)).await?;
+        let client = reqwest::Client::new();
+        for (path, content_type) in [
+            ("/", "text/html"),
+            ("/dashboard/app.js", "text/javascript"),
+            ("/dashboard/style.css", "text/css"),
+        ] {
+            let response = client
+                .get(format!("{server}{path}"))
+                .send()
+                .await?;
+            assert!(response.status().is_success(), "{path}");
+            let header = response.headers()["content-type"]
+                .to_str()
+                .unwrap();
+            assert!(header.starts_with(content_type), "{path}: {header}");
+        }
+        let index = client
+            .get(&server)
+            .send()
+            .await?
+            .text()
+            .await?;
+        assert!(index.contains("/dashboard/app.js"));
+        Ok(())
+    }
+}
@@ -0,0 +1,231 @@
+// The packtrack dashboard: shows the packages from GET /packages grouped by
+// status, and adds and removes URLs through the REST API. Changes found by
+// the server arrive through GET /events, so the full refresh is only a
+// fallback. On servers with users, it asks for the user's API token and keeps
+// it in the browser.
+
+const REFRESH_SECONDS = 300;
+
+/** The jobs as last shown, to patch with the changes from /events */
+let shown = [];
+
+const TOKEN_KEY = "packtrack-token";
+let token = localStorage.getItem(TOKEN_KEY);
+let events = null;
+
+const GROUPS = [
+  ["ReadyForPickup", "Ready for pickup"],
+  ["InTransit", "In transit"],
+  ["Delivered", "Delivered"],
+  ["error", "Errors"],
+];
+
+/** The group of a job: the package's status, or "error" */
+function group(job) {
+  if (!job.package) return "error";
+  const status = job.package.status;
+  const name = typeof status === "string" ? status : Object.keys(status)[0];
+  return name === "DeliveredToNeighbour" ? "Delivered" : name;
+}
+
+function statusText(status) {
+  if (typeof status === "string") {
+    return status.replace(/([a-z])([A-Z])/g, "$1 $2").toLowerCase();
+  }
+  const [name, details] = Object.entries(status)[0];
+  const where = details.location ?? details.address;
+  return `${statusText(name)}: ${where}`;
+}
+
+function formatTime(time) {
+  return new Date(time).toLocaleString([], {
+    weekday: "short",
+    day: "numeric",
+    month: "short",
+    hour: "2-digit",
+    minute: "2-digit",
+  });
+}
+
+/** Create an element with the text, which is never parsed as HTML */
+function element(tag, text, className) {
+  const el = document.createElement(tag);
+  if (text !== undefined) el.textContent = text;
+  if (className) el.className = className;
+  return el;
+}
+
+function renderJob(job) {
+  const details = element("details");
+  const pkg = job.package;
+  const title = pkg
+    ? `${pkg.channel} ${pkg.barcode}` +
+      (job.description ? ` (${job.description})` : "")
+    : job.description ?? job.url;
+  const summary = element("summary", title);
+  details.append(summary);
+
+  const remove = element("button", "Remove", "remove");
+  remove.type = "button";
+  remove.addEventListener("click", () => removeUrl(job.url));
+  details.append(remove);
+
+  if (!pkg) {
+    details.append(element("p", job.error, "error"));
+    details.append(element("p", job.url, "meta"));
+    return details;
+  }
+  const meta = [statusText(pkg.status)];
+  if (pkg.eta) meta.push(`ETA ${formatTime(pkg.eta)}`);
+  if (pkg.delivered) meta.push(`delivered ${formatTime(pkg.delivered)}`);
+  if (pkg.pickup?.deadline) {
+    meta.push(`collect before ${formatTime(pkg.pickup.deadline)}`);
+  }
+  if (pkg.sender) meta.push(`from ${pkg.sender}`);
+  summary.append(element("div", meta.join(" · "), "meta"));
+
+  const timeline = element("ul", undefined, "timeline");
+  const events = [...pkg.events].sort((a, b) =>
+    b.timestamp.localeCompare(a.timestamp),
+  );
+  for (const event of events) {
+    const item = element("li", undefined, "event");
+    item.append(element("time", formatTime(event.timestamp)));
+    item.append(element("div", event.text));
+    timeline.append(item);
+  }
+  details.append(timeline);
+  const link = element("a", "Tracking page");
+  link.href = job.url;
+  link.target = "_blank";
+  link.rel = "noreferrer";
+  details.append(link);
+  return details;
+}
+
+function render(jobs) {
+  const main = document.getElementById("packages");
+  const open = new Set(
+    [...main.querySelectorAll("details[open]")].map((d) => d.dataset.url),
+  );
+  main.replaceChildren();
+  for (const [key, heading] of GROUPS) {
+    const members = jobs.filter((job) => group(job) === key);
+    if (members.length === 0) continue;
+    main.append(element("h2", `${heading} (${members.length})`));
+    for (const job of members) {
+      const details = renderJob(job);
+      details.dataset.url = job.url;
+      details.open = open.has(job.url);
+      main.append(details);
+    }
+  }
+  if (jobs.length === 0) {
+    main.append(element("p", "No packages yet. Add a tracking URL above."));
+  }
+}
+
+function showMessage(text) {
+  document.getElementById("message").textContent = text;
+}
+
+/** fetch, with the API token if there is one. Asks for the token if the
+ * server wants one. */
+async function api(path, options = {}) {
+  const headers = { ...options.headers };
+  if (token) headers.Authorization = `Bearer ${token}`;
+  const response = await fetch(path, { ...options, headers });
+  if (response.status !== 401) return response;
+  const given = prompt("Your packtrack API token")?.trim();
+  if (!given) return response;
+  token = given;
+  localStorage.setItem(TOKEN_KEY, token);
+  followEvents();
+  return api(path, options);
+}
+
+/** The JSON body of the response, or an error with the API's message */
+async function json(response) {
+  const body = await response.json();
+  if (!response.ok) throw new Error(body.error ?? response.statusText);
+  return body;
+}
+
+async function refresh() {
+  try {
+    shown = await json(await api("/packages"));
+    render(shown);
+    document.getElementById("updated").textContent =
+      `Updated ${formatTime(new Date())}`;
+  } catch (err) {
+    showMessage(`Couldn't load the packages: ${err.message}`);
+  }
+}
+
+async function addUrl(event) {
+  event.preventDefault();
+  const form = event.target;
+  const body = {
+    url: form.url.value.trim(),
+    description: form.description.value.trim() || null,
+  };
+  try {
+    const added = await json(
+      await api("/urls", {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify(body),
+      }),
+    );
+    form.reset();
+    showMessage(`Added ${added.url}`);
+    await refresh();
+  } catch (err) {
+    showMessage(err.message);
+  }
+}
+
+async function removeUrl(url) {
+  if (!confirm(`Remove ${url}?`)) return;
+  try {
+    await json(
+      await api(`/urls?url=${encodeURIComponent(url)}`, {
+        method: "DELETE",
+      }),
+    );
+    showMessage(`Removed ${url}`);
+    await refresh();
+  } catch (err) {
+    showMessage(err.message);
+  }
+}
+
+/** Show the package from an update notification in place of the old one */
+function applyUpdate(event) {
+  const notification = JSON.parse(event.data);
+  showMessage(`${notification.title}: ${notification.body}`);
+  const pkg = notification.update?.package;
+  const job = shown.find((job) => job.url === notification.url);
+  if (!pkg || !job) return refresh();
+  job.package = pkg;
+  job.error = null;
+  job.error_kind = null;
+  render(shown);
+  document.getElementById("updated").textContent =
+    `Updated ${formatTime(new Date())}`;
+}
+
+/** Follow /events, which takes the token in the query as EventSource can't
+ * send headers */
+function followEvents() {
+  events?.close();
+  const query = token ? `?token=${encodeURIComponent(token)}` : "";
+  events = new EventSource(`/events${query}`);
+  events.addEventListener("update", applyUpdate);
+}
+
+followEvents();
+document.getElementById("add").addEventListener("submit", addUrl);
+document.getElementById("refresh").addEventListener("click", refresh);
+refresh();
+setInterval(refresh, REFRESH_SECONDS * 1000);
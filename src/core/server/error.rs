@@ -0,0 +1,58 @@
+use crate::error::Error;
+use crate::url_store::UrlError;
+use axum::Json;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::fmt;
+
+/// An error response: the status, and a JSON body like
+/// `{"error": "'abc' was not found in the URL store"}`
+#[derive(Debug)]
+pub struct ApiError {
+    pub status:  StatusCode,
+    pub message: String,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl From<Error> for ApiError {
+    fn from(err: Error) -> Self {
+        let status = match &err {
+            Error::Url(UrlError::NotFound(_)) => StatusCode::NOT_FOUND,
+            Error::Url(_) => StatusCode::CONFLICT,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Self::new(status, err.to_string())
+    }
+}
+
+/// The body of an error response
+#[derive(Serialize, JsonSchema)]
+pub struct ErrorBody {
+    /// What went wrong
+    pub error: String,
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = ErrorBody {
+            error: self.message,
+        };
+        (self.status, Json(body)).into_response()
+    }
+}
@@ -0,0 +1,169 @@
+use crate::alerts::change_notification;
+use crate::api::Job;
+use crate::polling::next_poll;
+use crate::server::ServerState;
+use crate::utils::UtcTime;
+use crate::verify::CachedPackage;
+use axum::Router;
+use axum::extract::State;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::routing::get;
+use chrono::Utc;
+use futures::Stream;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+
+pub fn routes() -> Router<Arc<ServerState>> {
+    Router::new().route("/events", get(events))
+}
+
+impl ServerState {
+    /// Send the changes since the previous version of each package to the
+    /// /events clients. Packages this server hasn't seen yet are compared
+    /// with the `cached` ones from before tracking.
+    pub(super) fn publish_changes(
+        &self,
+        jobs: &[Job],
+        cached: HashMap<String, CachedPackage>,
+    ) {
+        let now = Utc::now();
+        let mut known = self.known.lock().unwrap();
+        for job in jobs {
+            let Ok(package) = &job.result else {
+                continue;
+            };
+            let previous = known
+                .insert(job.url.url.clone(), package.clone())
+                .or_else(|| {
+                    cached
+                        .get(&job.url.url)
+                        .map(|c| c.package.clone())
+                });
+            if let Some(previous) = previous
+                && let Some(update) =
+                    change_notification(&job.url, &previous, package, now)
+            {
+                // Fails when nobody is listening, which is fine
+                let _ = self.updates.send(update);
+            }
+        }
+    }
+}
+
+/// GET /events: a server-sent event named "update" for every change, with
+/// the same JSON as the webhook
+async fn events(
+    State(state): State<Arc<ServerState>>,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    let updates = state.updates.subscribe();
+    let stream = futures::stream::unfold(updates, |mut updates| async move {
+        loop {
+            match updates.recv().await {
+                Ok(update) => {
+                    let event = Event::default()
+                        .event("update")
+                        .json_data(&update);
+                    return Some((event, updates));
+                }
+                Err(RecvError::Lagged(missed)) => {
+                    log::warn!("An /events client missed {missed} updates");
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+/// Track the packages whenever they're due, like watch mode, so that the
+/// changes reach /events without anyone asking for the packages
+pub async fn poll(state: Arc<ServerState>) {
+    let Some(settings) = &state.polling else {
+        return;
+    };
+    // When each URL is due again. None for packages that won't change
+    // anymore.
+    let mut next: HashMap<String, Option<UtcTime>> = HashMap::new();
+    loop {
+        let now = Utc::now();
+        match state.urls() {
+            Ok(urls) => {
+                next.retain(|url, _| urls.iter().any(|u| &u.url == url));
+                let due: Vec<_> = urls
+                    .into_iter()
+                    .filter(|url| match next.get(&url.url) {
+                        Some(at) => at.is_some_and(|at| at <= now),
+                        None => true,
+                    })
+                    .collect();
+                if !due.is_empty() {
+                    match state.track(due).await {
+                        Ok(jobs) => {
+                            for job in jobs {
+                                let at = next_poll(&job, now, settings);
+                                next.insert(job.url.url, at);
+                            }
+                        }
+                        Err(err) => log::error!("Couldn't poll: {err}"),
+                    }
+                }
+            }
+            Err(err) => log::error!("Couldn't read the URLs: {err}"),
+        }
+        // Look for new URLs at least this often
+        let latest = now + settings.max();
+        let wake = next
+            .values()
+            .flatten()
+            .min()
+            .map_or(latest, |at| (*at).min(latest));
+        let wait = (wake - Utc::now())
+            .to_std()
+            .unwrap_or_default();
+        tokio::time::sleep(wait).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::test_utils::{demo_state, serve_state};
+    use crate::tracker::{MockTracker, PackageStatus, Tracker};
+
+    fn job(url: &str, status: PackageStatus) -> Job {
+        let mut package = MockTracker
+            .parse("demo:postnl".into())
+            .unwrap();
+        package.status = status;
+        Job {
+            url:    url.into(),
+            result: Ok(package),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_events() -> crate::Result<()> {
+        let (state, _) = demo_state(&[]);
+        let state = Arc::new(state);
+        let server = serve_state(state.clone()).await?;
+        let mut response = reqwest::get(format!("{server}/events")).await?;
+        assert_eq!(response.headers()["content-type"], "text/event-stream");
+
+        // The first version of a package isn't a change
+        state.publish_changes(
+            &[job("demo:postnl", PackageStatus::InTransit)],
+            HashMap::new(),
+        );
+        state.publish_changes(
+            &[job("demo:postnl", PackageStatus::Delivered)],
+            HashMap::new(),
+        );
+        let chunk = response.chunk().await?.unwrap();
+        let text = String::from_utf8_lossy(&chunk);
+        assert!(text.starts_with("event: update\ndata: {"), "{text}");
+        assert!(text.contains(r#""title":"Package delivered""#), "{text}");
+        assert!(text.contains("status_changed"), "{text}");
+        Ok(())
+    }
+}
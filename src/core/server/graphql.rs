@@ -0,0 +1,422 @@
+use crate::api::Job;
+use crate::server::{NewUrl, ServerState};
+use crate::tracker::{Event, Package, PackageStatus, PickupPoint, TimeWindow};
+use crate::url_store::AnnotatedUrl;
+use crate::utils::UtcTime;
+use async_graphql::http::GraphiQLSource;
+use async_graphql::{
+    Context, EmptySubscription, Enum, Object, Result, Schema, SimpleObject,
+};
+use axum::response::Html;
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::NaiveDate;
+use std::sync::Arc;
+
+pub type PacktrackSchema = Schema<Query, Mutation, EmptySubscription>;
+
+/// The GraphQL schema, answering from the server's state
+pub fn schema(state: Arc<ServerState>) -> PacktrackSchema {
+    Schema::build(Query, Mutation, EmptySubscription)
+        .data(state)
+        .finish()
+}
+
+/// POST /graphql runs the queries, GET /graphql opens GraphiQL to try them
+pub fn routes(schema: PacktrackSchema) -> Router<Arc<ServerState>> {
+    Router::new().route(
+        "/graphql",
+        get(graphiql).post(
+            move |Json(request): Json<async_graphql::Request>| async move {
+                Json(schema.execute(request).await)
+            },
+        ),
+    )
+}
+
+async fn graphiql() -> Html<String> {
+    Html(
+        GraphiQLSource::build()
+            .endpoint("/graphql")
+            .finish(),
+    )
+}
+
+fn state<'a>(ctx: &Context<'a>) -> &'a Arc<ServerState> {
+    ctx.data_unchecked::<Arc<ServerState>>()
+}
+
+pub struct Query;
+
+#[Object]
+impl Query {
+    /// The packages in the urls file, optionally only those with one of the
+    /// statuses, or from the carrier
+    async fn packages(
+        &self,
+        ctx: &Context<'_>,
+        status: Option<Vec<Status>>,
+        carrier: Option<String>,
+    ) -> Result<Vec<JobObject>> {
+        let state = state(ctx);
+        let jobs = state.track(state.urls()?).await?;
+        Ok(jobs
+            .into_iter()
+            .filter(|job| {
+                let Ok(package) = &job.result else {
+                    // Errors only match when not filtering
+                    return status.is_none() && carrier.is_none();
+                };
+                status.as_ref().is_none_or(|wanted| {
+                    wanted.contains(&(&package.status).into())
+                }) && carrier.as_ref().is_none_or(|carrier| {
+                    package
+                        .channel
+                        .eq_ignore_ascii_case(carrier)
+                })
+            })
+            .map(JobObject::from)
+            .collect())
+    }
+
+    /// One package, by its tracking number
+    async fn package(
+        &self,
+        ctx: &Context<'_>,
+        barcode: String,
+    ) -> Result<Option<JobObject>> {
+        Ok(state(ctx)
+            .package(&barcode)
+            .await?
+            .map(JobObject::from))
+    }
+
+    /// The entries in the urls file, including the placeholders
+    async fn urls(&self, ctx: &Context<'_>) -> Result<Vec<UrlObject>> {
+        Ok((state(ctx).open_urls)()?
+            .filter(None)
+            .into_iter()
+            .map(UrlObject::from)
+            .collect())
+    }
+}
+
+pub struct Mutation;
+
+#[Object]
+impl Mutation {
+    /// Add a tracking URL (or a bare tracking number) to the urls file
+    async fn add_url(
+        &self,
+        ctx: &Context<'_>,
+        url: String,
+        #[graphql(
+            desc = "Short name for the package, shown instead of the carrier \
+                    and barcode"
+        )]
+        label: Option<String>,
+        description: Option<String>,
+        #[graphql(desc = "Carrier to use if the tracking number is ambiguous")]
+        carrier: Option<String>,
+        #[graphql(desc = "Language to request from the carrier")]
+        language: Option<String>,
+    ) -> Result<UrlObject> {
+        let new = NewUrl {
+            url,
+            label,
+            description,
+            carrier,
+            language,
+        };
+        Ok(state(ctx).add_url(new).await?.into())
+    }
+
+    /// Remove the URLs containing the fragment from the urls file
+    async fn remove_urls(
+        &self,
+        ctx: &Context<'_>,
+        url: String,
+    ) -> Result<Vec<UrlObject>> {
+        Ok(state(ctx)
+            .remove_urls(&url)
+            .await?
+            .into_iter()
+            .map(UrlObject::from)
+            .collect())
+    }
+}
+
+/// The result of tracking one URL
+#[derive(SimpleObject)]
+#[graphql(name = "Job")]
+struct JobObject {
+    url:         String,
+    /// Label from the urls file
+    label:       Option<String>,
+    /// Description from the urls file
+    description: Option<String>,
+    /// The tracked package, unless tracking failed
+    package:     Option<PackageObject>,
+    /// Why tracking failed, if it did
+    error:       Option<String>,
+}
+
+impl From<Job> for JobObject {
+    fn from(job: Job) -> Self {
+        let (package, error) = match job.result {
+            Ok(package) => (Some(package.into()), None),
+            Err(err) => (None, Some(err.to_string())),
+        };
+        Self {
+            url: job.url.url,
+            label: job.url.label,
+            description: job.url.description,
+            package,
+            error,
+        }
+    }
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+pub enum Status {
+    InTransit,
+    ReadyForPickup,
+    Delivered,
+    DeliveredToNeighbour,
+}
+
+impl From<&PackageStatus> for Status {
+    fn from(status: &PackageStatus) -> Self {
+        match status {
+            PackageStatus::InTransit => Self::InTransit,
+            PackageStatus::ReadyForPickup { .. } => Self::ReadyForPickup,
+            PackageStatus::Delivered => Self::Delivered,
+            PackageStatus::DeliveredToNeighbour { .. } => {
+                Self::DeliveredToNeighbour
+            }
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "Package")]
+struct PackageObject {
+    /// Tracking number
+    barcode:     String,
+    /// The carrier, e.g. "PostNL"
+    carrier:     String,
+    status:      Status,
+    /// The status as the CLI shows it, e.g. "Delivered to neighbour at ..."
+    status_text: String,
+    sender:      Option<String>,
+    recipient:   Option<String>,
+    /// Expected delivery time
+    eta:         Option<UtcTime>,
+    /// Promised delivery window
+    eta_window:  Option<TimeWindowObject>,
+    delivered:   Option<UtcTime>,
+    /// The carrier's events, in the order the carrier gives them
+    events:      Vec<EventObject>,
+    /// Where the package is waiting to be collected
+    pickup:      Option<PickupObject>,
+}
+
+impl From<Package> for PackageObject {
+    fn from(package: Package) -> Self {
+        Self {
+            barcode:     package.barcode,
+            carrier:     package.channel,
+            status:      (&package.status).into(),
+            status_text: package.status.to_string(),
+            sender:      package.sender,
+            recipient:   package.recipient,
+            eta:         package.eta,
+            eta_window:  package
+                .eta_window
+                .map(TimeWindowObject::from),
+            delivered:   package.delivered,
+            events:      package
+                .events
+                .into_iter()
+                .map(EventObject::from)
+                .collect(),
+            pickup:      package.pickup.map(PickupObject::from),
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "TimeWindow")]
+struct TimeWindowObject {
+    start: UtcTime,
+    end:   UtcTime,
+}
+
+impl From<TimeWindow> for TimeWindowObject {
+    fn from(window: TimeWindow) -> Self {
+        Self {
+            start: window.start,
+            end:   window.end,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "Event")]
+struct EventObject {
+    timestamp: UtcTime,
+    text:      String,
+}
+
+impl From<Event> for EventObject {
+    fn from(event: Event) -> Self {
+        Self {
+            timestamp: event.timestamp,
+            text:      event.text,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "PickupPoint")]
+struct PickupObject {
+    /// Name and/or address of the pickup point
+    location: String,
+    /// When the package goes back to the sender if it isn't collected
+    deadline: Option<UtcTime>,
+}
+
+impl From<PickupPoint> for PickupObject {
+    fn from(pickup: PickupPoint) -> Self {
+        Self {
+            location: pickup.location,
+            deadline: pickup.deadline,
+        }
+    }
+}
+
+/// An entry in the urls file
+#[derive(SimpleObject)]
+#[graphql(name = "Url")]
+struct UrlObject {
+    url:         String,
+    /// Short name for the package
+    label:       Option<String>,
+    description: Option<String>,
+    /// When it was added
+    created:     Option<UtcTime>,
+    /// When the package is expected, for placeholders
+    expected:    Option<NaiveDate>,
+    /// Language to request from the carrier, instead of the preferred one
+    language:    Option<String>,
+}
+
+impl From<AnnotatedUrl> for UrlObject {
+    fn from(url: AnnotatedUrl) -> Self {
+        Self {
+            url:         url.url,
+            label:       url.label,
+            description: url.description,
+            created:     url.created,
+            expected:    url.expected,
+            language:    url.language,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::test_utils::{demo_state, serve_state};
+    use serde_json::{Value, json};
+
+    #[tokio::test]
+    async fn test_graphql() -> crate::Result<()> {
+        let (state, _) = demo_state(&[
+            "demo:postnl",
+            "demo:gls/123456789012",
+            "demo:broken",
+        ]);
+        let schema = schema(Arc::new(state));
+
+        let query =
+            "{ packages(status: [IN_TRANSIT]) { package { carrier eta } } }";
+        let response = schema.execute(query).await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        let data = response.data.into_json()?;
+        let packages = data["packages"].as_array().unwrap();
+        assert_eq!(packages.len(), 1);
+        assert_eq!(packages[0]["package"]["carrier"], "PostNL");
+        assert!(packages[0]["package"]["eta"].is_string());
+        // Only the fields that were asked for
+        assert!(
+            packages[0]["package"]
+                .get("barcode")
+                .is_none()
+        );
+
+        let query =
+            r#"{ package(barcode: "123456789012") { package { status } } }"#;
+        let data = schema
+            .execute(query)
+            .await
+            .data
+            .into_json()?;
+        assert_eq!(data["package"]["package"]["status"], "DELIVERED");
+        // None of the URLs contain it, so nothing is tracked
+        let query =
+            r#"{ package(barcode: "3SDEMO1234567") { package { status } } }"#;
+        let data = schema
+            .execute(query)
+            .await
+            .data
+            .into_json()?;
+        assert!(data["package"].is_null());
+
+        let mutation = r#"mutation {
+            addUrl(url: "demo:dhl", description: "books") { url }
+            removeUrls(url: "broken") { url }
+        }"#;
+        let data = schema
+            .execute(mutation)
+            .await
+            .data
+            .into_json()?;
+        assert_eq!(data["addUrl"]["url"], "demo:dhl");
+        assert_eq!(data["removeUrls"][0]["url"], "demo:broken");
+        let data = schema
+            .execute("{ urls { url description } }")
+            .await
+            .data
+            .into_json()?;
+        assert_eq!(data["urls"].as_array().unwrap().len(), 3);
+        assert_eq!(data["urls"][2]["description"], "books");
+
+        let response = schema
+            .execute(r#"mutation { removeUrls(url: "nope") { url } }"#)
+            .await;
+        assert_eq!(
+            response.errors[0].message,
+            "'nope' was not found in the URL store"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_graphql_endpoint() -> crate::Result<()> {
+        let (state, _) = demo_state(&["demo:postnl"]);
+        let server = serve_state(Arc::new(state)).await?;
+        let response: Value = reqwest::Client::new()
+            .post(format!("{server}/graphql"))
+            .json(&json!({"query": "{ packages { package { barcode } } }"}))
+            .send()
+            .await?
+            .json()
+            .await?;
+        assert_eq!(
+            response["data"]["packages"][0]["package"]["barcode"],
+            "3SDEMO1234567"
+        );
+        Ok(())
+    }
+}
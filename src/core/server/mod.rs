@@ -0,0 +1,305 @@
+// `packtrack serve`: the packages over HTTP, so that phones, dashboards and
+// other tools can use packtrack over the network. There's a REST API
+// (described by the OpenAPI document at /openapi.json), a GraphQL endpoint
+// and a web dashboard, which all answer from the same tracking pipeline as
+// the CLI. The URL store and the cache are opened afresh
+// for every request, so that changes made with the CLI (or a running `packtrack
+// watch`) are picked up. The server also keeps polling the carriers like watch
+// mode, takes push notifications from the carriers that send them, and streams
+// the changes it sees to /events.
+
+mod dashboard;
+mod error;
+mod events;
+mod graphql;
+mod openapi;
+mod push;
+mod rest;
+mod users;
+
+use crate::Result;
+use crate::api::{Context, Job, track_urls};
+use crate::barcode;
+use crate::cache::{Cache, CacheEntry};
+use crate::notify::Notification;
+use crate::polling::PollingSettings;
+use crate::tracker::Package;
+use crate::url_store::{AnnotatedUrl, UrlStore};
+use crate::verify::cached_packages;
+use axum::Router;
+use axum::http::StatusCode;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio::sync::{Mutex, broadcast};
+
+pub use error::ApiError;
+pub use graphql::{PacktrackSchema, schema};
+pub use users::{User, check_tokens, user_file, users_router};
+
+/// How many changes a slow /events client may fall behind before it misses
+/// some
+const UPDATES_CAPACITY: usize = 64;
+
+type Opener<T> = Box<dyn Fn() -> Result<Box<T>> + Send + Sync>;
+
+/// What the request handlers share
+pub struct ServerState {
+    /// Shared by the users of a multi-user server
+    pub ctx:        Arc<Context>,
+    /// Opens the URL store, e.g. the urls file
+    pub open_urls:  Opener<dyn UrlStore>,
+    /// Opens the cache
+    pub open_cache: Opener<dyn Cache>,
+    /// Held while tracking or changing the URLs, so that concurrent requests
+    /// don't overwrite each other's changes to the cache and the urls file
+    lock:           Mutex<()>,
+    /// How often to poll the carriers in the background, if at all
+    polling:        Option<PollingSettings>,
+    /// The latest version of each package, to find out what changed
+    known:          std::sync::Mutex<HashMap<String, Package>>,
+    /// The changes, for the clients following /events
+    updates:        broadcast::Sender<Notification>,
+}
+
+/// A URL to add, like the arguments of `packtrack url add`
+#[derive(Deserialize, JsonSchema)]
+pub struct NewUrl {
+    /// Tracking URL, or a bare tracking number
+    pub url:         String,
+    /// Short name for the package, shown instead of the carrier and barcode
+    pub label:       Option<String>,
+    pub description: Option<String>,
+    /// Carrier to use if the tracking number is ambiguous
+    pub carrier:     Option<String>,
+    /// Language to request from the carrier for this package
+    pub language:    Option<String>,
+}
+
+impl ServerState {
+    pub fn new(
+        ctx: Arc<Context>,
+        open_urls: Opener<dyn UrlStore>,
+        open_cache: Opener<dyn Cache>,
+    ) -> Self {
+        Self {
+            ctx,
+            open_urls,
+            open_cache,
+            lock: Mutex::new(()),
+            polling: None,
+            known: Default::default(),
+            updates: broadcast::channel(UPDATES_CAPACITY).0,
+        }
+    }
+
+    /// Keep polling the carriers as often as the settings say
+    pub fn with_polling(mut self, polling: PollingSettings) -> Self {
+        self.polling = Some(polling);
+        self
+    }
+
+    /// The URLs to track, leaving out the placeholders, and each package only
+    /// once
+    pub fn urls(&self) -> Result<Vec<AnnotatedUrl>> {
+        let urls = (self.open_urls)()?.filter(None);
+        Ok(self
+            .ctx
+            .registry
+            .merge_duplicates(urls)
+            .into_iter()
+            .filter(|url| !url.is_placeholder())
+            .collect())
+    }
+
+    /// Track the URLs, reusing the cache as the settings allow, and publish
+    /// what changed
+    pub async fn track(&self, urls: Vec<AnnotatedUrl>) -> Result<Vec<Job>> {
+        self.track_with(urls, vec![]).await
+    }
+
+    /// Like `track`, after caching the `fresh` entries by URL, so that those
+    /// packages don't have to be fetched
+    async fn track_with(
+        &self,
+        urls: Vec<AnnotatedUrl>,
+        fresh: Vec<(String, CacheEntry)>,
+    ) -> Result<Vec<Job>> {
+        let _lock = self.lock.lock().await;
+        let mut cache = (self.open_cache)()?;
+        let cached = cached_packages(cache.as_ref(), &self.ctx.registry, &urls);
+        for (url, entry) in fresh {
+            cache.insert_entry(url, entry);
+        }
+        let jobs = track_urls(urls, cache, &self.ctx).await?;
+        self.publish_changes(&jobs, cached);
+        Ok(jobs)
+    }
+
+    /// The package with the tracking number, if it's in the URL store. Only
+    /// the URLs that contain the tracking number are tracked, so there is
+    /// none if no URL does.
+    pub async fn package(&self, barcode: &str) -> Result<Option<Job>> {
+        let wanted = barcode::normalize(barcode);
+        let urls: Vec<_> = self
+            .urls()?
+            .into_iter()
+            .filter(|url| url.url.to_uppercase().contains(&wanted))
+            .collect();
+        if urls.is_empty() {
+            return Ok(None);
+        }
+        Ok(self
+            .track(urls)
+            .await?
+            .into_iter()
+            .find(|job| match &job.result {
+                Ok(package) => barcode::normalize(&package.barcode) == wanted,
+                Err(_) => job
+                    .url
+                    .url
+                    .to_uppercase()
+                    .contains(&wanted),
+            }))
+    }
+
+    /// Add a URL to the URL store, like `packtrack url add`
+    pub async fn add_url(
+        &self,
+        new: NewUrl,
+    ) -> std::result::Result<AnnotatedUrl, ApiError> {
+        for problem in barcode::check(&new.url) {
+            log::warn!("{}: {problem}", new.url);
+        }
+        let url = self
+            .ctx
+            .registry
+            .resolve(&new.url, new.carrier.as_deref())
+            .map_err(|err| {
+                ApiError::new(StatusCode::BAD_REQUEST, err.to_string())
+            })?;
+        let url = AnnotatedUrl {
+            language: new.language,
+            label: new.label,
+            ..AnnotatedUrl::new(url, new.description)
+        };
+        let _lock = self.lock.lock().await;
+        let mut store = (self.open_urls)()?;
+        store.add(url.clone())?;
+        store.save()?;
+        Ok(url)
+    }
+
+    /// Remove the URLs containing the fragment, like `packtrack url remove`
+    pub async fn remove_urls(
+        &self,
+        fragment: &str,
+    ) -> std::result::Result<Vec<AnnotatedUrl>, ApiError> {
+        // An empty fragment would match every URL
+        if fragment.trim().is_empty() {
+            return Err(ApiError::new(
+                StatusCode::BAD_REQUEST,
+                "Pass the URL to remove, e.g. 3SABCD1234567",
+            ));
+        }
+        let _lock = self.lock.lock().await;
+        let mut store = (self.open_urls)()?;
+        let removed = store.remove(fragment)?;
+        store.save()?;
+        Ok(removed)
+    }
+}
+
+/// The HTTP API
+pub fn router(state: Arc<ServerState>) -> Router {
+    rest::routes()
+        .merge(graphql::routes(schema(state.clone())))
+        .merge(dashboard::routes())
+        .merge(events::routes())
+        .merge(openapi::routes())
+        .merge(push::routes())
+        .with_state(state)
+}
+
+/// Serve the HTTP API on the listener until the process is stopped, polling
+/// the carriers in the background if the state says so
+pub async fn serve(listener: TcpListener, state: ServerState) -> Result<()> {
+    let state = Arc::new(state);
+    start_polling(&state);
+    axum::serve(listener, router(state)).await?;
+    Ok(())
+}
+
+/// Like `serve`, but every user only gets to their own packages, with their
+/// token
+pub async fn serve_users(
+    listener: TcpListener,
+    users: Vec<User>,
+) -> Result<()> {
+    check_tokens(&users)?;
+    for user in &users {
+        start_polling(&user.state);
+    }
+    axum::serve(listener, users_router(&users)).await?;
+    Ok(())
+}
+
+fn start_polling(state: &Arc<ServerState>) {
+    if state.polling.is_some() {
+        tokio::spawn(events::poll(state.clone()));
+    }
+}
+
+/// Shared by the tests of the APIs
+#[cfg(test)]
+mod test_utils {
+    use super::*;
+    use crate::test_utils::{MemoryCache, MemoryUrlStore};
+    use crate::tracker::{Credentials, MockTracker, TrackerRegistry};
+
+    /// The webhook secret of the test states
+    pub const SECRET: &str = "webhook-secret";
+
+    /// Server state for the demo packages at the URLs
+    pub fn demo_state(urls: &[&str]) -> (ServerState, MemoryUrlStore) {
+        test_state(TrackerRegistry::empty().with(MockTracker), urls)
+    }
+
+    /// Server state for the URLs, tracked with the registry
+    pub fn test_state(
+        registry: TrackerRegistry,
+        urls: &[&str],
+    ) -> (ServerState, MemoryUrlStore) {
+        let store = MemoryUrlStore::new(
+            urls.iter()
+                .map(|url| AnnotatedUrl::new(url.to_string(), None))
+                .collect(),
+        );
+        let credentials = Credentials::new(
+            [("webhooks".into(), [("secret".into(), SECRET.into())].into())]
+                .into(),
+        );
+        let ctx = Arc::new(Context {
+            registry,
+            credentials,
+            ..Context::default()
+        });
+        let shared = store.clone();
+        let state = ServerState::new(
+            ctx,
+            Box::new(move || Ok(Box::new(shared.clone()))),
+            Box::new(|| Ok(Box::new(MemoryCache::default()))),
+        );
+        (state, store)
+    }
+
+    /// Serve the API for the state, returning its base URL
+    pub async fn serve_state(state: Arc<ServerState>) -> Result<String> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        tokio::spawn(async move { axum::serve(listener, router(state)).await });
+        Ok(format!("http://{addr}"))
+    }
+}
@@ -0,0 +1,247 @@
+// The OpenAPI document for the REST API at /openapi.json, so that clients can
+// be generated against it, and Swagger UI at /docs to try it out. The schemas
+// of the bodies are generated from the Rust types, like `packtrack schema`
+// does for the JSON output, so they can't drift from what the API sends.
+
+use crate::output::JobOutput;
+use crate::server::NewUrl;
+use crate::server::error::ErrorBody;
+use crate::url_store::AnnotatedUrl;
+use axum::Json;
+use axum::Router;
+use axum::http::header::CONTENT_TYPE;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use schemars::generate::SchemaSettings;
+use serde_json::{Value, json};
+
+const SWAGGER_UI: &str = include_str!("swagger.html");
+
+/// Public, like the dashboard, so that clients can be generated without a
+/// token
+pub fn routes<S: Clone + Send + Sync + 'static>() -> Router<S> {
+    Router::new()
+        .route("/openapi.json", get(|| async { Json(openapi()) }))
+        .route("/docs", get(swagger_ui))
+}
+
+async fn swagger_ui() -> impl IntoResponse {
+    ([(CONTENT_TYPE, "text/html; charset=utf-8")], SWAGGER_UI)
+}
+
+/// The OpenAPI 3.0 document describing the REST API
+pub fn openapi() -> Value {
+    let mut generator = SchemaSettings::openapi3().into_generator();
+    let job = generator
+        .subschema_for::<JobOutput>()
+        .to_value();
+    let url = generator
+        .subschema_for::<AnnotatedUrl>()
+        .to_value();
+    let new_url = generator
+        .subschema_for::<NewUrl>()
+        .to_value();
+    let error = generator
+        .subschema_for::<ErrorBody>()
+        .to_value();
+    let body = |schema: &Value| json!({"application/json": {"schema": schema}});
+    let response = |description: &str, schema: &Value| json!({"description": description, "content": body(schema)});
+    let list = |schema: &Value| json!({"type": "array", "items": schema});
+    let failed = response("Something went wrong", &error);
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "packtrack",
+            "description": "Track your packages over HTTP",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "paths": {
+            "/packages": {
+                "get": {
+                    "operationId": "listPackages",
+                    "summary": "All the packages, like `packtrack --json`",
+                    "responses": {
+                        "200": response("The packages", &list(&job)),
+                        "500": failed,
+                    },
+                },
+            },
+            "/packages/{barcode}": {
+                "get": {
+                    "operationId": "getPackage",
+                    "summary": "One package, by its tracking number",
+                    "parameters": [{
+                        "name": "barcode",
+                        "in": "path",
+                        "required": true,
+                        "schema": {"type": "string"},
+                    }],
+                    "responses": {
+                        "200": response("The package", &job),
+                        "404": response("Not in the urls file", &error),
+                        "500": failed,
+                    },
+                },
+            },
+            "/urls": {
+                "post": {
+                    "operationId": "addUrl",
+                    "summary": "Add a URL, like `packtrack url add`",
+                    "requestBody": {"required": true, "content": body(&new_url)},
+                    "responses": {
+                        "201": response("The added URL", &url),
+                        "400": response("Not a tracking URL", &error),
+                        "409": response("Already in the urls file", &error),
+                        "500": failed,
+                    },
+                },
+                "delete": {
+                    "operationId": "removeUrls",
+                    "summary": "Remove the URLs containing the fragment, like \
+                                `packtrack url remove`",
+                    "parameters": [{
+                        "name": "url",
+                        "in": "query",
+                        "required": true,
+                        "schema": {"type": "string"},
+                    }],
+                    "responses": {
+                        "200": response("The removed URLs", &list(&url)),
+                        "400": response("The fragment is empty", &error),
+                        "404": response("No URL matches", &error),
+                        "500": failed,
+                    },
+                },
+            },
+            "/webhooks/{carrier}": {
+                "post": {
+                    "operationId": "push",
+                    "summary": "A push notification from the carrier, e.g. \
+                                17track",
+                    "parameters": [{
+                        "name": "carrier",
+                        "in": "path",
+                        "required": true,
+                        "schema": {"type": "string"},
+                    }, {
+                        "name": "secret",
+                        "in": "query",
+                        "required": true,
+                        "description": "`credentials.webhooks.secret`",
+                        "schema": {"type": "string"},
+                    }],
+                    "requestBody": {
+                        "required": true,
+                        "content": {"*/*": {"schema": {"type": "string"}}},
+                    },
+                    "responses": {
+                        "200": response(
+                            "The packages it was about",
+                            &list(&job)
+                        ),
+                        "400": response("Not a push from the carrier", &error),
+                        "401": response("Wrong secret", &error),
+                        "403": response("No secret configured", &error),
+                        "404": response("Unknown carrier", &error),
+                        "500": failed,
+                    },
+                },
+            },
+            "/events": {
+                "get": {
+                    "operationId": "events",
+                    "summary": "Server-sent events: an `update` whenever a \
+                                package changes, with the same JSON as the \
+                                webhook",
+                    "responses": {
+                        "200": {
+                            "description": "The event stream",
+                            "content": {
+                                "text/event-stream": {
+                                    "schema": {"type": "string"},
+                                },
+                            },
+                        },
+                    },
+                },
+            },
+        },
+        // The token is only needed when the server has users
+        "security": [{"token": []}, {}],
+        "components": {
+            "schemas": generator.take_definitions(true),
+            "securitySchemes": {
+                "token": {"type": "http", "scheme": "bearer"},
+            },
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::test_utils::{demo_state, serve_state};
+    use std::sync::Arc;
+
+    /// The values of all the "$ref"s in the document
+    fn refs(value: &Value) -> Vec<String> {
+        match value {
+            Value::Object(map) => map
+                .iter()
+                .flat_map(|(key, value)| match (key.as_str(), value) {
+                    ("$ref", Value::String(target)) => vec![target.clone()],
+                    _ => refs(value),
+                })
+                .collect(),
+            Value::Array(values) => values.iter().flat_map(refs).collect(),
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_openapi() {
+        let doc = openapi();
+        let paths: Vec<_> = doc["paths"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                "/events",
+                "/packages",
+                "/packages/{barcode}",
+                "/urls",
+                "/webhooks/{carrier}"
+            ]
+        );
+        let refs = refs(&doc);
+        assert!(refs.contains(&"#/components/schemas/JobOutput".into()));
+        for target in refs {
+            let name = target
+                .strip_prefix("#/components/schemas/")
+                .unwrap();
+            assert!(doc["components"]["schemas"][name].is_object(), "{name}");
+        }
+        let new_url = &doc["components"]["schemas"]["NewUrl"];
+        assert_eq!(new_url["required"], json!(["url"]));
+    }
+
+    #[tokio::test]
+    async fn test_openapi_endpoints() -> crate::Result<()> {
+        let (state, _) = demo_state(&[]);
+        let server = serve_state(Arc::new(state)).await?;
+        let doc: Value = reqwest::get(format!("{server}/openapi.json"))
+            .await?
+            .json()
+            .await?;
+        assert_eq!(doc, openapi());
+        let docs = reqwest::get(format!("{server}/docs"))
+            .await?
+            .text()
+            .await?;
+        assert!(docs.contains("/openapi.json"));
+        Ok(())
+    }
+}
@@ -0,0 +1,248 @@
+// Push notifications from carriers: POST /webhooks/{carrier}, where {carrier}
+// is the tracker's name, e.g. "17track". The carrier's callback either
+// contains the new tracking data, which is cached as if it had been fetched,
+// or only says which packages changed, which are then fetched straight away.
+// Either way, the changes go out to /events like the ones found by polling.
+// Anyone could call the webhook, so it needs the secret from
+// `credentials.webhooks.secret` in its URL, e.g. `?secret=<secret>`.
+
+use crate::api::{Job, choose_language};
+use crate::barcode;
+use crate::cache::CacheEntry;
+use crate::output::JobOutput;
+use crate::server::users::same_token;
+use crate::server::{ApiError, ServerState};
+use crate::tracker::TrackerContext;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::routing::post;
+use axum::{Json, Router};
+use serde::Deserialize;
+use std::sync::Arc;
+
+pub fn routes() -> Router<Arc<ServerState>> {
+    Router::new().route("/webhooks/{carrier}", post(receive))
+}
+
+/// The query of POST /webhooks/{carrier}
+#[derive(Deserialize)]
+struct PushQuery {
+    secret: Option<String>,
+}
+
+/// POST /webhooks/{carrier}: a push notification from the carrier. Answers
+/// with the packages it was about.
+async fn receive(
+    State(state): State<Arc<ServerState>>,
+    Path(carrier): Path<String>,
+    Query(query): Query<PushQuery>,
+    body: String,
+) -> Result<Json<Vec<JobOutput>>, ApiError> {
+    state.check_webhook_secret(query.secret.as_deref())?;
+    let jobs = state.push(&carrier, &body).await?;
+    Ok(Json(
+        jobs.iter()
+            .map(JobOutput::from)
+            .collect(),
+    ))
+}
+
+impl ServerState {
+    /// An error unless the secret is the configured webhook secret. Without
+    /// one, the webhooks are turned off.
+    fn check_webhook_secret(
+        &self,
+        secret: Option<&str>,
+    ) -> Result<(), ApiError> {
+        let expected = self
+            .ctx
+            .credentials
+            .get("webhooks", "secret")
+            .filter(|expected| !expected.trim().is_empty())
+            .ok_or_else(|| {
+                ApiError::new(
+                    StatusCode::FORBIDDEN,
+                    "Webhooks are turned off. Turn them on by setting \
+                     `credentials.webhooks.secret`.",
+                )
+            })?;
+        match secret.is_some_and(|secret| same_token(&expected, secret)) {
+            true => Ok(()),
+            false => Err(ApiError::new(
+                StatusCode::UNAUTHORIZED,
+                "Pass the webhook secret as `?secret=<secret>`",
+            )),
+        }
+    }
+
+    /// Update the packages in the carrier's push notification, and publish
+    /// what changed. Packages that aren't in the URL store are ignored.
+    pub async fn push(
+        &self,
+        carrier: &str,
+        body: &str,
+    ) -> Result<Vec<Job>, ApiError> {
+        let registry = &self.ctx.registry;
+        let tracker = registry
+            .trackers()
+            .find(|tracker| tracker.name() == carrier)
+            .ok_or_else(|| {
+                ApiError::new(
+                    StatusCode::NOT_FOUND,
+                    format!("Unknown carrier: {carrier}"),
+                )
+            })?;
+        let updates = tracker
+            .parse_push(body)
+            .map_err(|err| {
+                ApiError::new(StatusCode::BAD_REQUEST, err.to_string())
+            })?;
+        let mut urls = vec![];
+        let mut fresh = vec![];
+        for url in self.urls()? {
+            let handled = registry
+                .get_handler(&url.url)
+                .is_ok_and(|handler| handler.name() == carrier);
+            let Some(update) = updates.iter().find(|update| {
+                handled
+                    && url
+                        .url
+                        .to_uppercase()
+                        .contains(&barcode::normalize(&update.barcode))
+            }) else {
+                continue;
+            };
+            let text = match &update.raw {
+                Some(raw) => Some(raw.clone()),
+                None => {
+                    let ctx = TrackerContext {
+                        language: choose_language(tracker, &url, &self.ctx),
+                        ..self.ctx.tracker_context()
+                    };
+                    match tracker.get_raw(&url.url, &ctx).await {
+                        Ok(raw) if raw.is_success() => Some(raw.body),
+                        Ok(raw) => {
+                            log::warn!("HTTP {} for {}", raw.status, url.url);
+                            None
+                        }
+                        Err(err) => {
+                            log::warn!("Couldn't fetch {}: {err}", url.url);
+                            None
+                        }
+                    }
+                }
+            };
+            // If that didn't work, tracking tries again as usual
+            if let Some(text) = text {
+                let entry = CacheEntry {
+                    tracker: Some(tracker.name().into()),
+                    ..CacheEntry::new(text)
+                };
+                fresh.push((url.url.clone(), entry));
+            }
+            urls.push(url);
+        }
+        log::info!(
+            "{carrier} pushed {} packages, {} of them in the URL store",
+            updates.len(),
+            urls.len()
+        );
+        Ok(self.track_with(urls, fresh).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::test_utils::{SECRET, serve_state, test_state};
+    use crate::tracker::{
+        Package, PackageStatus, RawResponse, Tracker, TrackerRegistry,
+    };
+    use async_trait::async_trait;
+    use serde_json::Value;
+    use std::sync::Mutex;
+
+    /// A carrier that only pushes the tracking numbers of the packages that
+    /// changed, with the packages' status in `status`
+    struct PushingTracker(Arc<Mutex<PackageStatus>>);
+
+    #[async_trait]
+    impl Tracker for PushingTracker {
+        fn name(&self) -> &str {
+            "pushing"
+        }
+        fn can_handle(&self, url: &str) -> bool {
+            url.starts_with("pushing:")
+        }
+        fn barcode_patterns(&self) -> &[&str] {
+            &["3S[A-Z]{4}[0-9]{7}"]
+        }
+        async fn get_raw(
+            &self,
+            url: &str,
+            _: &TrackerContext,
+        ) -> crate::Result<RawResponse> {
+            let package = Package {
+                barcode:    url["pushing:".len()..].into(),
+                channel:    "Pushing".into(),
+                status:     self.0.lock().unwrap().clone(),
+                sender:     None,
+                recipient:  None,
+                eta:        None,
+                eta_window: None,
+                delivered:  None,
+                events:     vec![],
+                pickup:     None,
+            };
+            Ok(RawResponse::ok(serde_json::to_string(&package)?))
+        }
+        fn parse(&self, text: String) -> crate::Result<Package> {
+            Ok(serde_json::from_str(&text)?)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_push() -> crate::Result<()> {
+        let status = Arc::new(Mutex::new(PackageStatus::InTransit));
+        let registry = TrackerRegistry::empty()
+            .with(PushingTracker(status.clone()))
+            .with(crate::tracker::MockTracker);
+        let (state, _) =
+            test_state(registry, &["pushing:3SABCD1234567", "demo:gls"]);
+        let state = Arc::new(state);
+        state.track(state.urls()?).await?;
+        let mut updates = state.updates.subscribe();
+        let server = serve_state(state).await?;
+
+        *status.lock().unwrap() = PackageStatus::Delivered;
+        let client = reqwest::Client::new();
+        let push = |carrier: &str, body: &str| {
+            client
+                .post(format!("{server}/webhooks/{carrier}?secret={SECRET}"))
+                .body(body.to_string())
+                .send()
+        };
+        let response = push("pushing", r#"{"id": "3SABCD1234567"}"#).await?;
+        assert_eq!(response.status(), 200);
+        let jobs: Value = response.json().await?;
+        assert_eq!(jobs.as_array().unwrap().len(), 1);
+        assert_eq!(jobs[0]["package"]["status"], "Delivered");
+        assert_eq!(updates.recv().await.unwrap().title, "Package delivered");
+
+        // Not one of ours
+        let response = push("pushing", r#"{"id": "3SZZZZ7654321"}"#).await?;
+        assert_eq!(response.json::<Value>().await?, Value::Array(vec![]));
+        assert_eq!(push("nobody", "{}").await?.status(), 404);
+
+        // Without the secret, or with the wrong one
+        for url in ["webhooks/pushing", "webhooks/pushing?secret=guess"] {
+            let response = client
+                .post(format!("{server}/{url}"))
+                .body(r#"{"id": "3SABCD1234567"}"#)
+                .send()
+                .await?;
+            assert_eq!(response.status(), 401);
+        }
+        Ok(())
+    }
+}
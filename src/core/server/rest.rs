@@ -0,0 +1,144 @@
+use crate::output::JobOutput;
+use crate::server::{ApiError, NewUrl, ServerState};
+use crate::url_store::AnnotatedUrl;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Deserialize;
+use std::sync::Arc;
+
+type ApiResult<T> = std::result::Result<T, ApiError>;
+
+pub fn routes() -> Router<Arc<ServerState>> {
+    Router::new()
+        .route("/packages", get(list_packages))
+        .route("/packages/{barcode}", get(get_package))
+        .route("/urls", axum::routing::post(add_url).delete(remove_urls))
+}
+
+/// GET /packages: all the packages, like `packtrack --json`
+async fn list_packages(
+    State(state): State<Arc<ServerState>>,
+) -> ApiResult<Json<Vec<JobOutput>>> {
+    let jobs = state.track(state.urls()?).await?;
+    Ok(Json(
+        jobs.iter()
+            .map(JobOutput::from)
+            .collect(),
+    ))
+}
+
+/// GET /packages/{barcode}: one package, by its tracking number
+async fn get_package(
+    State(state): State<Arc<ServerState>>,
+    Path(barcode): Path<String>,
+) -> ApiResult<Json<JobOutput>> {
+    match state.package(&barcode).await? {
+        Some(job) => Ok(Json((&job).into())),
+        None => Err(ApiError::new(
+            StatusCode::NOT_FOUND,
+            format!("No package with tracking number {barcode}"),
+        )),
+    }
+}
+
+/// POST /urls: add a URL, like `packtrack url add`
+async fn add_url(
+    State(state): State<Arc<ServerState>>,
+    Json(new): Json<NewUrl>,
+) -> ApiResult<(StatusCode, Json<AnnotatedUrl>)> {
+    let url = state.add_url(new).await?;
+    Ok((StatusCode::CREATED, Json(url)))
+}
+
+/// The query of DELETE /urls
+#[derive(Deserialize)]
+struct RemoveQuery {
+    /// Fragment of the URLs to remove
+    url: String,
+}
+
+/// DELETE /urls?url=...: remove the URLs containing the fragment, like
+/// `packtrack url remove`
+async fn remove_urls(
+    State(state): State<Arc<ServerState>>,
+    Query(query): Query<RemoveQuery>,
+) -> ApiResult<Json<Vec<AnnotatedUrl>>> {
+    Ok(Json(state.remove_urls(&query.url).await?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Result;
+    use crate::server::test_utils::{demo_state, serve_state};
+    use crate::url_store::UrlStore;
+    use serde_json::{Value, json};
+
+    #[tokio::test]
+    async fn test_rest_api() -> Result<()> {
+        let (state, urls) = demo_state(&[]);
+        let server = serve_state(Arc::new(state)).await?;
+        let client = reqwest::Client::new();
+
+        let response = client
+            .post(format!("{server}/urls"))
+            .json(&json!({
+                "url": "demo:postnl/3SDEMO1234567",
+                "description": "shoes",
+            }))
+            .send()
+            .await?;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        client
+            .post(format!("{server}/urls"))
+            .json(&json!({"url": "demo:broken"}))
+            .send()
+            .await?;
+        assert_eq!(urls.filter(None).len(), 2);
+
+        let packages: Value = client
+            .get(format!("{server}/packages"))
+            .send()
+            .await?
+            .json()
+            .await?;
+        assert_eq!(packages[0]["description"], "shoes");
+        assert_eq!(packages[0]["package"]["barcode"], "3SDEMO1234567");
+        assert!(packages[1]["error"].is_string());
+
+        let response = client
+            .get(format!("{server}/packages/3sdemo1234567"))
+            .send()
+            .await?;
+        assert_eq!(response.status(), StatusCode::OK);
+        let package: Value = response.json().await?;
+        assert_eq!(package["url"], "demo:postnl/3SDEMO1234567");
+        let response = client
+            .get(format!("{server}/packages/JVGL0123456789012345"))
+            .send()
+            .await?;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = client
+            .delete(format!("{server}/urls?url=postnl"))
+            .send()
+            .await?;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(urls.filter(None).len(), 1);
+        let response = client
+            .delete(format!("{server}/urls?url=postnl"))
+            .send()
+            .await?;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let error: Value = response.json().await?;
+        assert_eq!(error["error"], "'postnl' was not found in the URL store");
+        let response = client
+            .delete(format!("{server}/urls?url="))
+            .send()
+            .await?;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        Ok(())
+    }
+}
@@ -0,0 +1,230 @@
+// Multi-user mode, e.g. for a household sharing one server. Every user has an
+// API token, and their own URL store and cache, in a `ServerState` of their
+// own: the token picks the state that answers the request, so users only see
+// (and only get /events about) their own packages. The dashboard and the API
+// docs are public, as they don't contain any packages.
+
+use crate::Result;
+use crate::server::{ApiError, ServerState, dashboard, openapi, router};
+use axum::Router;
+use axum::extract::{Query, Request, State};
+use axum::http::StatusCode;
+use axum::http::header::{AUTHORIZATION, WWW_AUTHENTICATE};
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+/// Someone using a multi-user server
+pub struct User {
+    pub name:  String,
+    /// Sent as `Authorization: Bearer <token>`, or as `?token=<token>` where
+    /// headers can't be set, like for `EventSource`
+    pub token: String,
+    pub state: Arc<ServerState>,
+}
+
+impl User {
+    pub fn new(name: String, token: String, state: ServerState) -> Self {
+        Self {
+            name,
+            token,
+            state: Arc::new(state),
+        }
+    }
+}
+
+/// The user's own version of a file, e.g. "packtrack-alice.urls" for
+/// "packtrack.urls"
+pub fn user_file(path: &Path, user: &str) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy();
+    let name = match path.extension() {
+        Some(ext) => format!("{stem}-{user}.{}", ext.to_string_lossy()),
+        None => format!("{stem}-{user}"),
+    };
+    path.with_file_name(name)
+}
+
+/// An error unless every user has a token of their own
+pub fn check_tokens(users: &[User]) -> Result<()> {
+    for (i, user) in users.iter().enumerate() {
+        if user.token.trim().is_empty() {
+            return Err(format!("{} has an empty API token", user.name).into());
+        }
+        if let Some(other) = users[..i]
+            .iter()
+            .find(|other| other.token == user.token)
+        {
+            return Err(format!(
+                "{} and {} have the same API token",
+                other.name, user.name
+            )
+            .into());
+        }
+    }
+    Ok(())
+}
+
+/// The HTTP API for the users, each answered by their own state
+pub fn users_router(users: &[User]) -> Router {
+    let users: Vec<_> = users
+        .iter()
+        .map(|user| {
+            (
+                user.name.clone(),
+                user.token.clone(),
+                router(user.state.clone()),
+            )
+        })
+        .collect();
+    dashboard::routes()
+        .merge(openapi::routes())
+        .fallback(dispatch)
+        .with_state(Arc::new(users))
+}
+
+type Users = Arc<Vec<(String, String, Router)>>;
+
+/// Pass the request on to the API of the user with the token
+async fn dispatch(State(users): State<Users>, request: Request) -> Response {
+    let user = token(&request).and_then(|token| {
+        users
+            .iter()
+            .find(|(_, user_token, _)| same_token(user_token, &token))
+    });
+    let Some((name, _, router)) = user else {
+        let error = ApiError::new(
+            StatusCode::UNAUTHORIZED,
+            "Pass your API token as `Authorization: Bearer <token>`",
+        );
+        return ([(WWW_AUTHENTICATE, "Bearer")], error).into_response();
+    };
+    log::debug!("{} {} by {name}", request.method(), request.uri().path());
+    match router.clone().oneshot(request).await {
+        Ok(response) => response,
+        Err(never) => match never {},
+    }
+}
+
+#[derive(Deserialize)]
+struct TokenQuery {
+    token: String,
+}
+
+/// The token from the Authorization header, or else from the query
+fn token(request: &Request) -> Option<String> {
+    let header = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    match header {
+        Some(token) => Some(token.trim().into()),
+        None => Query::<TokenQuery>::try_from_uri(request.uri())
+            .ok()
+            .map(|query| query.0.token),
+    }
+}
+
+/// Compare the tokens in the same time however much of them matches, so that
+/// the response times don't give away how close a guess was
+pub(crate) fn same_token(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected
+            .bytes()
+            .zip(given.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::test_utils::demo_state;
+    use serde_json::Value;
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn test_user_file() {
+        assert_eq!(
+            user_file(Path::new("/home/me/packtrack.urls"), "alice"),
+            Path::new("/home/me/packtrack-alice.urls")
+        );
+        assert_eq!(
+            user_file(Path::new("cache/packtrack-cache.json"), "bob"),
+            Path::new("cache/packtrack-cache-bob.json")
+        );
+        assert_eq!(user_file(Path::new("urls"), "bob"), Path::new("urls-bob"));
+    }
+
+    #[test]
+    fn test_check_tokens() {
+        let user = |name: &str, token: &str| {
+            User::new(name.into(), token.into(), demo_state(&[]).0)
+        };
+        assert!(check_tokens(&[user("alice", "a"), user("bob", "b")]).is_ok());
+        let error = check_tokens(&[user("alice", "a"), user("bob", "a")]);
+        assert_eq!(
+            error.unwrap_err().to_string(),
+            "alice and bob have the same API token"
+        );
+        assert!(check_tokens(&[user("alice", " ")]).is_err());
+        assert!(same_token("secret", "secret"));
+        assert!(!same_token("secret", "secreT"));
+        assert!(!same_token("secret", "secret2"));
+    }
+
+    #[tokio::test]
+    async fn test_users() -> Result<()> {
+        let users = [
+            User::new("alice".into(), "a1".into(), demo_state(&["demo:dhl"]).0),
+            User::new("bob".into(), "b2".into(), demo_state(&["demo:gls"]).0),
+        ];
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let server = format!("http://{}", listener.local_addr()?);
+        let app = users_router(&users);
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let client = reqwest::Client::new();
+        let packages = |token: Option<&str>| {
+            let request = client.get(format!("{server}/packages"));
+            match token {
+                Some(token) => request.bearer_auth(token),
+                None => request,
+            }
+            .send()
+        };
+        let response = packages(None).await?;
+        assert_eq!(response.status(), 401);
+        assert_eq!(response.headers()["www-authenticate"], "Bearer");
+        assert_eq!(packages(Some("a2")).await?.status(), 401);
+
+        let alice: Value = packages(Some("a1"))
+            .await?
+            .json()
+            .await?;
+        assert_eq!(alice[0]["url"], "demo:dhl");
+        assert_eq!(alice.as_array().unwrap().len(), 1);
+        let bob: Value = client
+            .get(format!("{server}/packages?token=b2"))
+            .send()
+            .await?
+            .json()
+            .await?;
+        assert_eq!(bob[0]["url"], "demo:gls");
+
+        // No packages in these, so no token needed
+        for path in ["/", "/openapi.json"] {
+            let response = client
+                .get(format!("{server}{path}"))
+                .send()
+                .await?;
+            assert!(response.status().is_success(), "{path}");
+        }
+        Ok(())
+    }
+}
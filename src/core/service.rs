@@ -0,0 +1,345 @@
+// Running packtrack as a service, e.g. `packtrack watch --daemon` under
+// systemd. The service tells systemd when it's ready and that it's still
+// alive (sd_notify), and can serve a /healthz endpoint for other
+// supervisors. Both report a problem when polling the carriers has been
+// stuck for too long, so that the supervisor restarts it.
+
+use crate::Result;
+use crate::utils::UtcTime;
+use chrono::{TimeDelta, Utc};
+use serde_json::json;
+use std::ffi::OsStr;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// A poll that takes longer than this is considered stuck
+pub const POLL_TIMEOUT: TimeDelta = TimeDelta::minutes(10);
+
+/// How long systemd waits for a watchdog ping before restarting the service
+const WATCHDOG_SECONDS: u64 = 120;
+
+/// Whether the polling loop is making progress. Cloning gives another handle
+/// to the same state, so the loop can update it while the watchdog and the
+/// health endpoint read it.
+#[derive(Clone, Default)]
+pub struct Liveness {
+    state: Arc<Mutex<LivenessState>>,
+}
+
+#[derive(Default)]
+struct LivenessState {
+    /// When the running poll started, if one is running
+    poll_started: Option<UtcTime>,
+    /// When the last poll finished
+    last_poll:    Option<UtcTime>,
+}
+
+impl Liveness {
+    pub fn poll_started(&self, now: UtcTime) {
+        self.state.lock().unwrap().poll_started = Some(now);
+    }
+
+    pub fn poll_finished(&self, now: UtcTime) {
+        let mut state = self.state.lock().unwrap();
+        state.poll_started = None;
+        state.last_poll = Some(now);
+    }
+
+    pub fn last_poll(&self) -> Option<UtcTime> {
+        self.state.lock().unwrap().last_poll
+    }
+
+    /// An error if a poll has been running for longer than `POLL_TIMEOUT`
+    pub fn check(&self, now: UtcTime) -> std::result::Result<(), String> {
+        match self.state.lock().unwrap().poll_started {
+            Some(started) if now - started > POLL_TIMEOUT => Err(format!(
+                "Polling the carriers has been running since {started}"
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Send a state like "READY=1" to systemd. Returns false if packtrack isn't
+/// running under systemd (with `Type=notify`).
+pub fn sd_notify(state: &str) -> Result<bool> {
+    match std::env::var_os("NOTIFY_SOCKET") {
+        Some(socket) => {
+            notify_socket(&socket, state)?;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+#[cfg(unix)]
+fn notify_socket(socket: &OsStr, state: &str) -> Result<()> {
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::net::UnixDatagram;
+
+    let datagram = UnixDatagram::unbound()?;
+    // Names starting with "@" are in the abstract namespace
+    match socket.as_bytes().strip_prefix(b"@") {
+        #[cfg(target_os = "linux")]
+        Some(name) => {
+            use std::os::linux::net::SocketAddrExt;
+            use std::os::unix::net::SocketAddr;
+            let addr = SocketAddr::from_abstract_name(name)?;
+            datagram.send_to_addr(state.as_bytes(), &addr)?;
+        }
+        #[cfg(not(target_os = "linux"))]
+        Some(_) => {
+            return Err("Abstract notify sockets only exist on Linux".into());
+        }
+        None => {
+            datagram.send_to(state.as_bytes(), socket)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn notify_socket(_socket: &OsStr, _state: &str) -> Result<()> {
+    Err("sd_notify is only supported on Unix".into())
+}
+
+/// How often to ping the systemd watchdog, if it's enabled: twice per
+/// watchdog period, as systemd recommends
+pub fn watchdog_interval() -> Option<Duration> {
+    if let Ok(pid) = std::env::var("WATCHDOG_PID")
+        && pid != std::process::id().to_string()
+    {
+        return None; // meant for another process
+    }
+    let usec: u64 = std::env::var("WATCHDOG_USEC")
+        .ok()?
+        .parse()
+        .ok()?;
+    Some(Duration::from_micros(usec / 2))
+}
+
+/// Ping the systemd watchdog every `interval` for as long as polling isn't
+/// stuck. Once it is, the pings stop and systemd restarts the service.
+pub async fn run_watchdog(interval: Duration, liveness: Liveness) {
+    let mut ticks = tokio::time::interval(interval);
+    loop {
+        ticks.tick().await;
+        match liveness.check(Utc::now()) {
+            Ok(()) => {
+                if let Err(err) = sd_notify("WATCHDOG=1") {
+                    log::warn!("Couldn't ping the systemd watchdog: {err}");
+                }
+            }
+            Err(err) => log::error!("Not pinging the watchdog: {err}"),
+        }
+    }
+}
+
+/// Answer GET /healthz on the listener: 200 while polling makes progress,
+/// 503 when it's stuck
+pub async fn serve_healthz(listener: TcpListener, liveness: Liveness) {
+    loop {
+        let mut stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(err) => {
+                log::warn!("Couldn't accept a health check connection: {err}");
+                continue;
+            }
+        };
+        let liveness = liveness.clone();
+        tokio::spawn(async move {
+            let mut buffer = [0; 1024];
+            let read = stream
+                .read(&mut buffer)
+                .await
+                .unwrap_or(0);
+            let request = String::from_utf8_lossy(&buffer[..read]);
+            // e.g. "GET /healthz HTTP/1.1"
+            let path = request
+                .split_whitespace()
+                .nth(1)
+                .unwrap_or_default();
+            let (status, body) = healthz_response(path, &liveness, Utc::now());
+            let response = format!(
+                "HTTP/1.1 {status}\r\nContent-Type: application/json\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            if let Err(err) = stream
+                .write_all(response.as_bytes())
+                .await
+            {
+                log::debug!("Couldn't answer a health check: {err}");
+            }
+        });
+    }
+}
+
+/// The HTTP status line and JSON body for a request to the path
+fn healthz_response(
+    path: &str,
+    liveness: &Liveness,
+    now: UtcTime,
+) -> (&'static str, String) {
+    if path != "/healthz" {
+        return ("404 Not Found", json!({"error": "Not found"}).to_string());
+    }
+    let last_poll = liveness.last_poll();
+    match liveness.check(now) {
+        Ok(()) => (
+            "200 OK",
+            json!({"status": "ok", "last_poll": last_poll}).to_string(),
+        ),
+        Err(err) => (
+            "503 Service Unavailable",
+            json!({"status": "stuck", "error": err, "last_poll": last_poll})
+                .to_string(),
+        ),
+    }
+}
+
+/// Where systemd looks for the user's own units
+pub fn systemd_user_dir() -> Result<PathBuf> {
+    let dirs =
+        directories::BaseDirs::new().ok_or("Couldn't find the config dir")?;
+    Ok(dirs.config_dir().join("systemd/user"))
+}
+
+/// The systemd user units for running packtrack with the executable at
+/// `exe`, by file name: a service that keeps watching the packages and
+/// sends notifications, and a timer that sends the pickup reminders every
+/// hour
+pub fn systemd_units(
+    exe: &Path,
+    healthz: Option<SocketAddr>,
+) -> Vec<(&'static str, String)> {
+    let exe = match exe.to_string_lossy() {
+        path if path.contains(char::is_whitespace) => format!("\"{path}\""),
+        path => path.into_owned(),
+    };
+    let healthz = healthz
+        .map(|addr| format!(" --healthz {addr}"))
+        .unwrap_or_default();
+    let watch = format!(
+        "[Unit]
+Description=packtrack: keep tracking packages and send notifications
+
+[Service]
+Type=notify
+ExecStart={exe} watch --daemon{healthz} --verbosity info
+WatchdogSec={WATCHDOG_SECONDS}
+Restart=on-failure
+RestartSec=30
+
+[Install]
+WantedBy=default.target
+"
+    );
+    let remind = format!(
+        "[Unit]
+Description=packtrack: remind about packages to collect
+
+[Service]
+Type=oneshot
+ExecStart={exe} remind
+"
+    );
+    let timer = "[Unit]
+Description=Send packtrack's pickup reminders every hour
+
+[Timer]
+OnCalendar=hourly
+Persistent=true
+
+[Install]
+WantedBy=timers.target
+"
+    .to_string();
+    vec![
+        ("packtrack.service", watch),
+        ("packtrack-remind.service", remind),
+        ("packtrack-remind.timer", timer),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_liveness() {
+        let now: UtcTime = "2026-10-16T12:00:00Z".parse().unwrap();
+        let liveness = Liveness::default();
+        assert!(liveness.check(now).is_ok());
+
+        liveness.poll_started(now);
+        assert!(
+            liveness
+                .check(now + TimeDelta::minutes(5))
+                .is_ok()
+        );
+        let later = now + TimeDelta::minutes(11);
+        let (status, body) = healthz_response("/healthz", &liveness, later);
+        assert_eq!(status, "503 Service Unavailable");
+        assert!(body.contains("stuck"));
+
+        liveness.poll_finished(later);
+        let (status, body) = healthz_response("/healthz", &liveness, later);
+        assert_eq!(status, "200 OK");
+        assert!(body.contains("2026-10-16T12:11:00Z"));
+        assert_eq!(healthz_response("/", &liveness, later).0, "404 Not Found");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_notify_socket() -> Result<()> {
+        use std::os::unix::net::UnixDatagram;
+        let path = std::env::temp_dir()
+            .join(format!("packtrack-notify-test-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let systemd = UnixDatagram::bind(&path)?;
+        notify_socket(path.as_os_str(), "READY=1")?;
+        let mut buffer = [0; 64];
+        let received = systemd.recv(&mut buffer)?;
+        assert_eq!(&buffer[..received], b"READY=1");
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_systemd_units() {
+        let addr = "127.0.0.1:8787".parse().ok();
+        let units = systemd_units(Path::new("/usr/bin/packtrack"), addr);
+        let names: Vec<_> = units
+            .iter()
+            .map(|(name, _)| *name)
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "packtrack.service",
+                "packtrack-remind.service",
+                "packtrack-remind.timer"
+            ]
+        );
+        assert!(units[0].1.contains(
+            "ExecStart=/usr/bin/packtrack watch --daemon --healthz \
+             127.0.0.1:8787 --verbosity info\n"
+        ));
+        assert!(
+            units[1]
+                .1
+                .contains("ExecStart=/usr/bin/packtrack remind\n")
+        );
+
+        let units = systemd_units(Path::new("/opt/my apps/packtrack"), None);
+        assert!(units[0].1.contains(
+            "ExecStart=\"/opt/my apps/packtrack\" watch --daemon \
+             --verbosity info\n"
+        ));
+    }
+}
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::{
     Result,
@@ -22,7 +22,7 @@ pub struct FileSettingsManager {
 impl FileSettingsManager {
     /// RAII -- instantiating the struct also loads the settings from file
     pub fn new(path: PathBuf) -> Result<Self> {
-        #[allow(unused_mut)]
+        #[allow(unused_mut, unused_assignments)]
         let mut file_handler: Box<dyn FileHandler> = Box::new(TextFileHandler);
 
         // Use a mock file handler in tests to prevent tests doing IO
@@ -43,9 +43,7 @@ impl FileSettingsManager {
         })
     }
 
-    fn select_serializer(
-        file: &PathBuf,
-    ) -> Result<Box<dyn SettingsSerializer>> {
+    fn select_serializer(file: &Path) -> Result<Box<dyn SettingsSerializer>> {
         if !file.exists() {
             return Err(
                 format!("File {} does not exist!", file.display()).into()
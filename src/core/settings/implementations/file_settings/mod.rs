@@ -1,3 +1,4 @@
+#[allow(clippy::module_inception)]
 pub mod file_settings;
 pub mod serializer;
 pub mod utils;
@@ -5,5 +5,7 @@ mod traits;
 pub use implementations::file_settings::{
     FileSettingsManager, get_settings_file,
 };
-pub use models::Settings;
+pub use models::{
+    ImapSettings, ServerSettings, Settings, Theme, TrackerSettings,
+};
 pub use traits::SettingsManager;
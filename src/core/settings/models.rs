@@ -1,21 +1,139 @@
-use crate::cache::get_cache_dir;
+use crate::cache::{Cache, CacheBackend, get_cache_dir};
+use crate::notify::NotifySettings;
+use crate::polling::PollingSettings;
+use crate::sync::SyncSettings;
+use crate::tracker::CredentialMap;
 use crate::{Result, utils::get_home_dir};
+use byte_unit::Byte;
+use chrono::NaiveTime;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
 pub struct Settings {
-    pub urls_file:         PathBuf, // owned equivalent to Path
-    pub postcode:          Option<String>,
-    pub language:          Option<String>,
-    pub cache_file:        PathBuf,
+    pub urls_file:              PathBuf, // owned equivalent to Path
+    pub postcode:               Option<String>,
+    pub language:               Option<String>,
+    /// Also fetch the events in these languages, for carriers that support
+    /// more than one, so the language can be switched without fetching again
+    pub languages:              Vec<String>,
+    pub cache_file:             PathBuf,
+    /// Where to keep the cache: "json" (the cache file), "sqlite", "memory",
+    /// or a redis:// URL
+    pub cache_backend:          CacheBackend,
     /// Maximum age (in seconds) for cache entries to be reused.
-    pub cache_seconds:     usize,
+    pub cache_seconds:          usize,
     /// Maximum number of entries to cache (per URL)
-    pub cache_max_entries: usize,
+    pub cache_max_entries:      usize,
+    /// Maximum size of the cache in bytes. When it's bigger, the least
+    /// recently used URLs are evicted. No limit if not set.
+    pub cache_max_bytes:        Option<u64>,
+    /// Show packages from the cache straight away, even if the entries are
+    /// older than `cache_seconds`, and refresh those in the background
+    pub stale_while_revalidate: bool,
+    pub trackers:               TrackerSettings,
+    /// Chrome/Chromium executable, for carriers whose tracking page only
+    /// works with JS. If not set, packtrack looks for one on the system.
+    pub browser_path:           Option<PathBuf>,
+    /// API keys etc. for carriers that need them, per carrier. Secrets can
+    /// also be kept in the OS keyring instead.
+    pub credentials:            CredentialMap,
+    /// Mailbox to scan for tracking links with `packtrack import imap`
+    pub imap:                   ImapSettings,
+    /// Where to share the URLs file with other machines (`packtrack sync`)
+    pub sync:                   SyncSettings,
+    /// Which channels to send notifications through
+    pub notify:                 NotifySettings,
+    /// Remind about packages at a pickup point when there are fewer than
+    /// this many days left to collect them
+    pub pickup_reminder_days:   i64,
+    /// Remove packages from the urls file (and the cache) when tracking, once
+    /// they were delivered more than this many days ago. Never if not set.
+    pub auto_archive_days:      Option<u32>,
+    /// How often to poll packages when packtrack keeps running
+    pub polling:                PollingSettings,
+    /// Who may use `packtrack serve`
+    pub server:                 ServerSettings,
+    /// Which icons to show in the report
+    pub theme:                  Theme,
+    /// Icons to use instead of the theme's, by status (e.g. "delivered") or
+    /// carrier (e.g. "postnl")
+    pub icons:                  BTreeMap<String, String>,
 }
+
+/// Sets of icons for the statuses and carriers in the report
+#[derive(Serialize, Deserialize, Default, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Theme {
+    /// No icons
+    #[default]
+    Plain,
+    Emoji,
+    /// Glyphs from a Nerd Font (https://www.nerdfonts.com), which the
+    /// terminal needs to use
+    NerdFont,
+}
+
+/// Settings controlling which trackers are used, and in what order
+#[derive(Serialize, Deserialize, Default)]
+pub struct TrackerSettings {
+    /// Names of trackers that should never be used
+    #[serde(default)]
+    pub disabled: Vec<String>,
+    /// Names of trackers that should be tried first, in this order. Trackers
+    /// not in this list are tried afterwards, in their default order.
+    #[serde(default)]
+    pub priority: Vec<String>,
+}
+
+/// Settings for `packtrack serve`
+#[derive(Serialize, Deserialize, Default)]
+pub struct ServerSettings {
+    /// Names of the people using the server, each with their own urls file
+    /// and cache, and an API token in `credentials.server.<name>`. Without
+    /// users, anyone who can reach the server can use it.
+    #[serde(default)]
+    pub users: Vec<String>,
+}
+
+/// Where to find the user's emails. The password is a credential (see
+/// `Settings.credentials`), so that it can be kept in the OS keyring.
+#[derive(Serialize, Deserialize)]
+pub struct ImapSettings {
+    /// e.g. "imap.gmail.com"
+    pub server:   Option<String>,
+    #[serde(default = "default_imap_port")]
+    pub port:     u16,
+    pub username: Option<String>,
+    #[serde(default = "default_imap_mailbox")]
+    pub mailbox:  String,
+}
+impl Default for ImapSettings {
+    fn default() -> Self {
+        Self {
+            server:   None,
+            port:     default_imap_port(),
+            username: None,
+            mailbox:  default_imap_mailbox(),
+        }
+    }
+}
+fn default_imap_port() -> u16 {
+    993
+}
+fn default_imap_mailbox() -> String {
+    "INBOX".into()
+}
+
 impl Settings {
+    /// Open the cache with the chosen backend
+    pub fn open_cache(&self) -> Result<Box<dyn Cache>> {
+        self.cache_backend
+            .open(&self.cache_file)
+    }
+
     /// Handle updating arbitrary key/value pairs. These could come from the CLI
     /// or API query parameters, for example.
     pub fn update(
@@ -34,28 +152,334 @@ impl Settings {
                 }
                 self.urls_file = path;
             }
+            "browser_path" => {
+                let path: PathBuf = value.into();
+                if !path.try_exists()? {
+                    return Err(format!(
+                        "browser_path doesn't exist: {path:?}"
+                    )
+                    .into());
+                }
+                self.browser_path = Some(path);
+            }
             "postcode" => self.postcode = Some(value),
             "language" => self.language = Some(value),
+            "languages" => self.languages = parse_list(&value),
             "cache_seconds" => self.cache_seconds = value.parse()?,
             "cache_max_entries" => self.cache_max_entries = value.parse()?,
+            "cache_max_bytes" => {
+                self.cache_max_bytes = match value.is_empty() {
+                    true => None,
+                    false => Some(
+                        Byte::parse_str(&value, true)
+                            .map_err(|err| {
+                                format!("Invalid cache_max_bytes: {err}")
+                            })?
+                            .as_u64(),
+                    ),
+                }
+            }
+            "cache_backend" => self.cache_backend = value.parse()?,
+            "stale_while_revalidate" => {
+                self.stale_while_revalidate = parse_bool(key, &value)?
+            }
+            "trackers.disabled" => self.trackers.disabled = parse_list(&value),
+            "trackers.priority" => self.trackers.priority = parse_list(&value),
+            "imap.server" => self.imap.server = Some(value),
+            "imap.port" => self.imap.port = value.parse()?,
+            "imap.username" => self.imap.username = Some(value),
+            "imap.mailbox" => self.imap.mailbox = value,
+            "sync.backend" => self.sync.backend = Some(value),
+            "sync.location" => self.sync.location = Some(value),
+            "notify.stdout" => self.notify.stdout = parse_bool(key, &value)?,
+            "notify.bell" => self.notify.bell = parse_bool(key, &value)?,
+            "notify.sound" => {
+                self.notify.sound = match value.is_empty() {
+                    true => None,
+                    false => {
+                        let path: PathBuf = value.into();
+                        if !path.try_exists()? {
+                            return Err(format!(
+                                "notify.sound doesn't exist: {path:?}"
+                            )
+                            .into());
+                        }
+                        Some(path)
+                    }
+                }
+            }
+            "notify.webhook" => {
+                self.notify.webhook = match value.is_empty() {
+                    true => None,
+                    false => Some(
+                        reqwest::Url::parse(&value)
+                            .map_err(|err| {
+                                format!("Invalid notify.webhook: {err}")
+                            })?
+                            .into(),
+                    ),
+                }
+            }
+            "notify.on_change" => {
+                self.notify.on_change = match value.trim().is_empty() {
+                    true => None,
+                    false => Some(value),
+                }
+            }
+            "notify.pushover" => {
+                self.notify.pushover = parse_bool(key, &value)?
+            }
+            "notify.gotify" => {
+                self.notify.gotify = match value.is_empty() {
+                    true => None,
+                    false => {
+                        reqwest::Url::parse(&value).map_err(|err| {
+                            format!("Invalid notify.gotify: {err}")
+                        })?;
+                        Some(value)
+                    }
+                }
+            }
+            "notify.signal.account" => {
+                self.notify.signal.account = match value.is_empty() {
+                    true => None,
+                    false => Some(parse_phone_number(key, &value)?),
+                }
+            }
+            "notify.signal.recipient" => {
+                self.notify.signal.recipient = match value.is_empty() {
+                    true => None,
+                    false => Some(parse_phone_number(key, &value)?),
+                }
+            }
+            "notify.signal.api" => {
+                self.notify.signal.api = match value.is_empty() {
+                    true => None,
+                    false => {
+                        reqwest::Url::parse(&value).map_err(|err| {
+                            format!("Invalid notify.signal.api: {err}")
+                        })?;
+                        Some(value)
+                    }
+                }
+            }
+            "notify.email.to" | "notify.email.from" => {
+                let address = match value.is_empty() {
+                    true => None,
+                    false if value.contains('@') => Some(value),
+                    false => {
+                        return Err(format!(
+                            "Invalid {key}: {value}. Use an email address"
+                        )
+                        .into());
+                    }
+                };
+                match key {
+                    "notify.email.to" => self.notify.email.to = address,
+                    _ => self.notify.email.from = address,
+                }
+            }
+            "notify.email.server" => {
+                self.notify.email.server = match value.is_empty() {
+                    true => None,
+                    false => Some(value),
+                }
+            }
+            "notify.email.port" => self.notify.email.port = value.parse()?,
+            "notify.email.username" => {
+                self.notify.email.username = match value.is_empty() {
+                    true => None,
+                    false => Some(value),
+                }
+            }
+            "notify.digest_at" => {
+                self.notify.digest_at = match value.is_empty() {
+                    true => None,
+                    false => Some(
+                        NaiveTime::parse_from_str(value.trim(), "%H:%M")
+                            .map_err(|_| {
+                                format!(
+                                    "Invalid notify.digest_at: {value}. Try \
+                                     e.g. 07:00"
+                                )
+                            })?,
+                    ),
+                }
+            }
+            "notify.quiet_hours" => {
+                self.notify.quiet_hours = match value.is_empty() {
+                    true => None,
+                    false => Some(value.parse()?),
+                }
+            }
+            "notify.ntfy.topic" => {
+                self.notify.ntfy.topic = match value.is_empty() {
+                    true => None,
+                    false => Some(value),
+                }
+            }
+            "notify.ntfy.server" => {
+                reqwest::Url::parse(&value).map_err(|err| {
+                    format!("Invalid notify.ntfy.server: {err}")
+                })?;
+                self.notify.ntfy.server = value
+            }
+            "pickup_reminder_days" => {
+                self.pickup_reminder_days = value.parse()?
+            }
+            "auto_archive_days" => {
+                self.auto_archive_days = match value.is_empty() {
+                    true => None,
+                    false => Some(value.parse()?),
+                }
+            }
+            "polling.min_minutes" => {
+                self.polling.min_minutes = value.parse()?
+            }
+            "polling.max_minutes" => {
+                self.polling.max_minutes = value.parse()?
+            }
+            "server.users" => {
+                let users = parse_list(&value);
+                if let Some(bad) = users.iter().find(|name| {
+                    !name.chars().all(|c| {
+                        c.is_ascii_alphanumeric() || c == '-' || c == '_'
+                    })
+                }) {
+                    return Err(format!(
+                        "Invalid user name for server.users: {bad}. Use \
+                         letters, digits, '-' and '_'"
+                    )
+                    .into());
+                }
+                self.server.users = users;
+            }
+            "theme" => {
+                self.theme = match value.as_str() {
+                    "plain" => Theme::Plain,
+                    "emoji" => Theme::Emoji,
+                    "nerd-font" => Theme::NerdFont,
+                    _ => {
+                        return Err(format!(
+                            "Invalid value for theme: {value}. Expected \
+                             plain, emoji or nerd-font"
+                        )
+                        .into());
+                    }
+                }
+            }
+            // e.g. "icons.delivered". An empty value removes it.
+            _ if key.starts_with("icons.") => {
+                let name = &key["icons.".len()..];
+                match value.is_empty() {
+                    true => self.icons.remove(name),
+                    false => self.icons.insert(name.into(), value),
+                };
+            }
+            // e.g. "credentials.ups.client_id". An empty value removes it.
+            _ if key.starts_with("credentials.") => {
+                let Some((carrier, name)) = key
+                    .strip_prefix("credentials.")
+                    .and_then(|rest| rest.split_once('.'))
+                else {
+                    return Err(format!(
+                        "Invalid setting key: {key}. Expected \
+                         credentials.<carrier>.<key>"
+                    )
+                    .into());
+                };
+                let values = self
+                    .credentials
+                    .entry(carrier.into())
+                    .or_default();
+                match value.is_empty() {
+                    true => values.remove(name),
+                    false => values.insert(name.into(), value),
+                };
+                if values.is_empty() {
+                    self.credentials.remove(carrier);
+                }
+            }
             _ => return Err(format!("Invalid setting key: {key}").into()),
         }
         Ok(())
     }
+    #[allow(clippy::should_implement_trait)]
     pub fn default() -> Result<Self> {
         let home = get_home_dir().expect("Couldn't compute home dir!");
-        let urls_file = home.join("packtrack.urls");
+        let urls_file = home.join("packtrack.json");
         Ok(Self {
             urls_file,
             postcode: None,
             language: None,
+            languages: vec![],
             cache_file: get_cache_dir()?.join("packtrack-cache.json"),
+            cache_backend: CacheBackend::default(),
             cache_seconds: 30,
             cache_max_entries: 10,
+            cache_max_bytes: None,
+            stale_while_revalidate: false,
+            trackers: TrackerSettings::default(),
+            browser_path: None,
+            credentials: CredentialMap::new(),
+            imap: ImapSettings::default(),
+            sync: SyncSettings::default(),
+            notify: NotifySettings::default(),
+            pickup_reminder_days: 2,
+            auto_archive_days: None,
+            polling: PollingSettings::default(),
+            server: ServerSettings::default(),
+            theme: Theme::default(),
+            icons: BTreeMap::new(),
         })
     }
 }
 
+/// Parse a comma separated list like "postnl, dhl"
+fn parse_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Parse "true"/"false" (or "yes"/"no", "on"/"off", "1"/"0")
+fn parse_bool(key: &str, value: &str) -> Result<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Ok(true),
+        "false" | "no" | "off" | "0" => Ok(false),
+        _ => Err(format!(
+            "Invalid value for {key}: {value}. Expected true or false"
+        )
+        .into()),
+    }
+}
+
+/// Parse an international phone number like "+31 6 12345678", leaving out
+/// the spaces and dashes
+fn parse_phone_number(key: &str, value: &str) -> Result<String> {
+    let number: String = value
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-'))
+        .collect();
+    match number.strip_prefix('+') {
+        Some(digits)
+            if !digits.is_empty()
+                && digits
+                    .chars()
+                    .all(|c| c.is_ascii_digit()) =>
+        {
+            Ok(number)
+        }
+        _ => Err(format!(
+            "Invalid value for {key}: {value}. Expected a phone number with \
+             the country code, e.g. +31612345678"
+        )
+        .into()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -88,6 +512,259 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_settings_update_auto_archive_days() -> Result<()> {
+        let mut settings = Settings::default()?;
+        assert_eq!(settings.auto_archive_days, None);
+        settings.update("auto_archive_days", "30")?;
+        assert_eq!(settings.auto_archive_days, Some(30));
+        settings.update("auto_archive_days", "")?;
+        assert_eq!(settings.auto_archive_days, None);
+        assert!(
+            settings
+                .update("auto_archive_days", "-1")
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_settings_update_bytes() -> Result<()> {
+        let mut settings = Settings::default()?;
+        settings.update("cache_max_bytes", "50 MiB")?;
+        assert_eq!(settings.cache_max_bytes, Some(50 * 1024 * 1024));
+        settings.update("cache_max_bytes", "1000")?;
+        assert_eq!(settings.cache_max_bytes, Some(1000));
+        settings.update("cache_max_bytes", "")?;
+        assert_eq!(settings.cache_max_bytes, None);
+        assert!(
+            settings
+                .update("cache_max_bytes", "lots")
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_settings_update_list() -> Result<()> {
+        let mut settings = Settings::default()?;
+        settings.update("trackers.priority", "gls, postnl,")?;
+        assert_eq!(settings.trackers.priority, vec!["gls", "postnl"]);
+        settings.update("trackers.priority", "")?;
+        assert!(settings.trackers.priority.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_settings_update_server_users() -> Result<()> {
+        let mut settings = Settings::default()?;
+        settings.update("server.users", "alice, bob_2")?;
+        assert_eq!(settings.server.users, vec!["alice", "bob_2"]);
+        assert!(
+            settings
+                .update("server.users", "alice, ../bob")
+                .is_err()
+        );
+        assert_eq!(settings.server.users, vec!["alice", "bob_2"]);
+        settings.update("server.users", "")?;
+        assert!(settings.server.users.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_settings_update_bool() -> Result<()> {
+        let mut settings = Settings::default()?;
+        settings.update("notify.stdout", "no")?;
+        assert!(!settings.notify.stdout);
+        settings.update("notify.stdout", "True")?;
+        assert!(settings.notify.stdout);
+        assert!(
+            settings
+                .update("notify.stdout", "maybe")
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_settings_update_webhook() -> Result<()> {
+        let mut settings = Settings::default()?;
+        settings.update("notify.webhook", "https://example.com/hook")?;
+        assert_eq!(
+            settings.notify.webhook.as_deref(),
+            Some("https://example.com/hook")
+        );
+        assert!(
+            settings
+                .update("notify.webhook", "not a url")
+                .is_err()
+        );
+        settings.update("notify.webhook", "")?;
+        assert!(settings.notify.webhook.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_settings_update_ntfy() -> Result<()> {
+        let mut settings = Settings::default()?;
+        assert_eq!(settings.notify.ntfy.server, "https://ntfy.sh");
+        settings.update("notify.ntfy.topic", "my-packages")?;
+        assert_eq!(settings.notify.ntfy.topic.as_deref(), Some("my-packages"));
+        settings.update("notify.ntfy.server", "https://ntfy.example.com")?;
+        assert!(
+            settings
+                .update("notify.ntfy.server", "ntfy.example.com")
+                .is_err()
+        );
+        assert_eq!(settings.notify.ntfy.server, "https://ntfy.example.com");
+        Ok(())
+    }
+
+    #[test]
+    fn test_settings_update_gotify() -> Result<()> {
+        let mut settings = Settings::default()?;
+        settings.update("notify.gotify", "https://gotify.example.com")?;
+        assert!(
+            settings
+                .update("notify.gotify", "gotify.example.com")
+                .is_err()
+        );
+        assert_eq!(
+            settings.notify.gotify.as_deref(),
+            Some("https://gotify.example.com")
+        );
+        settings.update("notify.gotify", "")?;
+        assert!(settings.notify.gotify.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_settings_update_signal() -> Result<()> {
+        let mut settings = Settings::default()?;
+        settings.update("notify.signal.account", "+31600000001")?;
+        settings.update("notify.signal.recipient", "+31 6 00000002")?;
+        assert!(
+            settings
+                .update("notify.signal.recipient", "0600000002")
+                .is_err()
+        );
+        settings.update("notify.signal.api", "http://localhost:8080")?;
+        let signal = &settings.notify.signal;
+        assert_eq!(signal.account.as_deref(), Some("+31600000001"));
+        assert_eq!(signal.recipient.as_deref(), Some("+31600000002"));
+        assert_eq!(signal.api.as_deref(), Some("http://localhost:8080"));
+        settings.update("notify.signal.api", "")?;
+        assert!(settings.notify.signal.api.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_settings_update_on_change() -> Result<()> {
+        let mut settings = Settings::default()?;
+        settings.update("notify.on_change", "~/bin/package-changed.sh")?;
+        assert_eq!(
+            settings.notify.on_change.as_deref(),
+            Some("~/bin/package-changed.sh")
+        );
+        settings.update("notify.on_change", "")?;
+        assert!(settings.notify.on_change.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_settings_update_email() -> Result<()> {
+        let mut settings = Settings::default()?;
+        settings.update("notify.email.to", "me@example.com")?;
+        settings.update("notify.email.server", "smtp.example.com")?;
+        settings.update("notify.email.port", "465")?;
+        assert!(
+            settings
+                .update("notify.email.from", "packtrack")
+                .is_err()
+        );
+        let email = &settings.notify.email;
+        assert_eq!(email.to.as_deref(), Some("me@example.com"));
+        assert_eq!(email.from, None);
+        assert_eq!(email.server.as_deref(), Some("smtp.example.com"));
+        assert_eq!(email.port, 465);
+        settings.update("notify.email.to", "")?;
+        assert!(settings.notify.email.to.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_settings_update_digest_at() -> Result<()> {
+        let mut settings = Settings::default()?;
+        settings.update("notify.digest_at", "07:30")?;
+        assert_eq!(
+            settings.notify.digest_at,
+            NaiveTime::from_hms_opt(7, 30, 0)
+        );
+        assert!(
+            settings
+                .update("notify.digest_at", "morning")
+                .is_err()
+        );
+        settings.update("notify.digest_at", "")?;
+        assert!(settings.notify.digest_at.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_settings_update_quiet_hours() -> Result<()> {
+        let mut settings = Settings::default()?;
+        settings.update("notify.quiet_hours", "22:00-08:00")?;
+        assert_eq!(
+            settings
+                .notify
+                .quiet_hours
+                .map(|hours| hours.to_string())
+                .as_deref(),
+            Some("22:00-08:00")
+        );
+        assert!(
+            settings
+                .update("notify.quiet_hours", "at night")
+                .is_err()
+        );
+        settings.update("notify.quiet_hours", "")?;
+        assert!(settings.notify.quiet_hours.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_settings_update_credentials() -> Result<()> {
+        let mut settings = Settings::default()?;
+        settings.update("credentials.ups.client_id", "abc")?;
+        assert_eq!(settings.credentials["ups"]["client_id"], "abc");
+        settings.update("credentials.ups.client_id", "")?;
+        assert!(settings.credentials.is_empty());
+        assert!(
+            settings
+                .update("credentials.ups", "abc")
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_settings_update_theme() -> Result<()> {
+        let mut settings = Settings::default()?;
+        settings.update("theme", "nerd-font")?;
+        assert_eq!(settings.theme, Theme::NerdFont);
+        assert!(
+            settings
+                .update("theme", "fancy")
+                .is_err()
+        );
+
+        settings.update("icons.postnl", "🟧")?;
+        assert_eq!(settings.icons["postnl"], "🟧");
+        settings.update("icons.postnl", "")?;
+        assert!(settings.icons.is_empty());
+        Ok(())
+    }
+
     #[test]
     fn test_settings_update_path() -> Result<()> {
         let mut settings = Settings::default()?;
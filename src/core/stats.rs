@@ -0,0 +1,191 @@
+// Statistics about past deliveries. The cache keeps the last few responses
+// for each URL, so for delivered packages we can see what the carrier
+// promised before delivery, and compare it with what actually happened.
+
+use crate::cache::Cache;
+use crate::tracker::{TimeWindow, TrackerRegistry};
+use crate::utils::UtcTime;
+use chrono::TimeDelta;
+use std::collections::BTreeMap;
+
+/// A delivered package, and the last delivery window the carrier promised
+#[derive(Debug, Clone, PartialEq)]
+pub struct Delivery {
+    /// e.g. "PostNL"
+    pub carrier:   String,
+    pub promised:  TimeWindow,
+    pub delivered: UtcTime,
+}
+impl Delivery {
+    /// How far outside the promised window the package was delivered:
+    /// positive if late, negative if early, zero if on time.
+    pub fn slip(&self) -> TimeDelta {
+        if self.delivered > self.promised.end {
+            self.delivered - self.promised.end
+        } else if self.delivered < self.promised.start {
+            self.delivered - self.promised.start
+        } else {
+            TimeDelta::zero()
+        }
+    }
+
+    /// Delivered before the end of the promised window
+    pub fn is_on_time(&self) -> bool {
+        self.delivered <= self.promised.end
+    }
+}
+
+/// Find the delivered packages in the cache that had a delivery window (or
+/// ETA) at some point. Packages whose responses can't be parsed are skipped.
+pub fn deliveries_from_cache(
+    cache: &dyn Cache,
+    registry: &TrackerRegistry,
+) -> Vec<Delivery> {
+    let mut deliveries = vec![];
+    for url in cache.get_all_urls() {
+        let Ok(tracker) = registry.get_handler(&url) else {
+            continue;
+        };
+        let mut entries = cache.get_all(&url);
+        entries.sort_by_key(|entry| entry.created);
+        let packages: Vec<_> = entries
+            .into_iter()
+            .filter_map(|entry| tracker.parse(entry.text.clone()).ok())
+            .collect();
+        let Some(delivered) = packages
+            .iter()
+            .rev()
+            .find_map(|p| p.delivered)
+        else {
+            continue;
+        };
+        // The most recent promise, which is usually the most accurate one
+        let promised = packages.iter().rev().find_map(|p| {
+            p.eta_window
+                .clone()
+                .or(p.eta.map(|eta| TimeWindow {
+                    start: eta,
+                    end:   eta,
+                }))
+        });
+        let Some(promised) = promised else {
+            continue;
+        };
+        deliveries.push(Delivery {
+            carrier: packages[0].channel.clone(),
+            promised,
+            delivered,
+        });
+    }
+    deliveries
+}
+
+/// How punctual one carrier was
+#[derive(Debug, PartialEq)]
+pub struct Punctuality {
+    pub carrier:      String,
+    pub deliveries:   usize,
+    /// Deliveries before the end of the promised window
+    pub on_time:      usize,
+    /// Average `Delivery::slip`
+    pub average_slip: TimeDelta,
+}
+impl Punctuality {
+    pub fn on_time_percentage(&self) -> f64 {
+        100.0 * self.on_time as f64 / self.deliveries as f64
+    }
+}
+
+/// Punctuality per carrier, sorted by carrier
+pub fn punctuality(deliveries: &[Delivery]) -> Vec<Punctuality> {
+    let mut by_carrier: BTreeMap<&str, Vec<&Delivery>> = BTreeMap::new();
+    for delivery in deliveries {
+        by_carrier
+            .entry(&delivery.carrier)
+            .or_default()
+            .push(delivery);
+    }
+    by_carrier
+        .into_iter()
+        .map(|(carrier, deliveries)| {
+            let total_slip: TimeDelta = deliveries
+                .iter()
+                .map(|d| d.slip())
+                .sum();
+            Punctuality {
+                carrier:      carrier.into(),
+                deliveries:   deliveries.len(),
+                on_time:      deliveries
+                    .iter()
+                    .filter(|d| d.is_on_time())
+                    .count(),
+                average_slip: total_slip / deliveries.len() as i32,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::MemoryCache;
+    use crate::mocks::load_text;
+
+    fn delivery(carrier: &str, delivered: &str) -> Delivery {
+        Delivery {
+            carrier:   carrier.into(),
+            promised:  TimeWindow {
+                start: "2026-10-16T10:00:00Z".parse().unwrap(),
+                end:   "2026-10-16T12:00:00Z".parse().unwrap(),
+            },
+            delivered: delivered.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_punctuality() {
+        let deliveries = vec![
+            delivery("PostNL", "2026-10-16T11:00:00Z"),
+            delivery("PostNL", "2026-10-16T14:00:00Z"),
+            delivery("PostNL", "2026-10-16T09:00:00Z"),
+            delivery("DHL", "2026-10-16T12:00:00Z"),
+        ];
+        assert_eq!(deliveries[0].slip(), TimeDelta::zero());
+        assert_eq!(deliveries[1].slip(), TimeDelta::hours(2));
+        assert_eq!(deliveries[2].slip(), TimeDelta::hours(-1));
+        assert_eq!(
+            punctuality(&deliveries),
+            vec![
+                Punctuality {
+                    carrier:      "DHL".into(),
+                    deliveries:   1,
+                    on_time:      1,
+                    average_slip: TimeDelta::zero(),
+                },
+                Punctuality {
+                    carrier:      "PostNL".into(),
+                    deliveries:   3,
+                    on_time:      2,
+                    average_slip: TimeDelta::minutes(20),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_deliveries_from_cache() -> crate::Result<()> {
+        let mut cache = MemoryCache::default();
+        let url = "https://jouw.postnl.nl/track-and-trace/3SABCD1234567";
+        cache.insert(url.into(), load_text("postnl_undelivered.json")?);
+        cache.insert(url.into(), load_text("postnl_delivered.json")?);
+        // Never delivered
+        let other = "https://jouw.postnl.nl/track-and-trace/3SABCD7654321";
+        cache.insert(other.into(), load_text("postnl_undelivered.json")?);
+
+        let deliveries =
+            deliveries_from_cache(&cache, &TrackerRegistry::default());
+        assert_eq!(deliveries.len(), 1);
+        assert_eq!(deliveries[0].carrier, "PostNL");
+        Ok(())
+    }
+}
@@ -0,0 +1,153 @@
+// Output for a desktop status bar, in the JSON that waybar's custom modules
+// read (with `"return-type": "json"`). Status bars run this every few seconds,
+// so like the prompt it only looks at the cache.
+
+use crate::cache::Cache;
+use crate::tracker::{Package, TrackerRegistry};
+use crate::url_store::AnnotatedUrl;
+use crate::utils::UtcTime;
+use crate::verify::cached_packages;
+use chrono::{Local, NaiveDate};
+use serde::Serialize;
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct StatusBar {
+    /// "📦 2" for 2 packages arriving today. Empty if none are, which makes
+    /// waybar hide the module.
+    pub text:    String,
+    /// The packages arriving today, and how many others are on their way
+    pub tooltip: String,
+    /// For styling the module: "arriving", "in-transit" or "none"
+    pub class:   &'static str,
+}
+
+impl StatusBar {
+    /// Summarise the URLs using the latest cached response for each, however
+    /// old it is. URLs that aren't in the cache are left out.
+    pub fn from_cache(
+        urls: &[AnnotatedUrl],
+        cache: &dyn Cache,
+        registry: &TrackerRegistry,
+        now: UtcTime,
+    ) -> Self {
+        let today = now.with_timezone(&Local).date_naive();
+        let cached = cached_packages(cache, registry, urls);
+        let mut arriving = vec![];
+        let mut in_progress = 0;
+        for url in urls {
+            let Some(cached) = cached.get(&url.url) else {
+                continue;
+            };
+            let package = &cached.package;
+            if package.status.is_final() {
+                continue;
+            }
+            in_progress += 1;
+            if let Some(time) = arrival_today(package, today) {
+                let mut line = url.package_name(package);
+                if let Some(description) = &url.description {
+                    line += &format!(" ({description})");
+                }
+                arriving.push(format!("{line}: {time}"));
+            }
+        }
+
+        let others = in_progress - arriving.len();
+        let mut tooltip = arriving.clone();
+        match (arriving.is_empty(), others) {
+            (true, 0) => tooltip.push("No packages on their way".into()),
+            (true, n) => tooltip.push(format!("{n} on their way, none today")),
+            (false, 0) => {}
+            (false, n) => tooltip.push(format!("{n} more on their way")),
+        }
+        Self {
+            text:    match arriving.len() {
+                0 => String::new(),
+                n => format!("📦 {n}"),
+            },
+            tooltip: tooltip.join("\n"),
+            class:   match (arriving.is_empty(), in_progress) {
+                (false, _) => "arriving",
+                (true, 0) => "none",
+                (true, _) => "in-transit",
+            },
+        }
+    }
+}
+
+/// "17:00 -- 19:00" if the package is expected on the day
+fn arrival_today(package: &Package, today: NaiveDate) -> Option<String> {
+    let local = |time: UtcTime| time.with_timezone(&Local);
+    if let Some(window) = &package.eta_window
+        && local(window.start).date_naive() == today
+    {
+        return Some(format!(
+            "{} -- {}",
+            local(window.start).format("%H:%M"),
+            local(window.end).format("%H:%M")
+        ));
+    }
+    let eta = package
+        .eta
+        .filter(|eta| local(*eta).date_naive() == today)?;
+    Some(local(eta).format("%H:%M").to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::MemoryCache;
+    use crate::mocks::load_text;
+
+    #[test]
+    fn test_status_bar() -> crate::Result<()> {
+        let in_transit = "https://jouw.postnl.nl/track-and-trace/3SABCD1234567";
+        let delivered = "https://jouw.postnl.nl/track-and-trace/3SABCD7654321";
+        let mut cache = MemoryCache::default();
+        cache.insert(in_transit.into(), load_text("postnl_undelivered.json")?);
+        cache.insert(delivered.into(), load_text("postnl_delivered.json")?);
+        let urls = vec![
+            AnnotatedUrl::new(in_transit.into(), Some("shoes".into())),
+            delivered.into(),
+        ];
+        let registry = TrackerRegistry::default();
+
+        let bar = StatusBar::from_cache(
+            &urls,
+            &cache,
+            &registry,
+            "2000-01-01T12:00:00Z".parse()?,
+        );
+        assert_eq!(
+            bar,
+            StatusBar {
+                text:    "".into(),
+                tooltip: "1 on their way, none today".into(),
+                class:   "in-transit",
+            }
+        );
+
+        // On the day it's expected
+        let package = registry
+            .get_handler(in_transit)?
+            .parse(load_text("postnl_undelivered.json")?)?;
+        let expected = package
+            .eta
+            .or(package.eta_window.map(|w| w.start))
+            .unwrap();
+        let bar = StatusBar::from_cache(&urls, &cache, &registry, expected);
+        assert_eq!(bar.text, "📦 1");
+        assert_eq!(
+            bar.tooltip,
+            "PostNL 3SPYVS100737499 (shoes): 10:45 -- 12:05"
+        );
+        assert_eq!(bar.class, "arriving");
+
+        let bar = StatusBar::from_cache(&[], &cache, &registry, expected);
+        assert_eq!(
+            serde_json::to_string(&bar)?,
+            r#"{"text":"","tooltip":"No packages on their way","class":"none"}"#
+        );
+        Ok(())
+    }
+}
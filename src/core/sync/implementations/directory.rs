@@ -0,0 +1,85 @@
+use crate::Result;
+use crate::sync::SyncBackend;
+use async_trait::async_trait;
+use std::path::PathBuf;
+
+/// Keeps the shared copy in a plain directory, which something else (rsync,
+/// Syncthing, a network mount) keeps in sync between machines.
+pub struct DirectoryBackend {
+    dir: PathBuf,
+}
+
+impl DirectoryBackend {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn file(&self, file: &str) -> PathBuf {
+        self.dir.join(file)
+    }
+}
+
+#[async_trait]
+impl SyncBackend for DirectoryBackend {
+    fn describe(&self) -> String {
+        self.dir.display().to_string()
+    }
+
+    async fn pull(&self, file: &str) -> Result<Option<String>> {
+        if !self.dir.is_dir() {
+            return Err(format!(
+                "Sync directory {} doesn't exist",
+                self.dir.display()
+            )
+            .into());
+        }
+        match tokio::fs::read_to_string(self.file(file)).await {
+            Ok(text) => Ok(Some(text)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    async fn push(&self, file: &str, text: String) -> Result<()> {
+        // Write to a temporary file first, so that a sync tool never picks up
+        // a half written file
+        let tmp = self.dir.join(format!(".{file}.tmp"));
+        tokio::fs::write(&tmp, text).await?;
+        tokio::fs::rename(&tmp, self.file(file)).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::URLS_FILE_NAME;
+    use super::*;
+
+    #[tokio::test]
+    async fn test_directory_backend() -> Result<()> {
+        let dir = std::env::temp_dir()
+            .join(format!("packtrack-sync-test-{}", std::process::id()));
+        let backend = DirectoryBackend::new(dir.clone());
+        assert!(
+            backend
+                .pull(URLS_FILE_NAME)
+                .await
+                .is_err()
+        );
+
+        std::fs::create_dir_all(&dir)?;
+        assert_eq!(backend.pull(URLS_FILE_NAME).await?, None);
+        backend
+            .push(URLS_FILE_NAME, "[]".into())
+            .await?;
+        assert_eq!(
+            backend
+                .pull(URLS_FILE_NAME)
+                .await?
+                .as_deref(),
+            Some("[]")
+        );
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,88 @@
+use crate::Result;
+use crate::sync::SyncBackend;
+use async_trait::async_trait;
+use std::path::PathBuf;
+use tokio::process::Command;
+
+/// Keeps the shared copy in a local clone of a git repo. If the repo has a
+/// remote, we pull before reading and push after writing, so the remote is
+/// what's shared between machines.
+pub struct GitBackend {
+    repo: PathBuf,
+}
+
+impl GitBackend {
+    pub fn new(repo: PathBuf) -> Self {
+        Self { repo }
+    }
+
+    fn file(&self, file: &str) -> PathBuf {
+        self.repo.join(file)
+    }
+
+    /// Run a git command in the repo, returning its stdout
+    async fn git(&self, args: &[&str]) -> Result<String> {
+        log::debug!("Running git {}", args.join(" "));
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(&self.repo)
+            .args(args)
+            .output()
+            .await
+            .map_err(|err| format!("Couldn't run git: {err}"))?;
+        if !output.status.success() {
+            return Err(format!(
+                "`git {}` failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )
+            .into());
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    async fn has_remote(&self) -> Result<bool> {
+        Ok(!self
+            .git(&["remote"])
+            .await?
+            .trim()
+            .is_empty())
+    }
+}
+
+#[async_trait]
+impl SyncBackend for GitBackend {
+    fn describe(&self) -> String {
+        format!("{} (git)", self.repo.display())
+    }
+
+    async fn pull(&self, file: &str) -> Result<Option<String>> {
+        if self.has_remote().await? {
+            self.git(&["pull", "--ff-only"]).await?;
+        }
+        match tokio::fs::read_to_string(self.file(file)).await {
+            Ok(text) => Ok(Some(text)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    async fn push(&self, file: &str, text: String) -> Result<()> {
+        tokio::fs::write(self.file(file), text).await?;
+        self.git(&["add", file]).await?;
+        let unchanged = self
+            .git(&["status", "--porcelain", file])
+            .await?
+            .trim()
+            .is_empty();
+        if unchanged {
+            return Ok(());
+        }
+        self.git(&["commit", "-m", &format!("Sync {file}"), "--", file])
+            .await?;
+        if self.has_remote().await? {
+            self.git(&["push"]).await?;
+        }
+        Ok(())
+    }
+}
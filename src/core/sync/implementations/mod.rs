@@ -0,0 +1,10 @@
+pub mod directory;
+pub mod git;
+pub mod webdav;
+
+/// Name of the shared copy of the URLs file in the sync location
+pub const URLS_FILE_NAME: &str = "packtrack-urls.json";
+/// Name of the shared copy of the archived URLs
+pub const ARCHIVED_FILE_NAME: &str = "packtrack-archived.json";
+/// Name of the shared copy of the sent notifications
+pub const SENT_FILE_NAME: &str = "packtrack-notified.json";
@@ -0,0 +1,124 @@
+use crate::Result;
+use crate::sync::SyncBackend;
+use async_trait::async_trait;
+use reqwest::{Client, RequestBuilder, StatusCode};
+
+/// Keeps the shared copy in a WebDAV folder, e.g. on Nextcloud
+pub struct WebDavBackend {
+    /// URL of the folder, without a trailing slash
+    folder:   String,
+    username: Option<String>,
+    password: Option<String>,
+    client:   Client,
+}
+
+impl WebDavBackend {
+    /// `folder` is the URL of the WebDAV folder, which must already exist
+    pub fn new(
+        folder: &str,
+        username: Option<String>,
+        password: Option<String>,
+    ) -> Self {
+        Self {
+            folder: folder.trim_end_matches('/').to_owned(),
+            username,
+            password,
+            client: Client::new(),
+        }
+    }
+
+    fn url(&self, file: &str) -> String {
+        format!("{}/{file}", self.folder)
+    }
+
+    fn authenticate(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.username {
+            Some(username) => {
+                request.basic_auth(username, self.password.as_ref())
+            }
+            None => request,
+        }
+    }
+}
+
+#[async_trait]
+impl SyncBackend for WebDavBackend {
+    fn describe(&self) -> String {
+        self.folder.clone()
+    }
+
+    async fn pull(&self, file: &str) -> Result<Option<String>> {
+        let url = self.url(file);
+        let response = self
+            .authenticate(self.client.get(&url))
+            .send()
+            .await?;
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => Ok(Some(response.text().await?)),
+            status => Err(format!("HTTP {status} when getting {url}").into()),
+        }
+    }
+
+    async fn push(&self, file: &str, text: String) -> Result<()> {
+        let url = self.url(file);
+        let response = self
+            .authenticate(self.client.put(&url))
+            .body(text)
+            .send()
+            .await?;
+        match response.status() {
+            status if status.is_success() => Ok(()),
+            status => Err(format!("HTTP {status} when uploading {url}").into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::URLS_FILE_NAME;
+    use super::*;
+    use wiremock::matchers::{basic_auth, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_webdav_backend() -> Result<()> {
+        let server = MockServer::start().await;
+        let file = format!("/dav/{URLS_FILE_NAME}");
+        Mock::given(method("GET"))
+            .and(path(&file))
+            .respond_with(ResponseTemplate::new(404))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path(&file))
+            .and(basic_auth("me", "secret"))
+            .respond_with(ResponseTemplate::new(201))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(&file))
+            .respond_with(ResponseTemplate::new(200).set_body_string("[]"))
+            .mount(&server)
+            .await;
+
+        let backend = WebDavBackend::new(
+            &format!("{}/dav/", server.uri()),
+            Some("me".into()),
+            Some("secret".into()),
+        );
+        assert_eq!(backend.pull(URLS_FILE_NAME).await?, None);
+        backend
+            .push(URLS_FILE_NAME, "[]".into())
+            .await?;
+        assert_eq!(
+            backend
+                .pull(URLS_FILE_NAME)
+                .await?
+                .as_deref(),
+            Some("[]")
+        );
+        Ok(())
+    }
+}
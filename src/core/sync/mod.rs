@@ -0,0 +1,20 @@
+// Syncing the URLs file between machines, e.g. a laptop and a home server.
+// Each machine merges the shared copy into its own URLs file, and then writes
+// the result back, so entries added on any machine end up everywhere. Merging
+// is a union, so it never conflicts, but it also means that removing a URL
+// only removes it locally; it comes back on the next sync if another machine
+// still has it. The exception is archived URLs: the archived URLs and the sent
+// notifications are synced (and merged) too, and the archived URLs are
+// removed on every machine.
+
+mod implementations;
+mod models;
+mod traits;
+mod utils;
+
+pub use implementations::directory::DirectoryBackend;
+pub use implementations::git::GitBackend;
+pub use implementations::webdav::WebDavBackend;
+pub use models::{SyncReport, SyncSettings};
+pub use traits::SyncBackend;
+pub use utils::{get_backend, sync};
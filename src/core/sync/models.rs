@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+/// Where to sync the URLs file to with `packtrack sync`
+#[derive(Serialize, Deserialize, Default)]
+pub struct SyncSettings {
+    /// "directory", "git" or "webdav"
+    pub backend:  Option<String>,
+    /// Path of the directory or git repo, or URL of the WebDAV folder
+    pub location: Option<String>,
+}
+
+/// What changed during a sync
+#[derive(Debug, PartialEq)]
+pub struct SyncReport {
+    /// Entries added or updated locally
+    pub pulled:  usize,
+    /// Entries added or updated in the shared copy
+    pub pushed:  usize,
+    /// Entries removed locally because they were archived, here or on
+    /// another machine
+    pub removed: usize,
+}
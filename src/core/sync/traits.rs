@@ -0,0 +1,17 @@
+use crate::Result;
+use async_trait::async_trait;
+
+/// Somewhere to keep the shared copies of the synced files (see
+/// `SYNC_FILES`), e.g. a git repo or a WebDAV folder.
+#[async_trait]
+pub trait SyncBackend: Send + Sync {
+    /// Short description of where the shared copies live, for logging
+    fn describe(&self) -> String;
+
+    /// Get the latest shared copy of the file, or None if it hasn't been
+    /// synced yet
+    async fn pull(&self, file: &str) -> Result<Option<String>>;
+
+    /// Replace the shared copy of the file
+    async fn push(&self, file: &str, text: String) -> Result<()>;
+}
@@ -0,0 +1,334 @@
+use super::implementations::{
+    ARCHIVED_FILE_NAME, SENT_FILE_NAME, URLS_FILE_NAME,
+};
+use super::{
+    DirectoryBackend, GitBackend, SyncBackend, SyncReport, SyncSettings,
+    WebDavBackend,
+};
+use crate::Result;
+use crate::archive::ArchivedUrls;
+use crate::notify::SentNotifications;
+use crate::tracker::Credentials;
+use crate::url_store::{AnnotatedUrl, UrlStore, merge_lists};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+/// Create the backend configured in the settings. WebDAV logins are
+/// credentials, e.g. `credentials.webdav.username`.
+pub fn get_backend(
+    settings: &SyncSettings,
+    credentials: &Credentials,
+) -> Result<Box<dyn SyncBackend>> {
+    let (Some(backend), Some(location)) =
+        (&settings.backend, &settings.location)
+    else {
+        return Err("Syncing isn't configured. Set it up with `packtrack \
+                    config set sync.backend <directory|git|webdav>` and \
+                    `packtrack config set sync.location <path or URL>`."
+            .into());
+    };
+    let backend: Box<dyn SyncBackend> = match backend.as_str() {
+        "directory" => Box::new(DirectoryBackend::new(location.into())),
+        "git" => Box::new(GitBackend::new(location.into())),
+        "webdav" => Box::new(WebDavBackend::new(
+            location,
+            credentials.get("webdav", "username"),
+            credentials.get("webdav", "password"),
+        )),
+        other => {
+            return Err(format!(
+                "Unknown sync backend: {other}. Expected directory, git or \
+                 webdav."
+            )
+            .into());
+        }
+    };
+    Ok(backend)
+}
+
+/// Merge the shared copies into the url store, the archived URLs and the sent
+/// notifications, then update the shared copies with anything they were
+/// missing. URLs archived on either side are removed from both. The url store
+/// is saved here; saving the other two is up to the caller.
+pub async fn sync(
+    url_store: &mut dyn UrlStore,
+    archived: &mut ArchivedUrls,
+    sent: &mut SentNotifications,
+    backend: &dyn SyncBackend,
+) -> Result<SyncReport> {
+    let location = backend.describe();
+    log::debug!("Syncing with {location}");
+    sync_file(backend, ARCHIVED_FILE_NAME, archived, ArchivedUrls::merge)
+        .await?;
+    sync_file(backend, SENT_FILE_NAME, sent, SentNotifications::merge).await?;
+
+    let removed: Vec<AnnotatedUrl> = url_store
+        .filter(None)
+        .iter()
+        .filter(|url| archived.is_archived(url))
+        .filter_map(|url| url_store.remove_url(&url.url))
+        .collect();
+    let remote: Option<Vec<AnnotatedUrl>> =
+        pull(backend, URLS_FILE_NAME).await?;
+    let exists = remote.is_some();
+    let (stale, mut remote): (Vec<_>, Vec<_>) = remote
+        .unwrap_or_default()
+        .into_iter()
+        .partition(|url| archived.is_archived(url));
+
+    let pulled = url_store.merge(remote.clone());
+    if pulled > 0 || !removed.is_empty() {
+        url_store.save()?;
+    }
+
+    let local = url_store.filter(None);
+    let pushed = merge_lists(&mut remote, local.clone());
+    if pushed > 0 || !stale.is_empty() || !exists {
+        push(backend, URLS_FILE_NAME, &local).await?;
+    }
+    let removed = removed.len();
+    log::info!(
+        "Synced with {location}: {pulled} pulled, {pushed} pushed, {removed} \
+         archived"
+    );
+    Ok(SyncReport {
+        pulled,
+        pushed,
+        removed,
+    })
+}
+
+/// Merge the shared copy of a file into `local`, and push the result if the
+/// shared copy was missing anything
+async fn sync_file<T>(
+    backend: &dyn SyncBackend,
+    file: &str,
+    local: &mut T,
+    merge: fn(&mut T, T) -> usize,
+) -> Result<()>
+where
+    T: Serialize + DeserializeOwned + Clone,
+{
+    match pull::<T>(backend, file).await? {
+        Some(mut remote) => {
+            merge(local, remote.clone());
+            if merge(&mut remote, local.clone()) > 0 {
+                push(backend, file, local).await?;
+            }
+        }
+        None => push(backend, file, local).await?,
+    }
+    Ok(())
+}
+
+async fn pull<T: DeserializeOwned>(
+    backend: &dyn SyncBackend,
+    file: &str,
+) -> Result<Option<T>> {
+    let Some(text) = backend.pull(file).await? else {
+        return Ok(None);
+    };
+    let parsed = serde_json::from_str(&text).map_err(|err| {
+        format!("Couldn't read {file} at {}: {err}", backend.describe())
+    })?;
+    Ok(Some(parsed))
+}
+
+async fn push<T: Serialize>(
+    backend: &dyn SyncBackend,
+    file: &str,
+    value: &T,
+) -> Result<()> {
+    backend
+        .push(file, serde_json::to_string_pretty(value)?)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notify::{Notification, Priority};
+    use crate::test_utils::MemoryUrlStore;
+    use async_trait::async_trait;
+    use chrono::Utc;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct FakeBackend(Mutex<HashMap<String, String>>);
+    #[async_trait]
+    impl SyncBackend for FakeBackend {
+        fn describe(&self) -> String {
+            "fake".into()
+        }
+        async fn pull(&self, file: &str) -> Result<Option<String>> {
+            Ok(self
+                .0
+                .lock()
+                .unwrap()
+                .get(file)
+                .cloned())
+        }
+        async fn push(&self, file: &str, text: String) -> Result<()> {
+            self.0
+                .lock()
+                .unwrap()
+                .insert(file.into(), text);
+            Ok(())
+        }
+    }
+
+    /// The state that a machine syncs
+    struct Machine {
+        urls:     MemoryUrlStore,
+        archived: ArchivedUrls,
+        sent:     SentNotifications,
+    }
+
+    impl Machine {
+        fn new(urls: &[&str]) -> Self {
+            Self {
+                urls:     MemoryUrlStore::new(
+                    urls.iter()
+                        .map(|&url| AnnotatedUrl::from(url))
+                        .collect(),
+                ),
+                archived: ArchivedUrls::default(),
+                sent:     SentNotifications::default(),
+            }
+        }
+
+        async fn sync(
+            &mut self,
+            backend: &dyn SyncBackend,
+        ) -> Result<SyncReport> {
+            sync(&mut self.urls, &mut self.archived, &mut self.sent, backend)
+                .await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sync_two_machines() -> Result<()> {
+        let backend = FakeBackend::default();
+        let mut laptop = Machine::new(&["a.com", "b.com"]);
+        let mut server = Machine::new(&["b.com", "c.com"]);
+
+        let report = laptop.sync(&backend).await?;
+        assert_eq!(
+            report,
+            SyncReport {
+                pulled:  0,
+                pushed:  2,
+                removed: 0,
+            }
+        );
+
+        let report = server.sync(&backend).await?;
+        assert_eq!(
+            report,
+            SyncReport {
+                pulled:  1,
+                pushed:  1,
+                removed: 0,
+            }
+        );
+        assert_eq!(server.urls.urls(), vec!["b.com", "c.com", "a.com"]);
+
+        let report = laptop.sync(&backend).await?;
+        assert_eq!(
+            report,
+            SyncReport {
+                pulled:  1,
+                pushed:  0,
+                removed: 0,
+            }
+        );
+        assert_eq!(laptop.urls.urls(), vec!["a.com", "b.com", "c.com"]);
+
+        // Nothing left to do
+        let report = server.sync(&backend).await?;
+        assert_eq!(
+            report,
+            SyncReport {
+                pulled:  0,
+                pushed:  0,
+                removed: 0,
+            }
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sync_archived() -> Result<()> {
+        let backend = FakeBackend::default();
+        let mut laptop = Machine::new(&["a.com", "b.com"]);
+        let mut server = Machine::new(&[]);
+        laptop.sync(&backend).await?;
+        server.sync(&backend).await?;
+        assert_eq!(server.urls.urls(), vec!["a.com", "b.com"]);
+
+        // The laptop archives a.com, but the server still has it
+        let archived = laptop.urls.remove_url("a.com").unwrap();
+        laptop
+            .archived
+            .mark_archived(&archived.url, Utc::now());
+        let notification = Notification {
+            title:    "Package delivered".into(),
+            body:     "PostNL ABC: Delivered".into(),
+            url:      Some("a.com".into()),
+            priority: Priority::Normal,
+            update:   None,
+        };
+        laptop
+            .sent
+            .mark_sent(&notification, Utc::now());
+        let report = laptop.sync(&backend).await?;
+        assert_eq!(
+            report,
+            SyncReport {
+                pulled:  0,
+                pushed:  0,
+                removed: 0,
+            }
+        );
+        assert_eq!(laptop.urls.urls(), vec!["b.com"]);
+
+        let report = server.sync(&backend).await?;
+        assert_eq!(
+            report,
+            SyncReport {
+                pulled:  0,
+                pushed:  0,
+                removed: 1,
+            }
+        );
+        assert_eq!(server.urls.urls(), vec!["b.com"]);
+        assert!(server.sent.is_sent(&notification));
+
+        // Adding it again afterwards brings it back everywhere
+        server
+            .urls
+            .add(AnnotatedUrl::new("a.com".into(), None))?;
+        server.sync(&backend).await?;
+        laptop.sync(&backend).await?;
+        assert_eq!(laptop.urls.urls(), vec!["b.com", "a.com"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_backend() {
+        let credentials = Credentials::default();
+        let mut settings = SyncSettings::default();
+        assert!(get_backend(&settings, &credentials).is_err());
+        settings.backend = Some("webdav".into());
+        settings.location = Some("https://dav.example.com/packtrack/".into());
+        let backend = get_backend(&settings, &credentials).unwrap();
+        assert!(
+            backend
+                .describe()
+                .starts_with("https://dav.example.com/packtrack")
+        );
+        settings.backend = Some("ftp".into());
+        assert!(get_backend(&settings, &credentials).is_err());
+    }
+}
@@ -0,0 +1,345 @@
+// Helpers for testing code that uses packtrack, without hitting the real
+// carrier APIs. Enable the `test-utils` feature to use these outside of
+// packtrack's own tests.
+//
+// ```ignore
+// let server = FakeCarrierServer::start().await;
+// server.respond("3SABCD1234567", fixtures::POSTNL_DELIVERED).await;
+// let url = AnnotatedUrl::new(
+//     "https://jouw.postnl.nl/track-and-trace/3SABCD1234567".into(),
+//     None,
+// );
+// let jobs = track_urls(vec![url], MemoryCache::default(), &server.context())
+//     .await?;
+// ```
+
+use crate::Result;
+use crate::api::Context;
+use crate::url_store::{
+    AnnotatedUrl, UrlStore, add_to_list, attach_to_placeholder, filter,
+    merge_lists, note_url, remove_from_list, tag_urls,
+};
+use crate::utils::UtcTime;
+use base64::{Engine, engine::general_purpose};
+use reqwest::header::AUTHORIZATION;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use wiremock::{Match, Mock, MockServer, Request, ResponseTemplate};
+
+pub use crate::cache::MemoryCache;
+
+/// Parse an RFC 3339 time, e.g. "2024-06-01T12:00:00Z"
+pub fn utc(s: &str) -> UtcTime {
+    s.parse().unwrap()
+}
+
+/// A url store that keeps its URLs in memory instead of a file. Clones share
+/// the URLs, so a test can keep one to see what the code under test did to
+/// its copy.
+#[derive(Clone, Default)]
+pub struct MemoryUrlStore(Arc<Mutex<Vec<AnnotatedUrl>>>);
+
+impl MemoryUrlStore {
+    pub fn new(urls: Vec<AnnotatedUrl>) -> Self {
+        Self(Arc::new(Mutex::new(urls)))
+    }
+
+    /// Just the URLs of the entries, in order
+    pub fn urls(&self) -> Vec<String> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|u| u.url.clone())
+            .collect()
+    }
+}
+
+impl UrlStore for MemoryUrlStore {
+    fn add(&mut self, entry: AnnotatedUrl) -> Result<()> {
+        add_to_list(&mut self.0.lock().unwrap(), entry)
+    }
+    fn remove(&mut self, query: &str) -> Result<Vec<AnnotatedUrl>> {
+        remove_from_list(&mut self.0.lock().unwrap(), query)
+    }
+    fn remove_url(&mut self, url: &str) -> Option<AnnotatedUrl> {
+        let mut urls = self.0.lock().unwrap();
+        let idx = urls.iter().position(|u| u.url == url)?;
+        Some(urls.remove(idx))
+    }
+    fn attach(&mut self, query: &str, url: String) -> Result<AnnotatedUrl> {
+        attach_to_placeholder(&mut self.0.lock().unwrap(), query, url)
+    }
+    fn tag(
+        &mut self,
+        query: &str,
+        tags: &[String],
+    ) -> Result<Vec<AnnotatedUrl>> {
+        tag_urls(&mut self.0.lock().unwrap(), query, tags, false)
+    }
+    fn untag(
+        &mut self,
+        query: &str,
+        tags: &[String],
+    ) -> Result<Vec<AnnotatedUrl>> {
+        tag_urls(&mut self.0.lock().unwrap(), query, tags, true)
+    }
+    fn note(
+        &mut self,
+        query: &str,
+        note: Option<String>,
+    ) -> Result<AnnotatedUrl> {
+        note_url(&mut self.0.lock().unwrap(), query, note)
+    }
+    fn merge(&mut self, other: Vec<AnnotatedUrl>) -> usize {
+        merge_lists(&mut self.0.lock().unwrap(), other)
+    }
+    fn filter(&self, query: Option<&str>) -> Vec<AnnotatedUrl> {
+        filter(&self.0.lock().unwrap(), query)
+    }
+    fn save(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// A new, empty directory in the system's temp dir, which no other test (or
+/// test run) uses, e.g. "/tmp/packtrack-sync-test-1234-0"
+pub fn temp_dir(name: &str) -> Result<PathBuf> {
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+    let count = COUNT.fetch_add(1, Ordering::Relaxed);
+    let dir = std::env::temp_dir()
+        .join(format!("packtrack-{name}-{}-{count}", std::process::id()));
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir)?;
+    }
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Canned responses from the carrier APIs
+pub mod fixtures {
+    pub const DHL_DELIVERED: &str =
+        include_str!("../../mocks/dhlecommerce_delivered.json");
+    pub const DHL_UNDELIVERED: &str =
+        include_str!("../../mocks/dhlecommerce_undelivered.json");
+    pub const DHL_SERVICEPOINT: &str =
+        include_str!("../../mocks/dhlecommerce_servicepoint.json");
+    pub const DPD_DELIVERED: &str =
+        include_str!("../../mocks/dpd_delivered.html");
+    pub const DPD_UNDELIVERED: &str =
+        include_str!("../../mocks/dpd_undelivered.html");
+    pub const FEDEX_DELIVERED: &str =
+        include_str!("../../mocks/fedex_delivered.json");
+    pub const FEDEX_UNDELIVERED: &str =
+        include_str!("../../mocks/fedex_undelivered.json");
+    pub const GLS_DELIVERED: &str =
+        include_str!("../../mocks/gls_delivered.json");
+    pub const GLS_UNDELIVERED: &str =
+        include_str!("../../mocks/gls_undelivered.json");
+    pub const POSTNL_DELIVERED: &str =
+        include_str!("../../mocks/postnl_delivered.json");
+    pub const POSTNL_UNDELIVERED: &str =
+        include_str!("../../mocks/postnl_undelivered.json");
+    pub const TRUNKRS_DELIVERED: &str =
+        include_str!("../../mocks/trunkrs_delivered.json");
+    pub const TRUNKRS_UNDELIVERED: &str =
+        include_str!("../../mocks/trunkrs_undelivered.json");
+}
+
+/// A local HTTP server that pretends to be all the carrier APIs. Requests are
+/// matched by the barcode they contain, so the same server works for every
+/// carrier.
+pub struct FakeCarrierServer {
+    server: MockServer,
+}
+
+impl FakeCarrierServer {
+    pub async fn start() -> Self {
+        Self {
+            server: MockServer::start().await,
+        }
+    }
+
+    /// e.g. "http://127.0.0.1:1234"
+    pub fn origin(&self) -> String {
+        self.server.uri()
+    }
+
+    /// A Context which sends all carrier requests to this server, and doesn't
+    /// reuse cache entries.
+    pub fn context(&self) -> Context {
+        Context {
+            api_origin: Some(self.origin()),
+            use_cache: false,
+            ..Context::default()
+        }
+    }
+
+    /// Respond to requests for the given barcode with the given body (e.g.
+    /// one of the `fixtures`).
+    pub async fn respond(&self, barcode: &str, body: &str) {
+        let content_type = match body.trim_start().starts_with('<') {
+            true => "text/html",
+            false => "application/json",
+        };
+        let response = ResponseTemplate::new(200)
+            .set_body_raw(body.as_bytes().to_vec(), content_type);
+        self.respond_with(barcode, response)
+            .await
+    }
+
+    /// Respond to requests for the given barcode with an HTTP error status
+    pub async fn respond_status(&self, barcode: &str, status: u16) {
+        self.respond_with(barcode, ResponseTemplate::new(status))
+            .await
+    }
+
+    /// Respond to requests for the given barcode with a custom response
+    pub async fn respond_with(
+        &self,
+        barcode: &str,
+        response: ResponseTemplate,
+    ) {
+        Mock::given(Barcode(barcode.into()))
+            .respond_with(response)
+            .mount(&self.server)
+            .await
+    }
+}
+
+/// Matches requests containing the barcode, either in the URL, in the body
+/// (FedEx), or in a basic auth header (Trunkrs).
+struct Barcode(String);
+impl Match for Barcode {
+    fn matches(&self, request: &Request) -> bool {
+        if request.url.as_str().contains(&self.0)
+            || String::from_utf8_lossy(&request.body).contains(&self.0)
+        {
+            return true;
+        }
+        request
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Basic "))
+            .and_then(|b64| {
+                general_purpose::STANDARD
+                    .decode(b64)
+                    .ok()
+            })
+            .is_some_and(|creds| {
+                String::from_utf8_lossy(&creds).contains(&self.0)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Result;
+    use crate::api::{track_urls, track_urls_with};
+    use crate::tracker::PackageStatus;
+    use crate::url_store::AnnotatedUrl;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_track_urls_end_to_end() -> Result<()> {
+        let server = FakeCarrierServer::start().await;
+        server
+            .respond("3SABCD1234567", fixtures::POSTNL_UNDELIVERED)
+            .await;
+        server
+            .respond("05112087822887", fixtures::DPD_DELIVERED)
+            .await;
+        server
+            .respond("419108119", fixtures::TRUNKRS_DELIVERED)
+            .await;
+        server
+            .respond_status("JVGL0123456789012345", 503)
+            .await;
+        server
+            .respond("772124253626", fixtures::FEDEX_UNDELIVERED)
+            .await;
+        let urls = [
+            "https://jouw.postnl.nl/track-and-trace/3SABCD1234567",
+            "https://www.dpdgroup.com/nl/mydpd/my-parcels/incoming?parcelNumber=05112087822887",
+            "https://parcel.trunkrs.nl/419108119/1234AB",
+            "https://my.dhlecommerce.nl/home/tracktrace/JVGL0123456789012345",
+            "https://www.fedex.com/fedextrack/?trknbr=772124253626",
+        ]
+        .into_iter()
+        .map(|url| AnnotatedUrl::new(url.into(), None))
+        .collect();
+
+        let ctx = server.context();
+        let jobs = track_urls(urls, MemoryCache::default(), &ctx).await?;
+        let statuses: Vec<_> = jobs
+            .iter()
+            .map(|job| {
+                job.result
+                    .as_ref()
+                    .map(|p| (p.channel.as_str(), p.status.clone()))
+                    .map_err(|e| e.to_string())
+            })
+            .collect();
+        assert_eq!(statuses[0], Ok(("PostNL", PackageStatus::InTransit)));
+        assert_eq!(statuses[1], Ok(("DPD", PackageStatus::Delivered)));
+        assert_eq!(statuses[2], Ok(("Trunkrs", PackageStatus::Delivered)));
+        assert!(
+            statuses[3]
+                .as_ref()
+                .unwrap_err()
+                .contains("HTTP 503")
+        );
+        assert_eq!(statuses[4], Ok(("FedEx", PackageStatus::InTransit)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_track_urls_with_streams_jobs_as_they_finish() -> Result<()> {
+        let server = FakeCarrierServer::start().await;
+        server
+            .respond_with(
+                "3SABCD1234567",
+                ResponseTemplate::new(200)
+                    .set_body_raw(
+                        fixtures::POSTNL_UNDELIVERED
+                            .as_bytes()
+                            .to_vec(),
+                        "application/json",
+                    )
+                    .set_delay(Duration::from_millis(300)),
+            )
+            .await;
+        server
+            .respond("05112087822887", fixtures::DPD_DELIVERED)
+            .await;
+        server
+            .respond("419108119", fixtures::TRUNKRS_DELIVERED)
+            .await;
+        let urls = [
+            "https://jouw.postnl.nl/track-and-trace/3SABCD1234567",
+            "https://www.dpdgroup.com/nl/mydpd/my-parcels/incoming?parcelNumber=05112087822887",
+            "https://parcel.trunkrs.nl/419108119/1234AB",
+        ]
+        .into_iter()
+        .map(|url| AnnotatedUrl::new(url.into(), None))
+        .collect();
+
+        let mut ctx = server.context();
+        ctx.filters.carrier = Some("n".into()); // PostNL and Trunkrs
+        let mut finished = vec![];
+        track_urls_with(urls, MemoryCache::default(), &ctx, |i, job| {
+            let channel = job.result.map(|p| p.channel);
+            finished.push((i, channel.unwrap_or_default()));
+        })
+        .await?;
+        // The slow PostNL response comes last, and DPD is filtered out
+        assert_eq!(
+            finished,
+            vec![(2, "Trunkrs".to_owned()), (0, "PostNL".to_owned())]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+// Some carriers render their tracking page client-side with JS, so the HTML
+// we get from a plain GET request doesn't contain the tracking data. Trackers
+// for these carriers can call `fetch_rendered` in their `get_raw` to load the
+// page in a headless Chrome/Chromium instead. This is behind the
+// `headless-browser` feature, because it's a heavy dependency that most users
+// don't need.
+
+use crate::Result;
+use crate::tracker::{RawResponse, TrackerContext};
+
+/// Load the page in a headless browser, wait for it to finish loading, and
+/// return the rendered HTML. Uses the browser at `ctx.browser_path` if it is
+/// set; otherwise tries to find Chrome/Chromium on the system.
+#[cfg(feature = "headless-browser")]
+pub async fn fetch_rendered(
+    url: &str,
+    ctx: &TrackerContext<'_>,
+) -> Result<RawResponse> {
+    use chromiumoxide::browser::{Browser, BrowserConfig};
+    use futures::StreamExt;
+
+    let err = |e: chromiumoxide::error::CdpError| format!("Browser error: {e}");
+    let mut config = BrowserConfig::builder();
+    if let Some(path) = ctx.browser_path {
+        config = config.chrome_executable(path);
+    }
+    let config = config
+        .build()
+        .map_err(|e| format!("Couldn't configure browser: {e}"))?;
+    log::debug!("Launching headless browser for {url}");
+    let (mut browser, mut handler) = Browser::launch(config)
+        .await
+        .map_err(err)?;
+    // The handler drives the connection to the browser; it has to be polled
+    // for anything to happen.
+    let handle = tokio::spawn(async move {
+        while let Some(event) = handler.next().await {
+            if event.is_err() {
+                break;
+            }
+        }
+    });
+
+    let result = async {
+        let page = browser.new_page(url).await?;
+        page.wait_for_navigation().await?;
+        page.content().await
+    }
+    .await;
+    if let Err(e) = browser.close().await {
+        log::warn!("Couldn't close browser: {e}");
+    }
+    handle.abort();
+
+    let body = result.map_err(err)?;
+    Ok(RawResponse {
+        body,
+        content_type: Some("text/html".into()),
+        status: 200,
+    })
+}
+
+#[cfg(not(feature = "headless-browser"))]
+pub async fn fetch_rendered(
+    url: &str,
+    _: &TrackerContext<'_>,
+) -> Result<RawResponse> {
+    Err(format!(
+        "{url} can only be tracked with a headless browser. Reinstall \
+         packtrack with `--features headless-browser` to enable this."
+    )
+    .into())
+}
+
+#[cfg(all(test, not(feature = "headless-browser")))]
+mod tests {
+    use super::*;
+    use crate::tracker::Credentials;
+
+    #[tokio::test]
+    async fn test_fetch_rendered_disabled() {
+        let ctx = TrackerContext {
+            recipient_postcode: None,
+            language:           "en",
+            browser_path:       None,
+            api_origin:         None,
+            credentials:        &Credentials::default(),
+        };
+        let err = fetch_rendered("https://example.com", &ctx)
+            .await
+            .err()
+            .unwrap();
+        assert!(
+            err.to_string()
+                .contains("--features headless-browser")
+        );
+    }
+}
@@ -0,0 +1,118 @@
+// Some carrier APIs need an API key or OAuth client credentials. Trackers get
+// these from `TrackerContext.credentials`, which looks in the settings file
+// first, and then in the OS keyring (with the `keyring` feature) so that
+// secrets don't have to be stored in plain text.
+
+use crate::Result;
+use std::collections::BTreeMap;
+
+/// Credentials per carrier, e.g. `{"ups": {"client_id": "...", ...}}`
+pub type CredentialMap = BTreeMap<String, BTreeMap<String, String>>;
+
+/// Name under which packtrack's secrets are stored in the OS keyring
+#[cfg(feature = "keyring")]
+const KEYRING_SERVICE: &str = "packtrack";
+
+/// Looks up carrier credentials by carrier and key, e.g. ("ups", "client_id")
+#[derive(Default, Clone)]
+pub struct Credentials {
+    values: CredentialMap,
+}
+
+impl Credentials {
+    pub fn new(values: CredentialMap) -> Self {
+        Self { values }
+    }
+
+    /// Get a credential from the settings, or else from the OS keyring
+    pub fn get(&self, carrier: &str, key: &str) -> Option<String> {
+        self.values
+            .get(carrier)
+            .and_then(|values| values.get(key))
+            .cloned()
+            .or_else(|| keyring_get(carrier, key))
+    }
+
+    /// Like `get`, but with an error explaining how to configure the missing
+    /// credential.
+    pub fn require(&self, carrier: &str, key: &str) -> Result<String> {
+        self.get(carrier, key).ok_or(
+            format!(
+                "No {key} configured for {carrier}. Set it with `packtrack \
+                 config set credentials.{carrier}.{key} <value>`, or store it \
+                 in the OS keyring with `packtrack config set-secret \
+                 {carrier} {key}`."
+            )
+            .into(),
+        )
+    }
+}
+
+#[cfg(feature = "keyring")]
+fn keyring_get(carrier: &str, key: &str) -> Option<String> {
+    let entry =
+        keyring::Entry::new(KEYRING_SERVICE, &format!("{carrier}.{key}"))
+            .inspect_err(|e| log::warn!("Couldn't access the OS keyring: {e}"))
+            .ok()?;
+    match entry.get_password() {
+        Ok(secret) => Some(secret),
+        Err(keyring::Error::NoEntry) => None,
+        Err(e) => {
+            log::warn!(
+                "Couldn't read {carrier}.{key} from the OS keyring: {e}"
+            );
+            None
+        }
+    }
+}
+
+#[cfg(not(feature = "keyring"))]
+fn keyring_get(_carrier: &str, _key: &str) -> Option<String> {
+    None
+}
+
+/// Save a credential in the OS keyring, where `Credentials::get` will find it
+#[cfg(feature = "keyring")]
+pub fn store_in_keyring(carrier: &str, key: &str, secret: &str) -> Result<()> {
+    keyring::Entry::new(KEYRING_SERVICE, &format!("{carrier}.{key}"))
+        .and_then(|entry| entry.set_password(secret))
+        .map_err(|e| format!("Couldn't write to the OS keyring: {e}").into())
+}
+
+#[cfg(not(feature = "keyring"))]
+pub fn store_in_keyring(carrier: &str, key: &str, _secret: &str) -> Result<()> {
+    Err(format!(
+        "Storing credentials in the OS keyring needs the `keyring` feature. \
+         Reinstall packtrack with `--features keyring`, or use `packtrack \
+         config set credentials.{carrier}.{key} <value>` instead."
+    )
+    .into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_credentials_from_settings() {
+        let mut values = CredentialMap::new();
+        values
+            .entry("ups".into())
+            .or_default()
+            .insert("client_id".into(), "abc".into());
+        let credentials = Credentials::new(values);
+        assert_eq!(
+            credentials
+                .get("ups", "client_id")
+                .as_deref(),
+            Some("abc")
+        );
+        let err = credentials
+            .require("fedex", "api_key")
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("No api_key configured for fedex"));
+        assert!(err.contains("credentials.fedex.api_key"));
+    }
+}
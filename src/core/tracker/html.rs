@@ -0,0 +1,176 @@
+use crate::Result;
+use scraper::{ElementRef, Html, Selector};
+
+/// Helper for trackers that have to scrape the carrier's HTML tracking page,
+/// because there is no JSON API.
+pub struct HtmlPage {
+    document: Html,
+}
+
+impl HtmlPage {
+    pub fn parse(text: &str) -> Self {
+        Self {
+            document: Html::parse_document(text),
+        }
+    }
+
+    /// The text of the first element matching the CSS selector, with the
+    /// whitespace collapsed.
+    pub fn text(&self, selector: &str) -> Result<Option<String>> {
+        Ok(self.texts(selector)?.into_iter().next())
+    }
+
+    /// The text of every element matching the CSS selector, with the
+    /// whitespace collapsed. Elements without any text are skipped.
+    pub fn texts(&self, selector: &str) -> Result<Vec<String>> {
+        let selector = parse_selector(selector)?;
+        Ok(self
+            .document
+            .select(&selector)
+            .map(element_text)
+            .filter(|text| !text.is_empty())
+            .collect())
+    }
+
+    /// The cell texts of every table row matching the CSS selector, e.g.
+    /// `"table.history tr"`. Rows without any `td` cells (e.g. header rows)
+    /// are skipped, but empty cells are kept so the columns line up.
+    pub fn rows(&self, selector: &str) -> Result<Vec<Vec<String>>> {
+        let selector = parse_selector(selector)?;
+        let cell = parse_selector("td")?;
+        Ok(self
+            .document
+            .select(&selector)
+            .map(|row| {
+                row.select(&cell)
+                    .map(element_text)
+                    .collect::<Vec<_>>()
+            })
+            .filter(|cells| !cells.is_empty())
+            .collect())
+    }
+
+    /// For every element matching the CSS selector, the text of the first
+    /// descendant matching each of the `fields` selectors (if any). An empty
+    /// field selector means the element itself.
+    pub fn records(
+        &self,
+        selector: &str,
+        fields: &[&str],
+    ) -> Result<Vec<Vec<Option<String>>>> {
+        let selector = parse_selector(selector)?;
+        let fields = fields
+            .iter()
+            .map(|field| match field.is_empty() {
+                true => Ok(None),
+                false => parse_selector(field).map(Some),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(self
+            .document
+            .select(&selector)
+            .map(|element| {
+                fields
+                    .iter()
+                    .map(|field| match field {
+                        Some(field) => element
+                            .select(field)
+                            .next()
+                            .map(element_text),
+                        None => Some(element_text(element)),
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+
+    /// Like `text`, but it's an error if there is no match
+    pub fn require_text(&self, selector: &str) -> Result<String> {
+        self.text(selector)?
+            .ok_or(format!("Couldn't find {selector:?} in the page").into())
+    }
+}
+
+/// All the text in the element, with runs of whitespace collapsed to a single
+/// space (HTML source is usually indented, which we don't want to display).
+fn element_text(element: ElementRef) -> String {
+    element
+        .text()
+        .flat_map(str::split_whitespace)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn parse_selector(selector: &str) -> Result<Selector> {
+    Selector::parse(selector)
+        .map_err(|err| format!("Invalid selector {selector:?}: {err}").into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_page() -> Result<()> {
+        let page = HtmlPage::parse(
+            r#"
+            <div class="status">
+                Out for
+                <b>delivery</b>
+            </div>
+            <ul><li>one</li><li></li><li>two</li></ul>
+            "#,
+        );
+        assert_eq!(page.text(".status")?.unwrap(), "Out for delivery");
+        assert_eq!(page.texts("li")?, vec!["one", "two"]);
+        assert_eq!(page.text(".missing")?, None);
+        assert_eq!(
+            page.require_text(".missing")
+                .err()
+                .unwrap(),
+            "Couldn't find \".missing\" in the page".into()
+        );
+        assert!(page.text("<<<").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_rows() -> Result<()> {
+        let page = HtmlPage::parse(
+            r#"
+            <table class="history">
+                <tr><th>Date</th><th>Event</th></tr>
+                <tr><td>2026/10/10</td><td>Posted</td></tr>
+                <tr><td>2026/10/11</td><td></td></tr>
+            </table>
+            "#,
+        );
+        assert_eq!(
+            page.rows("table.history tr")?,
+            vec![vec!["2026/10/10", "Posted"], vec!["2026/10/11", ""]]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_records() -> Result<()> {
+        let page = HtmlPage::parse(
+            r#"
+            <div class="event"><span class="date">10-10</span> Posted</div>
+            <div class="event"><b>Sorted</b></div>
+            "#,
+        );
+        assert_eq!(
+            page.records(".event", &[".date", ""])?,
+            vec![
+                vec![Some("10-10".into()), Some("10-10 Posted".into())],
+                vec![None, Some("Sorted".into())],
+            ]
+        );
+        assert!(
+            page.records(".event", &["<<<"])
+                .is_err()
+        );
+        Ok(())
+    }
+}
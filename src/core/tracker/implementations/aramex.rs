@@ -0,0 +1,273 @@
+// Consumer URLs look like:
+// https://www.aramex.com/ae/en/track/results?ShipmentNumber=44012345678
+
+// API url (the Shipping Services API, which needs an Aramex account):
+// POST https://ws.aramex.net/ShippingAPI.V2/Tracking/Service_1_0.svc/json/TrackShipments
+// Dates are in the WCF JSON format, e.g. "/Date(1791958500000+0400)/": the
+// milliseconds since the epoch, followed by the local UTC offset.
+
+use crate::Result;
+use crate::tracker::{
+    Event, Package, PackageStatus, RawResponse, Tracker, TrackerContext,
+};
+use crate::utils::UtcTime;
+use async_trait::async_trait;
+use chrono::DateTime;
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::json;
+
+const API_URL: &str = "https://ws.aramex.net/ShippingAPI.V2/Tracking/Service_1_0.svc/json/TrackShipments";
+
+pub struct AramexTracker;
+
+#[async_trait]
+impl Tracker for AramexTracker {
+    fn name(&self) -> &str {
+        "aramex"
+    }
+    fn can_handle(&self, url: &str) -> bool {
+        url.contains("aramex.com")
+    }
+    // Aramex waybill numbers are plain 11 digit numbers, which we can't tell
+    // apart from GLS ones, so there are no `barcode_patterns`
+    fn barcode_url(&self, barcode: &str) -> Option<String> {
+        Some(format!(
+            "https://www.aramex.com/ae/en/track/results?ShipmentNumber={barcode}"
+        ))
+    }
+    async fn get_raw(
+        &self,
+        url: &str,
+        ctx: &TrackerContext,
+    ) -> Result<RawResponse> {
+        let barcode = get_barcode(url)?;
+        let credential = |key| ctx.credentials.require("aramex", key);
+        let payload = json!({
+            "ClientInfo": {
+                "UserName": credential("username")?,
+                "Password": credential("password")?,
+                "AccountNumber": credential("account_number")?,
+                "AccountPin": credential("account_pin")?,
+                "AccountEntity": credential("account_entity")?,
+                "AccountCountryCode": credential("account_country_code")?,
+                "Version": "v1.0",
+            },
+            "Shipments": [barcode],
+            "GetLastTrackingUpdateOnly": false,
+        });
+        let response = reqwest::Client::new()
+            .post(ctx.api_url(API_URL))
+            .json(&payload)
+            .send()
+            .await?;
+        RawResponse::from_response(response).await
+    }
+    fn parse(&self, text: String) -> Result<Package> {
+        let response: TrackResponse = serde_json::from_str(&text)
+            .map_err(|err| format!("Error parsing Aramex response: {err}"))?;
+        if response.has_errors {
+            let messages = response
+                .notifications
+                .iter()
+                .map(|n| n.message.as_str())
+                .collect::<Vec<_>>()
+                .join("; ");
+            return Err(format!("Aramex: {messages}").into());
+        }
+        let result = response
+            .tracking_results
+            .into_iter()
+            .next()
+            .ok_or("Aramex doesn't know this shipment")?;
+        result.to_package()
+    }
+}
+
+fn get_barcode(url: &str) -> Result<String> {
+    Regex::new(r"(?i)ShipmentNumber=([0-9]+)")?
+        .captures(url)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_owned())
+        .ok_or(format!("Couldn't get barcode from {url}").into())
+}
+
+/// Parse a WCF JSON date like "/Date(1791958500000+0400)/". The offset
+/// doesn't change the moment in time, so we can ignore it.
+fn parse_date(text: &str) -> Result<UtcTime> {
+    Regex::new(r"/Date\((-?[0-9]+)(?:[+-][0-9]{4})?\)/")?
+        .captures(text)
+        .and_then(|caps| caps.get(1))
+        .and_then(|m| m.as_str().parse().ok())
+        .and_then(DateTime::from_timestamp_millis)
+        .ok_or(format!("Invalid Aramex date {text}").into())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TrackResponse {
+    #[serde(default)]
+    has_errors:       bool,
+    #[serde(default)]
+    notifications:    Vec<Notification>,
+    #[serde(default)]
+    tracking_results: Vec<TrackingResult>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Notification {
+    message: String,
+}
+
+/// The updates for one waybill
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TrackingResult {
+    /// The waybill number
+    key:   String,
+    /// Newest first
+    value: Vec<Update>,
+}
+impl TrackingResult {
+    fn to_package(&self) -> Result<Package> {
+        let events = self
+            .value
+            .iter()
+            .map(|u| u.to_event())
+            .collect::<Result<Vec<_>>>()?;
+        let delivered = self
+            .value
+            .iter()
+            .find(|u| u.is_delivery())
+            .map(|u| parse_date(&u.update_date_time))
+            .transpose()?;
+        Ok(Package {
+            barcode: self.key.clone(),
+            channel: "Aramex".into(),
+            status: match delivered {
+                Some(_) => PackageStatus::Delivered,
+                None => PackageStatus::InTransit,
+            },
+            sender: None,
+            recipient: None,
+            eta: None,
+            eta_window: None,
+            delivered,
+            events,
+            pickup: None,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Update {
+    /// e.g. "SH003" (out for delivery) or "SH005" (delivered)
+    update_code:        String,
+    update_description: String,
+    update_date_time:   String,
+    #[serde(default)]
+    update_location:    String,
+    /// e.g. who signed for the delivery
+    #[serde(default)]
+    comments:           String,
+}
+impl Update {
+    fn is_delivery(&self) -> bool {
+        self.update_code == "SH005"
+    }
+    fn to_event(&self) -> Result<Event> {
+        let mut text = self.update_description.clone();
+        if !self.comments.is_empty() {
+            text = format!("{text}: {}", self.comments);
+        }
+        if !self.update_location.is_empty() {
+            text = format!("{text} ({})", self.update_location);
+        }
+        Ok(Event::new(parse_date(&self.update_date_time)?, text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks;
+    use crate::test_utils::utc;
+
+    #[test]
+    fn test_get_barcode() -> Result<()> {
+        assert_eq!(
+            get_barcode(
+                "https://www.aramex.com/ae/en/track/results?ShipmentNumber=44012345678"
+            )?,
+            "44012345678"
+        );
+        assert_eq!(
+            get_barcode(
+                "https://www.aramex.com/jo/en/track/results?mode=0&shipmentNumber=44012345678"
+            )?,
+            "44012345678"
+        );
+        assert!(get_barcode("https://www.aramex.com/").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_date() -> Result<()> {
+        assert_eq!(
+            parse_date("/Date(1791958500000+0400)/")?,
+            utc("2026-10-14T06:15:00Z")
+        );
+        assert_eq!(
+            parse_date("/Date(1791958500000)/")?,
+            utc("2026-10-14T06:15:00Z")
+        );
+        assert!(parse_date("2026-10-14").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_undelivered() -> Result<()> {
+        let mock = mocks::load_text("aramex_undelivered.json")?;
+        let package = AramexTracker.parse(mock)?;
+        assert_eq!(package.barcode, "44012345678");
+        assert_eq!(package.channel, "Aramex");
+        assert_eq!(package.status, PackageStatus::InTransit);
+        assert_eq!(package.delivered, None);
+        assert_eq!(package.events.len(), 4);
+        assert_eq!(package.events[0].timestamp, utc("2026-10-14T06:15:00Z"));
+        assert_eq!(
+            package.events[0].text,
+            "Out for Delivery (Dubai, United Arab Emirates)"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_delivered() -> Result<()> {
+        let mock = mocks::load_text("aramex_delivered.json")?;
+        let package = AramexTracker.parse(mock)?;
+        assert_eq!(package.status, PackageStatus::Delivered);
+        assert_eq!(package.delivered, Some(utc("2026-10-14T11:13:00Z")));
+        assert_eq!(
+            package.events[0].text,
+            "Delivered: Received by: AHMED (Dubai, United Arab Emirates)"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let text = r#"{"HasErrors": true, "Notifications": [{"Code": "ERR01",
+            "Message": "Invalid user name or password"}],
+            "TrackingResults": []}"#;
+        let err = AramexTracker
+            .parse(text.into())
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Invalid user name or password")
+        );
+    }
+}
@@ -0,0 +1,282 @@
+// Austrian Post (Österreichische Post). Consumer URLs look like:
+// https://www.post.at/sendungsverfolgung?snr=1012345678901234567890
+// https://www.post.at/s/sendungsdetails?snr=1012345678901234567890
+
+// The tracking page gets the "sendungsdetails" from a public GraphQL API:
+// POST https://api.post.at/sendungen/sv/graphqlPublic
+
+use crate::Result;
+use crate::tracker::{
+    Event, Package, PackageStatus, RawResponse, TimeWindow, Tracker,
+    TrackerContext,
+};
+use crate::utils::UtcTime;
+use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Europe::Vienna;
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::json;
+
+const API_URL: &str = "https://api.post.at/sendungen/sv/graphqlPublic";
+
+pub struct AustrianPostTracker;
+
+#[async_trait]
+impl Tracker for AustrianPostTracker {
+    fn name(&self) -> &str {
+        "austrianpost"
+    }
+    fn can_handle(&self, url: &str) -> bool {
+        url.contains("post.at/")
+    }
+    fn barcode_patterns(&self) -> &[&str] {
+        &["10[0-9]{20}", "[A-Z]{2}[0-9]{9}AT"]
+    }
+    fn barcode_url(&self, barcode: &str) -> Option<String> {
+        Some(format!(
+            "https://www.post.at/sendungsverfolgung?snr={barcode}"
+        ))
+    }
+    fn languages(&self) -> &[&str] {
+        &["de", "en"]
+    }
+    async fn get_raw(
+        &self,
+        url: &str,
+        ctx: &TrackerContext,
+    ) -> Result<RawResponse> {
+        let barcode = get_barcode(url)?;
+        // The events have German and English texts; only ask for the one we
+        // want, so that `parse` doesn't need to know the language
+        let text_field = match self.choose_language(&[ctx.language]) {
+            Some("en") => "text: textEn",
+            _ => "text",
+        };
+        let query = format!(
+            "query {{ einzelsendung(sendungsnummer: \"{barcode}\") {{ \
+             sendungsnummer status \
+             estimatedDelivery {{ startDate endDate startTime endTime }} \
+             sendungsEvents {{ timestamp status {text_field} \
+             eventpostalcode eventcountry }} }} }}"
+        );
+        let response = reqwest::Client::new()
+            .post(ctx.api_url(API_URL))
+            .json(&json!({ "query": query }))
+            .send()
+            .await?;
+        RawResponse::from_response(response).await
+    }
+    fn parse(&self, text: String) -> Result<Package> {
+        let response: GraphqlResponse =
+            serde_json::from_str(&text).map_err(|err| {
+                format!("Error parsing Austrian Post response: {err}")
+            })?;
+        let sendung = response
+            .data
+            .and_then(|data| data.einzelsendung)
+            .ok_or("Austrian Post doesn't know this shipment")?;
+        sendung.to_package()
+    }
+}
+
+fn get_barcode(url: &str) -> Result<String> {
+    Regex::new(r"snr=([0-9A-Za-z]+)")?
+        .captures(url)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_uppercase())
+        .ok_or(format!("Couldn't get barcode from {url}").into())
+}
+
+#[derive(Deserialize)]
+struct GraphqlResponse {
+    data: Option<GraphqlData>,
+}
+
+#[derive(Deserialize)]
+struct GraphqlData {
+    einzelsendung: Option<Sendung>,
+}
+
+/// A shipment
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Sendung {
+    sendungsnummer:     String,
+    estimated_delivery: Option<EstimatedDelivery>,
+    /// Newest first
+    #[serde(default)]
+    sendungs_events:    Vec<SendungsEvent>,
+}
+impl Sendung {
+    fn to_package(&self) -> Result<Package> {
+        let events = self
+            .sendungs_events
+            .iter()
+            .map(|e| e.to_event())
+            .collect();
+        let delivered = self
+            .sendungs_events
+            .iter()
+            .find(|e| e.is_delivery())
+            .map(|e| e.timestamp.with_timezone(&Utc));
+        let eta_window = match delivered {
+            Some(_) => None,
+            None => self
+                .estimated_delivery
+                .as_ref()
+                .map(|eta| eta.to_time_window())
+                .transpose()?,
+        };
+        Ok(Package {
+            barcode: self.sendungsnummer.clone(),
+            channel: "Austrian Post".into(),
+            status: match delivered {
+                Some(_) => PackageStatus::Delivered,
+                None => PackageStatus::InTransit,
+            },
+            sender: None,
+            recipient: None,
+            eta: eta_window.as_ref().map(|w| w.start),
+            eta_window,
+            delivered,
+            events,
+            pickup: None,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EstimatedDelivery {
+    start_date: NaiveDate,
+    end_date:   Option<NaiveDate>,
+    /// e.g. "10:00", if the delivery window is known
+    start_time: Option<String>,
+    end_time:   Option<String>,
+}
+impl EstimatedDelivery {
+    /// Without times, the window is the whole day(s)
+    fn to_time_window(&self) -> Result<TimeWindow> {
+        let time = |text: &Option<String>, default: NaiveTime| {
+            text.as_deref()
+                .map(|text| NaiveTime::parse_from_str(text, "%H:%M"))
+                .transpose()
+                .map(|time| time.unwrap_or(default))
+        };
+        let end_of_day = NaiveTime::from_hms_opt(23, 59, 59).unwrap();
+        Ok(TimeWindow {
+            start: local_time(
+                self.start_date,
+                time(&self.start_time, NaiveTime::MIN)?,
+            )?,
+            end:   local_time(
+                self.end_date.unwrap_or(self.start_date),
+                time(&self.end_time, end_of_day)?,
+            )?,
+        })
+    }
+}
+
+fn local_time(date: NaiveDate, time: NaiveTime) -> Result<UtcTime> {
+    Vienna
+        .from_local_datetime(&date.and_time(time))
+        .earliest()
+        .map(|t| t.to_utc())
+        .ok_or(format!("Invalid local time {date} {time}").into())
+}
+
+#[derive(Deserialize)]
+struct SendungsEvent {
+    timestamp:       DateTime<FixedOffset>,
+    /// e.g. "IV" (out for delivery) or "ZU" (delivered)
+    status:          String,
+    text:            String,
+    eventpostalcode: Option<String>,
+    eventcountry:    Option<String>,
+}
+impl SendungsEvent {
+    fn is_delivery(&self) -> bool {
+        self.status == "ZU"
+    }
+    fn to_event(&self) -> Event {
+        let location = [&self.eventpostalcode, &self.eventcountry]
+            .into_iter()
+            .flatten()
+            .filter(|s| !s.is_empty())
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let text = match location.is_empty() {
+            true => self.text.clone(),
+            false => format!("{} ({location})", self.text),
+        };
+        Event::new(self.timestamp.with_timezone(&Utc), text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks;
+    use crate::test_utils::utc;
+
+    #[test]
+    fn test_get_barcode() -> Result<()> {
+        assert_eq!(
+            get_barcode(
+                "https://www.post.at/sendungsverfolgung?snr=1012345678901234567890"
+            )?,
+            "1012345678901234567890"
+        );
+        assert!(get_barcode("https://www.post.at/").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_undelivered() -> Result<()> {
+        let mock = mocks::load_text("austrianpost_undelivered.json")?;
+        let package = AustrianPostTracker.parse(mock)?;
+        assert_eq!(package.barcode, "1012345678901234567890");
+        assert_eq!(package.channel, "Austrian Post");
+        assert_eq!(package.status, PackageStatus::InTransit);
+        assert_eq!(
+            package.eta_window,
+            Some(TimeWindow {
+                start: utc("2026-10-15T08:00:00Z"),
+                end:   utc("2026-10-15T11:00:00Z"),
+            })
+        );
+        assert_eq!(package.events.len(), 3);
+        assert_eq!(package.events[0].text, "Sendung in Zustellung (1100 AT)");
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_delivered() -> Result<()> {
+        let mock = mocks::load_text("austrianpost_delivered.json")?;
+        let package = AustrianPostTracker.parse(mock)?;
+        assert_eq!(package.status, PackageStatus::Delivered);
+        assert_eq!(package.delivered, Some(utc("2026-10-15T09:47:00Z")));
+        assert_eq!(package.eta_window, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_whole_day_eta() -> Result<()> {
+        let eta = EstimatedDelivery {
+            start_date: "2026-10-15".parse().unwrap(),
+            end_date:   None,
+            start_time: None,
+            end_time:   None,
+        };
+        assert_eq!(
+            eta.to_time_window()?,
+            TimeWindow {
+                start: utc("2026-10-14T22:00:00Z"),
+                end:   utc("2026-10-15T21:59:59Z"),
+            }
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+// BRT (Bartolini, Italy). Consumer URLs look like:
+// https://vas.brt.it/vas/sped_det_show.hsm?referer=sped_numspe_par.htm&Nspediz=084123456789012
+
+// There is no public API, so we scrape the tracking page. Events are listed
+// newest first, with the date ("14.10.2026") and time ("08.35") in separate
+// columns, in Italian time.
+
+use crate::Result;
+use crate::tracker::html::HtmlPage;
+use crate::tracker::{
+    Event, Package, PackageStatus, RawResponse, Tracker, TrackerContext,
+};
+use crate::utils::UtcTime;
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, TimeZone};
+use chrono_tz::Europe::Rome;
+use regex::Regex;
+
+pub struct BrtTracker;
+
+#[async_trait]
+impl Tracker for BrtTracker {
+    fn name(&self) -> &str {
+        "brt"
+    }
+    fn can_handle(&self, url: &str) -> bool {
+        url.contains("brt.it")
+    }
+    // BRT shipment numbers are plain 12-15 digit numbers, which we can't tell
+    // apart from GLS and FedEx ones, so there are no `barcode_patterns`
+    fn barcode_url(&self, barcode: &str) -> Option<String> {
+        Some(format!(
+            "https://vas.brt.it/vas/sped_det_show.hsm?referer=sped_numspe_par.htm&Nspediz={barcode}"
+        ))
+    }
+    fn languages(&self) -> &[&str] {
+        // `parse` relies on the Italian event names
+        &["it"]
+    }
+    fn probe_url(&self) -> Option<String> {
+        self.barcode_url("000000000000000")
+    }
+    fn check_probe(&self, raw: &RawResponse) -> Result<()> {
+        raw.expect_html()
+    }
+    async fn get_raw(
+        &self,
+        url: &str,
+        ctx: &TrackerContext,
+    ) -> Result<RawResponse> {
+        let barcode = get_barcode(url)?;
+        let api_url = self
+            .barcode_url(&barcode)
+            .unwrap_or_default();
+        let response = reqwest::get(ctx.api_url(&api_url)).await?;
+        RawResponse::from_response(response).await
+    }
+    fn parse(&self, text: String) -> Result<Package> {
+        let page = HtmlPage::parse(&text);
+        let barcode = page
+            .text("#numeroSpedizione")?
+            .ok_or("BRT doesn't know this shipment")?;
+        let mut events = vec![];
+        let mut delivered = None;
+        for cells in page.rows("table.table_eventi tr")? {
+            let [date, time, branch, description] = &cells[..] else {
+                continue;
+            };
+            let timestamp = local_time(date, time)?;
+            if description == "CONSEGNATA" && delivered.is_none() {
+                delivered = Some(timestamp);
+            }
+            let text = match branch.is_empty() {
+                true => description.clone(),
+                false => format!("{description} ({branch})"),
+            };
+            events.push(Event::new(timestamp, text));
+        }
+        Ok(Package {
+            barcode,
+            channel: "BRT".into(),
+            status: match delivered {
+                Some(_) => PackageStatus::Delivered,
+                None => PackageStatus::InTransit,
+            },
+            sender: page.text("#mittente")?,
+            recipient: page.text("#destinatario")?,
+            eta: None,
+            eta_window: None,
+            delivered,
+            events,
+            pickup: None,
+        })
+    }
+}
+
+fn get_barcode(url: &str) -> Result<String> {
+    Regex::new(r"(?i)(?:nspediz|brtcode)=([0-9A-Za-z]+)")?
+        .captures(url)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_uppercase())
+        .ok_or(format!("Couldn't get barcode from {url}").into())
+}
+
+/// Parse "14.10.2026" and "08.35" in Italian time
+fn local_time(date: &str, time: &str) -> Result<UtcTime> {
+    let time = NaiveDateTime::parse_from_str(
+        &format!("{date} {time}"),
+        "%d.%m.%Y %H.%M",
+    )?;
+    Rome.from_local_datetime(&time)
+        .earliest()
+        .map(|t| t.to_utc())
+        .ok_or(format!("Invalid local time {time}").into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks;
+    use crate::test_utils::utc;
+
+    #[test]
+    fn test_get_barcode() -> Result<()> {
+        assert_eq!(
+            get_barcode(
+                "https://vas.brt.it/vas/sped_det_show.hsm?referer=sped_numspe_par.htm&Nspediz=084123456789012"
+            )?,
+            "084123456789012"
+        );
+        assert_eq!(
+            get_barcode(
+                "https://vas.brt.it/vas/sped_det_show.htm?brtCode=084123456789012"
+            )?,
+            "084123456789012"
+        );
+        assert!(get_barcode("https://vas.brt.it/").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_undelivered() -> Result<()> {
+        let mock = mocks::load_text("brt_undelivered.html")?;
+        let package = BrtTracker.parse(mock)?;
+        assert_eq!(package.barcode, "084123456789012");
+        assert_eq!(package.channel, "BRT");
+        assert_eq!(package.status, PackageStatus::InTransit);
+        assert_eq!(package.sender, Some("NEGOZIO ONLINE SRL".into()));
+        assert_eq!(package.recipient, Some("MARIO ROSSI".into()));
+        assert_eq!(package.delivered, None);
+        // Without the header row
+        assert_eq!(package.events.len(), 4);
+        assert_eq!(package.events[0].text, "IN CONSEGNA (VERONA)");
+        assert_eq!(package.events[0].timestamp, utc("2026-10-14T06:35:00Z"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_delivered() -> Result<()> {
+        let mock = mocks::load_text("brt_delivered.html")?;
+        let package = BrtTracker.parse(mock)?;
+        assert_eq!(package.status, PackageStatus::Delivered);
+        assert_eq!(package.delivered, Some(utc("2026-10-14T10:27:00Z")));
+        assert_eq!(package.events.len(), 5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_not_found() {
+        let err = BrtTracker
+            .parse("<html><body>Spedizione non trovata</body></html>".into())
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("BRT doesn't know this shipment")
+        );
+    }
+}
@@ -0,0 +1,268 @@
+// Cainiao (AliExpress). Consumer URLs look like:
+// https://global.cainiao.com/detail.htm?mailNoList=LP00123456789012
+
+// API url:
+// https://global.cainiao.com/global/detail.json?mailNos=LP00123456789012&lang=en-US
+//
+// Cainiao carries the parcel to the destination country ("line-haul"), and
+// then hands it over to a local carrier for the last mile. The response
+// includes the local carrier, and often its own tracking number.
+
+use crate::Result;
+use crate::tracker::{
+    Event, Package, PackageStatus, RawResponse, TimeWindow, Tracker,
+    TrackerContext,
+};
+use crate::utils::UtcTime;
+use async_trait::async_trait;
+use chrono::DateTime;
+use regex::Regex;
+use serde::Deserialize;
+
+pub struct CainiaoTracker;
+
+#[async_trait]
+impl Tracker for CainiaoTracker {
+    fn name(&self) -> &str {
+        "cainiao"
+    }
+    fn can_handle(&self, url: &str) -> bool {
+        url.contains("cainiao.com")
+    }
+    fn barcode_patterns(&self) -> &[&str] {
+        &["LP[0-9]{14}", "CN[0-9]{14}"]
+    }
+    fn barcode_url(&self, barcode: &str) -> Option<String> {
+        Some(format!(
+            "https://global.cainiao.com/detail.htm?mailNoList={barcode}"
+        ))
+    }
+    async fn get_raw(
+        &self,
+        url: &str,
+        ctx: &TrackerContext,
+    ) -> Result<RawResponse> {
+        let barcode = get_barcode(url)?;
+        let api_url = format!(
+            "https://global.cainiao.com/global/detail.json?mailNos={barcode}&lang=en-US"
+        );
+        let response = reqwest::get(ctx.api_url(&api_url)).await?;
+        RawResponse::from_response(response).await
+    }
+    fn parse(&self, text: String) -> Result<Package> {
+        let response: DetailResponse = serde_json::from_str(&text)
+            .map_err(|err| format!("Error parsing Cainiao response: {err}"))?;
+        let module = response
+            .module
+            .into_iter()
+            .next()
+            .ok_or("No parcels in Cainiao response")?;
+        module.to_package()
+    }
+}
+
+fn get_barcode(url: &str) -> Result<String> {
+    Regex::new(r"mailNoList=([0-9A-Za-z]+)")?
+        .captures(url)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_uppercase())
+        .ok_or(format!("Couldn't get barcode from {url}").into())
+}
+
+fn from_millis(millis: i64) -> Result<UtcTime> {
+    DateTime::from_timestamp_millis(millis)
+        .ok_or(format!("Invalid Cainiao timestamp {millis}").into())
+}
+
+#[derive(Deserialize)]
+struct DetailResponse {
+    #[serde(default)]
+    module: Vec<Module>,
+}
+
+/// A parcel
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Module {
+    mail_no:         String,
+    /// e.g. "DELIVERING" or "SIGN" (delivered)
+    status:          Option<String>,
+    /// Newest first
+    #[serde(default)]
+    detail_list:     Vec<Detail>,
+    global_eta_info: Option<EtaInfo>,
+    /// The last-mile carrier
+    dest_cp_info:    Option<CarrierInfo>,
+}
+impl Module {
+    fn to_package(&self) -> Result<Package> {
+        let mut events = vec![];
+        for detail in self.detail_list.iter().rev() {
+            // The first last-mile event is where the local carrier takes over
+            if detail.is_last_mile()
+                && !events
+                    .iter()
+                    .any(|e: &Event| e.text.starts_with(HANDOVER))
+                && let Some(carrier) = &self.dest_cp_info
+            {
+                events.push(Event::new(
+                    from_millis(detail.time)?,
+                    carrier.handover_text(),
+                ));
+            }
+            events.push(detail.to_event()?);
+        }
+        events.reverse();
+
+        let delivered = self
+            .detail_list
+            .iter()
+            .find(|d| d.is_delivery())
+            .map(|d| from_millis(d.time))
+            .transpose()?;
+        let is_delivered =
+            delivered.is_some() || self.status.as_deref() == Some("SIGN");
+        let eta_window = match is_delivered {
+            true => None,
+            false => self
+                .global_eta_info
+                .as_ref()
+                .map(|eta| eta.to_time_window())
+                .transpose()?,
+        };
+        Ok(Package {
+            barcode: self.mail_no.clone(),
+            channel: "Cainiao".into(),
+            status: match is_delivered {
+                true => PackageStatus::Delivered,
+                false => PackageStatus::InTransit,
+            },
+            sender: None,
+            recipient: None,
+            eta: eta_window.as_ref().map(|w| w.start),
+            eta_window,
+            delivered,
+            events,
+            pickup: None,
+        })
+    }
+}
+
+const HANDOVER: &str = "Handed over to";
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CarrierInfo {
+    /// e.g. "PostNL"
+    cp_name:      String,
+    /// The local carrier's own tracking number
+    dest_mail_no: Option<String>,
+}
+impl CarrierInfo {
+    fn handover_text(&self) -> String {
+        match &self.dest_mail_no {
+            Some(number) if !number.is_empty() => format!(
+                "{HANDOVER} {} for delivery, with tracking number {number}",
+                self.cp_name
+            ),
+            _ => format!("{HANDOVER} {} for delivery", self.cp_name),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EtaInfo {
+    /// Milliseconds since the epoch
+    delivery_min_time: i64,
+    delivery_max_time: i64,
+}
+impl EtaInfo {
+    fn to_time_window(&self) -> Result<TimeWindow> {
+        Ok(TimeWindow {
+            start: from_millis(self.delivery_min_time)?,
+            end:   from_millis(self.delivery_max_time)?,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Detail {
+    /// Milliseconds since the epoch
+    time:        i64,
+    /// e.g. "CW_INBOUND" (sorting center), "LH_..." (line-haul), "CC_..."
+    /// (customs) or "GTMS_..." (last-mile)
+    action_code: String,
+    desc:        String,
+}
+impl Detail {
+    fn is_last_mile(&self) -> bool {
+        self.action_code.starts_with("GTMS_")
+    }
+    fn is_delivery(&self) -> bool {
+        self.action_code == "GTMS_SIGNED"
+    }
+    fn to_event(&self) -> Result<Event> {
+        Ok(Event::new(from_millis(self.time)?, self.desc.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks;
+    use crate::test_utils::utc;
+
+    #[test]
+    fn test_get_barcode() -> Result<()> {
+        assert_eq!(
+            get_barcode(
+                "https://global.cainiao.com/detail.htm?mailNoList=LP00123456789012"
+            )?,
+            "LP00123456789012"
+        );
+        assert!(get_barcode("https://global.cainiao.com/").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_undelivered() -> Result<()> {
+        let mock = mocks::load_text("cainiao_undelivered.json")?;
+        let package = CainiaoTracker.parse(mock)?;
+        assert_eq!(package.barcode, "LP00123456789012");
+        assert_eq!(package.channel, "Cainiao");
+        assert_eq!(package.status, PackageStatus::InTransit);
+        assert_eq!(
+            package.eta_window,
+            Some(TimeWindow {
+                start: utc("2026-10-13T07:00:00Z"),
+                end:   utc("2026-10-13T15:00:00Z"),
+            })
+        );
+        // 5 events from Cainiao, and the handover
+        assert_eq!(package.events.len(), 6);
+        assert_eq!(package.events[0].text, "Out for delivery");
+        assert_eq!(
+            package.events[1].text,
+            "Received by local delivery company"
+        );
+        assert_eq!(
+            package.events[2].text,
+            "Handed over to PostNL for delivery, with tracking number \
+             3SCNAB1234567"
+        );
+        assert_eq!(package.events[2].timestamp, utc("2026-10-12T08:31:00Z"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_delivered() -> Result<()> {
+        let mock = mocks::load_text("cainiao_delivered.json")?;
+        let package = CainiaoTracker.parse(mock)?;
+        assert_eq!(package.status, PackageStatus::Delivered);
+        assert_eq!(package.delivered, Some(utc("2026-10-13T10:41:00Z")));
+        assert_eq!(package.eta_window, None);
+        Ok(())
+    }
+}
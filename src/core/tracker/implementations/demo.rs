@@ -0,0 +1,211 @@
+// Synthetic packages for `--demo` mode, so that people can try packtrack (and
+// we can test display changes) without real tracking numbers or network
+// access. Demo URLs look like `demo:<carrier>`, optionally followed by e.g.
+// the tracking number (`demo:postnl/3SDEMO1234567`), and the packages' times
+// are relative to now so the output always looks current.
+
+use crate::Result;
+use crate::tracker::{
+    Event, Package, PackageStatus, PickupPoint, RawResponse, TimeWindow,
+    Tracker, TrackerContext,
+};
+use crate::url_store::AnnotatedUrl;
+use crate::utils::UtcTime;
+use async_trait::async_trait;
+use chrono::{Local, TimeDelta, Utc};
+
+const PREFIX: &str = "demo:";
+
+pub struct MockTracker;
+
+#[async_trait]
+impl Tracker for MockTracker {
+    fn name(&self) -> &str {
+        "demo"
+    }
+    fn can_handle(&self, url: &str) -> bool {
+        url.starts_with(PREFIX)
+    }
+    async fn get_raw(
+        &self,
+        url: &str,
+        _: &TrackerContext,
+    ) -> Result<RawResponse> {
+        // Nothing to fetch; the URL tells `parse` which package to generate.
+        Ok(RawResponse::ok(url))
+    }
+    fn parse(&self, text: String) -> Result<Package> {
+        let key = text
+            .strip_prefix(PREFIX)
+            .ok_or(format!("Not a demo URL: {text}"))?;
+        demo_package(key.split('/').next().unwrap_or(key))
+    }
+}
+
+/// The URLs to track in demo mode: one package in each interesting state, a
+/// carrier error, and a package without a tracking URL yet.
+pub fn demo_urls() -> Vec<AnnotatedUrl> {
+    let url = |key: &str, description: Option<&str>| {
+        AnnotatedUrl::new(
+            format!("{PREFIX}{key}"),
+            description.map(String::from),
+        )
+    };
+    let in_3_days = Local::now().date_naive() + TimeDelta::days(3);
+    vec![
+        url("postnl", Some("running shoes")),
+        url("dhl", None),
+        url("gls", Some("books")),
+        url("dpd", None),
+        url("trunkrs", None),
+        url("broken", Some("birthday present")),
+        AnnotatedUrl::placeholder(
+            "keyboard from Amazon".into(),
+            Some(in_3_days),
+        ),
+    ]
+}
+
+fn demo_package(key: &str) -> Result<Package> {
+    let now = Utc::now();
+    let hours = |h: i64| now + TimeDelta::hours(h);
+    let events = |texts: &[(i64, &str)]| {
+        texts
+            .iter()
+            .map(|(h, text)| Event::new(hours(*h), text.to_string()))
+            .collect()
+    };
+    let package = match key {
+        "postnl" => Package {
+            barcode:    "3SDEMO1234567".into(),
+            channel:    "PostNL".into(),
+            status:     PackageStatus::InTransit,
+            sender:     Some("Zalando".into()),
+            recipient:  Some("Packtrack User".into()),
+            eta:        Some(hours(3)),
+            eta_window: Some(window(hours(2), hours(4))),
+            delivered:  None,
+            events:     events(&[
+                (-40, "Package accepted"),
+                (-20, "Package sorted at depot"),
+                (-2, "Package out for delivery"),
+            ]),
+            pickup:     None,
+        },
+        "dhl" => Package {
+            barcode:    "JVGL0123456789012345".into(),
+            channel:    "DHL".into(),
+            status:     PackageStatus::ReadyForPickup {
+                location: "Primera Centrum, Hoofdstraat 12".into(),
+            },
+            sender:     Some("Bol.com".into()),
+            recipient:  Some("Packtrack User".into()),
+            eta:        None,
+            eta_window: None,
+            delivered:  None,
+            events:     events(&[
+                (-50, "The shipment has been registered"),
+                (-30, "The shipment has been sorted"),
+                (-6, "Ready for pickup at ServicePoint"),
+            ]),
+            pickup:     Some(PickupPoint {
+                location: "Primera Centrum, Hoofdstraat 12".into(),
+                deadline: Some(hours(30)),
+            }),
+        },
+        "gls" => Package {
+            barcode:    "123456789012".into(),
+            channel:    "GLS".into(),
+            status:     PackageStatus::Delivered,
+            sender:     Some("Coolblue".into()),
+            recipient:  Some("Packtrack User".into()),
+            eta:        Some(hours(-26)),
+            eta_window: None,
+            delivered:  Some(hours(-25)),
+            events:     events(&[
+                (-72, "Parcel data entered"),
+                (-25, "Delivered"),
+            ]),
+            pickup:     None,
+        },
+        "dpd" => Package {
+            barcode:    "05112087822887".into(),
+            channel:    "DPD".into(),
+            status:     PackageStatus::DeliveredToNeighbour {
+                address: "Streetname 422".into(),
+            },
+            sender:     Some("Thuisbezorgd".into()),
+            recipient:  Some("Packtrack User".into()),
+            eta:        None,
+            eta_window: None,
+            delivered:  Some(hours(-5)),
+            events:     events(&[
+                (-30, "Parcel registered"),
+                (-5, "Delivered"),
+            ]),
+            pickup:     None,
+        },
+        "trunkrs" => Package {
+            barcode:    "419108119".into(),
+            channel:    "Trunkrs".into(),
+            status:     PackageStatus::InTransit,
+            sender:     Some("Picnic".into()),
+            recipient:  Some("Packtrack User".into()),
+            eta:        None,
+            eta_window: Some(window(hours(26), hours(28))),
+            delivered:  None,
+            events:     events(&[(-3, "Shipment announced")]),
+            pickup:     None,
+        },
+        "broken" => {
+            return Err("HTTP 503 from demo carrier API (this is a demo \
+                        of an error)"
+                .into());
+        }
+        other => return Err(format!("Unknown demo package: {other}").into()),
+    };
+    Ok(package)
+}
+
+fn window(start: UtcTime, end: UtcTime) -> TimeWindow {
+    TimeWindow { start, end }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tracker::Credentials;
+
+    #[tokio::test]
+    async fn test_demo_urls() -> Result<()> {
+        let ctx = TrackerContext {
+            recipient_postcode: None,
+            language:           "en",
+            browser_path:       None,
+            api_origin:         None,
+            credentials:        &Credentials::default(),
+        };
+        let urls = demo_urls();
+        let mut packages = vec![];
+        for url in urls
+            .iter()
+            .filter(|u| !u.is_placeholder())
+        {
+            assert!(MockTracker.can_handle(&url.url));
+            let raw = MockTracker
+                .get_raw(&url.url, &ctx)
+                .await?;
+            packages.push(MockTracker.parse(raw.body));
+        }
+        assert_eq!(packages.len(), 6);
+        assert_eq!(
+            packages
+                .iter()
+                .filter(|p| p.is_err())
+                .count(),
+            1
+        );
+        assert!(urls.iter().any(|u| u.is_placeholder()));
+        Ok(())
+    }
+}
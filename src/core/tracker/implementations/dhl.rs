@@ -1,6 +1,11 @@
 use crate::Result;
-use crate::tracker::{Event, Package, PackageStatus, TimeWindow, Tracker};
-use crate::{tracker::TrackerContext, utils::UtcTime};
+use crate::tracker::{
+    Event, Package, PackageStatus, PickupPoint, TimeWindow, Tracker,
+};
+use crate::{
+    tracker::{RawResponse, TrackerContext},
+    utils::UtcTime,
+};
 use async_trait::async_trait;
 use regex::Regex;
 use serde::Deserialize;
@@ -9,17 +14,39 @@ pub struct DhlTracker;
 
 #[async_trait]
 impl Tracker for DhlTracker {
+    fn name(&self) -> &str {
+        "dhl"
+    }
     fn can_handle(&self, url: &str) -> bool {
-        url.contains("dhl")
+        // dhl.de is DHL Paket, see `DhlPaketTracker`
+        url.contains("dhl") && !url.contains("dhl.de")
+    }
+    fn barcode_patterns(&self) -> &[&str] {
+        &["JVGL[0-9]{12,20}", "JJD[0-9]{10,20}", "3S[A-Z0-9]{13}"]
+    }
+    fn barcode_url(&self, barcode: &str) -> Option<String> {
+        Some(format!(
+            "https://my.dhlecommerce.nl/home/tracktrace/{barcode}"
+        ))
+    }
+    fn probe_url(&self) -> Option<String> {
+        Some(
+            "https://my.dhlecommerce.nl/home/tracktrace/JVGL000000000000"
+                .into(),
+        )
     }
-    async fn get_raw(&self, url: &str, ctx: &TrackerContext) -> Result<String> {
+    fn check_probe(&self, raw: &RawResponse) -> Result<()> {
+        raw.expect_json()
+    }
+    async fn get_raw(
+        &self,
+        url: &str,
+        ctx: &TrackerContext,
+    ) -> Result<RawResponse> {
         let barcode = get_barcode(url, ctx.recipient_postcode)?;
         let url = get_url(barcode);
-        let response = reqwest::get(url)
-            .await?
-            .error_for_status()?;
-        let body = response.text().await?;
-        Ok(body)
+        let response = reqwest::get(ctx.api_url(&url)).await?;
+        RawResponse::from_response(response).await
     }
 
     fn parse(&self, text: String) -> Result<Package> {
@@ -36,6 +63,7 @@ impl Tracker for DhlTracker {
             eta_window: package.eta_window()?,
             delivered:  package.delivered_at,
             events:     package.events(),
+            pickup:     package.pickup(),
         })
     }
 }
@@ -117,6 +145,8 @@ struct DhlPackage {
     events:                     Vec<DhlEvent>,
     transit_time:               Option<TransitTime>,
     destination:                Option<Destination>,
+    /// Only present once the package has arrived at a ServicePoint
+    pickup_deadline:            Option<UtcTime>,
 }
 fn get_neighbour_address(package: &DhlPackage) -> Option<String> {
     let dest = package.clone().destination?;
@@ -129,16 +159,57 @@ fn get_neighbour_address(package: &DhlPackage) -> Option<String> {
     let number = address.house_number?;
     Some(format!("{street} {number}"))
 }
+/// Describe the ServicePoint as "Name, Street 12, City", skipping any missing
+/// parts.
+fn get_servicepoint_location(package: &DhlPackage) -> Option<String> {
+    let dest = package.destination.as_ref()?;
+    if dest.r#type.as_deref() != Some("PARCELSHOP") {
+        return None;
+    }
+    let mut parts: Vec<String> = Vec::new();
+    if let Some(name) = &dest.name {
+        parts.push(name.clone());
+    }
+    if let Some(address) = &dest.address {
+        let street = [&address.street, &address.house_number]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if !street.is_empty() {
+            parts.push(street);
+        }
+        if let Some(city) = &address.city {
+            parts.push(city.clone());
+        }
+    }
+    Some(parts.join(", "))
+}
 impl DhlPackage {
     fn status(&self) -> PackageStatus {
-        if let Some(_) = &self.delivered_at {
+        if self.delivered_at.is_some() {
             if let Some(address) = get_neighbour_address(self) {
                 return PackageStatus::DeliveredToNeighbour { address };
             }
             return PackageStatus::Delivered;
         }
+        if let Some(pickup) = self.pickup()
+            && pickup.deadline.is_some()
+        {
+            return PackageStatus::ReadyForPickup {
+                location: pickup.location,
+            };
+        }
         PackageStatus::InTransit
     }
+    fn pickup(&self) -> Option<PickupPoint> {
+        let location = get_servicepoint_location(self)?;
+        Some(PickupPoint {
+            location,
+            deadline: self.pickup_deadline,
+        })
+    }
     fn events(&self) -> Vec<Event> {
         self.events
             .iter()
@@ -200,15 +271,16 @@ struct DhlEvent {
 }
 impl DhlEvent {
     fn to_event(&self) -> Event {
-        Event {
-            timestamp: self.timestamp,
-            text:      format!("{}: {}", self.category, self.status),
-        }
+        Event::new(
+            self.timestamp,
+            format!("{}: {}", self.category, self.status),
+        )
     }
 }
 #[derive(Deserialize, Clone)]
 struct Destination {
     address: Option<Address>,
+    name:    Option<String>,
     r#type:  Option<String>,
 }
 #[allow(unused)]
@@ -303,4 +375,36 @@ mod tests {
         }
         Ok(())
     }
+
+    #[test]
+    fn test_servicepoint() -> Result<()> {
+        let mock = mocks::load_text("dhlecommerce_servicepoint.json")?;
+        let package = DhlTracker.parse(mock)?;
+        let location = "Primera Amsterdam, Shopstreet 12, Amsterdam";
+        assert_eq!(
+            package.status,
+            PackageStatus::ReadyForPickup {
+                location: location.into(),
+            }
+        );
+        assert_eq!(
+            package.pickup,
+            Some(PickupPoint {
+                location: location.into(),
+                deadline: Some(utc("2024-11-15T22:59:00Z")),
+            })
+        );
+        assert_eq!(package.delivered, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_servicepoint() -> Result<()> {
+        let mock =
+            mocks::load_text("dhlecommerce_undelivered_with_postcode.json")?;
+        let package = DhlTracker.parse(mock)?;
+        assert_eq!(package.status, PackageStatus::InTransit);
+        assert_eq!(package.pickup, None);
+        Ok(())
+    }
 }
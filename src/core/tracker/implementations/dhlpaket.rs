@@ -0,0 +1,247 @@
+// DHL Paket (Germany) isn't covered by the dhlparcel.nl / dhlecommerce.nl API
+// that `DhlTracker` uses. Consumer URLs look like:
+// https://www.dhl.de/de/privatkunden/pakete-empfangen/verfolgen.html?piececode=00340434161094042557
+// https://nolp.dhl.de/nextt-online-public/set_identcodes.do?idc=00340434161094042557
+
+// API url (what the tracking page itself uses):
+// https://www.dhl.de/int-verfolgen/data/search?piececode=00340434161094042557&language=de
+
+use crate::Result;
+use crate::tracker::{
+    Event, Package, PackageStatus, RawResponse, TimeWindow, Tracker,
+    TrackerContext,
+};
+use crate::utils::UtcTime;
+use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset, Utc};
+use regex::Regex;
+use serde::Deserialize;
+
+pub struct DhlPaketTracker;
+
+#[async_trait]
+impl Tracker for DhlPaketTracker {
+    fn name(&self) -> &str {
+        "dhlpaket"
+    }
+    fn can_handle(&self, url: &str) -> bool {
+        url.contains("dhl.de")
+    }
+    fn barcode_patterns(&self) -> &[&str] {
+        &["00340[0-9]{15}"]
+    }
+    fn barcode_url(&self, barcode: &str) -> Option<String> {
+        Some(format!(
+            "https://www.dhl.de/de/privatkunden/pakete-empfangen/verfolgen.html?piececode={barcode}"
+        ))
+    }
+    fn languages(&self) -> &[&str] {
+        &["de", "en"]
+    }
+    fn probe_url(&self) -> Option<String> {
+        self.barcode_url("00340000000000000000")
+    }
+    fn check_probe(&self, raw: &RawResponse) -> Result<()> {
+        raw.expect_json()
+    }
+    async fn get_raw(
+        &self,
+        url: &str,
+        ctx: &TrackerContext,
+    ) -> Result<RawResponse> {
+        let barcode = get_barcode(url)?;
+        let language = self
+            .choose_language(&[ctx.language])
+            .unwrap_or("de");
+        let api_url = format!(
+            "https://www.dhl.de/int-verfolgen/data/search?piececode={barcode}&language={language}"
+        );
+        let response = reqwest::get(ctx.api_url(&api_url)).await?;
+        RawResponse::from_response(response).await
+    }
+    fn parse(&self, text: String) -> Result<Package> {
+        let response: SearchResponse =
+            serde_json::from_str(&text).map_err(|err| {
+                format!("Error parsing DHL Paket response: {err}")
+            })?;
+        let sendung = response
+            .sendungen
+            .into_iter()
+            .next()
+            .ok_or("No shipments in DHL Paket response")?;
+        Ok(sendung.to_package())
+    }
+}
+
+fn get_barcode(url: &str) -> Result<String> {
+    Regex::new(r"(?:piececode|idc)=([0-9A-Za-z]+)")?
+        .captures(url)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_uppercase())
+        .ok_or(format!("Couldn't get barcode from {url}").into())
+}
+
+fn to_utc(time: &DateTime<FixedOffset>) -> UtcTime {
+    time.with_timezone(&Utc)
+}
+
+#[derive(Deserialize)]
+struct SearchResponse {
+    sendungen: Vec<Sendung>,
+}
+
+/// A shipment
+#[derive(Deserialize)]
+struct Sendung {
+    id:              String,
+    sendungsdetails: Sendungsdetails,
+}
+impl Sendung {
+    fn to_package(&self) -> Package {
+        let details = &self.sendungsdetails;
+        let history = &details.sendungsverlauf;
+        let events: Vec<Event> = history
+            .events
+            .iter()
+            .map(|e| e.to_event())
+            .collect();
+        // The last event is the delivery
+        let delivered = match details.ist_zugestellt {
+            true => history
+                .events
+                .last()
+                .map(|e| to_utc(&e.datum)),
+            false => None,
+        };
+        let eta_window = match details.ist_zugestellt {
+            true => None,
+            false => details
+                .zustellung
+                .as_ref()
+                .and_then(|z| z.time_window()),
+        };
+        Package {
+            barcode: self.id.clone(),
+            channel: "DHL Paket".into(),
+            status: match details.ist_zugestellt {
+                true => PackageStatus::Delivered,
+                false => PackageStatus::InTransit,
+            },
+            sender: None,
+            recipient: None,
+            eta: eta_window.as_ref().map(|w| w.start),
+            eta_window,
+            delivered,
+            events,
+            pickup: None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Sendungsdetails {
+    #[serde(default)]
+    ist_zugestellt:  bool,
+    /// Delivery
+    zustellung:      Option<Zustellung>,
+    /// Shipment history
+    sendungsverlauf: Sendungsverlauf,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Zustellung {
+    zustellzeitfenster_von: Option<DateTime<FixedOffset>>,
+    zustellzeitfenster_bis: Option<DateTime<FixedOffset>>,
+}
+impl Zustellung {
+    fn time_window(&self) -> Option<TimeWindow> {
+        Some(TimeWindow {
+            start: to_utc(self.zustellzeitfenster_von.as_ref()?),
+            end:   to_utc(self.zustellzeitfenster_bis.as_ref()?),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct Sendungsverlauf {
+    /// Oldest first
+    #[serde(default)]
+    events: Vec<DhlPaketEvent>,
+}
+
+#[derive(Deserialize)]
+struct DhlPaketEvent {
+    datum:  DateTime<FixedOffset>,
+    /// Location
+    ort:    Option<String>,
+    status: String,
+}
+impl DhlPaketEvent {
+    fn to_event(&self) -> Event {
+        let text = match &self.ort {
+            Some(ort) => format!("{} ({ort})", self.status),
+            None => self.status.clone(),
+        };
+        Event::new(to_utc(&self.datum), text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks;
+    use crate::test_utils::utc;
+
+    #[test]
+    fn test_get_barcode() -> Result<()> {
+        assert_eq!(
+            get_barcode(
+                "https://www.dhl.de/de/privatkunden/pakete-empfangen/verfolgen.html?piececode=00340434161094042557"
+            )?,
+            "00340434161094042557"
+        );
+        assert_eq!(
+            get_barcode(
+                "https://nolp.dhl.de/nextt-online-public/set_identcodes.do?idc=00340434161094042557"
+            )?,
+            "00340434161094042557"
+        );
+        assert!(get_barcode("https://www.dhl.de/").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_undelivered() -> Result<()> {
+        let mock = mocks::load_text("dhlpaket_undelivered.json")?;
+        let package = DhlPaketTracker.parse(mock)?;
+        assert_eq!(package.barcode, "00340434161094042557");
+        assert_eq!(package.channel, "DHL Paket");
+        assert_eq!(package.status, PackageStatus::InTransit);
+        assert_eq!(
+            package.eta_window,
+            Some(TimeWindow {
+                start: utc("2026-10-15T11:00:00Z"),
+                end:   utc("2026-10-15T13:00:00Z"),
+            })
+        );
+        assert_eq!(package.delivered, None);
+        assert_eq!(package.events.len(), 3);
+        assert_eq!(
+            package.events[0].text,
+            "Die Sendung wurde im Start-Paketzentrum bearbeitet. (Bruchsal)"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_delivered() -> Result<()> {
+        let mock = mocks::load_text("dhlpaket_delivered.json")?;
+        let package = DhlPaketTracker.parse(mock)?;
+        assert_eq!(package.status, PackageStatus::Delivered);
+        assert_eq!(package.delivered, Some(utc("2026-10-15T11:27:00Z")));
+        assert_eq!(package.eta_window, None);
+        Ok(())
+    }
+}
@@ -0,0 +1,450 @@
+// DPD NL/BE URLs point at the myDPD tracking page, which we scrape:
+// https://www.dpdgroup.com/nl/mydpd/my-parcels/incoming?parcelNumber=05112087822887
+//
+// DPD DE URLs point at a page backed by the "parcel life cycle" JSON API:
+// https://tracking.dpd.de/status/en_US/parcel/05112087822887
+// API url:
+// https://tracking.dpd.de/rest/plc/en_US/05112087822887
+
+use crate::Result;
+use crate::tracker::html::HtmlPage;
+use crate::tracker::{
+    Event, Package, PackageStatus, RawResponse, TimeWindow, Tracker,
+    TrackerContext,
+};
+use crate::utils::UtcTime;
+use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use chrono_tz::Europe::Amsterdam;
+use regex::Regex;
+use serde::Deserialize;
+
+pub struct DpdTracker;
+
+#[async_trait]
+impl Tracker for DpdTracker {
+    fn name(&self) -> &str {
+        "dpd"
+    }
+    fn can_handle(&self, url: &str) -> bool {
+        url.contains("dpd")
+    }
+    fn barcode_patterns(&self) -> &[&str] {
+        &["[0-9]{14}"]
+    }
+    fn barcode_url(&self, barcode: &str) -> Option<String> {
+        Some(get_url(barcode))
+    }
+    fn languages(&self) -> &[&str] {
+        // `parse` relies on the English labels in the page
+        &["en"]
+    }
+    fn probe_url(&self) -> Option<String> {
+        Some("https://www.dpdgroup.com/nl/mydpd/my-parcels/incoming?parcelNumber=99999999999999".into())
+    }
+    fn check_probe(&self, raw: &RawResponse) -> Result<()> {
+        raw.expect_html()
+    }
+    async fn get_raw(
+        &self,
+        url: &str,
+        ctx: &TrackerContext,
+    ) -> Result<RawResponse> {
+        let barcode = get_barcode(url)?;
+        if url.contains("tracking.dpd.de") {
+            let response =
+                reqwest::get(ctx.api_url(&get_plc_url(&barcode))).await?;
+            return RawResponse::from_response(response).await;
+        }
+        let response = reqwest::get(ctx.api_url(&get_url(&barcode))).await?;
+        let raw = RawResponse::from_response(response).await?;
+        if raw.is_success() && !raw.is_html() {
+            log::warn!(
+                "Expected an HTML page from DPD, got {:?}",
+                raw.content_type
+            );
+        }
+        Ok(raw)
+    }
+    fn parse(&self, text: String) -> Result<Package> {
+        if text.trim_start().starts_with('{') {
+            return parse_plc(&text);
+        }
+        let page = HtmlPage::parse(&text);
+        let barcode =
+            page.require_text(".deliveryStatus .parcelSender span")?;
+        let status_text = page.require_text(".deliveryStatusType")?;
+        let sender = page.text(".deliveryStatus .parcelSender h4")?;
+        // The first paragraph is the "To:" label
+        let recipient = page
+            .texts(".detailsBox .box-4 p")?
+            .into_iter()
+            .nth(1);
+        let date_text = page.text(".deliveryDate span")?;
+        let delivery = date_text
+            .as_deref()
+            .map(parse_delivery_date)
+            .transpose()?;
+
+        let is_delivered = status_text == "Delivered";
+        let (status, delivered, eta_window) = match (is_delivered, delivery) {
+            (true, Some(DeliveryDate::Day(date))) => (
+                PackageStatus::Delivered,
+                Some(local_time(date, NaiveTime::MIN)?),
+                None,
+            ),
+            (true, _) => (PackageStatus::Delivered, None, None),
+            (false, Some(DeliveryDate::Window(window))) => {
+                (PackageStatus::InTransit, None, Some(window))
+            }
+            (false, _) => (PackageStatus::InTransit, None, None),
+        };
+        Ok(Package {
+            barcode,
+            channel: "DPD".into(),
+            status,
+            sender,
+            recipient,
+            eta: eta_window.as_ref().map(|w| w.start),
+            eta_window,
+            delivered,
+            events: vec![],
+            pickup: None,
+        })
+    }
+}
+
+fn get_barcode(url: &str) -> Result<String> {
+    Regex::new(r"parcelNumber=([0-9A-Z]+)")?
+        .captures(url)
+        .or(Regex::new(r"\b([0-9]{14})\b")?.captures(url))
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_owned())
+        .ok_or(format!("Couldn't get barcode from {url}").into())
+}
+
+fn get_url(barcode: &str) -> String {
+    format!(
+        "https://www.dpdgroup.com/nl/mydpd/my-parcels/incoming?parcelNumber={barcode}&lang=en"
+    )
+}
+
+fn get_plc_url(barcode: &str) -> String {
+    format!("https://tracking.dpd.de/rest/plc/en_US/{barcode}")
+}
+
+/// Parse the parcel life cycle JSON
+fn parse_plc(text: &str) -> Result<Package> {
+    let response: PlcResponse = serde_json::from_str(text)
+        .map_err(|err| format!("Error parsing DPD response: {err}"))?;
+    let data = response
+        .parcellifecycle_response
+        .parcel_life_cycle_data
+        .ok_or("DPD doesn't know this parcel")?;
+    let shipment = data.shipment_info;
+    let mut events = vec![];
+    let mut delivered = None;
+    for scan in &data.scan_info.scan {
+        let timestamp = local_time(scan.date.date(), scan.date.time())?;
+        if scan.scan_data.scan_type.code == DELIVERED_SCAN {
+            delivered = Some(timestamp);
+        }
+        let mut text = scan.scan_description.content.join(" ");
+        if let Some(location) = &scan.scan_data.location {
+            text += &format!(" ({location})");
+        }
+        events.push(Event::new(timestamp, text));
+    }
+    let is_delivered = delivered.is_some()
+        || data
+            .status_info
+            .iter()
+            .any(|s| s.status == "DELIVERED" && s.status_has_been_reached);
+    let eta_window = match is_delivered {
+        true => None,
+        false => shipment
+            .predict_information
+            .map(|predict| predict.to_time_window())
+            .transpose()?,
+    };
+    Ok(Package {
+        barcode: shipment.parcel_label_number,
+        channel: "DPD".into(),
+        status: match is_delivered {
+            true => PackageStatus::Delivered,
+            false => PackageStatus::InTransit,
+        },
+        sender: shipment.sender_name,
+        recipient: shipment.receiver_name,
+        eta: eta_window.as_ref().map(|w| w.start),
+        eta_window,
+        delivered,
+        events,
+        pickup: None,
+    })
+}
+
+/// The scan type code for delivery to the recipient
+const DELIVERED_SCAN: &str = "13";
+
+#[derive(Deserialize)]
+struct PlcResponse {
+    #[serde(rename = "parcellifecycleResponse")]
+    parcellifecycle_response: PlcBody,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PlcBody {
+    parcel_life_cycle_data: Option<PlcData>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PlcData {
+    shipment_info: ShipmentInfo,
+    #[serde(default)]
+    status_info:   Vec<StatusInfo>,
+    #[serde(default)]
+    scan_info:     ScanInfo,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ShipmentInfo {
+    parcel_label_number: String,
+    sender_name:         Option<String>,
+    receiver_name:       Option<String>,
+    /// The one-hour "Predict" delivery slot
+    predict_information: Option<PredictInformation>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StatusInfo {
+    /// e.g. "AT_DELIVERY_DEPOT" or "DELIVERED"
+    status:                  String,
+    status_has_been_reached: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PredictInformation {
+    /// e.g. "2024-11-26"
+    date:      NaiveDate,
+    /// e.g. "13:28"
+    time_from: String,
+    time_to:   String,
+}
+impl PredictInformation {
+    fn to_time_window(&self) -> Result<TimeWindow> {
+        let time = |text: &str| -> Result<UtcTime> {
+            local_time(self.date, NaiveTime::parse_from_str(text, "%H:%M")?)
+        };
+        Ok(TimeWindow {
+            start: time(&self.time_from)?,
+            end:   time(&self.time_to)?,
+        })
+    }
+}
+
+#[derive(Deserialize, Default)]
+struct ScanInfo {
+    scan: Vec<Scan>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Scan {
+    /// Local time
+    date:             NaiveDateTime,
+    scan_data:        ScanData,
+    scan_description: ScanDescription,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ScanData {
+    location:  Option<String>,
+    scan_type: ScanType,
+}
+
+#[derive(Deserialize)]
+struct ScanType {
+    code: String,
+}
+
+#[derive(Deserialize)]
+struct ScanDescription {
+    content: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+enum DeliveryDate {
+    /// Delivered packages only show the date
+    Day(NaiveDate),
+    /// Undelivered packages show a date and time window
+    Window(TimeWindow),
+}
+
+/// Parse "26-11-2024" or "26-11-2024, 13:28-14:28"
+fn parse_delivery_date(text: &str) -> Result<DeliveryDate> {
+    let (date, window) = match text.split_once(',') {
+        Some((date, window)) => (date, Some(window.trim())),
+        None => (text, None),
+    };
+    let date = NaiveDate::parse_from_str(date.trim(), "%d-%m-%Y")?;
+    let Some(window) = window else {
+        return Ok(DeliveryDate::Day(date));
+    };
+    let (start, end) = window
+        .split_once('-')
+        .ok_or(format!("Couldn't parse DPD time window {window:?}"))?;
+    Ok(DeliveryDate::Window(TimeWindow {
+        start: local_time(date, NaiveTime::parse_from_str(start, "%H:%M")?)?,
+        end:   local_time(date, NaiveTime::parse_from_str(end, "%H:%M")?)?,
+    }))
+}
+
+/// DPD shows times in (Central European) local time
+fn local_time(date: NaiveDate, time: NaiveTime) -> Result<UtcTime> {
+    Amsterdam
+        .from_local_datetime(&date.and_time(time))
+        .earliest()
+        .map(|t| t.to_utc())
+        .ok_or(format!("Invalid local time {date} {time}").into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks;
+    use crate::test_utils::utc;
+
+    #[test]
+    fn test_get_barcode() -> Result<()> {
+        assert_eq!(
+            get_barcode(
+                "https://www.dpdgroup.com/nl/mydpd/my-parcels/incoming?parcelNumber=05112087822887"
+            )?,
+            "05112087822887"
+        );
+        assert_eq!(
+            get_barcode(
+                "https://tracking.dpd.de/status/en_US/parcel/05112087822887"
+            )?,
+            "05112087822887"
+        );
+        assert!(get_barcode("https://dpd.com").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_delivery_date() -> Result<()> {
+        assert_eq!(
+            parse_delivery_date("26-11-2024")?,
+            DeliveryDate::Day(NaiveDate::from_ymd_opt(2024, 11, 26).unwrap())
+        );
+        assert_eq!(
+            parse_delivery_date("26-11-2024, 13:28-14:28")?,
+            DeliveryDate::Window(TimeWindow {
+                start: utc("2024-11-26T12:28:00Z"),
+                end:   utc("2024-11-26T13:28:00Z"),
+            })
+        );
+        assert!(parse_delivery_date("yesterday").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_undelivered() -> Result<()> {
+        let text = mocks::load_text("dpd_undelivered.html")?;
+        let package = DpdTracker.parse(text)?;
+        assert_eq!(package.barcode, "05112087822887");
+        assert_eq!(package.channel, "DPD");
+        assert_eq!(package.status, PackageStatus::InTransit);
+        assert_eq!(package.sender.unwrap(), "Sender Name");
+        assert_eq!(package.recipient.unwrap(), "Receiver Name");
+        assert_eq!(package.eta, Some(utc("2024-11-26T12:28:00Z")));
+        assert_eq!(
+            package.eta_window,
+            Some(TimeWindow {
+                start: utc("2024-11-26T12:28:00Z"),
+                end:   utc("2024-11-26T13:28:00Z"),
+            })
+        );
+        assert_eq!(package.delivered, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_delivered() -> Result<()> {
+        let text = mocks::load_text("dpd_delivered.html")?;
+        let package = DpdTracker.parse(text)?;
+        assert_eq!(package.barcode, "05112087822887");
+        assert_eq!(package.status, PackageStatus::Delivered);
+        assert_eq!(package.delivered, Some(utc("2024-11-25T23:00:00Z")));
+        assert_eq!(package.eta_window, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_plc_undelivered() -> Result<()> {
+        let text = mocks::load_text("dpd_plc_undelivered.json")?;
+        let package = DpdTracker.parse(text)?;
+        assert_eq!(package.barcode, "05112087822887");
+        assert_eq!(package.status, PackageStatus::InTransit);
+        assert_eq!(package.sender.unwrap(), "Sender Name");
+        assert_eq!(package.recipient.unwrap(), "Receiver Name");
+        assert_eq!(
+            package.eta_window,
+            Some(TimeWindow {
+                start: utc("2024-11-26T12:28:00Z"),
+                end:   utc("2024-11-26T13:28:00Z"),
+            })
+        );
+        assert_eq!(package.delivered, None);
+        assert_eq!(package.events.len(), 3);
+        assert_eq!(package.events[0].timestamp, utc("2024-11-25T18:03:12Z"));
+        assert_eq!(
+            package.events[0].text,
+            "At parcel delivery centre. (Oldenzaal (NL))"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_plc_delivered() -> Result<()> {
+        let text = mocks::load_text("dpd_plc_delivered.json")?;
+        let package = DpdTracker.parse(text)?;
+        assert_eq!(package.status, PackageStatus::Delivered);
+        assert_eq!(package.delivered, Some(utc("2024-11-26T12:41:02Z")));
+        assert_eq!(package.eta_window, None);
+        assert_eq!(package.events.len(), 5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_plc_unknown_parcel() {
+        let text = r#"{"parcellifecycleResponse": {}}"#;
+        let err = DpdTracker
+            .parse(text.into())
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("doesn't know this parcel")
+        );
+    }
+
+    #[test]
+    fn test_parse_not_a_tracking_page() {
+        let err = DpdTracker
+            .parse("<html><body>Not found</body></html>".into())
+            .err()
+            .unwrap();
+        assert!(
+            err.to_string()
+                .contains("Couldn't find")
+        );
+    }
+}
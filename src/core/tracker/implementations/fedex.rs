@@ -0,0 +1,298 @@
+// Consumer URLs look like:
+// https://www.fedex.com/fedextrack/?trknbr=772124253626
+// https://www.fedex.com/fedextrack/?tracknumbers=772124253626
+
+// API url (this is what the tracking page itself uses):
+// POST https://api.fedex.com/track/v2/shipments
+// with the tracking number in the body, see mocks/fedex_payload.json
+
+use crate::Result;
+use crate::tracker::{
+    Event, Package, PackageStatus, RawResponse, TimeWindow, Tracker,
+    TrackerContext,
+};
+use crate::utils::UtcTime;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::json;
+
+const API_URL: &str = "https://api.fedex.com/track/v2/shipments";
+
+pub struct FedexTracker;
+
+#[async_trait]
+impl Tracker for FedexTracker {
+    fn name(&self) -> &str {
+        "fedex"
+    }
+    fn can_handle(&self, url: &str) -> bool {
+        url.contains("fedex.com")
+    }
+    fn barcode_patterns(&self) -> &[&str] {
+        // Express tracking numbers are 12 digits, like GLS parcel numbers, so
+        // we only recognise the longer Ground ones
+        &["[0-9]{15}"]
+    }
+    fn barcode_url(&self, barcode: &str) -> Option<String> {
+        Some(format!(
+            "https://www.fedex.com/fedextrack/?trknbr={barcode}"
+        ))
+    }
+    fn probe_url(&self) -> Option<String> {
+        Some("https://www.fedex.com/fedextrack/?trknbr=000000000000".into())
+    }
+    fn check_probe(&self, raw: &RawResponse) -> Result<()> {
+        raw.expect_json()
+    }
+    async fn get_raw(
+        &self,
+        url: &str,
+        ctx: &TrackerContext,
+    ) -> Result<RawResponse> {
+        let barcode = get_barcode(url)?;
+        let payload = json!({
+            "appDeviceType": "WTRK",
+            "appType": "WTRK",
+            "supportHTML": true,
+            "supportCurrentLocation": true,
+            "trackingInfo": [{
+                "trackNumberInfo": {
+                    "trackingCarrier": "",
+                    "trackingNumber": barcode,
+                    "trackingQualifier": "",
+                }
+            }],
+            "uniqueKey": "",
+            "guestAuthenticationToken": "",
+        });
+        let response = reqwest::Client::new()
+            .post(ctx.api_url(API_URL))
+            .json(&payload)
+            .send()
+            .await?;
+        RawResponse::from_response(response).await
+    }
+    fn parse(&self, text: String) -> Result<Package> {
+        let response: FedexResponse = serde_json::from_str(&text)
+            .map_err(|err| format!("Error parsing FedEx response: {err}"))?;
+        let package = response
+            .output
+            .packages
+            .into_iter()
+            .next()
+            .ok_or("No packages in FedEx response")?;
+        if package.not_found {
+            return Err(format!(
+                "FedEx couldn't find tracking number {}",
+                package.tracking_nbr
+            )
+            .into());
+        }
+        Ok(package.to_package())
+    }
+}
+
+fn get_barcode(url: &str) -> Result<String> {
+    Regex::new(r"(?:trknbr|tracknumbers)=([0-9]+)")?
+        .captures(url)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_owned())
+        .ok_or(format!("Couldn't get barcode from {url}").into())
+}
+
+/// FedEx uses empty strings for missing values, including timestamps
+fn parse_time(text: &str) -> Option<UtcTime> {
+    DateTime::parse_from_rfc3339(text)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+fn non_empty(text: &str) -> Option<String> {
+    Some(text.trim())
+        .filter(|text| !text.is_empty())
+        .map(String::from)
+}
+
+#[derive(Deserialize)]
+struct FedexResponse {
+    output: FedexOutput,
+}
+
+#[derive(Deserialize)]
+struct FedexOutput {
+    packages: Vec<FedexPackage>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct FedexPackage {
+    tracking_nbr:        String,
+    shipper_cmpny_name:  String,
+    shipper_name:        String,
+    recipient_name:      String,
+    delivered:           bool,
+    not_found:           bool,
+    /// Held at a FedEx location for pickup
+    hal:                 bool,
+    hal_cmpny_name:      String,
+    act_delivery_dt:     String,
+    est_delivery_dt:     String,
+    est_del_time_window: Option<EstimatedWindow>,
+    scan_event_list:     Vec<ScanEvent>,
+}
+impl FedexPackage {
+    fn to_package(&self) -> Package {
+        Package {
+            barcode:    self.tracking_nbr.clone(),
+            channel:    "FedEx".into(),
+            status:     self.status(),
+            sender:     non_empty(&self.shipper_cmpny_name)
+                .or(non_empty(&self.shipper_name)),
+            recipient:  non_empty(&self.recipient_name),
+            eta:        parse_time(&self.est_delivery_dt),
+            eta_window: self.eta_window(),
+            delivered:  parse_time(&self.act_delivery_dt),
+            events:     self.events(),
+            pickup:     None,
+        }
+    }
+    fn status(&self) -> PackageStatus {
+        if self.delivered {
+            PackageStatus::Delivered
+        } else if self.hal {
+            PackageStatus::ReadyForPickup {
+                location: non_empty(&self.hal_cmpny_name)
+                    .unwrap_or("a FedEx location".into()),
+            }
+        } else {
+            PackageStatus::InTransit
+        }
+    }
+    fn eta_window(&self) -> Option<TimeWindow> {
+        let window = self.est_del_time_window.as_ref()?;
+        Some(TimeWindow {
+            start: parse_time(&window.est_del_tm_window_start)?,
+            end:   parse_time(&window.est_del_tm_window_end)?,
+        })
+    }
+    fn events(&self) -> Vec<Event> {
+        let mut events = vec![];
+        for scan in &self.scan_event_list {
+            match scan.to_event() {
+                Some(event) => events.push(event),
+                None => log::warn!(
+                    "Couldn't parse the time of FedEx scan {:?}",
+                    scan.status
+                ),
+            }
+        }
+        events
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct EstimatedWindow {
+    est_del_tm_window_start: String,
+    est_del_tm_window_end:   String,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct ScanEvent {
+    /// e.g. "2025-02-20"
+    date:          String,
+    /// e.g. "12:54:00"
+    time:          String,
+    /// e.g. "+01:00"
+    gmt_offset:    String,
+    status:        String,
+    scan_location: String,
+    scan_details:  String,
+}
+impl ScanEvent {
+    fn to_event(&self) -> Option<Event> {
+        let timestamp = parse_time(&format!(
+            "{}T{}{}",
+            self.date, self.time, self.gmt_offset
+        ))?;
+        let mut text = self.status.clone();
+        if let Some(details) = non_empty(&self.scan_details) {
+            text += &format!(": {details}");
+        }
+        if let Some(location) = non_empty(&self.scan_location) {
+            text += &format!(" ({location})");
+        }
+        Some(Event::new(timestamp, text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks;
+    use crate::test_utils::utc;
+
+    #[test]
+    fn test_get_barcode() -> Result<()> {
+        assert_eq!(
+            get_barcode(
+                "https://www.fedex.com/fedextrack/?trknbr=772124253626"
+            )?,
+            "772124253626"
+        );
+        assert_eq!(
+            get_barcode(
+                "https://www.fedex.com/fedextrack/?tracknumbers=772124253626&cntry_code=nl"
+            )?,
+            "772124253626"
+        );
+        assert!(get_barcode("https://www.fedex.com/").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_undelivered() -> Result<()> {
+        let mock = mocks::load_text("fedex_undelivered.json")?;
+        let package = FedexTracker.parse(mock)?;
+        assert_eq!(package.barcode, "772124253626");
+        assert_eq!(package.channel, "FedEx");
+        assert_eq!(package.status, PackageStatus::InTransit);
+        assert_eq!(package.eta, Some(utc("2025-02-21T17:00:00Z")));
+        assert_eq!(package.eta_window, None);
+        assert_eq!(package.delivered, None);
+        assert_eq!(package.events.len(), 6);
+        assert_eq!(package.events[0].timestamp, utc("2025-02-18T13:26:00Z"));
+        assert_eq!(package.events[0].text, "On the way (QUITO EC)");
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_delivered() -> Result<()> {
+        let mock = mocks::load_text("fedex_delivered.json")?;
+        let package = FedexTracker.parse(mock)?;
+        assert_eq!(package.status, PackageStatus::Delivered);
+        assert_eq!(package.delivered, Some(utc("2025-02-20T11:54:00Z")));
+        assert_eq!(package.eta, None);
+        assert_eq!(package.sender, None);
+        assert_eq!(package.events.len(), 20);
+        assert_eq!(package.events[0].text, "Delivered (City NL)");
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_not_found() {
+        let text = r#"{"output": {"packages": [
+            {"trackingNbr": "000000000000", "notFound": true}
+        ]}}"#;
+        let err = FedexTracker
+            .parse(text.into())
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("couldn't find tracking number 000000000000")
+        );
+    }
+}
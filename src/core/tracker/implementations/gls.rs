@@ -1,6 +1,9 @@
 use crate::Result;
 use crate::tracker::{Event, Package, PackageStatus, TimeWindow, Tracker};
-use crate::{tracker::TrackerContext, utils::UtcTime};
+use crate::{
+    tracker::{RawResponse, TrackerContext},
+    utils::UtcTime,
+};
 use async_trait::async_trait;
 use chrono::NaiveDateTime;
 use log;
@@ -11,18 +14,36 @@ pub struct GlsTracker;
 
 #[async_trait]
 impl Tracker for GlsTracker {
+    fn name(&self) -> &str {
+        "gls"
+    }
     fn can_handle(&self, url: &str) -> bool {
         url.contains("gls-info.nl") || url.contains("gls-group.eu")
     }
-    async fn get_raw(&self, url: &str, ctx: &TrackerContext) -> Result<String> {
+    fn barcode_patterns(&self) -> &[&str] {
+        &["[0-9]{11,12}"]
+    }
+    fn barcode_url(&self, barcode: &str) -> Option<String> {
+        Some(format!(
+            "https://www.gls-info.nl/tracking?parcelNo={barcode}"
+        ))
+    }
+    fn probe_url(&self) -> Option<String> {
+        Some("https://www.gls-info.nl/tracking?parcelNo=000000000000&zipcode=1234AB".into())
+    }
+    fn check_probe(&self, raw: &RawResponse) -> Result<()> {
+        raw.expect_json()
+    }
+    async fn get_raw(
+        &self,
+        url: &str,
+        ctx: &TrackerContext,
+    ) -> Result<RawResponse> {
         let (barcode, postcode) =
-            get_barcode_postcode(url, ctx.recipient_postcode.as_deref())?;
+            get_barcode_postcode(url, ctx.recipient_postcode)?;
         let url = get_url(&barcode, &postcode);
-        let response = reqwest::get(&url)
-            .await?
-            .error_for_status()?;
-        let text = response.text().await?;
-        Ok(text)
+        let response = reqwest::get(ctx.api_url(&url)).await?;
+        RawResponse::from_response(response).await
     }
     fn parse(&self, text: String) -> Result<Package> {
         let data: Value = serde_json::from_str(&text).map_err(|err| {
@@ -93,17 +114,16 @@ impl GlsPackage {
             .filter(|name| !name.is_empty()) // convert "" to None
     }
     fn status(&self) -> PackageStatus {
-        if let Some(_) = self.delivered() {
+        if self.delivered().is_some() {
             let mut neighbour = None;
             if let Some(events) = &self.scans {
                 for event in events.iter() {
                     // FIXME: so robust
                     if let Some(scan_type) = event.scan_type
                         && scan_type == 7
+                        && let Some(name) = &event.deliver_name
                     {
-                        if let Some(name) = &event.deliver_name {
-                            neighbour = Some(name.clone());
-                        }
+                        neighbour = Some(name.clone());
                     }
                 }
             }
@@ -128,6 +148,7 @@ impl GlsPackage {
             eta_window: self.eta_window(),
             events:     self.events()?,
             delivered:  self.delivered(),
+            pickup:     None,
         })
     }
 }
@@ -165,7 +186,7 @@ impl GlsEvent {
             text_parts.push(alt_text)
         }
         let text = text_parts.join(" | ");
-        Ok(Event { timestamp, text })
+        Ok(Event::new(timestamp, text))
     }
 }
 
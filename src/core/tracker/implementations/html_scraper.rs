@@ -0,0 +1,334 @@
+// Trackers for carriers without a JSON API, defined by the user in
+// `scrapers.json` in the config dir instead of in Rust. Each scraper fetches
+// the carrier's tracking page, and picks the events out of the HTML with CSS
+// selectors:
+//
+// [{
+//     "name": "examplepost",
+//     "channel": "Example Post",
+//     "url_pattern": "example-post\\.com/track/([A-Z0-9]+)",
+//     "event": "table.history tr",
+//     "event_time": "td.date",
+//     "event_text": "td.status",
+//     "time_regex": "([0-9-]+ [0-9:]+)",
+//     "time_format": "%d-%m-%Y %H:%M",
+//     "timezone": "Europe/Amsterdam",
+//     "delivered": "(?i)delivered"
+// }]
+//
+// Scrapers are added after the built-in trackers, so they can't take over
+// URLs that a built-in tracker handles.
+
+use crate::tracker::html::HtmlPage;
+use crate::tracker::{
+    Event, Package, PackageStatus, RawResponse, Tracker, TrackerContext,
+};
+use crate::utils::UtcTime;
+use crate::{Result, utils};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone};
+use chrono_tz::Tz;
+use regex::Regex;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// The definition of a scraper, as written in `scrapers.json`
+#[derive(Deserialize)]
+pub struct ScraperConfig {
+    /// Tracker name, e.g. "examplepost"
+    pub name:        String,
+    /// Carrier name for the packages. Defaults to the `name`.
+    pub channel:     Option<String>,
+    /// Regex for the tracking URLs this scraper handles. The first capture
+    /// group is the tracking number.
+    pub url_pattern: String,
+    /// The page to fetch, with "{barcode}" replaced by the tracking number.
+    /// Defaults to the tracking URL itself.
+    pub fetch_url:   Option<String>,
+    /// CSS selector for the events, e.g. "table.history tr"
+    pub event:       String,
+    /// CSS selector for the event's time, within the event
+    pub event_time:  String,
+    /// CSS selector for the event's description, within the event
+    pub event_text:  String,
+    /// Regex to pick the time out of the `event_time` text. The first capture
+    /// group is the time.
+    pub time_regex:  Option<String>,
+    /// chrono format of the time, e.g. "%d-%m-%Y %H:%M". Times without an
+    /// offset (`%z`) are in the `timezone`.
+    pub time_format: String,
+    #[serde(default = "default_timezone")]
+    pub timezone:    Tz,
+    /// Regex matching the description of the delivery event
+    pub delivered:   Option<String>,
+}
+
+fn default_timezone() -> Tz {
+    Tz::UTC
+}
+
+pub struct ScraperTracker {
+    config:      ScraperConfig,
+    url_pattern: Regex,
+    time_regex:  Option<Regex>,
+    delivered:   Option<Regex>,
+}
+
+impl ScraperTracker {
+    /// Check the regexes and selectors up front, so that mistakes show up when
+    /// the scraper is loaded rather than when it's used
+    pub fn new(config: ScraperConfig) -> Result<Self> {
+        let regex = |pattern: &str| {
+            Regex::new(pattern).map_err(|err| {
+                format!("Scraper {}: invalid regex: {err}", config.name)
+            })
+        };
+        let url_pattern = regex(&config.url_pattern)?;
+        let time_regex = config
+            .time_regex
+            .as_deref()
+            .map(regex)
+            .transpose()?;
+        let delivered = config
+            .delivered
+            .as_deref()
+            .map(regex)
+            .transpose()?;
+        HtmlPage::parse("")
+            .records(&config.event, &[&config.event_time, &config.event_text])
+            .map_err(|err| format!("Scraper {}: {err}", config.name))?;
+        Ok(Self {
+            config,
+            url_pattern,
+            time_regex,
+            delivered,
+        })
+    }
+
+    fn get_barcode(&self, url: &str) -> Result<String> {
+        self.url_pattern
+            .captures(url)
+            .and_then(|caps| caps.get(1))
+            .map(|m| m.as_str().to_owned())
+            .ok_or(format!("Couldn't get barcode from {url}").into())
+    }
+
+    fn parse_time(&self, text: &str) -> Result<UtcTime> {
+        let text = match &self.time_regex {
+            Some(rx) => rx
+                .captures(text)
+                .and_then(|caps| caps.get(1))
+                .map(|m| m.as_str())
+                .ok_or(format!("Couldn't find a time in {text:?}"))?,
+            None => text,
+        };
+        let format = &self.config.time_format;
+        if format.contains("%z") || format.contains("%:z") {
+            return Ok(DateTime::parse_from_str(text, format)?.to_utc());
+        }
+        let time =
+            NaiveDateTime::parse_from_str(text, format).or_else(|_| {
+                NaiveDate::parse_from_str(text, format)
+                    .map(|date| date.and_time(Default::default()))
+            })?;
+        self.config
+            .timezone
+            .from_local_datetime(&time)
+            .earliest()
+            .map(|t| t.to_utc())
+            .ok_or(format!("Invalid local time {time}").into())
+    }
+}
+
+#[async_trait]
+impl Tracker for ScraperTracker {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+    fn can_handle(&self, url: &str) -> bool {
+        self.url_pattern.is_match(url)
+    }
+    async fn get_raw(
+        &self,
+        url: &str,
+        ctx: &TrackerContext,
+    ) -> Result<RawResponse> {
+        let fetch_url = match &self.config.fetch_url {
+            Some(template) => {
+                template.replace("{barcode}", &self.get_barcode(url)?)
+            }
+            None => url.to_owned(),
+        };
+        log::debug!("Scraper {} fetching {fetch_url}", self.config.name);
+        let response = reqwest::get(ctx.api_url(&fetch_url)).await?;
+        // The tracking number isn't always in the page, so remember it
+        let mut raw = RawResponse::from_response(response).await?;
+        raw.body = format!("<!-- {url} -->\n{}", raw.body);
+        Ok(raw)
+    }
+    fn parse(&self, text: String) -> Result<Package> {
+        let url = text
+            .strip_prefix("<!-- ")
+            .and_then(|rest| rest.split_once(" -->"))
+            .map(|(url, _)| url)
+            .unwrap_or_default();
+        let barcode = self
+            .get_barcode(url)
+            .unwrap_or_default();
+        let page = HtmlPage::parse(&text);
+        let config = &self.config;
+        let mut events = vec![];
+        let mut delivered: Option<UtcTime> = None;
+        for record in page
+            .records(&config.event, &[&config.event_time, &config.event_text])?
+        {
+            // e.g. header rows
+            let [Some(time), Some(text)] = &record[..] else {
+                continue;
+            };
+            let timestamp = self.parse_time(time)?;
+            if self
+                .delivered
+                .as_ref()
+                .is_some_and(|rx| rx.is_match(text))
+            {
+                delivered = delivered.max(Some(timestamp));
+            }
+            events.push(Event::new(timestamp, text.clone()));
+        }
+        Ok(Package {
+            barcode,
+            channel: config
+                .channel
+                .clone()
+                .unwrap_or(config.name.clone()),
+            status: match delivered {
+                Some(_) => PackageStatus::Delivered,
+                None => PackageStatus::InTransit,
+            },
+            sender: None,
+            recipient: None,
+            eta: None,
+            eta_window: None,
+            delivered,
+            events,
+            pickup: None,
+        })
+    }
+}
+
+/// Scrapers are defined in `scrapers.json` in the config dir
+pub fn get_scrapers_file() -> Result<PathBuf> {
+    utils::project_dirs().map(|dirs| dirs.config_dir().join("scrapers.json"))
+}
+
+/// Load the scrapers defined in the given file, if it exists. Scrapers with
+/// invalid regexes or selectors are skipped with a warning, so that one broken
+/// scraper doesn't break packtrack.
+pub fn load_scrapers(path: &Path) -> Result<Vec<ScraperTracker>> {
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let text = std::fs::read_to_string(path)?;
+    let configs: Vec<ScraperConfig> =
+        serde_json::from_str(&text).map_err(|err| {
+            format!("Couldn't read scrapers from {}: {err}", path.display())
+        })?;
+    let mut scrapers = vec![];
+    for config in configs {
+        match ScraperTracker::new(config) {
+            Ok(scraper) => {
+                log::info!("Loaded scraper {}", scraper.name());
+                scrapers.push(scraper);
+            }
+            Err(err) => log::warn!("{err}"),
+        }
+    }
+    Ok(scrapers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks;
+    use crate::test_utils::utc;
+
+    fn example() -> Result<ScraperTracker> {
+        let config = serde_json::from_str(
+            r#"{
+                "name": "examplepost",
+                "channel": "Example Post",
+                "url_pattern": "example-post\\.com/track/([A-Z0-9]+)",
+                "event": "table.history tr",
+                "event_time": "td.date",
+                "event_text": "td.status",
+                "time_regex": "([0-9-]+ [0-9:]+)",
+                "time_format": "%d-%m-%Y %H:%M",
+                "timezone": "Europe/Amsterdam",
+                "delivered": "(?i)delivered$"
+            }"#,
+        )?;
+        ScraperTracker::new(config)
+    }
+
+    #[test]
+    fn test_can_handle() -> Result<()> {
+        let scraper = example()?;
+        assert!(scraper.can_handle("https://www.example-post.com/track/EP1"));
+        assert!(!scraper.can_handle("https://www.example.com/track/EP1"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse() -> Result<()> {
+        let scraper = example()?;
+        let text = format!(
+            "<!-- https://www.example-post.com/track/EP123456789 -->\n{}",
+            mocks::load_text("scraper_example.html")?
+        );
+        let package = scraper.parse(text)?;
+        assert_eq!(package.barcode, "EP123456789");
+        assert_eq!(package.channel, "Example Post");
+        assert_eq!(package.status, PackageStatus::InTransit);
+        // The header row is skipped
+        assert_eq!(package.events.len(), 3);
+        assert_eq!(package.events[0].text, "Out for delivery");
+        assert_eq!(package.events[0].timestamp, utc("2026-10-14T07:12:00Z"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_delivered() -> Result<()> {
+        let scraper = example()?;
+        let text = mocks::load_text("scraper_example.html")?
+            .replace("Out for delivery", "Your parcel has been delivered");
+        let package = scraper.parse(text)?;
+        assert_eq!(package.status, PackageStatus::Delivered);
+        assert_eq!(package.delivered, Some(utc("2026-10-14T07:12:00Z")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let config: ScraperConfig = serde_json::from_str(
+            r#"{"name": "broken", "url_pattern": "(", "event": "tr",
+                "event_time": "td", "event_text": "td",
+                "time_format": "%d-%m-%Y"}"#,
+        )
+        .unwrap();
+        let err = ScraperTracker::new(config)
+            .err()
+            .unwrap();
+        assert!(
+            err.to_string()
+                .contains("Scraper broken: invalid regex")
+        );
+    }
+
+    #[test]
+    fn test_load_scrapers_missing_file() -> Result<()> {
+        let scrapers = load_scrapers(Path::new("/does/not/exist.json"))?;
+        assert!(scrapers.is_empty());
+        Ok(())
+    }
+}
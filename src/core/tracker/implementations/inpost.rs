@@ -0,0 +1,332 @@
+// InPost (parcel lockers). Consumer URLs look like:
+// https://inpost.pl/sledzenie-przesylek?number=620123456789012345678901
+// https://inpost.it/trova-il-tuo-pacco?number=620123456789012345678901
+
+// API url (ShipX, which doesn't need an API key for tracking). Italy has its
+// own instance:
+// https://api-shipx-pl.easypack24.net/v1/tracking/620123456789012345678901
+// https://api-shipx-it.easypack24.net/v1/tracking/620123456789012345678901
+
+use crate::Result;
+use crate::tracker::{
+    Event, Package, PackageStatus, PickupPoint, RawResponse, Tracker,
+    TrackerContext,
+};
+use crate::utils::UtcTime;
+use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset, TimeDelta, Utc};
+use regex::Regex;
+use serde::Deserialize;
+
+/// How long a parcel stays in the locker before it goes back to the sender.
+/// The API doesn't tell us, but this is InPost's standard.
+const LOCKER_HOURS: i64 = 48;
+
+pub struct InPostTracker;
+
+#[async_trait]
+impl Tracker for InPostTracker {
+    fn name(&self) -> &str {
+        "inpost"
+    }
+    fn can_handle(&self, url: &str) -> bool {
+        url.contains("inpost.pl") || url.contains("inpost.it")
+    }
+    fn barcode_patterns(&self) -> &[&str] {
+        &["[0-9]{24}"]
+    }
+    fn barcode_url(&self, barcode: &str) -> Option<String> {
+        Some(format!(
+            "https://inpost.pl/sledzenie-przesylek?number={barcode}"
+        ))
+    }
+    fn probe_url(&self) -> Option<String> {
+        self.barcode_url("000000000000000000000000")
+    }
+    fn check_probe(&self, raw: &RawResponse) -> Result<()> {
+        raw.expect_json()
+    }
+    async fn get_raw(
+        &self,
+        url: &str,
+        ctx: &TrackerContext,
+    ) -> Result<RawResponse> {
+        let barcode = get_barcode(url)?;
+        let country = match url.contains("inpost.it") {
+            true => "it",
+            false => "pl",
+        };
+        let api_url = format!(
+            "https://api-shipx-{country}.easypack24.net/v1/tracking/{barcode}"
+        );
+        let response = reqwest::get(ctx.api_url(&api_url)).await?;
+        RawResponse::from_response(response).await
+    }
+    fn parse(&self, text: String) -> Result<Package> {
+        let response: TrackingResponse = serde_json::from_str(&text)
+            .map_err(|err| format!("Error parsing InPost response: {err}"))?;
+        match response {
+            TrackingResponse::Parcel(parcel) => Ok(parcel.to_package()),
+            TrackingResponse::Error { message } => {
+                Err(format!("InPost: {message}").into())
+            }
+        }
+    }
+}
+
+fn get_barcode(url: &str) -> Result<String> {
+    Regex::new(r"number=([0-9A-Za-z]+)")?
+        .captures(url)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_uppercase())
+        .ok_or(format!("Couldn't get barcode from {url}").into())
+}
+
+fn to_utc(time: &DateTime<FixedOffset>) -> UtcTime {
+    time.with_timezone(&Utc)
+}
+
+/// A readable description of a ShipX status, e.g. "ready_to_pickup"
+fn describe(status: &str) -> String {
+    match status {
+        "created" | "confirmed" => "Label created".into(),
+        "dispatched_by_sender" => "Dropped off by the sender".into(),
+        "collected_from_sender" => "Collected from the sender".into(),
+        "taken_by_courier" => "Taken by the courier".into(),
+        "adopted_at_source_branch" | "adopted_at_sorting_center" => {
+            "Arrived at the sorting center".into()
+        }
+        "sent_from_source_branch" | "sent_from_sorting_center" => {
+            "Left the sorting center".into()
+        }
+        "out_for_delivery" => "Out for delivery".into(),
+        "ready_to_pickup" => "Ready to collect from the parcel locker".into(),
+        "pickup_reminder_sent" => "Reminder to collect the parcel sent".into(),
+        "pickup_time_expired" => "Not collected in time".into(),
+        "delivered" => "Delivered".into(),
+        "returned_to_sender" => "Returned to the sender".into(),
+        other => other.replace('_', " "),
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TrackingResponse {
+    Parcel(Parcel),
+    /// e.g. {"status": 404, "error": "resource_not_found", "message": ...}
+    Error {
+        message: String,
+    },
+}
+
+#[derive(Deserialize)]
+struct Parcel {
+    tracking_number:   String,
+    /// The latest status, e.g. "ready_to_pickup"
+    status:            String,
+    custom_attributes: Option<CustomAttributes>,
+    /// Newest first
+    #[serde(default)]
+    tracking_details:  Vec<TrackingDetail>,
+}
+impl Parcel {
+    fn to_package(&self) -> Package {
+        let events = self
+            .tracking_details
+            .iter()
+            .map(|d| d.to_event())
+            .collect();
+        let delivered = self
+            .tracking_details
+            .iter()
+            .find(|d| d.status == "delivered")
+            .map(|d| to_utc(&d.datetime));
+        // A reminder doesn't change the deadline
+        let ready = self
+            .tracking_details
+            .iter()
+            .find(|d| d.status == "ready_to_pickup");
+        let locker = self
+            .custom_attributes
+            .as_ref()
+            .and_then(|attrs| attrs.target_machine_detail.as_ref());
+        let pickup = match (delivered, ready, locker) {
+            (None, Some(ready), Some(locker))
+                if matches!(
+                    self.status.as_str(),
+                    "ready_to_pickup" | "pickup_reminder_sent"
+                ) =>
+            {
+                Some(PickupPoint {
+                    location: locker.describe(),
+                    deadline: Some(
+                        to_utc(&ready.datetime)
+                            + TimeDelta::hours(LOCKER_HOURS),
+                    ),
+                })
+            }
+            _ => None,
+        };
+        let status = match (&delivered, &pickup) {
+            (Some(_), _) => PackageStatus::Delivered,
+            (None, Some(pickup)) => PackageStatus::ReadyForPickup {
+                location: pickup.location.clone(),
+            },
+            (None, None) => PackageStatus::InTransit,
+        };
+        Package {
+            barcode: self.tracking_number.clone(),
+            channel: "InPost".into(),
+            status,
+            sender: None,
+            recipient: None,
+            eta: None,
+            eta_window: None,
+            delivered,
+            events,
+            pickup,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct CustomAttributes {
+    target_machine_detail: Option<Machine>,
+}
+
+/// A parcel locker
+#[derive(Deserialize)]
+struct Machine {
+    /// e.g. "KRA01M"
+    name:                 String,
+    /// e.g. "Next to the supermarket"
+    location_description: Option<String>,
+    address:              Option<Address>,
+}
+impl Machine {
+    /// e.g. "KRA01M, Karmelicka 12, 31-128 Kraków (Przy sklepie Biedronka)"
+    fn describe(&self) -> String {
+        let mut parts = vec![self.name.clone()];
+        if let Some(address) = &self.address {
+            parts.extend(
+                [&address.line1, &address.line2]
+                    .into_iter()
+                    .flatten()
+                    .cloned(),
+            );
+        }
+        let text = parts.join(", ");
+        match &self.location_description {
+            Some(description) if !description.is_empty() => {
+                format!("{text} ({description})")
+            }
+            _ => text,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Address {
+    line1: Option<String>,
+    line2: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TrackingDetail {
+    status:   String,
+    datetime: DateTime<FixedOffset>,
+}
+impl TrackingDetail {
+    fn to_event(&self) -> Event {
+        Event::new(to_utc(&self.datetime), describe(&self.status))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks;
+    use crate::test_utils::utc;
+
+    const LOCKER: &str =
+        "KRA01M, Karmelicka 12, 31-128 Kraków (Przy sklepie Biedronka)";
+
+    #[test]
+    fn test_get_barcode() -> Result<()> {
+        assert_eq!(
+            get_barcode(
+                "https://inpost.pl/sledzenie-przesylek?number=620123456789012345678901"
+            )?,
+            "620123456789012345678901"
+        );
+        assert_eq!(
+            get_barcode(
+                "https://inpost.it/trova-il-tuo-pacco?number=620123456789012345678901"
+            )?,
+            "620123456789012345678901"
+        );
+        assert!(get_barcode("https://inpost.pl/").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_undelivered() -> Result<()> {
+        let mock = mocks::load_text("inpost_undelivered.json")?;
+        let package = InPostTracker.parse(mock)?;
+        assert_eq!(package.barcode, "620123456789012345678901");
+        assert_eq!(package.channel, "InPost");
+        assert_eq!(package.status, PackageStatus::InTransit);
+        assert_eq!(package.pickup, None);
+        assert_eq!(package.events.len(), 4);
+        assert_eq!(package.events[0].text, "Out for delivery");
+        assert_eq!(package.events[0].timestamp, utc("2026-10-14T05:41:12Z"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_pickup() -> Result<()> {
+        let mock = mocks::load_text("inpost_pickup.json")?;
+        let package = InPostTracker.parse(mock)?;
+        assert_eq!(
+            package.status,
+            PackageStatus::ReadyForPickup {
+                location: LOCKER.into(),
+            }
+        );
+        assert_eq!(
+            package.pickup,
+            Some(PickupPoint {
+                location: LOCKER.into(),
+                deadline: Some(utc("2026-10-16T07:12:33Z")),
+            })
+        );
+        assert_eq!(
+            package.events[0].text,
+            "Ready to collect from the parcel locker"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_delivered() -> Result<()> {
+        let mock = mocks::load_text("inpost_delivered.json")?;
+        let package = InPostTracker.parse(mock)?;
+        assert_eq!(package.status, PackageStatus::Delivered);
+        assert_eq!(package.delivered, Some(utc("2026-10-14T16:03:09Z")));
+        assert_eq!(package.pickup, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_not_found() {
+        let text = r#"{"status": 404, "error": "resource_not_found",
+            "message": "Parcel not found", "details": {}}"#;
+        let err = InPostTracker
+            .parse(text.into())
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Parcel not found")
+        );
+    }
+}
@@ -0,0 +1,265 @@
+// Japan Post, for EMS and other international items. Consumer URLs look like:
+// https://trackings.post.japanpost.jp/services/srv/search/direct?reqCodeNo1=EJ123456789JP&searchKind=S002&locale=en
+
+// There is no API, so we scrape the tracking page. Even in English, the
+// "Details" column is often in Japanese, or in both languages, e.g.
+// "お届け先不在のため持ち戻り（Absence. Attempted delivery.）". Times are local
+// to the office that scanned the item, which may be abroad.
+
+use crate::Result;
+use crate::tracker::html::HtmlPage;
+use crate::tracker::{
+    Event, Package, PackageStatus, RawResponse, Tracker, TrackerContext,
+};
+use crate::utils::UtcTime;
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, TimeZone};
+use chrono_tz::{America, Asia, Australia, Europe, Tz};
+use regex::Regex;
+
+pub struct JapanPostTracker;
+
+#[async_trait]
+impl Tracker for JapanPostTracker {
+    fn name(&self) -> &str {
+        "japanpost"
+    }
+    fn can_handle(&self, url: &str) -> bool {
+        url.contains("japanpost.jp")
+    }
+    fn barcode_patterns(&self) -> &[&str] {
+        &["[A-Z]{2}[0-9]{9}JP"]
+    }
+    fn barcode_url(&self, barcode: &str) -> Option<String> {
+        Some(format!(
+            "https://trackings.post.japanpost.jp/services/srv/search/direct?reqCodeNo1={barcode}&searchKind=S002&locale=en"
+        ))
+    }
+    fn languages(&self) -> &[&str] {
+        // `parse` relies on the English column contents
+        &["en"]
+    }
+    fn probe_url(&self) -> Option<String> {
+        self.barcode_url("EJ000000000JP")
+    }
+    fn check_probe(&self, raw: &RawResponse) -> Result<()> {
+        raw.expect_html()
+    }
+    async fn get_raw(
+        &self,
+        url: &str,
+        ctx: &TrackerContext,
+    ) -> Result<RawResponse> {
+        let barcode = get_barcode(url)?;
+        let api_url = self
+            .barcode_url(&barcode)
+            .unwrap_or_default();
+        let response = reqwest::get(ctx.api_url(&api_url)).await?;
+        RawResponse::from_response(response).await
+    }
+    fn parse(&self, text: String) -> Result<Package> {
+        let page = HtmlPage::parse(&text);
+        let barcode = page
+            .rows("table[summary=\"照会結果\"] tr")?
+            .into_iter()
+            .next()
+            .and_then(|cells| cells.into_iter().next())
+            .ok_or("Japan Post doesn't know this item")?;
+        let mut events = vec![];
+        let mut delivered = None;
+        // Each event takes up two rows; the second one only has the office's
+        // phone number
+        for cells in page.rows("table[summary=\"履歴情報\"] tr")? {
+            let [date, record, details, office, region, _zip] = &cells[..]
+            else {
+                continue;
+            };
+            let timestamp = local_time(date, region_timezone(region))?;
+            if record == "Final delivery" {
+                delivered = Some(timestamp);
+            }
+            let mut text = record.clone();
+            let details = normalize(details);
+            if !details.is_empty() && details != *record {
+                text = format!("{text}: {details}");
+            }
+            let location = [office.as_str(), region.as_str()]
+                .into_iter()
+                .filter(|s| !s.is_empty())
+                .collect::<Vec<_>>()
+                .join(", ");
+            if !location.is_empty() {
+                text = format!("{text} ({location})");
+            }
+            events.push(Event::new(timestamp, text));
+        }
+        Ok(Package {
+            barcode,
+            channel: "Japan Post".into(),
+            status: match delivered {
+                Some(_) => PackageStatus::Delivered,
+                None => PackageStatus::InTransit,
+            },
+            sender: None,
+            recipient: None,
+            eta: None,
+            eta_window: None,
+            delivered,
+            events,
+            pickup: None,
+        })
+    }
+}
+
+fn get_barcode(url: &str) -> Result<String> {
+    Regex::new(r"reqCodeNo1=([0-9A-Za-z]+)")?
+        .captures(url)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_uppercase())
+        .ok_or(format!("Couldn't get barcode from {url}").into())
+}
+
+fn is_japanese(c: char) -> bool {
+    matches!(c, '\u{3040}'..='\u{30ff}' | '\u{4e00}'..='\u{9fff}')
+}
+
+/// Reduce a (partly) Japanese description to English: use the English
+/// translation in brackets if there is one, or else our own translation of
+/// the common phrases. Unknown Japanese text is left alone.
+fn normalize(text: &str) -> String {
+    if !text.chars().any(is_japanese) {
+        return text.to_owned();
+    }
+    let translation = text
+        .split_once(['（', '('])
+        .map(|(_, rest)| {
+            rest.trim_end_matches(['）', ')'])
+                .trim()
+        })
+        .filter(|rest| !rest.is_empty() && !rest.chars().any(is_japanese));
+    if let Some(translation) = translation {
+        return translation.to_owned();
+    }
+    TRANSLATIONS
+        .iter()
+        .find(|(japanese, _)| *japanese == text)
+        .map(|(_, english)| english.to_string())
+        .unwrap_or(text.to_owned())
+}
+
+#[rustfmt::skip]
+const TRANSLATIONS: &[(&str, &str)] = &[
+    ("引受", "Posting/Collection"),
+    ("国際交換局から発送", "Dispatch from outward office of exchange"),
+    ("国際交換局に到着", "Arrival at outward office of exchange"),
+    ("通関手続中", "Customs clearance"),
+    ("配達中", "Out for delivery"),
+    ("保管", "Held at delivery office"),
+    ("お届け先にお届け済み", "Delivered to the addressee"),
+];
+
+/// Parse "2026/10/13 06:40" in the given timezone
+fn local_time(text: &str, tz: Tz) -> Result<UtcTime> {
+    let time = NaiveDateTime::parse_from_str(text, "%Y/%m/%d %H:%M")?;
+    tz.from_local_datetime(&time)
+        .earliest()
+        .map(|t| t.to_utc())
+        .ok_or(format!("Invalid local time {time}").into())
+}
+
+/// The "Prefecture/Country" column is a prefecture for offices in Japan, and
+/// a country for offices abroad. Countries we don't know are assumed to be in
+/// Japan's timezone.
+fn region_timezone(region: &str) -> Tz {
+    COUNTRY_TIMEZONES
+        .iter()
+        .find(|(country, _)| *country == region)
+        .map(|(_, tz)| *tz)
+        .unwrap_or(Asia::Tokyo)
+}
+
+#[rustfmt::skip]
+const COUNTRY_TIMEZONES: &[(&str, Tz)] = &[
+    ("AUSTRALIA", Australia::Sydney), ("AUSTRIA", Europe::Vienna),
+    ("BELGIUM", Europe::Brussels), ("CANADA", America::Toronto),
+    ("CHINA", Asia::Shanghai), ("FRANCE", Europe::Paris),
+    ("GERMANY", Europe::Berlin), ("HONG KONG", Asia::Hong_Kong),
+    ("ITALY", Europe::Rome), ("KOREA (SOUTH)", Asia::Seoul),
+    ("NETHERLANDS", Europe::Amsterdam), ("SINGAPORE", Asia::Singapore),
+    ("SPAIN", Europe::Madrid), ("SWITZERLAND", Europe::Zurich),
+    ("TAIWAN", Asia::Taipei), ("UNITED KINGDOM", Europe::London),
+    ("UNITED STATES OF AMERICA", America::New_York),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks;
+    use crate::test_utils::utc;
+
+    #[test]
+    fn test_get_barcode() -> Result<()> {
+        assert_eq!(
+            get_barcode(
+                "https://trackings.post.japanpost.jp/services/srv/search/direct?reqCodeNo1=EJ123456789JP&searchKind=S002&locale=en"
+            )?,
+            "EJ123456789JP"
+        );
+        assert!(
+            get_barcode("https://trackings.post.japanpost.jp/services/srv/")
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("Customs clearance"), "Customs clearance");
+        assert_eq!(
+            normalize(
+                "お届け先不在のため持ち戻り（Absence. Attempted delivery.）"
+            ),
+            "Absence. Attempted delivery."
+        );
+        assert_eq!(normalize("通関手続中"), "Customs clearance");
+        assert_eq!(normalize("調査中"), "調査中");
+    }
+
+    #[test]
+    fn test_parse_undelivered() -> Result<()> {
+        let mock = mocks::load_text("japanpost_undelivered.html")?;
+        let package = JapanPostTracker.parse(mock)?;
+        assert_eq!(package.barcode, "EJ123456789JP");
+        assert_eq!(package.channel, "Japan Post");
+        assert_eq!(package.status, PackageStatus::InTransit);
+        assert_eq!(package.events.len(), 5);
+        // Tokyo time
+        assert_eq!(package.events[0].timestamp, utc("2026-10-10T05:32:00Z"));
+        assert_eq!(package.events[0].text, "Posting/Collection (KOTO, TOKYO)");
+        // Amsterdam time
+        assert_eq!(package.events[3].timestamp, utc("2026-10-13T13:20:00Z"));
+        assert_eq!(
+            package.events[3].text,
+            "Customs clearance (AMSTERDAM, NETHERLANDS)"
+        );
+        assert_eq!(
+            package.events[4].text,
+            "Held at delivery office: Absence. Attempted delivery. (UTRECHT, \
+             NETHERLANDS)"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_delivered() -> Result<()> {
+        let mock = mocks::load_text("japanpost_delivered.html")?;
+        let package = JapanPostTracker.parse(mock)?;
+        assert_eq!(package.status, PackageStatus::Delivered);
+        assert_eq!(package.delivered, Some(utc("2026-10-15T09:45:00Z")));
+        assert_eq!(
+            package.events[5].text,
+            "Final delivery: Delivered to the addressee (UTRECHT, NETHERLANDS)"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,285 @@
+// La Poste / Colissimo. Consumer URLs look like:
+// https://www.laposte.fr/outils/suivre-vos-envois?code=6A12345678901
+
+// API url (needs an API key from developer.laposte.fr, in the X-Okapi-Key
+// header):
+// https://api.laposte.fr/suivi/v2/idships/6A12345678901?lang=fr_FR
+
+use crate::Result;
+use crate::tracker::{
+    Event, Package, PackageStatus, PickupPoint, RawResponse, Tracker,
+    TrackerContext,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Europe::Paris;
+use regex::Regex;
+use serde::Deserialize;
+
+pub struct LaPosteTracker;
+
+#[async_trait]
+impl Tracker for LaPosteTracker {
+    fn name(&self) -> &str {
+        "laposte"
+    }
+    fn can_handle(&self, url: &str) -> bool {
+        url.contains("laposte.fr") || url.contains("colissimo.fr")
+    }
+    fn barcode_patterns(&self) -> &[&str] {
+        &["[0-9][A-Z][0-9]{11}", "[A-Z]{2}[0-9]{9}FR"]
+    }
+    fn barcode_url(&self, barcode: &str) -> Option<String> {
+        Some(format!(
+            "https://www.laposte.fr/outils/suivre-vos-envois?code={barcode}"
+        ))
+    }
+    fn languages(&self) -> &[&str] {
+        // `parse` relies on the French phrasing for pickup points
+        &["fr"]
+    }
+    async fn get_raw(
+        &self,
+        url: &str,
+        ctx: &TrackerContext,
+    ) -> Result<RawResponse> {
+        let barcode = get_barcode(url)?;
+        let api_key = ctx
+            .credentials
+            .require("laposte", "api_key")?;
+        let api_url = format!(
+            "https://api.laposte.fr/suivi/v2/idships/{barcode}?lang=fr_FR"
+        );
+        let response = reqwest::Client::new()
+            .get(ctx.api_url(&api_url))
+            .header("X-Okapi-Key", api_key)
+            .send()
+            .await?;
+        RawResponse::from_response(response).await
+    }
+    fn parse(&self, text: String) -> Result<Package> {
+        let response: SuiviResponse = serde_json::from_str(&text)
+            .map_err(|err| format!("Error parsing La Poste response: {err}"))?;
+        let shipment = response.shipment.ok_or(format!(
+            "La Poste couldn't find this shipment: {}",
+            response
+                .return_message
+                .unwrap_or(format!("HTTP {}", response.return_code))
+        ))?;
+        Ok(shipment.to_package())
+    }
+}
+
+fn get_barcode(url: &str) -> Result<String> {
+    Regex::new(r"code=([0-9A-Za-z]+)")?
+        .captures(url)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_uppercase())
+        .ok_or(format!("Couldn't get barcode from {url}").into())
+}
+
+/// French typography puts a (narrow) non-breaking space before colons,
+/// exclamation marks etc. Replace those with normal spaces, so that the
+/// labels are easier to match and display.
+fn normalize(label: &str) -> String {
+    label
+        .replace(['\u{a0}', '\u{202f}'], " ")
+        .trim()
+        .to_owned()
+}
+
+/// The pickup point and deadline from the label of an AG1 event, e.g. "Votre
+/// colis est disponible dans votre point de retrait : BUREAU DE POSTE PARIS
+/// LOUVRE RP. Il y sera conservé jusqu'au 29/10/2026."
+fn parse_pickup(label: &str) -> PickupPoint {
+    let location = label
+        .split_once(" : ")
+        .map(|(_, rest)| rest)
+        .and_then(|rest| rest.split(". ").next())
+        .unwrap_or("un point de retrait")
+        .trim_end_matches('.')
+        .to_owned();
+    let deadline = Regex::new(r"jusqu'au ([0-9]{2}/[0-9]{2}/[0-9]{4})")
+        .ok()
+        .and_then(|rx| rx.captures(label))
+        .and_then(|caps| caps.get(1))
+        .and_then(|m| NaiveDate::parse_from_str(m.as_str(), "%d/%m/%Y").ok())
+        .and_then(|date| {
+            // Until closing time on that day
+            Paris
+                .from_local_datetime(
+                    &date.and_time(NaiveTime::from_hms_opt(23, 59, 0)?),
+                )
+                .earliest()
+        })
+        .map(|time| time.to_utc());
+    PickupPoint { location, deadline }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SuiviResponse {
+    return_code:    u16,
+    return_message: Option<String>,
+    shipment:       Option<Shipment>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Shipment {
+    id_ship:       String,
+    #[serde(default)]
+    is_final:      bool,
+    delivery_date: Option<DateTime<FixedOffset>>,
+    /// Estimated delivery date
+    estim_date:    Option<DateTime<FixedOffset>>,
+    /// Newest first
+    #[serde(default)]
+    event:         Vec<LaPosteEvent>,
+}
+impl Shipment {
+    fn to_package(&self) -> Package {
+        let events = self
+            .event
+            .iter()
+            .map(|e| e.to_event())
+            .collect();
+        let delivered = self
+            .event
+            .iter()
+            .find(|e| e.is_delivery())
+            .map(|e| e.date)
+            .or(self.delivery_date)
+            .map(|time| time.with_timezone(&Utc));
+        let pickup = match self.event.first() {
+            Some(latest) if latest.code == "AG1" => {
+                Some(parse_pickup(&normalize(&latest.label)))
+            }
+            _ => None,
+        };
+        let status = match (&delivered, &pickup) {
+            (Some(_), _) => PackageStatus::Delivered,
+            (None, Some(pickup)) => PackageStatus::ReadyForPickup {
+                location: pickup.location.clone(),
+            },
+            (None, None) => PackageStatus::InTransit,
+        };
+        let eta = match self.is_final {
+            true => None,
+            false => self
+                .estim_date
+                .map(|time| time.with_timezone(&Utc)),
+        };
+        Package {
+            barcode: self.id_ship.clone(),
+            channel: "La Poste".into(),
+            status,
+            sender: None,
+            recipient: None,
+            eta,
+            eta_window: None,
+            delivered,
+            events,
+            pickup,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct LaPosteEvent {
+    /// e.g. "PC1" (accepted), "AG1" (waiting at a pickup point) or "DI1"
+    /// (delivered)
+    code:  String,
+    label: String,
+    date:  DateTime<FixedOffset>,
+}
+impl LaPosteEvent {
+    fn is_delivery(&self) -> bool {
+        self.code == "DI1"
+    }
+    fn to_event(&self) -> Event {
+        Event::new(self.date.with_timezone(&Utc), normalize(&self.label))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks;
+    use crate::test_utils::utc;
+
+    #[test]
+    fn test_get_barcode() -> Result<()> {
+        assert_eq!(
+            get_barcode(
+                "https://www.laposte.fr/outils/suivre-vos-envois?code=6a12345678901"
+            )?,
+            "6A12345678901"
+        );
+        assert!(get_barcode("https://www.laposte.fr/").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_undelivered() -> Result<()> {
+        let mock = mocks::load_text("laposte_undelivered.json")?;
+        let package = LaPosteTracker.parse(mock)?;
+        assert_eq!(package.barcode, "6A12345678901");
+        assert_eq!(package.channel, "La Poste");
+        assert_eq!(package.status, PackageStatus::InTransit);
+        assert_eq!(package.eta, Some(utc("2026-10-14T22:00:00Z")));
+        assert_eq!(package.delivered, None);
+        assert_eq!(package.events.len(), 4);
+        // With a normal space before the colon
+        assert_eq!(
+            package.events[1].text,
+            "Votre colis est en cours de traitement : il vient d'arriver sur \
+             notre site de Wissous."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_delivered() -> Result<()> {
+        let mock = mocks::load_text("laposte_delivered.json")?;
+        let package = LaPosteTracker.parse(mock)?;
+        assert_eq!(package.status, PackageStatus::Delivered);
+        assert_eq!(package.delivered, Some(utc("2026-10-15T09:30:00Z")));
+        assert_eq!(package.eta, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_pickup() -> Result<()> {
+        let mock = mocks::load_text("laposte_pickup.json")?;
+        let package = LaPosteTracker.parse(mock)?;
+        let location = "BUREAU DE POSTE PARIS LOUVRE RP";
+        assert_eq!(
+            package.status,
+            PackageStatus::ReadyForPickup {
+                location: location.into(),
+            }
+        );
+        assert_eq!(
+            package.pickup,
+            Some(PickupPoint {
+                location: location.into(),
+                deadline: Some(utc("2026-10-29T22:59:00Z")),
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_not_found() {
+        let text = r#"{"lang": "fr_FR", "returnCode": 104,
+            "returnMessage": "Numéro invalide", "idShip": "6A00000000000"}"#;
+        let err = LaPosteTracker
+            .parse(text.into())
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Numéro invalide")
+        );
+    }
+}
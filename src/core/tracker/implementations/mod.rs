@@ -1,4 +1,24 @@
+pub mod aramex;
+pub mod austrianpost;
+pub mod brt;
+pub mod cainiao;
+pub mod demo;
 pub mod dhl;
+pub mod dhlpaket;
+pub mod dpd;
+pub mod fedex;
 pub mod gls;
+pub mod html_scraper;
+pub mod inpost;
+pub mod japanpost;
+pub mod laposte;
+pub mod novaposhta;
+pub mod posti;
 pub mod postnl;
+pub mod royalmail;
+pub mod seventeen_track;
+pub mod tnt;
 pub mod trunkrs;
+pub mod usps;
+#[cfg(feature = "wasm-plugins")]
+pub mod wasm_plugin;
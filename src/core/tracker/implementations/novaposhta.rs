@@ -0,0 +1,357 @@
+// Nova Poshta (Ukraine). Consumer URLs look like:
+// https://novaposhta.ua/tracking/?cargo_number=20450912345678
+// https://tracking.novaposhta.ua/#/uk/search/20450912345678
+
+// API (public, the API key is optional for tracking):
+// POST https://api.novaposhta.ua/v2.0/json/
+// {"modelName": "TrackingDocument", "calledMethod": "getStatusDocuments", ...}
+
+// The API only gives the current status of a shipment, not its history, so
+// the events are made up from the status and the dates we do get. With the
+// sender's or recipient's phone number, the recipient's name is included.
+
+use crate::Result;
+use crate::tracker::{
+    Event, Package, PackageStatus, PickupPoint, RawResponse, Tracker,
+    TrackerContext,
+};
+use crate::utils::UtcTime;
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, TimeZone};
+use chrono_tz::Europe::Kyiv;
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::json;
+
+pub struct NovaPoshtaTracker;
+
+#[async_trait]
+impl Tracker for NovaPoshtaTracker {
+    fn name(&self) -> &str {
+        "novaposhta"
+    }
+    fn can_handle(&self, url: &str) -> bool {
+        url.contains("novaposhta.ua")
+    }
+    // Nova Poshta numbers are 14 digits, just like DPD ones, so there are no
+    // `barcode_patterns`
+    fn barcode_url(&self, barcode: &str) -> Option<String> {
+        Some(format!(
+            "https://novaposhta.ua/tracking/?cargo_number={barcode}"
+        ))
+    }
+    fn probe_url(&self) -> Option<String> {
+        self.barcode_url("00000000000000")
+    }
+    fn check_probe(&self, raw: &RawResponse) -> Result<()> {
+        raw.expect_json()
+    }
+    async fn get_raw(
+        &self,
+        url: &str,
+        ctx: &TrackerContext,
+    ) -> Result<RawResponse> {
+        let barcode = get_barcode(url)?;
+        let phone = ctx
+            .credentials
+            .get("novaposhta", "phone")
+            .unwrap_or_default();
+        let api_key = ctx
+            .credentials
+            .get("novaposhta", "api_key")
+            .unwrap_or_default();
+        let body = json!({
+            "apiKey": api_key,
+            "modelName": "TrackingDocument",
+            "calledMethod": "getStatusDocuments",
+            "methodProperties": {
+                "Documents": [{ "DocumentNumber": barcode, "Phone": phone }],
+            },
+        });
+        let response = reqwest::Client::new()
+            .post(ctx.api_url("https://api.novaposhta.ua/v2.0/json/"))
+            .json(&body)
+            .send()
+            .await?;
+        RawResponse::from_response(response).await
+    }
+    fn parse(&self, text: String) -> Result<Package> {
+        let response: StatusResponse =
+            serde_json::from_str(&text).map_err(|err| {
+                format!("Error parsing Nova Poshta response: {err}")
+            })?;
+        if !response.success {
+            return Err(
+                format!("Nova Poshta: {}", response.errors.join(", ")).into()
+            );
+        }
+        let document = response
+            .data
+            .into_iter()
+            .next()
+            .filter(|doc| doc.status_code != "3")
+            .ok_or("Nova Poshta doesn't know this shipment")?;
+        document.to_package()
+    }
+}
+
+fn get_barcode(url: &str) -> Result<String> {
+    Regex::new(r"(?:cargo_number=|search/)([0-9]+)")?
+        .captures(url)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_owned())
+        .ok_or(format!("Couldn't get barcode from {url}").into())
+}
+
+/// A readable description of a Nova Poshta status code. The API's own
+/// descriptions are in Ukrainian only.
+fn describe(code: &str) -> &str {
+    match code {
+        "1" => "Waiting for the sender to hand over the shipment",
+        "2" => "Deleted",
+        "4" | "41" => "In the sender's city",
+        "5" => "On its way to the recipient's city",
+        "6" => "In the recipient's city",
+        "7" | "8" => "Arrived at the branch",
+        "9" | "10" | "11" => "Received",
+        "12" => "Being prepared for delivery",
+        "101" => "On its way to the recipient",
+        "102" | "103" | "108" => "Refused by the recipient",
+        "104" => "Address changed",
+        "105" => "Storage stopped",
+        "106" => "Received, and a return shipment was created",
+        "111" => "Delivery attempt failed",
+        "112" => "Delivery date postponed by the recipient",
+        _ => "Status unknown",
+    }
+}
+
+/// Parse a Kyiv time in the given format. The API uses a different format for
+/// almost every field.
+fn local_time(text: &str, format: &str) -> Result<UtcTime> {
+    let time = NaiveDateTime::parse_from_str(text, format)?;
+    Kyiv.from_local_datetime(&time)
+        .earliest()
+        .map(|t| t.to_utc())
+        .ok_or(format!("Invalid local time {time}").into())
+}
+
+/// Like `local_time`, for fields that are empty when they don't apply
+fn optional_time(text: &str, format: &str) -> Result<Option<UtcTime>> {
+    match text.is_empty() {
+        true => Ok(None),
+        false => local_time(text, format).map(Some),
+    }
+}
+
+#[derive(Deserialize)]
+struct StatusResponse {
+    success: bool,
+    #[serde(default)]
+    data:    Vec<Document>,
+    #[serde(default)]
+    errors:  Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Document {
+    number:                  String,
+    /// e.g. "7" (arrived at the branch)
+    status_code:             String,
+    /// "13-10-2026 10:22:15"
+    date_created:            String,
+    /// "15-10-2026 13:00:00"
+    #[serde(default)]
+    scheduled_delivery_date: String,
+    /// "2026-10-15 08:35:12"
+    #[serde(default)]
+    tracking_update_date:    String,
+    /// "2026-10-15 12:11:00"
+    #[serde(default)]
+    actual_delivery_date:    String,
+    #[serde(default)]
+    city_sender:             String,
+    #[serde(default)]
+    city_recipient:          String,
+    /// e.g. "Відділення №12 (до 30 кг): вул. Шевченка, 5"
+    #[serde(default)]
+    warehouse_recipient:     String,
+    /// Only with the phone number
+    #[serde(default)]
+    recipient_full_name:     String,
+}
+impl Document {
+    fn to_package(&self) -> Result<Package> {
+        const FORMAT: &str = "%d-%m-%Y %H:%M:%S";
+        const ISO_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+        let delivered = match self.status_code.as_str() {
+            "9" | "10" | "11" | "106" => {
+                optional_time(&self.actual_delivery_date, ISO_FORMAT)?
+            }
+            _ => None,
+        };
+        let mut events = vec![Event::new(
+            local_time(&self.date_created, FORMAT)?,
+            with_place("Shipment created", &self.city_sender),
+        )];
+        if self.status_code != "1" {
+            let place = match self.status_code.as_str() {
+                "4" | "41" => &self.city_sender,
+                _ => &self.city_recipient,
+            };
+            let updated =
+                optional_time(&self.tracking_update_date, ISO_FORMAT)?;
+            if let Some(timestamp) = delivered.or(updated) {
+                events.push(Event::new(
+                    timestamp,
+                    with_place(describe(&self.status_code), place),
+                ));
+            }
+        }
+        let pickup = match self.status_code.as_str() {
+            "7" | "8" if !self.warehouse_recipient.is_empty() => {
+                Some(PickupPoint {
+                    location: with_place(
+                        &self.warehouse_recipient,
+                        &self.city_recipient,
+                    ),
+                    // Storage only starts costing money after a while; the
+                    // API doesn't say when the shipment goes back
+                    deadline: None,
+                })
+            }
+            _ => None,
+        };
+        let status = match (&delivered, &pickup) {
+            (Some(_), _) => PackageStatus::Delivered,
+            (None, Some(pickup)) => PackageStatus::ReadyForPickup {
+                location: pickup.location.clone(),
+            },
+            (None, None) => PackageStatus::InTransit,
+        };
+        let eta = match delivered {
+            Some(_) => None,
+            None => optional_time(&self.scheduled_delivery_date, FORMAT)?,
+        };
+        Ok(Package {
+            barcode: self.number.clone(),
+            channel: "Nova Poshta".into(),
+            status,
+            sender: None,
+            recipient: Some(self.recipient_full_name.clone())
+                .filter(|name| !name.is_empty()),
+            eta,
+            eta_window: None,
+            delivered,
+            events,
+            pickup,
+        })
+    }
+}
+
+/// e.g. "Arrived at the branch (Львів)"
+fn with_place(text: &str, place: &str) -> String {
+    match place.is_empty() {
+        true => text.to_owned(),
+        false => format!("{text} ({place})"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks;
+    use crate::test_utils::utc;
+
+    const BRANCH: &str = "Відділення №12 (до 30 кг): вул. Шевченка, 5 (Львів)";
+
+    #[test]
+    fn test_get_barcode() -> Result<()> {
+        assert_eq!(
+            get_barcode(
+                "https://novaposhta.ua/tracking/?cargo_number=20450912345678"
+            )?,
+            "20450912345678"
+        );
+        assert_eq!(
+            get_barcode(
+                "https://tracking.novaposhta.ua/#/uk/search/20450912345678"
+            )?,
+            "20450912345678"
+        );
+        assert!(get_barcode("https://novaposhta.ua/tracking/").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_pickup() -> Result<()> {
+        let mock = mocks::load_text("novaposhta_pickup.json")?;
+        let package = NovaPoshtaTracker.parse(mock)?;
+        assert_eq!(package.barcode, "20450912345678");
+        assert_eq!(package.channel, "Nova Poshta");
+        assert_eq!(
+            package.status,
+            PackageStatus::ReadyForPickup {
+                location: BRANCH.into(),
+            }
+        );
+        assert_eq!(
+            package.pickup,
+            Some(PickupPoint {
+                location: BRANCH.into(),
+                deadline: None,
+            })
+        );
+        assert_eq!(package.recipient, None);
+        assert_eq!(package.eta, Some(utc("2026-10-15T10:00:00Z")));
+        assert_eq!(package.events.len(), 2);
+        assert_eq!(package.events[0].text, "Shipment created (Київ)");
+        assert_eq!(package.events[0].timestamp, utc("2026-10-13T07:22:15Z"));
+        assert_eq!(package.events[1].text, "Arrived at the branch (Львів)");
+        assert_eq!(package.events[1].timestamp, utc("2026-10-15T05:35:12Z"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_in_transit() -> Result<()> {
+        let mock = mocks::load_text("novaposhta_pickup.json")?
+            .replace(r#""StatusCode": "7""#, r#""StatusCode": "5""#);
+        let package = NovaPoshtaTracker.parse(mock)?;
+        assert_eq!(package.status, PackageStatus::InTransit);
+        assert_eq!(package.pickup, None);
+        assert_eq!(
+            package.events[1].text,
+            "On its way to the recipient's city (Львів)"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_delivered() -> Result<()> {
+        let mock = mocks::load_text("novaposhta_delivered.json")?;
+        let package = NovaPoshtaTracker.parse(mock)?;
+        assert_eq!(package.status, PackageStatus::Delivered);
+        assert_eq!(package.delivered, Some(utc("2026-10-15T09:11:00Z")));
+        assert_eq!(package.eta, None);
+        assert_eq!(package.pickup, None);
+        assert_eq!(package.recipient, Some("Петренко Олена".into()));
+        assert_eq!(package.events[1].text, "Received (Львів)");
+        assert_eq!(package.events[1].timestamp, utc("2026-10-15T09:11:00Z"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_not_found() {
+        let text = r#"{"success": true, "data": [{"Number": "20450912345678",
+            "StatusCode": "3", "Status": "Номер не знайдено",
+            "DateCreated": ""}], "errors": []}"#;
+        let err = NovaPoshtaTracker
+            .parse(text.into())
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Nova Poshta doesn't know this shipment")
+        );
+    }
+}
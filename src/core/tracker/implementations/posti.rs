@@ -0,0 +1,383 @@
+// Posti (Finland). Consumer URLs look like:
+// https://www.posti.fi/fi/seuranta#/lahetys/JJFI64123450012345678
+// https://www.posti.fi/en/tracking#/shipment/JJFI64123450012345678
+
+// API url (what the tracking page itself uses):
+// https://www.posti.fi/henkiloasiakkaat/seuranta/api/shipments/JJFI64123450012345678
+
+// The API returns every text in Finnish, Swedish and English at once, so
+// `get_raw` adds the language it was asked for to the response, for `parse`
+// to pick.
+
+use crate::Result;
+use crate::tracker::{
+    Event, Package, PackageStatus, PickupPoint, RawResponse, Tracker,
+    TrackerContext,
+};
+use crate::utils::UtcTime;
+use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Europe::Helsinki;
+use regex::Regex;
+use serde::Deserialize;
+
+pub struct PostiTracker;
+
+#[async_trait]
+impl Tracker for PostiTracker {
+    fn name(&self) -> &str {
+        "posti"
+    }
+    fn can_handle(&self, url: &str) -> bool {
+        url.contains("posti.fi")
+    }
+    fn barcode_patterns(&self) -> &[&str] {
+        &["JJFI[0-9]{13,20}", "[A-Z]{2}[0-9]{9}FI"]
+    }
+    fn barcode_url(&self, barcode: &str) -> Option<String> {
+        Some(format!(
+            "https://www.posti.fi/fi/seuranta#/lahetys/{barcode}"
+        ))
+    }
+    fn languages(&self) -> &[&str] {
+        &["fi", "sv", "en"]
+    }
+    fn probe_url(&self) -> Option<String> {
+        self.barcode_url("JJFI00000000000000000")
+    }
+    fn check_probe(&self, raw: &RawResponse) -> Result<()> {
+        raw.expect_json()
+    }
+    async fn get_raw(
+        &self,
+        url: &str,
+        ctx: &TrackerContext,
+    ) -> Result<RawResponse> {
+        let barcode = get_barcode(url)?;
+        let api_url = format!(
+            "https://www.posti.fi/henkiloasiakkaat/seuranta/api/shipments/{barcode}"
+        );
+        let response = reqwest::get(ctx.api_url(&api_url)).await?;
+        let mut raw = RawResponse::from_response(response).await?;
+        let language = self
+            .choose_language(&[ctx.language])
+            .unwrap_or("fi");
+        if let Ok(serde_json::Value::Object(mut object)) =
+            serde_json::from_str(&raw.body)
+        {
+            object.insert("language".into(), language.into());
+            raw.body = serde_json::to_string(&object)?;
+        }
+        Ok(raw)
+    }
+    fn parse(&self, text: String) -> Result<Package> {
+        let response: ShipmentsResponse = serde_json::from_str(&text)
+            .map_err(|err| format!("Error parsing Posti response: {err}"))?;
+        let shipment = response
+            .shipments
+            .into_iter()
+            .next()
+            .ok_or("Posti doesn't know this shipment")?;
+        shipment.to_package(&response.language)
+    }
+}
+
+fn get_barcode(url: &str) -> Result<String> {
+    Regex::new(r"(?:lahetys|shipment|forsandelse)/([0-9A-Za-z]+)")?
+        .captures(url)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_uppercase())
+        .ok_or(format!("Couldn't get barcode from {url}").into())
+}
+
+fn to_utc(time: &DateTime<FixedOffset>) -> UtcTime {
+    time.with_timezone(&Utc)
+}
+
+#[derive(Deserialize)]
+struct ShipmentsResponse {
+    #[serde(default)]
+    shipments: Vec<Shipment>,
+    /// Added by `get_raw`
+    #[serde(default = "default_language")]
+    language:  String,
+}
+
+fn default_language() -> String {
+    "fi".into()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Shipment {
+    tracking_code:           String,
+    /// e.g. "IN_TRANSPORT", "READY_FOR_PICKUP" or "DELIVERED"
+    phase:                   String,
+    estimated_delivery_time: Option<DateTime<FixedOffset>>,
+    /// The last day the shipment can be picked up, e.g. "2026-10-22"
+    last_pickup_date:        Option<NaiveDate>,
+    sender_name:             Option<String>,
+    destination_point:       Option<DestinationPoint>,
+    /// Newest first
+    #[serde(default)]
+    events:                  Vec<ShipmentEvent>,
+}
+impl Shipment {
+    fn to_package(&self, language: &str) -> Result<Package> {
+        let events: Vec<Event> = self
+            .events
+            .iter()
+            .map(|e| e.to_event(language))
+            .collect();
+        let delivered = match self.phase.as_str() {
+            "DELIVERED" => events.first().map(|e| e.timestamp),
+            _ => None,
+        };
+        let pickup = match (self.phase.as_str(), &self.destination_point) {
+            ("READY_FOR_PICKUP", Some(point)) => Some(PickupPoint {
+                location: point.describe(language),
+                deadline: self
+                    .last_pickup_date
+                    .map(end_of_day)
+                    .transpose()?,
+            }),
+            _ => None,
+        };
+        let status = match (&delivered, &pickup) {
+            (Some(_), _) => PackageStatus::Delivered,
+            (None, Some(pickup)) => PackageStatus::ReadyForPickup {
+                location: pickup.location.clone(),
+            },
+            (None, None) => PackageStatus::InTransit,
+        };
+        Ok(Package {
+            barcode: self.tracking_code.clone(),
+            channel: "Posti".into(),
+            status,
+            sender: self.sender_name.clone(),
+            recipient: None,
+            eta: match delivered {
+                Some(_) => None,
+                None => self
+                    .estimated_delivery_time
+                    .as_ref()
+                    .map(to_utc),
+            },
+            eta_window: None,
+            delivered,
+            events,
+            pickup,
+        })
+    }
+}
+
+/// Pickup points keep shipments until the end of the last pickup day
+fn end_of_day(date: NaiveDate) -> Result<UtcTime> {
+    let time = date.and_time(NaiveTime::from_hms_opt(23, 59, 59).unwrap());
+    Helsinki
+        .from_local_datetime(&time)
+        .earliest()
+        .map(|t| t.to_utc())
+        .ok_or(format!("Invalid local time {time}").into())
+}
+
+/// A text in Finnish, Swedish and English
+#[derive(Deserialize)]
+struct Translated {
+    fi: Option<String>,
+    sv: Option<String>,
+    en: Option<String>,
+}
+impl Translated {
+    /// The text in the given language, or else in whichever we have
+    fn get(&self, language: &str) -> String {
+        let wanted = match language {
+            "sv" => &self.sv,
+            "en" => &self.en,
+            _ => &self.fi,
+        };
+        wanted
+            .iter()
+            .chain(
+                [&self.fi, &self.en, &self.sv]
+                    .into_iter()
+                    .flatten(),
+            )
+            .next()
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// The pickup point, or the recipient's address
+#[derive(Deserialize)]
+struct DestinationPoint {
+    name:     Option<Translated>,
+    street:   Option<String>,
+    postcode: Option<String>,
+    city:     Option<String>,
+}
+impl DestinationPoint {
+    /// e.g. "K-Market Kamppi, Urho Kekkosen katu 1, 00100 HELSINKI"
+    fn describe(&self, language: &str) -> String {
+        let place = [&self.postcode, &self.city]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(" ");
+        [
+            self.name
+                .as_ref()
+                .map(|name| name.get(language)),
+            self.street.clone(),
+            Some(place),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(", ")
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ShipmentEvent {
+    timestamp:     DateTime<FixedOffset>,
+    description:   Translated,
+    location_name: Option<String>,
+}
+impl ShipmentEvent {
+    fn to_event(&self, language: &str) -> Event {
+        let description = self.description.get(language);
+        let text = match &self.location_name {
+            Some(location) if !location.is_empty() => {
+                format!("{description} ({location})")
+            }
+            _ => description,
+        };
+        Event::new(to_utc(&self.timestamp), text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks;
+    use crate::test_utils::utc;
+
+    /// Like the responses `get_raw` gives
+    fn load(name: &str, language: &str) -> Result<String> {
+        let text = mocks::load_text(name)?;
+        Ok(text.replacen('{', &format!("{{\"language\": \"{language}\","), 1))
+    }
+
+    const LOCATION: &str =
+        "K-Market Kamppi, Urho Kekkosen katu 1, 00100 HELSINKI";
+
+    #[test]
+    fn test_get_barcode() -> Result<()> {
+        assert_eq!(
+            get_barcode(
+                "https://www.posti.fi/fi/seuranta#/lahetys/JJFI64123450012345678"
+            )?,
+            "JJFI64123450012345678"
+        );
+        assert_eq!(
+            get_barcode(
+                "https://www.posti.fi/en/tracking#/shipment/jjfi64123450012345678"
+            )?,
+            "JJFI64123450012345678"
+        );
+        assert!(get_barcode("https://www.posti.fi/fi/seuranta").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_undelivered() -> Result<()> {
+        let package =
+            PostiTracker.parse(load("posti_undelivered.json", "en")?)?;
+        assert_eq!(package.barcode, "JJFI64123450012345678");
+        assert_eq!(package.channel, "Posti");
+        assert_eq!(package.status, PackageStatus::InTransit);
+        assert_eq!(package.sender, Some("Verkkokauppa Oy".into()));
+        assert_eq!(package.eta, Some(utc("2026-10-15T09:00:00Z")));
+        assert_eq!(package.pickup, None);
+        assert_eq!(package.events.len(), 3);
+        assert_eq!(
+            package.events[0].text,
+            "The shipment has been sorted (VANTAA)"
+        );
+        assert_eq!(package.events[0].timestamp, utc("2026-10-15T00:12:00Z"));
+        // No location
+        assert_eq!(package.events[2].text, "Shipment information received");
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_pickup() -> Result<()> {
+        let package = PostiTracker.parse(load("posti_pickup.json", "en")?)?;
+        assert_eq!(
+            package.status,
+            PackageStatus::ReadyForPickup {
+                location: LOCATION.into(),
+            }
+        );
+        assert_eq!(
+            package.pickup,
+            Some(PickupPoint {
+                location: LOCATION.into(),
+                deadline: Some(utc("2026-10-22T20:59:59Z")),
+            })
+        );
+        assert_eq!(
+            package.events[0].text,
+            "The shipment can be picked up (HELSINKI)"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_delivered() -> Result<()> {
+        let package =
+            PostiTracker.parse(load("posti_delivered.json", "en")?)?;
+        assert_eq!(package.status, PackageStatus::Delivered);
+        assert_eq!(package.delivered, Some(utc("2026-10-16T14:20:00Z")));
+        assert_eq!(package.pickup, None);
+        assert_eq!(package.eta, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_language() -> Result<()> {
+        let package = PostiTracker.parse(load("posti_pickup.json", "sv")?)?;
+        assert_eq!(
+            package.events[0].text,
+            "Försändelsen kan avhämtas (HELSINKI)"
+        );
+        assert_eq!(
+            package.pickup.unwrap().location,
+            "K-Market Kampen, Urho Kekkosen katu 1, 00100 HELSINKI"
+        );
+        // A response without the language falls back to Finnish
+        let package =
+            PostiTracker.parse(mocks::load_text("posti_pickup.json")?)?;
+        assert_eq!(
+            package.events[0].text,
+            "Lähetys on noudettavissa (HELSINKI)"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_not_found() {
+        let err = PostiTracker
+            .parse(r#"{"shipments": []}"#.into())
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Posti doesn't know this shipment")
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use crate::Result;
 use crate::tracker::PackageStatus;
+use crate::tracker::RawResponse;
 use crate::tracker::Tracker;
 use crate::tracker::TrackerContext;
 use crate::tracker::{Event, Package, TimeWindow};
@@ -12,22 +13,98 @@ pub struct PostNLTracker;
 
 #[async_trait]
 impl Tracker for PostNLTracker {
+    fn name(&self) -> &str {
+        "postnl"
+    }
     fn can_handle(&self, url: &str) -> bool {
         url.contains("postnl")
     }
-    async fn get_raw(&self, url: &str, ctx: &TrackerContext) -> Result<String> {
+    fn barcode_patterns(&self) -> &[&str] {
+        // 3S codes for domestic parcels, S10 codes for international ones
+        &["3S[A-Z]{4}[0-9]{6,9}", "[A-Z]{2}[0-9]{9}NL"]
+    }
+    fn barcode_url(&self, barcode: &str) -> Option<String> {
+        Some(format!("https://jouw.postnl.nl/track-and-trace/{barcode}"))
+    }
+    fn languages(&self) -> &[&str] {
+        &["nl", "en"]
+    }
+    fn probe_url(&self) -> Option<String> {
+        Some("https://jouw.postnl.nl/track-and-trace/3SXXXX0000000".into())
+    }
+    fn check_probe(&self, raw: &RawResponse) -> Result<()> {
+        raw.expect_json()
+    }
+    async fn get_raw(
+        &self,
+        url: &str,
+        ctx: &TrackerContext,
+    ) -> Result<RawResponse> {
         let (barcode, country, url_postcode) = get_barcode_and_postcode(url);
-        let url = build_url(
+        let urls = candidate_urls(
             barcode.ok_or(format!("Couldn't get barcode from {url}"))?,
             country,
             url_postcode.or(ctx.recipient_postcode),
             ctx.language,
         );
-        let response = reqwest::get(url)
-            .await?
-            .error_for_status()?;
-        let text = response.text().await?;
-        Ok(text)
+        // Try each URL until one works. If none do, report the last failure.
+        let mut last_result = Err(format!("No PostNL URLs for {url}").into());
+        for url in urls {
+            let result = match reqwest::get(ctx.api_url(&url)).await {
+                Ok(response) => RawResponse::from_response(response).await,
+                Err(err) => Err(err.into()),
+            };
+            match &result {
+                Ok(raw) if raw.is_success() => return result,
+                Ok(raw) => log::debug!("HTTP {} from {url}", raw.status),
+                Err(err) => log::debug!("Request to {url} failed: {err}"),
+            }
+            last_result = result;
+        }
+        last_result
+    }
+
+    fn batch_size(&self) -> usize {
+        BATCH_SIZE
+    }
+
+    async fn get_raw_batch(
+        &self,
+        urls: &[&str],
+        ctx: &TrackerContext,
+    ) -> Result<Vec<RawResponse>> {
+        let mut barcodes = vec![];
+        let mut ids = vec![];
+        for url in urls {
+            let (barcode, country, url_postcode) =
+                get_barcode_and_postcode(url);
+            let barcode =
+                barcode.ok_or(format!("Couldn't get barcode from {url}"))?;
+            barcodes.push(barcode);
+            ids.push(package_id(
+                barcode,
+                country,
+                url_postcode.or(ctx.recipient_postcode),
+            ));
+        }
+        let ids = ids.join(",");
+        let mut last_error = format!("No PostNL URLs for {ids}");
+        for endpoint in ENDPOINTS {
+            let url = format!("{endpoint}/{ids}?language={}", ctx.language);
+            let raw = match reqwest::get(ctx.api_url(&url)).await {
+                Ok(response) => RawResponse::from_response(response).await?,
+                Err(err) => {
+                    last_error = format!("Request to {url} failed: {err}");
+                    continue;
+                }
+            };
+            if !raw.is_success() {
+                last_error = format!("HTTP {} from {url}", raw.status);
+                continue;
+            }
+            return split_batch(&raw, &barcodes);
+        }
+        Err(last_error.into())
     }
 
     fn parse(&self, text: String) -> Result<Package> {
@@ -44,6 +121,7 @@ impl Tracker for PostNLTracker {
             eta_window: package.eta_window(),
             delivered:  package.delivery_datetime(),
             events:     package.events(),
+            pickup:     None,
         })
     }
 }
@@ -78,27 +156,96 @@ fn get_barcode_and_postcode(
     );
     (barcode, country, postcode)
 }
+/// The API endpoints to try, in order of preference. jouw.postnl.nl
+/// occasionally rejects requests that the main postnl.nl site still accepts.
+const ENDPOINTS: &[&str] = &[
+    "https://jouw.postnl.nl/track-and-trace/api/trackAndTrace",
+    "https://www.postnl.nl/track-and-trace/api/trackAndTrace",
+];
+
+/// All the API URLs to try for a barcode, in order of preference. The full
+/// barcode (with country and postcode) gives the most data, so we try that
+/// on every endpoint first, before falling back to the bare barcode.
+fn candidate_urls(
+    barcode: &str,
+    country: Option<&str>,
+    postcode: Option<&str>,
+    language: &str,
+) -> Vec<String> {
+    let mut formats = vec![(country, postcode)];
+    if country.zip(postcode).is_some() {
+        formats.push((None, None));
+    }
+    formats
+        .into_iter()
+        .flat_map(|(country, postcode)| {
+            ENDPOINTS.iter().map(move |endpoint| {
+                build_url(endpoint, barcode, country, postcode, language)
+            })
+        })
+        .collect()
+}
 fn build_url(
+    endpoint: &str,
     barcode: &str,
     country: Option<&str>,
     postcode: Option<&str>,
     language: &str,
 ) -> String {
-    let mut barcode = barcode.to_string();
-
-    // Only append the country and postcode if both are present
-    if let Some((c, p)) = country.zip(postcode) {
-        barcode.push_str(&format!("-{c}-{p}"));
-    }
-    let url = format!(
-        "https://jouw.postnl.nl/track-and-trace/api/trackAndTrace/{barcode}?language={language}"
-    );
+    let barcode = package_id(barcode, country, postcode);
+    let url = format!("{endpoint}/{barcode}?language={language}");
     log::debug!(
         "Built URL {url} using barcode {barcode:?}, country {country:?}, postcode {postcode:?}"
     );
     url
 }
 
+/// The barcode, with the country and postcode if both are present, e.g.
+/// "3SABCD1234567-NL-1234AB"
+fn package_id(
+    barcode: &str,
+    country: Option<&str>,
+    postcode: Option<&str>,
+) -> String {
+    match country.zip(postcode) {
+        Some((c, p)) => format!("{barcode}-{c}-{p}"),
+        None => barcode.to_string(),
+    }
+}
+
+/// How many barcodes to request at once. The API accepts a comma separated
+/// list; this keeps the URL at a sensible length.
+const BATCH_SIZE: usize = 20;
+
+/// Split the response for several barcodes into a response per barcode,
+/// in the same shape as the response for a single barcode. Barcodes missing
+/// from the response get a 404.
+fn split_batch(
+    raw: &RawResponse,
+    barcodes: &[&str],
+) -> Result<Vec<RawResponse>> {
+    let value: Value = serde_json::from_str(&raw.body)?;
+    let colli = value
+        .get("colli")
+        .and_then(|colli| colli.as_object())
+        .ok_or("No packages in payload!")?;
+    Ok(barcodes
+        .iter()
+        .map(|barcode| match colli.get(*barcode) {
+            Some(package) => RawResponse {
+                body: serde_json::json!({ "colli": { *barcode: package } })
+                    .to_string(),
+                ..raw.clone()
+            },
+            None => RawResponse {
+                body:         String::new(),
+                content_type: None,
+                status:       404,
+            },
+        })
+        .collect())
+}
+
 #[derive(Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 struct PostNLPackage {
@@ -127,7 +274,7 @@ impl PostNLPackage {
         Some(format!("{street} {number}"))
     }
     fn status(&self) -> PackageStatus {
-        if let Some(_) = self.delivery_datetime() {
+        if self.delivery_datetime().is_some() {
             if let Some(address) = self.get_neighbour_address() {
                 return PackageStatus::DeliveredToNeighbour { address };
             }
@@ -206,7 +353,7 @@ impl PostNLPackage {
         }
     }
 }
-fn get_last_event_datetime(events: &Vec<Event>) -> Option<UtcTime> {
+fn get_last_event_datetime(events: &[Event]) -> Option<UtcTime> {
     events
         .iter()
         .max_by(|a, b| a.timestamp.cmp(&b.timestamp))
@@ -286,10 +433,7 @@ struct PostNLEvent {
 }
 impl PostNLEvent {
     fn to_event(&self) -> Event {
-        Event {
-            timestamp: self.observation_date,
-            text:      self.description.clone(),
-        }
+        Event::new(self.observation_date, self.description.clone())
     }
 }
 
@@ -568,31 +712,144 @@ mod tests {
     fn test_build_url() {
         // bare minimum
         assert_eq!(
-            build_url("1ABCDE1234567", None, None, "en"),
+            build_url(ENDPOINTS[0], "1ABCDE1234567", None, None, "en"),
             "https://jouw.postnl.nl/track-and-trace/api/trackAndTrace/1ABCDE1234567?language=en"
         );
 
         // both the country and postcode should be present for them to be added.
         assert_eq!(
-            build_url("1ABCDE1234567", None, Some("1234AB"), "en"),
+            build_url(
+                ENDPOINTS[0],
+                "1ABCDE1234567",
+                None,
+                Some("1234AB"),
+                "en"
+            ),
             "https://jouw.postnl.nl/track-and-trace/api/trackAndTrace/1ABCDE1234567?language=en"
         );
         assert_eq!(
-            build_url("1ABCDE1234567", Some("NL"), None, "en"),
+            build_url(ENDPOINTS[0], "1ABCDE1234567", Some("NL"), None, "en"),
             "https://jouw.postnl.nl/track-and-trace/api/trackAndTrace/1ABCDE1234567?language=en"
         );
 
         // fully populated
         assert_eq!(
-            build_url("1ABCDE1234567", Some("NL"), Some("1234AB"), "nl"),
+            build_url(
+                ENDPOINTS[0],
+                "1ABCDE1234567",
+                Some("NL"),
+                Some("1234AB"),
+                "nl"
+            ),
             "https://jouw.postnl.nl/track-and-trace/api/trackAndTrace/1ABCDE1234567-NL-1234AB?language=nl"
         );
     }
 
+    #[test]
+    fn test_candidate_urls() {
+        assert_eq!(
+            candidate_urls("1ABCDE1234567", Some("NL"), Some("1234AB"), "nl"),
+            vec![
+                "https://jouw.postnl.nl/track-and-trace/api/trackAndTrace/1ABCDE1234567-NL-1234AB?language=nl",
+                "https://www.postnl.nl/track-and-trace/api/trackAndTrace/1ABCDE1234567-NL-1234AB?language=nl",
+                "https://jouw.postnl.nl/track-and-trace/api/trackAndTrace/1ABCDE1234567?language=nl",
+                "https://www.postnl.nl/track-and-trace/api/trackAndTrace/1ABCDE1234567?language=nl",
+            ]
+        );
+        assert_eq!(candidate_urls("1ABCDE1234567", None, None, "en").len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_get_raw_falls_back() -> Result<()> {
+        use crate::tracker::Credentials;
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        // Only the bare barcode works
+        let server = MockServer::start().await;
+        Mock::given(path("/track-and-trace/api/trackAndTrace/3SABCD1234567"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+            .mount(&server)
+            .await;
+        let origin = server.uri();
+        let ctx = TrackerContext {
+            recipient_postcode: None,
+            language:           "en",
+            browser_path:       None,
+            api_origin:         Some(&origin),
+            credentials:        &Credentials::default(),
+        };
+        let url =
+            "https://jouw.postnl.nl/track-and-trace/3SABCD1234567-NL-1234AB";
+        let raw = PostNLTracker.get_raw(url, &ctx).await?;
+        assert!(raw.is_success());
+
+        // Nothing works: the last response is returned
+        let url = "https://jouw.postnl.nl/track-and-trace/3SXXXX1234567";
+        let raw = PostNLTracker.get_raw(url, &ctx).await?;
+        assert_eq!(raw.status, 404);
+        Ok(())
+    }
+
     #[test]
     fn test_can_handle() {
         let tracker = PostNLTracker;
-        assert_eq!(tracker.can_handle("xxx"), false);
-        assert_eq!(tracker.can_handle("jouw.postnl.com/..."), true);
+        assert!(!tracker.can_handle("xxx"));
+        assert!(tracker.can_handle("jouw.postnl.com/..."));
+    }
+
+    #[tokio::test]
+    async fn test_get_raw_batch() -> Result<()> {
+        use crate::tracker::Credentials;
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        // One package from each mock, under its own barcode
+        let mut colli = serde_json::Map::new();
+        for (barcode, mock) in [
+            ("3SAAAA1111111", "postnl_delivered.json"),
+            ("3SBBBB2222222", "postnl_undelivered.json"),
+        ] {
+            let value: Value = serde_json::from_str(&mocks::load_text(mock)?)?;
+            let mut package = get_first_package(value)?;
+            package["barcode"] = barcode.into();
+            colli.insert(barcode.into(), package);
+        }
+        let body = serde_json::json!({ "colli": colli }).to_string();
+        let server = MockServer::start().await;
+        Mock::given(path(
+            "/track-and-trace/api/trackAndTrace/\
+             3SAAAA1111111,3SBBBB2222222-NL-1234AB,3SCCCC3333333",
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_string(body))
+        .expect(1)
+        .mount(&server)
+        .await;
+        let origin = server.uri();
+        let ctx = TrackerContext {
+            recipient_postcode: None,
+            language:           "en",
+            browser_path:       None,
+            api_origin:         Some(&origin),
+            credentials:        &Credentials::default(),
+        };
+        let urls = [
+            "https://jouw.postnl.nl/track-and-trace/3SAAAA1111111",
+            "https://jouw.postnl.nl/track-and-trace/3SBBBB2222222-NL-1234AB",
+            "https://jouw.postnl.nl/track-and-trace/3SCCCC3333333",
+        ];
+        let responses = PostNLTracker
+            .get_raw_batch(&urls, &ctx)
+            .await?;
+        assert_eq!(responses.len(), 3);
+        let first = PostNLTracker.parse(responses[0].body.clone())?;
+        assert_eq!(first.barcode, "3SAAAA1111111");
+        assert_eq!(first.status, PackageStatus::Delivered);
+        let second = PostNLTracker.parse(responses[1].body.clone())?;
+        assert_eq!(second.barcode, "3SBBBB2222222");
+        assert_eq!(second.status, PackageStatus::InTransit);
+        // Not in the response
+        assert_eq!(responses[2].status, 404);
+        Ok(())
     }
 }
@@ -0,0 +1,271 @@
+// Consumer URLs look like:
+// https://www.royalmail.com/track-your-item#/tracking-results/AB123456785GB
+
+// API url:
+// https://api.royalmail.net/mailpieces/v2/AB123456785GB/events
+// The API needs a client ID and secret, which you can get by registering an
+// app on the Royal Mail developer portal.
+
+use crate::Result;
+use crate::tracker::{
+    Event, Package, PackageStatus, RawResponse, TimeWindow, Tracker,
+    TrackerContext,
+};
+use crate::utils::UtcTime;
+use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+use regex::Regex;
+use serde::Deserialize;
+
+pub struct RoyalMailTracker;
+
+#[async_trait]
+impl Tracker for RoyalMailTracker {
+    fn name(&self) -> &str {
+        "royalmail"
+    }
+    fn can_handle(&self, url: &str) -> bool {
+        url.contains("royalmail.com")
+    }
+    fn barcode_patterns(&self) -> &[&str] {
+        // International tracking numbers end in the country code
+        &["[A-Z]{2}[0-9]{9}GB"]
+    }
+    fn barcode_url(&self, barcode: &str) -> Option<String> {
+        Some(format!(
+            "https://www.royalmail.com/track-your-item#/tracking-results/{barcode}"
+        ))
+    }
+    async fn get_raw(
+        &self,
+        url: &str,
+        ctx: &TrackerContext,
+    ) -> Result<RawResponse> {
+        let barcode = get_barcode(url)?;
+        let client_id = ctx
+            .credentials
+            .require("royalmail", "client_id")?;
+        let client_secret = ctx
+            .credentials
+            .require("royalmail", "client_secret")?;
+        let api_url =
+            format!("https://api.royalmail.net/mailpieces/v2/{barcode}/events");
+        let response = reqwest::Client::new()
+            .get(ctx.api_url(&api_url))
+            .header("X-IBM-Client-Id", client_id)
+            .header("X-IBM-Client-Secret", client_secret)
+            .header("X-Accept-RMG-Terms", "yes")
+            .send()
+            .await?;
+        RawResponse::from_response(response).await
+    }
+    fn parse(&self, text: String) -> Result<Package> {
+        let response: RoyalMailResponse =
+            serde_json::from_str(&text).map_err(|err| {
+                format!("Error parsing Royal Mail response: {err}")
+            })?;
+        response.mail_pieces.to_package()
+    }
+}
+
+fn get_barcode(url: &str) -> Result<String> {
+    Regex::new(r"tracking-results/([0-9A-Za-z]+)")?
+        .captures(url)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_uppercase())
+        .ok_or(format!("Couldn't get barcode from {url}").into())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RoyalMailResponse {
+    mail_pieces: MailPiece,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MailPiece {
+    mail_piece_id:      String,
+    summary:            Summary,
+    estimated_delivery: Option<EstimatedDelivery>,
+    #[serde(default)]
+    events:             Vec<RoyalMailEvent>,
+}
+impl MailPiece {
+    fn to_package(&self) -> Result<Package> {
+        let events = self
+            .events
+            .iter()
+            .map(|e| e.to_event())
+            .collect();
+        let delivered = self
+            .events
+            .iter()
+            .find(|e| e.is_delivery())
+            .map(|e| e.event_date_time.with_timezone(&Utc));
+        let status = match &delivered {
+            Some(_) => self.delivered_status(),
+            None => PackageStatus::InTransit,
+        };
+        let eta_window = match &delivered {
+            Some(_) => None,
+            None => self
+                .estimated_delivery
+                .as_ref()
+                .map(|eta| eta.to_time_window())
+                .transpose()?,
+        };
+        Ok(Package {
+            barcode: self.mail_piece_id.clone(),
+            channel: "Royal Mail".into(),
+            status,
+            sender: None,
+            recipient: None,
+            eta: eta_window.as_ref().map(|w| w.start),
+            eta_window,
+            delivered,
+            events,
+            pickup: None,
+        })
+    }
+
+    fn delivered_status(&self) -> PackageStatus {
+        let delivered_to_neighbour = self
+            .events
+            .iter()
+            .any(|e| e.event_name.contains("neighbour"));
+        if !delivered_to_neighbour {
+            return PackageStatus::Delivered;
+        }
+        // The address is only mentioned in the summary, e.g. "Your item was
+        // delivered to your neighbour at 14 Acacia Avenue on 15-10-2026."
+        let address = Regex::new(r"neighbour at (.+?)(?: on [0-9]|\.?$)")
+            .ok()
+            .and_then(|rx| rx.captures(&self.summary.summary_line))
+            .and_then(|caps| caps.get(1))
+            .map(|m| m.as_str().to_owned())
+            .unwrap_or("an unknown address".into());
+        PackageStatus::DeliveredToNeighbour { address }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Summary {
+    #[serde(default)]
+    summary_line: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EstimatedDelivery {
+    /// e.g. "2026-10-15"
+    date:                      NaiveDate,
+    /// e.g. "10:15:00+01:00"
+    start_of_estimated_window: String,
+    end_of_estimated_window:   String,
+}
+impl EstimatedDelivery {
+    fn to_time_window(&self) -> Result<TimeWindow> {
+        let time = |text: &str| -> Result<UtcTime> {
+            let time =
+                DateTime::parse_from_rfc3339(&format!("{}T{text}", self.date))?;
+            Ok(time.with_timezone(&Utc))
+        };
+        Ok(TimeWindow {
+            start: time(&self.start_of_estimated_window)?,
+            end:   time(&self.end_of_estimated_window)?,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RoyalMailEvent {
+    /// e.g. "EVKSF"
+    event_code:      String,
+    /// e.g. "Delivered to safeplace"
+    event_name:      String,
+    event_date_time: DateTime<FixedOffset>,
+    location_name:   Option<String>,
+}
+impl RoyalMailEvent {
+    /// Delivery events have codes starting with "EVK", e.g. "EVKSP"
+    /// (signature), "EVKSF" (safeplace) or "EVKNB" (neighbour)
+    fn is_delivery(&self) -> bool {
+        self.event_code.starts_with("EVK")
+    }
+    fn to_event(&self) -> Event {
+        let mut text = self.event_name.clone();
+        if let Some(location) = &self.location_name {
+            text += &format!(" ({location})");
+        }
+        Event::new(self.event_date_time.with_timezone(&Utc), text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks;
+    use crate::test_utils::utc;
+
+    #[test]
+    fn test_get_barcode() -> Result<()> {
+        assert_eq!(
+            get_barcode(
+                "https://www.royalmail.com/track-your-item#/tracking-results/ab123456785gb"
+            )?,
+            "AB123456785GB"
+        );
+        assert!(get_barcode("https://www.royalmail.com/").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_undelivered() -> Result<()> {
+        let mock = mocks::load_text("royalmail_undelivered.json")?;
+        let package = RoyalMailTracker.parse(mock)?;
+        assert_eq!(package.barcode, "AB123456785GB");
+        assert_eq!(package.channel, "Royal Mail");
+        assert_eq!(package.status, PackageStatus::InTransit);
+        assert_eq!(
+            package.eta_window,
+            Some(TimeWindow {
+                start: utc("2026-10-15T09:15:00Z"),
+                end:   utc("2026-10-15T11:15:00Z"),
+            })
+        );
+        assert_eq!(package.delivered, None);
+        assert_eq!(package.events.len(), 3);
+        assert_eq!(package.events[0].text, "Out for delivery (Chester DO)");
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_delivered_to_neighbour() -> Result<()> {
+        let mock = mocks::load_text("royalmail_delivered_neighbour.json")?;
+        let package = RoyalMailTracker.parse(mock)?;
+        assert_eq!(
+            package.status,
+            PackageStatus::DeliveredToNeighbour {
+                address: "14 Acacia Avenue".into(),
+            }
+        );
+        assert_eq!(package.delivered, Some(utc("2026-10-15T10:48:00Z")));
+        assert_eq!(package.eta_window, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_delivered_to_safeplace() -> Result<()> {
+        let mock = mocks::load_text("royalmail_delivered_safeplace.json")?;
+        let package = RoyalMailTracker.parse(mock)?;
+        assert_eq!(package.status, PackageStatus::Delivered);
+        assert_eq!(
+            package.events[0].text,
+            "Delivered to safeplace (Chester DO)"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,440 @@
+// 17TRACK aggregates the tracking info of thousands of carriers. It is only
+// used as a fallback, for tracking numbers that none of the other trackers
+// recognise. Consumer URLs look like:
+// https://t.17track.net/en#nums=YT2612345678901234
+
+// API (needs the API key, or "security key", from the 17TRACK API dashboard,
+// in the 17token header). Numbers have to be registered before 17TRACK starts
+// tracking them:
+// POST https://api.17track.net/track/v2.2/register
+// POST https://api.17track.net/track/v2.2/gettrackinfo
+// 17TRACK can also push the tracking info of the registered numbers to a
+// webhook (`packtrack serve`), as {"event": "TRACKING_UPDATED", "data": ...},
+// where the data is the same as an accepted number's in gettrackinfo.
+
+use crate::Result;
+use crate::tracker::{
+    Event, Package, PackageStatus, PickupPoint, PushUpdate, RawResponse,
+    TimeWindow, Tracker, TrackerContext,
+};
+use crate::utils::UtcTime;
+use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset, Utc};
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+const ORIGIN: &str = "https://api.17track.net";
+
+pub struct SeventeenTrackTracker;
+
+#[async_trait]
+impl Tracker for SeventeenTrackTracker {
+    fn name(&self) -> &str {
+        "17track"
+    }
+    fn can_handle(&self, url: &str) -> bool {
+        url.contains("17track.net")
+    }
+    fn barcode_patterns(&self) -> &[&str] {
+        // Anything that could plausibly be a tracking number
+        &["[A-Z]{0,4}[0-9][A-Z0-9]{6,28}"]
+    }
+    fn barcode_url(&self, barcode: &str) -> Option<String> {
+        Some(format!("https://t.17track.net/en#nums={barcode}"))
+    }
+    fn is_fallback(&self) -> bool {
+        true
+    }
+    async fn get_raw(
+        &self,
+        url: &str,
+        ctx: &TrackerContext,
+    ) -> Result<RawResponse> {
+        let barcode = get_barcode(url)?;
+        let api_key = ctx
+            .credentials
+            .require("17track", "api_key")?;
+        let client = reqwest::Client::new();
+        let body = json!([{ "number": barcode }]);
+        // Registering a number twice is harmless (it's rejected as already
+        // registered), so we don't need to remember which ones we did
+        client
+            .post(ctx.api_url(&format!("{ORIGIN}/track/v2.2/register")))
+            .header("17token", &api_key)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?;
+        let response = client
+            .post(ctx.api_url(&format!("{ORIGIN}/track/v2.2/gettrackinfo")))
+            .header("17token", &api_key)
+            .json(&body)
+            .send()
+            .await?;
+        RawResponse::from_response(response).await
+    }
+    fn parse(&self, text: String) -> Result<Package> {
+        let response: TrackInfoResponse = serde_json::from_str(&text)
+            .map_err(|err| format!("Error parsing 17TRACK response: {err}"))?;
+        if let Some(rejected) = response.data.rejected.first() {
+            return Err(format!(
+                "17TRACK couldn't track {}: {}",
+                rejected.number, rejected.error.message
+            )
+            .into());
+        }
+        let accepted = response
+            .data
+            .accepted
+            .into_iter()
+            .next()
+            .ok_or("No tracking info in 17TRACK response")?;
+        Ok(accepted.to_package())
+    }
+    fn parse_push(&self, body: &str) -> Result<Vec<PushUpdate>> {
+        let push: Push = serde_json::from_str(body)
+            .map_err(|err| format!("Error parsing 17TRACK push: {err}"))?;
+        // e.g. TRACKING_STOPPED, which doesn't change the package
+        if push.event != "TRACKING_UPDATED" {
+            return Ok(vec![]);
+        }
+        let barcode = push.data["number"]
+            .as_str()
+            .ok_or("No tracking number in 17TRACK push")?
+            .to_string();
+        // Shaped like a gettrackinfo response, so that `parse` takes it
+        let raw = json!({"code": 0, "data": {"accepted": [push.data]}});
+        Ok(vec![PushUpdate {
+            barcode,
+            raw: Some(raw.to_string()),
+        }])
+    }
+}
+
+#[derive(Deserialize)]
+struct Push {
+    event: String,
+    data:  Value,
+}
+
+fn get_barcode(url: &str) -> Result<String> {
+    Regex::new(r"nums=([0-9A-Za-z]+)")?
+        .captures(url)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_uppercase())
+        .ok_or(format!("Couldn't get barcode from {url}").into())
+}
+
+fn to_utc(time: &DateTime<FixedOffset>) -> UtcTime {
+    time.with_timezone(&Utc)
+}
+
+#[derive(Deserialize)]
+struct TrackInfoResponse {
+    data: TrackInfoData,
+}
+
+#[derive(Deserialize)]
+struct TrackInfoData {
+    #[serde(default)]
+    accepted: Vec<Accepted>,
+    #[serde(default)]
+    rejected: Vec<Rejected>,
+}
+
+#[derive(Deserialize)]
+struct Rejected {
+    number: String,
+    error:  RejectedError,
+}
+
+#[derive(Deserialize)]
+struct RejectedError {
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct Accepted {
+    number:     String,
+    track_info: TrackInfo,
+}
+impl Accepted {
+    fn to_package(&self) -> Package {
+        let info = &self.track_info;
+        // 17TRACK may combine the events of several carriers, e.g. the
+        // carrier in the origin country and the one in the destination
+        // country. The first one has the latest events.
+        let provider = info.tracking.providers.first();
+        let events: Vec<Event> = provider
+            .map(|p| {
+                p.events
+                    .iter()
+                    .map(|e| e.to_event())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let latest = info.latest_event.as_ref();
+        let (status, delivered) = match info.latest_status.status.as_str() {
+            "Delivered" => (
+                PackageStatus::Delivered,
+                latest.map(|e| to_utc(&e.time_iso)),
+            ),
+            "AvailableForPickup" => (
+                PackageStatus::ReadyForPickup {
+                    location: latest
+                        .and_then(|e| e.location.clone())
+                        .unwrap_or("a pickup point".into()),
+                },
+                None,
+            ),
+            _ => (PackageStatus::InTransit, None),
+        };
+        let pickup = match &status {
+            PackageStatus::ReadyForPickup { location } => Some(PickupPoint {
+                location: location.clone(),
+                deadline: None,
+            }),
+            _ => None,
+        };
+        let eta_window = match delivered {
+            Some(_) => None,
+            None => info
+                .time_metrics
+                .as_ref()
+                .and_then(|m| m.estimated_delivery_date.as_ref())
+                .and_then(|eta| eta.time_window()),
+        };
+        Package {
+            barcode: self.number.clone(),
+            channel: provider
+                .map(|p| p.provider.name.clone())
+                .unwrap_or("17TRACK".into()),
+            status,
+            sender: None,
+            recipient: None,
+            eta: eta_window.as_ref().map(|w| w.start),
+            eta_window,
+            delivered,
+            events,
+            pickup,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TrackInfo {
+    latest_status: LatestStatus,
+    latest_event:  Option<TrackEvent>,
+    time_metrics:  Option<TimeMetrics>,
+    tracking:      Tracking,
+}
+
+#[derive(Deserialize)]
+struct LatestStatus {
+    /// e.g. "InTransit", "AvailableForPickup" or "Delivered"
+    status: String,
+}
+
+#[derive(Deserialize)]
+struct TimeMetrics {
+    estimated_delivery_date: Option<EstimatedDelivery>,
+}
+
+#[derive(Deserialize)]
+struct EstimatedDelivery {
+    from: Option<DateTime<FixedOffset>>,
+    to:   Option<DateTime<FixedOffset>>,
+}
+impl EstimatedDelivery {
+    fn time_window(&self) -> Option<TimeWindow> {
+        Some(TimeWindow {
+            start: to_utc(self.from.as_ref()?),
+            end:   to_utc(self.to.as_ref()?),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct Tracking {
+    #[serde(default)]
+    providers: Vec<Provider>,
+}
+
+/// A carrier, and the events it reported
+#[derive(Deserialize)]
+struct Provider {
+    provider: ProviderInfo,
+    /// Newest first
+    #[serde(default)]
+    events:   Vec<TrackEvent>,
+}
+
+#[derive(Deserialize)]
+struct ProviderInfo {
+    /// e.g. "YunExpress"
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct TrackEvent {
+    /// In the local time of the event, with its offset
+    time_iso:    DateTime<FixedOffset>,
+    description: String,
+    location:    Option<String>,
+}
+impl TrackEvent {
+    fn to_event(&self) -> Event {
+        let text = match self.location.as_deref() {
+            Some(location) if !location.is_empty() => {
+                format!("{} ({location})", self.description)
+            }
+            _ => self.description.clone(),
+        };
+        Event::new(to_utc(&self.time_iso), text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks;
+    use crate::test_utils::utc;
+
+    #[test]
+    fn test_get_barcode() -> Result<()> {
+        assert_eq!(
+            get_barcode("https://t.17track.net/en#nums=YT2612345678901234")?,
+            "YT2612345678901234"
+        );
+        assert!(get_barcode("https://t.17track.net/en").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_undelivered() -> Result<()> {
+        let mock = mocks::load_text("17track_undelivered.json")?;
+        let package = SeventeenTrackTracker.parse(mock)?;
+        assert_eq!(package.barcode, "YT2612345678901234");
+        assert_eq!(package.channel, "YunExpress");
+        assert_eq!(package.status, PackageStatus::InTransit);
+        assert_eq!(
+            package.eta_window,
+            Some(TimeWindow {
+                start: utc("2026-10-14T07:00:00Z"),
+                end:   utc("2026-10-14T16:00:00Z"),
+            })
+        );
+        assert_eq!(package.events.len(), 4);
+        assert_eq!(package.events[0].text, "Out for delivery (Rotterdam, NL)");
+        assert_eq!(package.events[3].text, "Shipment information received");
+        assert_eq!(package.events[3].timestamp, utc("2026-10-08T03:40:00Z"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_delivered() -> Result<()> {
+        let mock = mocks::load_text("17track_delivered.json")?;
+        let package = SeventeenTrackTracker.parse(mock)?;
+        assert_eq!(package.status, PackageStatus::Delivered);
+        assert_eq!(package.delivered, Some(utc("2026-10-14T11:21:00Z")));
+        assert_eq!(package.eta_window, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_push() -> Result<()> {
+        let response: Value =
+            serde_json::from_str(&mocks::load_text("17track_delivered.json")?)?;
+        let push = json!({
+            "event": "TRACKING_UPDATED",
+            "data": response["data"]["accepted"][0],
+        });
+        let updates = SeventeenTrackTracker.parse_push(&push.to_string())?;
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].barcode, "YT2612345678901234");
+        let raw = updates[0].raw.clone().unwrap();
+        let package = SeventeenTrackTracker.parse(raw)?;
+        assert_eq!(package.status, PackageStatus::Delivered);
+
+        let stopped = json!({"event": "TRACKING_STOPPED", "data": {}});
+        assert!(
+            SeventeenTrackTracker
+                .parse_push(&stopped.to_string())?
+                .is_empty()
+        );
+        assert!(
+            SeventeenTrackTracker
+                .parse_push("<xml/>")
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_rejected() {
+        let text = r#"{"code": 0, "data": {"accepted": [], "rejected": [
+            {"number": "YT2612345678901234", "error": {"code": -18019909,
+            "message": "No tracking information at this time."}}]}}"#;
+        let err = SeventeenTrackTracker
+            .parse(text.into())
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("No tracking information at this time.")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_raw() -> Result<()> {
+        use crate::tracker::Credentials;
+        use wiremock::matchers::{body_json, header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let body = json!([{ "number": "YT2612345678901234" }]);
+        Mock::given(method("POST"))
+            .and(path("/track/v2.2/register"))
+            .and(header("17token", "secret"))
+            .and(body_json(&body))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"code": 0, "data": {"accepted": [], "rejected": []}}"#,
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/track/v2.2/gettrackinfo"))
+            .and(header("17token", "secret"))
+            .and(body_json(&body))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string(mocks::load_text(
+                    "17track_delivered.json",
+                )?),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        let origin = server.uri();
+        let credentials = Credentials::new(
+            [(
+                "17track".into(),
+                [("api_key".into(), "secret".into())].into(),
+            )]
+            .into(),
+        );
+        let ctx = TrackerContext {
+            recipient_postcode: None,
+            language:           "en",
+            browser_path:       None,
+            api_origin:         Some(&origin),
+            credentials:        &credentials,
+        };
+        let raw = SeventeenTrackTracker
+            .get_raw("https://t.17track.net/en#nums=YT2612345678901234", &ctx)
+            .await?;
+        let package = SeventeenTrackTracker.parse(raw.body)?;
+        assert_eq!(package.status, PackageStatus::Delivered);
+        Ok(())
+    }
+}
@@ -0,0 +1,241 @@
+// TNT is part of FedEx, but still has its own tracking portal. Consumer URLs
+// look like:
+// https://www.tnt.com/express/en_gb/site/shipping-tools/tracking.html?searchType=con&cons=123456789
+
+// API url (what the tracking page itself uses):
+// https://www.tnt.com/api/v3/shipment?con=123456789&searchType=CON&locale=en_GB&channel=OPENTRACK
+
+use crate::Result;
+use crate::tracker::{
+    Event, Package, PackageStatus, RawResponse, Tracker, TrackerContext,
+};
+use crate::utils::UtcTime;
+use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset, Utc};
+use regex::Regex;
+use serde::Deserialize;
+
+pub struct TntTracker;
+
+#[async_trait]
+impl Tracker for TntTracker {
+    fn name(&self) -> &str {
+        "tnt"
+    }
+    fn can_handle(&self, url: &str) -> bool {
+        url.contains("tnt.com")
+    }
+    fn barcode_patterns(&self) -> &[&str] {
+        // Domestic consignment numbers are just 9 digits, like Trunkrs ones,
+        // so only match the international format
+        &["GE[0-9]{9}WW"]
+    }
+    fn barcode_url(&self, barcode: &str) -> Option<String> {
+        Some(format!(
+            "https://www.tnt.com/express/en_gb/site/shipping-tools/tracking.html?searchType=con&cons={barcode}"
+        ))
+    }
+    fn languages(&self) -> &[&str] {
+        &["en", "nl", "de", "fr", "it"]
+    }
+    fn probe_url(&self) -> Option<String> {
+        self.barcode_url("000000000")
+    }
+    fn check_probe(&self, raw: &RawResponse) -> Result<()> {
+        raw.expect_json()
+    }
+    async fn get_raw(
+        &self,
+        url: &str,
+        ctx: &TrackerContext,
+    ) -> Result<RawResponse> {
+        let barcode = get_barcode(url)?;
+        let locale = match self.choose_language(&[ctx.language]) {
+            Some("nl") => "nl_NL",
+            Some("de") => "de_DE",
+            Some("fr") => "fr_FR",
+            Some("it") => "it_IT",
+            _ => "en_GB",
+        };
+        let api_url = format!(
+            "https://www.tnt.com/api/v3/shipment?con={barcode}&searchType=CON&locale={locale}&channel=OPENTRACK"
+        );
+        let response = reqwest::get(ctx.api_url(&api_url)).await?;
+        RawResponse::from_response(response).await
+    }
+    fn parse(&self, text: String) -> Result<Package> {
+        let response: ShipmentResponse = serde_json::from_str(&text)
+            .map_err(|err| format!("Error parsing TNT response: {err}"))?;
+        let consignment = response
+            .output
+            .consignment
+            .into_iter()
+            .next()
+            .ok_or("TNT doesn't know this consignment")?;
+        Ok(consignment.to_package())
+    }
+}
+
+fn get_barcode(url: &str) -> Result<String> {
+    Regex::new(r"cons=([0-9A-Za-z]+)")?
+        .captures(url)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_uppercase())
+        .ok_or(format!("Couldn't get barcode from {url}").into())
+}
+
+fn to_utc(time: &DateTime<FixedOffset>) -> UtcTime {
+    time.with_timezone(&Utc)
+}
+
+#[derive(Deserialize)]
+struct ShipmentResponse {
+    #[serde(rename = "tracker.output")]
+    output: TrackerOutput,
+}
+
+#[derive(Deserialize)]
+struct TrackerOutput {
+    #[serde(default)]
+    consignment: Vec<Consignment>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Consignment {
+    consignment_number: String,
+    status:             ConsignmentStatus,
+    /// Newest first
+    #[serde(default)]
+    status_data:        Vec<StatusData>,
+    /// Who signed for the delivery
+    signatory:          Option<String>,
+    /// Expected delivery date
+    due_date:           Option<DateTime<FixedOffset>>,
+}
+impl Consignment {
+    fn to_package(&self) -> Package {
+        let events = self
+            .status_data
+            .iter()
+            .map(|s| s.to_event())
+            .collect();
+        let delivered = self
+            .status_data
+            .iter()
+            .find(|s| s.is_delivery())
+            .map(|s| to_utc(&s.local_event_date));
+        let is_delivered = delivered.is_some() || self.status.is_delivered;
+        Package {
+            barcode: self.consignment_number.clone(),
+            channel: "TNT".into(),
+            status: match is_delivered {
+                true => PackageStatus::Delivered,
+                false => PackageStatus::InTransit,
+            },
+            sender: None,
+            recipient: self.signatory.clone(),
+            eta: match is_delivered {
+                true => None,
+                false => self.due_date.as_ref().map(to_utc),
+            },
+            eta_window: None,
+            delivered,
+            events,
+            pickup: None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConsignmentStatus {
+    #[serde(default)]
+    is_delivered: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StatusData {
+    /// e.g. "OF" (out for delivery) or "OK" (delivered)
+    status_code:        String,
+    status_description: String,
+    local_event_date:   DateTime<FixedOffset>,
+    depot_name:         Option<String>,
+}
+impl StatusData {
+    fn is_delivery(&self) -> bool {
+        self.status_code == "OK"
+    }
+    fn to_event(&self) -> Event {
+        let text = match &self.depot_name {
+            Some(depot) => format!("{} ({depot})", self.status_description),
+            None => self.status_description.clone(),
+        };
+        Event::new(to_utc(&self.local_event_date), text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks;
+    use crate::test_utils::utc;
+
+    #[test]
+    fn test_get_barcode() -> Result<()> {
+        assert_eq!(
+            get_barcode(
+                "https://www.tnt.com/express/en_gb/site/shipping-tools/tracking.html?searchType=con&cons=123456789"
+            )?,
+            "123456789"
+        );
+        assert_eq!(
+            get_barcode(
+                "https://www.tnt.com/express/nl_nl/site/shipping-tools/tracking.html?cons=ge123456789ww&searchType=con"
+            )?,
+            "GE123456789WW"
+        );
+        assert!(get_barcode("https://www.tnt.com/").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_undelivered() -> Result<()> {
+        let mock = mocks::load_text("tnt_undelivered.json")?;
+        let package = TntTracker.parse(mock)?;
+        assert_eq!(package.barcode, "123456789");
+        assert_eq!(package.channel, "TNT");
+        assert_eq!(package.status, PackageStatus::InTransit);
+        assert_eq!(package.eta, Some(utc("2026-10-14T22:00:00Z")));
+        assert_eq!(package.delivered, None);
+        assert_eq!(package.events.len(), 4);
+        assert_eq!(package.events[0].text, "Out for delivery (Amsterdam)");
+        assert_eq!(package.events[3].timestamp, utc("2026-10-14T15:25:00Z"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_delivered() -> Result<()> {
+        let mock = mocks::load_text("tnt_delivered.json")?;
+        let package = TntTracker.parse(mock)?;
+        assert_eq!(package.status, PackageStatus::Delivered);
+        assert_eq!(package.delivered, Some(utc("2026-10-15T09:48:00Z")));
+        assert_eq!(package.recipient, Some("J. JANSEN".into()));
+        assert_eq!(package.eta, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_not_found() {
+        let text =
+            r#"{"tracker.output": {"notFound": [{"input": "000000000"}]}}"#;
+        let err = TntTracker
+            .parse(text.into())
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("doesn't know this consignment")
+        );
+    }
+}
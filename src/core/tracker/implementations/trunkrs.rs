@@ -10,7 +10,8 @@
 
 use crate::Result;
 use crate::tracker::{
-    Event, Package, PackageStatus, TimeWindow, Tracker, TrackerContext,
+    Event, Package, PackageStatus, RawResponse, TimeWindow, Tracker,
+    TrackerContext,
 };
 use crate::utils::UtcTime;
 use async_trait::async_trait;
@@ -24,10 +25,23 @@ pub struct TrunkrsTracker;
 
 #[async_trait]
 impl Tracker for TrunkrsTracker {
+    fn name(&self) -> &str {
+        "trunkrs"
+    }
     fn can_handle(&self, url: &str) -> bool {
         url.contains("trunkrs")
     }
-    async fn get_raw(&self, url: &str, _: &TrackerContext) -> Result<String> {
+    fn probe_url(&self) -> Option<String> {
+        Some("https://parcel.trunkrs.nl/000000000/1234AB".into())
+    }
+    fn check_probe(&self, raw: &RawResponse) -> Result<()> {
+        raw.expect_json()
+    }
+    async fn get_raw(
+        &self,
+        url: &str,
+        ctx: &TrackerContext,
+    ) -> Result<RawResponse> {
         let (barcode, postcode) = get_barcode_and_postcode(url)?;
         log::debug!("barcode = {barcode}");
         log::debug!("postcode = {postcode}");
@@ -38,13 +52,11 @@ impl Tracker for TrunkrsTracker {
         let auth_header = format!("Basic {pwd_b64}");
         let client = reqwest::Client::new();
         let response = client
-            .get("https://api.trunkrs.app/v2/tracing/details")
+            .get(ctx.api_url("https://api.trunkrs.app/v2/tracing/details"))
             .header(AUTHORIZATION, auth_header)
             .send()
-            .await?
-            .error_for_status()?;
-        let text = response.text().await?;
-        Ok(text)
+            .await?;
+        RawResponse::from_response(response).await
     }
 
     fn parse(&self, text: String) -> Result<Package> {
@@ -60,6 +72,7 @@ impl Tracker for TrunkrsTracker {
             eta_window: package.time_window(),
             delivered:  package.delivered(),
             events:     package.events(),
+            pickup:     None,
         })
     }
 }
@@ -118,8 +131,7 @@ impl TrunkrsPackage {
         let delivery_attempts = self.delivery_attempts.clone()?;
         let delivered = delivery_attempts
             .into_iter()
-            .filter(|attempt| attempt.state_name == "SHIPMENT_DELIVERED")
-            .next()?;
+            .find(|attempt| attempt.state_name == "SHIPMENT_DELIVERED")?;
         Some(delivered.set_at)
     }
     fn status(&self) -> PackageStatus {
@@ -155,16 +167,12 @@ impl TrunkrsEvent {
     fn to_event(&self) -> Result<Event> {
         let created = self
             .created_at
-            .clone()
             .ok_or(format!("No createdAt on Trunkrs Event: {self:?}"))?;
         let text = self
             .source
             .clone()
             .ok_or("No description on Trunkrs Event: {self:?}")?;
-        Ok(Event {
-            timestamp: created,
-            text,
-        })
+        Ok(Event::new(created, text))
     }
 }
 
@@ -0,0 +1,328 @@
+// Consumer URLs look like:
+// https://tools.usps.com/go/TrackConfirmAction?tLabels=9400111899223397623910
+
+// API url (Web Tools TrackV2, which needs a free Web Tools user ID):
+// https://secure.shippingapis.com/ShippingAPI.dll?API=TrackV2&XML=<TrackFieldRequest USERID="...">...
+// The response is XML. The latest event is in <TrackSummary>, and the earlier
+// ones in <TrackDetail>, newest first. Event times are local to wherever the
+// event happened, so we need the state to convert them to UTC.
+
+use crate::Result;
+use crate::tracker::{
+    Event, Package, PackageStatus, RawResponse, Tracker, TrackerContext,
+};
+use crate::utils::UtcTime;
+use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveTime, TimeZone};
+use chrono_tz::{America, Pacific, Tz};
+use regex::Regex;
+use serde::Deserialize;
+
+pub struct UspsTracker;
+
+#[async_trait]
+impl Tracker for UspsTracker {
+    fn name(&self) -> &str {
+        "usps"
+    }
+    fn can_handle(&self, url: &str) -> bool {
+        url.contains("usps.com")
+    }
+    fn barcode_patterns(&self) -> &[&str] {
+        &["9[0-9]{21}", "[A-Z]{2}[0-9]{9}US"]
+    }
+    fn barcode_url(&self, barcode: &str) -> Option<String> {
+        Some(format!(
+            "https://tools.usps.com/go/TrackConfirmAction?tLabels={barcode}"
+        ))
+    }
+    async fn get_raw(
+        &self,
+        url: &str,
+        ctx: &TrackerContext,
+    ) -> Result<RawResponse> {
+        let barcode = get_barcode(url)?;
+        let user_id = ctx
+            .credentials
+            .require("usps", "user_id")?;
+        let xml = format!(
+            "<TrackFieldRequest USERID=\"{user_id}\"><Revision>1</Revision>\
+             <ClientIp>127.0.0.1</ClientIp><SourceId>packtrack</SourceId>\
+             <TrackID ID=\"{barcode}\"></TrackID></TrackFieldRequest>"
+        );
+        let response = reqwest::Client::new()
+            .get(ctx.api_url("https://secure.shippingapis.com/ShippingAPI.dll"))
+            .query(&[("API", "TrackV2"), ("XML", &xml)])
+            .send()
+            .await?;
+        RawResponse::from_response(response).await
+    }
+    fn parse(&self, text: String) -> Result<Package> {
+        let response: TrackResponse = quick_xml::de::from_str(&text)
+            .map_err(|err| format!("Error parsing USPS response: {err}"))?;
+        response.track_info.to_package()
+    }
+}
+
+fn get_barcode(url: &str) -> Result<String> {
+    Regex::new(r"tLabels=([0-9A-Za-z]+)")?
+        .captures(url)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_uppercase())
+        .ok_or(format!("Couldn't get barcode from {url}").into())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TrackResponse {
+    track_info: TrackInfo,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TrackInfo {
+    #[serde(rename = "@ID")]
+    id:                     String,
+    error:                  Option<UspsError>,
+    destination_state:      Option<String>,
+    /// e.g. "October 17, 2026"
+    expected_delivery_date: Option<String>,
+    status_category:        Option<String>,
+    track_summary:          Option<TrackEvent>,
+    #[serde(default)]
+    track_detail:           Vec<TrackEvent>,
+}
+impl TrackInfo {
+    fn to_package(&self) -> Result<Package> {
+        if let Some(error) = &self.error {
+            return Err(format!("USPS: {}", error.description).into());
+        }
+        let destination = self
+            .destination_state
+            .as_deref()
+            .unwrap_or_default();
+        let mut events = vec![];
+        let mut delivered = None;
+        for track_event in self
+            .track_summary
+            .iter()
+            .chain(&self.track_detail)
+        {
+            let event = track_event.to_event(destination)?;
+            if delivered.is_none() && track_event.is_delivery() {
+                delivered = Some(event.timestamp);
+            }
+            events.push(event);
+        }
+        let is_delivered = delivered.is_some()
+            || self.status_category.as_deref() == Some("Delivered");
+        let eta = match is_delivered {
+            true => None,
+            false => self
+                .expected_delivery_date
+                .as_deref()
+                .map(|date| {
+                    local_time(date, "12:00 am", state_timezone(destination))
+                })
+                .transpose()?,
+        };
+        Ok(Package {
+            barcode: self.id.clone(),
+            channel: "USPS".into(),
+            status: match is_delivered {
+                true => PackageStatus::Delivered,
+                false => PackageStatus::InTransit,
+            },
+            sender: None,
+            recipient: None,
+            eta,
+            eta_window: None,
+            delivered,
+            events,
+            pickup: None,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct UspsError {
+    description: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TrackEvent {
+    /// e.g. "2:45 pm"
+    event_time:  String,
+    /// e.g. "October 17, 2026"
+    event_date:  String,
+    event:       String,
+    #[serde(default)]
+    event_city:  String,
+    #[serde(default)]
+    event_state: String,
+    #[serde(default)]
+    event_code:  String,
+}
+impl TrackEvent {
+    /// Event code 01 is "Delivered"
+    fn is_delivery(&self) -> bool {
+        self.event_code == "01"
+    }
+
+    fn to_event(&self, destination: &str) -> Result<Event> {
+        // Facility scans often leave the state empty, and put it in the city
+        // instead, e.g. "CHICAGO IL DISTRIBUTION CENTER"
+        let state = match self.event_state.as_str() {
+            "" => Regex::new(r"\b([A-Z]{2})\b")?
+                .captures_iter(&self.event_city)
+                .filter_map(|caps| caps.get(1))
+                .map(|m| m.as_str())
+                .find(|state| {
+                    STATE_TIMEZONES
+                        .iter()
+                        .any(|(s, _)| s == state)
+                })
+                .unwrap_or(destination),
+            state => state,
+        };
+        let timestamp = local_time(
+            &self.event_date,
+            &self.event_time,
+            state_timezone(state),
+        )?;
+        let location = [self.event_city.as_str(), self.event_state.as_str()]
+            .into_iter()
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let text = match location.is_empty() {
+            true => self.event.clone(),
+            false => format!("{} ({location})", self.event),
+        };
+        Ok(Event::new(timestamp, text))
+    }
+}
+
+/// Parse "October 17, 2026" and "2:45 pm" in the given timezone
+fn local_time(date: &str, time: &str, tz: Tz) -> Result<UtcTime> {
+    let date = NaiveDate::parse_from_str(date, "%B %d, %Y")?;
+    let time = match time {
+        "" => NaiveTime::MIN,
+        time => NaiveTime::parse_from_str(time, "%I:%M %P")?,
+    };
+    tz.from_local_datetime(&date.and_time(time))
+        .earliest()
+        .map(|t| t.to_utc())
+        .ok_or(format!("Invalid local time {date} {time}").into())
+}
+
+/// The timezone most of the state is in. States that span two zones are
+/// mostly in the first one, so this is usually right to within an hour.
+fn state_timezone(state: &str) -> Tz {
+    STATE_TIMEZONES
+        .iter()
+        .find(|(s, _)| *s == state)
+        .map(|(_, tz)| *tz)
+        .unwrap_or(America::New_York)
+}
+
+#[rustfmt::skip]
+const STATE_TIMEZONES: &[(&str, Tz)] = &[
+    ("AL", America::Chicago), ("AK", America::Anchorage),
+    ("AZ", America::Phoenix), ("AR", America::Chicago),
+    ("CA", America::Los_Angeles), ("CO", America::Denver),
+    ("CT", America::New_York), ("DC", America::New_York),
+    ("DE", America::New_York), ("FL", America::New_York),
+    ("GA", America::New_York), ("HI", Pacific::Honolulu),
+    ("ID", America::Boise), ("IL", America::Chicago),
+    ("IN", America::Indiana::Indianapolis), ("IA", America::Chicago),
+    ("KS", America::Chicago), ("KY", America::New_York),
+    ("LA", America::Chicago), ("ME", America::New_York),
+    ("MD", America::New_York), ("MA", America::New_York),
+    ("MI", America::Detroit), ("MN", America::Chicago),
+    ("MS", America::Chicago), ("MO", America::Chicago),
+    ("MT", America::Denver), ("NE", America::Chicago),
+    ("NV", America::Los_Angeles), ("NH", America::New_York),
+    ("NJ", America::New_York), ("NM", America::Denver),
+    ("NY", America::New_York), ("NC", America::New_York),
+    ("ND", America::Chicago), ("OH", America::New_York),
+    ("OK", America::Chicago), ("OR", America::Los_Angeles),
+    ("PA", America::New_York), ("PR", America::Puerto_Rico),
+    ("RI", America::New_York), ("SC", America::New_York),
+    ("SD", America::Chicago), ("TN", America::Chicago),
+    ("TX", America::Chicago), ("UT", America::Denver),
+    ("VT", America::New_York), ("VA", America::New_York),
+    ("WA", America::Los_Angeles), ("WV", America::New_York),
+    ("WI", America::Chicago), ("WY", America::Denver),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks;
+    use crate::test_utils::utc;
+
+    #[test]
+    fn test_get_barcode() -> Result<()> {
+        assert_eq!(
+            get_barcode(
+                "https://tools.usps.com/go/TrackConfirmAction?tLabels=9400111899223397623910"
+            )?,
+            "9400111899223397623910"
+        );
+        assert!(get_barcode("https://tools.usps.com/").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_undelivered() -> Result<()> {
+        let mock = mocks::load_text("usps_undelivered.xml")?;
+        let package = UspsTracker.parse(mock)?;
+        assert_eq!(package.barcode, "9400111899223397623910");
+        assert_eq!(package.channel, "USPS");
+        assert_eq!(package.status, PackageStatus::InTransit);
+        // Midnight in Boston
+        assert_eq!(package.eta, Some(utc("2026-10-17T04:00:00Z")));
+        assert_eq!(package.delivered, None);
+        assert_eq!(package.events.len(), 3);
+        // Chicago time, from the facility name
+        assert_eq!(package.events[0].timestamp, utc("2026-10-15T11:41:00Z"));
+        assert_eq!(
+            package.events[0].text,
+            "Departed USPS Regional Facility (CHICAGO IL DISTRIBUTION CENTER)"
+        );
+        assert_eq!(
+            package.events[2].text,
+            "USPS in possession of item (CHICAGO, IL)"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_delivered() -> Result<()> {
+        let mock = mocks::load_text("usps_delivered.xml")?;
+        let package = UspsTracker.parse(mock)?;
+        assert_eq!(package.status, PackageStatus::Delivered);
+        assert_eq!(package.delivered, Some(utc("2026-10-17T18:45:00Z")));
+        assert_eq!(package.eta, None);
+        assert_eq!(package.events.len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let text = r#"<TrackResponse><TrackInfo ID="9400111899223397623910">
+            <Error><Number>-2147219283</Number>
+            <Description>A status update is not yet available on your package.</Description>
+            </Error></TrackInfo></TrackResponse>"#;
+        let err = UspsTracker
+            .parse(text.into())
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("status update is not yet available")
+        );
+    }
+}
@@ -0,0 +1,274 @@
+// Tracker plugins compiled to WebAssembly. Plugins are dropped into the
+// `plugins` folder in the config dir, and are loaded at startup. They run
+// sandboxed: they get no imports (so no filesystem or network access) and a
+// limited amount of fuel per call. Packtrack does the HTTP request on their
+// behalf.
+//
+// A plugin module must export:
+//
+// - `memory`
+// - `alloc(len: i32) -> i32`: allocate `len` bytes and return a pointer to them
+// - `can_handle(ptr: i32, len: i32) -> i32`: receives the tracking URL; returns
+//   1 if the plugin can handle it, and 0 otherwise.
+// - `get_url(ptr: i32, len: i32) -> i64`: receives the tracking URL; returns
+//   the URL that packtrack should GET to fetch the raw tracking data.
+// - `parse(ptr: i32, len: i32) -> i64`: receives the raw tracking data; returns
+//   the Package serialized as JSON.
+//
+// Strings are passed as UTF-8. Functions that return a string return a
+// pointer and length packed into an i64: `(ptr << 32) | len`.
+
+use crate::tracker::{Package, RawResponse, Tracker, TrackerContext};
+use crate::{Result, utils};
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+use wasmtime::{Config, Engine, Instance, Memory, Module, Store};
+
+/// Max amount of work a plugin can do in a single call, so that a buggy plugin
+/// can't hang packtrack.
+const FUEL_PER_CALL: u64 = 100_000_000;
+
+#[derive(Clone)]
+pub struct WasmTracker {
+    pub name: String,
+    engine:   Engine,
+    module:   Module,
+}
+
+impl WasmTracker {
+    /// Compile the plugin from a .wasm (or .wat) file
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let engine = engine()?;
+        let module = Module::from_file(&engine, path).map_err(|err| {
+            format!("Couldn't load plugin {}: {err}", path.display())
+        })?;
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Ok(Self {
+            name,
+            engine,
+            module,
+        })
+    }
+
+    /// Compile the plugin from bytes (either binary or text format)
+    pub fn from_bytes(name: &str, bytes: &[u8]) -> Result<Self> {
+        let engine = engine()?;
+        let module = Module::new(&engine, bytes)
+            .map_err(|err| format!("Couldn't load plugin {name}: {err}"))?;
+        Ok(Self {
+            name: name.into(),
+            engine,
+            module,
+        })
+    }
+
+    /// Instantiate the plugin and copy the argument into its memory. Every
+    /// call gets a fresh instance, so plugins can't keep state between calls.
+    fn prepare_call(&self, arg: &str) -> Result<PluginCall> {
+        let err = |e: wasmtime::Error| format!("Plugin {}: {e}", self.name);
+        let mut store = Store::new(&self.engine, ());
+        store
+            .set_fuel(FUEL_PER_CALL)
+            .map_err(err)?;
+        let instance =
+            Instance::new(&mut store, &self.module, &[]).map_err(err)?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or(format!("Plugin {} exports no memory", self.name))?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&mut store, "alloc")
+            .map_err(err)?;
+
+        let bytes = arg.as_bytes();
+        let len = i32::try_from(bytes.len()).map_err(|_| {
+            format!("Argument too large for plugin {}", self.name)
+        })?;
+        let ptr = alloc
+            .call(&mut store, len)
+            .map_err(err)?;
+        memory
+            .write(&mut store, ptr as usize, bytes)
+            .map_err(|e| format!("Plugin {}: {e}", self.name))?;
+        Ok(PluginCall {
+            store,
+            instance,
+            memory,
+            ptr,
+            len,
+        })
+    }
+
+    /// Call an exported function that returns a boolean as an i32
+    fn call_bool(&self, func: &str, arg: &str) -> Result<bool> {
+        let err = |e: wasmtime::Error| {
+            format!("Plugin {} failed in {func}: {e}", self.name)
+        };
+        let mut call = self.prepare_call(arg)?;
+        let out = call
+            .instance
+            .get_typed_func::<(i32, i32), i32>(&mut call.store, func)
+            .and_then(|f| f.call(&mut call.store, (call.ptr, call.len)))
+            .map_err(err)?;
+        Ok(out != 0)
+    }
+
+    /// Call an exported function that returns a packed string
+    fn call_string(&self, func: &str, arg: &str) -> Result<String> {
+        let err = |e: wasmtime::Error| {
+            format!("Plugin {} failed in {func}: {e}", self.name)
+        };
+        let mut call = self.prepare_call(arg)?;
+        let packed = call
+            .instance
+            .get_typed_func::<(i32, i32), i64>(&mut call.store, func)
+            .and_then(|f| f.call(&mut call.store, (call.ptr, call.len)))
+            .map_err(err)?;
+        read_packed_string(&call.store, &call.memory, packed).map_err(|e| {
+            format!("Plugin {} failed in {func}: {e}", self.name).into()
+        })
+    }
+}
+
+/// A plugin instance, with the argument already copied into its memory
+struct PluginCall {
+    store:    Store<()>,
+    instance: Instance,
+    memory:   Memory,
+    ptr:      i32,
+    len:      i32,
+}
+
+fn engine() -> Result<Engine> {
+    let mut config = Config::new();
+    config.consume_fuel(true);
+    Engine::new(&config)
+        .map_err(|err| format!("Couldn't create WASM engine: {err}").into())
+}
+
+fn read_packed_string(
+    store: &Store<()>,
+    memory: &Memory,
+    packed: i64,
+) -> std::result::Result<String, String> {
+    let ptr = (packed >> 32) as u32 as usize;
+    let len = (packed & 0xFFFF_FFFF) as u32 as usize;
+    let data = memory
+        .data(store)
+        .get(ptr..ptr + len)
+        .ok_or(format!("returned out of bounds string {ptr}+{len}"))?;
+    String::from_utf8(data.to_vec()).map_err(|e| e.to_string())
+}
+
+#[async_trait]
+impl Tracker for WasmTracker {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn can_handle(&self, url: &str) -> bool {
+        self.call_bool("can_handle", url)
+            .inspect_err(|err| log::warn!("{err}"))
+            .unwrap_or(false)
+    }
+    async fn get_raw(
+        &self,
+        url: &str,
+        ctx: &TrackerContext,
+    ) -> Result<RawResponse> {
+        let api_url = self.call_string("get_url", url)?;
+        log::debug!("Plugin {} fetching {api_url}", self.name);
+        let response = reqwest::get(ctx.api_url(&api_url)).await?;
+        RawResponse::from_response(response).await
+    }
+    fn parse(&self, text: String) -> Result<Package> {
+        let json = self.call_string("parse", &text)?;
+        let package: Package = serde_json::from_str(&json).map_err(|err| {
+            format!("Plugin {} returned an invalid package: {err}", self.name)
+        })?;
+        Ok(package)
+    }
+}
+
+/// Plugins live in the `plugins` folder in the config dir
+pub fn get_plugins_dir() -> Result<PathBuf> {
+    utils::project_dirs().map(|dirs| dirs.config_dir().join("plugins"))
+}
+
+/// Load all the .wasm plugins in the given folder. Plugins that fail to load
+/// are skipped with a warning, so that one broken plugin doesn't break
+/// packtrack.
+pub fn load_plugins(dir: &Path) -> Result<Vec<WasmTracker>> {
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+    let mut plugins = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path
+            .extension()
+            .is_none_or(|ext| ext != "wasm")
+        {
+            continue;
+        }
+        match WasmTracker::from_file(&path) {
+            Ok(plugin) => {
+                log::info!("Loaded plugin {}", plugin.name);
+                plugins.push(plugin);
+            }
+            Err(err) => log::warn!("{err}"),
+        }
+    }
+    Ok(plugins)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tracker::PackageStatus;
+
+    /// Minimal plugin that handles any URL containing "example", and always
+    /// returns the same package.
+    const PLUGIN: &str = r#"
+(module
+  (memory (export "memory") 1)
+  (data (i32.const 0) "https://api.example.com/track")
+  (data (i32.const 64) "{\"barcode\":\"EX1\",\"channel\":\"Example\",\"status\":\"InTransit\",\"sender\":null,\"recipient\":null,\"eta\":null,\"eta_window\":null,\"delivered\":null,\"events\":[],\"pickup\":null}")
+  (func (export "alloc") (param i32) (result i32)
+    i32.const 1024)
+  (func (export "can_handle") (param $ptr i32) (param $len i32) (result i32)
+    ;; "example" is long enough that it can't be a 1 character URL
+    local.get $len
+    i32.const 7
+    i32.ge_u)
+  (func (export "get_url") (param i32 i32) (result i64)
+    i64.const 29)
+  (func (export "parse") (param i32 i32) (result i64)
+    i64.const 274877907105)
+)
+"#;
+
+    #[test]
+    fn test_wasm_plugin() -> Result<()> {
+        let plugin = WasmTracker::from_bytes("example", PLUGIN.as_bytes())?;
+        assert!(plugin.can_handle("https://example.com/EX1"));
+        assert!(!plugin.can_handle("x"));
+        assert_eq!(
+            plugin.call_string("get_url", "https://example.com/EX1")?,
+            "https://api.example.com/track"
+        );
+        let package = plugin.parse("anything".into())?;
+        assert_eq!(package.barcode, "EX1");
+        assert_eq!(package.channel, "Example");
+        assert_eq!(package.status, PackageStatus::InTransit);
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_plugins_missing_dir() -> Result<()> {
+        let plugins = load_plugins(Path::new("/does/not/exist"))?;
+        assert!(plugins.is_empty());
+        Ok(())
+    }
+}
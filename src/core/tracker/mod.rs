@@ -1,15 +1,67 @@
+pub mod browser;
+mod credentials;
+mod html;
 mod implementations;
 mod models;
+mod registry;
 mod traits;
 
+pub use implementations::aramex;
+pub use implementations::austrianpost;
+pub use implementations::brt;
+pub use implementations::cainiao;
+pub use implementations::demo;
 pub use implementations::dhl;
+pub use implementations::dhlpaket;
+pub use implementations::dpd;
+pub use implementations::fedex;
 pub use implementations::gls;
+pub use implementations::html_scraper;
+pub use implementations::inpost;
+pub use implementations::japanpost;
+pub use implementations::laposte;
+pub use implementations::novaposhta;
+pub use implementations::posti;
 pub use implementations::postnl;
+pub use implementations::royalmail;
+pub use implementations::seventeen_track;
+pub use implementations::tnt;
 pub use implementations::trunkrs;
+pub use implementations::usps;
+#[cfg(feature = "wasm-plugins")]
+pub use implementations::wasm_plugin;
 
+pub use aramex::AramexTracker;
+pub use austrianpost::AustrianPostTracker;
+pub use brt::BrtTracker;
+pub use cainiao::CainiaoTracker;
+pub use credentials::{CredentialMap, Credentials, store_in_keyring};
+pub use demo::{MockTracker, demo_urls};
 pub use dhl::DhlTracker;
+pub use dhlpaket::DhlPaketTracker;
+pub use dpd::DpdTracker;
+pub use fedex::FedexTracker;
 pub use gls::GlsTracker;
-pub use models::{Event, Package, PackageStatus, TimeWindow, TrackerContext};
+pub use html::HtmlPage;
+pub use html_scraper::{
+    ScraperConfig, ScraperTracker, get_scrapers_file, load_scrapers,
+};
+pub use inpost::InPostTracker;
+pub use japanpost::JapanPostTracker;
+pub use laposte::LaPosteTracker;
+pub use models::{
+    Event, Package, PackageStatus, PickupPoint, PushUpdate, RawResponse,
+    TimeWindow, TrackerContext,
+};
+pub use novaposhta::NovaPoshtaTracker;
+pub use posti::PostiTracker;
 pub use postnl::PostNLTracker;
-pub use traits::{Tracker, get_handler, register};
+pub use registry::TrackerRegistry;
+pub use royalmail::RoyalMailTracker;
+pub use seventeen_track::SeventeenTrackTracker;
+pub use tnt::TntTracker;
+pub use traits::Tracker;
 pub use trunkrs::TrunkrsTracker;
+pub use usps::UspsTracker;
+#[cfg(feature = "wasm-plugins")]
+pub use wasm_plugin::{WasmTracker, get_plugins_dir, load_plugins};
@@ -1,34 +1,216 @@
+use crate::Result;
+use crate::tracker::Credentials;
 use crate::utils::UtcTime;
+use chrono::{TimeDelta, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt::Display;
+use std::path::Path;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct Package {
+    /// Tracking number
     pub barcode:    String,
+    /// The carrier, e.g. "PostNL"
     pub channel:    String,
     pub status:     PackageStatus,
     pub sender:     Option<String>,
     pub recipient:  Option<String>,
+    /// Expected delivery time
     pub eta:        Option<UtcTime>,
+    /// Promised delivery window
     pub eta_window: Option<TimeWindow>,
     pub delivered:  Option<UtcTime>,
+    /// The carrier's events, in the order the carrier gives them
     pub events:     Vec<Event>,
+    /// Where the package is waiting to be collected, if it was delivered to a
+    /// pickup point instead of a home address.
+    pub pickup:     Option<PickupPoint>,
+}
+impl Package {
+    /// Add the event texts of the same package in another language as
+    /// translations. Events are matched by timestamp.
+    pub fn add_translations(&mut self, language: &str, translated: &Package) {
+        let mut unused: Vec<&Event> = translated.events.iter().collect();
+        for event in &mut self.events {
+            let Some(i) = unused
+                .iter()
+                .position(|e| e.timestamp == event.timestamp)
+            else {
+                continue;
+            };
+            event
+                .translations
+                .insert(language.into(), unused.remove(i).text.clone());
+        }
+    }
+
+    /// Show the event texts in the given language, where there's a
+    /// translation
+    pub fn localize(&mut self, language: &str) {
+        for event in &mut self.events {
+            if let Some(text) = event.translations.get(language) {
+                event.text = text.clone();
+            }
+        }
+    }
+}
+
+/// What a push notification from a carrier says about one package (see
+/// `Tracker::parse_push`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct PushUpdate {
+    pub barcode: String,
+    /// The new tracking data in the form `Tracker::parse` takes, if the push
+    /// contains it. Otherwise the package has to be fetched again.
+    pub raw:     Option<String>,
+}
+
+/// The raw response from a carrier, as returned by `Tracker::get_raw`. This
+/// is what gets cached, so that it can be parsed again later.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawResponse {
+    /// The response body. Usually JSON, but some carriers only have an HTML
+    /// tracking page.
+    pub body:         String,
+    /// The Content-Type header, e.g. "text/html; charset=UTF-8"
+    pub content_type: Option<String>,
+    /// HTTP status code
+    pub status:       u16,
+}
+impl RawResponse {
+    /// A successful response with the given body
+    pub fn ok(body: impl Into<String>) -> Self {
+        Self {
+            body:         body.into(),
+            content_type: None,
+            status:       200,
+        }
+    }
+
+    /// Read the whole response. Unlike `error_for_status`, this doesn't fail
+    /// on 4xx/5xx statuses, so that the caller can decide what to do.
+    pub async fn from_response(response: reqwest::Response) -> Result<Self> {
+        let status = response.status().as_u16();
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(String::from);
+        let body = response.text().await?;
+        Ok(Self {
+            body,
+            content_type,
+            status,
+        })
+    }
+
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    pub fn is_client_error(&self) -> bool {
+        (400..500).contains(&self.status)
+    }
+
+    pub fn is_server_error(&self) -> bool {
+        self.status >= 500
+    }
+
+    /// True if the body is valid JSON, whatever the content type says
+    pub fn is_json(&self) -> bool {
+        serde_json::from_str::<serde::de::IgnoredAny>(&self.body).is_ok()
+    }
+
+    pub fn is_html(&self) -> bool {
+        self.content_type
+            .as_ref()
+            .is_some_and(|ct| ct.contains("text/html"))
+    }
+
+    /// Error unless this is a non-server-error response with a JSON body
+    pub fn expect_json(&self) -> Result<()> {
+        match !self.is_server_error() && self.is_json() {
+            true => Ok(()),
+            false => Err(self.unexpected("a JSON response")),
+        }
+    }
+
+    /// Error unless this is a non-server-error response with an HTML page
+    pub fn expect_html(&self) -> Result<()> {
+        match !self.is_server_error() && self.is_html() {
+            true => Ok(()),
+            false => Err(self.unexpected("an HTML page")),
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> crate::Error {
+        format!(
+            "Expected {expected}, got HTTP {} ({})",
+            self.status,
+            self.content_type
+                .as_deref()
+                .unwrap_or("no content type")
+        )
+        .into()
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
 pub struct TimeWindow {
     pub start: UtcTime,
     pub end:   UtcTime,
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
 pub struct Event {
-    pub timestamp: UtcTime,
-    pub text:      String,
+    pub timestamp:    UtcTime,
+    pub text:         String,
+    /// The text in other languages, by language. Only filled in when the
+    /// `languages` setting asks for more than one language.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub translations: BTreeMap<String, String>,
 }
+impl Event {
+    pub fn new(timestamp: UtcTime, text: impl Into<String>) -> Self {
+        Self {
+            timestamp,
+            text: text.into(),
+            translations: BTreeMap::new(),
+        }
+    }
+}
+
+/// A pickup point (e.g. a DHL ServicePoint) where a package can be collected.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+pub struct PickupPoint {
+    /// Human-readable name and/or address of the pickup point
+    pub location: String,
+    /// The package will be returned to the sender if it is not collected
+    /// before this time.
+    pub deadline: Option<UtcTime>,
+}
+impl PickupPoint {
+    /// Time remaining until the pickup deadline (negative if it has passed)
+    pub fn time_left(&self) -> Option<TimeDelta> {
+        self.deadline
+            .map(|deadline| deadline - Utc::now())
+    }
 
-#[derive(Debug, Hash, PartialEq, Eq, Clone)]
+    /// True if the deadline is less than `within` away (or has passed).
+    pub fn deadline_within(&self, within: TimeDelta) -> bool {
+        self.time_left()
+            .is_some_and(|left| left < within)
+    }
+}
+
+#[derive(
+    Debug, Hash, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema,
+)]
 pub enum PackageStatus {
     Delivered,
     DeliveredToNeighbour { address: String },
+    ReadyForPickup { location: String },
     InTransit,
 }
 impl PackageStatus {
@@ -36,11 +218,7 @@ impl PackageStatus {
     /// will be no more updates.
     pub fn is_final(&self) -> bool {
         use PackageStatus::*;
-        match self {
-            Delivered => true,
-            DeliveredToNeighbour { address: _ } => true,
-            _ => false,
-        }
+        matches!(self, Delivered | DeliveredToNeighbour { address: _ })
     }
 }
 impl Display for PackageStatus {
@@ -58,4 +236,35 @@ pub struct TrackerContext<'a> {
     pub recipient_postcode: Option<&'a str>,
     /// Preferred language (usually passed as a query param to the API)
     pub language:           &'a str,
+    /// Chrome/Chromium executable for trackers that need a headless browser
+    pub browser_path:       Option<&'a Path>,
+    /// Send all carrier API requests to this origin (e.g.
+    /// "http://127.0.0.1:1234") instead. Used to test against a fake carrier
+    /// server.
+    pub api_origin:         Option<&'a str>,
+    /// API keys and tokens for carriers that need them
+    pub credentials:        &'a Credentials,
+}
+impl TrackerContext<'_> {
+    /// The URL that should actually be requested for the given carrier API
+    /// URL. This is the URL itself, unless `api_origin` is set.
+    pub fn api_url(&self, url: &str) -> String {
+        let Some(origin) = self.api_origin else {
+            return url.into();
+        };
+        match reqwest::Url::parse(url) {
+            Ok(parsed) => {
+                let query = parsed
+                    .query()
+                    .map(|q| format!("?{q}"))
+                    .unwrap_or_default();
+                format!(
+                    "{}{}{query}",
+                    origin.trim_end_matches('/'),
+                    parsed.path()
+                )
+            }
+            Err(_) => url.into(),
+        }
+    }
 }
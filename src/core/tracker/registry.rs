@@ -0,0 +1,697 @@
+use crate::Result;
+use crate::barcode;
+use crate::settings::TrackerSettings;
+use crate::tracker::{
+    AramexTracker, AustrianPostTracker, BrtTracker, CainiaoTracker,
+    DhlPaketTracker, DhlTracker, DpdTracker, FedexTracker, GlsTracker,
+    InPostTracker, JapanPostTracker, LaPosteTracker, NovaPoshtaTracker,
+    PostNLTracker, PostiTracker, RoyalMailTracker, TntTracker, Tracker,
+    TrunkrsTracker, UspsTracker,
+};
+use crate::url_store::{self, AnnotatedUrl};
+use regex::Regex;
+
+/// The collection of Tracker implementations to choose from when tracking a
+/// URL. Trackers are tried in order, and the first one that can handle the URL
+/// is used.
+///
+/// `TrackerRegistry::default()` contains all the built-in trackers. Use the
+/// builder methods to compose your own:
+///
+/// ```
+/// use packtrack::tracker::{DhlTracker, PostNLTracker, TrackerRegistry};
+///
+/// let registry = TrackerRegistry::empty()
+///     .with(PostNLTracker)
+///     .with(DhlTracker);
+/// assert_eq!(registry.names(), vec!["postnl", "dhl"]);
+/// ```
+pub struct TrackerRegistry {
+    trackers: Vec<Box<dyn Tracker>>,
+}
+
+impl TrackerRegistry {
+    /// A registry with no trackers in it
+    pub fn empty() -> Self {
+        Self { trackers: vec![] }
+    }
+
+    /// Builder method to add a tracker (at the lowest priority)
+    pub fn with(mut self, tracker: impl Tracker + 'static) -> Self {
+        self.add(Box::new(tracker));
+        self
+    }
+
+    /// Builder method to remove the tracker with the given name
+    pub fn without(mut self, name: &str) -> Self {
+        self.remove(name);
+        self
+    }
+
+    /// Add a tracker (at the lowest priority)
+    pub fn add(&mut self, tracker: Box<dyn Tracker>) {
+        self.trackers.push(tracker);
+    }
+
+    /// Remove the tracker with the given name, returning it if it was present
+    pub fn remove(&mut self, name: &str) -> Option<Box<dyn Tracker>> {
+        let idx = self
+            .trackers
+            .iter()
+            .position(|t| t.name() == name)?;
+        Some(self.trackers.remove(idx))
+    }
+
+    /// Move the named trackers to the front, in the given order. The other
+    /// trackers keep their relative order. Unknown names are ignored.
+    pub fn prioritise(&mut self, names: &[String]) {
+        // sort_by_key is stable, so unranked trackers stay in order
+        self.trackers.sort_by_key(|tracker| {
+            names
+                .iter()
+                .position(|name| name == tracker.name())
+                .unwrap_or(names.len())
+        });
+    }
+
+    /// Apply the user's tracker settings: remove the disabled trackers and
+    /// reorder the rest according to the priority list.
+    pub fn configure(mut self, settings: &TrackerSettings) -> Self {
+        let known = self.names().join(", ");
+        for name in settings
+            .disabled
+            .iter()
+            .chain(&settings.priority)
+        {
+            if !self.names().contains(&name.as_str()) {
+                log::warn!("Unknown tracker {name:?}; expected one of {known}");
+            }
+        }
+        for name in &settings.disabled {
+            self.remove(name);
+        }
+        self.prioritise(&settings.priority);
+        self
+    }
+
+    /// The names of the registered trackers, in order of priority
+    pub fn names(&self) -> Vec<&str> {
+        self.trackers
+            .iter()
+            .map(|t| t.name())
+            .collect()
+    }
+
+    /// The registered trackers, in order of priority
+    pub fn trackers(&self) -> impl Iterator<Item = &dyn Tracker> {
+        self.trackers.iter().map(|t| t.as_ref())
+    }
+
+    /// Try to get a Tracker implementation for the given url.
+    pub fn get_handler(&self, url: &str) -> Result<&dyn Tracker> {
+        self.trackers
+            .iter()
+            .find(|t| t.can_handle(url))
+            .map(|t| t.as_ref())
+            .ok_or(format!("Couldn't find a handler for {url}").into())
+    }
+
+    /// Find the trackers that recognise a bare tracking number, and the URL
+    /// each of them would use for it. If `carrier` is given, only trackers
+    /// whose name contains it are considered. Fallback trackers are left out.
+    pub fn detect(
+        &self,
+        barcode: &str,
+        carrier: Option<&str>,
+    ) -> Vec<(&str, String)> {
+        self.matching(barcode, carrier)
+            .filter(|t| !t.is_fallback())
+            .filter_map(|t| Some((t.name(), t.barcode_url(barcode)?)))
+            .collect()
+    }
+
+    /// The first fallback tracker that recognises the tracking number, and
+    /// the URL it would use for it.
+    pub fn detect_fallback(
+        &self,
+        barcode: &str,
+        carrier: Option<&str>,
+    ) -> Option<(&str, String)> {
+        self.matching(barcode, carrier)
+            .filter(|t| t.is_fallback())
+            .find_map(|t| Some((t.name(), t.barcode_url(barcode)?)))
+    }
+
+    /// The trackers whose barcode patterns match the tracking number
+    fn matching(
+        &self,
+        barcode: &str,
+        carrier: Option<&str>,
+    ) -> impl Iterator<Item = &dyn Tracker> {
+        let carrier = carrier.map(|c| c.to_lowercase());
+        self.trackers()
+            .filter(move |t| {
+                carrier
+                    .as_ref()
+                    .is_none_or(|c| t.name().contains(c.as_str()))
+            })
+            .filter(move |t| {
+                t.barcode_patterns()
+                    .iter()
+                    .filter_map(|p| Regex::new(&format!("^(?:{p})$")).ok())
+                    .any(|rx| rx.is_match(barcode))
+            })
+    }
+
+    /// The carrier and tracking number of a URL, to recognise URLs for the
+    /// same package, e.g. with and without the postcode. The tracking number
+    /// is the first part of the URL that matches one of the carrier's
+    /// barcode patterns, so there is none for carriers without patterns.
+    pub fn shipment_key(&self, url: &str) -> Option<(String, String)> {
+        let tracker = self.get_handler(url).ok()?;
+        let patterns: Vec<Regex> = tracker
+            .barcode_patterns()
+            .iter()
+            .filter_map(|p| Regex::new(&format!("^(?:{p})$")).ok())
+            .collect();
+        let word = Regex::new("[A-Za-z0-9]+").ok()?;
+        let barcode = word
+            .find_iter(url)
+            .map(|m| m.as_str().to_uppercase())
+            .find(|word| {
+                patterns
+                    .iter()
+                    .any(|rx| rx.is_match(word))
+            })?;
+        Some((tracker.name().to_owned(), barcode))
+    }
+
+    /// Merge the entries for the same package (see `shipment_key`), so that
+    /// the package is tracked, and notified about, only once
+    pub fn merge_duplicates(
+        &self,
+        urls: Vec<AnnotatedUrl>,
+    ) -> Vec<AnnotatedUrl> {
+        url_store::merge_duplicates(urls, |u| self.shipment_key(&u.url))
+    }
+
+    /// For a URL that none of the trackers can handle, the URL of a fallback
+    /// tracker for the last tracking number-like part of it.
+    fn fallback_url(&self, url: &str) -> Option<String> {
+        if self.get_handler(url).is_ok() {
+            return None;
+        }
+        let path = url
+            .split_once("://")
+            .map_or(url, |(_, rest)| rest);
+        let (_, path) = path.split_once('/')?;
+        let word = Regex::new("[A-Za-z0-9]+").ok()?;
+        let words: Vec<_> = word
+            .find_iter(path)
+            .map(|m| m.as_str().to_uppercase())
+            .collect();
+        words.iter().rev().find_map(|barcode| {
+            let (name, fallback) = self.detect_fallback(barcode, None)?;
+            log::info!("No tracker for {url}; using {name} for {barcode}");
+            Some(fallback)
+        })
+    }
+
+    /// Turn the user's input into a tracking URL. URLs are passed through
+    /// as-is; bare tracking numbers are matched against the trackers' barcode
+    /// patterns.
+    pub fn resolve(
+        &self,
+        input: &str,
+        carrier: Option<&str>,
+    ) -> Result<String> {
+        let barcode = barcode::normalize(input);
+        if !barcode::is_barcode(&barcode) {
+            return Ok(self
+                .fallback_url(input)
+                .unwrap_or(input.into()));
+        }
+        let candidates = self.detect(&barcode, carrier);
+        match candidates.as_slice() {
+            [] => match self.detect_fallback(&barcode, carrier) {
+                Some((name, url)) => {
+                    log::info!("Falling back to {name} for {barcode}");
+                    Ok(url)
+                }
+                None => Err(format!(
+                    "Couldn't recognise tracking number {barcode}. Try \
+                     passing the full tracking URL instead."
+                )
+                .into()),
+            },
+            [(name, url)] => {
+                log::info!("Recognised {barcode} as a {name} tracking number");
+                Ok(url.clone())
+            }
+            _ => {
+                let options = candidates
+                    .iter()
+                    .map(|(name, url)| format!("\n  {name}: {url}"))
+                    .collect::<String>();
+                Err(format!(
+                    "Tracking number {barcode} could belong to several \
+                     carriers. Pass --carrier to choose one, or use one of \
+                     these URLs:{options}"
+                )
+                .into())
+            }
+        }
+    }
+
+    /// The canonical tracking URL for a tracking number. With a carrier, its
+    /// tracker builds the URL even if the tracking number doesn't look like
+    /// one of theirs; without one, the carrier is recognised like in
+    /// `resolve`.
+    pub fn barcode_url(
+        &self,
+        barcode: &str,
+        carrier: Option<&str>,
+    ) -> Result<String> {
+        let barcode = barcode::normalize(barcode);
+        if !barcode::is_barcode(&barcode) {
+            return Err(format!(
+                "{barcode} isn't a tracking number: it should only have \
+                 letters and digits"
+            )
+            .into());
+        }
+        let Some(carrier) = carrier else {
+            return self.resolve(&barcode, None);
+        };
+        let carrier = carrier.to_lowercase();
+        let trackers: Vec<_> = self
+            .trackers()
+            .filter(|t| t.name().contains(carrier.as_str()))
+            .collect();
+        let tracker = match trackers
+            .iter()
+            .find(|t| t.name() == carrier)
+        {
+            Some(tracker) => *tracker,
+            None => match trackers.as_slice() {
+                [tracker] => *tracker,
+                [] => return Err(format!("Unknown carrier {carrier}").into()),
+                _ => {
+                    let names: Vec<_> = trackers
+                        .iter()
+                        .map(|t| t.name())
+                        .collect();
+                    return Err(format!(
+                        "Carrier {carrier} could be any of: {}",
+                        names.join(", ")
+                    )
+                    .into());
+                }
+            },
+        };
+        tracker.barcode_url(&barcode).ok_or(
+            format!(
+                "{} can't build a tracking URL from a tracking number. \
+                     Pass the full tracking URL instead.",
+                tracker.name()
+            )
+            .into(),
+        )
+    }
+}
+
+impl Default for TrackerRegistry {
+    fn default() -> Self {
+        Self::empty()
+            .with(PostNLTracker)
+            .with(DhlTracker)
+            .with(GlsTracker)
+            .with(TrunkrsTracker)
+            .with(DpdTracker)
+            .with(FedexTracker)
+            .with(RoyalMailTracker)
+            .with(UspsTracker)
+            .with(DhlPaketTracker)
+            .with(LaPosteTracker)
+            .with(AustrianPostTracker)
+            .with(CainiaoTracker)
+            .with(AramexTracker)
+            .with(TntTracker)
+            .with(InPostTracker)
+            .with(JapanPostTracker)
+            .with(BrtTracker)
+            .with(NovaPoshtaTracker)
+            .with(PostiTracker)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_handler() -> Result<()> {
+        let registry = TrackerRegistry::default();
+        let url = "https://jouw.postnl.nl/track-and-trace/3SABCD-NL-1234AB";
+        assert_eq!(registry.get_handler(url)?.name(), "postnl");
+        let url = "https://www.dhl.de/de/privatkunden/pakete-empfangen/verfolgen.html?piececode=00340434161094042557";
+        assert_eq!(registry.get_handler(url)?.name(), "dhlpaket");
+        let url =
+            "https://my.dhlecommerce.nl/home/tracktrace/JVGL0123456789012";
+        assert_eq!(registry.get_handler(url)?.name(), "dhl");
+        assert_eq!(
+            registry
+                .get_handler("https://example.com")
+                .err()
+                .unwrap(),
+            "Couldn't find a handler for https://example.com".into()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_remove() {
+        let mut registry = TrackerRegistry::default().without("dhl");
+        assert_eq!(
+            registry.names(),
+            vec![
+                "postnl",
+                "gls",
+                "trunkrs",
+                "dpd",
+                "fedex",
+                "royalmail",
+                "usps",
+                "dhlpaket",
+                "laposte",
+                "austrianpost",
+                "cainiao",
+                "aramex",
+                "tnt",
+                "inpost",
+                "japanpost",
+                "brt",
+                "novaposhta",
+                "posti"
+            ]
+        );
+        assert!(
+            registry
+                .get_handler("https://my.dhlecommerce.nl/home/tracktrace/ABC")
+                .is_err()
+        );
+
+        registry.add(Box::new(DhlTracker));
+        assert_eq!(
+            registry.names(),
+            vec![
+                "postnl",
+                "gls",
+                "trunkrs",
+                "dpd",
+                "fedex",
+                "royalmail",
+                "usps",
+                "dhlpaket",
+                "laposte",
+                "austrianpost",
+                "cainiao",
+                "aramex",
+                "tnt",
+                "inpost",
+                "japanpost",
+                "brt",
+                "novaposhta",
+                "posti",
+                "dhl"
+            ]
+        );
+        assert!(registry.remove("foo").is_none());
+        assert!(registry.remove("gls").is_some());
+        assert_eq!(
+            registry.names(),
+            vec![
+                "postnl",
+                "trunkrs",
+                "dpd",
+                "fedex",
+                "royalmail",
+                "usps",
+                "dhlpaket",
+                "laposte",
+                "austrianpost",
+                "cainiao",
+                "aramex",
+                "tnt",
+                "inpost",
+                "japanpost",
+                "brt",
+                "novaposhta",
+                "posti",
+                "dhl"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_choose_language() {
+        assert_eq!(PostNLTracker.choose_language(&["nl", "en"]), Some("nl"));
+        assert_eq!(PostNLTracker.choose_language(&["fr", "en"]), Some("en"));
+        assert_eq!(PostNLTracker.choose_language(&["fr"]), Some("nl"));
+        assert_eq!(DpdTracker.choose_language(&["nl", "en"]), Some("en"));
+        // GLS doesn't advertise any languages, so we just pass on the request
+        assert_eq!(GlsTracker.choose_language(&["nl", "en"]), Some("nl"));
+        assert_eq!(GlsTracker.choose_language(&[]), None);
+    }
+
+    #[test]
+    fn test_parse_push() -> Result<()> {
+        let body = r#"{"shipments": [{"id": "3SABCD1234567", "status": "x"},
+            {"id": "3SABCD1234567"}, {"id": "RR123456789NL"}],
+            "ref": "X3SABCD7654321"}"#;
+        let barcodes: Vec<_> = PostNLTracker
+            .parse_push(body)?
+            .into_iter()
+            .map(|update| {
+                assert_eq!(update.raw, None);
+                update.barcode
+            })
+            .collect();
+        assert_eq!(barcodes, ["3SABCD1234567", "RR123456789NL"]);
+        assert!(GlsTracker.parse_push(body)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve() -> Result<()> {
+        let registry = TrackerRegistry::default();
+        let url = "https://jouw.postnl.nl/track-and-trace/3SABCD1234567";
+        assert_eq!(registry.resolve(url, None)?, url);
+        assert_eq!(registry.resolve("3sabcd1234567", None)?, url);
+        assert_eq!(
+            registry.resolve("JVGL0123456789012345", None)?,
+            "https://my.dhlecommerce.nl/home/tracktrace/JVGL0123456789012345"
+        );
+        assert_eq!(
+            registry.resolve("123456789012", None)?,
+            "https://www.gls-info.nl/tracking?parcelNo=123456789012"
+        );
+        assert!(
+            registry
+                .resolve("HELLO", None)
+                .err()
+                .unwrap()
+                .to_string()
+                .contains("Couldn't recognise tracking number HELLO")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_barcode_url() -> Result<()> {
+        let registry = TrackerRegistry::default();
+        let url = "https://jouw.postnl.nl/track-and-trace/3SABCD1234567";
+        assert_eq!(registry.barcode_url("3sabcd 1234567", None)?, url);
+        assert_eq!(registry.barcode_url("3SABCD1234567", Some("PostNL"))?, url);
+        // Not a PostNL pattern, but the carrier knows best
+        assert_eq!(
+            registry.barcode_url("ABC123", Some("postnl"))?,
+            "https://jouw.postnl.nl/track-and-trace/ABC123"
+        );
+        assert_eq!(
+            registry.barcode_url("3SABCD1234567", Some("dhl"))?,
+            "https://my.dhlecommerce.nl/home/tracktrace/3SABCD1234567"
+        );
+        assert!(
+            registry
+                .barcode_url("3SABCD1234567", Some("nosuchcarrier"))
+                .is_err()
+        );
+        assert!(
+            registry
+                .barcode_url("https://example.com/1", None)
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_shipment_key() {
+        let registry = TrackerRegistry::default();
+        let key = Some(("postnl".to_owned(), "3SABCD1234567".to_owned()));
+        assert_eq!(
+            registry.shipment_key(
+                "https://jouw.postnl.nl/track-and-trace/3SABCD1234567"
+            ),
+            key
+        );
+        assert_eq!(
+            registry.shipment_key(
+                "https://jouw.postnl.nl/track-and-trace/3SABCD1234567-NL-1234AB"
+            ),
+            key
+        );
+        assert_eq!(
+            registry.shipment_key("https://jouw.postnl.nl/track-and-trace/"),
+            None
+        );
+        assert_eq!(registry.shipment_key("https://example.com/1"), None);
+    }
+
+    #[test]
+    fn test_merge_duplicates() {
+        let registry = TrackerRegistry::default();
+        let url = "https://jouw.postnl.nl/track-and-trace/3SABCD1234567";
+        let merged = registry.merge_duplicates(vec![
+            url.into(),
+            "https://example.com/1".into(),
+            format!("{url}-NL-1234AB")
+                .as_str()
+                .into(),
+        ]);
+        let merged: Vec<_> = merged
+            .iter()
+            .map(|u| u.url.as_str())
+            .collect();
+        assert_eq!(
+            merged,
+            [format!("{url}-NL-1234AB").as_str(), "https://example.com/1"]
+        );
+    }
+
+    #[test]
+    fn test_resolve_fallback() -> Result<()> {
+        use crate::tracker::SeventeenTrackTracker;
+
+        let registry = TrackerRegistry::default().with(SeventeenTrackTracker);
+        // Dedicated trackers still win
+        assert_eq!(
+            registry.resolve("3sabcd1234567", None)?,
+            "https://jouw.postnl.nl/track-and-trace/3SABCD1234567"
+        );
+        assert_eq!(registry.detect("YT2612345678901234", None), vec![]);
+        assert_eq!(
+            registry.resolve("yt2612345678901234", None)?,
+            "https://t.17track.net/en#nums=YT2612345678901234"
+        );
+        assert_eq!(
+            registry.resolve(
+                "https://www.yuntrack.com/parcelTracking?id=YT2612345678901234",
+                None
+            )?,
+            "https://t.17track.net/en#nums=YT2612345678901234"
+        );
+        // Nothing that looks like a tracking number
+        assert_eq!(
+            registry.resolve("https://example.com/track", None)?,
+            "https://example.com/track"
+        );
+        assert!(registry.resolve("HELLO", None).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_ambiguous() -> Result<()> {
+        let registry = TrackerRegistry::default();
+        let barcode = "3SABCD123456789";
+        let err = registry
+            .resolve(barcode, None)
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("could belong to several carriers"));
+        assert!(err.contains("postnl: https://jouw.postnl.nl/"));
+        assert!(err.contains("dhl: https://my.dhlecommerce.nl/"));
+
+        // The carrier filter disambiguates
+        assert_eq!(
+            registry.resolve(barcode, Some("DHL"))?,
+            "https://my.dhlecommerce.nl/home/tracktrace/3SABCD123456789"
+        );
+        // This one can only be DHL
+        assert_eq!(
+            registry.resolve("3SQLW0022110709", None)?,
+            "https://my.dhlecommerce.nl/home/tracktrace/3SQLW0022110709"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_configure() {
+        let settings = TrackerSettings {
+            disabled: vec!["dhl".into()],
+            priority: vec!["trunkrs".into(), "foo".into(), "gls".into()],
+        };
+        let registry = TrackerRegistry::default().configure(&settings);
+        assert_eq!(
+            registry.names(),
+            vec![
+                "trunkrs",
+                "gls",
+                "postnl",
+                "dpd",
+                "fedex",
+                "royalmail",
+                "usps",
+                "dhlpaket",
+                "laposte",
+                "austrianpost",
+                "cainiao",
+                "aramex",
+                "tnt",
+                "inpost",
+                "japanpost",
+                "brt",
+                "novaposhta",
+                "posti"
+            ]
+        );
+
+        let registry =
+            TrackerRegistry::default().configure(&TrackerSettings::default());
+        assert_eq!(
+            registry.names(),
+            vec![
+                "postnl",
+                "dhl",
+                "gls",
+                "trunkrs",
+                "dpd",
+                "fedex",
+                "royalmail",
+                "usps",
+                "dhlpaket",
+                "laposte",
+                "austrianpost",
+                "cainiao",
+                "aramex",
+                "tnt",
+                "inpost",
+                "japanpost",
+                "brt",
+                "novaposhta",
+                "posti"
+            ]
+        );
+    }
+}
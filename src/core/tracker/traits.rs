@@ -1,63 +1,141 @@
 use async_trait::async_trait;
+use regex::Regex;
 
 use crate::{
     error::Result,
-    tracker::{
-        DhlTracker, GlsTracker, PostNLTracker, TrunkrsTracker,
-        models::TrackerContext,
-    },
+    tracker::models::{PushUpdate, RawResponse, TrackerContext},
 };
-use std::sync::Mutex;
 
 use super::models::Package;
 
-type Registry = Mutex<Vec<Box<dyn Fn() -> Box<dyn Tracker> + Send + Sync>>>;
-
-// TODO: find a good mechanism for this
-lazy_static::lazy_static! {
-    static ref REGISTRY: Registry = Mutex::new(vec![
-        Box::new(|| Box::new(PostNLTracker)),
-        Box::new(|| Box::new(DhlTracker)),
-        Box::new(|| Box::new(GlsTracker)),
-        Box::new(|| Box::new(TrunkrsTracker)),
-    ]);
-}
-
 /// Handles getting and parsing tracking data for a specific channel, e.g. DHL.
 #[async_trait]
 pub trait Tracker: Send + Sync {
+    /// Short, unique, lowercase name for the tracker, e.g. "dhl". Used to
+    /// refer to the tracker in the registry.
+    fn name(&self) -> &str;
+
     /// Lets caller code know whether the Tracker implementation is suitable for
     /// the given url, so that caller code can do dynamic dispatch.
     fn can_handle(&self, url: &str) -> bool;
 
+    /// Regex patterns matching the bare tracking numbers this tracker
+    /// recognises, e.g. `3S[A-Z]{4}[0-9]{7,9}`. This lets the user pass a
+    /// tracking number instead of a URL.
+    fn barcode_patterns(&self) -> &[&str] {
+        &[]
+    }
+
+    /// Build the canonical tracking URL for a tracking number that matches
+    /// one of the `barcode_patterns`.
+    fn barcode_url(&self, _barcode: &str) -> Option<String> {
+        None
+    }
+
+    /// Fallback trackers (e.g. aggregators that cover many carriers) are only
+    /// offered for tracking numbers and URLs that no other tracker recognises.
+    fn is_fallback(&self) -> bool {
+        false
+    }
+
+    /// Languages the carrier can return tracking info in, e.g. `["nl", "en"]`.
+    /// Empty if the carrier doesn't let us choose.
+    fn languages(&self) -> &[&str] {
+        &[]
+    }
+
+    /// Pick the language to request: the first of `wanted` (in order of
+    /// preference) that the carrier supports, or else the carrier's first
+    /// language.
+    fn choose_language<'a>(&'a self, wanted: &[&'a str]) -> Option<&'a str> {
+        let supported = self.languages();
+        if supported.is_empty() {
+            return wanted.first().copied();
+        }
+        wanted
+            .iter()
+            .find(|lang| supported.contains(lang))
+            .or(supported.first())
+            .copied()
+    }
+
+    /// A tracking URL for a barcode that doesn't exist. `carriers check` uses
+    /// this to check that the carrier is reachable, without looking up a real
+    /// package. None if the tracker can't be checked.
+    fn probe_url(&self) -> Option<String> {
+        None
+    }
+
+    /// Check that the response for the `probe_url` looks the way we expect,
+    /// as an early warning that the carrier has changed its API. The default
+    /// only checks that the carrier didn't have a server error.
+    fn check_probe(&self, raw: &RawResponse) -> Result<()> {
+        if raw.is_server_error() {
+            return Err(format!("HTTP {}", raw.status).into());
+        }
+        Ok(())
+    }
+
     /// Get raw data that can be cached
-    /// Using String as the data type because we can't guarantee the format of
-    /// the reponse (HTML / JSON etc).
+    /// The body is a String because we can't guarantee the format of the
+    /// reponse (HTML / JSON etc).
     /// `Result` because the request may not succeed
-    async fn get_raw(&self, url: &str, ctx: &TrackerContext) -> Result<String>;
+    async fn get_raw(
+        &self,
+        url: &str,
+        ctx: &TrackerContext,
+    ) -> Result<RawResponse>;
+
+    /// How many URLs `get_raw_batch` can fetch in one request. 1 (the
+    /// default) means the carrier doesn't support batch requests.
+    fn batch_size(&self) -> usize {
+        1
+    }
+
+    /// Get the raw data for several URLs, returning one response per URL in
+    /// the same order. Each response must be something `parse` can handle on
+    /// its own, so it can be cached for its URL. The default fetches them one
+    /// by one; trackers whose carrier accepts several tracking numbers in one
+    /// request should override this and `batch_size`.
+    async fn get_raw_batch(
+        &self,
+        urls: &[&str],
+        ctx: &TrackerContext,
+    ) -> Result<Vec<RawResponse>> {
+        let mut responses = vec![];
+        for url in urls {
+            responses.push(self.get_raw(url, ctx).await?);
+        }
+        Ok(responses)
+    }
 
     /// Parse the result of `get_raw` into a Package.
     /// `Result` because we may get parse errors.
     fn parse(&self, text: String) -> Result<Package>;
-}
 
-/// Register the given Tracker implementation so that it can be selected
-pub fn register(creator: Box<dyn Fn() -> Box<dyn Tracker> + Send + Sync>) {
-    REGISTRY.lock().unwrap().push(creator);
-}
-
-/// Try to get a Tracker implementation for the given url.
-pub fn get_handler(url: &str) -> Result<Box<dyn Tracker>> {
-    for creator in REGISTRY
-        .lock()
-        .map_err(|err| format!("Error unlocking mutex: {err}"))?
-        .iter()
-    {
-        let tracker = creator();
-        if tracker.can_handle(url) {
-            return Ok(tracker);
+    /// The packages that a push notification from the carrier (a callback
+    /// to `packtrack serve`) is about. The default finds the tracking
+    /// numbers in the body with the `barcode_patterns`, so that those
+    /// packages are fetched again. Trackers whose carrier pushes the tracking
+    /// data itself should override this to pass it on, which saves a
+    /// request.
+    fn parse_push(&self, body: &str) -> Result<Vec<PushUpdate>> {
+        let mut updates: Vec<PushUpdate> = vec![];
+        for pattern in self.barcode_patterns() {
+            for found in
+                Regex::new(&format!(r"\b(?:{pattern})\b"))?.find_iter(body)
+            {
+                if !updates
+                    .iter()
+                    .any(|update| update.barcode == found.as_str())
+                {
+                    updates.push(PushUpdate {
+                        barcode: found.as_str().into(),
+                        raw:     None,
+                    });
+                }
+            }
         }
+        Ok(updates)
     }
-
-    Err(format!("Couldn't find a handler for {}", url).into())
 }
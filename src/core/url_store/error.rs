@@ -7,4 +7,7 @@ pub enum UrlError {
 
     #[display("'{_0}' was not found in the URL store")]
     NotFound(String),
+
+    #[display("'{_0}' matches more than one entry in the URL store")]
+    Ambiguous(String),
 }
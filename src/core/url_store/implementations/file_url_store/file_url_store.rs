@@ -7,10 +7,13 @@ use crate::{
             JsonUrlSerializer, SimpleUrlSerializer, UrlSerializer,
         },
         models::AnnotatedUrl,
-        utils::{add_to_list, filter, remove_from_list},
+        utils::{
+            add_to_list, attach_to_placeholder, filter, merge_lists, note_url,
+            remove_from_list, tag_urls,
+        },
     },
 };
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// URL store which stores its urls as a `Vec<AnnotatedUrl>` in memory, and as a
 /// text file on disk. Handles different file formats.
@@ -24,7 +27,7 @@ pub struct FileUrlStore {
 impl FileUrlStore {
     /// RAII -- instantiating the struct also loads the urls from file.
     pub fn new(path: PathBuf) -> Result<Self> {
-        #[allow(unused_mut)]
+        #[allow(unused_mut, unused_assignments)]
         let mut file_handler: Box<dyn FileHandler> = Box::new(TextFileHandler);
 
         // Use a mock file handler in tests to prevent tests doing IO
@@ -45,7 +48,7 @@ impl FileUrlStore {
     }
 
     /// Select the appropriate serializer based on the file's extension
-    fn select_serializer(file: &PathBuf) -> Result<Box<dyn UrlSerializer>> {
+    fn select_serializer(file: &Path) -> Result<Box<dyn UrlSerializer>> {
         if !file.exists() {
             return Err(
                 format!("File {} does not exist!", file.display()).into()
@@ -67,6 +70,50 @@ impl FileUrlStore {
     }
 }
 
+/// Convert a urls file in the line format (see `SimpleUrlSerializer`) to JSON,
+/// which can hold everything about a URL. The JSON file is put next to it,
+/// e.g. "packtrack.urls" -> "packtrack.json", and the old file is kept with
+/// ".bak" added to its name. Return the path of the JSON file.
+pub fn migrate_to_json(path: &Path) -> Result<PathBuf> {
+    let json = path.with_extension("json");
+    if json.exists() {
+        return Err(format!(
+            "Can't convert {} to JSON: {} already exists",
+            path.display(),
+            json.display()
+        )
+        .into());
+    }
+    let urls =
+        SimpleUrlSerializer.deserialize(&std::fs::read_to_string(path)?)?;
+    std::fs::write(&json, JsonUrlSerializer.serialize(&urls)?)?;
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".bak");
+    std::fs::rename(path, backup)?;
+    log::info!("Converted {} to {}", path.display(), json.display());
+    Ok(json)
+}
+
+/// The urls file in the line format that should be converted to JSON for the
+/// `urls_file` setting, if any: the setting itself if it isn't JSON yet, or
+/// else the old default "packtrack.urls" in `home`, when the setting is the
+/// new default and that file doesn't exist yet.
+pub fn legacy_urls_file(urls_file: &Path, home: &Path) -> Option<PathBuf> {
+    let is_json = urls_file
+        .extension()
+        .is_some_and(|ext| ext == "json");
+    if !is_json {
+        return urls_file
+            .exists()
+            .then(|| urls_file.to_owned());
+    }
+    let legacy = home.join("packtrack.urls");
+    (urls_file == legacy.with_extension("json")
+        && !urls_file.exists()
+        && legacy.exists())
+    .then_some(legacy)
+}
+
 impl UrlStore for FileUrlStore {
     fn add(&mut self, entry: AnnotatedUrl) -> Result<()> {
         let path = &self.path.display();
@@ -89,6 +136,78 @@ impl UrlStore for FileUrlStore {
                 "Error removing URLs from {path} matching pattern {query}: {err}")
             )
     }
+    fn remove_url(&mut self, url: &str) -> Option<AnnotatedUrl> {
+        let idx = self
+            .urls
+            .iter()
+            .position(|u| u.url == url)?;
+        log::info!("Removed URL {url} from {}", self.path.display());
+        Some(self.urls.remove(idx))
+    }
+    fn attach(&mut self, query: &str, url: String) -> Result<AnnotatedUrl> {
+        let path = &self.path.display();
+        attach_to_placeholder(&mut self.urls, query, url)
+            .inspect(|entry| log::info!("Attached URL to {entry} in {path}"))
+            .inspect_err(|err| {
+                log::warn!("Error attaching URL to {query} in {path}: {err}")
+            })
+    }
+    fn tag(
+        &mut self,
+        query: &str,
+        tags: &[String],
+    ) -> Result<Vec<AnnotatedUrl>> {
+        let path = &self.path.display();
+        tag_urls(&mut self.urls, query, tags, false)
+            .inspect(|tagged| {
+                log::info!("Tagged {} URLs in {path}: {tags:?}", tagged.len())
+            })
+            .inspect_err(|err| {
+                log::warn!(
+                    "Error tagging URLs matching {query} in {path}: {err}"
+                )
+            })
+    }
+    fn untag(
+        &mut self,
+        query: &str,
+        tags: &[String],
+    ) -> Result<Vec<AnnotatedUrl>> {
+        let path = &self.path.display();
+        tag_urls(&mut self.urls, query, tags, true)
+            .inspect(|untagged| {
+                log::info!(
+                    "Untagged {} URLs in {path}: {tags:?}",
+                    untagged.len()
+                )
+            })
+            .inspect_err(|err| {
+                log::warn!(
+                    "Error untagging URLs matching {query} in {path}: {err}"
+                )
+            })
+    }
+    fn note(
+        &mut self,
+        query: &str,
+        note: Option<String>,
+    ) -> Result<AnnotatedUrl> {
+        let path = &self.path.display();
+        note_url(&mut self.urls, query, note)
+            .inspect(|entry| {
+                log::info!("Updated the notes of {entry} in {path}")
+            })
+            .inspect_err(|err| {
+                log::warn!(
+                    "Error updating the notes of {query} in {path}: {err}"
+                )
+            })
+    }
+    fn merge(&mut self, other: Vec<AnnotatedUrl>) -> usize {
+        let changed = merge_lists(&mut self.urls, other);
+        log::info!("Merged {changed} URLs into {}", self.path.display());
+        changed
+    }
     fn filter(&self, query: Option<&str>) -> Vec<AnnotatedUrl> {
         filter(&self.urls, query)
     }
@@ -108,7 +227,10 @@ impl UrlStore for FileUrlStore {
 mod tests {
     use crate::{
         file_handler::MockFileHandler,
-        url_store::file_url_store::url_serializer::SimpleUrlSerializer,
+        test_utils::temp_dir,
+        url_store::file_url_store::url_serializer::{
+            JsonUrlSerializer, SimpleUrlSerializer,
+        },
     };
 
     use super::*;
@@ -135,6 +257,12 @@ mod tests {
             url:         "example.com".into(),
             description: None,
             created:     None,
+            expected:    None,
+            language:    None,
+            postcode:    None,
+            label:       None,
+            tags:        vec![],
+            notes:       vec![],
         };
         s.add(url.clone())
             .expect("The first add should work");
@@ -143,4 +271,52 @@ mod tests {
         assert!(result.is_err());
         Ok(())
     }
+
+    #[test]
+    fn test_migrate_to_json() -> Result<()> {
+        let dir = temp_dir("migrate-test")?;
+        let path = dir.join("packtrack.urls");
+        std::fs::write(&path, "example.com/1 | shoes\nexample.com/2")?;
+
+        let json = migrate_to_json(&path)?;
+        assert_eq!(json, dir.join("packtrack.json"));
+        let urls =
+            JsonUrlSerializer.deserialize(&std::fs::read_to_string(&json)?)?;
+        assert_eq!(urls.len(), 2);
+        assert_eq!(urls[0].description.as_deref(), Some("shoes"));
+        assert!(!path.exists());
+        assert!(dir.join("packtrack.urls.bak").exists());
+
+        // Don't overwrite an existing JSON file
+        std::fs::write(&path, "example.com/3")?;
+        assert!(migrate_to_json(&path).is_err());
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_legacy_urls_file() -> Result<()> {
+        let home = temp_dir("legacy-urls-test")?;
+        let json = home.join("packtrack.json");
+        let legacy = home.join("packtrack.urls");
+        let custom = home.join("my.urls");
+        assert_eq!(legacy_urls_file(&json, &home), None);
+        assert_eq!(legacy_urls_file(&custom, &home), None);
+
+        // The old default, while the settings have the new one
+        std::fs::write(&legacy, "example.com/1")?;
+        assert_eq!(legacy_urls_file(&json, &home), Some(legacy.clone()));
+        // Unless it was converted already
+        std::fs::write(&json, "[]")?;
+        assert_eq!(legacy_urls_file(&json, &home), None);
+        // Or the settings point at another JSON file
+        let other = home.join("other.json");
+        assert_eq!(legacy_urls_file(&other, &home), None);
+
+        // A line-format file in the settings
+        std::fs::write(&custom, "example.com/2")?;
+        assert_eq!(legacy_urls_file(&custom, &home), Some(custom));
+        std::fs::remove_dir_all(&home)?;
+        Ok(())
+    }
 }
@@ -1,4 +1,5 @@
+#[allow(clippy::module_inception)]
 mod file_url_store;
 mod url_serializer;
 
-pub use file_url_store::FileUrlStore;
+pub use file_url_store::{FileUrlStore, legacy_urls_file, migrate_to_json};
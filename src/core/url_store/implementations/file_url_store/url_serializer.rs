@@ -1,9 +1,9 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 
 use crate::{Result, url_store::models::AnnotatedUrl};
 
 pub trait UrlSerializer {
-    fn serialize(&self, urls: &Vec<AnnotatedUrl>) -> Result<String>;
+    fn serialize(&self, urls: &[AnnotatedUrl]) -> Result<String>;
     fn deserialize(&self, text: &str) -> Result<Vec<AnnotatedUrl>>;
 }
 
@@ -33,10 +33,14 @@ pub trait UrlSerializer {
 /// ]
 pub struct JsonUrlSerializer;
 impl UrlSerializer for JsonUrlSerializer {
-    fn serialize(&self, urls: &Vec<AnnotatedUrl>) -> Result<String> {
+    fn serialize(&self, urls: &[AnnotatedUrl]) -> Result<String> {
         Ok(serde_json::to_string_pretty(&urls)?)
     }
     fn deserialize(&self, text: &str) -> Result<Vec<AnnotatedUrl>> {
+        // A new, empty file has no URLs yet
+        if text.trim().is_empty() {
+            return Ok(vec![]);
+        }
         Ok(serde_json::from_str(text)?)
     }
 }
@@ -49,10 +53,73 @@ impl UrlSerializer for JsonUrlSerializer {
 /// https://example.com | 2026-01-26 20:29:30.811840299 UTC
 /// https://example.com | description
 /// https://example.com
+///
+/// If the `expected` date is present, all 4 columns are written (empty if
+/// missing):
+///
+/// pending:keyboard | 2026-01-26 20:29:30.811840299 UTC | keyboard | 2026-01-30
+/// https://example.com |  |  | 2026-01-30
+///
+/// If the `language` is present, a 5th column is added, if the `label` is
+/// present, a 6th, and if there are `tags`, a 7th with the tags separated by
+/// ",":
+///
+/// https://example.com |  |  |  | nl
+/// https://example.com |  |  |  |  | trail shoes
+/// https://example.com |  |  |  |  |  | work,gift
+///
+/// The `notes` come after that, one per column, because they're the last
+/// thing on the line:
+///
+/// https://example.com |  |  |  |  |  |  | Ring twice | Not before 9:00
 pub struct SimpleUrlSerializer;
 impl SimpleUrlSerializer {
     fn serialize_one(&self, entry: &AnnotatedUrl) -> String {
-        let mut s = format!("{}", entry.url);
+        let mut s = entry.url.to_string();
+        let has_tags = !entry.tags.is_empty();
+        let has_notes = !entry.notes.is_empty();
+        if entry.expected.is_some()
+            || entry.language.is_some()
+            || entry.label.is_some()
+            || has_tags
+            || has_notes
+        {
+            let c = entry
+                .created
+                .map(|c| c.to_string())
+                .unwrap_or_default();
+            let d = entry
+                .description
+                .clone()
+                .unwrap_or_default();
+            let e = entry
+                .expected
+                .map(|e| e.to_string())
+                .unwrap_or_default();
+            s = format!("{s} | {c} | {d} | {e}");
+            if entry.language.is_some()
+                || entry.label.is_some()
+                || has_tags
+                || has_notes
+            {
+                let l = entry
+                    .language
+                    .clone()
+                    .unwrap_or_default();
+                s += &format!(" | {l}");
+            }
+            if entry.label.is_some() || has_tags || has_notes {
+                let l = entry.label.clone().unwrap_or_default();
+                s += &format!(" | {l}");
+            }
+            if has_tags || has_notes {
+                s += &format!(" | {}", entry.tags.join(","));
+            }
+            for note in &entry.notes {
+                s += &format!(" | {note}");
+            }
+            return s;
+        }
         if let Some(c) = &entry.created {
             s += &format!(" | {c}")
         }
@@ -69,12 +136,16 @@ impl SimpleUrlSerializer {
     fn deserialize_one(&self, s: &str) -> Result<AnnotatedUrl> {
         let parts: Vec<String> = s
             .split("|")
-            .take(3)
             .map(|s| s.trim().to_owned())
             .collect();
 
         let mut created: Option<DateTime<Utc>> = None;
         let mut description: Option<String> = None;
+        let mut expected: Option<NaiveDate> = None;
+        let mut language: Option<String> = None;
+        let mut label: Option<String> = None;
+        let mut tags: Vec<String> = vec![];
+        let mut notes: Vec<String> = vec![];
         let url = match parts.len() {
             1 => parts[0].clone(),
             2 => {
@@ -91,21 +162,87 @@ impl SimpleUrlSerializer {
                 description = Some(parts[2].clone());
                 parts[0].clone()
             }
-            n => panic!("Unexpected length {n}!"),
+            _ => {
+                if !parts[1].is_empty() {
+                    created = Some(parts[1].parse()?);
+                }
+                if !parts[2].is_empty() {
+                    description = Some(parts[2].clone());
+                }
+                if !parts[3].is_empty() {
+                    expected = Some(parts[3].parse()?);
+                }
+                language = parts
+                    .get(4)
+                    .filter(|l| !l.is_empty())
+                    .cloned();
+                label = parts
+                    .get(5)
+                    .filter(|l| !l.is_empty())
+                    .cloned();
+                tags = parts
+                    .get(6)
+                    .map(|t| {
+                        t.split(',')
+                            .map(|tag| tag.trim().to_owned())
+                            .filter(|tag| !tag.is_empty())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                notes = parts
+                    .iter()
+                    .skip(7)
+                    .filter(|n| !n.is_empty())
+                    .cloned()
+                    .collect();
+                parts[0].clone()
+            }
         };
         Ok(AnnotatedUrl {
             url,
             description,
             created,
+            expected,
+            language,
+            postcode: None,
+            label,
+            tags,
+            notes,
         })
     }
 }
 impl UrlSerializer for SimpleUrlSerializer {
-    fn serialize(&self, urls: &Vec<AnnotatedUrl>) -> Result<String> {
+    fn serialize(&self, urls: &[AnnotatedUrl]) -> Result<String> {
+        // "|" separates the columns, so it can't be in the text
+        for url in urls {
+            if url.postcode.is_some() {
+                return Err(format!(
+                    "Can't save the postcode of {}: only .json urls files \
+                     can hold postcodes",
+                    url.url
+                )
+                .into());
+            }
+            let texts = url
+                .description
+                .iter()
+                .chain(&url.label)
+                .chain(&url.notes);
+            if let Some(text) = texts
+                .into_iter()
+                .find(|t| t.contains('|'))
+            {
+                return Err(format!(
+                    "Can't save {text:?} for {}: only .json urls files can \
+                     hold text with \"|\" in it",
+                    url.url
+                )
+                .into());
+            }
+        }
         let serialized_urls: Vec<String> = urls
             .iter()
-            .cloned()
-            .map(|u| self.serialize_one(&u))
+            .map(|u| self.serialize_one(u))
             .collect();
         let text = serialized_urls.join("\n");
         Ok(text)
@@ -135,6 +272,12 @@ mod tests {
                     url:         "https://example.com".to_owned(),
                     description: Some("description".to_owned()),
                     created:     Some(dt),
+                    expected:    None,
+                    language:    None,
+                    postcode:    None,
+                    label:       None,
+                    tags:        vec![],
+                    notes:       vec![],
                 },
                 "https://example.com | 2026-01-26 20:29:30.811840299 UTC | description",
             ),
@@ -144,6 +287,12 @@ mod tests {
                     url:         "https://example.com".to_owned(),
                     description: None,
                     created:     Some(dt),
+                    expected:    None,
+                    language:    None,
+                    postcode:    None,
+                    label:       None,
+                    tags:        vec![],
+                    notes:       vec![],
                 },
                 "https://example.com | 2026-01-26 20:29:30.811840299 UTC",
             ),
@@ -153,6 +302,12 @@ mod tests {
                     url:         "https://example.com".to_owned(),
                     description: Some("description".to_owned()),
                     created:     None,
+                    expected:    None,
+                    language:    None,
+                    postcode:    None,
+                    label:       None,
+                    tags:        vec![],
+                    notes:       vec![],
                 },
                 "https://example.com | description",
             ),
@@ -162,9 +317,108 @@ mod tests {
                     url:         "https://example.com".to_owned(),
                     description: None,
                     created:     None,
+                    expected:    None,
+                    language:    None,
+                    postcode:    None,
+                    label:       None,
+                    tags:        vec![],
+                    notes:       vec![],
                 },
                 "https://example.com",
             ),
+            (
+                "placeholder",
+                AnnotatedUrl {
+                    url:         "pending:keyboard".to_owned(),
+                    description: Some("keyboard".to_owned()),
+                    created:     Some(dt),
+                    expected:    NaiveDate::from_ymd_opt(2026, 1, 30),
+                    language:    None,
+                    postcode:    None,
+                    label:       None,
+                    tags:        vec![],
+                    notes:       vec![],
+                },
+                "pending:keyboard | 2026-01-26 20:29:30.811840299 UTC | keyboard | 2026-01-30",
+            ),
+            (
+                "only url and expected",
+                AnnotatedUrl {
+                    url:         "https://example.com".to_owned(),
+                    description: None,
+                    created:     None,
+                    expected:    NaiveDate::from_ymd_opt(2026, 1, 30),
+                    language:    None,
+                    postcode:    None,
+                    label:       None,
+                    tags:        vec![],
+                    notes:       vec![],
+                },
+                "https://example.com |  |  | 2026-01-30",
+            ),
+            (
+                "language",
+                AnnotatedUrl {
+                    url:         "https://example.com".to_owned(),
+                    description: Some("description".to_owned()),
+                    created:     None,
+                    expected:    None,
+                    language:    Some("nl".to_owned()),
+                    postcode:    None,
+                    label:       None,
+                    tags:        vec![],
+                    notes:       vec![],
+                },
+                "https://example.com |  | description |  | nl",
+            ),
+            (
+                "label",
+                AnnotatedUrl {
+                    url:         "https://example.com".to_owned(),
+                    description: None,
+                    created:     None,
+                    expected:    None,
+                    language:    None,
+                    postcode:    None,
+                    label:       Some("trail shoes".to_owned()),
+                    tags:        vec![],
+                    notes:       vec![],
+                },
+                "https://example.com |  |  |  |  | trail shoes",
+            ),
+            (
+                "tags",
+                AnnotatedUrl {
+                    url:         "https://example.com".to_owned(),
+                    description: None,
+                    created:     None,
+                    expected:    None,
+                    language:    None,
+                    postcode:    None,
+                    label:       None,
+                    tags:        vec!["work".to_owned(), "gift".to_owned()],
+                    notes:       vec![],
+                },
+                "https://example.com |  |  |  |  |  | work,gift",
+            ),
+            (
+                "notes",
+                AnnotatedUrl {
+                    url:         "https://example.com".to_owned(),
+                    description: None,
+                    created:     None,
+                    expected:    None,
+                    language:    None,
+                    postcode:    None,
+                    label:       None,
+                    tags:        vec![],
+                    notes:       vec![
+                        "Ring twice".to_owned(),
+                        "Not before 9:00".to_owned(),
+                    ],
+                },
+                "https://example.com |  |  |  |  |  |  | Ring twice | Not before 9:00",
+            ),
         ];
         for (description, url, expected_string) in testcases {
             let s = SimpleUrlSerializer.serialize_one(&url);
@@ -193,21 +447,45 @@ mod tests {
                 url:         "https://example.com".to_owned(),
                 description: Some("description".to_owned()),
                 created:     Some(dt),
+                expected:    None,
+                language:    None,
+                postcode:    None,
+                label:       None,
+                tags:        vec![],
+                notes:       vec![],
             },
             AnnotatedUrl {
                 url:         "https://example.com".to_owned(),
                 description: None,
                 created:     Some(dt),
+                expected:    None,
+                language:    None,
+                postcode:    None,
+                label:       None,
+                tags:        vec![],
+                notes:       vec![],
             },
             AnnotatedUrl {
                 url:         "https://example.com".to_owned(),
                 description: Some("description".to_owned()),
                 created:     None,
+                expected:    None,
+                language:    None,
+                postcode:    None,
+                label:       None,
+                tags:        vec![],
+                notes:       vec![],
             },
             AnnotatedUrl {
                 url:         "https://example.com".to_owned(),
                 description: None,
                 created:     None,
+                expected:    None,
+                language:    None,
+                postcode:    None,
+                label:       None,
+                tags:        vec![],
+                notes:       vec![],
             },
         ];
 
@@ -263,21 +541,45 @@ mod tests {
                 url:         "https://example.com".to_owned(),
                 description: Some("description".to_owned()),
                 created:     Some(dt),
+                expected:    None,
+                language:    None,
+                postcode:    None,
+                label:       None,
+                tags:        vec![],
+                notes:       vec![],
             },
             AnnotatedUrl {
                 url:         "https://example.com".to_owned(),
                 description: None,
                 created:     Some(dt),
+                expected:    None,
+                language:    None,
+                postcode:    None,
+                label:       None,
+                tags:        vec![],
+                notes:       vec![],
             },
             AnnotatedUrl {
                 url:         "https://example.com".to_owned(),
                 description: Some("description".to_owned()),
                 created:     None,
+                expected:    None,
+                language:    None,
+                postcode:    None,
+                label:       None,
+                tags:        vec![],
+                notes:       vec![],
             },
             AnnotatedUrl {
                 url:         "https://example.com".to_owned(),
                 description: None,
                 created:     None,
+                expected:    None,
+                language:    None,
+                postcode:    None,
+                label:       None,
+                tags:        vec![],
+                notes:       vec![],
             },
         ];
         let expected_serialized = "
@@ -303,4 +605,34 @@ https://example.com
 
         Ok(())
     }
+
+    #[test]
+    fn test_simple_url_serialize_unsupported() -> Result<()> {
+        let url = AnnotatedUrl {
+            notes: vec!["Left at door | back".into()],
+            ..AnnotatedUrl::from("https://example.com")
+        };
+        assert!(
+            SimpleUrlSerializer
+                .serialize(&[url])
+                .is_err()
+        );
+        let url = AnnotatedUrl {
+            postcode: Some("1234AB".into()),
+            ..AnnotatedUrl::from("https://example.com")
+        };
+        assert!(
+            SimpleUrlSerializer
+                .serialize(std::slice::from_ref(&url))
+                .is_err()
+        );
+        let json = JsonUrlSerializer.serialize(std::slice::from_ref(&url))?;
+        assert_eq!(JsonUrlSerializer.deserialize(&json)?, [url]);
+        assert!(
+            JsonUrlSerializer
+                .deserialize("\n")?
+                .is_empty()
+        );
+        Ok(())
+    }
 }
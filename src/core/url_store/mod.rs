@@ -10,3 +10,8 @@ pub use error::UrlError;
 pub use file_url_store::FileUrlStore;
 pub use models::AnnotatedUrl;
 pub use traits::UrlStore;
+#[cfg(any(test, feature = "test-utils"))]
+pub(crate) use utils::{
+    add_to_list, attach_to_placeholder, note_url, remove_from_list, tag_urls,
+};
+pub use utils::{filter, merge_duplicates, merge_lists};
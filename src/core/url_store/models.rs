@@ -1,10 +1,16 @@
+use crate::tracker::Package;
 use crate::utils::UtcTime;
-use chrono::Utc;
+use chrono::{NaiveDate, Utc};
 use derive_more::Display;
+use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+/// Placeholder entries (packages that are expected, but don't have a tracking
+/// URL yet) use this prefix instead of a real URL.
+pub const PLACEHOLDER_PREFIX: &str = "pending:";
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
 pub struct AnnotatedUrl {
     pub url:         String,
     /// Sometimes URLs don't give you much context and it's easy to forget what
@@ -12,6 +18,28 @@ pub struct AnnotatedUrl {
     pub description: Option<String>,
     /// When the URL was added to the URL store.
     pub created:     Option<UtcTime>,
+    /// When the package is expected to arrive. Mostly useful for placeholders.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected:    Option<NaiveDate>,
+    /// Language to request from the carrier for this package, instead of the
+    /// preferred language, e.g. "nl".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language:    Option<String>,
+    /// Postcode of the recipient of this package, for carriers that need one,
+    /// instead of the `postcode` setting
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub postcode:    Option<String>,
+    /// Short name for the package, e.g. "trail shoes", shown in the report
+    /// instead of the carrier and barcode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label:       Option<String>,
+    /// Tags to group packages by, e.g. "work" or "gift"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags:        Vec<String>,
+    /// Freeform notes about the package, e.g. "left with the neighbours last
+    /// time", oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes:       Vec<String>,
 }
 impl AnnotatedUrl {
     pub fn new(url: String, description: Option<String>) -> Self {
@@ -19,18 +47,98 @@ impl AnnotatedUrl {
             url,
             description,
             created: Some(Utc::now()),
+            expected: None,
+            language: None,
+            postcode: None,
+            label: None,
+            tags: vec![],
+            notes: vec![],
         }
     }
+
+    /// Create a placeholder for a package that doesn't have a tracking URL
+    /// yet. The label is used as the description.
+    pub fn placeholder(label: String, expected: Option<NaiveDate>) -> Self {
+        Self {
+            url: format!("{PLACEHOLDER_PREFIX}{}", slugify(&label)),
+            description: Some(label),
+            created: Some(Utc::now()),
+            expected,
+            language: None,
+            postcode: None,
+            label: None,
+            tags: vec![],
+            notes: vec![],
+        }
+    }
+
+    /// True if this entry is waiting for a tracking URL to be attached.
+    pub fn is_placeholder(&self) -> bool {
+        self.url.starts_with(PLACEHOLDER_PREFIX)
+    }
+
+    /// How to refer to the package in reports and notifications, e.g.
+    /// "PostNL trail shoes", or "PostNL 3SABCD1234567" if it has no label
+    pub fn package_name(&self, package: &Package) -> String {
+        let name = self
+            .label
+            .as_ref()
+            .unwrap_or(&package.barcode);
+        format!("{} {name}", package.channel)
+    }
+
+    /// True if the URL has the tag, ignoring case
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags
+            .iter()
+            .any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
+    /// True if the query occurs in the URL, its label or its description
+    pub fn matches(&self, query: &str) -> bool {
+        [
+            Some(&self.url),
+            self.label.as_ref(),
+            self.description.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .any(|field| field.contains(query))
+    }
 }
 impl Display for AnnotatedUrl {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.url)?;
+        if let Some(l) = &self.label {
+            write!(f, " \"{l}\"")?;
+        }
         if let Some(d) = &self.description {
             write!(f, " ({d})")?;
         }
+        if let Some(e) = &self.expected {
+            write!(f, " [expected {e}]")?;
+        }
+        if let Some(l) = &self.language {
+            write!(f, " [language {l}]")?;
+        }
+        if let Some(p) = &self.postcode {
+            write!(f, " [postcode {p}]")?;
+        }
+        for tag in &self.tags {
+            write!(f, " #{tag}")?;
+        }
         Ok(())
     }
 }
+
+/// "Keyboard from Amazon!" -> "keyboard-from-amazon"
+fn slugify(s: &str) -> String {
+    s.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
 /// Mostly useful for tests where we're not concerned with the `description` or
 /// `created` fields
 impl From<&str> for AnnotatedUrl {
@@ -39,6 +147,54 @@ impl From<&str> for AnnotatedUrl {
             url:         value.into(),
             description: None,
             created:     None,
+            expected:    None,
+            language:    None,
+            postcode:    None,
+            label:       None,
+            tags:        vec![],
+            notes:       vec![],
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_placeholder() {
+        let expected = NaiveDate::from_ymd_opt(2026, 10, 23);
+        let url =
+            AnnotatedUrl::placeholder("Keyboard from Amazon!".into(), expected);
+        assert_eq!(url.url, "pending:keyboard-from-amazon");
+        assert_eq!(url.description.as_deref(), Some("Keyboard from Amazon!"));
+        assert_eq!(url.expected, expected);
+        assert!(url.is_placeholder());
+        assert!(!AnnotatedUrl::from("example.com").is_placeholder());
+    }
+
+    #[test]
+    fn test_matches() {
+        let url = AnnotatedUrl {
+            label: Some("trail shoes".into()),
+            ..AnnotatedUrl::new(
+                "postnl.nl/3SABC".into(),
+                Some("Zalando".into()),
+            )
+        };
+        assert!(url.matches("3SABC"));
+        assert!(url.matches("trail"));
+        assert!(url.matches("Zalando"));
+        assert!(!url.matches("boots"));
+        let url = AnnotatedUrl {
+            tags: vec!["Gift".into()],
+            ..url
+        };
+        assert!(url.has_tag("gift"));
+        assert!(!url.has_tag("work"));
+        assert_eq!(
+            url.to_string(),
+            "postnl.nl/3SABC \"trail shoes\" (Zalando) #Gift"
+        );
+    }
+}
@@ -12,6 +12,45 @@ pub trait UrlStore {
     /// were removed.
     fn remove(&mut self, query: &str) -> Result<Vec<AnnotatedUrl>>;
 
+    /// Remove the entry whose URL is exactly `url`, unlike `remove`, which
+    /// also removes the entries that merely contain it. Return the removed
+    /// entry, if there was one.
+    fn remove_url(&mut self, url: &str) -> Option<AnnotatedUrl>;
+
+    /// Attach a tracking URL to the placeholder entry matching the given
+    /// query, turning it into a normally tracked package. Return the updated
+    /// entry.
+    fn attach(&mut self, query: &str, url: String) -> Result<AnnotatedUrl>;
+
+    /// Add tags to the entries matching the given query. Return the updated
+    /// entries.
+    fn tag(
+        &mut self,
+        query: &str,
+        tags: &[String],
+    ) -> Result<Vec<AnnotatedUrl>>;
+
+    /// Remove tags from the entries matching the given query. Return the
+    /// updated entries.
+    fn untag(
+        &mut self,
+        query: &str,
+        tags: &[String],
+    ) -> Result<Vec<AnnotatedUrl>>;
+
+    /// Add a note to the one entry matching the given query, or remove its
+    /// notes if `note` is None. Return the updated entry.
+    fn note(
+        &mut self,
+        query: &str,
+        note: Option<String>,
+    ) -> Result<AnnotatedUrl>;
+
+    /// Merge entries from another copy of the url store (e.g. from another
+    /// machine) into this one, without removing anything. Return the number of
+    /// entries added or changed.
+    fn merge(&mut self, other: Vec<AnnotatedUrl>) -> usize;
+
     /// Filter the contents of the url store by a query. If the query is none,
     /// return all the urls.
     fn filter(&self, query: Option<&str>) -> Vec<AnnotatedUrl>;
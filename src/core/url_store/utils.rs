@@ -29,38 +29,202 @@ pub fn remove_from_list(
     let mut removed: Vec<AnnotatedUrl> = vec![];
     while let Some(idx) = urls
         .iter()
-        .position(|x| x.url.contains(&pattern))
+        .position(|x| x.url.contains(pattern))
     {
         let url = urls.remove(idx);
         log::debug!("Removed URL: {url}");
         removed.push(url);
     }
-    if removed.len() == 0 {
+    if removed.is_empty() {
         Err(UrlError::NotFound(pattern.into()).into())
     } else {
         Ok(removed)
     }
 }
 
-/// Filter an in-memory list of URLs.
-pub fn filter<'a>(
-    urls: &'a Vec<AnnotatedUrl>,
-    query: Option<&str>,
+/// Replace the URL of the placeholder matching `query` with a real tracking
+/// URL. The query must match exactly one placeholder, so that we don't attach
+/// the same URL to several entries.
+pub fn attach_to_placeholder(
+    urls: &mut [AnnotatedUrl],
+    query: &str,
+    url: String,
+) -> Result<AnnotatedUrl> {
+    if urls.iter().any(|u| u.url == url) {
+        return Err(UrlError::AlreadyInStore(url).into());
+    }
+    let mut matches = urls.iter_mut().filter(|u| {
+        u.is_placeholder()
+            && (u.url.contains(query)
+                || u.description
+                    .as_ref()
+                    .is_some_and(|d| d.contains(query)))
+    });
+    let placeholder = matches
+        .next()
+        .ok_or(UrlError::NotFound(query.into()))?;
+    if matches.next().is_some() {
+        return Err(UrlError::Ambiguous(query.into()).into());
+    }
+    placeholder.url = url;
+    Ok(placeholder.clone())
+}
+
+/// Add the tags to (or with `remove`, remove them from) the URLs matching
+/// `query`. Return the entries that matched.
+pub fn tag_urls(
+    urls: &mut [AnnotatedUrl],
+    query: &str,
+    tags: &[String],
+    remove: bool,
+) -> Result<Vec<AnnotatedUrl>> {
+    let mut tagged = vec![];
+    for url in urls
+        .iter_mut()
+        .filter(|u| u.matches(query))
+    {
+        for tag in tags {
+            match remove {
+                true => url
+                    .tags
+                    .retain(|t| !t.eq_ignore_ascii_case(tag)),
+                false if !url.has_tag(tag) => url.tags.push(tag.clone()),
+                false => {}
+            }
+        }
+        tagged.push(url.clone());
+    }
+    match tagged.is_empty() {
+        true => Err(UrlError::NotFound(query.into()).into()),
+        false => Ok(tagged),
+    }
+}
+
+/// Add a note to the one URL matching `query`, or with `None`, remove its
+/// notes. Return the updated entry.
+pub fn note_url(
+    urls: &mut [AnnotatedUrl],
+    query: &str,
+    note: Option<String>,
+) -> Result<AnnotatedUrl> {
+    let mut matches = urls
+        .iter_mut()
+        .filter(|u| u.matches(query));
+    let url = matches
+        .next()
+        .ok_or(UrlError::NotFound(query.into()))?;
+    if matches.next().is_some() {
+        return Err(UrlError::Ambiguous(query.into()).into());
+    }
+    match note {
+        Some(note) => url.notes.push(note),
+        None => url.notes.clear(),
+    }
+    Ok(url.clone())
+}
+
+/// Merge another list of URLs (e.g. from another machine) into a list of URLs.
+/// This is a union: entries missing from `urls` are added, and entries in both
+/// lists are combined, filling in any fields that are missing in `urls`.
+/// Nothing is removed. Return the number of entries added or changed.
+pub fn merge_lists(
+    urls: &mut Vec<AnnotatedUrl>,
+    other: Vec<AnnotatedUrl>,
+) -> usize {
+    let mut changed = 0;
+    for entry in other {
+        let Some(existing) = urls
+            .iter_mut()
+            .find(|u| u.url == entry.url)
+        else {
+            urls.push(entry);
+            changed += 1;
+            continue;
+        };
+        let before = existing.clone();
+        merge_entry(existing, entry);
+        if *existing != before {
+            changed += 1;
+        }
+    }
+    changed
+}
+
+/// Fill in the fields of `existing` that are missing, from another entry for
+/// the same package
+fn merge_entry(existing: &mut AnnotatedUrl, entry: AnnotatedUrl) {
+    existing.description = existing
+        .description
+        .take()
+        .or(entry.description);
+    // Keep the earliest creation time
+    existing.created = match (existing.created, entry.created) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    };
+    existing.expected = existing.expected.or(entry.expected);
+    existing.language = existing
+        .language
+        .take()
+        .or(entry.language);
+    existing.postcode = existing
+        .postcode
+        .take()
+        .or(entry.postcode);
+    existing.label = existing.label.take().or(entry.label);
+    for tag in entry.tags {
+        if !existing.has_tag(&tag) {
+            existing.tags.push(tag);
+        }
+    }
+    for note in entry.notes {
+        if !existing.notes.contains(&note) {
+            existing.notes.push(note);
+        }
+    }
+}
+
+/// Merge the entries that `key` says are the same package, e.g. the same
+/// tracking number with and without a postcode in the URL, into the one with
+/// the longest URL, which usually tells the carrier the most. Entries without
+/// a key are kept as they are.
+pub fn merge_duplicates<K: PartialEq>(
+    urls: Vec<AnnotatedUrl>,
+    key: impl Fn(&AnnotatedUrl) -> Option<K>,
 ) -> Vec<AnnotatedUrl> {
+    let mut merged: Vec<(Option<K>, AnnotatedUrl)> = vec![];
+    for url in urls {
+        let k = key(&url);
+        let duplicate = merged
+            .iter_mut()
+            .find(|(other, _)| k.is_some() && *other == k);
+        let Some((_, existing)) = duplicate else {
+            merged.push((k, url));
+            continue;
+        };
+        log::info!("{} and {} are the same package", existing.url, url.url);
+        let (mut keep, other) = match url.url.len() > existing.url.len() {
+            true => (url, existing.clone()),
+            false => (existing.clone(), url),
+        };
+        merge_entry(&mut keep, other);
+        *existing = keep;
+    }
+    merged
+        .into_iter()
+        .map(|(_, url)| url)
+        .collect()
+}
+
+/// Filter an in-memory list of URLs.
+pub fn filter(urls: &[AnnotatedUrl], query: Option<&str>) -> Vec<AnnotatedUrl> {
     match query {
         Some(q) => urls
-            .into_iter()
-            .filter(|url| {
-                url.url.contains(&q)
-                    || url
-                        .description
-                        .as_ref()
-                        .map(|d| d.contains(&q))
-                        .unwrap_or(false)
-            })
+            .iter()
+            .filter(|url| url.matches(q))
             .cloned()
             .collect(),
-        None => urls.clone(),
+        None => urls.to_vec(),
     }
 }
 
@@ -118,6 +282,41 @@ mod tests {
         Ok(())
     }
     #[test]
+    fn test_attach_to_placeholder() -> Result<()> {
+        let mut urls = urls();
+        urls.push(AnnotatedUrl::placeholder("Keyboard".into(), None));
+        urls.push(AnnotatedUrl::placeholder("Shoes".into(), None));
+
+        let attached =
+            attach_to_placeholder(&mut urls, "Keyboard", "www.dhl.com".into())?;
+        assert_eq!(attached.url, "www.dhl.com");
+        assert_eq!(attached.description.as_deref(), Some("Keyboard"));
+        assert!(!urls[3].is_placeholder());
+
+        // Only placeholders can be attached to
+        let result =
+            attach_to_placeholder(&mut urls, "Keyboard", "www.gls.com".into());
+        assert_eq!(
+            result.err().unwrap(),
+            UrlError::NotFound("Keyboard".into()).into()
+        );
+        Ok(())
+    }
+    #[test]
+    fn test_attach_to_placeholder_ambiguous() {
+        let mut urls = vec![
+            AnnotatedUrl::placeholder("Red shoes".into(), None),
+            AnnotatedUrl::placeholder("Blue shoes".into(), None),
+        ];
+        let result =
+            attach_to_placeholder(&mut urls, "shoes", "www.dhl.com".into());
+        assert_eq!(
+            result.err().unwrap(),
+            UrlError::Ambiguous("shoes".into()).into()
+        );
+        assert!(urls.iter().all(|u| u.is_placeholder()));
+    }
+    #[test]
     fn test_add_sad() {
         let mut urls = urls();
         let result = add_to_list(&mut urls, "www.ups.org".into());
@@ -126,4 +325,98 @@ mod tests {
             UrlError::AlreadyInStore("www.ups.org".into()).into()
         );
     }
+    #[test]
+    fn test_tag_urls() -> Result<()> {
+        let mut urls = urls();
+        let tags = ["work".to_string(), "gift".to_string()];
+        let tagged = tag_urls(&mut urls, ".org", &tags, false)?;
+        assert_eq!(tagged, vec!["www.ups.org", "www.dhl.org"]);
+        tag_urls(&mut urls, "ups", &["Work".into()], false)?;
+        assert_eq!(urls[0].tags, ["work", "gift"]);
+        assert!(urls[1].tags.is_empty());
+
+        tag_urls(&mut urls, "dhl", &["WORK".into()], true)?;
+        assert_eq!(urls[2].tags, ["gift"]);
+        assert!(tag_urls(&mut urls, "gls", &tags, false).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_note_url() -> Result<()> {
+        let mut urls = urls();
+        note_url(&mut urls, "ups", Some("Left with the neighbours".into()))?;
+        note_url(&mut urls, "ups", Some("Ring twice".into()))?;
+        assert_eq!(urls[0].notes, ["Left with the neighbours", "Ring twice"]);
+        assert_eq!(
+            note_url(&mut urls, ".org", None).unwrap_err(),
+            UrlError::Ambiguous(".org".into()).into()
+        );
+        let cleared = note_url(&mut urls, "ups", None)?;
+        assert!(cleared.notes.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_lists() {
+        let early = "2026-01-01T00:00:00Z".parse().unwrap();
+        let late = "2026-02-01T00:00:00Z".parse().unwrap();
+        let mut urls = urls();
+        urls[0].created = Some(late);
+        let mut ups =
+            AnnotatedUrl::new("www.ups.org".into(), Some("Shoes".into()));
+        ups.created = Some(early);
+        ups.postcode = Some("1234AB".into());
+        let other = vec![ups, "www.dhl.org".into(), "foo.bar".into()];
+
+        let changed = merge_lists(&mut urls, other);
+        assert_eq!(changed, 2);
+        assert_eq!(
+            urls,
+            vec!["www.ups.org", "www.example.com", "www.dhl.org", "foo.bar"]
+        );
+        assert_eq!(urls[0].description.as_deref(), Some("Shoes"));
+        assert_eq!(urls[0].created, Some(early));
+        assert_eq!(urls[0].postcode.as_deref(), Some("1234AB"));
+
+        // Local descriptions win, and merging again changes nothing
+        let mut ups = AnnotatedUrl::from("www.ups.org");
+        ups.description = Some("Boots".into());
+        assert_eq!(merge_lists(&mut urls, vec![ups]), 0);
+        assert_eq!(urls[0].description.as_deref(), Some("Shoes"));
+    }
+
+    #[test]
+    fn test_merge_duplicates() {
+        let short = AnnotatedUrl {
+            label: Some("shoes".into()),
+            ..AnnotatedUrl::from("postnl.nl/3SABC")
+        };
+        let long = AnnotatedUrl {
+            tags: vec!["gift".into()],
+            ..AnnotatedUrl::from("postnl.nl/3SABC-NL-1234AB")
+        };
+        let urls = vec![short, "dhl.nl/JVGL1".into(), long, "other".into()];
+        // The part before the first "-", for URLs with a digit
+        let key = |u: &AnnotatedUrl| {
+            u.url
+                .contains(|c: char| c.is_ascii_digit())
+                .then(|| {
+                    u.url
+                        .split('-')
+                        .next()
+                        .unwrap()
+                        .to_owned()
+                })
+        };
+        let merged = merge_duplicates(urls, key);
+        assert_eq!(
+            merged,
+            vec!["postnl.nl/3SABC-NL-1234AB", "dhl.nl/JVGL1", "other"]
+        );
+        assert_eq!(merged[0].label.as_deref(), Some("shoes"));
+        assert_eq!(merged[0].tags, ["gift"]);
+        // Entries without a key are never merged
+        let merged = merge_duplicates(vec!["a".into(), "a".into()], key);
+        assert_eq!(merged.len(), 2);
+    }
 }
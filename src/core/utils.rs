@@ -4,7 +4,9 @@ use std::{
 };
 
 use crate::Result;
-use chrono::{DateTime, Utc};
+use chrono::{
+    DateTime, Datelike, Local, NaiveDate, NaiveTime, TimeDelta, Utc, Weekday,
+};
 use directories::{ProjectDirs, UserDirs};
 use serde::{Serialize, de::DeserializeOwned};
 
@@ -60,3 +62,140 @@ pub fn check_path_exists(s: &str) -> Result<PathBuf> {
         Err(format!("{s} does not exist").into())
     }
 }
+
+/// Parse a loosely specified date: "2026-10-23", "today", "tomorrow", or a
+/// weekday like "friday" / "fri" (meaning the next one).
+pub fn parse_date(s: &str) -> Result<NaiveDate> {
+    parse_date_relative_to(s, Local::now().date_naive())
+}
+
+fn parse_date_relative_to(s: &str, today: NaiveDate) -> Result<NaiveDate> {
+    let s = s
+        .trim()
+        .trim_start_matches('~')
+        .to_lowercase();
+    match s.as_str() {
+        "today" => return Ok(today),
+        "tomorrow" => return Ok(today + TimeDelta::days(1)),
+        _ => {}
+    }
+    if let Ok(weekday) = s.parse::<Weekday>() {
+        let days_ahead = (7 + weekday.num_days_from_monday()
+            - today.weekday().num_days_from_monday())
+            % 7;
+        let days_ahead = if days_ahead == 0 { 7 } else { days_ahead };
+        return Ok(today + TimeDelta::days(days_ahead.into()));
+    }
+    s.parse()
+        .map_err(|_| format!("Couldn't parse date: {s}").into())
+}
+
+/// Parse an age like "90d", "2w", "12h" or "30m"
+pub fn parse_age(s: &str) -> Result<TimeDelta> {
+    let s = s.trim();
+    let error = || format!("Couldn't parse age: {s}. Try e.g. 90d or 12h");
+    let split = s.len()
+        - s.chars()
+            .last()
+            .map_or(0, char::len_utf8);
+    let (number, unit) = s.split_at(split);
+    let number: i64 = number.parse().map_err(|_| error())?;
+    match unit {
+        "w" => Ok(TimeDelta::weeks(number)),
+        "d" => Ok(TimeDelta::days(number)),
+        "h" => Ok(TimeDelta::hours(number)),
+        "m" => Ok(TimeDelta::minutes(number)),
+        _ => Err(error().into()),
+    }
+}
+
+/// Parse a point in the past: "2026-10-14T08:00:00Z", "2026-10-14 08:00",
+/// "yesterday 09:00", "2026-10-14" (the start of the day), or an age like
+/// "12h" (that long ago). Times without a timezone are local.
+pub fn parse_time(s: &str) -> Result<UtcTime> {
+    parse_time_relative_to(s, Local::now())
+}
+
+fn parse_time_relative_to(s: &str, now: DateTime<Local>) -> Result<UtcTime> {
+    let s = s.trim();
+    let error = || format!("Couldn't parse time: {s}");
+    if let Ok(time) = DateTime::parse_from_rfc3339(s) {
+        return Ok(time.to_utc());
+    }
+    if let Ok(age) = parse_age(s) {
+        return Ok(now.to_utc() - age);
+    }
+    let (date, time) = s
+        .split_once(' ')
+        .unwrap_or((s, "00:00"));
+    let today = now.date_naive();
+    let date = match date.to_lowercase().as_str() {
+        "today" => today,
+        "yesterday" => today - TimeDelta::days(1),
+        date => date.parse().map_err(|_| error())?,
+    };
+    let time =
+        NaiveTime::parse_from_str(time.trim(), "%H:%M").map_err(|_| error())?;
+    date.and_time(time)
+        .and_local_timezone(Local)
+        .earliest()
+        .map(|time| time.to_utc())
+        .ok_or(error().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_date() -> Result<()> {
+        let today: NaiveDate = "2026-10-14".parse()?; // a Wednesday
+        for (input, expected) in [
+            ("2026-10-23", "2026-10-23"),
+            ("today", "2026-10-14"),
+            ("Tomorrow", "2026-10-15"),
+            ("friday", "2026-10-16"),
+            ("~Fri", "2026-10-16"),
+            ("wed", "2026-10-21"),
+            ("monday", "2026-10-19"),
+        ] {
+            let date = parse_date_relative_to(input, today)?;
+            assert_eq!(date, expected.parse::<NaiveDate>()?, "{input}");
+        }
+        assert!(parse_date_relative_to("someday", today).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_age() -> Result<()> {
+        assert_eq!(parse_age("90d")?, TimeDelta::days(90));
+        assert_eq!(parse_age("2w")?, TimeDelta::days(14));
+        assert_eq!(parse_age(" 12h")?, TimeDelta::hours(12));
+        assert_eq!(parse_age("30m")?, TimeDelta::minutes(30));
+        for bad in ["", "d", "90", "90y", "-d", "1.5d"] {
+            assert!(parse_age(bad).is_err(), "{bad}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_time() -> Result<()> {
+        // Tests run in the Europe/Amsterdam timezone
+        let now: DateTime<Local> = "2026-10-14T12:00:00+02:00".parse()?;
+        for (input, expected) in [
+            ("2026-10-13T08:00:00Z", "2026-10-13T08:00:00Z"),
+            ("2026-10-13 08:00", "2026-10-13T06:00:00Z"),
+            ("2026-10-13", "2026-10-12T22:00:00Z"),
+            ("yesterday 09:30", "2026-10-13T07:30:00Z"),
+            ("Today 08:00", "2026-10-14T06:00:00Z"),
+            ("12h", "2026-10-13T22:00:00Z"),
+        ] {
+            let time = parse_time_relative_to(input, now)?;
+            assert_eq!(time, expected.parse::<UtcTime>()?, "{input}");
+        }
+        for bad in ["", "someday", "yesterday morning", "2026-10-13 8"] {
+            assert!(parse_time_relative_to(bad, now).is_err(), "{bad}");
+        }
+        Ok(())
+    }
+}
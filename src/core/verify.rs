@@ -0,0 +1,217 @@
+// Comparing the newest cache entry for each package with a fresh response from
+// the carrier. Big differences mean `cache_seconds` is too long to keep up
+// with a carrier; events that disappear mean the carrier silently drops
+// history, which the cache can't be trusted to fill in.
+
+use crate::api::Job;
+use crate::cache::Cache;
+use crate::tracker::TrackerRegistry;
+use crate::tracker::{Event, Package, PackageStatus, TimeWindow};
+use crate::url_store::AnnotatedUrl;
+use crate::utils::UtcTime;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// The newest cached version of a package
+pub struct CachedPackage {
+    /// When the cache entry was created
+    pub created: UtcTime,
+    pub package: Package,
+}
+
+/// The newest parseable cache entry for each of the URLs, by URL
+pub fn cached_packages(
+    cache: &dyn Cache,
+    registry: &TrackerRegistry,
+    urls: &[AnnotatedUrl],
+) -> HashMap<String, CachedPackage> {
+    urls.iter()
+        .filter_map(|url| {
+            let tracker = registry.get_handler(&url.url).ok()?;
+            let entry = cache.get(&url.url)?;
+            let package = tracker.parse(entry.text.clone()).ok()?;
+            let cached = CachedPackage {
+                created: entry.created,
+                package,
+            };
+            Some((url.url.clone(), cached))
+        })
+        .collect()
+}
+
+/// A difference between the cached and the fresh package
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Discrepancy {
+    /// Events the cache didn't have yet
+    MissedEvents(Vec<Event>),
+    /// Events in the cache that the carrier doesn't report anymore
+    DroppedEvents(Vec<Event>),
+    /// The expected delivery time changed. An ETA without a window is
+    /// treated as a window of zero length.
+    EtaChanged {
+        cached: Option<TimeWindow>,
+        fresh:  Option<TimeWindow>,
+    },
+    StatusChanged {
+        cached: PackageStatus,
+        fresh:  PackageStatus,
+    },
+}
+
+/// The differences between the cached and the fresh package
+pub fn compare(cached: &Package, fresh: &Package) -> Vec<Discrepancy> {
+    let mut discrepancies = vec![];
+    let missing_from = |events: &[Event], other: &[Event]| -> Vec<Event> {
+        events
+            .iter()
+            .filter(|e| !other.contains(e))
+            .cloned()
+            .collect()
+    };
+    let missed = missing_from(&fresh.events, &cached.events);
+    if !missed.is_empty() {
+        discrepancies.push(Discrepancy::MissedEvents(missed));
+    }
+    let dropped = missing_from(&cached.events, &fresh.events);
+    if !dropped.is_empty() {
+        discrepancies.push(Discrepancy::DroppedEvents(dropped));
+    }
+    let (cached_eta, fresh_eta) = (expected(cached), expected(fresh));
+    if cached_eta != fresh_eta {
+        discrepancies.push(Discrepancy::EtaChanged {
+            cached: cached_eta,
+            fresh:  fresh_eta,
+        });
+    }
+    if cached.status != fresh.status {
+        discrepancies.push(Discrepancy::StatusChanged {
+            cached: cached.status.clone(),
+            fresh:  fresh.status.clone(),
+        });
+    }
+    discrepancies
+}
+
+fn expected(package: &Package) -> Option<TimeWindow> {
+    package
+        .eta_window
+        .clone()
+        .or(package.eta.map(|eta| TimeWindow {
+            start: eta,
+            end:   eta,
+        }))
+}
+
+/// How a freshly tracked package compares to the cache
+pub struct Verification<'a> {
+    pub job:           &'a Job,
+    /// When the cache entry that was compared was created, or None if the
+    /// package wasn't in the cache
+    pub cached_at:     Option<UtcTime>,
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+/// Compare the freshly tracked jobs with the cached packages. Jobs that
+/// couldn't be tracked are left out.
+pub fn verify<'a>(
+    jobs: &'a [Job],
+    cached: &HashMap<String, CachedPackage>,
+) -> Vec<Verification<'a>> {
+    jobs.iter()
+        .filter_map(|job| {
+            let fresh = job.result.as_ref().ok()?;
+            let cached = cached.get(&job.url.url);
+            Some(Verification {
+                job,
+                cached_at: cached.map(|c| c.created),
+                discrepancies: cached
+                    .map(|c| compare(&c.package, fresh))
+                    .unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::MemoryCache;
+    use crate::mocks::load_text;
+
+    #[test]
+    fn test_compare() -> crate::Result<()> {
+        let registry = TrackerRegistry::default();
+        let url = "https://jouw.postnl.nl/track-and-trace/3SABCD1234567";
+        let tracker = registry.get_handler(url)?;
+        let undelivered =
+            tracker.parse(load_text("postnl_undelivered.json")?)?;
+        let delivered = tracker.parse(load_text("postnl_delivered.json")?)?;
+
+        assert_eq!(compare(&delivered, &delivered), vec![]);
+
+        let discrepancies = compare(&undelivered, &delivered);
+        assert!(matches!(
+            discrepancies[0],
+            Discrepancy::MissedEvents(ref events) if !events.is_empty()
+        ));
+        assert!(discrepancies.contains(&Discrepancy::StatusChanged {
+            cached: PackageStatus::InTransit,
+            fresh:  PackageStatus::Delivered,
+        }));
+
+        let mut dropped = delivered.clone();
+        dropped.events.remove(0);
+        assert_eq!(
+            compare(&delivered, &dropped),
+            vec![Discrepancy::DroppedEvents(vec![
+                delivered.events[0].clone()
+            ])]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify() -> crate::Result<()> {
+        let registry = TrackerRegistry::default();
+        let url = "https://jouw.postnl.nl/track-and-trace/3SABCD1234567";
+        let mut cache = MemoryCache::default();
+        cache.insert(url.into(), load_text("postnl_undelivered.json")?);
+        let urls = vec![url.into(), "https://example.com".into()];
+        let cached = cached_packages(&cache, &registry, &urls);
+        assert_eq!(cached.len(), 1);
+
+        let fresh = registry
+            .get_handler(url)?
+            .parse(load_text("postnl_delivered.json")?)?;
+        let jobs = vec![
+            Job {
+                url:    url.into(),
+                result: Ok(fresh.clone()),
+            },
+            Job {
+                url:    "https://jouw.postnl.nl/track-and-trace/OTHER".into(),
+                result: Ok(fresh),
+            },
+            Job {
+                url:    "https://example.com".into(),
+                result: Err("HTTP 503".into()),
+            },
+        ];
+        let verifications = verify(&jobs, &cached);
+        assert_eq!(verifications.len(), 2);
+        assert!(verifications[0].cached_at.is_some());
+        assert!(
+            !verifications[0]
+                .discrepancies
+                .is_empty()
+        );
+        assert!(verifications[1].cached_at.is_none());
+        assert!(
+            verifications[1]
+                .discrepancies
+                .is_empty()
+        );
+        Ok(())
+    }
+}
@@ -2,8 +2,7 @@ mod cli;
 
 #[tokio::main]
 async fn main() {
-    match cli::main().await {
-        Err(err) => println!("{err}"),
-        _ => {}
+    if let Err(err) = cli::main().await {
+        println!("{err}")
     }
 }